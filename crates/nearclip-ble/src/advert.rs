@@ -0,0 +1,324 @@
+//! BLE 广播身份模块
+//!
+//! 过去广播的服务数据是不透明字节，扫描方必须先连接并读取
+//! `DEVICE_ID_CHARACTERISTIC_UUID` 才知道外设是谁：既慢，又把设备 ID
+//! 暴露给附近任何人。现在服务数据携带一个轮换的 HMAC 令牌：
+//!
+//! ```text
+//! ┌─────────┬──────────────────────┐
+//! │ Version │ Token                │
+//! │ 1 byte  │ 8 bytes              │
+//! └─────────┴──────────────────────┘
+//!
+//! token = HMAC-SHA256(advertisement_key, "nearclip-ble" || device_id || epoch)[..8]
+//! epoch = unix_time / rotation_period
+//! ```
+//!
+//! 广播包只有 31 字节，放不下每个已配对设备各一个令牌，因此每台设备只有
+//! 一个广播密钥（类似 BLE 的 IRK），配对时交给对端。扫描方用已配对设备的
+//! 广播密钥逐一计算并比对令牌，仅凭广播就能认出自己的设备；陌生设备
+//! 只能看到每个周期都会变化的随机值，也无需为识别它们而建立连接。
+
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 广播负载格式版本
+pub const ADVERTISEMENT_VERSION: u8 = 1;
+
+/// 令牌长度（字节）
+pub const ADVERTISEMENT_TOKEN_LEN: usize = 8;
+
+/// 广播负载总长度（字节）
+///
+/// 加上 128 位服务 UUID 的服务数据 AD 结构共 27 字节，
+/// 与 Flags 一起仍在 31 字节的传统广播包以内。
+pub const ADVERTISEMENT_PAYLOAD_LEN: usize = 1 + ADVERTISEMENT_TOKEN_LEN;
+
+/// 默认令牌轮换周期（15 分钟）
+pub const DEFAULT_ADVERTISEMENT_ROTATION_SECS: u64 = 15 * 60;
+
+/// 令牌计算的域分隔前缀
+const TOKEN_DOMAIN: &[u8] = b"nearclip-ble";
+
+/// 广播密钥派生的域分隔前缀
+const KEY_DOMAIN: &[u8] = b"nearclip-ble-advertisement-key";
+
+/// 设备的广播密钥
+///
+/// 广播方用它生成令牌，已配对的扫描方用它识别令牌。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_ble::AdvertisementKey;
+///
+/// let key = AdvertisementKey::derive(b"device secret");
+/// let payload = key.payload("device-a", 42);
+/// assert_eq!(payload.len(), nearclip_ble::ADVERTISEMENT_PAYLOAD_LEN);
+/// assert_ne!(payload, key.payload("device-a", 43));
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct AdvertisementKey([u8; 32]);
+
+impl AdvertisementKey {
+    /// 从原始字节创建
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// 从字节切片创建（配对时收到的密钥），长度不是 32 字节时返回 None
+    pub fn from_slice(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
+    /// 随机生成新的广播密钥（设备首次启动时生成，由平台层持久化）
+    pub fn generate() -> Self {
+        Self(rand::random())
+    }
+
+    /// 从设备密钥材料派生广播密钥
+    ///
+    /// 使用独立的域分隔前缀，广播密钥泄露不会影响原始密钥材料。
+    pub fn derive(secret: &[u8]) -> Self {
+        let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+        mac.update(KEY_DOMAIN);
+        Self(mac.finalize().into_bytes().into())
+    }
+
+    /// 原始字节（配对时交给对端）
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// 计算设备在指定周期的令牌
+    pub fn token(&self, device_id: &str, epoch: u64) -> [u8; ADVERTISEMENT_TOKEN_LEN] {
+        let mut mac = HmacSha256::new_from_slice(&self.0).expect("HMAC accepts any key length");
+        mac.update(TOKEN_DOMAIN);
+        mac.update(device_id.as_bytes());
+        mac.update(&epoch.to_be_bytes());
+        let mut token = [0u8; ADVERTISEMENT_TOKEN_LEN];
+        token.copy_from_slice(&mac.finalize().into_bytes()[..ADVERTISEMENT_TOKEN_LEN]);
+        token
+    }
+
+    /// 构建指定周期的广播服务数据
+    pub fn payload(&self, device_id: &str, epoch: u64) -> Vec<u8> {
+        let mut payload = Vec::with_capacity(ADVERTISEMENT_PAYLOAD_LEN);
+        payload.push(ADVERTISEMENT_VERSION);
+        payload.extend_from_slice(&self.token(device_id, epoch));
+        payload
+    }
+}
+
+impl std::fmt::Debug for AdvertisementKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密钥内容
+        f.write_str("AdvertisementKey(..)")
+    }
+}
+
+/// 从广播服务数据中取出令牌
+///
+/// # Returns
+///
+/// 不是本格式的服务数据（旧版本设备广播的任意字节）返回 None
+pub fn parse_advertisement(service_data: &[u8]) -> Option<[u8; ADVERTISEMENT_TOKEN_LEN]> {
+    if service_data.len() != ADVERTISEMENT_PAYLOAD_LEN || service_data[0] != ADVERTISEMENT_VERSION {
+        return None;
+    }
+    service_data[1..].try_into().ok()
+}
+
+/// 计算当前时间所在的轮换周期
+pub fn advertisement_epoch(rotation_period: Duration) -> u64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    secs / rotation_period.as_secs().max(1)
+}
+
+/// 距离下一个轮换周期开始的时间
+pub fn until_next_advertisement_epoch(rotation_period: Duration) -> Duration {
+    let period = rotation_period.as_secs().max(1);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let elapsed_in_epoch =
+        Duration::from_secs(now.as_secs() % period) + Duration::from_nanos(now.subsec_nanos() as u64);
+    Duration::from_secs(period).saturating_sub(elapsed_in_epoch)
+}
+
+/// 广播令牌解析器
+///
+/// 由扫描方持有，用已配对设备的广播密钥把广播服务数据还原为设备 ID。
+/// 为容忍设备间的时钟偏差，会同时比对前后相邻周期的令牌。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_ble::{advertisement_epoch, AdvertisementKey, AdvertisementResolver};
+/// use std::time::Duration;
+///
+/// let key = AdvertisementKey::derive(b"secret");
+/// let period = Duration::from_secs(900);
+/// let service_data = key.payload("device-a", advertisement_epoch(period));
+///
+/// let mut resolver = AdvertisementResolver::new(period);
+/// resolver.add_peer("device-a", key);
+/// assert_eq!(resolver.resolve(&service_data), Some("device-a".to_string()));
+/// assert_eq!(resolver.resolve(&[1, 0, 0, 0, 0, 0, 0, 0, 0]), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AdvertisementResolver {
+    /// (设备 ID, 广播密钥)
+    peers: Vec<(String, AdvertisementKey)>,
+    /// 令牌轮换周期
+    rotation_period: Duration,
+}
+
+impl AdvertisementResolver {
+    /// 创建空解析器
+    pub fn new(rotation_period: Duration) -> Self {
+        Self {
+            peers: Vec::new(),
+            rotation_period,
+        }
+    }
+
+    /// 添加已配对设备（同一设备重复添加时替换密钥）
+    pub fn add_peer(&mut self, device_id: impl Into<String>, key: AdvertisementKey) {
+        let device_id = device_id.into();
+        self.peers.retain(|(id, _)| *id != device_id);
+        self.peers.push((device_id, key));
+    }
+
+    /// 移除已配对设备
+    pub fn remove_peer(&mut self, device_id: &str) {
+        self.peers.retain(|(id, _)| id != device_id);
+    }
+
+    /// 是否为已配对设备
+    pub fn contains(&self, device_id: &str) -> bool {
+        self.peers.iter().any(|(id, _)| id == device_id)
+    }
+
+    /// 已配对设备数
+    pub fn peer_count(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// 获取轮换周期
+    pub fn rotation_period(&self) -> Duration {
+        self.rotation_period
+    }
+
+    /// 解析广播服务数据
+    ///
+    /// # Returns
+    ///
+    /// 匹配到的已配对设备 ID；陌生设备或无法解析的服务数据返回 None
+    pub fn resolve(&self, service_data: &[u8]) -> Option<String> {
+        self.resolve_at(service_data, advertisement_epoch(self.rotation_period))
+    }
+
+    /// 以指定周期为基准解析广播服务数据
+    pub fn resolve_at(&self, service_data: &[u8], epoch: u64) -> Option<String> {
+        let token = parse_advertisement(service_data)?;
+        let epochs = [epoch, epoch.saturating_sub(1), epoch.saturating_add(1)];
+        self.peers.iter().find_map(|(device_id, key)| {
+            epochs
+                .iter()
+                .any(|&e| key.token(device_id, e) == token)
+                .then(|| device_id.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_derive_deterministic() {
+        assert_eq!(AdvertisementKey::derive(b"secret"), AdvertisementKey::derive(b"secret"));
+        assert_ne!(AdvertisementKey::derive(b"secret"), AdvertisementKey::derive(b"other"));
+    }
+
+    #[test]
+    fn test_key_from_slice_and_generate() {
+        let key = AdvertisementKey::from_slice(&[7u8; 32]).unwrap();
+        assert_eq!(key, AdvertisementKey::from_bytes([7u8; 32]));
+        assert!(AdvertisementKey::from_slice(&[7u8; 16]).is_none());
+        assert_ne!(AdvertisementKey::generate(), AdvertisementKey::generate());
+    }
+
+    #[test]
+    fn test_key_debug_hides_bytes() {
+        let key = AdvertisementKey::from_bytes([7u8; 32]);
+        assert_eq!(format!("{:?}", key), "AdvertisementKey(..)");
+    }
+
+    #[test]
+    fn test_payload_layout_and_rotation() {
+        let key = AdvertisementKey::derive(b"secret");
+        let payload = key.payload("device-a", 100);
+
+        assert_eq!(payload.len(), ADVERTISEMENT_PAYLOAD_LEN);
+        assert_eq!(payload[0], ADVERTISEMENT_VERSION);
+        assert_eq!(parse_advertisement(&payload), Some(key.token("device-a", 100)));
+        assert_ne!(payload, key.payload("device-a", 101));
+        assert_ne!(payload, key.payload("device-b", 100));
+    }
+
+    #[test]
+    fn test_parse_rejects_legacy_service_data() {
+        assert_eq!(parse_advertisement(&[]), None);
+        assert_eq!(parse_advertisement(&[1]), None);
+        assert_eq!(parse_advertisement(&[2, 0, 0, 0, 0, 0, 0, 0, 0]), None);
+        assert_eq!(parse_advertisement(&[1; ADVERTISEMENT_PAYLOAD_LEN + 1]), None);
+    }
+
+    #[test]
+    fn test_epoch_helpers() {
+        let period = Duration::from_secs(900);
+        assert!(until_next_advertisement_epoch(period) <= period);
+        // 周期为 0 时按 1 秒处理，不会除零
+        assert!(advertisement_epoch(Duration::ZERO) >= advertisement_epoch(period));
+    }
+
+    #[test]
+    fn test_resolver_tolerates_adjacent_epochs() {
+        let key = AdvertisementKey::derive(b"secret-a");
+        let payload = key.payload("device-x", 42);
+
+        let mut resolver = AdvertisementResolver::new(Duration::from_secs(900));
+        resolver.add_peer("device-y", AdvertisementKey::derive(b"secret-b"));
+        resolver.add_peer("device-x", key);
+        assert_eq!(resolver.resolve_at(&payload, 42), Some("device-x".to_string()));
+
+        // 相邻周期容忍时钟偏差，更远的周期不匹配
+        assert_eq!(resolver.resolve_at(&payload, 43), Some("device-x".to_string()));
+        assert_eq!(resolver.resolve_at(&payload, 41), Some("device-x".to_string()));
+        assert_eq!(resolver.resolve_at(&payload, 44), None);
+    }
+
+    #[test]
+    fn test_resolver_rejects_strangers() {
+        let key = AdvertisementKey::derive(b"secret");
+        let mut resolver = AdvertisementResolver::new(Duration::from_secs(900));
+        resolver.add_peer("device-b", key.clone());
+        assert!(resolver.contains("device-b"));
+
+        // 陌生密钥、其他设备 ID 和旧格式都不匹配
+        let stranger = AdvertisementKey::derive(b"stranger").payload("device-b", 7);
+        assert_eq!(resolver.resolve_at(&stranger, 7), None);
+        assert_eq!(resolver.resolve_at(&key.payload("device-a", 7), 7), None);
+        assert_eq!(resolver.resolve_at(&[1], 7), None);
+
+        resolver.remove_peer("device-b");
+        assert_eq!(resolver.peer_count(), 0);
+        assert_eq!(resolver.resolve_at(&key.payload("device-b", 7), 7), None);
+    }
+}
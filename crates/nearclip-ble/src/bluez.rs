@@ -0,0 +1,1428 @@
+//! Linux BlueZ 后端
+//!
+//! 通过 D-Bus 调用 BlueZ 实现 [`BleHardware`]，为 Linux 桌面端提供 BLE 通道：
+//!
+//! - 扫描：`Adapter1.StartDiscovery`，按 [`NEARCLIP_SERVICE_UUID`] 过滤；
+//!   服务数据带身份令牌的设备直接上报广播，不连接识别
+//! - 中心模式：`Device1.Connect`，GATT 读 / 写 / 订阅
+//! - 外设模式：经 `GattManager1` 注册 GATT 应用，经 `LEAdvertisingManager1` 注册广播
+//!
+//! `BleHardware` 是同步接口，而 D-Bus 调用是异步的。[`BlueZHardware`] 在独立线程上
+//! 运行自己的 tokio 运行时并持有 D-Bus 连接，接口方法通过命令通道与其通信，
+//! 因此可以在任意线程（包括 `BleController` 的异步任务）中调用而不会阻塞 D-Bus 处理。
+//!
+//! 平台事件（发现、连接、断开、数据）通过 [`BleHardwareEvent`] 通道返回，
+//! 交给 [`BleController::handle_hardware_event`](crate::BleController::handle_hardware_event) 处理。
+//!
+//! 需要启用 `linux` feature。
+//!
+//! # 示例
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use nearclip_ble::bluez::{BlueZConfig, BlueZHardware};
+//! use nearclip_ble::{BleController, BleControllerCallback, BleControllerConfig, BleError};
+//!
+//! # async fn example(callback: Arc<dyn BleControllerCallback>) -> Result<(), BleError> {
+//! let config = BlueZConfig::new("my-device-id".to_string(), "hash".to_string());
+//! let (hardware, mut events) = BlueZHardware::new(config).await?;
+//! let controller = Arc::new(BleController::new(
+//!     Arc::new(hardware),
+//!     BleControllerConfig::default(),
+//!     callback,
+//! ));
+//!
+//! let forwarder = controller.clone();
+//! tokio::spawn(async move {
+//!     while let Some(event) = events.recv().await {
+//!         forwarder.handle_hardware_event(event).await;
+//!     }
+//! });
+//!
+//! controller.connect_with_scan("peer-device-id", 10_000).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{mpsc as std_mpsc, Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, info, warn};
+use zbus::fdo::{self, ObjectManager, ObjectManagerProxy};
+use zbus::message::Type as MessageType;
+use zbus::names::OwnedInterfaceName;
+use zbus::object_server::SignalEmitter;
+use zbus::proxy::CacheProperties;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{interface, proxy, Connection, MatchRule, MessageStream};
+
+use crate::advert::parse_advertisement;
+use crate::controller::{BleHardware, BleHardwareEvent};
+use crate::error::BleError;
+use crate::chunk::ChunkVersion;
+use crate::gatt::{
+    CHUNK_VERSION_CHARACTERISTIC_UUID, DATA_ACK_CHARACTERISTIC_UUID, DATA_TRANSFER_CHARACTERISTIC_UUID, DEFAULT_BLE_MTU,
+    DEVICE_ID_CHARACTERISTIC_UUID, FRAMING_CHARACTERISTIC_UUID, MUX_FRAMING_VERSION, NEARCLIP_SERVICE_UUID,
+    PUBKEY_HASH_CHARACTERISTIC_UUID,
+};
+
+/// 默认蓝牙适配器
+pub const DEFAULT_ADAPTER: &str = "hci0";
+
+/// 默认单次操作超时（连接、GATT 读写）
+pub const DEFAULT_OPERATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 本机导出的 GATT 应用路径
+pub const GATT_APPLICATION_PATH: &str = "/org/nearclip/gatt";
+
+/// 本机导出的广播对象路径
+pub const ADVERTISEMENT_PATH: &str = "/org/nearclip/advertisement0";
+
+const BLUEZ_SERVICE: &str = "org.bluez";
+const BLUEZ_PATH: &str = "/org/bluez";
+const DEVICE_INTERFACE: &str = "org.bluez.Device1";
+const CHARACTERISTIC_INTERFACE: &str = "org.bluez.GattCharacteristic1";
+
+/// 等待 ServicesResolved 的轮询间隔
+const SERVICES_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// ============================================================================
+// 配置与事件
+// ============================================================================
+
+/// BlueZ 后端配置
+#[derive(Debug, Clone)]
+pub struct BlueZConfig {
+    /// 本机设备 ID（外设模式下 DEVICE_ID 特征的值）
+    pub device_id: String,
+    /// 本机公钥哈希（外设模式下 PUBKEY_HASH 特征的值）
+    pub public_key_hash: String,
+    /// 适配器名称，如 `hci0`
+    pub adapter: String,
+    /// D-Bus 地址，`None` 表示系统总线
+    pub bus_address: Option<String>,
+    /// 单次操作超时
+    pub operation_timeout: Duration,
+}
+
+impl BlueZConfig {
+    /// 创建配置
+    pub fn new(device_id: String, public_key_hash: String) -> Self {
+        Self {
+            device_id,
+            public_key_hash,
+            adapter: DEFAULT_ADAPTER.to_string(),
+            bus_address: None,
+            operation_timeout: DEFAULT_OPERATION_TIMEOUT,
+        }
+    }
+
+    /// 设置适配器
+    pub fn with_adapter(mut self, adapter: impl Into<String>) -> Self {
+        self.adapter = adapter.into();
+        self
+    }
+
+    /// 连接指定的 D-Bus 地址而不是系统总线（用于测试）
+    pub fn with_bus_address(mut self, address: impl Into<String>) -> Self {
+        self.bus_address = Some(address.into());
+        self
+    }
+
+    /// 设置单次操作超时
+    pub fn with_operation_timeout(mut self, timeout: Duration) -> Self {
+        self.operation_timeout = timeout;
+        self
+    }
+}
+
+// ============================================================================
+// BlueZ 代理
+// ============================================================================
+
+#[proxy(interface = "org.bluez.Adapter1", default_service = "org.bluez")]
+trait Adapter1 {
+    fn start_discovery(&self) -> zbus::Result<()>;
+
+    fn stop_discovery(&self) -> zbus::Result<()>;
+
+    fn set_discovery_filter(&self, filter: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn powered(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(interface = "org.bluez.Device1", default_service = "org.bluez")]
+trait Device1 {
+    fn connect(&self) -> zbus::Result<()>;
+
+    fn disconnect(&self) -> zbus::Result<()>;
+
+    #[zbus(property)]
+    fn services_resolved(&self) -> zbus::Result<bool>;
+}
+
+#[proxy(interface = "org.bluez.GattCharacteristic1", default_service = "org.bluez")]
+trait GattCharacteristic1 {
+    fn read_value(&self, options: HashMap<&str, Value<'_>>) -> zbus::Result<Vec<u8>>;
+
+    fn write_value(&self, value: &[u8], options: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    fn start_notify(&self) -> zbus::Result<()>;
+}
+
+#[proxy(interface = "org.bluez.GattManager1", default_service = "org.bluez")]
+trait GattManager1 {
+    fn register_application(
+        &self,
+        application: &ObjectPath<'_>,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+
+    fn unregister_application(&self, application: &ObjectPath<'_>) -> zbus::Result<()>;
+}
+
+#[proxy(interface = "org.bluez.LEAdvertisingManager1", default_service = "org.bluez")]
+trait LEAdvertisingManager1 {
+    fn register_advertisement(
+        &self,
+        advertisement: &ObjectPath<'_>,
+        options: HashMap<&str, Value<'_>>,
+    ) -> zbus::Result<()>;
+
+    fn unregister_advertisement(&self, advertisement: &ObjectPath<'_>) -> zbus::Result<()>;
+}
+
+// ============================================================================
+// 共享状态
+// ============================================================================
+
+/// 作为中心建立的外设连接
+struct Link {
+    /// 特征 UUID（小写）-> 对象路径
+    characteristics: HashMap<String, OwnedObjectPath>,
+    mtu: u16,
+    /// 是否已上报 Connected（识别设备时的临时连接不上报）
+    announced: bool,
+}
+
+#[derive(Default)]
+struct State {
+    links: HashMap<String, Link>,
+    /// 连接到本机 GATT 服务的中心设备
+    centrals: HashSet<String>,
+    /// 已识别的设备：地址 -> (device_id, public_key_hash)
+    identities: HashMap<String, (String, String)>,
+    /// 识别失败的设备，下次开始扫描前不再重试
+    unidentifiable: HashSet<String>,
+    /// 广播了身份令牌的设备：地址 -> 服务数据
+    advertisements: HashMap<String, Vec<u8>>,
+    /// 广播了 NearClip 服务的设备
+    nearclip_devices: HashSet<String>,
+    /// 正在由本机连接（识别或 connect）的设备
+    pending: HashSet<String>,
+    scanning: bool,
+    advertising: bool,
+}
+
+/// 后台线程、导出对象和 [`BlueZHardware`] 共享的部分
+struct Shared {
+    state: Mutex<State>,
+    events: mpsc::UnboundedSender<BleHardwareEvent>,
+}
+
+impl Shared {
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn emit(&self, event: BleHardwareEvent) {
+        let _ = self.events.send(event);
+    }
+
+    /// 记录连接到本机的中心设备，首次出现时上报
+    fn note_central(&self, address: &str) {
+        if self.state().centrals.insert(address.to_string()) {
+            info!(central = %address, "Central connected to GATT server");
+            self.emit(BleHardwareEvent::CentralConnected {
+                peripheral_id: address.to_string(),
+            });
+        }
+    }
+}
+
+/// 设备属性中与本后端相关的部分
+#[derive(Default)]
+struct DeviceUpdate {
+    uuids: Option<Vec<String>>,
+    /// NearClip 服务的服务数据
+    service_data: Option<Vec<u8>>,
+    rssi: Option<i16>,
+    connected: Option<bool>,
+}
+
+impl DeviceUpdate {
+    fn parse<'a, 'v: 'a>(props: impl IntoIterator<Item = (&'a str, &'a Value<'v>)>) -> Self {
+        let mut update = Self::default();
+        for (name, value) in props {
+            match (name, value) {
+                ("UUIDs", Value::Array(uuids)) => {
+                    update.uuids = Some(
+                        uuids
+                            .iter()
+                            .filter_map(|uuid| match uuid {
+                                Value::Str(s) => Some(s.to_lowercase()),
+                                _ => None,
+                            })
+                            .collect(),
+                    );
+                }
+                ("ServiceData", Value::Dict(_)) => {
+                    let service_uuid = NEARCLIP_SERVICE_UUID.to_string();
+                    update.service_data = value
+                        .try_clone()
+                        .ok()
+                        .and_then(|value| HashMap::<String, OwnedValue>::try_from(value).ok())
+                        .and_then(|data| {
+                            data.into_iter()
+                                .find(|(uuid, _)| uuid.eq_ignore_ascii_case(&service_uuid))
+                        })
+                        .and_then(|(_, data)| Vec::<u8>::try_from(data).ok());
+                }
+                ("RSSI", Value::I16(rssi)) => update.rssi = Some(*rssi),
+                ("Connected", Value::Bool(connected)) => update.connected = Some(*connected),
+                _ => {}
+            }
+        }
+        update
+    }
+}
+
+/// `/org/bluez/hci0/dev_AA_BB_CC_DD_EE_FF` -> `AA:BB:CC:DD:EE:FF`
+fn address_from_path(path: &str) -> Option<String> {
+    let device = path.rsplit('/').next()?.strip_prefix("dev_")?;
+    Some(device.replace('_', ":"))
+}
+
+fn find_interface<'a>(
+    interfaces: &'a HashMap<OwnedInterfaceName, HashMap<String, OwnedValue>>,
+    name: &str,
+) -> Option<&'a HashMap<String, OwnedValue>> {
+    interfaces
+        .iter()
+        .find(|(interface, _)| interface.as_str() == name)
+        .map(|(_, props)| props)
+}
+
+fn dbus_error(e: zbus::Error) -> String {
+    e.to_string()
+}
+
+// ============================================================================
+// BlueZHardware
+// ============================================================================
+
+enum Command {
+    StartScan,
+    StopScan,
+    Connect(String),
+    Disconnect(String),
+    Read {
+        peripheral_id: String,
+        char_uuid: String,
+        reply: std_mpsc::Sender<Result<Vec<u8>, String>>,
+    },
+    Write {
+        peripheral_id: String,
+        char_uuid: String,
+        data: Vec<u8>,
+        reply: std_mpsc::Sender<Result<(), String>>,
+    },
+    Subscribe {
+        peripheral_id: String,
+        char_uuid: String,
+        reply: std_mpsc::Sender<Result<(), String>>,
+    },
+    StartAdvertising(Vec<u8>),
+    StopAdvertising,
+}
+
+/// 基于 BlueZ 的 [`BleHardware`] 实现
+///
+/// 丢弃后后台线程停止广播并退出。
+pub struct BlueZHardware {
+    commands: mpsc::UnboundedSender<Command>,
+    shared: Arc<Shared>,
+    timeout: Duration,
+}
+
+impl BlueZHardware {
+    /// 连接 BlueZ 并启动后台线程
+    ///
+    /// 返回硬件实例和平台事件通道。适配器不存在时返回
+    /// [`BleError::Initialization`]，未上电时返回 [`BleError::NotPowered`]。
+    pub async fn new(
+        config: BlueZConfig,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<BleHardwareEvent>), BleError> {
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let (command_tx, command_rx) = mpsc::unbounded_channel();
+        let (ready_tx, ready_rx) = oneshot::channel();
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::default()),
+            events: event_tx,
+        });
+        let timeout = config.operation_timeout;
+
+        let worker_shared = shared.clone();
+        thread::Builder::new()
+            .name("nearclip-bluez".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(BleError::Initialization(e.to_string())));
+                        return;
+                    }
+                };
+                runtime.block_on(async move {
+                    match Worker::start(config, worker_shared).await {
+                        Ok((worker, signals)) => {
+                            let _ = ready_tx.send(Ok(()));
+                            worker.run(command_rx, signals).await;
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                        }
+                    }
+                });
+            })
+            .map_err(|e| BleError::Initialization(e.to_string()))?;
+
+        ready_rx
+            .await
+            .map_err(|_| BleError::Initialization("BlueZ worker exited".to_string()))??;
+
+        Ok((
+            Self {
+                commands: command_tx,
+                shared,
+                timeout,
+            },
+            event_rx,
+        ))
+    }
+
+    fn send(&self, command: Command) {
+        if self.commands.send(command).is_err() {
+            warn!("BlueZ worker has stopped, command dropped");
+        }
+    }
+
+    /// 发送命令并同步等待结果
+    fn request<T>(&self, command: impl FnOnce(std_mpsc::Sender<Result<T, String>>) -> Command) -> Result<T, String> {
+        let (reply, result) = std_mpsc::channel();
+        self.commands
+            .send(command(reply))
+            .map_err(|_| "BlueZ worker has stopped".to_string())?;
+        result
+            .recv_timeout(self.timeout)
+            .map_err(|_| "BlueZ operation timed out".to_string())?
+    }
+}
+
+impl BleHardware for BlueZHardware {
+    fn start_scan(&self) {
+        self.send(Command::StartScan);
+    }
+
+    fn stop_scan(&self) {
+        self.send(Command::StopScan);
+    }
+
+    fn connect(&self, peripheral_id: &str) {
+        self.send(Command::Connect(peripheral_id.to_string()));
+    }
+
+    fn disconnect(&self, peripheral_id: &str) {
+        self.send(Command::Disconnect(peripheral_id.to_string()));
+    }
+
+    fn read_characteristic(&self, peripheral_id: &str, char_uuid: &str) -> Result<Vec<u8>, String> {
+        self.request(|reply| Command::Read {
+            peripheral_id: peripheral_id.to_string(),
+            char_uuid: char_uuid.to_lowercase(),
+            reply,
+        })
+    }
+
+    fn write_characteristic(&self, peripheral_id: &str, char_uuid: &str, data: &[u8]) -> Result<(), String> {
+        self.request(|reply| Command::Write {
+            peripheral_id: peripheral_id.to_string(),
+            char_uuid: char_uuid.to_lowercase(),
+            data: data.to_vec(),
+            reply,
+        })
+    }
+
+    fn subscribe_characteristic(&self, peripheral_id: &str, char_uuid: &str) -> Result<(), String> {
+        self.request(|reply| Command::Subscribe {
+            peripheral_id: peripheral_id.to_string(),
+            char_uuid: char_uuid.to_lowercase(),
+            reply,
+        })
+    }
+
+    fn start_advertising(&self, service_data: &[u8]) {
+        self.send(Command::StartAdvertising(service_data.to_vec()));
+    }
+
+    fn stop_advertising(&self) {
+        self.send(Command::StopAdvertising);
+    }
+
+    fn is_connected(&self, peripheral_id: &str) -> bool {
+        let state = self.shared.state();
+        state.links.get(peripheral_id).is_some_and(|link| link.announced)
+            || state.centrals.contains(peripheral_id)
+    }
+
+    fn get_mtu(&self, peripheral_id: &str) -> u16 {
+        self.shared
+            .state()
+            .links
+            .get(peripheral_id)
+            .map_or(DEFAULT_BLE_MTU as u16, |link| link.mtu)
+    }
+}
+
+// ============================================================================
+// 后台线程
+// ============================================================================
+
+/// BlueZ 发出的信号
+struct Signals {
+    properties_changed: MessageStream,
+    interfaces_added: fdo::InterfacesAddedStream,
+}
+
+struct Worker {
+    conn: Connection,
+    shared: Arc<Shared>,
+    adapter_path: String,
+    device_id: String,
+    public_key_hash: String,
+    timeout: Duration,
+}
+
+impl Worker {
+    async fn start(config: BlueZConfig, shared: Arc<Shared>) -> Result<(Arc<Self>, Signals), BleError> {
+        let init_error = |e: zbus::Error| BleError::Initialization(e.to_string());
+
+        let conn = match &config.bus_address {
+            Some(address) => zbus::connection::Builder::address(address.as_str())
+                .map_err(init_error)?
+                .build()
+                .await
+                .map_err(init_error)?,
+            None => Connection::system().await.map_err(init_error)?,
+        };
+
+        let worker = Arc::new(Self {
+            conn,
+            shared,
+            adapter_path: format!("{}/{}", BLUEZ_PATH, config.adapter),
+            device_id: config.device_id,
+            public_key_hash: config.public_key_hash,
+            timeout: config.operation_timeout,
+        });
+
+        let powered = worker.adapter().await.map_err(init_error)?.powered().await.map_err(|e| {
+            BleError::Initialization(format!("adapter {} unavailable: {}", config.adapter, e))
+        })?;
+        if !powered {
+            return Err(BleError::NotPowered);
+        }
+
+        let rule = MatchRule::builder()
+            .msg_type(MessageType::Signal)
+            .interface("org.freedesktop.DBus.Properties")
+            .map_err(init_error)?
+            .member("PropertiesChanged")
+            .map_err(init_error)?
+            .path_namespace(BLUEZ_PATH)
+            .map_err(init_error)?
+            .build();
+        let properties_changed = MessageStream::for_match_rule(rule, &worker.conn, None)
+            .await
+            .map_err(init_error)?;
+        let interfaces_added = worker
+            .object_manager()
+            .await
+            .map_err(init_error)?
+            .receive_interfaces_added()
+            .await
+            .map_err(init_error)?;
+
+        info!(adapter = %worker.adapter_path, "BlueZ backend started");
+        Ok((
+            worker,
+            Signals {
+                properties_changed,
+                interfaces_added,
+            },
+        ))
+    }
+
+    async fn run(self: Arc<Self>, mut commands: mpsc::UnboundedReceiver<Command>, mut signals: Signals) {
+        loop {
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(command) => self.handle(command).await,
+                    None => break,
+                },
+                Some(message) = signals.properties_changed.next() => {
+                    if let Ok(message) = message {
+                        self.on_properties_changed(&message);
+                    }
+                }
+                Some(signal) = signals.interfaces_added.next() => {
+                    if let Ok(args) = signal.args() {
+                        self.on_interfaces_added(args.object_path(), args.interfaces_and_properties());
+                    }
+                }
+            }
+        }
+
+        if self.shared.state().advertising {
+            self.stop_advertising().await;
+        }
+        debug!("BlueZ backend stopped");
+    }
+
+    async fn handle(self: &Arc<Self>, command: Command) {
+        match command {
+            Command::StartScan => {
+                if let Err(e) = self.start_scan().await {
+                    self.report(None, format!("Failed to start scan: {}", e));
+                }
+            }
+            Command::StopScan => {
+                self.shared.state().scanning = false;
+                if let Ok(adapter) = self.adapter().await {
+                    if let Err(e) = adapter.stop_discovery().await {
+                        debug!(error = %e, "StopDiscovery failed");
+                    }
+                }
+            }
+            Command::Connect(address) => {
+                // 连接要等服务解析完成，不能阻塞命令循环
+                tokio::spawn(self.clone().connect(address));
+            }
+            Command::Disconnect(address) => {
+                let result = match self.device(&address).await {
+                    Ok(device) => device.disconnect().await.map_err(dbus_error),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    self.report(Some(address), format!("Failed to disconnect: {}", e));
+                }
+            }
+            Command::Read {
+                peripheral_id,
+                char_uuid,
+                reply,
+            } => {
+                let _ = reply.send(self.read(&peripheral_id, &char_uuid).await);
+            }
+            Command::Write {
+                peripheral_id,
+                char_uuid,
+                data,
+                reply,
+            } => {
+                let _ = reply.send(self.write(&peripheral_id, &char_uuid, data).await);
+            }
+            Command::Subscribe {
+                peripheral_id,
+                char_uuid,
+                reply,
+            } => {
+                let _ = reply.send(self.subscribe(&peripheral_id, &char_uuid).await);
+            }
+            Command::StartAdvertising(service_data) => {
+                if let Err(e) = self.start_advertising(service_data).await {
+                    self.report(None, format!("Failed to start advertising: {}", e));
+                }
+            }
+            Command::StopAdvertising => self.stop_advertising().await,
+        }
+    }
+
+    fn report(&self, peripheral_id: Option<String>, message: String) {
+        warn!(peripheral_id = ?peripheral_id, "{}", message);
+        self.shared.emit(BleHardwareEvent::Error {
+            peripheral_id,
+            message,
+        });
+    }
+
+    // ========== 代理 ==========
+
+    async fn adapter(&self) -> zbus::Result<Adapter1Proxy<'static>> {
+        Adapter1Proxy::builder(&self.conn)
+            .path(self.adapter_path.clone())?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await
+    }
+
+    async fn object_manager(&self) -> zbus::Result<ObjectManagerProxy<'static>> {
+        ObjectManagerProxy::builder(&self.conn)
+            .destination(BLUEZ_SERVICE)?
+            .path("/")?
+            .build()
+            .await
+    }
+
+    fn device_path(&self, address: &str) -> String {
+        format!("{}/dev_{}", self.adapter_path, address.replace(':', "_"))
+    }
+
+    async fn device(&self, address: &str) -> Result<Device1Proxy<'static>, String> {
+        Device1Proxy::builder(&self.conn)
+            .path(self.device_path(address))
+            .map_err(dbus_error)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await
+            .map_err(dbus_error)
+    }
+
+    async fn characteristic(&self, address: &str, char_uuid: &str) -> Result<GattCharacteristic1Proxy<'static>, String> {
+        let path = {
+            let state = self.shared.state();
+            let link = state
+                .links
+                .get(address)
+                .ok_or_else(|| format!("{} is not connected", address))?;
+            link.characteristics
+                .get(char_uuid)
+                .cloned()
+                .ok_or_else(|| format!("{} has no characteristic {}", address, char_uuid))?
+        };
+        GattCharacteristic1Proxy::builder(&self.conn)
+            .path(path)
+            .map_err(dbus_error)?
+            .cache_properties(CacheProperties::No)
+            .build()
+            .await
+            .map_err(dbus_error)
+    }
+
+    // ========== 扫描 ==========
+
+    async fn start_scan(self: &Arc<Self>) -> zbus::Result<()> {
+        let adapter = self.adapter().await?;
+        let mut filter = HashMap::new();
+        filter.insert("UUIDs", Value::from(vec![NEARCLIP_SERVICE_UUID.to_string()]));
+        filter.insert("Transport", Value::from("le"));
+        adapter.set_discovery_filter(filter).await?;
+        adapter.start_discovery().await?;
+
+        {
+            let mut state = self.shared.state();
+            state.scanning = true;
+            state.unidentifiable.clear();
+        }
+
+        // BlueZ 缓存的设备不会再触发 InterfacesAdded，先补报一次
+        let objects = self.object_manager().await?.get_managed_objects().await?;
+        for (path, interfaces) in &objects {
+            if let Some(props) = find_interface(interfaces, DEVICE_INTERFACE) {
+                let update = DeviceUpdate::parse(props.iter().map(|(name, value)| (name.as_str(), &**value)));
+                self.on_device_update(path.as_str(), update);
+            }
+        }
+        Ok(())
+    }
+
+    fn on_interfaces_added(
+        self: &Arc<Self>,
+        path: &ObjectPath<'_>,
+        interfaces: &HashMap<zbus::names::InterfaceName<'_>, HashMap<&str, Value<'_>>>,
+    ) {
+        let device = interfaces
+            .iter()
+            .find(|(interface, _)| interface.as_str() == DEVICE_INTERFACE);
+        if let Some((_, props)) = device {
+            self.on_device_update(path.as_str(), DeviceUpdate::parse(props.iter().map(|(name, value)| (*name, value))));
+        }
+    }
+
+    fn on_properties_changed(self: &Arc<Self>, message: &zbus::Message) {
+        let header = message.header();
+        let Some(path) = header.path() else { return };
+        let Ok((interface, changed, _)) =
+            message.body().deserialize::<(String, HashMap<String, OwnedValue>, Vec<String>)>()
+        else {
+            return;
+        };
+
+        match interface.as_str() {
+            DEVICE_INTERFACE => {
+                let update = DeviceUpdate::parse(changed.iter().map(|(name, value)| (name.as_str(), &**value)));
+                self.on_device_update(path.as_str(), update);
+            }
+            CHARACTERISTIC_INTERFACE => {
+                if let Some(Value::Array(value)) = changed.get("Value").map(|value| &**value) {
+                    let data = value
+                        .iter()
+                        .filter_map(|byte| match byte {
+                            Value::U8(byte) => Some(*byte),
+                            _ => None,
+                        })
+                        .collect();
+                    self.on_notification(path.as_str(), data);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_device_update(self: &Arc<Self>, path: &str, update: DeviceUpdate) {
+        let Some(address) = address_from_path(path) else { return };
+        let service_uuid = NEARCLIP_SERVICE_UUID.to_string();
+        let mut state = self.shared.state();
+
+        if update.uuids.as_ref().is_some_and(|uuids| uuids.contains(&service_uuid)) {
+            state.nearclip_devices.insert(address.clone());
+        }
+        if let Some(service_data) = &update.service_data {
+            state.nearclip_devices.insert(address.clone());
+            if parse_advertisement(service_data).is_some() {
+                state.advertisements.insert(address.clone(), service_data.clone());
+            } else {
+                state.advertisements.remove(&address);
+            }
+        }
+
+        match update.connected {
+            Some(false) => {
+                let was_link = state.links.get(&address).map(|link| link.announced);
+                let was_central = state.centrals.remove(&address);
+                if !state.pending.contains(&address) {
+                    state.links.remove(&address);
+                }
+                if was_link == Some(true) || was_central {
+                    drop(state);
+                    info!(peripheral_id = %address, "BLE device disconnected");
+                    self.shared.emit(BleHardwareEvent::Disconnected {
+                        peripheral_id: address,
+                        reason: "disconnected".to_string(),
+                    });
+                    return;
+                }
+            }
+            // 不是本机发起的连接，且正在广播：中心设备连接了本机 GATT 服务
+            Some(true)
+                if !state.links.contains_key(&address)
+                    && !state.pending.contains(&address)
+                    && state.advertising =>
+            {
+                drop(state);
+                self.shared.note_central(&address);
+                return;
+            }
+            _ => {}
+        }
+
+        let seen = update.rssi.is_some() || update.uuids.is_some() || update.service_data.is_some();
+        if !seen || !state.scanning || !state.nearclip_devices.contains(&address) {
+            return;
+        }
+        let rssi = i32::from(update.rssi.unwrap_or(0));
+
+        // 带身份令牌的广播交给控制器解析，不为识别陌生设备而连接
+        if let Some(service_data) = state.advertisements.get(&address).cloned() {
+            drop(state);
+            self.shared.emit(BleHardwareEvent::AdvertisementReceived {
+                peripheral_id: address,
+                service_data,
+                rssi,
+            });
+            return;
+        }
+
+        if let Some((device_id, public_key_hash)) = state.identities.get(&address).cloned() {
+            drop(state);
+            self.shared.emit(BleHardwareEvent::DeviceDiscovered {
+                peripheral_id: address,
+                device_id,
+                public_key_hash,
+                rssi,
+            });
+        } else if !state.links.contains_key(&address)
+            && !state.unidentifiable.contains(&address)
+            && state.pending.insert(address.clone())
+        {
+            drop(state);
+            tokio::spawn(self.clone().identify(address, rssi));
+        }
+    }
+
+    fn on_notification(&self, path: &str, data: Vec<u8>) {
+        let source = {
+            let state = self.shared.state();
+            state.links.iter().filter(|(_, link)| link.announced).find_map(|(address, link)| {
+                link.characteristics
+                    .iter()
+                    .find(|(_, char_path)| char_path.as_str() == path)
+                    .map(|(uuid, _)| (address.clone(), uuid.clone()))
+            })
+        };
+        if let Some((peripheral_id, char_uuid)) = source {
+            self.shared.emit(BleHardwareEvent::DataReceived {
+                peripheral_id,
+                char_uuid,
+                data,
+            });
+        }
+    }
+
+    // ========== 中心模式 ==========
+
+    /// 读取新设备的设备 ID 和公钥哈希
+    ///
+    /// 与移动端一致：短暂连接读取两个只读特征后断开，结果缓存到下次扫描。
+    async fn identify(self: Arc<Self>, address: String, rssi: i32) {
+        let result = async {
+            self.open_link(&address).await?;
+            let device_id = String::from_utf8(self.read(&address, &DEVICE_ID_CHARACTERISTIC_UUID.to_string()).await?)
+                .map_err(|e| e.to_string())?;
+            let public_key_hash = self
+                .read(&address, &PUBKEY_HASH_CHARACTERISTIC_UUID.to_string())
+                .await
+                .ok()
+                .and_then(|hash| String::from_utf8(hash).ok())
+                .unwrap_or_default();
+            Ok::<_, String>((device_id, public_key_hash))
+        }
+        .await;
+
+        // 识别期间 connect() 可能已接管这条连接
+        let close = {
+            let mut state = self.shared.state();
+            state.pending.remove(&address);
+            match state.links.get(&address) {
+                Some(link) if link.announced => false,
+                Some(_) => {
+                    state.links.remove(&address);
+                    true
+                }
+                None => true,
+            }
+        };
+        if close {
+            if let Ok(device) = self.device(&address).await {
+                let _ = device.disconnect().await;
+            }
+        }
+
+        match result {
+            Ok((device_id, public_key_hash)) => {
+                debug!(peripheral_id = %address, device_id = %device_id, "Identified NearClip device");
+                self.shared
+                    .state()
+                    .identities
+                    .insert(address.clone(), (device_id.clone(), public_key_hash.clone()));
+                self.shared.emit(BleHardwareEvent::DeviceDiscovered {
+                    peripheral_id: address,
+                    device_id,
+                    public_key_hash,
+                    rssi,
+                });
+            }
+            Err(e) => {
+                debug!(peripheral_id = %address, error = %e, "Failed to identify device");
+                self.shared.state().unidentifiable.insert(address);
+            }
+        }
+    }
+
+    async fn connect(self: Arc<Self>, address: String) {
+        let reuse = {
+            let mut state = self.shared.state();
+            match state.links.get_mut(&address) {
+                Some(link) => {
+                    link.announced = true;
+                    true
+                }
+                None => {
+                    state.pending.insert(address.clone());
+                    false
+                }
+            }
+        };
+
+        if !reuse {
+            let result = self.open_link(&address).await;
+            let mut state = self.shared.state();
+            state.pending.remove(&address);
+            match (result, state.links.get_mut(&address)) {
+                (Ok(()), Some(link)) => link.announced = true,
+                (result, _) => {
+                    drop(state);
+                    let reason = result.err().unwrap_or_else(|| "disconnected".to_string());
+                    warn!(peripheral_id = %address, reason = %reason, "BLE connect failed");
+                    self.shared.emit(BleHardwareEvent::Disconnected {
+                        peripheral_id: address,
+                        reason,
+                    });
+                    return;
+                }
+            }
+        }
+
+        // 与 FFI 中心模式一致：订阅数据和 ACK 通知
+        for char_uuid in [DATA_TRANSFER_CHARACTERISTIC_UUID, DATA_ACK_CHARACTERISTIC_UUID] {
+            if let Err(e) = self.subscribe(&address, &char_uuid.to_string()).await {
+                warn!(peripheral_id = %address, char_uuid = %char_uuid, error = %e, "Failed to subscribe");
+            }
+        }
+
+        info!(peripheral_id = %address, "BLE device connected");
+        self.shared.emit(BleHardwareEvent::Connected { peripheral_id: address });
+    }
+
+    /// 连接设备并等待 GATT 服务解析完成
+    async fn open_link(&self, address: &str) -> Result<(), String> {
+        let device = self.device(address).await?;
+        tokio::time::timeout(self.timeout, async {
+            device.connect().await?;
+            while !device.services_resolved().await? {
+                tokio::time::sleep(SERVICES_POLL_INTERVAL).await;
+            }
+            Ok::<_, zbus::Error>(())
+        })
+        .await
+        .map_err(|_| format!("Connecting to {} timed out", address))?
+        .map_err(dbus_error)?;
+
+        let device_path = self.device_path(address);
+        let prefix = format!("{}/", device_path);
+        let objects = self
+            .object_manager()
+            .await
+            .map_err(dbus_error)?
+            .get_managed_objects()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut characteristics = HashMap::new();
+        let mut mtu = DEFAULT_BLE_MTU as u16;
+        for (path, interfaces) in &objects {
+            if !path.as_str().starts_with(&prefix) {
+                continue;
+            }
+            let Some(props) = find_interface(interfaces, CHARACTERISTIC_INTERFACE) else { continue };
+            if let Some(Value::Str(uuid)) = props.get("UUID").map(|value| &**value) {
+                characteristics.insert(uuid.to_lowercase(), path.clone());
+            }
+            // BlueZ 5.62+ 在特征上报告协商后的 ATT MTU
+            if let Some(Value::U16(value)) = props.get("MTU").map(|value| &**value) {
+                mtu = mtu.max(*value);
+            }
+        }
+
+        self.shared.state().links.insert(
+            address.to_string(),
+            Link {
+                characteristics,
+                mtu,
+                announced: false,
+            },
+        );
+        Ok(())
+    }
+
+    async fn read(&self, address: &str, char_uuid: &str) -> Result<Vec<u8>, String> {
+        self.characteristic(address, char_uuid)
+            .await?
+            .read_value(HashMap::new())
+            .await
+            .map_err(dbus_error)
+    }
+
+    async fn write(&self, address: &str, char_uuid: &str, data: Vec<u8>) -> Result<(), String> {
+        let to_central = {
+            let state = self.shared.state();
+            !state.links.contains_key(address) && state.centrals.contains(address)
+        };
+        if to_central {
+            return self.notify_centrals(char_uuid, data).await;
+        }
+
+        // 数据分片用 Write Without Response，其余特征要求确认
+        let write_type = if char_uuid == DATA_TRANSFER_CHARACTERISTIC_UUID.to_string() {
+            "command"
+        } else {
+            "request"
+        };
+        let mut options = HashMap::new();
+        options.insert("type", Value::from(write_type));
+        self.characteristic(address, char_uuid)
+            .await?
+            .write_value(&data, options)
+            .await
+            .map_err(dbus_error)
+    }
+
+    async fn subscribe(&self, address: &str, char_uuid: &str) -> Result<(), String> {
+        self.characteristic(address, char_uuid)
+            .await?
+            .start_notify()
+            .await
+            .map_err(dbus_error)
+    }
+
+    // ========== 外设模式 ==========
+
+    fn local_characteristic_path(index: usize) -> String {
+        format!("{}/service0/char{}", GATT_APPLICATION_PATH, index)
+    }
+
+    /// 通过本机特征的通知把数据发给中心设备
+    ///
+    /// BlueZ 会通知所有订阅了该特征的中心设备，无法指定单个中心。
+    async fn notify_centrals(&self, char_uuid: &str, data: Vec<u8>) -> Result<(), String> {
+        let index = LOCAL_CHARACTERISTICS
+            .iter()
+            .position(|(uuid, _)| uuid.to_string() == char_uuid)
+            .ok_or_else(|| format!("Unknown characteristic {}", char_uuid))?;
+        let characteristic = self
+            .conn
+            .object_server()
+            .interface::<_, LocalCharacteristic>(Self::local_characteristic_path(index))
+            .await
+            .map_err(|_| "GATT server is not running".to_string())?;
+
+        let mut local = characteristic.get_mut().await;
+        if !local.notifying {
+            return Err(format!("No central subscribed to {}", char_uuid));
+        }
+        local.value = data;
+        local
+            .value_changed(characteristic.signal_emitter())
+            .await
+            .map_err(dbus_error)
+    }
+
+    async fn start_advertising(&self, service_data: Vec<u8>) -> zbus::Result<()> {
+        let server = self.conn.object_server();
+        let already_advertising = self.shared.state().advertising;
+
+        if already_advertising {
+            // 更新广播数据：先注销旧广播
+            let _ = self.advertising_manager().await?.unregister_advertisement(&advertisement_path()).await;
+            server.remove::<Advertisement, _>(ADVERTISEMENT_PATH).await?;
+        } else {
+            self.export_gatt_application().await?;
+            if let Err(e) = self
+                .gatt_manager()
+                .await?
+                .register_application(&application_path(), HashMap::new())
+                .await
+            {
+                self.remove_gatt_application().await;
+                return Err(e);
+            }
+        }
+
+        server.at(ADVERTISEMENT_PATH, Advertisement { service_data }).await?;
+        if let Err(e) = self
+            .advertising_manager()
+            .await?
+            .register_advertisement(&advertisement_path(), HashMap::new())
+            .await
+        {
+            let _ = server.remove::<Advertisement, _>(ADVERTISEMENT_PATH).await;
+            if !already_advertising {
+                let _ = self.gatt_manager().await?.unregister_application(&application_path()).await;
+                self.remove_gatt_application().await;
+            }
+            return Err(e);
+        }
+
+        self.shared.state().advertising = true;
+        info!("BLE advertising started");
+        Ok(())
+    }
+
+    async fn stop_advertising(&self) {
+        if !std::mem::take(&mut self.shared.state().advertising) {
+            return;
+        }
+        if let Ok(manager) = self.advertising_manager().await {
+            let _ = manager.unregister_advertisement(&advertisement_path()).await;
+        }
+        if let Ok(manager) = self.gatt_manager().await {
+            let _ = manager.unregister_application(&application_path()).await;
+        }
+        let _ = self
+            .conn
+            .object_server()
+            .remove::<Advertisement, _>(ADVERTISEMENT_PATH)
+            .await;
+        self.remove_gatt_application().await;
+        info!("BLE advertising stopped");
+    }
+
+    async fn gatt_manager(&self) -> zbus::Result<GattManager1Proxy<'static>> {
+        GattManager1Proxy::builder(&self.conn)
+            .path(self.adapter_path.clone())?
+            .build()
+            .await
+    }
+
+    async fn advertising_manager(&self) -> zbus::Result<LEAdvertisingManager1Proxy<'static>> {
+        LEAdvertisingManager1Proxy::builder(&self.conn)
+            .path(self.adapter_path.clone())?
+            .build()
+            .await
+    }
+
+    /// 导出 NearClip GATT 服务
+    ///
+    /// 先导出服务和特征再挂 ObjectManager，BlueZ 注册时通过它枚举整个应用。
+    async fn export_gatt_application(&self) -> zbus::Result<()> {
+        let server = self.conn.object_server();
+        let service_path = format!("{}/service0", GATT_APPLICATION_PATH);
+        server.at(service_path.as_str(), LocalService).await?;
+
+        for (index, (uuid, flags)) in LOCAL_CHARACTERISTICS.iter().enumerate() {
+            let value = if *uuid == DEVICE_ID_CHARACTERISTIC_UUID {
+                self.device_id.as_bytes().to_vec()
+            } else if *uuid == PUBKEY_HASH_CHARACTERISTIC_UUID {
+                self.public_key_hash.as_bytes().to_vec()
+            } else if *uuid == CHUNK_VERSION_CHARACTERISTIC_UUID {
+                vec![ChunkVersion::V2.as_u8()]
+            } else if *uuid == FRAMING_CHARACTERISTIC_UUID {
+                vec![MUX_FRAMING_VERSION]
+            } else {
+                Vec::new()
+            };
+            let characteristic = LocalCharacteristic {
+                uuid: uuid.to_string(),
+                service: OwnedObjectPath::try_from(service_path.as_str())?,
+                flags,
+                value,
+                notifying: false,
+                shared: self.shared.clone(),
+            };
+            server.at(Self::local_characteristic_path(index), characteristic).await?;
+        }
+
+        server.at(GATT_APPLICATION_PATH, ObjectManager).await?;
+        Ok(())
+    }
+
+    async fn remove_gatt_application(&self) {
+        let server = self.conn.object_server();
+        let _ = server.remove::<ObjectManager, _>(GATT_APPLICATION_PATH).await;
+        for index in 0..LOCAL_CHARACTERISTICS.len() {
+            let _ = server
+                .remove::<LocalCharacteristic, _>(Self::local_characteristic_path(index))
+                .await;
+        }
+        let _ = server
+            .remove::<LocalService, _>(format!("{}/service0", GATT_APPLICATION_PATH))
+            .await;
+    }
+}
+
+fn application_path() -> ObjectPath<'static> {
+    ObjectPath::from_static_str_unchecked(GATT_APPLICATION_PATH)
+}
+
+fn advertisement_path() -> ObjectPath<'static> {
+    ObjectPath::from_static_str_unchecked(ADVERTISEMENT_PATH)
+}
+
+// ============================================================================
+// 导出对象
+// ============================================================================
+
+/// 本机 GATT 特征及其 BlueZ 标志
+const LOCAL_CHARACTERISTICS: [(uuid::Uuid, &[&str]); 6] = [
+    (DEVICE_ID_CHARACTERISTIC_UUID, &["read"]),
+    (PUBKEY_HASH_CHARACTERISTIC_UUID, &["read"]),
+    (DATA_TRANSFER_CHARACTERISTIC_UUID, &["write", "write-without-response", "notify"]),
+    (DATA_ACK_CHARACTERISTIC_UUID, &["read", "notify"]),
+    (CHUNK_VERSION_CHARACTERISTIC_UUID, &["read"]),
+    (FRAMING_CHARACTERISTIC_UUID, &["read"]),
+];
+
+struct LocalService;
+
+#[interface(name = "org.bluez.GattService1")]
+impl LocalService {
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        NEARCLIP_SERVICE_UUID.to_string()
+    }
+
+    #[zbus(property)]
+    fn primary(&self) -> bool {
+        true
+    }
+}
+
+struct LocalCharacteristic {
+    uuid: String,
+    service: OwnedObjectPath,
+    flags: &'static [&'static str],
+    value: Vec<u8>,
+    notifying: bool,
+    shared: Arc<Shared>,
+}
+
+impl LocalCharacteristic {
+    fn allows(&self, flag: &str) -> bool {
+        self.flags.contains(&flag)
+    }
+}
+
+#[interface(name = "org.bluez.GattCharacteristic1")]
+impl LocalCharacteristic {
+    async fn read_value(&self, options: HashMap<String, OwnedValue>) -> fdo::Result<Vec<u8>> {
+        if !self.allows("read") {
+            return Err(fdo::Error::NotSupported("read not permitted".to_string()));
+        }
+        let offset = match options.get("offset").map(|value| &**value) {
+            Some(Value::U16(offset)) => usize::from(*offset),
+            _ => 0,
+        };
+        Ok(self.value.get(offset..).unwrap_or_default().to_vec())
+    }
+
+    async fn write_value(&mut self, value: Vec<u8>, options: HashMap<String, OwnedValue>) -> fdo::Result<()> {
+        if !self.allows("write") {
+            return Err(fdo::Error::NotSupported("write not permitted".to_string()));
+        }
+        let central = match options.get("device").map(|value| &**value) {
+            Some(Value::ObjectPath(path)) => address_from_path(path.as_str()),
+            _ => None,
+        }
+        .ok_or_else(|| fdo::Error::InvalidArgs("missing device option".to_string()))?;
+
+        self.shared.note_central(&central);
+        self.shared.emit(BleHardwareEvent::DataReceived {
+            peripheral_id: central,
+            char_uuid: self.uuid.clone(),
+            data: value,
+        });
+        Ok(())
+    }
+
+    async fn start_notify(&mut self, #[zbus(signal_emitter)] emitter: SignalEmitter<'_>) -> fdo::Result<()> {
+        if !self.allows("notify") {
+            return Err(fdo::Error::NotSupported("notify not permitted".to_string()));
+        }
+        self.notifying = true;
+        self.notifying_changed(&emitter).await?;
+        Ok(())
+    }
+
+    async fn stop_notify(&mut self, #[zbus(signal_emitter)] emitter: SignalEmitter<'_>) -> fdo::Result<()> {
+        self.notifying = false;
+        self.notifying_changed(&emitter).await?;
+        Ok(())
+    }
+
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        self.uuid.clone()
+    }
+
+    #[zbus(property)]
+    fn service(&self) -> OwnedObjectPath {
+        self.service.clone()
+    }
+
+    #[zbus(property)]
+    fn flags(&self) -> Vec<String> {
+        self.flags.iter().map(|flag| flag.to_string()).collect()
+    }
+
+    #[zbus(property)]
+    fn value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+
+    #[zbus(property)]
+    fn notifying(&self) -> bool {
+        self.notifying
+    }
+}
+
+/// LE 广播
+///
+/// 128-bit 服务 UUID 加服务数据已接近传统广播的 31 字节上限，
+/// 因此不附带本地名称。
+struct Advertisement {
+    service_data: Vec<u8>,
+}
+
+#[interface(name = "org.bluez.LEAdvertisement1")]
+impl Advertisement {
+    fn release(&self) {
+        debug!("Advertisement released by BlueZ");
+    }
+
+    #[zbus(property, name = "Type")]
+    fn kind(&self) -> String {
+        "peripheral".to_string()
+    }
+
+    #[zbus(property, name = "ServiceUUIDs")]
+    fn service_uuids(&self) -> Vec<String> {
+        vec![NEARCLIP_SERVICE_UUID.to_string()]
+    }
+
+    #[zbus(property)]
+    fn service_data(&self) -> fdo::Result<HashMap<String, OwnedValue>> {
+        let data = OwnedValue::try_from(Value::from(self.service_data.clone()))
+            .map_err(|e| fdo::Error::Failed(e.to_string()))?;
+        Ok(HashMap::from([(NEARCLIP_SERVICE_UUID.to_string(), data)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_from_path() {
+        assert_eq!(
+            address_from_path("/org/bluez/hci0/dev_AA_BB_CC_DD_EE_FF").as_deref(),
+            Some("AA:BB:CC:DD:EE:FF")
+        );
+        assert_eq!(address_from_path("/org/bluez/hci0"), None);
+    }
+
+    #[test]
+    fn test_config_builders() {
+        let config = BlueZConfig::new("id".to_string(), "hash".to_string())
+            .with_adapter("hci1")
+            .with_bus_address("unix:path=/tmp/bus")
+            .with_operation_timeout(Duration::from_secs(3));
+
+        assert_eq!(config.adapter, "hci1");
+        assert_eq!(config.bus_address.as_deref(), Some("unix:path=/tmp/bus"));
+        assert_eq!(config.operation_timeout, Duration::from_secs(3));
+    }
+}
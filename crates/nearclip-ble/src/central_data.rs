@@ -54,6 +54,7 @@
 
 use crate::chunk::Chunker;
 use crate::gatt::{ATT_HEADER_SIZE, CHUNK_HEADER_SIZE};
+use crate::link::LinkParameters;
 use crate::BleError;
 use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::Arc;
@@ -155,6 +156,8 @@ pub struct CentralDataConfig {
     pub retry_count: u32,
     /// ACK 等待超时
     pub ack_timeout: Duration,
+    /// 连续发送分片的间隔（0 = 不限速）
+    pub chunk_interval: Duration,
 }
 
 impl Default for CentralDataConfig {
@@ -171,12 +174,14 @@ impl CentralDataConfig {
     /// - 发送超时: 30 秒
     /// - 重试次数: 3
     /// - ACK 超时: 5 秒
+    /// - 分片间隔: 0（不限速）
     pub fn new() -> Self {
         Self {
             mtu: DEFAULT_MTU,
             send_timeout: Duration::from_secs(DEFAULT_SEND_TIMEOUT_SECS),
             retry_count: DEFAULT_RETRY_COUNT,
             ack_timeout: Duration::from_millis(DEFAULT_ACK_TIMEOUT_MS),
+            chunk_interval: Duration::ZERO,
         }
     }
 
@@ -220,6 +225,28 @@ impl CentralDataConfig {
         self
     }
 
+    /// 设置分片发送间隔
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - 两个分片之间的最小间隔
+    pub fn with_chunk_interval(mut self, interval: Duration) -> Self {
+        self.chunk_interval = interval;
+        self
+    }
+
+    /// 按协商后的链路参数设置 MTU 和分片间隔
+    ///
+    /// MTU 取 [`LinkParameters::chunk_mtu`]，间隔取 [`LinkParameters::chunk_interval`]。
+    ///
+    /// # Arguments
+    ///
+    /// * `link` - 链路参数
+    pub fn with_link_parameters(self, link: &LinkParameters) -> Self {
+        self.with_mtu(link.chunk_mtu())
+            .with_chunk_interval(link.chunk_interval())
+    }
+
     /// 验证配置
     pub fn validate(&self) -> Result<(), BleError> {
         // MTU 必须足够容纳头部和至少 1 字节 payload
@@ -495,6 +522,11 @@ impl CentralDataSender {
                 "Sending chunk"
             );
 
+            // 按链路节奏发送，避免塞满平台的写队列
+            if i > 0 && !self.config.chunk_interval.is_zero() {
+                tokio::time::sleep(self.config.chunk_interval).await;
+            }
+
             // 实际发送逻辑（平台特定）
             let send_result = self.send_chunk(chunk).await;
 
@@ -736,6 +768,7 @@ mod tests {
         assert_eq!(config.send_timeout, Duration::from_secs(DEFAULT_SEND_TIMEOUT_SECS));
         assert_eq!(config.retry_count, DEFAULT_RETRY_COUNT);
         assert_eq!(config.ack_timeout, Duration::from_millis(DEFAULT_ACK_TIMEOUT_MS));
+        assert_eq!(config.chunk_interval, Duration::ZERO);
     }
 
     #[test]
@@ -762,6 +795,18 @@ mod tests {
         assert_eq!(config.ack_timeout, Duration::from_secs(10));
     }
 
+    #[test]
+    fn test_config_with_link_parameters() {
+        let link = LinkParameters::new()
+            .with_mtu(247)
+            .with_connection_interval(Duration::from_millis(30));
+        let config = CentralDataConfig::new().with_link_parameters(&link);
+
+        assert_eq!(config.mtu, link.chunk_mtu());
+        assert_eq!(config.chunk_interval, link.chunk_interval());
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_config_validate_success() {
         let config = CentralDataConfig::new();
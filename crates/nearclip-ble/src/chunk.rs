@@ -10,7 +10,7 @@
 //!
 //! # 分片格式
 //!
-//! 每个分片由头部 (8 bytes) + payload 组成：
+//! v1 分片由头部 (8 bytes) + payload 组成：
 //!
 //! ```text
 //! +----------------+----------------+----------------+----------------+
@@ -20,6 +20,25 @@
 //! +------------------------------------------------------------------+
 //! ```
 //!
+//! v2 分片头部 14 字节，消息 ID 扩展为 32 位，并带标志位和每分片 CRC32：
+//!
+//! ```text
+//! +-----------+-----------+----------------+--------------+-----------+-----------+
+//! | 0x02 (1)  | flags (1) | message_id (4) | sequence (2) | total (2) | crc32 (4) |
+//! +-----------+-----------+----------------+--------------+-----------+-----------+
+//! |                          payload (variable)                                   |
+//! +-------------------------------------------------------------------------------+
+//! ```
+//!
+//! CRC32 覆盖头部前 10 字节和 payload；payload 长度由分片长度得出。
+//! 标志位见 [`ChunkFlags`]。
+//!
+//! v1 头部没有版本标记，首字节是 message_id 的低字节，可能恰好是 `0x02`。
+//! [`ChunkHeader::from_bytes`] 只有在 CRC 校验通过时才按 v2 解析，否则按 v1 解析，
+//! 因此能同时接收新旧两种对端的分片。发送端通过
+//! [`CHUNK_VERSION_CHARACTERISTIC_UUID`](crate::gatt::CHUNK_VERSION_CHARACTERISTIC_UUID)
+//! 确认对端支持 v2 后才发送 v2 分片。
+//!
 //! # Example
 //!
 //! ```
@@ -54,7 +73,9 @@
 //! assert_eq!(assembled, data);
 //! ```
 
-use crate::gatt::{ATT_HEADER_SIZE, CHUNK_HEADER_SIZE};
+use crate::gatt::{ATT_HEADER_SIZE, CHUNK_HEADER_SIZE, CHUNK_HEADER_V2_SIZE};
+use crate::link::LinkParameters;
+use crate::retransmit::{ChunkReport, REPORT_INTERVAL};
 use crate::BleError;
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
@@ -63,34 +84,142 @@ use tracing::{debug, trace, warn};
 /// 默认重组超时时间 (30 秒)
 pub const DEFAULT_REASSEMBLE_TIMEOUT: Duration = Duration::from_secs(30);
 
+/// v2 头部的版本标记（首字节）
+const CHUNK_V2_MARKER: u8 = 0x02;
+
+/// v2 头部中 CRC32 之前的字节数
+const CHUNK_V2_CHECKSUM_OFFSET: usize = CHUNK_HEADER_V2_SIZE - 4;
+
+/// 分片头部版本
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum ChunkVersion {
+    /// 8 字节头部，16 位消息 ID，无校验
+    #[default]
+    V1,
+    /// 14 字节头部，32 位消息 ID，带标志位和 CRC32
+    V2,
+}
+
+impl ChunkVersion {
+    /// 版本号（用于 GATT 特征值）
+    pub const fn as_u8(self) -> u8 {
+        match self {
+            Self::V1 => 1,
+            Self::V2 => 2,
+        }
+    }
+
+    /// 头部大小
+    pub const fn header_size(self) -> usize {
+        match self {
+            Self::V1 => CHUNK_HEADER_SIZE,
+            Self::V2 => CHUNK_HEADER_V2_SIZE,
+        }
+    }
+}
+
+/// v2 分片标志位
+///
+/// `FIRST` 和 `LAST` 由 [`Chunker`] 自动设置；`COMPRESSED` 和 `MUX` 由调用方指定，
+/// 分别表示整条消息的 payload 经过压缩、是一个多路复用帧，同一消息的所有分片必须一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ChunkFlags(u8);
+
+impl ChunkFlags {
+    /// 消息的第一个分片
+    pub const FIRST: Self = Self(0x01);
+    /// 消息的最后一个分片
+    pub const LAST: Self = Self(0x02);
+    /// 消息 payload 已压缩
+    pub const COMPRESSED: Self = Self(0x04);
+    /// 消息 payload 是多路复用帧（否则为整条消息）
+    pub const MUX: Self = Self(0x08);
+
+    const ALL: u8 = 0x0f;
+
+    /// 无标志位
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// 原始字节
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// 从字节解析，包含未知位时返回 `None`
+    pub const fn from_bits(bits: u8) -> Option<Self> {
+        if bits & !Self::ALL == 0 {
+            Some(Self(bits))
+        } else {
+            None
+        }
+    }
+
+    /// 是否包含 `other` 的全部标志位
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// 设置或清除 `other` 的标志位
+    pub fn set(&mut self, other: Self, value: bool) {
+        if value {
+            self.0 |= other.0;
+        } else {
+            self.0 &= !other.0;
+        }
+    }
+}
+
+impl std::ops::BitOr for ChunkFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
 /// 分片头部
 ///
 /// 每个数据分片的头部信息，用于标识分片属于哪个消息以及在消息中的位置。
 ///
 /// # 字段
 ///
-/// - `message_id`: 消息唯一标识符 (2 bytes)
+/// - `version`: 头部版本，决定线上格式
+/// - `flags`: v2 标志位（v1 恒为空）
+/// - `message_id`: 消息唯一标识符（v1 为 2 bytes，v2 为 4 bytes）
 /// - `sequence_number`: 当前分片序号，从 0 开始 (2 bytes)
 /// - `total_chunks`: 总分片数 (2 bytes)
-/// - `payload_length`: 当前分片的 payload 长度 (2 bytes)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// - `payload_length`: 当前分片的 payload 长度（v1 为 2 bytes；v2 由分片长度得出）
+/// - `checksum`: v2 分片携带的 CRC32（v1 为 `None`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct ChunkHeader {
+    /// 头部版本
+    pub version: ChunkVersion,
+    /// 标志位（仅 v2）
+    pub flags: ChunkFlags,
     /// 消息 ID（用于识别属于同一消息的分片）
-    pub message_id: u16,
+    pub message_id: u32,
     /// 当前分片序号（从 0 开始）
     pub sequence_number: u16,
     /// 总分片数
     pub total_chunks: u16,
     /// 当前分片 payload 长度
     pub payload_length: u16,
+    /// 分片携带的 CRC32（仅 v2）
+    pub checksum: Option<u32>,
 }
 
 impl ChunkHeader {
-    /// 从字节数组解析头部
+    /// 从分片解析头部，自动识别版本
+    ///
+    /// 首字节为 v2 版本标记、长度足够且 CRC32 校验通过时按 v2 解析，
+    /// 否则按 v1 解析。v2 解析需要完整分片（头部 + payload）；
+    /// 只传入 8 字节时总是按 v1 解析。
     ///
     /// # Arguments
     ///
-    /// * `bytes` - 包含头部数据的字节数组，至少需要 8 字节
+    /// * `bytes` - 分片数据，至少需要 8 字节
     ///
     /// # Returns
     ///
@@ -110,27 +239,74 @@ impl ChunkHeader {
     /// assert_eq!(header.payload_length, 10);
     /// ```
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, BleError> {
-        if bytes.len() < CHUNK_HEADER_SIZE {
+        if let Ok(header) = Self::parse(bytes, ChunkVersion::V2) {
+            if header.verify(&bytes[CHUNK_HEADER_V2_SIZE..]) {
+                return Ok(header);
+            }
+        }
+        Self::parse(bytes, ChunkVersion::V1)
+    }
+
+    /// 按指定版本解析头部
+    ///
+    /// 已知对端使用 v2 时用此方法解析，CRC32 不匹配的分片仍能得到头部，
+    /// 由 [`Reassembler::add_chunk`] 拒绝，而不会被误当作 v1 分片。
+    ///
+    /// # Errors
+    ///
+    /// - `BleError::ChunkError` - 长度不足、缺少 v2 版本标记或包含未知标志位
+    pub fn parse(bytes: &[u8], version: ChunkVersion) -> Result<Self, BleError> {
+        let header_size = version.header_size();
+        if bytes.len() < header_size {
             return Err(BleError::ChunkError(format!(
                 "Header too short: {} bytes, expected at least {}",
                 bytes.len(),
-                CHUNK_HEADER_SIZE
+                header_size
             )));
         }
-
-        Ok(Self {
-            message_id: u16::from_le_bytes([bytes[0], bytes[1]]),
-            sequence_number: u16::from_le_bytes([bytes[2], bytes[3]]),
-            total_chunks: u16::from_le_bytes([bytes[4], bytes[5]]),
-            payload_length: u16::from_le_bytes([bytes[6], bytes[7]]),
-        })
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes([bytes[i], bytes[i + 1], bytes[i + 2], bytes[i + 3]]);
+
+        match version {
+            ChunkVersion::V1 => Ok(Self::new(u16_at(0) as u32, u16_at(2), u16_at(4), u16_at(6))),
+            ChunkVersion::V2 => {
+                if bytes[0] != CHUNK_V2_MARKER {
+                    return Err(BleError::ChunkError(format!(
+                        "Not a v2 chunk header: marker {:#04x}",
+                        bytes[0]
+                    )));
+                }
+                let flags = ChunkFlags::from_bits(bytes[1]).ok_or_else(|| {
+                    BleError::ChunkError(format!("Unknown chunk flags: {:#04x}", bytes[1]))
+                })?;
+                let payload_length = bytes.len() - CHUNK_HEADER_V2_SIZE;
+                if payload_length > u16::MAX as usize {
+                    return Err(BleError::ChunkError(format!(
+                        "Chunk payload too long: {} bytes",
+                        payload_length
+                    )));
+                }
+                Ok(Self {
+                    version,
+                    flags,
+                    message_id: u32_at(2),
+                    sequence_number: u16_at(6),
+                    total_chunks: u16_at(8),
+                    payload_length: payload_length as u16,
+                    checksum: Some(u32_at(CHUNK_V2_CHECKSUM_OFFSET)),
+                })
+            }
+        }
     }
 
     /// 序列化为字节数组
     ///
+    /// v1 的 `message_id` 只写入低 16 位；v2 写入 `checksum`（为 `None` 时写 0），
+    /// 要得到带正确校验和的分片请用 [`encode`](Self::encode)。
+    ///
     /// # Returns
     ///
-    /// 8 字节的头部数据
+    /// 头部数据，长度为 [`ChunkVersion::header_size`]
     ///
     /// # Example
     ///
@@ -142,32 +318,113 @@ impl ChunkHeader {
     ///     sequence_number: 0,
     ///     total_chunks: 3,
     ///     payload_length: 12,
+    ///     ..Default::default()
     /// };
     ///
     /// let bytes = header.to_bytes();
     /// assert_eq!(bytes.len(), 8);
     /// ```
-    pub fn to_bytes(&self) -> [u8; CHUNK_HEADER_SIZE] {
-        let mut bytes = [0u8; CHUNK_HEADER_SIZE];
-        bytes[0..2].copy_from_slice(&self.message_id.to_le_bytes());
-        bytes[2..4].copy_from_slice(&self.sequence_number.to_le_bytes());
-        bytes[4..6].copy_from_slice(&self.total_chunks.to_le_bytes());
-        bytes[6..8].copy_from_slice(&self.payload_length.to_le_bytes());
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.version.header_size());
+        match self.version {
+            ChunkVersion::V1 => {
+                bytes.extend_from_slice(&(self.message_id as u16).to_le_bytes());
+                bytes.extend_from_slice(&self.sequence_number.to_le_bytes());
+                bytes.extend_from_slice(&self.total_chunks.to_le_bytes());
+                bytes.extend_from_slice(&self.payload_length.to_le_bytes());
+            }
+            ChunkVersion::V2 => {
+                bytes.push(CHUNK_V2_MARKER);
+                bytes.push(self.flags.bits());
+                bytes.extend_from_slice(&self.message_id.to_le_bytes());
+                bytes.extend_from_slice(&self.sequence_number.to_le_bytes());
+                bytes.extend_from_slice(&self.total_chunks.to_le_bytes());
+                bytes.extend_from_slice(&self.checksum.unwrap_or(0).to_le_bytes());
+            }
+        }
         bytes
     }
 
-    /// 创建新的头部
+    /// 编码完整分片（头部 + payload），v2 头部写入 payload 的 CRC32
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nearclip_ble::chunk::{ChunkFlags, ChunkHeader, ChunkVersion};
+    ///
+    /// let header = ChunkHeader::new_v2(70_000, 0, 1, ChunkFlags::FIRST | ChunkFlags::LAST);
+    /// let chunk = header.encode(b"hello");
+    ///
+    /// let parsed = ChunkHeader::from_bytes(&chunk).unwrap();
+    /// assert_eq!(parsed.version, ChunkVersion::V2);
+    /// assert_eq!(parsed.message_id, 70_000);
+    /// assert!(parsed.verify(b"hello"));
+    /// ```
+    pub fn encode(&self, payload: &[u8]) -> Vec<u8> {
+        let header = Self {
+            payload_length: payload.len() as u16,
+            checksum: (self.version == ChunkVersion::V2).then(|| self.compute_checksum(payload)),
+            ..*self
+        };
+        let mut chunk = header.to_bytes();
+        chunk.extend_from_slice(payload);
+        chunk
+    }
+
+    /// 计算 v2 校验和：头部前 10 字节 + payload 的 CRC32
+    fn compute_checksum(&self, payload: &[u8]) -> u32 {
+        let header = Self {
+            version: ChunkVersion::V2,
+            checksum: None,
+            ..*self
+        };
+        let mut hasher = crc32fast::Hasher::new();
+        hasher.update(&header.to_bytes()[..CHUNK_V2_CHECKSUM_OFFSET]);
+        hasher.update(payload);
+        hasher.finalize()
+    }
+
+    /// 校验 payload 是否与头部携带的 CRC32 一致（没有校验和时总是通过）
+    pub fn verify(&self, payload: &[u8]) -> bool {
+        self.checksum
+            .is_none_or(|checksum| checksum == self.compute_checksum(payload))
+    }
+
+    /// 头部大小
+    pub fn header_size(&self) -> usize {
+        self.version.header_size()
+    }
+
+    /// 创建新的 v1 头部
     pub fn new(
-        message_id: u16,
+        message_id: u32,
         sequence_number: u16,
         total_chunks: u16,
         payload_length: u16,
     ) -> Self {
         Self {
+            version: ChunkVersion::V1,
+            flags: ChunkFlags::empty(),
             message_id,
             sequence_number,
             total_chunks,
             payload_length,
+            checksum: None,
+        }
+    }
+
+    /// 创建新的 v2 头部
+    ///
+    /// `payload_length` 和 `checksum` 在 [`encode`](Self::encode) 时填入。
+    pub fn new_v2(message_id: u32, sequence_number: u16, total_chunks: u16, flags: ChunkFlags) -> Self {
+        Self {
+            version: ChunkVersion::V2,
+            flags,
+            message_id,
+            sequence_number,
+            total_chunks,
+            payload_length: 0,
+            checksum: None,
         }
     }
 }
@@ -212,30 +469,62 @@ impl Chunker {
     /// }
     /// ```
     pub fn chunk(data: &[u8], message_id: u16, mtu: usize) -> Result<Vec<Vec<u8>>, BleError> {
+        Self::chunk_versioned(data, message_id as u32, mtu, ChunkVersion::V1, ChunkFlags::empty())
+    }
+
+    /// 按指定头部版本分片
+    ///
+    /// v2 分片自动设置 `FIRST` / `LAST` 标志位并携带 CRC32；`flags` 中的其他
+    /// 标志位（如 `COMPRESSED`）写入每个分片。v1 分片忽略 `flags`。
+    ///
+    /// # Errors
+    ///
+    /// - `BleError::ChunkError` - MTU 太小、数据过大，或 v1 的 `message_id` 超过 16 位
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nearclip_ble::chunk::{ChunkFlags, ChunkHeader, ChunkVersion, Chunker};
+    ///
+    /// let chunks = Chunker::chunk_versioned(&[0u8; 20], 100_000, 23, ChunkVersion::V2, ChunkFlags::empty()).unwrap();
+    /// assert_eq!(chunks.len(), 4); // 每个分片 23 - 3 - 14 = 6 字节 payload
+    ///
+    /// let first = ChunkHeader::from_bytes(&chunks[0]).unwrap();
+    /// assert!(first.flags.contains(ChunkFlags::FIRST));
+    /// let last = ChunkHeader::from_bytes(&chunks[3]).unwrap();
+    /// assert!(last.flags.contains(ChunkFlags::LAST));
+    /// ```
+    pub fn chunk_versioned(
+        data: &[u8],
+        message_id: u32,
+        mtu: usize,
+        version: ChunkVersion,
+        flags: ChunkFlags,
+    ) -> Result<Vec<Vec<u8>>, BleError> {
+        if version == ChunkVersion::V1 && message_id > u16::MAX as u32 {
+            return Err(BleError::ChunkError(format!(
+                "Message ID {} does not fit a v1 chunk header",
+                message_id
+            )));
+        }
+
         // 计算有效 payload 大小
-        // MTU - ATT_HEADER_SIZE - CHUNK_HEADER_SIZE
+        // MTU - ATT_HEADER_SIZE - 头部大小
+        let header_size = version.header_size();
         let effective_mtu = mtu.saturating_sub(ATT_HEADER_SIZE);
-        if effective_mtu <= CHUNK_HEADER_SIZE {
+        if effective_mtu <= header_size {
             return Err(BleError::ChunkError(format!(
                 "MTU too small: {} (effective: {}), minimum needed: {}",
                 mtu,
                 effective_mtu,
-                ATT_HEADER_SIZE + CHUNK_HEADER_SIZE + 1
+                ATT_HEADER_SIZE + header_size + 1
             )));
         }
 
-        let max_payload_size = effective_mtu - CHUNK_HEADER_SIZE;
-
-        // 空数据特殊处理：发送一个空 payload 的分片
-        if data.is_empty() {
-            let header = ChunkHeader::new(message_id, 0, 1, 0);
-            let mut chunk = Vec::with_capacity(CHUNK_HEADER_SIZE);
-            chunk.extend_from_slice(&header.to_bytes());
-            return Ok(vec![chunk]);
-        }
+        let max_payload_size = effective_mtu - header_size;
 
-        // 计算需要的分片数
-        let total_chunks = data.len().div_ceil(max_payload_size);
+        // 计算需要的分片数（空数据发送一个空 payload 的分片）
+        let total_chunks = data.len().div_ceil(max_payload_size).max(1);
         if total_chunks > u16::MAX as usize {
             return Err(BleError::ChunkError(format!(
                 "Data too large: {} bytes would require {} chunks (max: {})",
@@ -253,20 +542,26 @@ impl Chunker {
             data_len = data.len(),
             total_chunks,
             max_payload_size,
+            ?version,
             "Chunking data"
         );
 
-        for (i, chunk_data) in data.chunks(max_payload_size).enumerate() {
-            let header = ChunkHeader::new(
-                message_id,
-                i as u16,
-                total_chunks,
-                chunk_data.len() as u16,
-            );
-
-            let mut chunk = Vec::with_capacity(CHUNK_HEADER_SIZE + chunk_data.len());
-            chunk.extend_from_slice(&header.to_bytes());
-            chunk.extend_from_slice(chunk_data);
+        let payloads = data
+            .chunks(max_payload_size)
+            .chain(data.is_empty().then_some(data));
+        for (i, chunk_data) in payloads.enumerate() {
+            let sequence = i as u16;
+            let header = match version {
+                ChunkVersion::V1 => {
+                    ChunkHeader::new(message_id, sequence, total_chunks, chunk_data.len() as u16)
+                }
+                ChunkVersion::V2 => {
+                    let mut flags = flags;
+                    flags.set(ChunkFlags::FIRST, sequence == 0);
+                    flags.set(ChunkFlags::LAST, sequence + 1 == total_chunks);
+                    ChunkHeader::new_v2(message_id, sequence, total_chunks, flags)
+                }
+            };
 
             trace!(
                 message_id,
@@ -275,11 +570,34 @@ impl Chunker {
                 "Created chunk"
             );
 
-            chunks.push(chunk);
+            chunks.push(header.encode(chunk_data));
         }
 
         Ok(chunks)
     }
+
+    /// 按协商后的链路参数分片
+    ///
+    /// 分片大小取 [`LinkParameters::chunk_mtu`]，使每个分片填满整数个链路层数据包。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nearclip_ble::chunk::Chunker;
+    /// use nearclip_ble::LinkParameters;
+    ///
+    /// // MTU 247 但未启用 DLE：分片收缩到 9 个 27 字节的数据包
+    /// let link = LinkParameters::new().with_mtu(247);
+    /// let chunks = Chunker::chunk_for_link(&[0u8; 1000], 1, &link).unwrap();
+    /// assert_eq!(chunks[0].len(), 239 - 3);
+    /// ```
+    pub fn chunk_for_link(
+        data: &[u8],
+        message_id: u16,
+        link: &LinkParameters,
+    ) -> Result<Vec<Vec<u8>>, BleError> {
+        Self::chunk(data, message_id, link.chunk_mtu())
+    }
 }
 
 /// 数据重组器
@@ -291,6 +609,8 @@ impl Chunker {
 /// - 支持乱序接收分片
 /// - 自动去重（相同序号的分片只保留第一个）
 /// - 支持超时检测
+/// - 生成选择性确认报告（见 [`crate::retransmit`]）
+/// - 拒绝 CRC32 不匹配、版本或标志位不一致的 v2 分片
 ///
 /// # Example
 ///
@@ -322,7 +642,13 @@ impl Chunker {
 #[derive(Debug)]
 pub struct Reassembler {
     /// 消息 ID
-    message_id: u16,
+    message_id: u32,
+    /// 分片头部版本（由第一个接受的分片决定）
+    version: Option<ChunkVersion>,
+    /// 消息 payload 是否已压缩（仅 v2）
+    compressed: bool,
+    /// 消息 payload 是否为多路复用帧（仅 v2）
+    mux: bool,
     /// 预期分片总数
     total_chunks: u16,
     /// 已接收的分片 (sequence_number -> payload)
@@ -331,6 +657,12 @@ pub struct Reassembler {
     created_at: Instant,
     /// 超时时间
     timeout: Duration,
+    /// 已收到的最大序号
+    highest: Option<u16>,
+    /// 自上次报告以来收到的分片数
+    unreported: u16,
+    /// 需要立即报告（出现缺口、重复或补洞）
+    report_due: bool,
 }
 
 impl Reassembler {
@@ -341,7 +673,7 @@ impl Reassembler {
     /// * `message_id` - 消息 ID
     /// * `total_chunks` - 预期分片总数
     /// * `timeout` - 超时时间
-    pub fn new(message_id: u16, total_chunks: u16, timeout: Duration) -> Self {
+    pub fn new(message_id: u32, total_chunks: u16, timeout: Duration) -> Self {
         debug!(
             message_id,
             total_chunks,
@@ -351,10 +683,16 @@ impl Reassembler {
 
         Self {
             message_id,
+            version: None,
+            compressed: false,
+            mux: false,
             total_chunks,
             chunks: HashMap::with_capacity(total_chunks as usize),
             created_at: Instant::now(),
             timeout,
+            highest: None,
+            unreported: 0,
+            report_due: false,
         }
     }
 
@@ -371,7 +709,8 @@ impl Reassembler {
     ///
     /// # Errors
     ///
-    /// - `BleError::ChunkError` - message_id 不匹配或 sequence 无效
+    /// - `BleError::ChunkError` - message_id 不匹配、sequence 无效、CRC32 不匹配，
+    ///   或头部版本、标志位与同一消息的其他分片不一致
     pub fn add_chunk(&mut self, header: ChunkHeader, payload: Vec<u8>) -> Result<(), BleError> {
         // 验证 message_id
         if header.message_id != self.message_id {
@@ -406,6 +745,41 @@ impl Reassembler {
             )));
         }
 
+        // 验证 CRC32（仅 v2）
+        if !header.verify(&payload) {
+            return Err(BleError::ChunkError(format!(
+                "Checksum mismatch: message {} chunk {}",
+                header.message_id, header.sequence_number
+            )));
+        }
+
+        // 验证版本和标志位
+        if self.version.is_some_and(|v| v != header.version) {
+            return Err(BleError::ChunkError(format!(
+                "Chunk version mismatch: expected {:?}, got {:?}",
+                self.version, header.version
+            )));
+        }
+        let compressed = header.flags.contains(ChunkFlags::COMPRESSED);
+        let mux = header.flags.contains(ChunkFlags::MUX);
+        if header.version == ChunkVersion::V2 {
+            let first = header.sequence_number == 0;
+            let last = header.sequence_number + 1 == self.total_chunks;
+            if header.flags.contains(ChunkFlags::FIRST) != first
+                || header.flags.contains(ChunkFlags::LAST) != last
+                || (self.version.is_some() && (compressed != self.compressed || mux != self.mux))
+            {
+                return Err(BleError::ChunkError(format!(
+                    "Inconsistent chunk flags {:#04x} at sequence {}",
+                    header.flags.bits(),
+                    header.sequence_number
+                )));
+            }
+        }
+        self.version = Some(header.version);
+        self.compressed = compressed;
+        self.mux = mux;
+
         // 检查是否重复
         if self.chunks.contains_key(&header.sequence_number) {
             warn!(
@@ -413,9 +787,20 @@ impl Reassembler {
                 sequence = header.sequence_number,
                 "Duplicate chunk received, ignoring"
             );
+            // 重复说明发送端没收到之前的报告
+            self.report_due = true;
             return Ok(());
         }
 
+        let sequence = header.sequence_number;
+        let gap = self.highest.map_or(sequence > 0, |h| sequence > h + 1);
+        let fill = self.highest.is_some_and(|h| sequence < h);
+        if gap || fill || sequence + 1 == self.total_chunks {
+            self.report_due = true;
+        }
+        self.highest = Some(self.highest.map_or(sequence, |h| h.max(sequence)));
+        self.unreported += 1;
+
         trace!(
             message_id = self.message_id,
             sequence = header.sequence_number,
@@ -444,10 +829,67 @@ impl Reassembler {
     }
 
     /// 获取消息 ID
-    pub fn message_id(&self) -> u16 {
+    pub fn message_id(&self) -> u32 {
         self.message_id
     }
 
+    /// 分片头部版本（尚未收到分片时为 `None`）
+    pub fn version(&self) -> Option<ChunkVersion> {
+        self.version
+    }
+
+    /// 消息 payload 是否标记为已压缩
+    pub fn is_compressed(&self) -> bool {
+        self.compressed
+    }
+
+    /// 消息 payload 是否标记为多路复用帧
+    pub fn is_mux(&self) -> bool {
+        self.mux
+    }
+
+    /// 第一个未收到的序号（之前的分片已全部收到）
+    pub fn next_expected(&self) -> u16 {
+        (0..self.total_chunks)
+            .find(|i| !self.chunks.contains_key(i))
+            .unwrap_or(self.total_chunks)
+    }
+
+    /// 已收到的最大序号之前缺失的序号区间（闭区间，升序）
+    pub fn missing_ranges(&self) -> Vec<(u16, u16)> {
+        let Some(highest) = self.highest else {
+            return Vec::new();
+        };
+        let mut ranges: Vec<(u16, u16)> = Vec::new();
+        for i in (0..highest).filter(|i| !self.chunks.contains_key(i)) {
+            match ranges.last_mut() {
+                Some((_, end)) if *end + 1 == i => *end = i,
+                _ => ranges.push((i, i)),
+            }
+        }
+        ranges
+    }
+
+    /// 取出待发送的接收报告
+    ///
+    /// 在每次 [`add_chunk`](Self::add_chunk) 之后调用。出现缺口、收到重复或补洞分片、
+    /// 收到最后一个序号、消息完整，或已累计 [`REPORT_INTERVAL`] 个未报告分片时返回报告。
+    pub fn take_report(&mut self) -> Option<ChunkReport> {
+        let highest = self.highest?;
+        if !(self.report_due || self.unreported >= REPORT_INTERVAL || self.is_complete()) {
+            return None;
+        }
+        self.report_due = false;
+        self.unreported = 0;
+        Some(ChunkReport {
+            version: self.version.unwrap_or_default(),
+            message_id: self.message_id,
+            next_expected: self.next_expected(),
+            highest,
+            missing: self.missing_ranges(),
+        })
+    }
+
     /// 重组完整数据
     ///
     /// 将所有分片按序号排序并拼接为完整数据。
@@ -557,6 +999,55 @@ mod tests {
         assert_eq!(original, parsed);
     }
 
+    #[test]
+    fn test_chunk_header_v2_roundtrip() {
+        let header = ChunkHeader::new_v2(0x1234_5678, 3, 9, ChunkFlags::COMPRESSED);
+        let chunk = header.encode(b"payload");
+        assert_eq!(chunk.len(), CHUNK_HEADER_V2_SIZE + 7);
+        assert_eq!(chunk[0], CHUNK_V2_MARKER);
+        assert_eq!(chunk[1], ChunkFlags::COMPRESSED.bits());
+
+        let parsed = ChunkHeader::from_bytes(&chunk).unwrap();
+        assert_eq!(parsed.version, ChunkVersion::V2);
+        assert_eq!(parsed.flags, ChunkFlags::COMPRESSED);
+        assert_eq!(parsed.message_id, 0x1234_5678);
+        assert_eq!((parsed.sequence_number, parsed.total_chunks), (3, 9));
+        assert_eq!(parsed.payload_length, 7);
+        assert!(parsed.verify(b"payload"));
+        assert!(!parsed.verify(b"paylaod"));
+        assert_eq!(parsed.encode(b"payload"), chunk);
+    }
+
+    #[test]
+    fn test_chunk_header_v1_marker_collision_parses_as_v1() {
+        // v1 消息 ID 的低字节恰好是 v2 版本标记
+        let chunk = ChunkHeader::new(0x0102, 0, 1, 12).encode(&[0xEE; 12]);
+        let header = ChunkHeader::from_bytes(&chunk).unwrap();
+        assert_eq!(header.version, ChunkVersion::V1);
+        assert_eq!(header.message_id, 0x0102);
+        assert_eq!(header.checksum, None);
+    }
+
+    #[test]
+    fn test_chunk_header_parse_v2_rejects_invalid() {
+        let chunk = ChunkHeader::new_v2(1, 0, 1, ChunkFlags::FIRST).encode(b"x");
+        assert!(ChunkHeader::parse(&chunk[..CHUNK_HEADER_V2_SIZE - 1], ChunkVersion::V2).is_err());
+
+        let mut bad_marker = chunk.clone();
+        bad_marker[0] = 0x01;
+        assert!(ChunkHeader::parse(&bad_marker, ChunkVersion::V2).is_err());
+
+        let mut unknown_flags = chunk.clone();
+        unknown_flags[1] = 0x80;
+        assert!(ChunkHeader::parse(&unknown_flags, ChunkVersion::V2).is_err());
+
+        // 校验和错误时仍能解析出头部，由重组器拒绝
+        let mut corrupt = chunk;
+        corrupt[CHUNK_HEADER_V2_SIZE] ^= 0xFF;
+        let header = ChunkHeader::parse(&corrupt, ChunkVersion::V2).unwrap();
+        assert!(!header.verify(&corrupt[CHUNK_HEADER_V2_SIZE..]));
+    }
+
     // ========================================
     // Chunker Tests
     // ========================================
@@ -645,6 +1136,54 @@ mod tests {
         assert_eq!(chunks.len(), 1); // Should fit in one chunk
     }
 
+    #[test]
+    fn test_chunker_for_link() {
+        use crate::link::MAX_DATA_LENGTH;
+
+        let data = vec![0xAB; 1000];
+        let link = LinkParameters::new().with_mtu(185);
+        let chunks = Chunker::chunk_for_link(&data, 1, &link).unwrap();
+        assert_eq!(chunks, Chunker::chunk(&data, 1, 185).unwrap());
+
+        // 启用 DLE 后用满 MTU
+        let link = link.with_mtu(247).with_data_length(MAX_DATA_LENGTH);
+        let chunks = Chunker::chunk_for_link(&data, 1, &link).unwrap();
+        assert_eq!(chunks[0].len(), 247 - ATT_HEADER_SIZE);
+    }
+
+    #[test]
+    fn test_chunker_v2_sets_flags_and_wide_message_id() {
+        let data = vec![0x5A; 30];
+        let chunks =
+            Chunker::chunk_versioned(&data, 100_000, 23, ChunkVersion::V2, ChunkFlags::COMPRESSED).unwrap();
+        // payload = 23 - 3 - 14 = 6 bytes
+        assert_eq!(chunks.len(), 5);
+
+        for (i, chunk) in chunks.iter().enumerate() {
+            let header = ChunkHeader::from_bytes(chunk).unwrap();
+            assert_eq!(header.version, ChunkVersion::V2);
+            assert_eq!(header.message_id, 100_000);
+            assert!(header.flags.contains(ChunkFlags::COMPRESSED));
+            assert_eq!(header.flags.contains(ChunkFlags::FIRST), i == 0);
+            assert_eq!(header.flags.contains(ChunkFlags::LAST), i == 4);
+        }
+
+        let single = Chunker::chunk_versioned(b"", 7, 23, ChunkVersion::V2, ChunkFlags::empty()).unwrap();
+        let header = ChunkHeader::from_bytes(&single[0]).unwrap();
+        assert_eq!(header.flags, ChunkFlags::FIRST | ChunkFlags::LAST);
+        assert_eq!(header.payload_length, 0);
+    }
+
+    #[test]
+    fn test_chunker_v1_rejects_wide_message_id() {
+        let result = Chunker::chunk_versioned(b"data", 70_000, 23, ChunkVersion::V1, ChunkFlags::empty());
+        assert!(result.unwrap_err().to_string().contains("does not fit"));
+
+        // v2 头部更大，最小 MTU 也随之增大
+        assert!(Chunker::chunk_versioned(b"data", 1, 17, ChunkVersion::V2, ChunkFlags::empty()).is_err());
+        assert!(Chunker::chunk_versioned(b"data", 1, 18, ChunkVersion::V2, ChunkFlags::empty()).is_ok());
+    }
+
     // ========================================
     // Reassembler Tests
     // ========================================
@@ -795,6 +1334,145 @@ mod tests {
         assert!(!reassembler.is_expired());
     }
 
+    fn add(reassembler: &mut Reassembler, sequence: u16) {
+        let header = ChunkHeader::new(1, sequence, reassembler.total_chunks, 1);
+        reassembler.add_chunk(header, vec![sequence as u8]).unwrap();
+    }
+
+    #[test]
+    fn test_reassembler_reports_every_interval() {
+        let mut reassembler = Reassembler::new(1, 20, DEFAULT_REASSEMBLE_TIMEOUT);
+        assert!(reassembler.take_report().is_none());
+
+        for i in 0..REPORT_INTERVAL - 1 {
+            add(&mut reassembler, i);
+            assert!(reassembler.take_report().is_none());
+        }
+        add(&mut reassembler, REPORT_INTERVAL - 1);
+        let report = reassembler.take_report().unwrap();
+        assert_eq!(report.next_expected, REPORT_INTERVAL);
+        assert_eq!(report.highest, REPORT_INTERVAL - 1);
+        assert!(report.missing.is_empty());
+    }
+
+    #[test]
+    fn test_reassembler_reports_gaps_immediately() {
+        let mut reassembler = Reassembler::new(1, 20, DEFAULT_REASSEMBLE_TIMEOUT);
+        add(&mut reassembler, 0);
+        assert!(reassembler.take_report().is_none());
+
+        // 1..=2 丢失
+        add(&mut reassembler, 3);
+        let report = reassembler.take_report().unwrap();
+        assert_eq!((report.next_expected, report.highest), (1, 3));
+        assert_eq!(report.missing, vec![(1, 2)]);
+
+        add(&mut reassembler, 4);
+        add(&mut reassembler, 6);
+        assert_eq!(reassembler.take_report().unwrap().missing, vec![(1, 2), (5, 5)]);
+
+        // 补洞和重复也会触发报告
+        add(&mut reassembler, 2);
+        assert_eq!(reassembler.take_report().unwrap().missing, vec![(1, 1), (5, 5)]);
+        add(&mut reassembler, 2);
+        assert!(reassembler.take_report().is_some());
+    }
+
+    #[test]
+    fn test_reassembler_reports_last_and_complete() {
+        let mut reassembler = Reassembler::new(1, 3, DEFAULT_REASSEMBLE_TIMEOUT);
+        add(&mut reassembler, 0);
+        add(&mut reassembler, 2);
+        assert_eq!(reassembler.take_report().unwrap().missing, vec![(1, 1)]);
+
+        add(&mut reassembler, 1);
+        let report = reassembler.take_report().unwrap();
+        assert_eq!((report.next_expected, report.highest), (3, 2));
+        assert!(report.missing.is_empty());
+    }
+
+    fn v2_chunks(data: &[u8], message_id: u32, flags: ChunkFlags) -> Vec<Vec<u8>> {
+        Chunker::chunk_versioned(data, message_id, 23, ChunkVersion::V2, flags).unwrap()
+    }
+
+    fn add_v2(reassembler: &mut Reassembler, chunk: &[u8]) -> Result<(), BleError> {
+        let header = ChunkHeader::parse(chunk, ChunkVersion::V2)?;
+        reassembler.add_chunk(header, chunk[CHUNK_HEADER_V2_SIZE..].to_vec())
+    }
+
+    #[test]
+    fn test_reassembler_rejects_corrupt_v2_chunk() {
+        let chunks = v2_chunks(b"integrity matters", 9, ChunkFlags::empty());
+        let mut reassembler = Reassembler::new(9, chunks.len() as u16, DEFAULT_REASSEMBLE_TIMEOUT);
+
+        let mut corrupt = chunks[1].clone();
+        *corrupt.last_mut().unwrap() ^= 0x01;
+        let err = add_v2(&mut reassembler, &corrupt).unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert_eq!(reassembler.received_count(), 0);
+
+        // 自动识别时损坏的 v2 分片不会被当作同一消息的 v1 分片
+        let header = ChunkHeader::from_bytes(&corrupt).unwrap();
+        assert_eq!(header.version, ChunkVersion::V1);
+        assert!(reassembler.add_chunk(header, corrupt[CHUNK_HEADER_SIZE..].to_vec()).is_err());
+
+        for chunk in &chunks {
+            add_v2(&mut reassembler, chunk).unwrap();
+        }
+        assert_eq!(reassembler.version(), Some(ChunkVersion::V2));
+        assert_eq!(reassembler.take_report().unwrap().version, ChunkVersion::V2);
+        assert_eq!(reassembler.assemble().unwrap(), b"integrity matters");
+    }
+
+    #[test]
+    fn test_reassembler_rejects_inconsistent_version_and_flags() {
+        let chunks = v2_chunks(&[1u8; 12], 1, ChunkFlags::empty());
+        let mut reassembler = Reassembler::new(1, chunks.len() as u16, DEFAULT_REASSEMBLE_TIMEOUT);
+        add_v2(&mut reassembler, &chunks[0]).unwrap();
+
+        // 同一消息混入 v1 分片
+        let err = reassembler
+            .add_chunk(ChunkHeader::new(1, 1, 2, 6), vec![1u8; 6])
+            .unwrap_err();
+        assert!(err.to_string().contains("version mismatch"));
+
+        // FIRST 标志出现在非首个分片
+        let wrong_first = ChunkHeader::new_v2(1, 1, 2, ChunkFlags::FIRST | ChunkFlags::LAST).encode(&[1u8; 6]);
+        assert!(add_v2(&mut reassembler, &wrong_first).is_err());
+
+        // 压缩标志与首个分片不一致
+        let compressed = ChunkHeader::new_v2(1, 1, 2, ChunkFlags::LAST | ChunkFlags::COMPRESSED).encode(&[1u8; 6]);
+        assert!(add_v2(&mut reassembler, &compressed).is_err());
+
+        // 多路复用标志与首个分片不一致
+        let mux = ChunkHeader::new_v2(1, 1, 2, ChunkFlags::LAST | ChunkFlags::MUX).encode(&[1u8; 6]);
+        assert!(add_v2(&mut reassembler, &mux).is_err());
+
+        add_v2(&mut reassembler, &chunks[1]).unwrap();
+        assert!(reassembler.is_complete());
+        assert!(!reassembler.is_compressed());
+        assert!(!reassembler.is_mux());
+    }
+
+    #[test]
+    fn test_chunk_and_reassemble_v2_compressed() {
+        let original_data: Vec<u8> = (0..500).map(|i| (i % 251) as u8).collect();
+        let chunks = v2_chunks(&original_data, u32::MAX, ChunkFlags::COMPRESSED);
+        let header = ChunkHeader::from_bytes(&chunks[0]).unwrap();
+        let mut reassembler =
+            Reassembler::new(header.message_id, header.total_chunks, DEFAULT_REASSEMBLE_TIMEOUT);
+
+        for chunk in chunks.iter().rev() {
+            let header = ChunkHeader::from_bytes(chunk).unwrap();
+            reassembler
+                .add_chunk(header, chunk[header.header_size()..].to_vec())
+                .unwrap();
+        }
+
+        assert!(reassembler.is_compressed());
+        assert_eq!(reassembler.assemble().unwrap(), original_data);
+    }
+
     // ========================================
     // Chunker + Reassembler Integration Tests
     // ========================================
@@ -16,7 +16,12 @@ use tracing::{debug, error, info, warn};
 
 use tokio::sync::oneshot;
 
+use crate::advert::{
+    advertisement_epoch, until_next_advertisement_epoch, AdvertisementKey, AdvertisementResolver,
+    DEFAULT_ADVERTISEMENT_ROTATION_SECS,
+};
 use crate::error::BleError;
+use crate::link::{BlePhy, ConnectionPriority, LinkParameters, MAX_DATA_LENGTH};
 
 // ============================================================================
 // Configuration
@@ -45,6 +50,26 @@ pub struct BleControllerConfig {
 
     /// Connection timeout (milliseconds)
     pub connection_timeout_ms: u64,
+
+    /// Connection priority requested after connecting
+    pub connection_priority: ConnectionPriority,
+
+    /// PHY requested after connecting (`Le1M` = don't request a change)
+    pub preferred_phy: BlePhy,
+
+    /// Link-layer data length requested after connecting (0 = don't request DLE)
+    pub data_length: u16,
+
+    /// Rotation period of the advertisement identity token (milliseconds)
+    pub advertisement_rotation_ms: u64,
+
+    /// Report devices that are not paired
+    ///
+    /// When false, devices identified by connecting (legacy advertisements)
+    /// are only reported if they were registered with
+    /// [`BleController::add_paired_device`]. Advertisements carrying an
+    /// identity token are always filtered: strangers cannot be resolved.
+    pub discover_unpaired: bool,
 }
 
 impl Default for BleControllerConfig {
@@ -57,6 +82,11 @@ impl Default for BleControllerConfig {
             reconnect_base_delay_ms: 1000,   // 1 second
             health_check_interval_ms: 30000, // 30 seconds
             connection_timeout_ms: 10000,    // 10 seconds
+            connection_priority: ConnectionPriority::Balanced,
+            preferred_phy: BlePhy::Le2M,
+            data_length: MAX_DATA_LENGTH,
+            advertisement_rotation_ms: DEFAULT_ADVERTISEMENT_ROTATION_SECS * 1000,
+            discover_unpaired: true,
         }
     }
 }
@@ -66,6 +96,11 @@ impl Default for BleControllerConfig {
 // ============================================================================
 
 /// Discovered BLE device
+///
+/// A raw scan result from this controller only. Consumers that show nearby
+/// devices should merge it into `nearclip_device::NearbyDevices` (it converts
+/// with `nearclip_device::DiscoveredDevice::from`) rather than keep their own
+/// per-channel list.
 #[derive(Debug, Clone)]
 pub struct DiscoveredDevice {
     /// Platform-specific peripheral UUID
@@ -192,6 +227,9 @@ pub trait BleHardware: Send + Sync {
     // ========== Advertising ==========
 
     /// Start advertising with service data
+    ///
+    /// Called again with new service data when the identity token rotates;
+    /// backends replace the current advertisement.
     fn start_advertising(&self, service_data: &[u8]);
 
     /// Stop advertising
@@ -204,6 +242,135 @@ pub trait BleHardware: Send + Sync {
 
     /// Get MTU for a peripheral
     fn get_mtu(&self, peripheral_id: &str) -> u16;
+
+    // ========== L2CAP (optional) ==========
+    //
+    // Backends that support L2CAP connection-oriented channels publish their
+    // listening PSM in L2CAP_PSM_CHARACTERISTIC_UUID and override these.
+    // The defaults report L2CAP as unsupported, so callers fall back to GATT.
+
+    /// Open an L2CAP channel to a connected peer on the given PSM
+    ///
+    /// The outcome is reported asynchronously as
+    /// [`BleHardwareEvent::L2capOpened`] or [`BleHardwareEvent::L2capClosed`].
+    ///
+    /// # Returns
+    /// - `Ok(())` - The open was started
+    /// - `Err(String)` - Error message if it could not be started
+    fn open_l2cap_channel(&self, peripheral_id: &str, psm: u16) -> Result<(), String> {
+        let _ = (peripheral_id, psm);
+        Err("L2CAP is not supported".to_string())
+    }
+
+    /// Write bytes to an open L2CAP channel
+    ///
+    /// The channel is a reliable byte stream, not limited by the ATT MTU.
+    ///
+    /// # Returns
+    /// - `Ok(())` - Success
+    /// - `Err(String)` - Error message on failure
+    fn write_l2cap(&self, peripheral_id: &str, data: &[u8]) -> Result<(), String> {
+        let _ = (peripheral_id, data);
+        Err("L2CAP is not supported".to_string())
+    }
+
+    /// Close the L2CAP channel to a peer, if one is open
+    fn close_l2cap_channel(&self, peripheral_id: &str) {
+        let _ = peripheral_id;
+    }
+
+    // ========== Link Tuning (optional) ==========
+    //
+    // Requests are best effort: the controller or the peer may pick other
+    // values. Backends report what was actually negotiated with
+    // [`BleHardwareEvent::LinkParametersChanged`]. The defaults report the
+    // requests as unsupported, and the link keeps its current parameters.
+
+    /// Request a connection interval range for a connected peer
+    fn request_connection_priority(
+        &self,
+        peripheral_id: &str,
+        priority: ConnectionPriority,
+    ) -> Result<(), String> {
+        let _ = (peripheral_id, priority);
+        Err("Connection priority is not supported".to_string())
+    }
+
+    /// Request a PHY for both directions of a connection
+    fn request_phy(&self, peripheral_id: &str, phy: BlePhy) -> Result<(), String> {
+        let _ = (peripheral_id, phy);
+        Err("PHY update is not supported".to_string())
+    }
+
+    /// Request data length extension (link-layer payload in bytes, 27-251)
+    fn request_data_length(&self, peripheral_id: &str, octets: u16) -> Result<(), String> {
+        let _ = (peripheral_id, octets);
+        Err("Data length extension is not supported".to_string())
+    }
+
+    /// Get the current link parameters for a peer
+    ///
+    /// The default knows only the MTU and assumes defaults for the rest.
+    fn get_link_parameters(&self, peripheral_id: &str) -> LinkParameters {
+        LinkParameters::new().with_mtu(self.get_mtu(peripheral_id))
+    }
+}
+
+// ============================================================================
+// Hardware Events
+// ============================================================================
+
+/// Platform event reported by an in-process [`BleHardware`] backend
+///
+/// Rust backends (BlueZ, the simulated air) report events on a channel
+/// instead of calling the `handle_*` methods themselves; feed them to
+/// [`BleController::handle_hardware_event`]. `peripheral_id` is the ID the
+/// backend uses in `BleHardware` calls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BleHardwareEvent {
+    /// A NearClip device was seen (device ID and public key hash already read)
+    DeviceDiscovered {
+        peripheral_id: String,
+        device_id: String,
+        public_key_hash: String,
+        rssi: i32,
+    },
+    /// A NearClip advertisement carrying an identity token was seen
+    ///
+    /// Backends report these instead of connecting to read the device ID.
+    AdvertisementReceived {
+        peripheral_id: String,
+        service_data: Vec<u8>,
+        rssi: i32,
+    },
+    /// We connected to a peripheral as central
+    Connected { peripheral_id: String },
+    /// A central connected to our GATT server
+    CentralConnected { peripheral_id: String },
+    /// A connection was lost or a connect attempt failed
+    Disconnected { peripheral_id: String, reason: String },
+    /// A peripheral notification, or a central's write to our characteristic
+    DataReceived {
+        peripheral_id: String,
+        char_uuid: String,
+        data: Vec<u8>,
+    },
+    /// An L2CAP channel to the peer was opened (by either side)
+    L2capOpened { peripheral_id: String },
+    /// An L2CAP channel was closed, or an open attempt failed
+    L2capClosed { peripheral_id: String, reason: String },
+    /// Bytes arrived on an L2CAP channel (a stream segment, not a message)
+    L2capDataReceived { peripheral_id: String, data: Vec<u8> },
+    /// The connection interval, PHY, data length or MTU changed
+    LinkParametersChanged {
+        peripheral_id: String,
+        parameters: LinkParameters,
+    },
+    /// A background operation failed
+    Error {
+        peripheral_id: Option<String>,
+        message: String,
+    },
 }
 
 // ============================================================================
@@ -237,6 +404,15 @@ pub struct BleController {
     /// Reconnection state: peripheral_uuid -> ReconnectState
     reconnect_state: Arc<RwLock<HashMap<String, ReconnectState>>>,
 
+    /// Negotiated link parameters: peripheral_uuid -> LinkParameters
+    link_parameters: Arc<RwLock<HashMap<String, LinkParameters>>>,
+
+    /// Advertisement keys of paired devices
+    paired_devices: Arc<RwLock<AdvertisementResolver>>,
+
+    /// Task that rotates our advertisement identity token
+    advertising_task: Arc<RwLock<Option<tokio::task::JoinHandle<()>>>>,
+
     /// Configuration
     config: BleControllerConfig,
 
@@ -264,6 +440,11 @@ impl BleController {
             uuid_to_device_id: Arc::new(RwLock::new(HashMap::new())),
             device_id_to_uuid: Arc::new(RwLock::new(HashMap::new())),
             reconnect_state: Arc::new(RwLock::new(HashMap::new())),
+            link_parameters: Arc::new(RwLock::new(HashMap::new())),
+            paired_devices: Arc::new(RwLock::new(AdvertisementResolver::new(Duration::from_millis(
+                config.advertisement_rotation_ms,
+            )))),
+            advertising_task: Arc::new(RwLock::new(None)),
             config,
             callback,
             is_scanning: Arc::new(RwLock::new(false)),
@@ -304,6 +485,54 @@ impl BleController {
         *scanning = false;
     }
 
+    /// Start advertising with a rotating identity token
+    ///
+    /// The service data is `key`'s token for the current epoch and is
+    /// replaced at every epoch boundary until [`Self::stop_advertising`].
+    /// Paired devices holding `key` recognize us without connecting.
+    pub async fn start_advertising(&self, device_id: &str, key: AdvertisementKey) {
+        let period = Duration::from_millis(self.config.advertisement_rotation_ms);
+        info!(device_id = %device_id, "Starting BLE advertising");
+        self.hardware
+            .start_advertising(&key.payload(device_id, advertisement_epoch(period)));
+
+        let hardware = Arc::downgrade(&self.hardware);
+        let device_id = device_id.to_string();
+        let task = tokio::spawn(async move {
+            loop {
+                sleep(until_next_advertisement_epoch(period)).await;
+                let Some(hardware) = hardware.upgrade() else { return };
+                debug!("Rotating advertisement token");
+                hardware.start_advertising(&key.payload(&device_id, advertisement_epoch(period)));
+            }
+        });
+        if let Some(previous) = self.advertising_task.write().await.replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// Stop advertising
+    pub async fn stop_advertising(&self) {
+        if let Some(task) = self.advertising_task.write().await.take() {
+            task.abort();
+        }
+        info!("Stopping BLE advertising");
+        self.hardware.stop_advertising();
+    }
+
+    /// Register the advertisement key of a paired device
+    ///
+    /// Its advertisements are then resolved to `device_id` without
+    /// connecting. Registering the same device again replaces the key.
+    pub async fn add_paired_device(&self, device_id: &str, key: AdvertisementKey) {
+        self.paired_devices.write().await.add_peer(device_id, key);
+    }
+
+    /// Forget the advertisement key of a device that was unpaired
+    pub async fn remove_paired_device(&self, device_id: &str) {
+        self.paired_devices.write().await.remove_peer(device_id);
+    }
+
     /// Connect to a device by device_id
     pub async fn connect(&self, device_id: &str) -> Result<(), BleError> {
         // Find peripheral_uuid from device_id
@@ -493,6 +722,21 @@ impl BleController {
         uuid_map.get(device_id).cloned()
     }
 
+    /// Get the link parameters of a connected device
+    ///
+    /// Returns the values last reported by the backend, or asks the hardware
+    /// if nothing was reported yet. `None` if the device is not connected.
+    pub async fn get_link_parameters(&self, device_id: &str) -> Option<LinkParameters> {
+        let peripheral_uuid = self.get_peripheral_uuid(device_id).await?;
+        if !self.connected_devices.read().await.contains_key(&peripheral_uuid) {
+            return None;
+        }
+        if let Some(parameters) = self.link_parameters.read().await.get(&peripheral_uuid) {
+            return Some(*parameters);
+        }
+        Some(self.hardware.get_link_parameters(&peripheral_uuid))
+    }
+
     /// Register a device_id <-> peripheral_uuid mapping
     /// This is used in peripheral mode where the platform provides the central's UUID
     pub async fn register_device_mapping(&self, device_id: &str, peripheral_uuid: &str) {
@@ -540,7 +784,80 @@ impl BleController {
     // Platform Event Handlers (called by platform code)
     // ========================================================================
 
+    /// Handle an event from an in-process hardware backend
+    ///
+    /// A central that connects to us is registered under its peripheral ID,
+    /// the same as peripheral mode on mobile. Events the controller does not
+    /// own (DATA_ACK notifications, L2CAP channel events, errors) are returned
+    /// to the caller.
+    pub async fn handle_hardware_event(&self, event: BleHardwareEvent) -> Option<BleHardwareEvent> {
+        match event {
+            BleHardwareEvent::DeviceDiscovered {
+                peripheral_id,
+                device_id,
+                public_key_hash,
+                rssi,
+            } => {
+                self.handle_device_discovered(&peripheral_id, &device_id, &public_key_hash, rssi)
+                    .await;
+            }
+            BleHardwareEvent::AdvertisementReceived {
+                peripheral_id,
+                service_data,
+                rssi,
+            } => self.handle_advertisement(&peripheral_id, &service_data, rssi).await,
+            BleHardwareEvent::Connected { peripheral_id } => self.handle_connected(&peripheral_id).await,
+            BleHardwareEvent::CentralConnected { peripheral_id } => {
+                self.register_device_mapping(&peripheral_id, &peripheral_id).await;
+                self.handle_connected(&peripheral_id).await;
+            }
+            BleHardwareEvent::Disconnected {
+                peripheral_id,
+                reason,
+            } => self.handle_disconnected(&peripheral_id, &reason).await,
+            BleHardwareEvent::DataReceived {
+                peripheral_id,
+                char_uuid,
+                data,
+            } if char_uuid == crate::gatt::DATA_TRANSFER_CHARACTERISTIC_UUID.to_string() => {
+                self.handle_data_received(&peripheral_id, &data).await;
+            }
+            BleHardwareEvent::LinkParametersChanged {
+                peripheral_id,
+                parameters,
+            } => self.handle_link_parameters_changed(&peripheral_id, parameters).await,
+            other => return Some(other),
+        }
+        None
+    }
+
+    /// Handle an advertisement's service data from platform
+    ///
+    /// Resolves the identity token against the paired devices. A paired
+    /// device is reported as discovered; anything else is ignored, so we
+    /// never connect to strangers just to learn who they are.
+    pub async fn handle_advertisement(&self, peripheral_uuid: &str, service_data: &[u8], rssi: i32) {
+        let Some(device_id) = self.paired_devices.read().await.resolve(service_data) else {
+            debug!(peripheral_uuid = %peripheral_uuid, "Ignoring advertisement from unpaired device");
+            return;
+        };
+
+        // The advertisement carries no public key hash; keep one learned earlier
+        let public_key_hash = self
+            .discovered_devices
+            .read()
+            .await
+            .get(peripheral_uuid)
+            .filter(|device| device.device_id == device_id)
+            .map(|device| device.public_key_hash.clone())
+            .unwrap_or_default();
+        self.handle_device_discovered(peripheral_uuid, &device_id, &public_key_hash, rssi)
+            .await;
+    }
+
     /// Handle device discovered event from platform
+    ///
+    /// Unpaired devices are skipped unless `discover_unpaired` is set.
     pub async fn handle_device_discovered(
         &self,
         peripheral_uuid: &str,
@@ -548,6 +865,11 @@ impl BleController {
         public_key_hash: &str,
         rssi: i32,
     ) {
+        if !self.config.discover_unpaired && !self.paired_devices.read().await.contains(device_id) {
+            debug!(peripheral_uuid = %peripheral_uuid, device_id = %device_id, "Ignoring unpaired device");
+            return;
+        }
+
         let now_ms = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map(|d| d.as_millis() as i64)
@@ -658,6 +980,23 @@ impl BleController {
 
         info!(device_id = %device_id, "Device connected");
         self.callback.on_device_connected(device_id);
+
+        self.tune_link(peripheral_uuid);
+    }
+
+    /// Handle negotiated link parameters reported by the platform
+    pub async fn handle_link_parameters_changed(&self, peripheral_uuid: &str, parameters: LinkParameters) {
+        debug!(
+            peripheral_uuid = %peripheral_uuid,
+            mtu = parameters.mtu,
+            interval_us = parameters.connection_interval.as_micros() as u64,
+            phy = ?parameters.phy,
+            data_length = parameters.data_length,
+            estimated_throughput = parameters.estimated_throughput(),
+            "Link parameters changed"
+        );
+        let mut link_parameters = self.link_parameters.write().await;
+        link_parameters.insert(peripheral_uuid.to_string(), parameters);
     }
 
     /// Handle disconnected event from platform
@@ -681,6 +1020,7 @@ impl BleController {
             let mut devices = self.connected_devices.write().await;
             devices.remove(peripheral_uuid);
         }
+        self.link_parameters.write().await.remove(peripheral_uuid);
 
         info!(device_id = %device_id, reason = %reason, "Device disconnected");
         self.callback.on_device_disconnected(device_id.clone(), reason.to_string());
@@ -763,6 +1103,33 @@ impl BleController {
         });
     }
 
+    /// Request the configured connection priority, PHY and data length
+    ///
+    /// Failures are logged and otherwise ignored: the link still works with
+    /// whatever parameters it has.
+    fn tune_link(&self, peripheral_uuid: &str) {
+        let config = &self.config;
+        let mut requests = vec![(
+            "connection priority",
+            self.hardware
+                .request_connection_priority(peripheral_uuid, config.connection_priority),
+        )];
+        if config.preferred_phy != BlePhy::Le1M {
+            requests.push(("PHY", self.hardware.request_phy(peripheral_uuid, config.preferred_phy)));
+        }
+        if config.data_length > 0 {
+            requests.push((
+                "data length",
+                self.hardware.request_data_length(peripheral_uuid, config.data_length),
+            ));
+        }
+        for (what, result) in requests {
+            if let Err(e) = result {
+                debug!(peripheral_uuid = %peripheral_uuid, error = %e, "Link {} request not applied", what);
+            }
+        }
+    }
+
     /// Spawn device lost detection task
     fn spawn_device_lost_detection(&self) {
         let discovered = self.discovered_devices.clone();
@@ -882,6 +1249,8 @@ mod tests {
     struct MockHardware {
         scan_started: Mutex<bool>,
         connected: Mutex<Vec<String>>,
+        link_requests: Mutex<Vec<String>>,
+        advertising: Mutex<Option<Vec<u8>>>,
     }
 
     impl MockHardware {
@@ -889,6 +1258,8 @@ mod tests {
             Self {
                 scan_started: Mutex::new(false),
                 connected: Mutex::new(Vec::new()),
+                link_requests: Mutex::new(Vec::new()),
+                advertising: Mutex::new(None),
             }
         }
     }
@@ -935,8 +1306,13 @@ mod tests {
             Ok(())
         }
 
-        fn start_advertising(&self, _service_data: &[u8]) {}
-        fn stop_advertising(&self) {}
+        fn start_advertising(&self, service_data: &[u8]) {
+            *self.advertising.lock().unwrap() = Some(service_data.to_vec());
+        }
+
+        fn stop_advertising(&self) {
+            *self.advertising.lock().unwrap() = None;
+        }
 
         fn is_connected(&self, peripheral_id: &str) -> bool {
             self.connected.lock().unwrap().contains(&peripheral_id.to_string())
@@ -945,6 +1321,20 @@ mod tests {
         fn get_mtu(&self, _peripheral_id: &str) -> u16 {
             20
         }
+
+        fn request_connection_priority(
+            &self,
+            _peripheral_id: &str,
+            priority: ConnectionPriority,
+        ) -> Result<(), String> {
+            self.link_requests.lock().unwrap().push(format!("{:?}", priority));
+            Ok(())
+        }
+
+        fn request_phy(&self, _peripheral_id: &str, phy: BlePhy) -> Result<(), String> {
+            self.link_requests.lock().unwrap().push(format!("{:?}", phy));
+            Ok(())
+        }
     }
 
     struct MockCallback {
@@ -1062,4 +1452,108 @@ mod tests {
         // Check callback
         assert_eq!(callback.connected.lock().unwrap().len(), 1);
     }
+
+    #[tokio::test]
+    async fn test_ble_controller_link_parameters() {
+        let hardware = Arc::new(MockHardware::new());
+        let callback = Arc::new(MockCallback::new());
+        let config = BleControllerConfig {
+            connection_priority: ConnectionPriority::High,
+            ..Default::default()
+        };
+
+        let controller = BleController::new(hardware.clone(), config, callback);
+        controller.handle_device_discovered("uuid-1", "device-1", "hash-1", -50).await;
+        assert_eq!(controller.get_link_parameters("device-1").await, None);
+
+        // Tuning is requested on connect; DLE is unsupported and skipped
+        controller.handle_connected("uuid-1").await;
+        assert_eq!(*hardware.link_requests.lock().unwrap(), vec!["High", "Le2M"]);
+
+        // Before the platform reports anything, the hardware defaults apply
+        let initial = controller.get_link_parameters("device-1").await.unwrap();
+        assert_eq!(initial, LinkParameters::new().with_mtu(20));
+
+        let negotiated = LinkParameters::new()
+            .with_mtu(247)
+            .with_phy(BlePhy::Le2M)
+            .with_connection_interval(Duration::from_millis(15));
+        let event = BleHardwareEvent::LinkParametersChanged {
+            peripheral_id: "uuid-1".to_string(),
+            parameters: negotiated,
+        };
+        assert_eq!(controller.handle_hardware_event(event).await, None);
+        assert_eq!(controller.get_link_parameters("device-1").await, Some(negotiated));
+
+        controller.handle_disconnected("uuid-1", "test").await;
+        assert_eq!(controller.get_link_parameters("device-1").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_ble_controller_advertises_identity_token() {
+        let hardware = Arc::new(MockHardware::new());
+        let callback = Arc::new(MockCallback::new());
+        let controller = BleController::new(hardware.clone(), BleControllerConfig::default(), callback);
+
+        let key = AdvertisementKey::derive(b"secret");
+        controller.start_advertising("device-1", key.clone()).await;
+        let service_data = hardware.advertising.lock().unwrap().clone().unwrap();
+
+        let mut resolver = AdvertisementResolver::new(Duration::from_secs(DEFAULT_ADVERTISEMENT_ROTATION_SECS));
+        resolver.add_peer("device-1", key);
+        assert_eq!(resolver.resolve(&service_data), Some("device-1".to_string()));
+
+        controller.stop_advertising().await;
+        assert_eq!(*hardware.advertising.lock().unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_ble_controller_resolves_paired_advertisements() {
+        let hardware = Arc::new(MockHardware::new());
+        let callback = Arc::new(MockCallback::new());
+        let controller = BleController::new(hardware, BleControllerConfig::default(), callback.clone());
+        let period = Duration::from_millis(controller.get_config().advertisement_rotation_ms);
+        let epoch = advertisement_epoch(period);
+
+        let key = AdvertisementKey::derive(b"paired");
+        controller.add_paired_device("device-1", key.clone()).await;
+
+        // Strangers are dropped without a device ID mapping to connect to
+        let stranger = AdvertisementKey::derive(b"stranger").payload("device-2", epoch);
+        controller.handle_advertisement("uuid-2", &stranger, -60).await;
+        controller.handle_advertisement("uuid-3", &[0x01, 0x02], -60).await;
+        assert!(controller.get_discovered_devices().await.is_empty());
+        assert_eq!(controller.get_peripheral_uuid("device-2").await, None);
+
+        let event = BleHardwareEvent::AdvertisementReceived {
+            peripheral_id: "uuid-1".to_string(),
+            service_data: key.payload("device-1", epoch),
+            rssi: -50,
+        };
+        assert_eq!(controller.handle_hardware_event(event).await, None);
+        assert_eq!(*callback.discovered.lock().unwrap(), vec!["device-1"]);
+        assert_eq!(controller.get_peripheral_uuid("device-1").await, Some("uuid-1".to_string()));
+
+        // Unpairing stops resolution
+        controller.remove_paired_device("device-1").await;
+        controller.handle_advertisement("uuid-1", &key.payload("device-1", epoch), -50).await;
+        assert_eq!(callback.discovered.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_ble_controller_skips_unpaired_discoveries() {
+        let hardware = Arc::new(MockHardware::new());
+        let callback = Arc::new(MockCallback::new());
+        let config = BleControllerConfig {
+            discover_unpaired: false,
+            ..Default::default()
+        };
+        let controller = BleController::new(hardware, config, callback.clone());
+        controller.add_paired_device("device-1", AdvertisementKey::derive(b"paired")).await;
+
+        controller.handle_device_discovered("uuid-2", "device-2", "hash-2", -50).await;
+        controller.handle_device_discovered("uuid-1", "device-1", "hash-1", -50).await;
+        assert_eq!(*callback.discovered.lock().unwrap(), vec!["device-1"]);
+        assert!(controller.connect("device-2").await.is_err());
+    }
 }
@@ -9,9 +9,14 @@
 //! ├── Device ID Characteristic (DEVICE_ID_CHARACTERISTIC_UUID) - Read
 //! ├── Public Key Hash Characteristic (PUBKEY_HASH_CHARACTERISTIC_UUID) - Read
 //! ├── Data Transfer Characteristic (DATA_TRANSFER_CHARACTERISTIC_UUID) - Write
-//! └── Data Ack Characteristic (DATA_ACK_CHARACTERISTIC_UUID) - Read + Notify
+//! ├── Data Ack Characteristic (DATA_ACK_CHARACTERISTIC_UUID) - Read + Notify
+//! ├── L2CAP PSM Characteristic (L2CAP_PSM_CHARACTERISTIC_UUID) - Read（可选）
+//! ├── Chunk Version Characteristic (CHUNK_VERSION_CHARACTERISTIC_UUID) - Read（可选）
+//! ├── Pairing Characteristic (PAIRING_CHARACTERISTIC_UUID) - Write + Notify
+//! └── Framing Characteristic (FRAMING_CHARACTERISTIC_UUID) - Read（可选）
 //! ```
 
+use crate::chunk::ChunkVersion;
 use uuid::Uuid;
 
 /// NearClip 服务 UUID (128-bit 自定义)
@@ -82,6 +87,97 @@ pub const DATA_ACK_CHARACTERISTIC_UUID: Uuid = Uuid::from_bytes([
     0x00, 0x00, 0x00, 0x00, 0x00, 0x05, // characteristic number
 ]);
 
+/// L2CAP PSM 特征 UUID
+///
+/// 可选的只读特征，值为外设监听 L2CAP 面向连接信道 (CoC) 的 PSM
+/// （2 字节小端）。只有支持 L2CAP 的外设才提供此特征，中心据此决定
+/// 是否改用 L2CAP 传输大块数据。
+///
+/// UUID: `4e454152-434c-4950-0000-000000000006`
+pub const L2CAP_PSM_CHARACTERISTIC_UUID: Uuid = Uuid::from_bytes([
+    0x4e, 0x45, 0x41, 0x52, // NEAR
+    0x43, 0x4c, // CL
+    0x49, 0x50, // IP
+    0x00, 0x00, // reserved
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x06, // characteristic number
+]);
+
+/// 解析 L2CAP PSM 特征的值
+///
+/// 值必须正好 2 字节（小端）且不为 0，否则视为不支持 L2CAP。
+pub fn parse_l2cap_psm(value: &[u8]) -> Option<u16> {
+    let bytes: [u8; 2] = value.try_into().ok()?;
+    match u16::from_le_bytes(bytes) {
+        0 => None,
+        psm => Some(psm),
+    }
+}
+
+/// 分片头部版本特征 UUID
+///
+/// 可选的只读特征，值为本端能接收的最高分片头部版本（1 字节）。
+/// 没有此特征的旧版本对端只支持 v1 头部。
+///
+/// UUID: `4e454152-434c-4950-0000-000000000007`
+pub const CHUNK_VERSION_CHARACTERISTIC_UUID: Uuid = Uuid::from_bytes([
+    0x4e, 0x45, 0x41, 0x52, // NEAR
+    0x43, 0x4c, // CL
+    0x49, 0x50, // IP
+    0x00, 0x00, // reserved
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x07, // characteristic number
+]);
+
+/// 解析分片头部版本特征的值
+///
+/// 返回双方都支持的最高版本。值为空或无法识别时按 v1 处理。
+pub fn parse_chunk_version(value: &[u8]) -> ChunkVersion {
+    match value.first() {
+        Some(&v) if v >= ChunkVersion::V2.as_u8() => ChunkVersion::V2,
+        _ => ChunkVersion::V1,
+    }
+}
+
+/// 配对特征 UUID
+///
+/// 可写可通知特征，承载 BLE 配对流程中分片后的配对消息（见 [`crate::pairing`]）。
+/// 中心写入，外设通过通知回复，因此中心需要先订阅此特征。
+/// 属性: Write Without Response + Notify
+///
+/// UUID: `4e454152-434c-4950-0000-000000000008`
+pub const PAIRING_CHARACTERISTIC_UUID: Uuid = Uuid::from_bytes([
+    0x4e, 0x45, 0x41, 0x52, // NEAR
+    0x43, 0x4c, // CL
+    0x49, 0x50, // IP
+    0x00, 0x00, // reserved
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x08, // characteristic number
+]);
+
+/// 帧格式特征 UUID
+///
+/// 可选的只读特征，值为本端支持的多路复用帧版本（1 字节）。
+/// 提供此特征的对端用 v2 分片收发多路复用帧，并以
+/// [`ChunkFlags::MUX`](crate::ChunkFlags::MUX) 标记；没有此特征的旧版本对端
+/// 只接收整条消息直接分片。
+///
+/// UUID: `4e454152-434c-4950-0000-000000000009`
+pub const FRAMING_CHARACTERISTIC_UUID: Uuid = Uuid::from_bytes([
+    0x4e, 0x45, 0x41, 0x52, // NEAR
+    0x43, 0x4c, // CL
+    0x49, 0x50, // IP
+    0x00, 0x00, // reserved
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x09, // characteristic number
+]);
+
+/// 本端支持的多路复用帧版本（帧格式特征的值）
+pub const MUX_FRAMING_VERSION: u8 = 1;
+
+/// 解析帧格式特征的值
+///
+/// 返回对端是否支持多路复用帧。值为空或为 0 时视为不支持。
+pub fn parse_mux_framing(value: &[u8]) -> bool {
+    value.first().is_some_and(|&v| v >= MUX_FRAMING_VERSION)
+}
+
 /// 默认广播名称
 pub const DEFAULT_ADVERTISE_NAME: &str = "NearClip";
 
@@ -110,9 +206,14 @@ pub const ATT_HEADER_SIZE: usize = 3;
 
 /// 分片头部大小
 ///
-/// ChunkHeader 占用 8 字节
+/// v1 ChunkHeader 占用 8 字节
 pub const CHUNK_HEADER_SIZE: usize = 8;
 
+/// v2 分片头部大小
+///
+/// 版本标记 + 标志位 + 4 字节 message_id + 序号 + 总数 + CRC32，共 14 字节
+pub const CHUNK_HEADER_V2_SIZE: usize = 14;
+
 /// 默认有效 payload 大小
 ///
 /// = DEFAULT_BLE_MTU - ATT_HEADER_SIZE - CHUNK_HEADER_SIZE = 23 - 3 - 8 = 12 bytes
@@ -141,6 +242,10 @@ mod tests {
         assert_eq!(PUBKEY_HASH_CHARACTERISTIC_UUID.as_bytes().len(), 16);
         assert_eq!(DATA_TRANSFER_CHARACTERISTIC_UUID.as_bytes().len(), 16);
         assert_eq!(DATA_ACK_CHARACTERISTIC_UUID.as_bytes().len(), 16);
+        assert_eq!(L2CAP_PSM_CHARACTERISTIC_UUID.as_bytes().len(), 16);
+        assert_eq!(CHUNK_VERSION_CHARACTERISTIC_UUID.as_bytes().len(), 16);
+        assert_eq!(PAIRING_CHARACTERISTIC_UUID.as_bytes().len(), 16);
+        assert_eq!(FRAMING_CHARACTERISTIC_UUID.as_bytes().len(), 16);
     }
 
     #[test]
@@ -151,6 +256,10 @@ mod tests {
             PUBKEY_HASH_CHARACTERISTIC_UUID,
             DATA_TRANSFER_CHARACTERISTIC_UUID,
             DATA_ACK_CHARACTERISTIC_UUID,
+            L2CAP_PSM_CHARACTERISTIC_UUID,
+            CHUNK_VERSION_CHARACTERISTIC_UUID,
+            PAIRING_CHARACTERISTIC_UUID,
+            FRAMING_CHARACTERISTIC_UUID,
         ];
         for i in 0..uuids.len() {
             for j in (i + 1)..uuids.len() {
@@ -203,12 +312,62 @@ mod tests {
         assert_eq!(uuid_str, "4e454152-434c-4950-0000-000000000005");
     }
 
+    #[test]
+    fn test_l2cap_psm_uuid_string_format() {
+        let uuid_str = L2CAP_PSM_CHARACTERISTIC_UUID.to_string();
+        assert_eq!(uuid_str, "4e454152-434c-4950-0000-000000000006");
+    }
+
+    #[test]
+    fn test_parse_l2cap_psm() {
+        assert_eq!(parse_l2cap_psm(&0x0081u16.to_le_bytes()), Some(0x0081));
+        assert_eq!(parse_l2cap_psm(&[0, 0]), None);
+        assert_eq!(parse_l2cap_psm(&[0x81]), None);
+        assert_eq!(parse_l2cap_psm(&[]), None);
+    }
+
+    #[test]
+    fn test_chunk_version_uuid_string_format() {
+        let uuid_str = CHUNK_VERSION_CHARACTERISTIC_UUID.to_string();
+        assert_eq!(uuid_str, "4e454152-434c-4950-0000-000000000007");
+    }
+
+    #[test]
+    fn test_pairing_uuid_string_format() {
+        let uuid_str = PAIRING_CHARACTERISTIC_UUID.to_string();
+        assert_eq!(uuid_str, "4e454152-434c-4950-0000-000000000008");
+    }
+
+    #[test]
+    fn test_framing_uuid_string_format() {
+        let uuid_str = FRAMING_CHARACTERISTIC_UUID.to_string();
+        assert_eq!(uuid_str, "4e454152-434c-4950-0000-000000000009");
+    }
+
+    #[test]
+    fn test_parse_mux_framing() {
+        assert!(parse_mux_framing(&[MUX_FRAMING_VERSION]));
+        assert!(parse_mux_framing(&[2]));
+        assert!(!parse_mux_framing(&[0]));
+        assert!(!parse_mux_framing(&[]));
+    }
+
+    #[test]
+    fn test_parse_chunk_version() {
+        assert_eq!(parse_chunk_version(&[2]), ChunkVersion::V2);
+        assert_eq!(parse_chunk_version(&[3]), ChunkVersion::V2);
+        assert_eq!(parse_chunk_version(&[1]), ChunkVersion::V1);
+        assert_eq!(parse_chunk_version(&[0]), ChunkVersion::V1);
+        assert_eq!(parse_chunk_version(&[]), ChunkVersion::V1);
+    }
+
     #[test]
     fn test_mtu_constants() {
         // 验证 MTU 相关常量
         assert_eq!(DEFAULT_BLE_MTU, 23);
         assert_eq!(ATT_HEADER_SIZE, 3);
         assert_eq!(CHUNK_HEADER_SIZE, 8);
+        assert_eq!(CHUNK_HEADER_V2_SIZE, 14);
         assert_eq!(MAX_BLE_MTU, 512);
 
         // 验证计算出的 payload 大小
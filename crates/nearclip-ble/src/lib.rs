@@ -9,85 +9,89 @@
 //! nearclip-ble
 //! ├── error.rs          - BLE error types
 //! ├── gatt.rs           - GATT service/characteristic UUID definitions
-//! ├── peripheral.rs     - BLE peripheral mode (advertising)
-//! ├── central.rs        - BLE central mode (scanning)
+//! ├── controller.rs     - BleController: scanning, advertising, connections
+//! ├── advert.rs         - Rotating HMAC identity in the advertisement payload
+//! ├── bluez.rs          - Linux BlueZ BleHardware backend (feature `linux`)
+//! ├── sim.rs            - Simulated BLE air for multi-device tests
 //! ├── chunk.rs          - Data chunking for MTU limitations
+//! ├── link.rs           - Link parameters (interval, PHY, DLE) and throughput
+//! ├── pairing.rs        - Pairing state machine over the pairing characteristic
+//! ├── retransmit.rs     - Selective retransmission window for chunked transfers
 //! ├── peripheral_data.rs - Peripheral mode data receiving
 //! └── central_data.rs    - Central mode data sending
 //! ```
 //!
-//! # Peripheral Mode (Advertising)
+//! # Discovery
 //!
-//! Use [`BleAdvertiser`] to broadcast this device for discovery:
+//! [`BleController`] owns scanning, advertising and connections on top of a
+//! platform [`BleHardware`] backend. Paired devices are recognized from the
+//! rotating identity token in their advertisement:
 //!
 //! ```no_run
-//! use nearclip_ble::{BleAdvertiser, BleAdvertiserConfig, BleError};
-//!
-//! # async fn example() -> Result<(), BleError> {
-//! let config = BleAdvertiserConfig::new(
-//!     "my-device-id".to_string(),
-//!     "MDEyMzQ1Njc4OTAxMjM0NTY3ODkwMTIzNDU2Nzg5MDE=".to_string(),
-//! );
-//!
-//! let mut advertiser = BleAdvertiser::new(config).await?;
-//! advertiser.start().await?;
-//!
-//! // ... device is now discoverable via BLE ...
-//!
-//! advertiser.stop().await?;
-//! # Ok(())
-//! # }
-//! ```
-//!
-//! # Central Mode (Scanning)
-//!
-//! Use [`BleScanner`] to discover nearby NearClip devices:
-//!
-//! ```no_run
-//! use nearclip_ble::{BleScanner, BleScannerConfig, BleError};
-//!
-//! # async fn example() -> Result<(), BleError> {
-//! let config = BleScannerConfig::new()
-//!     .with_scan_timeout(10000);
-//!
-//! let mut scanner = BleScanner::new(config).await?;
-//!
-//! // 订阅设备发现事件
-//! let mut rx = scanner.subscribe();
-//!
-//! // 开始扫描（当前返回 PlatformNotSupported）
-//! // scanner.start().await?;
-//!
-//! // 获取已发现的设备
-//! let devices = scanner.discovered_devices().await;
+//! use std::sync::Arc;
+//! use nearclip_ble::{
+//!     AdvertisementKey, BleController, BleControllerCallback, BleControllerConfig, BleError,
+//!     BleHardware,
+//! };
+//!
+//! # async fn example(
+//! #     hardware: Arc<dyn BleHardware>,
+//! #     callback: Arc<dyn BleControllerCallback>,
+//! # ) -> Result<(), BleError> {
+//! let controller = BleController::new(hardware, BleControllerConfig::default(), callback);
+//!
+//! controller
+//!     .start_advertising("my-device-id", AdvertisementKey::derive(b"my device secret"))
+//!     .await;
+//! controller
+//!     .add_paired_device("peer-device-id", AdvertisementKey::derive(b"peer device secret"))
+//!     .await;
+//!
+//! // 已配对设备通过回调的 on_device_discovered 上报
+//! controller.start_scan().await?;
 //! # Ok(())
 //! # }
 //! ```
 
-pub mod central;
+pub mod advert;
+#[cfg(feature = "linux")]
+pub mod bluez;
 pub mod central_data;
 pub mod chunk;
 pub mod controller;
 pub mod error;
 pub mod gatt;
-pub mod peripheral;
+pub mod link;
+pub mod pairing;
 pub mod peripheral_data;
+pub mod retransmit;
+pub mod sim;
 
 // Re-exports
-pub use central::{
-    BleScanner, BleScannerConfig, DiscoveredDevice, DEFAULT_DEVICE_TIMEOUT_MS,
-    DEFAULT_SCAN_TIMEOUT_MS,
+pub use advert::{
+    advertisement_epoch, parse_advertisement, until_next_advertisement_epoch, AdvertisementKey,
+    AdvertisementResolver, ADVERTISEMENT_PAYLOAD_LEN, ADVERTISEMENT_TOKEN_LEN,
+    ADVERTISEMENT_VERSION, DEFAULT_ADVERTISEMENT_ROTATION_SECS,
+};
+pub use chunk::{
+    ChunkFlags, ChunkHeader, ChunkVersion, Chunker, Reassembler, DEFAULT_REASSEMBLE_TIMEOUT,
 };
-pub use chunk::{ChunkHeader, Chunker, Reassembler, DEFAULT_REASSEMBLE_TIMEOUT};
 pub use error::BleError;
 pub use gatt::{
-    ATT_HEADER_SIZE, CHUNK_HEADER_SIZE, DATA_ACK_CHARACTERISTIC_UUID,
+    ATT_HEADER_SIZE, CHUNK_HEADER_SIZE, CHUNK_HEADER_V2_SIZE, CHUNK_VERSION_CHARACTERISTIC_UUID,
+    DATA_ACK_CHARACTERISTIC_UUID,
     DATA_TRANSFER_CHARACTERISTIC_UUID, DEFAULT_ADVERTISE_NAME, DEFAULT_BLE_MTU,
-    DEFAULT_CHUNK_PAYLOAD_SIZE, DEVICE_ID_CHARACTERISTIC_UUID, MAX_ADVERTISE_NAME_LENGTH,
-    MAX_BLE_MTU, MAX_CHUNK_PAYLOAD_SIZE, MAX_DEVICE_ID_LENGTH, NEARCLIP_SERVICE_UUID,
-    PUBKEY_HASH_CHARACTERISTIC_UUID, PUBKEY_HASH_LENGTH,
+    DEFAULT_CHUNK_PAYLOAD_SIZE, DEVICE_ID_CHARACTERISTIC_UUID, FRAMING_CHARACTERISTIC_UUID,
+    L2CAP_PSM_CHARACTERISTIC_UUID, MAX_ADVERTISE_NAME_LENGTH,
+    MAX_BLE_MTU, MAX_CHUNK_PAYLOAD_SIZE, MAX_DEVICE_ID_LENGTH, MUX_FRAMING_VERSION, NEARCLIP_SERVICE_UUID,
+    PAIRING_CHARACTERISTIC_UUID, PUBKEY_HASH_CHARACTERISTIC_UUID, PUBKEY_HASH_LENGTH, parse_chunk_version, parse_l2cap_psm,
+    parse_mux_framing,
+};
+pub use pairing::{BlePairingChannel, BlePairingRole, BlePairingSession, BlePairingState};
+pub use link::{BlePhy, ConnectionPriority, LinkParameters, ThroughputMeter, MAX_DATA_LENGTH};
+pub use retransmit::{
+    ChunkReport, SendWindow, CHUNK_REPORT_HEADER_SIZE, CHUNK_REPORT_V2_HEADER_SIZE,
 };
-pub use peripheral::{BleAdvertiser, BleAdvertiserConfig};
 pub use peripheral_data::{
     DataReceiverCallback, PeripheralDataConfig, PeripheralDataReceiver,
     DEFAULT_REASSEMBLE_TIMEOUT_SECS, MAX_CONCURRENT_MESSAGES,
@@ -97,6 +101,9 @@ pub use central_data::{
     DEFAULT_ACK_TIMEOUT_MS, DEFAULT_MTU, DEFAULT_RETRY_COUNT, DEFAULT_SEND_TIMEOUT_SECS,
 };
 pub use controller::{
-    BleController, BleControllerCallback, BleControllerConfig, BleHardware,
+    BleController, BleControllerCallback, BleControllerConfig, BleHardware, BleHardwareEvent,
     DiscoveredDevice as ControllerDiscoveredDevice,
 };
+pub use sim::{RssiCurve, SimAir, SimAirConfig, SimBleHardware, SimDeviceConfig};
+#[cfg(feature = "linux")]
+pub use bluez::{BlueZConfig, BlueZHardware};
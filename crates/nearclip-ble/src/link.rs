@@ -0,0 +1,326 @@
+//! BLE 链路参数
+//!
+//! ATT MTU 之外，连接间隔、PHY 和数据长度扩展 (DLE) 同样决定 BLE 的实际吞吐量。
+//! 本模块定义平台可以请求的参数（[`ConnectionPriority`]、[`BlePhy`]、数据长度）、
+//! 协商结果 [`LinkParameters`]，以及据此计算分片大小、发送节奏和估算吞吐量的方法。
+//! [`ThroughputMeter`] 统计实际达到的吞吐量。
+//!
+//! # 示例
+//!
+//! ```
+//! use std::time::Duration;
+//! use nearclip_ble::link::{BlePhy, LinkParameters};
+//!
+//! let tuned = LinkParameters::new()
+//!     .with_mtu(247)
+//!     .with_phy(BlePhy::Le2M)
+//!     .with_data_length(251)
+//!     .with_connection_interval(Duration::from_millis(15));
+//!
+//! // 一个分片正好占满一个链路层数据包
+//! assert_eq!(tuned.chunk_mtu(), 247);
+//! assert!(tuned.estimated_throughput() > 10 * LinkParameters::new().estimated_throughput());
+//! ```
+
+use std::time::Duration;
+
+use crate::gatt::{ATT_HEADER_SIZE, CHUNK_HEADER_SIZE, DEFAULT_BLE_MTU};
+
+/// L2CAP 基本头部大小（每个 ATT PDU 一个）
+pub const L2CAP_HEADER_SIZE: usize = 4;
+
+/// 未启用 DLE 时链路层数据包的最大 payload
+pub const DEFAULT_DATA_LENGTH: u16 = 27;
+
+/// DLE 允许的最大链路层 payload
+pub const MAX_DATA_LENGTH: u16 = 251;
+
+/// 未协商时假定的连接间隔
+pub const DEFAULT_CONNECTION_INTERVAL: Duration = Duration::from_millis(30);
+
+/// 估算时假定每个连接事件最多发送的数据包数
+///
+/// 多数手机和桌面控制器每个连接事件发送 4-7 个包。
+pub const MAX_PACKETS_PER_EVENT: usize = 6;
+
+/// 链路层数据包的额外空口字节：前导码 1 + 接入地址 4 + 头部 2 + CRC 3
+const LL_PACKET_OVERHEAD: usize = 10;
+
+/// 帧间间隔 T_IFS
+const INTER_FRAME_SPACE: Duration = Duration::from_micros(150);
+
+/// 吞吐量移动平均的权重
+const THROUGHPUT_EWMA_WEIGHT: f64 = 0.25;
+
+/// 连接优先级
+///
+/// 与 Android `requestConnectionPriority` 对应，请求的是连接间隔范围；
+/// 没有此接口的平台取范围内的值请求连接参数更新。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ConnectionPriority {
+    /// 省电：100-125ms
+    LowPower,
+    /// 平衡：30-50ms
+    #[default]
+    Balanced,
+    /// 高速：7.5-15ms，适合大块传输
+    High,
+}
+
+impl ConnectionPriority {
+    /// 请求的连接间隔范围 `(最小, 最大)`
+    pub fn interval_range(&self) -> (Duration, Duration) {
+        match self {
+            Self::LowPower => (Duration::from_millis(100), Duration::from_millis(125)),
+            Self::Balanced => (Duration::from_millis(30), Duration::from_millis(50)),
+            Self::High => (Duration::from_micros(7_500), Duration::from_millis(15)),
+        }
+    }
+}
+
+/// 物理层
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BlePhy {
+    /// LE 1M（所有设备都支持）
+    #[default]
+    Le1M,
+    /// LE 2M（BLE 5），空口速率翻倍
+    Le2M,
+    /// LE Coded（BLE 5，S=8），距离更远、速率最低
+    LeCoded,
+}
+
+impl BlePhy {
+    /// 空口比特率（bit/s）
+    pub fn bit_rate(&self) -> u32 {
+        match self {
+            Self::Le1M => 1_000_000,
+            Self::Le2M => 2_000_000,
+            Self::LeCoded => 125_000,
+        }
+    }
+}
+
+/// 协商后的链路参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkParameters {
+    /// ATT MTU
+    pub mtu: u16,
+    /// 连接间隔
+    pub connection_interval: Duration,
+    /// 发送方向的 PHY
+    pub phy: BlePhy,
+    /// 链路层最大发送 payload（DLE 协商结果，27-251）
+    pub data_length: u16,
+}
+
+impl LinkParameters {
+    /// 未协商时的参数：最小 MTU、30ms、LE 1M、无 DLE
+    pub fn new() -> Self {
+        Self {
+            mtu: DEFAULT_BLE_MTU as u16,
+            connection_interval: DEFAULT_CONNECTION_INTERVAL,
+            phy: BlePhy::Le1M,
+            data_length: DEFAULT_DATA_LENGTH,
+        }
+    }
+
+    /// 设置 ATT MTU
+    pub fn with_mtu(mut self, mtu: u16) -> Self {
+        self.mtu = mtu;
+        self
+    }
+
+    /// 设置连接间隔
+    pub fn with_connection_interval(mut self, interval: Duration) -> Self {
+        self.connection_interval = interval;
+        self
+    }
+
+    /// 设置 PHY
+    pub fn with_phy(mut self, phy: BlePhy) -> Self {
+        self.phy = phy;
+        self
+    }
+
+    /// 设置链路层数据长度
+    pub fn with_data_length(mut self, data_length: u16) -> Self {
+        self.data_length = data_length;
+        self
+    }
+
+    fn data_length_bytes(&self) -> usize {
+        self.data_length.clamp(DEFAULT_DATA_LENGTH, MAX_DATA_LENGTH) as usize
+    }
+
+    /// 分片使用的 MTU（传给 [`Chunker::chunk`](crate::Chunker::chunk)）
+    ///
+    /// 一个分片是一个 ATT PDU，加上 L2CAP 头后按数据长度拆成链路层数据包。
+    /// 不超过 ATT MTU，并在 PDU 跨多个数据包时收缩到整数个包，
+    /// 避免最后一个包几乎为空。
+    pub fn chunk_mtu(&self) -> usize {
+        let mtu = (self.mtu as usize).max(DEFAULT_BLE_MTU);
+        let data_length = self.data_length_bytes();
+        let pdu = mtu + L2CAP_HEADER_SIZE;
+        if pdu <= data_length {
+            return mtu;
+        }
+        let fitted = (pdu / data_length) * data_length - L2CAP_HEADER_SIZE;
+        fitted.max(DEFAULT_BLE_MTU)
+    }
+
+    /// 每个分片的有效数据字节数
+    pub fn chunk_payload_size(&self) -> usize {
+        self.chunk_mtu() - ATT_HEADER_SIZE - CHUNK_HEADER_SIZE
+    }
+
+    /// 发送一个满数据包并收到对端空包所需的空口时间
+    fn packet_airtime(&self) -> Duration {
+        let rate = self.phy.bit_rate() as u64;
+        let bits = |bytes: usize| Duration::from_nanos(bytes as u64 * 8 * 1_000_000_000 / rate);
+        bits(self.data_length_bytes() + LL_PACKET_OVERHEAD)
+            + bits(LL_PACKET_OVERHEAD)
+            + INTER_FRAME_SPACE * 2
+    }
+
+    /// 估算每个连接事件能发送的链路层数据包数
+    pub fn packets_per_event(&self) -> usize {
+        let packets = self.connection_interval.as_nanos() / self.packet_airtime().as_nanos().max(1);
+        (packets as usize).clamp(1, MAX_PACKETS_PER_EVENT)
+    }
+
+    /// 估算每个连接事件能发送的分片数（至少 1）
+    pub fn chunks_per_event(&self) -> usize {
+        let packets_per_chunk = (self.chunk_mtu() + L2CAP_HEADER_SIZE).div_ceil(self.data_length_bytes());
+        (self.packets_per_event() / packets_per_chunk).max(1)
+    }
+
+    /// 连续发送分片的间隔
+    ///
+    /// 按每个连接事件能发出的分片数平摊连接间隔，避免写入速度超过链路、
+    /// 塞满平台的发送队列。
+    pub fn chunk_interval(&self) -> Duration {
+        self.connection_interval / self.chunks_per_event() as u32
+    }
+
+    /// 估算的有效吞吐量（字节/秒，不含协议头）
+    pub fn estimated_throughput(&self) -> u32 {
+        let interval = self.connection_interval.as_secs_f64();
+        if interval <= 0.0 {
+            return 0;
+        }
+        let bytes_per_event = (self.chunks_per_event() * self.chunk_payload_size()) as f64;
+        (bytes_per_event / interval) as u32
+    }
+}
+
+impl Default for LinkParameters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 实际吞吐量统计
+///
+/// 每完成一次传输记录一次（数据量和耗时），结果为指数移动平均，
+/// 空闲时间不计入。
+#[derive(Debug, Clone, Default)]
+pub struct ThroughputMeter {
+    bytes_per_second: Option<f64>,
+    total_bytes: u64,
+}
+
+impl ThroughputMeter {
+    /// 创建空的统计
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次完成的传输
+    pub fn record(&mut self, bytes: usize, elapsed: Duration) {
+        self.total_bytes += bytes as u64;
+        let seconds = elapsed.as_secs_f64();
+        if bytes == 0 || seconds <= 0.0 {
+            return;
+        }
+        let sample = bytes as f64 / seconds;
+        self.bytes_per_second = Some(match self.bytes_per_second {
+            Some(rate) => rate + (sample - rate) * THROUGHPUT_EWMA_WEIGHT,
+            None => sample,
+        });
+    }
+
+    /// 平均吞吐量（字节/秒），还没有记录时为 `None`
+    pub fn bytes_per_second(&self) -> Option<u32> {
+        self.bytes_per_second.map(|rate| rate as u32)
+    }
+
+    /// 累计传输的字节数
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_mtu_fills_link_layer_packets() {
+        // 无 DLE：185 + 4 正好是 7 个 27 字节的包
+        assert_eq!(LinkParameters::new().with_mtu(185).chunk_mtu(), 185);
+        // 247 + 4 = 251 需要 9.3 个包，收缩到 9 个
+        assert_eq!(LinkParameters::new().with_mtu(247).chunk_mtu(), 239);
+        // DLE 后一个包就能装下
+        let dle = LinkParameters::new().with_data_length(MAX_DATA_LENGTH);
+        assert_eq!(dle.with_mtu(247).chunk_mtu(), 247);
+        assert_eq!(dle.with_mtu(512).chunk_mtu(), 498);
+        // 不低于最小 MTU
+        assert_eq!(LinkParameters::new().with_mtu(0).chunk_mtu(), DEFAULT_BLE_MTU);
+    }
+
+    #[test]
+    fn test_estimated_throughput() {
+        // 默认参数：每事件 6 个 12 字节分片，30ms
+        let default = LinkParameters::new();
+        assert_eq!(default.packets_per_event(), MAX_PACKETS_PER_EVENT);
+        assert_eq!(default.chunk_payload_size(), 12);
+        assert_eq!(default.estimated_throughput(), 2400);
+        assert_eq!(default.chunk_interval(), Duration::from_millis(5));
+
+        let tuned = LinkParameters::new()
+            .with_mtu(247)
+            .with_phy(BlePhy::Le2M)
+            .with_data_length(MAX_DATA_LENGTH)
+            .with_connection_interval(Duration::from_millis(15));
+        assert_eq!(tuned.chunks_per_event(), 6);
+        assert_eq!(tuned.estimated_throughput(), 6 * 236 * 1000 / 15);
+
+        // 间隔太短时每个事件至少一个包
+        let coded = tuned.with_phy(BlePhy::LeCoded).with_connection_interval(Duration::from_micros(7_500));
+        assert_eq!(coded.packets_per_event(), 1);
+        assert_eq!(coded.chunk_interval(), Duration::from_micros(7_500));
+    }
+
+    #[test]
+    fn test_connection_priority_intervals() {
+        let (min, max) = ConnectionPriority::High.interval_range();
+        assert!(min < max);
+        assert!(max < ConnectionPriority::Balanced.interval_range().0);
+        assert!(ConnectionPriority::Balanced.interval_range().1 < ConnectionPriority::LowPower.interval_range().0);
+    }
+
+    #[test]
+    fn test_throughput_meter() {
+        let mut meter = ThroughputMeter::new();
+        assert_eq!(meter.bytes_per_second(), None);
+
+        meter.record(1000, Duration::from_secs(1));
+        assert_eq!(meter.bytes_per_second(), Some(1000));
+        meter.record(5000, Duration::from_secs(1));
+        assert_eq!(meter.bytes_per_second(), Some(2000));
+        meter.record(0, Duration::ZERO);
+        assert_eq!(meter.bytes_per_second(), Some(2000));
+        assert_eq!(meter.total_bytes(), 6000);
+    }
+}
@@ -0,0 +1,640 @@
+//! BLE 配对模块
+//!
+//! 二维码配对流程携带的 `ConnectionInfo` 只有 IP 和端口，没有共享 WiFi 的两台设备
+//! 无法用它配对。本模块通过 [`PAIRING_CHARACTERISTIC_UUID`] 在 BLE 上完成
+//! nearclip-protocol 的 [`PairingMessage`] 交换：
+//! - `BlePairingSession`: 配对状态机，负责消息的分片、重组和状态转换
+//! - `BlePairingChannel`: 在 [`BleHardware`] 上收发配对消息的信道
+//!
+//! 消息用 MessagePack 序列化（与 WiFi 路径相同），再按 v2 头部分片（见 [`crate::chunk`]），
+//! 每个分片带 CRC32。配对特征是新特征，支持它的对端都支持 v2 头部。
+//!
+//! # 流程
+//!
+//! ```text
+//! 发起方 (Initiator)                         响应方 (Responder)
+//! Idle                                        Idle
+//!   |------------ PairingRequest ------------->|
+//! RequestSent                                 RequestReceived
+//!   |<----------- PairingResponse -------------|
+//! ResponseReceived                            ResponseSent
+//!   |------------ PairingConfirm ------------->|
+//! ConfirmSent                                 ConfirmReceived
+//!   |<----------- PairingComplete -------------|
+//! Completed                                   Completed
+//! ```
+//!
+//! 任一方发送或收到 `PairingRejected` 后进入 `Failed`。双方的 `PairedDevice`
+//! 由 nearclip-device 的 `PairingManager` 计算共享密钥并持久化，与 WiFi 路径一致；
+//! `BlePairingChannel` 在那里实现了配对的 `Transport`。
+//!
+//! # Example
+//!
+//! ```
+//! use nearclip_ble::pairing::{BlePairingRole, BlePairingSession, BlePairingState};
+//! use nearclip_protocol::pairing::DevicePlatform;
+//! use nearclip_protocol::{PairingMessage, PairingRequest};
+//!
+//! let mut initiator = BlePairingSession::new(BlePairingRole::Initiator, 23);
+//! let mut responder = BlePairingSession::new(BlePairingRole::Responder, 23);
+//!
+//! let request = PairingMessage::PairingRequest(PairingRequest::new(
+//!     "mac-1".to_string(),
+//!     "MacBook".to_string(),
+//!     DevicePlatform::MacOS,
+//!     vec![4u8; 65],
+//!     [7u8; 32],
+//! ));
+//!
+//! // 每个分片写入一次配对特征
+//! let mut received = None;
+//! for chunk in initiator.encode(&request).unwrap() {
+//!     received = received.or(responder.receive_chunk(&chunk).unwrap());
+//! }
+//!
+//! assert_eq!(received, Some(request));
+//! assert_eq!(*initiator.state(), BlePairingState::RequestSent);
+//! assert_eq!(*responder.state(), BlePairingState::RequestReceived);
+//! ```
+
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use nearclip_protocol::PairingMessage;
+use tracing::{debug, warn};
+
+use crate::chunk::{
+    ChunkFlags, ChunkHeader, ChunkVersion, Chunker, Reassembler, DEFAULT_REASSEMBLE_TIMEOUT,
+};
+use crate::controller::{BleHardware, BleHardwareEvent};
+use crate::gatt::{DEFAULT_BLE_MTU, PAIRING_CHARACTERISTIC_UUID};
+use crate::BleError;
+
+/// 配对中的角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlePairingRole {
+    /// 发起方：发送 `PairingRequest` 和 `PairingConfirm`
+    Initiator,
+    /// 响应方：发送 `PairingResponse` 和 `PairingComplete`
+    Responder,
+}
+
+/// BLE 配对状态
+///
+/// 状态以最后一条发送或收到的消息命名。
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum BlePairingState {
+    /// 尚未交换任何消息
+    #[default]
+    Idle,
+    /// 发起方已发送 `PairingRequest`
+    RequestSent,
+    /// 响应方已收到 `PairingRequest`
+    RequestReceived,
+    /// 响应方已发送 `PairingResponse`
+    ResponseSent,
+    /// 发起方已收到 `PairingResponse`
+    ResponseReceived,
+    /// 发起方已发送 `PairingConfirm`
+    ConfirmSent,
+    /// 响应方已收到 `PairingConfirm`
+    ConfirmReceived,
+    /// 配对完成
+    Completed,
+    /// 配对被拒绝
+    Failed {
+        /// 拒绝原因
+        reason: String,
+    },
+}
+
+impl BlePairingState {
+    /// 是否已结束（完成或失败）
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Completed | Self::Failed { .. })
+    }
+
+    /// 是否已完成
+    pub fn is_completed(&self) -> bool {
+        matches!(self, Self::Completed)
+    }
+}
+
+/// 消息方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Outgoing,
+    Incoming,
+}
+
+/// BLE 配对状态机
+///
+/// 不做任何 I/O：[`encode`](Self::encode) 返回要写入配对特征的分片，
+/// [`receive_chunk`](Self::receive_chunk) 处理从配对特征收到的分片。
+/// 不符合流程的消息（无论发送还是接收）返回 `BleError::DataTransfer`，状态不变。
+#[derive(Debug)]
+pub struct BlePairingSession {
+    role: BlePairingRole,
+    state: BlePairingState,
+    mtu: usize,
+    next_message_id: u32,
+    reassembler: Option<Reassembler>,
+}
+
+impl BlePairingSession {
+    /// 创建配对状态机
+    ///
+    /// # Arguments
+    ///
+    /// * `role` - 本端角色
+    /// * `mtu` - 连接的 ATT MTU（包含 ATT 头部）
+    pub fn new(role: BlePairingRole, mtu: usize) -> Self {
+        Self {
+            role,
+            state: BlePairingState::Idle,
+            mtu,
+            next_message_id: 0,
+            reassembler: None,
+        }
+    }
+
+    /// 本端角色
+    pub fn role(&self) -> BlePairingRole {
+        self.role
+    }
+
+    /// 当前状态
+    pub fn state(&self) -> &BlePairingState {
+        &self.state
+    }
+
+    /// 更新 MTU（连接参数变化后调用），只影响之后发送的消息
+    pub fn set_mtu(&mut self, mtu: usize) {
+        self.mtu = mtu;
+    }
+
+    /// 序列化并分片一条要发送的配对消息，同时推进状态
+    ///
+    /// # Errors
+    ///
+    /// - `BleError::DataTransfer` - 当前状态不能发送此消息，或序列化失败
+    /// - `BleError::ChunkError` - MTU 太小
+    pub fn encode(&mut self, message: &PairingMessage) -> Result<Vec<Vec<u8>>, BleError> {
+        let next = self.transition(message, Direction::Outgoing)?;
+
+        let data = rmp_serde::to_vec(message).map_err(|e| {
+            BleError::DataTransfer(format!("Failed to serialize pairing message: {}", e))
+        })?;
+        let chunks = Chunker::chunk_versioned(
+            &data,
+            self.next_message_id,
+            self.mtu,
+            ChunkVersion::V2,
+            ChunkFlags::empty(),
+        )?;
+
+        debug!(
+            role = ?self.role,
+            message = message_kind(message),
+            chunks = chunks.len(),
+            "Sending pairing message"
+        );
+        self.next_message_id = self.next_message_id.wrapping_add(1);
+        self.state = next;
+        Ok(chunks)
+    }
+
+    /// 处理从配对特征收到的一个分片
+    ///
+    /// 消息的所有分片到齐后反序列化并推进状态，返回 `Some(message)`；
+    /// 否则返回 `None`。新消息 ID 的分片会丢弃未完成的旧消息。
+    ///
+    /// # Errors
+    ///
+    /// - `BleError::ChunkError` - 分片不是有效的 v2 分片
+    /// - `BleError::DataTransfer` - 反序列化失败，或当前状态不应收到此消息
+    pub fn receive_chunk(&mut self, chunk: &[u8]) -> Result<Option<PairingMessage>, BleError> {
+        let header = ChunkHeader::from_bytes(chunk)?;
+        if header.version != ChunkVersion::V2 {
+            return Err(BleError::ChunkError(
+                "Pairing chunks must use v2 headers".to_string(),
+            ));
+        }
+        let payload = chunk[header.header_size()..].to_vec();
+
+        if self
+            .reassembler
+            .as_ref()
+            .is_some_and(|r| r.message_id() != header.message_id || r.is_expired())
+        {
+            debug!(role = ?self.role, "Dropping incomplete pairing message");
+            self.reassembler = None;
+        }
+        let reassembler = self.reassembler.get_or_insert_with(|| {
+            Reassembler::new(header.message_id, header.total_chunks, DEFAULT_REASSEMBLE_TIMEOUT)
+        });
+        reassembler.add_chunk(header, payload)?;
+        if !reassembler.is_complete() {
+            return Ok(None);
+        }
+
+        let data = match self.reassembler.take() {
+            Some(reassembler) => reassembler.assemble()?,
+            None => return Ok(None),
+        };
+        let message: PairingMessage = rmp_serde::from_slice(&data).map_err(|e| {
+            BleError::DataTransfer(format!("Failed to deserialize pairing message: {}", e))
+        })?;
+
+        self.state = self.transition(&message, Direction::Incoming)?;
+        debug!(
+            role = ?self.role,
+            message = message_kind(&message),
+            state = ?self.state,
+            "Received pairing message"
+        );
+        Ok(Some(message))
+    }
+
+    /// 计算发送或收到 `message` 后的状态
+    fn transition(
+        &self,
+        message: &PairingMessage,
+        direction: Direction,
+    ) -> Result<BlePairingState, BleError> {
+        use BlePairingRole::{Initiator, Responder};
+        use BlePairingState::*;
+        use Direction::{Incoming, Outgoing};
+
+        let next = match (self.role, &self.state, direction, message) {
+            (_, state, _, PairingMessage::PairingRejected(rejected)) if !state.is_finished() => {
+                Some(Failed {
+                    reason: rejected.reason.clone(),
+                })
+            }
+            (Initiator, Idle, Outgoing, PairingMessage::PairingRequest(_)) => Some(RequestSent),
+            (Initiator, RequestSent, Incoming, PairingMessage::PairingResponse(_)) => {
+                Some(ResponseReceived)
+            }
+            (Initiator, ResponseReceived, Outgoing, PairingMessage::PairingConfirm(_)) => {
+                Some(ConfirmSent)
+            }
+            (Initiator, ConfirmSent, Incoming, PairingMessage::PairingComplete) => Some(Completed),
+            (Responder, Idle, Incoming, PairingMessage::PairingRequest(_)) => Some(RequestReceived),
+            (Responder, RequestReceived, Outgoing, PairingMessage::PairingResponse(_)) => {
+                Some(ResponseSent)
+            }
+            (Responder, ResponseSent, Incoming, PairingMessage::PairingConfirm(_)) => {
+                Some(ConfirmReceived)
+            }
+            (Responder, ConfirmReceived, Outgoing, PairingMessage::PairingComplete) => {
+                Some(Completed)
+            }
+            _ => None,
+        };
+
+        next.ok_or_else(|| {
+            BleError::DataTransfer(format!(
+                "Unexpected {:?} {} for {:?} in state {:?}",
+                direction,
+                message_kind(message),
+                self.role,
+                self.state
+            ))
+        })
+    }
+}
+
+/// 配对消息的名称（用于日志和错误信息）
+fn message_kind(message: &PairingMessage) -> &'static str {
+    match message {
+        PairingMessage::PairingRequest(_) => "PairingRequest",
+        PairingMessage::PairingResponse(_) => "PairingResponse",
+        PairingMessage::PairingConfirm(_) => "PairingConfirm",
+        PairingMessage::PairingComplete => "PairingComplete",
+        PairingMessage::PairingRejected(_) => "PairingRejected",
+    }
+}
+
+/// BLE 配对信道
+///
+/// 把 [`BlePairingSession`] 接到一个已连接对端的配对特征上：
+/// - [`send`](Self::send) 分片后写入配对特征（中心写入，外设通知）
+/// - [`handle_hardware_event`](Self::handle_hardware_event) 处理对端写入配对特征的数据，
+///   重组完成的消息放入接收队列
+/// - [`recv_timeout`](Self::recv_timeout) 阻塞等待下一条消息
+///
+/// 配对特征之外的事件原样返回，可以接在
+/// [`BleController::handle_hardware_event`](crate::BleController::handle_hardware_event)
+/// 返回的事件之后。作为中心的一方需要先调用 [`subscribe`](Self::subscribe)，
+/// 外设才能通知回复。
+pub struct BlePairingChannel {
+    hardware: Arc<dyn BleHardware>,
+    peripheral_id: String,
+    session: Mutex<BlePairingSession>,
+    incoming_tx: mpsc::Sender<PairingMessage>,
+    incoming_rx: Mutex<mpsc::Receiver<PairingMessage>>,
+}
+
+impl BlePairingChannel {
+    /// 创建配对信道
+    ///
+    /// MTU 取自 `hardware.get_mtu`，未知时使用 [`DEFAULT_BLE_MTU`]。
+    ///
+    /// # Arguments
+    ///
+    /// * `hardware` - BLE 硬件后端
+    /// * `peripheral_id` - 对端在 `hardware` 中的 ID（必须已连接）
+    /// * `role` - 本端在配对中的角色
+    pub fn new(hardware: Arc<dyn BleHardware>, peripheral_id: &str, role: BlePairingRole) -> Self {
+        let mtu = (hardware.get_mtu(peripheral_id) as usize).max(DEFAULT_BLE_MTU);
+        let (incoming_tx, incoming_rx) = mpsc::channel();
+        Self {
+            hardware,
+            peripheral_id: peripheral_id.to_string(),
+            session: Mutex::new(BlePairingSession::new(role, mtu)),
+            incoming_tx,
+            incoming_rx: Mutex::new(incoming_rx),
+        }
+    }
+
+    /// 对端 ID
+    pub fn peripheral_id(&self) -> &str {
+        &self.peripheral_id
+    }
+
+    /// 当前配对状态
+    pub fn state(&self) -> BlePairingState {
+        self.session().state().clone()
+    }
+
+    /// 订阅对端的配对特征（作为中心时调用）
+    pub fn subscribe(&self) -> Result<(), BleError> {
+        self.hardware
+            .subscribe_characteristic(&self.peripheral_id, &PAIRING_CHARACTERISTIC_UUID.to_string())
+            .map_err(BleError::ConnectionFailed)
+    }
+
+    /// 发送一条配对消息
+    ///
+    /// # Errors
+    ///
+    /// - `BleError::DataTransfer` - 当前状态不能发送此消息，或写入失败
+    pub fn send(&self, message: &PairingMessage) -> Result<(), BleError> {
+        let chunks = self.session().encode(message)?;
+        let char_uuid = PAIRING_CHARACTERISTIC_UUID.to_string();
+        for chunk in chunks {
+            self.hardware
+                .write_characteristic(&self.peripheral_id, &char_uuid, &chunk)
+                .map_err(BleError::DataTransfer)?;
+        }
+        Ok(())
+    }
+
+    /// 阻塞等待下一条配对消息
+    ///
+    /// # Errors
+    ///
+    /// - `BleError::Timeout` - `timeout` 内没有完整的消息到达
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<PairingMessage, BleError> {
+        self.incoming_rx
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .recv_timeout(timeout)
+            .map_err(|_| {
+                BleError::Timeout(format!("No pairing message within {} ms", timeout.as_millis()))
+            })
+    }
+
+    /// 处理对端写入配对特征的数据
+    ///
+    /// # Errors
+    ///
+    /// 见 [`BlePairingSession::receive_chunk`]
+    pub fn handle_data(&self, data: &[u8]) -> Result<(), BleError> {
+        let message = self.session().receive_chunk(data)?;
+        if let Some(message) = message {
+            // 接收端总是由 self 持有，发送不会失败
+            let _ = self.incoming_tx.send(message);
+        }
+        Ok(())
+    }
+
+    /// 处理硬件事件
+    ///
+    /// 消费来自本对端、写入配对特征的 `DataReceived`，其他事件原样返回。
+    /// 无效的分片或消息只记录日志，由等待方超时处理。
+    pub fn handle_hardware_event(&self, event: BleHardwareEvent) -> Option<BleHardwareEvent> {
+        match event {
+            BleHardwareEvent::DataReceived {
+                peripheral_id,
+                char_uuid,
+                data,
+            } if peripheral_id == self.peripheral_id
+                && char_uuid.eq_ignore_ascii_case(&PAIRING_CHARACTERISTIC_UUID.to_string()) =>
+            {
+                if let Err(e) = self.handle_data(&data) {
+                    warn!(peripheral_id = %peripheral_id, error = %e, "Invalid pairing data");
+                }
+                None
+            }
+            other => Some(other),
+        }
+    }
+
+    fn session(&self) -> MutexGuard<'_, BlePairingSession> {
+        self.session.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sim::{SimAir, SimAirConfig, SimDeviceConfig};
+    use nearclip_protocol::pairing::DevicePlatform;
+    use nearclip_protocol::{PairingConfirm, PairingRejected, PairingRequest, PairingResponse};
+    use tokio::sync::mpsc::UnboundedReceiver;
+
+    fn request() -> PairingMessage {
+        PairingMessage::PairingRequest(PairingRequest::new(
+            "initiator".to_string(),
+            "Initiator".to_string(),
+            DevicePlatform::MacOS,
+            vec![4u8; 65],
+            [1u8; 32],
+        ))
+    }
+
+    fn response() -> PairingMessage {
+        PairingMessage::PairingResponse(PairingResponse::new(
+            "responder".to_string(),
+            "Responder".to_string(),
+            DevicePlatform::Android,
+            vec![5u8; 65],
+            [2u8; 32],
+            vec![6u8; 64],
+        ))
+    }
+
+    fn confirm() -> PairingMessage {
+        PairingMessage::PairingConfirm(PairingConfirm {
+            signature: vec![7u8; 64],
+        })
+    }
+
+    /// 把 `from` 发送的消息逐个分片交给 `to`，返回重组出的消息
+    fn deliver(
+        from: &mut BlePairingSession,
+        to: &mut BlePairingSession,
+        message: &PairingMessage,
+    ) -> Option<PairingMessage> {
+        let chunks = from.encode(message).unwrap();
+        let mut received = None;
+        for chunk in chunks {
+            if let Some(message) = to.receive_chunk(&chunk).unwrap() {
+                assert!(received.is_none());
+                received = Some(message);
+            }
+        }
+        received
+    }
+
+    #[test]
+    fn test_session_full_flow() {
+        let mut initiator = BlePairingSession::new(BlePairingRole::Initiator, DEFAULT_BLE_MTU);
+        let mut responder = BlePairingSession::new(BlePairingRole::Responder, 64);
+
+        assert_eq!(deliver(&mut initiator, &mut responder, &request()), Some(request()));
+        assert_eq!(*initiator.state(), BlePairingState::RequestSent);
+        assert_eq!(*responder.state(), BlePairingState::RequestReceived);
+
+        assert_eq!(deliver(&mut responder, &mut initiator, &response()), Some(response()));
+        assert_eq!(*initiator.state(), BlePairingState::ResponseReceived);
+        assert_eq!(*responder.state(), BlePairingState::ResponseSent);
+
+        assert_eq!(deliver(&mut initiator, &mut responder, &confirm()), Some(confirm()));
+        assert_eq!(*initiator.state(), BlePairingState::ConfirmSent);
+        assert_eq!(*responder.state(), BlePairingState::ConfirmReceived);
+
+        let complete = PairingMessage::PairingComplete;
+        assert_eq!(deliver(&mut responder, &mut initiator, &complete), Some(complete));
+        assert!(initiator.state().is_completed());
+        assert!(responder.state().is_completed());
+    }
+
+    #[test]
+    fn test_session_rejects_out_of_order_messages() {
+        let mut initiator = BlePairingSession::new(BlePairingRole::Initiator, DEFAULT_BLE_MTU);
+        let mut responder = BlePairingSession::new(BlePairingRole::Responder, DEFAULT_BLE_MTU);
+
+        // 响应方不能主动发起，发起方不能跳过请求
+        assert!(matches!(responder.encode(&request()), Err(BleError::DataTransfer(_))));
+        assert!(matches!(initiator.encode(&confirm()), Err(BleError::DataTransfer(_))));
+        assert_eq!(*initiator.state(), BlePairingState::Idle);
+
+        // 在等待请求时收到确认
+        let mut other = BlePairingSession::new(BlePairingRole::Initiator, DEFAULT_BLE_MTU);
+        other.encode(&request()).unwrap();
+        other.state = BlePairingState::ResponseReceived;
+        let chunks = other.encode(&confirm()).unwrap();
+        let result = chunks
+            .iter()
+            .map(|chunk| responder.receive_chunk(chunk))
+            .find(|result| !matches!(result, Ok(None)));
+        assert!(matches!(result, Some(Err(BleError::DataTransfer(_)))));
+        assert_eq!(*responder.state(), BlePairingState::Idle);
+    }
+
+    #[test]
+    fn test_session_rejection_fails_both_sides() {
+        let mut initiator = BlePairingSession::new(BlePairingRole::Initiator, DEFAULT_BLE_MTU);
+        let mut responder = BlePairingSession::new(BlePairingRole::Responder, DEFAULT_BLE_MTU);
+        deliver(&mut initiator, &mut responder, &request());
+
+        let rejected = PairingMessage::PairingRejected(PairingRejected::already_paired());
+        assert_eq!(deliver(&mut responder, &mut initiator, &rejected), Some(rejected.clone()));
+
+        let failed = BlePairingState::Failed {
+            reason: "Device is already paired".to_string(),
+        };
+        assert_eq!(*initiator.state(), failed);
+        assert_eq!(*responder.state(), failed);
+        assert!(initiator.encode(&rejected).is_err());
+    }
+
+    #[test]
+    fn test_session_rejects_corrupted_and_v1_chunks() {
+        let mut initiator = BlePairingSession::new(BlePairingRole::Initiator, DEFAULT_BLE_MTU);
+        let mut responder = BlePairingSession::new(BlePairingRole::Responder, DEFAULT_BLE_MTU);
+
+        let mut chunk = initiator.encode(&request()).unwrap().remove(0);
+        let last = chunk.len() - 1;
+        chunk[last] ^= 0xff;
+        assert!(matches!(responder.receive_chunk(&chunk), Err(BleError::ChunkError(_))));
+
+        let v1 = Chunker::chunk(b"pairing", 1, DEFAULT_BLE_MTU).unwrap();
+        assert!(matches!(responder.receive_chunk(&v1[0]), Err(BleError::ChunkError(_))));
+        assert_eq!(*responder.state(), BlePairingState::Idle);
+    }
+
+    /// 转发 `events` 给 `channel`，直到配对状态变为 `until`
+    async fn pump(
+        channel: &BlePairingChannel,
+        events: &mut UnboundedReceiver<BleHardwareEvent>,
+        until: BlePairingState,
+    ) {
+        while channel.state() != until {
+            let event = tokio::time::timeout(Duration::from_secs(1), events.recv())
+                .await
+                .expect("event should arrive")
+                .unwrap();
+            channel.handle_hardware_event(event);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_channel_over_sim_air() {
+        let air = SimAir::new(SimAirConfig::new());
+        let (central, mut central_events) =
+            air.add_device(SimDeviceConfig::new("central".into(), "hash-c".into()));
+        let (peripheral, mut peripheral_events) =
+            air.add_device(SimDeviceConfig::new("peripheral".into(), "hash-p".into()));
+        peripheral.start_advertising(&[1]);
+        central.connect(peripheral.address());
+        central_events.recv().await.unwrap();
+        peripheral_events.recv().await.unwrap();
+
+        let central_address = central.address().to_string();
+        let peripheral_address = peripheral.address().to_string();
+        let initiator =
+            BlePairingChannel::new(Arc::new(central), &peripheral_address, BlePairingRole::Initiator);
+        let responder = BlePairingChannel::new(
+            Arc::new(peripheral),
+            &central_address,
+            BlePairingRole::Responder,
+        );
+        initiator.subscribe().unwrap();
+
+        initiator.send(&request()).unwrap();
+        pump(&responder, &mut peripheral_events, BlePairingState::RequestReceived).await;
+        assert_eq!(responder.recv_timeout(Duration::ZERO).unwrap(), request());
+
+        // 外设通过通知回复
+        responder.send(&response()).unwrap();
+        pump(&initiator, &mut central_events, BlePairingState::ResponseReceived).await;
+        assert_eq!(initiator.recv_timeout(Duration::ZERO).unwrap(), response());
+        assert!(matches!(
+            initiator.recv_timeout(Duration::ZERO),
+            Err(BleError::Timeout(_))
+        ));
+
+        // 其他特征的事件原样返回
+        let event = BleHardwareEvent::DataReceived {
+            peripheral_id: peripheral_address,
+            char_uuid: crate::gatt::DATA_TRANSFER_CHARACTERISTIC_UUID.to_string(),
+            data: vec![1],
+        };
+        assert_eq!(initiator.handle_hardware_event(event.clone()), Some(event));
+    }
+}
@@ -212,7 +212,7 @@ struct ReceiverState {
     /// 是否正在运行
     is_running: bool,
     /// 当前活跃的消息重组器 (message_id -> Reassembler)
-    reassemblers: HashMap<u16, Reassembler>,
+    reassemblers: HashMap<u32, Reassembler>,
 }
 
 /// BLE 外设端数据接收器
@@ -422,7 +422,7 @@ impl PeripheralDataReceiver {
         }
 
         let header = ChunkHeader::from_bytes(data)?;
-        let payload = data[CHUNK_HEADER_SIZE..].to_vec();
+        let payload = data[header.header_size()..].to_vec();
 
         trace!(
             message_id = header.message_id,
@@ -510,7 +510,7 @@ impl PeripheralDataReceiver {
 
     /// 清理过期的重组器
     async fn cleanup_expired_reassemblers(&self, state: &mut ReceiverState) {
-        let expired_ids: Vec<u16> = state
+        let expired_ids: Vec<u32> = state
             .reassemblers
             .iter()
             .filter(|(_, r)| r.is_expired())
@@ -0,0 +1,754 @@
+//! BLE 分片选择性重传
+//!
+//! 在有损链路上，整条消息超时重发代价太高：一条剪贴板消息在 MTU 23 时
+//! 可能有上百个分片。本模块实现窗口化的选择性重传：
+//!
+//! - 接收端通过 DATA_ACK 特征发送 [`ChunkReport`]，报告累计确认位置和缺失的序号区间
+//! - 发送端的 [`SendWindow`] 只重传缺失的分片，并限制在途分片数
+//! - 窗口大小随观测到的丢包率调整：低丢包时逐步增大，高丢包或超时时减半
+//! - 紧急消息（控制消息、流控更新）优先发送且不受窗口限制，不会被大块数据阻塞
+//!
+//! # 报告格式
+//!
+//! ```text
+//! +----------------+----------------+----------------+-----------+
+//! | message_id (2) | next_expected  | highest (2)    | count (1) |
+//! |                | (2)            |                |           |
+//! +----------------+----------------+----------------+-----------+
+//! | start (2) | end (2) |  ... count 个缺失区间（闭区间）          |
+//! +-----------+---------+------------------------------------------+
+//! ```
+//!
+//! 所有字段为小端序。`next_expected` 之前的分片已全部收到；
+//! `next_expected..=highest` 中不在缺失区间内的分片也已收到。
+//! 报告至少 7 字节，与 2 字节的整条消息 ACK 按长度区分。
+//!
+//! 接收端用收到的分片的头部版本回复报告。v2 报告的 `message_id` 为 4 字节，
+//! 头部 9 字节；两种报告的长度除以 4 的余数不同（v1 为 3，v2 为 1），据此区分。
+//!
+//! # 接收端何时报告
+//!
+//! 由 [`Reassembler::take_report`](crate::chunk::Reassembler::take_report) 决定：
+//! 出现缺口、收到重复或补洞分片、收到最后一个序号、消息完整，
+//! 或自上次报告以来已收到 [`REPORT_INTERVAL`] 个分片。
+//!
+//! # Example
+//!
+//! ```
+//! use nearclip_ble::chunk::{ChunkHeader, Chunker, Reassembler, DEFAULT_REASSEMBLE_TIMEOUT};
+//! use nearclip_ble::gatt::CHUNK_HEADER_SIZE;
+//! use nearclip_ble::retransmit::SendWindow;
+//!
+//! let data = vec![7u8; 100];
+//! let chunks = Chunker::chunk(&data, 1, 23).unwrap();
+//! let total = chunks.len() as u16;
+//!
+//! let mut window = SendWindow::new();
+//! window.push(1, chunks, false);
+//! let mut reassembler = Reassembler::new(1, total, DEFAULT_REASSEMBLE_TIMEOUT);
+//!
+//! let mut dropped_one = false;
+//! while !reassembler.is_complete() {
+//!     let Some(chunk) = window.next_chunk() else { break };
+//!     let header = ChunkHeader::from_bytes(&chunk).unwrap();
+//!     // 丢掉第 2 个分片一次
+//!     if header.sequence_number == 2 && !dropped_one {
+//!         dropped_one = true;
+//!         continue;
+//!     }
+//!     reassembler.add_chunk(header, chunk[CHUNK_HEADER_SIZE..].to_vec()).unwrap();
+//!     if let Some(report) = reassembler.take_report() {
+//!         window.on_report(&report);
+//!     }
+//! }
+//!
+//! assert_eq!(reassembler.assemble().unwrap(), data);
+//! assert!(window.is_idle());
+//! ```
+
+use crate::chunk::ChunkVersion;
+use crate::BleError;
+use std::collections::VecDeque;
+use tracing::{debug, trace, warn};
+
+/// 报告固定头部大小
+pub const CHUNK_REPORT_HEADER_SIZE: usize = 7;
+
+/// v2 报告固定头部大小（4 字节 message_id）
+pub const CHUNK_REPORT_V2_HEADER_SIZE: usize = 9;
+
+/// 每个缺失区间的编码大小
+const MISSING_RANGE_SIZE: usize = 4;
+
+/// 接收端至少每收到这么多个分片报告一次
+pub const REPORT_INTERVAL: u16 = 4;
+
+/// 最小发送窗口（在途分片数）
+pub const MIN_SEND_WINDOW: usize = REPORT_INTERVAL as usize;
+
+/// 初始发送窗口
+pub const DEFAULT_SEND_WINDOW: usize = 16;
+
+/// 最大发送窗口
+pub const MAX_SEND_WINDOW: usize = 64;
+
+/// 连续多少次重传超时没有进展后放弃最早的消息
+pub const MAX_STALLED_TICKS: u32 = 5;
+
+/// 丢包率指数移动平均的权重
+const LOSS_EWMA_WEIGHT: f64 = 0.125;
+
+/// 丢包率高于此值且本次报告有丢包时窗口减半
+const LOSS_DECREASE_THRESHOLD: f64 = 0.05;
+
+/// 丢包率低于此值且本次报告无丢包时窗口加一
+const LOSS_INCREASE_THRESHOLD: f64 = 0.01;
+
+// ============================================================================
+// ChunkReport
+// ============================================================================
+
+/// 接收端的分片接收报告（选择性确认 / NACK）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkReport {
+    /// 报告所针对的分片头部版本，决定 `message_id` 的编码宽度
+    pub version: ChunkVersion,
+    /// 消息 ID（v1 只使用低 16 位）
+    pub message_id: u32,
+    /// 此序号之前的分片已全部收到
+    pub next_expected: u16,
+    /// 报告覆盖的最大序号
+    pub highest: u16,
+    /// `next_expected..=highest` 中缺失的序号区间（闭区间，升序）
+    pub missing: Vec<(u16, u16)>,
+}
+
+impl ChunkReport {
+    /// 编码报告，不超过 `max_len` 字节
+    ///
+    /// 区间放不下时只保留靠前的区间（至少一个），并把 `highest` 截到第一个
+    /// 被省略的区间之前，避免发送端把未报告的缺失分片当作已收到。
+    pub fn encode(&self, max_len: usize) -> Vec<u8> {
+        let header_size = report_header_size(self.version);
+        let capacity = (max_len.saturating_sub(header_size) / MISSING_RANGE_SIZE).max(1);
+        let count = self.missing.len().min(capacity).min(u8::MAX as usize);
+        let highest = match self.missing.get(count) {
+            Some(&(start, _)) => start - 1,
+            None => self.highest,
+        };
+
+        let mut bytes = Vec::with_capacity(header_size + count * MISSING_RANGE_SIZE);
+        match self.version {
+            ChunkVersion::V1 => bytes.extend_from_slice(&(self.message_id as u16).to_le_bytes()),
+            ChunkVersion::V2 => bytes.extend_from_slice(&self.message_id.to_le_bytes()),
+        }
+        bytes.extend_from_slice(&self.next_expected.to_le_bytes());
+        bytes.extend_from_slice(&highest.to_le_bytes());
+        bytes.push(count as u8);
+        for &(start, end) in &self.missing[..count] {
+            bytes.extend_from_slice(&start.to_le_bytes());
+            bytes.extend_from_slice(&end.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// 解码报告，按长度区分 v1 和 v2
+    ///
+    /// # Errors
+    ///
+    /// - `BleError::ChunkError` - 长度不符或区间无效
+    pub fn decode(bytes: &[u8]) -> Result<Self, BleError> {
+        if bytes.len() < CHUNK_REPORT_HEADER_SIZE {
+            return Err(BleError::ChunkError(format!(
+                "Chunk report too short: {} bytes",
+                bytes.len()
+            )));
+        }
+        let version = match bytes.len() % MISSING_RANGE_SIZE {
+            3 => ChunkVersion::V1,
+            1 if bytes.len() >= CHUNK_REPORT_V2_HEADER_SIZE => ChunkVersion::V2,
+            _ => {
+                return Err(BleError::ChunkError(format!(
+                    "Invalid chunk report length: {} bytes",
+                    bytes.len()
+                )))
+            }
+        };
+        let header_size = report_header_size(version);
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let (message_id, at) = match version {
+            ChunkVersion::V1 => (u16_at(0) as u32, 2),
+            ChunkVersion::V2 => (u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]), 4),
+        };
+        let count = bytes[header_size - 1] as usize;
+        if bytes.len() != header_size + count * MISSING_RANGE_SIZE {
+            return Err(BleError::ChunkError(format!(
+                "Chunk report length {} does not match {} ranges",
+                bytes.len(),
+                count
+            )));
+        }
+
+        let report = Self {
+            version,
+            message_id,
+            next_expected: u16_at(at),
+            highest: u16_at(at + 2),
+            missing: (0..count)
+                .map(|i| {
+                    let at = header_size + i * MISSING_RANGE_SIZE;
+                    (u16_at(at), u16_at(at + 2))
+                })
+                .collect(),
+        };
+
+        // 区间必须升序、不重叠，且落在 next_expected..=highest 内
+        let mut floor = report.next_expected;
+        for &(start, end) in &report.missing {
+            if start < floor || start > end || end > report.highest {
+                return Err(BleError::ChunkError(format!(
+                    "Invalid missing range {}..={} in chunk report",
+                    start, end
+                )));
+            }
+            floor = end.saturating_add(1);
+        }
+        Ok(report)
+    }
+
+    /// 缺失的分片总数
+    pub fn missing_count(&self) -> usize {
+        self.missing
+            .iter()
+            .map(|&(start, end)| (end - start) as usize + 1)
+            .sum()
+    }
+
+    fn is_missing(&self, sequence: u16) -> bool {
+        self.missing
+            .iter()
+            .any(|&(start, end)| (start..=end).contains(&sequence))
+    }
+}
+
+/// 报告固定头部大小
+fn report_header_size(version: ChunkVersion) -> usize {
+    match version {
+        ChunkVersion::V1 => CHUNK_REPORT_HEADER_SIZE,
+        ChunkVersion::V2 => CHUNK_REPORT_V2_HEADER_SIZE,
+    }
+}
+
+// ============================================================================
+// SendWindow
+// ============================================================================
+
+/// 一条待确认的消息
+struct Outgoing {
+    message_id: u32,
+    chunks: Vec<Vec<u8>>,
+    acked: Vec<bool>,
+    /// 每个分片最近一次发送的序号（0 表示未发送）
+    tx: Vec<u64>,
+    /// 已排队等待重传
+    queued: Vec<bool>,
+    next_unsent: usize,
+    urgent: bool,
+}
+
+impl Outgoing {
+    fn is_done(&self) -> bool {
+        self.acked.iter().all(|&acked| acked)
+    }
+
+    fn in_flight(&self) -> usize {
+        (0..self.next_unsent).filter(|&i| !self.acked[i]).count()
+    }
+
+    fn has_unsent(&self) -> bool {
+        self.next_unsent < self.chunks.len()
+    }
+}
+
+/// 发送端窗口：跟踪在途分片，按报告选择性重传
+///
+/// 与 IO 无关：调用方用 [`next_chunk`](Self::next_chunk) 取出要发送的分片，
+/// 把收到的报告交给 [`on_report`](Self::on_report)，
+/// 并在每个重传周期调用一次 [`on_tick`](Self::on_tick)。
+pub struct SendWindow {
+    window: usize,
+    loss_rate: f64,
+    tx_counter: u64,
+    in_flight: usize,
+    messages: VecDeque<Outgoing>,
+    retransmit: VecDeque<(u32, u16)>,
+    stalled_ticks: u32,
+    progressed: bool,
+}
+
+impl SendWindow {
+    /// 创建发送窗口，初始窗口为 [`DEFAULT_SEND_WINDOW`]
+    pub fn new() -> Self {
+        Self {
+            window: DEFAULT_SEND_WINDOW,
+            loss_rate: 0.0,
+            tx_counter: 0,
+            in_flight: 0,
+            messages: VecDeque::new(),
+            retransmit: VecDeque::new(),
+            stalled_ticks: 0,
+            progressed: false,
+        }
+    }
+
+    /// 加入一条已分片的消息
+    ///
+    /// `urgent` 消息的分片排在普通消息之前，且不受窗口限制。
+    pub fn push(&mut self, message_id: u32, chunks: Vec<Vec<u8>>, urgent: bool) {
+        let total = chunks.len();
+        self.messages.push_back(Outgoing {
+            message_id,
+            chunks,
+            acked: vec![false; total],
+            tx: vec![0; total],
+            queued: vec![false; total],
+            next_unsent: 0,
+            urgent,
+        });
+    }
+
+    /// 取出下一个要发送的分片
+    ///
+    /// 依次为：重传、紧急消息的新分片、窗口内普通消息的新分片。
+    pub fn next_chunk(&mut self) -> Option<Vec<u8>> {
+        while let Some((message_id, sequence)) = self.retransmit.pop_front() {
+            let Some(message) = self.messages.iter_mut().find(|m| m.message_id == message_id) else {
+                continue;
+            };
+            let i = sequence as usize;
+            message.queued[i] = false;
+            if message.acked[i] {
+                continue;
+            }
+            self.tx_counter += 1;
+            message.tx[i] = self.tx_counter;
+            trace!(message_id, sequence, "Retransmitting chunk");
+            return Some(message.chunks[i].clone());
+        }
+
+        let window_open = self.in_flight < self.window;
+        let message = match self.messages.iter().position(|m| m.urgent && m.has_unsent()) {
+            Some(index) => &mut self.messages[index],
+            None if window_open => self.messages.iter_mut().find(|m| m.has_unsent())?,
+            None => return None,
+        };
+        let i = message.next_unsent;
+        message.next_unsent += 1;
+        self.tx_counter += 1;
+        message.tx[i] = self.tx_counter;
+        self.in_flight += 1;
+        Some(message.chunks[i].clone())
+    }
+
+    /// 是否可以加入新消息：没有未发出的分片且窗口未满
+    ///
+    /// 调用方据此决定何时从上层取下一条普通消息，让优先级在上层生效；
+    /// 紧急消息随时可以加入。
+    pub fn can_accept(&self) -> bool {
+        self.retransmit.is_empty()
+            && self.in_flight < self.window
+            && self.messages.iter().all(|m| !m.has_unsent())
+    }
+
+    /// 处理接收端的报告
+    ///
+    /// 确认已收到的分片，重传自上次发送后仍缺失的分片，并调整窗口。
+    /// 消息全部确认时返回 `true`。
+    pub fn on_report(&mut self, report: &ChunkReport) -> bool {
+        let Some(index) = self
+            .messages
+            .iter()
+            .position(|m| m.message_id == report.message_id)
+        else {
+            trace!(message_id = report.message_id, "Report for unknown message");
+            return false;
+        };
+        let message = &mut self.messages[index];
+        let total = message.chunks.len();
+        let highest = (report.highest as usize).min(total.saturating_sub(1));
+
+        let mut acked = 0;
+        for i in 0..total.min(highest + 1) {
+            let received = i < report.next_expected as usize || !report.is_missing(i as u16);
+            // 只接受已发出分片的确认
+            if received && !message.acked[i] && message.tx[i] != 0 {
+                message.acked[i] = true;
+                acked += 1;
+            }
+        }
+
+        // 缺失分片若在 highest 最近一次发送之前发出，说明已经丢失
+        let mut lost = 0;
+        let highest_tx = message.tx[highest];
+        for &(start, end) in &report.missing {
+            for sequence in start..=end.min(highest as u16) {
+                let i = sequence as usize;
+                if !message.acked[i] && !message.queued[i] && message.tx[i] != 0 && message.tx[i] < highest_tx {
+                    message.queued[i] = true;
+                    self.retransmit.push_back((report.message_id, sequence));
+                    lost += 1;
+                }
+            }
+        }
+
+        let done = message.is_done();
+        if done {
+            self.messages.remove(index);
+        }
+        self.in_flight -= acked;
+        if acked > 0 {
+            self.progressed = true;
+            self.stalled_ticks = 0;
+        }
+        self.adapt(acked, lost);
+
+        debug!(
+            message_id = report.message_id,
+            acked,
+            lost,
+            window = self.window,
+            loss_rate = self.loss_rate,
+            "Chunk report processed"
+        );
+        done
+    }
+
+    /// 重传定时器到期
+    ///
+    /// 上个周期内没有任何确认时，重传最早消息的在途分片并缩小窗口；
+    /// 连续 [`MAX_STALLED_TICKS`] 次没有进展则放弃该消息并返回其 ID。
+    pub fn on_tick(&mut self) -> Option<u32> {
+        if self.in_flight == 0 || std::mem::take(&mut self.progressed) {
+            self.stalled_ticks = 0;
+            return None;
+        }
+
+        self.stalled_ticks += 1;
+        self.window = (self.window / 2).max(MIN_SEND_WINDOW);
+        self.loss_rate += (1.0 - self.loss_rate) * LOSS_EWMA_WEIGHT;
+
+        let index = self.messages.iter().position(|m| m.in_flight() > 0)?;
+        if self.stalled_ticks > MAX_STALLED_TICKS {
+            let message = self.messages.remove(index)?;
+            self.in_flight -= message.in_flight();
+            self.retransmit.retain(|(id, _)| *id != message.message_id);
+            self.stalled_ticks = 0;
+            warn!(message_id = message.message_id, "Giving up on unacknowledged BLE message");
+            return Some(message.message_id);
+        }
+
+        let message = &mut self.messages[index];
+        for i in 0..message.next_unsent {
+            if !message.acked[i] && !message.queued[i] {
+                message.queued[i] = true;
+                self.retransmit.push_back((message.message_id, i as u16));
+            }
+        }
+        debug!(
+            message_id = message.message_id,
+            stalled_ticks = self.stalled_ticks,
+            window = self.window,
+            "Retransmission timeout"
+        );
+        None
+    }
+
+    /// 根据本次报告的确认数和丢失数更新丢包率与窗口
+    fn adapt(&mut self, acked: usize, lost: usize) {
+        if acked + lost == 0 {
+            return;
+        }
+        let sample = lost as f64 / (acked + lost) as f64;
+        self.loss_rate += (sample - self.loss_rate) * LOSS_EWMA_WEIGHT;
+
+        if lost > 0 && self.loss_rate > LOSS_DECREASE_THRESHOLD {
+            self.window = (self.window / 2).max(MIN_SEND_WINDOW);
+        } else if lost == 0 && self.loss_rate < LOSS_INCREASE_THRESHOLD {
+            self.window = (self.window + 1).min(MAX_SEND_WINDOW);
+        }
+    }
+
+    /// 当前窗口大小
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// 观测到的丢包率（指数移动平均）
+    pub fn loss_rate(&self) -> f64 {
+        self.loss_rate
+    }
+
+    /// 已发出但未确认的分片数
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    /// 是否没有待确认的消息
+    pub fn is_idle(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+impl Default for SendWindow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for SendWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendWindow")
+            .field("window", &self.window)
+            .field("loss_rate", &self.loss_rate)
+            .field("in_flight", &self.in_flight)
+            .field("messages", &self.messages.len())
+            .field("retransmit", &self.retransmit.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::{ChunkHeader, Chunker, Reassembler, DEFAULT_REASSEMBLE_TIMEOUT};
+    use crate::gatt::CHUNK_HEADER_SIZE;
+
+    fn sequence(chunk: &[u8]) -> u16 {
+        ChunkHeader::from_bytes(chunk).unwrap().sequence_number
+    }
+
+    fn drain(window: &mut SendWindow) -> Vec<u16> {
+        std::iter::from_fn(|| window.next_chunk()).map(|c| sequence(&c)).collect()
+    }
+
+    fn report(next_expected: u16, highest: u16, missing: Vec<(u16, u16)>) -> ChunkReport {
+        ChunkReport {
+            version: ChunkVersion::V1,
+            message_id: 1,
+            next_expected,
+            highest,
+            missing,
+        }
+    }
+
+    // ========================================
+    // ChunkReport Tests
+    // ========================================
+
+    #[test]
+    fn test_report_roundtrip() {
+        let r = report(3, 20, vec![(3, 4), (9, 9), (15, 18)]);
+        let bytes = r.encode(64);
+        assert_eq!(bytes.len(), CHUNK_REPORT_HEADER_SIZE + 12);
+        assert_eq!(ChunkReport::decode(&bytes).unwrap(), r);
+        assert_eq!(r.missing_count(), 7);
+    }
+
+    #[test]
+    fn test_report_v2_roundtrip() {
+        let r = ChunkReport {
+            version: ChunkVersion::V2,
+            message_id: 0x0001_0002,
+            ..report(3, 20, vec![(3, 4), (9, 9)])
+        };
+        let bytes = r.encode(64);
+        assert_eq!(bytes.len(), CHUNK_REPORT_V2_HEADER_SIZE + 8);
+        assert_eq!(ChunkReport::decode(&bytes).unwrap(), r);
+
+        // 没有缺失区间的报告也能按长度区分版本
+        let r = ChunkReport { missing: vec![], ..r };
+        assert_eq!(r.encode(64).len(), CHUNK_REPORT_V2_HEADER_SIZE);
+        assert_eq!(ChunkReport::decode(&r.encode(64)).unwrap(), r);
+        assert_eq!(report(3, 20, vec![]).encode(64).len(), CHUNK_REPORT_HEADER_SIZE);
+    }
+
+    #[test]
+    fn test_report_truncation_lowers_highest() {
+        let r = report(0, 50, vec![(0, 1), (5, 5), (10, 12), (20, 20)]);
+        // MTU 23 的 ATT payload：20 字节，放得下 3 个区间
+        let decoded = ChunkReport::decode(&r.encode(20)).unwrap();
+        assert_eq!(decoded.missing, vec![(0, 1), (5, 5), (10, 12)]);
+        assert_eq!(decoded.highest, 19);
+
+        // 至少保留第一个区间
+        let decoded = ChunkReport::decode(&r.encode(CHUNK_REPORT_HEADER_SIZE)).unwrap();
+        assert_eq!(decoded.missing, vec![(0, 1)]);
+        assert_eq!(decoded.highest, 4);
+    }
+
+    #[test]
+    fn test_report_decode_rejects_invalid() {
+        assert!(ChunkReport::decode(&[1, 0]).is_err());
+
+        let mut bytes = report(0, 9, vec![(2, 3)]).encode(64);
+        bytes.pop();
+        assert!(ChunkReport::decode(&bytes).is_err());
+
+        // 区间超出 highest
+        assert!(ChunkReport::decode(&report(0, 5, vec![(4, 8)]).encode(64)).is_err());
+        // 区间倒序
+        assert!(ChunkReport::decode(&report(0, 9, vec![(6, 7), (2, 3)]).encode(64)).is_err());
+        // 区间在 next_expected 之前
+        assert!(ChunkReport::decode(&report(5, 9, vec![(2, 3)]).encode(64)).is_err());
+    }
+
+    // ========================================
+    // SendWindow Tests
+    // ========================================
+
+    #[test]
+    fn test_window_limits_in_flight() {
+        let mut window = SendWindow::new();
+        window.push(1, Chunker::chunk(&[0u8; 1000], 1, 23).unwrap(), false);
+
+        assert_eq!(drain(&mut window).len(), DEFAULT_SEND_WINDOW);
+        assert_eq!(window.in_flight(), DEFAULT_SEND_WINDOW);
+        assert!(!window.can_accept());
+
+        // 累计确认前 4 个后窗口滑动，且低丢包时窗口增大
+        assert!(!window.on_report(&report(4, 3, vec![])));
+        assert_eq!(window.window(), DEFAULT_SEND_WINDOW + 1);
+        assert_eq!(drain(&mut window), (16..21).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_urgent_messages_bypass_full_window() {
+        let mut window = SendWindow::new();
+        window.push(1, Chunker::chunk(&[0u8; 1000], 1, 23).unwrap(), false);
+        assert_eq!(drain(&mut window).len(), DEFAULT_SEND_WINDOW);
+
+        window.push(2, Chunker::chunk(b"heartbeat", 2, 23).unwrap(), true);
+        let chunk = window.next_chunk().expect("urgent chunk is not window limited");
+        assert_eq!(ChunkHeader::from_bytes(&chunk).unwrap().message_id, 2);
+        assert!(window.next_chunk().is_none());
+        assert_eq!(window.in_flight(), DEFAULT_SEND_WINDOW + 1);
+    }
+
+    #[test]
+    fn test_only_missing_chunks_are_retransmitted() {
+        let mut window = SendWindow::new();
+        window.push(1, Chunker::chunk(&[0u8; 120], 1, 23).unwrap(), false);
+        assert_eq!(drain(&mut window).len(), 10);
+
+        // 2 和 5..=6 丢失
+        window.on_report(&report(2, 9, vec![(2, 2), (5, 6)]));
+        assert_eq!(window.in_flight(), 3);
+        assert_eq!(drain(&mut window), vec![2, 5, 6]);
+
+        // 重传还在途时的旧报告不会触发再次重传
+        window.on_report(&report(2, 9, vec![(2, 2), (5, 6)]));
+        assert!(drain(&mut window).is_empty());
+
+        assert!(window.on_report(&report(10, 9, vec![])));
+        assert!(window.is_idle());
+        assert_eq!(window.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_window_shrinks_under_loss_and_recovers() {
+        let mut window = SendWindow::new();
+        window.push(1, Chunker::chunk(&[0u8; 2400], 1, 23).unwrap(), false);
+
+        for _ in 0..4 {
+            let sent = drain(&mut window);
+            let (first, last) = (sent[0], *sent.last().unwrap());
+            // 每批丢一半
+            window.on_report(&report(first, last, vec![(first, first + sent.len() as u16 / 2 - 1)]));
+        }
+        assert_eq!(window.window(), MIN_SEND_WINDOW);
+        assert!(window.loss_rate() > LOSS_DECREASE_THRESHOLD);
+
+        // 持续无丢包后窗口重新增大
+        for _ in 0..60 {
+            drain(&mut window);
+            let Some(message) = window.messages.front() else { break };
+            let next = message.next_unsent as u16;
+            window.on_report(&report(next, next - 1, vec![]));
+        }
+        assert!(window.window() > MIN_SEND_WINDOW);
+        assert!(window.loss_rate() < LOSS_DECREASE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_tick_retransmits_tail_then_gives_up() {
+        let mut window = SendWindow::new();
+        window.push(7, Chunker::chunk(&[0u8; 30], 7, 23).unwrap(), false);
+        window.push(8, Chunker::chunk(b"next", 8, 23).unwrap(), false);
+        assert_eq!(drain(&mut window).len(), 4);
+
+        // 有进展的周期不算超时
+        window.on_report(&ChunkReport {
+            version: ChunkVersion::V1,
+            message_id: 7,
+            next_expected: 1,
+            highest: 0,
+            missing: vec![],
+        });
+        assert_eq!(window.on_tick(), None);
+        assert!(drain(&mut window).is_empty());
+
+        // 尾部丢失：超时后重传未确认的分片
+        assert_eq!(window.on_tick(), None);
+        assert_eq!(drain(&mut window), vec![1, 2]);
+        assert_eq!(window.window(), DEFAULT_SEND_WINDOW / 2);
+
+        for _ in 1..MAX_STALLED_TICKS {
+            assert_eq!(window.on_tick(), None);
+            drain(&mut window);
+        }
+        assert_eq!(window.on_tick(), Some(7));
+        // 后面的消息不受影响
+        assert_eq!(window.in_flight(), 1);
+        assert!(!window.is_idle());
+    }
+
+    #[test]
+    fn test_lossy_transfer_with_reassembler() {
+        let data: Vec<u8> = (0..3000).map(|i| i as u8).collect();
+        let chunks = Chunker::chunk(&data, 3, 23).unwrap();
+        let total = chunks.len() as u16;
+        let mut window = SendWindow::new();
+        window.push(3, chunks, false);
+        let mut reassembler = Reassembler::new(3, total, DEFAULT_REASSEMBLE_TIMEOUT);
+
+        let mut transmissions = 0;
+        let mut ticks = 0;
+        while !reassembler.is_complete() {
+            let Some(chunk) = window.next_chunk() else {
+                // 窗口空转相当于重传超时
+                ticks += 1;
+                assert_eq!(window.on_tick(), None);
+                continue;
+            };
+            transmissions += 1;
+            // 确定性地丢掉每 7 个中的 1 个
+            if transmissions % 7 == 0 {
+                continue;
+            }
+            let header = ChunkHeader::from_bytes(&chunk).unwrap();
+            reassembler
+                .add_chunk(header, chunk[CHUNK_HEADER_SIZE..].to_vec())
+                .unwrap();
+            if let Some(report) = reassembler.take_report() {
+                let encoded = report.encode(20);
+                window.on_report(&ChunkReport::decode(&encoded).unwrap());
+            }
+        }
+
+        assert!(window.is_idle());
+        // 只重传丢失的分片，而不是整条消息
+        assert!(transmissions < total as usize * 5 / 4, "{} transmissions for {} chunks", transmissions, total);
+        assert!(ticks < 5);
+        assert_eq!(reassembler.assemble().unwrap(), data);
+    }
+}
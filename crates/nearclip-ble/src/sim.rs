@@ -0,0 +1,1355 @@
+//! 模拟 BLE 空中环境
+//!
+//! 在进程内连接任意多个虚拟设备。每个设备都有一个实现 [`BleHardware`] 的
+//! [`SimBleHardware`]，可以直接交给 [`BleController`](crate::BleController)，
+//! 让多个完整的控制器在没有蓝牙硬件的情况下互相发现、连接和传输数据。
+//!
+//! 每个虚拟设备同时是外设和中心，与手机和桌面端一致：
+//! - 广播中的设备会被正在扫描的设备按广播间隔反复发现
+//! - 中心连接外设后，双方分别收到 `Connected` / `CentralConnected`
+//! - 中心写特征、外设通知已订阅的特征，都变成对端的 `DataReceived`
+//! - 配置了 L2CAP PSM 的设备提供 PSM 特征，中心可在其上打开 L2CAP 信道
+//! - 连接优先级、PHY 和数据长度请求按双方能力协商，结果以 `LinkParametersChanged` 通知双方
+//!
+//! 可配置 MTU、延迟、丢包率和 RSSI 曲线，并可注入断开、L2CAP 信道关闭和定点丢包，
+//! 用于在 CI 中测试端到端同步、重连和分片丢失。丢包使用固定种子的随机数，
+//! 时间使用 tokio 时钟，配合 `tokio::time::pause` 可完全复现。
+//!
+//! 事件通过 [`BleHardwareEvent`] 通道返回，交给
+//! [`BleController::handle_hardware_event`](crate::BleController::handle_hardware_event)。
+//!
+//! # 示例
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use nearclip_ble::sim::{SimAir, SimAirConfig, SimDeviceConfig};
+//!
+//! # async fn example() {
+//! let air = SimAir::new(SimAirConfig::new().with_loss_rate(0.05).with_seed(7));
+//! let (phone, phone_events) = air.add_device(SimDeviceConfig::new("phone".into(), "hash-a".into()));
+//! let (laptop, laptop_events) = air.add_device(SimDeviceConfig::new("laptop".into(), "hash-b".into()));
+//!
+//! // ... 用 phone / laptop 分别创建 BleController，并转发各自的事件 ...
+//!
+//! // 注入断开，验证自动重连
+//! air.inject_disconnect(phone.address(), laptop.address(), "supervision timeout");
+//! # }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::debug;
+
+use crate::advert::parse_advertisement;
+use crate::chunk::ChunkVersion;
+use crate::controller::{BleHardware, BleHardwareEvent};
+use crate::link::{BlePhy, ConnectionPriority, LinkParameters, DEFAULT_DATA_LENGTH, MAX_DATA_LENGTH};
+use crate::gatt::{
+    ATT_HEADER_SIZE, CHUNK_VERSION_CHARACTERISTIC_UUID, DATA_ACK_CHARACTERISTIC_UUID,
+    DEFAULT_BLE_MTU, DEVICE_ID_CHARACTERISTIC_UUID, FRAMING_CHARACTERISTIC_UUID, L2CAP_PSM_CHARACTERISTIC_UUID,
+    MUX_FRAMING_VERSION, PUBKEY_HASH_CHARACTERISTIC_UUID,
+};
+
+/// 默认单向延迟
+pub const DEFAULT_SIM_LATENCY: Duration = Duration::from_millis(5);
+
+/// 默认广播间隔（扫描中的设备按此间隔收到发现事件）
+pub const DEFAULT_ADVERTISING_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 默认 ATT MTU（常见的协商结果）
+pub const DEFAULT_SIM_MTU: u16 = 185;
+
+/// RSSI 不高于此值时设备不可达：不会被发现，连接和写入失败
+pub const OUT_OF_RANGE_RSSI: i32 = -100;
+
+// ============================================================================
+// 配置
+// ============================================================================
+
+/// 空中环境配置
+#[derive(Debug, Clone)]
+pub struct SimAirConfig {
+    /// 单向延迟
+    pub latency: Duration,
+    /// 数据包丢失概率（0.0 - 1.0），只作用于特征写入和通知
+    pub loss_rate: f64,
+    /// 广播间隔
+    pub advertising_interval: Duration,
+    /// 丢包随机数种子
+    pub seed: u64,
+}
+
+impl SimAirConfig {
+    /// 创建默认配置：5ms 延迟，不丢包
+    pub fn new() -> Self {
+        Self {
+            latency: DEFAULT_SIM_LATENCY,
+            loss_rate: 0.0,
+            advertising_interval: DEFAULT_ADVERTISING_INTERVAL,
+            seed: 0,
+        }
+    }
+
+    /// 设置单向延迟
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// 设置丢包率
+    pub fn with_loss_rate(mut self, loss_rate: f64) -> Self {
+        self.loss_rate = loss_rate;
+        self
+    }
+
+    /// 设置广播间隔
+    pub fn with_advertising_interval(mut self, interval: Duration) -> Self {
+        self.advertising_interval = interval;
+        self
+    }
+
+    /// 设置丢包随机数种子
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+impl Default for SimAirConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// RSSI 随时间的变化，时间从 [`SimAir`] 创建时算起
+#[derive(Debug, Clone, PartialEq)]
+pub enum RssiCurve {
+    /// 固定值
+    Constant(i32),
+    /// 在 `duration` 内从 `from` 线性变化到 `to`，之后保持 `to`
+    Linear { from: i32, to: i32, duration: Duration },
+    /// 分段常数：`(起始时间, RSSI)`，按时间升序；第一段之前取第一段的值
+    Steps(Vec<(Duration, i32)>),
+}
+
+impl RssiCurve {
+    /// 计算 `elapsed` 时刻的 RSSI
+    pub fn at(&self, elapsed: Duration) -> i32 {
+        match self {
+            Self::Constant(rssi) => *rssi,
+            Self::Linear { from, to, duration } => {
+                if elapsed >= *duration || duration.is_zero() {
+                    return *to;
+                }
+                let progress = elapsed.as_secs_f64() / duration.as_secs_f64();
+                from + ((to - from) as f64 * progress).round() as i32
+            }
+            Self::Steps(steps) => steps
+                .iter()
+                .take_while(|(start, _)| *start <= elapsed)
+                .last()
+                .or(steps.first())
+                .map_or(OUT_OF_RANGE_RSSI, |(_, rssi)| *rssi),
+        }
+    }
+}
+
+/// 虚拟设备配置
+#[derive(Debug, Clone)]
+pub struct SimDeviceConfig {
+    /// DEVICE_ID 特征的值
+    pub device_id: String,
+    /// PUBKEY_HASH 特征的值
+    pub public_key_hash: String,
+    /// 支持的最大 ATT MTU，连接的 MTU 取双方较小值
+    pub mtu: u16,
+    /// 其他设备看到的信号强度
+    pub rssi: RssiCurve,
+    /// 监听 L2CAP 信道的 PSM，`None` 表示不支持 L2CAP
+    pub l2cap_psm: Option<u16>,
+    /// 支持的 PHY，请求的 PHY 双方都支持才生效
+    pub phys: Vec<BlePhy>,
+    /// 支持的最大链路层数据长度，协商取双方较小值
+    pub max_data_length: u16,
+    /// 能接收的最高分片头部版本，v1 表示旧版本设备（没有 CHUNK_VERSION 特征）
+    pub chunk_version: ChunkVersion,
+    /// 是否支持多路复用帧，`false` 表示旧版本设备（没有 FRAMING 特征）
+    pub mux_framing: bool,
+}
+
+impl SimDeviceConfig {
+    /// 创建配置：默认 MTU，RSSI 恒为 -50，支持 LE 1M/2M、DLE、v2 分片头部和多路复用帧
+    pub fn new(device_id: String, public_key_hash: String) -> Self {
+        Self {
+            device_id,
+            public_key_hash,
+            mtu: DEFAULT_SIM_MTU,
+            rssi: RssiCurve::Constant(-50),
+            l2cap_psm: None,
+            phys: vec![BlePhy::Le1M, BlePhy::Le2M],
+            max_data_length: MAX_DATA_LENGTH,
+            chunk_version: ChunkVersion::V2,
+            mux_framing: true,
+        }
+    }
+
+    /// 设置 MTU
+    pub fn with_mtu(mut self, mtu: u16) -> Self {
+        self.mtu = mtu;
+        self
+    }
+
+    /// 设置 RSSI 曲线
+    pub fn with_rssi(mut self, rssi: RssiCurve) -> Self {
+        self.rssi = rssi;
+        self
+    }
+
+    /// 在指定 PSM 上监听 L2CAP 信道
+    pub fn with_l2cap_psm(mut self, psm: u16) -> Self {
+        self.l2cap_psm = Some(psm);
+        self
+    }
+
+    /// 设置支持的 PHY
+    pub fn with_phys(mut self, phys: Vec<BlePhy>) -> Self {
+        self.phys = phys;
+        self
+    }
+
+    /// 设置支持的最大数据长度（27 表示不支持 DLE）
+    pub fn with_max_data_length(mut self, max_data_length: u16) -> Self {
+        self.max_data_length = max_data_length;
+        self
+    }
+
+    /// 设置能接收的最高分片头部版本
+    pub fn with_chunk_version(mut self, version: ChunkVersion) -> Self {
+        self.chunk_version = version;
+        self
+    }
+
+    /// 设置是否支持多路复用帧
+    pub fn with_mux_framing(mut self, supported: bool) -> Self {
+        self.mux_framing = supported;
+        self
+    }
+}
+
+// ============================================================================
+// 空中环境
+// ============================================================================
+
+/// 待投递的事件
+struct Delivery {
+    due: Instant,
+    /// 数据事件所属的连接，投递时连接已断开则丢弃
+    link: Option<(LinkKey, u64)>,
+    event: BleHardwareEvent,
+}
+
+/// 连接方向：中心 -> 外设
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct LinkKey {
+    central: String,
+    peripheral: String,
+}
+
+struct Link {
+    epoch: u64,
+    /// 连接参数，MTU 取双方较小值，其余从默认值开始协商
+    parameters: LinkParameters,
+    /// 中心订阅的外设特征
+    subscriptions: HashSet<String>,
+    /// L2CAP 信道是否打开
+    l2cap: bool,
+}
+
+struct Node {
+    config: SimDeviceConfig,
+    inbox: mpsc::UnboundedSender<Delivery>,
+    advertising: Option<Vec<u8>>,
+    /// 每次开始扫描递增，旧的扫描任务据此退出
+    scan_generation: u64,
+    scanning: bool,
+}
+
+struct AirState {
+    nodes: HashMap<String, Node>,
+    links: HashMap<LinkKey, Link>,
+    latency: Duration,
+    loss_rate: f64,
+    /// 定点丢弃接下来的 N 个数据包
+    drop_next: usize,
+    rng: StdRng,
+    next_address: u16,
+    next_epoch: u64,
+}
+
+impl AirState {
+    /// a 与 b 之间的连接，优先 a 作为中心的那条
+    fn link_between(&self, a: &str, b: &str) -> Option<LinkKey> {
+        let as_central = LinkKey {
+            central: a.to_string(),
+            peripheral: b.to_string(),
+        };
+        if self.links.contains_key(&as_central) {
+            return Some(as_central);
+        }
+        let as_peripheral = LinkKey {
+            central: b.to_string(),
+            peripheral: a.to_string(),
+        };
+        self.links.contains_key(&as_peripheral).then_some(as_peripheral)
+    }
+
+    fn send(&self, to: &str, due: Instant, link: Option<(LinkKey, u64)>, event: BleHardwareEvent) {
+        if let Some(node) = self.nodes.get(to) {
+            let _ = node.inbox.send(Delivery { due, link, event });
+        }
+    }
+
+    /// 断开 a 与 b 之间的所有连接，通知双方
+    fn drop_links(&mut self, a: &str, b: &str, due: Instant, reason: &str) -> bool {
+        let mut dropped = false;
+        for (central, peripheral) in [(a, b), (b, a)] {
+            let key = LinkKey {
+                central: central.to_string(),
+                peripheral: peripheral.to_string(),
+            };
+            if let Some(link) = self.links.remove(&key) {
+                dropped = true;
+                for (to, from) in [(central, peripheral), (peripheral, central)] {
+                    if link.l2cap {
+                        self.send(
+                            to,
+                            due,
+                            None,
+                            BleHardwareEvent::L2capClosed {
+                                peripheral_id: from.to_string(),
+                                reason: reason.to_string(),
+                            },
+                        );
+                    }
+                    self.send(
+                        to,
+                        due,
+                        None,
+                        BleHardwareEvent::Disconnected {
+                            peripheral_id: from.to_string(),
+                            reason: reason.to_string(),
+                        },
+                    );
+                }
+            }
+        }
+        dropped
+    }
+}
+
+/// 模拟的 BLE 空中环境
+///
+/// 需要在 tokio 运行时中使用：每个设备有一个按延迟投递事件的后台任务。
+pub struct SimAir {
+    started: Instant,
+    advertising_interval: Duration,
+    state: Mutex<AirState>,
+}
+
+impl SimAir {
+    /// 创建空中环境
+    pub fn new(config: SimAirConfig) -> Arc<Self> {
+        Arc::new(Self {
+            started: Instant::now(),
+            advertising_interval: config.advertising_interval,
+            state: Mutex::new(AirState {
+                nodes: HashMap::new(),
+                links: HashMap::new(),
+                latency: config.latency,
+                loss_rate: config.loss_rate,
+                drop_next: 0,
+                rng: StdRng::seed_from_u64(config.seed),
+                next_address: 1,
+                next_epoch: 1,
+            }),
+        })
+    }
+
+    fn state(&self) -> MutexGuard<'_, AirState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// 加入一个虚拟设备
+    ///
+    /// 返回设备的硬件接口和平台事件通道。设备地址形如 `02:00:00:00:00:01`，
+    /// 即其他设备在 `BleHardware` 调用中使用的 `peripheral_id`。
+    pub fn add_device(
+        self: &Arc<Self>,
+        config: SimDeviceConfig,
+    ) -> (SimBleHardware, mpsc::UnboundedReceiver<BleHardwareEvent>) {
+        let (inbox_tx, inbox_rx) = mpsc::unbounded_channel();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
+
+        let address = {
+            let mut state = self.state();
+            let n = state.next_address;
+            state.next_address += 1;
+            let address = format!("02:00:00:00:{:02X}:{:02X}", n >> 8, n & 0xff);
+            state.nodes.insert(
+                address.clone(),
+                Node {
+                    config,
+                    inbox: inbox_tx,
+                    advertising: None,
+                    scan_generation: 0,
+                    scanning: false,
+                },
+            );
+            address
+        };
+        tokio::spawn(deliver(Arc::downgrade(self), inbox_rx, event_tx));
+
+        (
+            SimBleHardware {
+                air: self.clone(),
+                address,
+            },
+            event_rx,
+        )
+    }
+
+    /// 移除设备（模拟关机或离开），其所有连接断开
+    pub fn remove_device(&self, address: &str) {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        let peers: Vec<String> = state
+            .links
+            .keys()
+            .filter_map(|key| {
+                if key.central == address {
+                    Some(key.peripheral.clone())
+                } else if key.peripheral == address {
+                    Some(key.central.clone())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        for peer in peers {
+            state.drop_links(address, &peer, due, "device removed");
+        }
+        state.nodes.remove(address);
+    }
+
+    /// 注入断开：两个设备之间的所有连接立即断开，双方收到 `Disconnected`
+    ///
+    /// 返回是否存在连接。
+    pub fn inject_disconnect(&self, a: &str, b: &str, reason: &str) -> bool {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        state.drop_links(a, b, due, reason)
+    }
+
+    /// 注入 L2CAP 信道关闭：连接保留，双方收到 `L2capClosed`
+    ///
+    /// 返回是否存在打开的信道。
+    pub fn inject_l2cap_close(&self, a: &str, b: &str, reason: &str) -> bool {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        let Some(key) = state.link_between(a, b) else { return false };
+        let Some(link) = state.links.get_mut(&key) else { return false };
+        if !std::mem::take(&mut link.l2cap) {
+            return false;
+        }
+        for (to, from) in [(a, b), (b, a)] {
+            state.send(
+                to,
+                due,
+                None,
+                BleHardwareEvent::L2capClosed {
+                    peripheral_id: from.to_string(),
+                    reason: reason.to_string(),
+                },
+            );
+        }
+        true
+    }
+
+    /// 修改单向延迟，影响之后发送的事件
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// 修改丢包率
+    pub fn set_loss_rate(&self, loss_rate: f64) {
+        self.state().loss_rate = loss_rate;
+    }
+
+    /// 丢弃接下来的 `count` 个数据包（特征写入或通知），与丢包率无关
+    pub fn drop_next(&self, count: usize) {
+        self.state().drop_next += count;
+    }
+
+    /// 修改设备的 RSSI 曲线
+    pub fn set_rssi(&self, address: &str, rssi: RssiCurve) {
+        if let Some(node) = self.state().nodes.get_mut(address) {
+            node.config.rssi = rssi;
+        }
+    }
+
+    /// 设备当前的 RSSI
+    pub fn rssi(&self, address: &str) -> Option<i32> {
+        let elapsed = self.started.elapsed();
+        self.state()
+            .nodes
+            .get(address)
+            .map(|node| node.config.rssi.at(elapsed))
+    }
+
+    /// 设备当前广播的服务数据
+    pub fn advertisement(&self, address: &str) -> Option<Vec<u8>> {
+        self.state()
+            .nodes
+            .get(address)
+            .and_then(|node| node.advertising.clone())
+    }
+
+    fn in_range(&self, node: &Node) -> bool {
+        node.config.rssi.at(self.started.elapsed()) > OUT_OF_RANGE_RSSI
+    }
+
+    fn link_alive(&self, key: &LinkKey, epoch: u64) -> bool {
+        self.state()
+            .links
+            .get(key)
+            .is_some_and(|link| link.epoch == epoch)
+    }
+
+    // ========== 设备操作 ==========
+
+    fn start_scan(self: &Arc<Self>, address: &str) {
+        let generation = {
+            let mut state = self.state();
+            let Some(node) = state.nodes.get_mut(address) else { return };
+            if node.scanning {
+                return;
+            }
+            node.scanning = true;
+            node.scan_generation += 1;
+            node.scan_generation
+        };
+        tokio::spawn(scan(Arc::downgrade(self), address.to_string(), generation));
+    }
+
+    fn stop_scan(&self, address: &str) {
+        if let Some(node) = self.state().nodes.get_mut(address) {
+            node.scanning = false;
+        }
+    }
+
+    /// 一轮广播：向扫描者报告所有可达的广播设备
+    ///
+    /// 携带身份令牌的广播原样上报，由控制器解析；旧格式的广播相当于
+    /// 连接读取特征后上报设备 ID。
+    fn advertising_round(&self, scanner: &str, generation: u64) -> bool {
+        let elapsed = self.started.elapsed();
+        let state = self.state();
+        match state.nodes.get(scanner) {
+            Some(node) if node.scanning && node.scan_generation == generation => {}
+            _ => return false,
+        }
+        let due = Instant::now() + state.latency;
+        for (address, node) in &state.nodes {
+            let Some(service_data) = node.advertising.as_ref().filter(|_| address != scanner) else {
+                continue;
+            };
+            let rssi = node.config.rssi.at(elapsed);
+            if rssi <= OUT_OF_RANGE_RSSI {
+                continue;
+            }
+            let event = if parse_advertisement(service_data).is_some() {
+                BleHardwareEvent::AdvertisementReceived {
+                    peripheral_id: address.clone(),
+                    service_data: service_data.clone(),
+                    rssi,
+                }
+            } else {
+                BleHardwareEvent::DeviceDiscovered {
+                    peripheral_id: address.clone(),
+                    device_id: node.config.device_id.clone(),
+                    public_key_hash: node.config.public_key_hash.clone(),
+                    rssi,
+                }
+            };
+            state.send(scanner, due, None, event);
+        }
+        true
+    }
+
+    fn connect(&self, central: &str, peripheral: &str) {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        let key = LinkKey {
+            central: central.to_string(),
+            peripheral: peripheral.to_string(),
+        };
+        if state.links.contains_key(&key) {
+            state.send(
+                central,
+                due,
+                None,
+                BleHardwareEvent::Connected {
+                    peripheral_id: peripheral.to_string(),
+                },
+            );
+            return;
+        }
+
+        let mtu = match (state.nodes.get(central), state.nodes.get(peripheral)) {
+            (Some(c), Some(p)) if p.advertising.is_some() && self.in_range(p) => c.config.mtu.min(p.config.mtu),
+            _ => {
+                state.send(
+                    central,
+                    due,
+                    None,
+                    BleHardwareEvent::Disconnected {
+                        peripheral_id: peripheral.to_string(),
+                        reason: "peripheral unreachable".to_string(),
+                    },
+                );
+                return;
+            }
+        };
+
+        let epoch = state.next_epoch;
+        state.next_epoch += 1;
+        state.links.insert(
+            key,
+            Link {
+                epoch,
+                parameters: LinkParameters::new().with_mtu(mtu),
+                subscriptions: HashSet::new(),
+                l2cap: false,
+            },
+        );
+        debug!(central = %central, peripheral = %peripheral, mtu, "Simulated link established");
+        state.send(
+            central,
+            due,
+            None,
+            BleHardwareEvent::Connected {
+                peripheral_id: peripheral.to_string(),
+            },
+        );
+        state.send(
+            peripheral,
+            due,
+            None,
+            BleHardwareEvent::CentralConnected {
+                peripheral_id: central.to_string(),
+            },
+        );
+    }
+
+    fn disconnect(&self, from: &str, peer: &str) {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        state.drop_links(from, peer, due, "local disconnect");
+    }
+
+    fn read(&self, central: &str, peripheral: &str, char_uuid: &str) -> Result<Vec<u8>, String> {
+        let state = self.state();
+        let key = LinkKey {
+            central: central.to_string(),
+            peripheral: peripheral.to_string(),
+        };
+        if !state.links.contains_key(&key) {
+            return Err(format!("{} is not connected", peripheral));
+        }
+        let node = state
+            .nodes
+            .get(peripheral)
+            .ok_or_else(|| format!("{} is gone", peripheral))?;
+
+        if char_uuid == DEVICE_ID_CHARACTERISTIC_UUID.to_string() {
+            Ok(node.config.device_id.as_bytes().to_vec())
+        } else if char_uuid == PUBKEY_HASH_CHARACTERISTIC_UUID.to_string() {
+            Ok(node.config.public_key_hash.as_bytes().to_vec())
+        } else if char_uuid == DATA_ACK_CHARACTERISTIC_UUID.to_string() {
+            Ok(Vec::new())
+        } else if char_uuid == L2CAP_PSM_CHARACTERISTIC_UUID.to_string() {
+            // 不支持 L2CAP 的外设没有此特征
+            node.config
+                .l2cap_psm
+                .map(|psm| psm.to_le_bytes().to_vec())
+                .ok_or_else(|| format!("Characteristic {} not found", char_uuid))
+        } else if char_uuid == CHUNK_VERSION_CHARACTERISTIC_UUID.to_string() {
+            // 只支持 v1 的旧版本设备没有此特征
+            match node.config.chunk_version {
+                ChunkVersion::V1 => Err(format!("Characteristic {} not found", char_uuid)),
+                version => Ok(vec![version.as_u8()]),
+            }
+        } else if char_uuid == FRAMING_CHARACTERISTIC_UUID.to_string() {
+            // 不支持多路复用帧的旧版本设备没有此特征
+            if node.config.mux_framing {
+                Ok(vec![MUX_FRAMING_VERSION])
+            } else {
+                Err(format!("Characteristic {} not found", char_uuid))
+            }
+        } else {
+            Err(format!("Characteristic {} is not readable", char_uuid))
+        }
+    }
+
+    fn write(&self, from: &str, to: &str, char_uuid: &str, data: &[u8]) -> Result<(), String> {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        let key = state
+            .link_between(from, to)
+            .ok_or_else(|| format!("{} is not connected", to))?;
+
+        let reachable = [from, to]
+            .iter()
+            .all(|address| state.nodes.get(*address).is_some_and(|node| self.in_range(node)));
+        if !reachable {
+            state.drop_links(from, to, due, "out of range");
+            return Err(format!("{} is out of range", to));
+        }
+
+        let link = &state.links[&key];
+        let mtu = link.parameters.mtu;
+        if data.len() + ATT_HEADER_SIZE > mtu as usize {
+            return Err(format!(
+                "{} bytes exceeds MTU {} (max payload {})",
+                data.len(),
+                mtu,
+                mtu as usize - ATT_HEADER_SIZE
+            ));
+        }
+        // 外设只能通知中心已订阅的特征
+        if key.peripheral == from && !link.subscriptions.contains(char_uuid) {
+            return Err(format!("{} has not subscribed to {}", to, char_uuid));
+        }
+        let epoch = link.epoch;
+
+        if state.drop_next > 0 {
+            state.drop_next -= 1;
+            debug!(from = %from, to = %to, "Simulated packet dropped");
+            return Ok(());
+        }
+        let loss_rate = state.loss_rate.clamp(0.0, 1.0);
+        if loss_rate > 0.0 && state.rng.gen_bool(loss_rate) {
+            debug!(from = %from, to = %to, "Simulated packet lost");
+            return Ok(());
+        }
+
+        state.send(
+            to,
+            due,
+            Some((key, epoch)),
+            BleHardwareEvent::DataReceived {
+                peripheral_id: from.to_string(),
+                char_uuid: char_uuid.to_string(),
+                data: data.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+    fn subscribe(&self, central: &str, peripheral: &str, char_uuid: &str) -> Result<(), String> {
+        let key = LinkKey {
+            central: central.to_string(),
+            peripheral: peripheral.to_string(),
+        };
+        self.state()
+            .links
+            .get_mut(&key)
+            .map(|link| {
+                link.subscriptions.insert(char_uuid.to_string());
+            })
+            .ok_or_else(|| format!("{} is not connected", peripheral))
+    }
+
+    /// 中心在外设的 PSM 上打开 L2CAP 信道，结果异步通知双方
+    fn open_l2cap(&self, central: &str, peripheral: &str, psm: u16) -> Result<(), String> {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        let key = LinkKey {
+            central: central.to_string(),
+            peripheral: peripheral.to_string(),
+        };
+        let listening = state
+            .nodes
+            .get(peripheral)
+            .is_some_and(|node| node.config.l2cap_psm == Some(psm));
+        let link = state
+            .links
+            .get_mut(&key)
+            .ok_or_else(|| format!("{} is not connected", peripheral))?;
+
+        if !listening {
+            state.send(
+                central,
+                due,
+                None,
+                BleHardwareEvent::L2capClosed {
+                    peripheral_id: peripheral.to_string(),
+                    reason: format!("PSM {:#06x} refused", psm),
+                },
+            );
+            return Ok(());
+        }
+        link.l2cap = true;
+        let epoch = link.epoch;
+        debug!(central = %central, peripheral = %peripheral, psm, "Simulated L2CAP channel opened");
+        for (to, from) in [(central, peripheral), (peripheral, central)] {
+            state.send(
+                to,
+                due,
+                Some((key.clone(), epoch)),
+                BleHardwareEvent::L2capOpened {
+                    peripheral_id: from.to_string(),
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// 在 L2CAP 信道上发送数据
+    ///
+    /// 信道在链路层可靠，不受 MTU、丢包率和 `drop_next` 影响。
+    fn write_l2cap(&self, from: &str, to: &str, data: &[u8]) -> Result<(), String> {
+        let mut state = self.state();
+        let due = Instant::now() + state.latency;
+        let key = state
+            .link_between(from, to)
+            .ok_or_else(|| format!("{} is not connected", to))?;
+
+        let reachable = [from, to]
+            .iter()
+            .all(|address| state.nodes.get(*address).is_some_and(|node| self.in_range(node)));
+        if !reachable {
+            state.drop_links(from, to, due, "out of range");
+            return Err(format!("{} is out of range", to));
+        }
+
+        let link = &state.links[&key];
+        if !link.l2cap {
+            return Err(format!("No L2CAP channel to {}", to));
+        }
+        let epoch = link.epoch;
+        state.send(
+            to,
+            due,
+            Some((key, epoch)),
+            BleHardwareEvent::L2capDataReceived {
+                peripheral_id: from.to_string(),
+                data: data.to_vec(),
+            },
+        );
+        Ok(())
+    }
+
+
+    fn set_advertising(&self, address: &str, service_data: Option<Vec<u8>>) {
+        if let Some(node) = self.state().nodes.get_mut(address) {
+            node.advertising = service_data;
+        }
+    }
+
+    fn is_connected(&self, a: &str, b: &str) -> bool {
+        self.state().link_between(a, b).is_some()
+    }
+
+    fn link_parameters(&self, a: &str, b: &str) -> LinkParameters {
+        let state = self.state();
+        state
+            .link_between(a, b)
+            .and_then(|key| state.links.get(&key))
+            .map_or_else(
+                || LinkParameters::new().with_mtu(DEFAULT_BLE_MTU as u16),
+                |link| link.parameters,
+            )
+    }
+
+    /// 按双方配置更新连接参数，并把协商结果通知双方
+    fn update_link_parameters(
+        &self,
+        a: &str,
+        b: &str,
+        update: impl FnOnce(&SimDeviceConfig, &SimDeviceConfig, &mut LinkParameters) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let mut guard = self.state();
+        let state = &mut *guard;
+        let due = Instant::now() + state.latency;
+        let key = state
+            .link_between(a, b)
+            .ok_or_else(|| format!("{} is not connected", b))?;
+        let (Some(node_a), Some(node_b), Some(link)) =
+            (state.nodes.get(a), state.nodes.get(b), state.links.get_mut(&key))
+        else {
+            return Err(format!("{} is not connected", b));
+        };
+        update(&node_a.config, &node_b.config, &mut link.parameters)?;
+
+        let (parameters, epoch) = (link.parameters, link.epoch);
+        debug!(a = %a, b = %b, ?parameters, "Simulated link parameters changed");
+        for (to, from) in [(a, b), (b, a)] {
+            state.send(
+                to,
+                due,
+                Some((key.clone(), epoch)),
+                BleHardwareEvent::LinkParametersChanged {
+                    peripheral_id: from.to_string(),
+                    parameters,
+                },
+            );
+        }
+        Ok(())
+    }
+}
+
+/// 按延迟把事件投递给设备，保持发送顺序
+async fn deliver(
+    air: Weak<SimAir>,
+    mut inbox: mpsc::UnboundedReceiver<Delivery>,
+    events: mpsc::UnboundedSender<BleHardwareEvent>,
+) {
+    while let Some(delivery) = inbox.recv().await {
+        tokio::time::sleep_until(delivery.due).await;
+        if let Some((key, epoch)) = &delivery.link {
+            let Some(air) = air.upgrade() else { break };
+            // 连接在传输途中断开，数据包丢失
+            if !air.link_alive(key, *epoch) {
+                continue;
+            }
+        }
+        if events.send(delivery.event).is_err() {
+            break;
+        }
+    }
+}
+
+/// 扫描任务：每个广播间隔报告一轮
+async fn scan(air: Weak<SimAir>, scanner: String, generation: u64) {
+    loop {
+        let interval = {
+            let Some(air) = air.upgrade() else { break };
+            if !air.advertising_round(&scanner, generation) {
+                break;
+            }
+            air.advertising_interval
+        };
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// ============================================================================
+// SimBleHardware
+// ============================================================================
+
+/// 虚拟设备的 [`BleHardware`] 实现
+pub struct SimBleHardware {
+    air: Arc<SimAir>,
+    address: String,
+}
+
+impl SimBleHardware {
+    /// 本设备地址
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// 所在的空中环境
+    pub fn air(&self) -> &Arc<SimAir> {
+        &self.air
+    }
+}
+
+impl BleHardware for SimBleHardware {
+    fn start_scan(&self) {
+        self.air.start_scan(&self.address);
+    }
+
+    fn stop_scan(&self) {
+        self.air.stop_scan(&self.address);
+    }
+
+    fn connect(&self, peripheral_id: &str) {
+        self.air.connect(&self.address, peripheral_id);
+    }
+
+    fn disconnect(&self, peripheral_id: &str) {
+        self.air.disconnect(&self.address, peripheral_id);
+    }
+
+    fn read_characteristic(&self, peripheral_id: &str, char_uuid: &str) -> Result<Vec<u8>, String> {
+        self.air.read(&self.address, peripheral_id, &char_uuid.to_lowercase())
+    }
+
+    fn write_characteristic(&self, peripheral_id: &str, char_uuid: &str, data: &[u8]) -> Result<(), String> {
+        self.air.write(&self.address, peripheral_id, &char_uuid.to_lowercase(), data)
+    }
+
+    fn subscribe_characteristic(&self, peripheral_id: &str, char_uuid: &str) -> Result<(), String> {
+        self.air.subscribe(&self.address, peripheral_id, &char_uuid.to_lowercase())
+    }
+
+    fn start_advertising(&self, service_data: &[u8]) {
+        self.air.set_advertising(&self.address, Some(service_data.to_vec()));
+    }
+
+    fn stop_advertising(&self) {
+        self.air.set_advertising(&self.address, None);
+    }
+
+    fn is_connected(&self, peripheral_id: &str) -> bool {
+        self.air.is_connected(&self.address, peripheral_id)
+    }
+
+    fn get_mtu(&self, peripheral_id: &str) -> u16 {
+        self.air.link_parameters(&self.address, peripheral_id).mtu
+    }
+
+    fn open_l2cap_channel(&self, peripheral_id: &str, psm: u16) -> Result<(), String> {
+        self.air.open_l2cap(&self.address, peripheral_id, psm)
+    }
+
+    fn write_l2cap(&self, peripheral_id: &str, data: &[u8]) -> Result<(), String> {
+        self.air.write_l2cap(&self.address, peripheral_id, data)
+    }
+
+    fn close_l2cap_channel(&self, peripheral_id: &str) {
+        self.air.inject_l2cap_close(&self.address, peripheral_id, "local close");
+    }
+
+    fn request_connection_priority(
+        &self,
+        peripheral_id: &str,
+        priority: ConnectionPriority,
+    ) -> Result<(), String> {
+        // 模拟的对端总是接受范围内最短的间隔
+        self.air.update_link_parameters(&self.address, peripheral_id, |_, _, parameters| {
+            parameters.connection_interval = priority.interval_range().0;
+            Ok(())
+        })
+    }
+
+    fn request_phy(&self, peripheral_id: &str, phy: BlePhy) -> Result<(), String> {
+        self.air.update_link_parameters(&self.address, peripheral_id, |local, remote, parameters| {
+            if !local.phys.contains(&phy) || !remote.phys.contains(&phy) {
+                return Err(format!("{:?} is not supported by both sides", phy));
+            }
+            parameters.phy = phy;
+            Ok(())
+        })
+    }
+
+    fn request_data_length(&self, peripheral_id: &str, octets: u16) -> Result<(), String> {
+        self.air.update_link_parameters(&self.address, peripheral_id, |local, remote, parameters| {
+            parameters.data_length = octets
+                .min(local.max_data_length)
+                .min(remote.max_data_length)
+                .clamp(DEFAULT_DATA_LENGTH, MAX_DATA_LENGTH);
+            Ok(())
+        })
+    }
+
+    fn get_link_parameters(&self, peripheral_id: &str) -> LinkParameters {
+        self.air.link_parameters(&self.address, peripheral_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gatt::DATA_TRANSFER_CHARACTERISTIC_UUID;
+
+    fn device(id: &str) -> SimDeviceConfig {
+        SimDeviceConfig::new(id.to_string(), format!("{}-hash", id))
+    }
+
+    async fn next(events: &mut mpsc::UnboundedReceiver<BleHardwareEvent>) -> BleHardwareEvent {
+        tokio::time::timeout(Duration::from_secs(1), events.recv())
+            .await
+            .expect("event should arrive")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_rssi_curves() {
+        assert_eq!(RssiCurve::Constant(-40).at(Duration::from_secs(9)), -40);
+
+        let linear = RssiCurve::Linear {
+            from: -90,
+            to: -50,
+            duration: Duration::from_secs(4),
+        };
+        assert_eq!(linear.at(Duration::ZERO), -90);
+        assert_eq!(linear.at(Duration::from_secs(1)), -80);
+        assert_eq!(linear.at(Duration::from_secs(10)), -50);
+
+        let steps = RssiCurve::Steps(vec![
+            (Duration::from_secs(1), -70),
+            (Duration::from_secs(3), -110),
+        ]);
+        assert_eq!(steps.at(Duration::ZERO), -70);
+        assert_eq!(steps.at(Duration::from_secs(2)), -70);
+        assert_eq!(steps.at(Duration::from_secs(3)), -110);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_connect_and_write() {
+        let air = SimAir::new(SimAirConfig::new());
+        let (a, mut a_events) = air.add_device(device("a"));
+        let (b, mut b_events) = air.add_device(device("b").with_mtu(64));
+        b.start_advertising(&[1]);
+
+        a.connect(b.address());
+        assert_eq!(
+            next(&mut a_events).await,
+            BleHardwareEvent::Connected {
+                peripheral_id: b.address().to_string()
+            }
+        );
+        assert_eq!(
+            next(&mut b_events).await,
+            BleHardwareEvent::CentralConnected {
+                peripheral_id: a.address().to_string()
+            }
+        );
+        assert_eq!(a.get_mtu(b.address()), 64);
+        assert_eq!(
+            a.read_characteristic(b.address(), &DEVICE_ID_CHARACTERISTIC_UUID.to_string()),
+            Ok(b"b".to_vec())
+        );
+        assert_eq!(
+            a.read_characteristic(b.address(), &CHUNK_VERSION_CHARACTERISTIC_UUID.to_string()),
+            Ok(vec![2])
+        );
+        assert_eq!(
+            a.read_characteristic(b.address(), &FRAMING_CHARACTERISTIC_UUID.to_string()),
+            Ok(vec![MUX_FRAMING_VERSION])
+        );
+
+        let data_uuid = DATA_TRANSFER_CHARACTERISTIC_UUID.to_string();
+        a.write_characteristic(b.address(), &data_uuid, b"hi").unwrap();
+        assert!(a.write_characteristic(b.address(), &data_uuid, &[0; 62]).is_err());
+        assert_eq!(
+            next(&mut b_events).await,
+            BleHardwareEvent::DataReceived {
+                peripheral_id: a.address().to_string(),
+                char_uuid: data_uuid.clone(),
+                data: b"hi".to_vec(),
+            }
+        );
+
+        // 外设通知需要中心先订阅
+        assert!(b.write_characteristic(a.address(), &data_uuid, b"yo").is_err());
+        a.subscribe_characteristic(b.address(), &data_uuid).unwrap();
+        b.write_characteristic(a.address(), &data_uuid, b"yo").unwrap();
+        assert!(matches!(next(&mut a_events).await, BleHardwareEvent::DataReceived { data, .. } if data == b"yo"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_connect_fails_when_not_advertising_or_out_of_range() {
+        let air = SimAir::new(SimAirConfig::new());
+        let (a, mut a_events) = air.add_device(device("a"));
+        let (b, _b_events) = air.add_device(device("b").with_rssi(RssiCurve::Constant(-120)));
+
+        a.connect(b.address());
+        assert!(matches!(next(&mut a_events).await, BleHardwareEvent::Disconnected { .. }));
+
+        b.start_advertising(&[]);
+        a.connect(b.address());
+        assert!(matches!(next(&mut a_events).await, BleHardwareEvent::Disconnected { .. }));
+        assert!(!a.is_connected(b.address()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_scan_reports_identity_token_without_device_id() {
+        let air = SimAir::new(SimAirConfig::new());
+        let (a, mut a_events) = air.add_device(device("a"));
+        let (b, _b_events) = air.add_device(device("b"));
+        let payload = crate::advert::AdvertisementKey::derive(b"b").payload("b", 1);
+        b.start_advertising(&payload);
+
+        a.start_scan();
+        assert_eq!(
+            next(&mut a_events).await,
+            BleHardwareEvent::AdvertisementReceived {
+                peripheral_id: b.address().to_string(),
+                service_data: payload,
+                rssi: -50,
+            }
+        );
+        a.stop_scan();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_inject_disconnect_notifies_both_sides() {
+        let air = SimAir::new(SimAirConfig::new());
+        let (a, mut a_events) = air.add_device(device("a"));
+        let (b, mut b_events) = air.add_device(device("b"));
+        b.start_advertising(&[]);
+        a.connect(b.address());
+        next(&mut a_events).await;
+        next(&mut b_events).await;
+
+        assert!(air.inject_disconnect(a.address(), b.address(), "supervision timeout"));
+        assert!(!a.is_connected(b.address()));
+        assert_eq!(
+            next(&mut a_events).await,
+            BleHardwareEvent::Disconnected {
+                peripheral_id: b.address().to_string(),
+                reason: "supervision timeout".to_string(),
+            }
+        );
+        assert!(matches!(next(&mut b_events).await, BleHardwareEvent::Disconnected { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_l2cap_channel() {
+        let air = SimAir::new(SimAirConfig::new().with_loss_rate(1.0));
+        let (a, mut a_events) = air.add_device(device("a"));
+        let (b, mut b_events) = air.add_device(device("b").with_l2cap_psm(0x0081));
+        let (c, _c_events) = air.add_device(device("c"));
+        b.start_advertising(&[]);
+        c.start_advertising(&[]);
+        a.connect(b.address());
+        a.connect(c.address());
+        next(&mut a_events).await;
+        next(&mut a_events).await;
+        next(&mut b_events).await;
+
+        let psm_uuid = L2CAP_PSM_CHARACTERISTIC_UUID.to_string();
+        assert_eq!(a.read_characteristic(b.address(), &psm_uuid), Ok(vec![0x81, 0x00]));
+        assert!(a.read_characteristic(c.address(), &psm_uuid).is_err());
+
+        // 错误的 PSM 被拒绝
+        assert!(a.write_l2cap(b.address(), b"early").is_err());
+        a.open_l2cap_channel(b.address(), 0x0083).unwrap();
+        assert!(matches!(next(&mut a_events).await, BleHardwareEvent::L2capClosed { .. }));
+
+        a.open_l2cap_channel(b.address(), 0x0081).unwrap();
+        assert_eq!(
+            next(&mut a_events).await,
+            BleHardwareEvent::L2capOpened {
+                peripheral_id: b.address().to_string()
+            }
+        );
+        assert!(matches!(next(&mut b_events).await, BleHardwareEvent::L2capOpened { .. }));
+
+        // 不受 MTU 和丢包率限制，双向可用
+        a.write_l2cap(b.address(), &[7; 1000]).unwrap();
+        assert!(matches!(
+            next(&mut b_events).await,
+            BleHardwareEvent::L2capDataReceived { data, .. } if data == [7; 1000]
+        ));
+        b.write_l2cap(a.address(), b"back").unwrap();
+        assert!(matches!(
+            next(&mut a_events).await,
+            BleHardwareEvent::L2capDataReceived { data, .. } if data == b"back"
+        ));
+
+        // 链路断开时信道先关闭
+        air.inject_disconnect(a.address(), b.address(), "gone");
+        assert!(matches!(next(&mut a_events).await, BleHardwareEvent::L2capClosed { .. }));
+        assert!(matches!(next(&mut a_events).await, BleHardwareEvent::Disconnected { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_link_parameter_negotiation() {
+        let air = SimAir::new(SimAirConfig::new());
+        let (a, mut a_events) = air.add_device(device("a"));
+        let (b, mut b_events) = air.add_device(device("b").with_phys(vec![BlePhy::Le1M]).with_max_data_length(123));
+        b.start_advertising(&[]);
+        a.connect(b.address());
+        next(&mut a_events).await;
+        next(&mut b_events).await;
+        assert_eq!(a.get_link_parameters(b.address()), LinkParameters::new().with_mtu(DEFAULT_SIM_MTU));
+
+        a.request_connection_priority(b.address(), ConnectionPriority::High).unwrap();
+        a.request_data_length(b.address(), MAX_DATA_LENGTH).unwrap();
+        // b 不支持 2M，请求失败且不通知
+        assert!(a.request_phy(b.address(), BlePhy::Le2M).is_err());
+
+        let expected = LinkParameters::new()
+            .with_mtu(DEFAULT_SIM_MTU)
+            .with_connection_interval(Duration::from_micros(7_500))
+            .with_data_length(123);
+        next(&mut a_events).await;
+        assert_eq!(
+            next(&mut a_events).await,
+            BleHardwareEvent::LinkParametersChanged {
+                peripheral_id: b.address().to_string(),
+                parameters: expected,
+            }
+        );
+        next(&mut b_events).await;
+        assert!(matches!(
+            next(&mut b_events).await,
+            BleHardwareEvent::LinkParametersChanged { parameters, .. } if parameters == expected
+        ));
+        assert_eq!(b.get_link_parameters(a.address()), expected);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_loss_is_deterministic_per_seed() {
+        async fn delivered(seed: u64) -> Vec<u8> {
+            let air = SimAir::new(SimAirConfig::new().with_loss_rate(0.3).with_seed(seed));
+            let (a, mut a_events) = air.add_device(device("a"));
+            let (b, mut b_events) = air.add_device(device("b"));
+            b.start_advertising(&[]);
+            a.connect(b.address());
+            next(&mut a_events).await;
+            next(&mut b_events).await;
+
+            let uuid = DATA_TRANSFER_CHARACTERISTIC_UUID.to_string();
+            for i in 0..100u8 {
+                a.write_characteristic(b.address(), &uuid, &[i]).unwrap();
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let mut received = Vec::new();
+            while let Ok(BleHardwareEvent::DataReceived { data, .. }) = b_events.try_recv() {
+                received.push(data[0]);
+            }
+            received
+        }
+
+        let first = delivered(42).await;
+        assert_eq!(first, delivered(42).await);
+        assert!(first.len() > 50 && first.len() < 90, "delivered {}", first.len());
+        assert!(first.windows(2).all(|w| w[0] < w[1]), "order preserved");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_drop_next_and_in_flight_loss() {
+        let air = SimAir::new(SimAirConfig::new().with_latency(Duration::from_millis(50)));
+        let (a, mut a_events) = air.add_device(device("a"));
+        let (b, mut b_events) = air.add_device(device("b"));
+        b.start_advertising(&[]);
+        a.connect(b.address());
+        next(&mut a_events).await;
+        next(&mut b_events).await;
+
+        let uuid = DATA_TRANSFER_CHARACTERISTIC_UUID.to_string();
+        air.drop_next(1);
+        a.write_characteristic(b.address(), &uuid, &[1]).unwrap();
+        a.write_characteristic(b.address(), &uuid, &[2]).unwrap();
+        assert!(matches!(next(&mut b_events).await, BleHardwareEvent::DataReceived { data, .. } if data == [2]));
+
+        // 还在空中的数据包随连接断开而丢失
+        a.write_characteristic(b.address(), &uuid, &[3]).unwrap();
+        air.inject_disconnect(a.address(), b.address(), "lost");
+        assert!(matches!(next(&mut b_events).await, BleHardwareEvent::Disconnected { .. }));
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(b_events.try_recv().is_err());
+    }
+}
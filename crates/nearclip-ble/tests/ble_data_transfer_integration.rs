@@ -65,6 +65,7 @@ fn test_chunk_header_creation() {
         sequence_number: 5,
         total_chunks: 10,
         payload_length: 100,
+        ..Default::default()
     };
 
     assert_eq!(header.message_id, 1234);
@@ -80,6 +81,7 @@ fn test_chunk_header_serialization_roundtrip() {
         sequence_number: 0x1234,
         total_chunks: 0x5678,
         payload_length: 0x9ABC,
+        ..Default::default()
     };
 
     let bytes = original.to_bytes();
@@ -93,10 +95,11 @@ fn test_chunk_header_serialization_roundtrip() {
 fn test_chunk_header_boundary_values() {
     // 测试边界值
     let max_header = ChunkHeader {
-        message_id: u16::MAX,
+        message_id: u16::MAX as u32,
         sequence_number: u16::MAX,
         total_chunks: u16::MAX,
         payload_length: u16::MAX,
+        ..Default::default()
     };
 
     let bytes = max_header.to_bytes();
@@ -108,6 +111,7 @@ fn test_chunk_header_boundary_values() {
         sequence_number: 0,
         total_chunks: 1,
         payload_length: 0,
+        ..Default::default()
     };
 
     let bytes = min_header.to_bytes();
@@ -240,6 +244,7 @@ fn test_reassembler_single_chunk() {
         sequence_number: 0,
         total_chunks: 1,
         payload_length: 5,
+        ..Default::default()
     };
 
     reassembler.add_chunk(header, b"Hello".to_vec()).unwrap();
@@ -264,6 +269,7 @@ fn test_reassembler_multiple_chunks_in_order() {
             sequence_number: i,
             total_chunks: 3,
             payload_length: payload.len() as u16, // 实际长度是 3
+            ..Default::default()
         };
         reassembler.add_chunk(header, payload).unwrap();
     }
@@ -292,6 +298,7 @@ fn test_reassembler_out_of_order() {
             sequence_number: seq,
             total_chunks: 3,
             payload_length: payload.len() as u16,
+            ..Default::default()
         };
         reassembler.add_chunk(header, payload).unwrap();
     }
@@ -312,6 +319,7 @@ fn test_reassembler_duplicate_chunk() {
         sequence_number: 0,
         total_chunks: 2,
         payload_length: 3,
+        ..Default::default()
     };
 
     // 第一次添加
@@ -326,6 +334,7 @@ fn test_reassembler_duplicate_chunk() {
         sequence_number: 1,
         total_chunks: 2,
         payload_length: 3,
+        ..Default::default()
     };
     reassembler.add_chunk(header2, b"CCC".to_vec()).unwrap();
 
@@ -344,6 +353,7 @@ fn test_reassembler_wrong_message_id() {
         sequence_number: 0,
         total_chunks: 2,
         payload_length: 3,
+        ..Default::default()
     };
 
     let result = reassembler.add_chunk(wrong_header, b"AAA".to_vec());
@@ -361,6 +371,7 @@ fn test_reassembler_wrong_total_chunks() {
         sequence_number: 0,
         total_chunks: 3, // 不匹配的 total_chunks
         payload_length: 3,
+        ..Default::default()
     };
 
     let result = reassembler.add_chunk(wrong_header, b"AAA".to_vec());
@@ -378,6 +389,7 @@ fn test_reassembler_incomplete_assemble() {
         sequence_number: 0,
         total_chunks: 3,
         payload_length: 3,
+        ..Default::default()
     };
     reassembler.add_chunk(header, b"AAA".to_vec()).unwrap();
 
@@ -535,6 +547,7 @@ async fn test_peripheral_data_receiver_handle_single_chunk() {
         sequence_number: 0,
         total_chunks: 1,
         payload_length: 5,
+        ..Default::default()
     };
 
     let mut chunk_data = header.to_bytes().to_vec();
@@ -573,6 +586,7 @@ async fn test_peripheral_data_receiver_handle_multi_chunk() {
             sequence_number: *seq,
             total_chunks: 3,
             payload_length: payload.len() as u16,
+            ..Default::default()
         };
 
         let mut chunk_data = header.to_bytes().to_vec();
@@ -609,6 +623,7 @@ async fn test_peripheral_data_receiver_out_of_order() {
             sequence_number: *seq,
             total_chunks: 3,
             payload_length: payload.len() as u16,
+            ..Default::default()
         };
 
         let mut chunk_data = header.to_bytes().to_vec();
@@ -640,6 +655,7 @@ async fn test_peripheral_data_receiver_multiple_messages() {
             sequence_number: 0,
             total_chunks: 1,
             payload_length: payload.len() as u16, // "Msg1" = 4 bytes
+            ..Default::default()
         };
 
         let mut chunk_data = header.to_bytes().to_vec();
@@ -0,0 +1,296 @@
+//! 模拟空中环境集成测试
+//!
+//! 用 `SimAir` 连接多个完整的 `BleController`，验证发现、连接、双向数据传输、
+//! MTU 限制、断开后自动重连、RSSI 变化和按配对关系过滤广播，全部不需要蓝牙硬件。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use nearclip_ble::{
+    AdvertisementKey, BleController, BleControllerCallback, BleControllerConfig, BleHardware,
+    ControllerDiscoveredDevice, RssiCurve, SimAir, SimAirConfig, SimBleHardware,
+    SimDeviceConfig, DATA_TRANSFER_CHARACTERISTIC_UUID,
+};
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Event {
+    Discovered { device_id: String, rssi: i32 },
+    Connected(String),
+    Disconnected(String),
+    Data(String, Vec<u8>),
+}
+
+struct ChannelCallback {
+    tx: mpsc::UnboundedSender<Event>,
+}
+
+impl BleControllerCallback for ChannelCallback {
+    fn on_device_discovered(&self, device: ControllerDiscoveredDevice) {
+        let _ = self.tx.send(Event::Discovered {
+            device_id: device.device_id,
+            rssi: device.rssi,
+        });
+    }
+
+    fn on_device_lost(&self, _peripheral_uuid: String) {}
+
+    fn on_device_connected(&self, device_id: String) {
+        let _ = self.tx.send(Event::Connected(device_id));
+    }
+
+    fn on_device_disconnected(&self, device_id: String, _reason: String) {
+        let _ = self.tx.send(Event::Disconnected(device_id));
+    }
+
+    fn on_data_received(&self, device_id: String, data: Vec<u8>) {
+        let _ = self.tx.send(Event::Data(device_id, data));
+    }
+
+    fn on_error(&self, _device_id: Option<String>, _error: String) {}
+}
+
+/// 一个虚拟设备：控制器 + 回调事件
+struct Node {
+    hardware: Arc<SimBleHardware>,
+    controller: Arc<BleController>,
+    events: mpsc::UnboundedReceiver<Event>,
+}
+
+impl Node {
+    fn new(air: &Arc<SimAir>, config: SimDeviceConfig) -> Self {
+        let (hardware, mut hardware_events) = air.add_device(config);
+        let hardware = Arc::new(hardware);
+        let (tx, events) = mpsc::unbounded_channel();
+        let controller_config = BleControllerConfig {
+            reconnect_base_delay_ms: 100,
+            ..Default::default()
+        };
+        let controller = Arc::new(BleController::new(
+            hardware.clone(),
+            controller_config,
+            Arc::new(ChannelCallback { tx }),
+        ));
+
+        let forwarder = controller.clone();
+        tokio::spawn(async move {
+            while let Some(event) = hardware_events.recv().await {
+                forwarder.handle_hardware_event(event).await;
+            }
+        });
+
+        Self {
+            hardware,
+            controller,
+            events,
+        }
+    }
+
+    fn address(&self) -> &str {
+        self.hardware.address()
+    }
+
+    /// 等待满足条件的事件，跳过其他事件
+    async fn wait_for(&mut self, pred: impl Fn(&Event) -> bool) -> Event {
+        tokio::time::timeout(Duration::from_secs(30), async {
+            loop {
+                let event = self.events.recv().await.expect("callback channel closed");
+                if pred(&event) {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("expected event did not arrive")
+    }
+}
+
+fn device(id: &str) -> SimDeviceConfig {
+    SimDeviceConfig::new(id.to_string(), format!("{}-hash", id))
+}
+
+/// A 扫描并连接正在广播的 B
+async fn connect(a: &mut Node, b: &mut Node, b_id: &str) {
+    b.hardware.start_advertising(&[]);
+    a.controller.start_scan().await.unwrap();
+    a.wait_for(|e| matches!(e, Event::Discovered { device_id, .. } if device_id == b_id))
+        .await;
+    a.controller.connect(b_id).await.unwrap();
+    b.wait_for(|e| matches!(e, Event::Connected(_))).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_controllers_discover_connect_and_exchange_data() {
+    let air = SimAir::new(SimAirConfig::new());
+    let mut phone = Node::new(&air, device("phone"));
+    let mut laptop = Node::new(&air, device("laptop"));
+
+    connect(&mut phone, &mut laptop, "laptop").await;
+    assert_eq!(phone.controller.get_connected_devices().await, vec!["laptop".to_string()]);
+
+    // 外设侧以中心的地址标识对端
+    let phone_address = phone.address().to_string();
+    assert_eq!(laptop.controller.get_connected_devices().await, vec![phone_address.clone()]);
+
+    // 中心 -> 外设：写特征
+    phone.controller.send_data("laptop", b"from phone").await.unwrap();
+    assert_eq!(
+        laptop.wait_for(|e| matches!(e, Event::Data(..))).await,
+        Event::Data(phone_address.clone(), b"from phone".to_vec())
+    );
+
+    // 外设 -> 中心：需要中心先订阅通知
+    assert!(laptop.controller.send_data(&phone_address, b"early").await.is_err());
+    phone
+        .hardware
+        .subscribe_characteristic(laptop.address(), &DATA_TRANSFER_CHARACTERISTIC_UUID.to_string())
+        .unwrap();
+    laptop.controller.send_data(&phone_address, b"from laptop").await.unwrap();
+    assert_eq!(
+        phone.wait_for(|e| matches!(e, Event::Data(..))).await,
+        Event::Data("laptop".to_string(), b"from laptop".to_vec())
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_three_devices_share_the_air() {
+    let air = SimAir::new(SimAirConfig::new());
+    let mut hub = Node::new(&air, device("hub"));
+    let mut a = Node::new(&air, device("a"));
+    let mut b = Node::new(&air, device("b"));
+
+    connect(&mut hub, &mut a, "a").await;
+    connect(&mut hub, &mut b, "b").await;
+
+    let mut connected = hub.controller.get_connected_devices().await;
+    connected.sort();
+    assert_eq!(connected, vec!["a".to_string(), "b".to_string()]);
+
+    hub.controller.send_data("b", b"only b").await.unwrap();
+    assert_eq!(
+        b.wait_for(|e| matches!(e, Event::Data(..))).await,
+        Event::Data(hub.address().to_string(), b"only b".to_vec())
+    );
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!std::iter::from_fn(|| a.events.try_recv().ok()).any(|e| matches!(e, Event::Data(..))));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_write_larger_than_mtu_fails() {
+    let air = SimAir::new(SimAirConfig::new());
+    let mut phone = Node::new(&air, device("phone"));
+    let mut watch = Node::new(&air, device("watch").with_mtu(23));
+
+    connect(&mut phone, &mut watch, "watch").await;
+    assert_eq!(phone.hardware.get_mtu(watch.address()), 23);
+
+    assert!(phone.controller.send_data("watch", &[0u8; 20]).await.is_ok());
+    assert!(phone.controller.send_data("watch", &[0u8; 21]).await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_injected_disconnect_triggers_auto_reconnect() {
+    let air = SimAir::new(SimAirConfig::new());
+    let mut phone = Node::new(&air, device("phone"));
+    let mut laptop = Node::new(&air, device("laptop"));
+
+    connect(&mut phone, &mut laptop, "laptop").await;
+
+    assert!(air.inject_disconnect(phone.address(), laptop.address(), "supervision timeout"));
+    phone.wait_for(|e| *e == Event::Disconnected("laptop".to_string())).await;
+
+    // 控制器按退避延迟自动重连
+    phone.wait_for(|e| *e == Event::Connected("laptop".to_string())).await;
+    assert!(phone.hardware.is_connected(laptop.address()));
+    phone.controller.send_data("laptop", b"after reconnect").await.unwrap();
+    assert_eq!(
+        laptop.wait_for(|e| matches!(e, Event::Data(..))).await,
+        Event::Data(phone.address().to_string(), b"after reconnect".to_vec())
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_device_moving_out_of_range_is_dropped() {
+    let air = SimAir::new(SimAirConfig::new());
+    let mut phone = Node::new(&air, device("phone"));
+    let mut laptop = Node::new(&air, device("laptop"));
+
+    // 先靠近，之后远离
+    air.set_rssi(
+        laptop.address(),
+        RssiCurve::Steps(vec![(Duration::ZERO, -60), (Duration::from_secs(5), -110)]),
+    );
+    connect(&mut phone, &mut laptop, "laptop").await;
+
+    tokio::time::sleep(Duration::from_secs(5)).await;
+    assert!(phone.controller.send_data("laptop", b"too far").await.is_err());
+    phone.wait_for(|e| *e == Event::Disconnected("laptop".to_string())).await;
+    assert!(!phone.hardware.is_connected(laptop.address()));
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_discovery_reports_rssi_curve() {
+    let air = SimAir::new(SimAirConfig::new());
+    let mut phone = Node::new(&air, device("phone"));
+    let laptop = Node::new(
+        &air,
+        device("laptop").with_rssi(RssiCurve::Linear {
+            from: -120,
+            to: -40,
+            duration: Duration::from_secs(8),
+        }),
+    );
+    laptop.hardware.start_advertising(&[]);
+    phone.controller.start_scan().await.unwrap();
+
+    // 信号低于可达阈值时不会被发现
+    let first = phone.wait_for(|e| matches!(e, Event::Discovered { .. })).await;
+    let Event::Discovered { rssi: first_rssi, .. } = first else { unreachable!() };
+    assert!(first_rssi > -100, "first rssi {}", first_rssi);
+    assert!(first_rssi <= -95, "first rssi {}", first_rssi);
+
+    tokio::time::sleep(Duration::from_secs(8)).await;
+    let later = phone
+        .wait_for(|e| matches!(e, Event::Discovered { rssi, .. } if *rssi == -40))
+        .await;
+    assert_eq!(
+        later,
+        Event::Discovered {
+            device_id: "laptop".to_string(),
+            rssi: -40
+        }
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_discovery_resolves_paired_advertisements_only() {
+    let air = SimAir::new(SimAirConfig::new());
+    let mut laptop = Node::new(&air, device("laptop"));
+    let mut phone = Node::new(&air, device("phone"));
+    let mut stranger = Node::new(&air, device("stranger"));
+
+    let phone_key = AdvertisementKey::derive(b"phone secret");
+    laptop.controller.add_paired_device("phone", phone_key.clone()).await;
+    phone.controller.start_advertising("phone", phone_key).await;
+    stranger
+        .controller
+        .start_advertising("stranger", AdvertisementKey::derive(b"stranger secret"))
+        .await;
+
+    // 已配对设备仅凭广播即被识别，可以直接连接
+    laptop.controller.start_scan().await.unwrap();
+    laptop
+        .wait_for(|e| matches!(e, Event::Discovered { device_id, .. } if device_id == "phone"))
+        .await;
+    laptop.controller.connect("phone").await.unwrap();
+    phone.wait_for(|e| matches!(e, Event::Connected(_))).await;
+
+    // 陌生设备既不上报也不会被连接
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    laptop.controller.stop_scan().await;
+    let discovered = laptop.controller.get_discovered_devices().await;
+    assert_eq!(discovered.len(), 1);
+    assert_eq!(discovered[0].device_id, "phone");
+    assert!(!laptop.hardware.is_connected(stranger.address()));
+    assert!(stranger.events.try_recv().is_err());
+}
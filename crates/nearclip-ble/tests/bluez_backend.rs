@@ -0,0 +1,745 @@
+//! BlueZ Backend Integration Tests
+//!
+//! 在私有 dbus-daemon 上运行模拟的 org.bluez 服务，验证 `BlueZHardware`
+//! 的扫描识别、身份令牌广播、中心连接、GATT 读写订阅以及外设广播。
+//! 需要 `linux` feature；找不到 dbus-daemon 时跳过。
+
+#![cfg(feature = "linux")]
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use nearclip_ble::bluez::{BlueZConfig, BlueZHardware, ADVERTISEMENT_PATH, GATT_APPLICATION_PATH};
+use nearclip_ble::{
+    AdvertisementKey, BleController, BleControllerCallback, BleControllerConfig, BleError, BleHardware, BleHardwareEvent,
+    ControllerDiscoveredDevice, CHUNK_VERSION_CHARACTERISTIC_UUID, DATA_ACK_CHARACTERISTIC_UUID,
+    DATA_TRANSFER_CHARACTERISTIC_UUID, DEVICE_ID_CHARACTERISTIC_UUID, FRAMING_CHARACTERISTIC_UUID,
+    MUX_FRAMING_VERSION, NEARCLIP_SERVICE_UUID, PUBKEY_HASH_CHARACTERISTIC_UUID,
+};
+use tokio::sync::mpsc;
+use zbus::fdo::{self, ObjectManager};
+use zbus::message::Header;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Value};
+use zbus::{interface, Connection, ObjectServer};
+
+const ADAPTER_PATH: &str = "/org/bluez/hci0";
+const REMOTE_ADDRESS: &str = "11:22:33:44:55:66";
+const REMOTE_PATH: &str = "/org/bluez/hci0/dev_11_22_33_44_55_66";
+const REMOTE_DEVICE_ID: &str = "remote-device";
+const REMOTE_PUBKEY_HASH: &str = "cmVtb3RlLWhhc2g=";
+const CENTRAL_PATH: &str = "/org/bluez/hci0/dev_AA_BB_CC_DD_EE_FF";
+const CENTRAL_ADDRESS: &str = "AA:BB:CC:DD:EE:FF";
+const REMOTE_MTU: u16 = 185;
+
+// ==================== 私有总线 ====================
+
+/// 测试专用的 dbus-daemon，drop 时结束进程
+struct TestBus {
+    daemon: Child,
+    address: String,
+}
+
+impl TestBus {
+    fn start() -> Option<Self> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take()?).read_line(&mut address).ok()?;
+        Some(Self {
+            daemon,
+            address: address.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for TestBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+    }
+}
+
+// ==================== 模拟 BlueZ ====================
+
+/// 模拟 BlueZ 记录的调用
+#[derive(Default)]
+struct Recorded {
+    discovery_filter_uuids: Vec<String>,
+    discovering: bool,
+    writes: Vec<(String, Vec<u8>, String)>,
+    /// (应用所在连接, 应用路径)
+    application: Option<(String, String)>,
+    /// (广播所在连接, 广播路径)
+    advertisement: Option<(String, String)>,
+    /// 远端外设广播的服务数据
+    remote_service_data: Option<Vec<u8>>,
+    /// 对远端外设的 Connect 调用次数
+    remote_connects: usize,
+}
+
+type Log = Arc<Mutex<Recorded>>;
+
+fn string_array(value: &Value<'_>) -> Vec<String> {
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                Value::Str(s) => Some(s.to_string()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+struct MockAdapter {
+    log: Log,
+}
+
+#[interface(name = "org.bluez.Adapter1")]
+impl MockAdapter {
+    async fn set_discovery_filter(&self, filter: HashMap<String, OwnedValue>) {
+        if let Some(uuids) = filter.get("UUIDs") {
+            self.log.lock().unwrap().discovery_filter_uuids = string_array(uuids);
+        }
+    }
+
+    async fn start_discovery(&self, #[zbus(object_server)] server: &ObjectServer) -> fdo::Result<()> {
+        let service_data = {
+            let mut log = self.log.lock().unwrap();
+            log.discovering = true;
+            log.remote_service_data.clone()
+        };
+        // 扫描到远端 NearClip 外设
+        server
+            .at(
+                REMOTE_PATH,
+                MockDevice {
+                    address: REMOTE_ADDRESS.to_string(),
+                    service_data,
+                    connected: false,
+                    services_resolved: false,
+                    log: self.log.clone(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn stop_discovery(&self) {
+        self.log.lock().unwrap().discovering = false;
+    }
+
+    #[zbus(property)]
+    fn powered(&self) -> bool {
+        true
+    }
+}
+
+struct MockGattManager {
+    log: Log,
+}
+
+#[interface(name = "org.bluez.GattManager1")]
+impl MockGattManager {
+    async fn register_application(
+        &self,
+        application: OwnedObjectPath,
+        _options: HashMap<String, OwnedValue>,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] conn: &Connection,
+    ) -> fdo::Result<()> {
+        let sender = header.sender().map(|s| s.to_string()).unwrap_or_default();
+        // 与 BlueZ 一样，注册时枚举应用的对象
+        let objects = fdo::ObjectManagerProxy::builder(conn)
+            .destination(sender.as_str())?
+            .path(application.as_str())?
+            .build()
+            .await?
+            .get_managed_objects()
+            .await?;
+        let has_service = objects.values().any(|interfaces| {
+            interfaces
+                .keys()
+                .any(|name| name.as_str() == "org.bluez.GattService1")
+        });
+        if !has_service {
+            return Err(fdo::Error::InvalidArgs("No GATT service".to_string()));
+        }
+        self.log.lock().unwrap().application = Some((sender, application.to_string()));
+        Ok(())
+    }
+
+    async fn unregister_application(&self, _application: OwnedObjectPath) {
+        self.log.lock().unwrap().application = None;
+    }
+}
+
+struct MockAdvertisingManager {
+    log: Log,
+}
+
+#[interface(name = "org.bluez.LEAdvertisingManager1")]
+impl MockAdvertisingManager {
+    async fn register_advertisement(
+        &self,
+        advertisement: OwnedObjectPath,
+        _options: HashMap<String, OwnedValue>,
+        #[zbus(header)] header: Header<'_>,
+    ) {
+        let sender = header.sender().map(|s| s.to_string()).unwrap_or_default();
+        self.log.lock().unwrap().advertisement = Some((sender, advertisement.to_string()));
+    }
+
+    async fn unregister_advertisement(&self, _advertisement: OwnedObjectPath) {
+        self.log.lock().unwrap().advertisement = None;
+    }
+}
+
+struct MockDevice {
+    address: String,
+    service_data: Option<Vec<u8>>,
+    connected: bool,
+    services_resolved: bool,
+    log: Log,
+}
+
+#[interface(name = "org.bluez.Device1")]
+impl MockDevice {
+    async fn connect(
+        &mut self,
+        #[zbus(object_server)] server: &ObjectServer,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> fdo::Result<()> {
+        if self.connected {
+            return Ok(());
+        }
+        self.log.lock().unwrap().remote_connects += 1;
+        let service_path = format!("{}/service0010", REMOTE_PATH);
+        server.at(service_path.as_str(), MockService).await?;
+        let characteristics = [
+            (DEVICE_ID_CHARACTERISTIC_UUID, REMOTE_DEVICE_ID.as_bytes().to_vec()),
+            (PUBKEY_HASH_CHARACTERISTIC_UUID, REMOTE_PUBKEY_HASH.as_bytes().to_vec()),
+            (DATA_TRANSFER_CHARACTERISTIC_UUID, Vec::new()),
+            (DATA_ACK_CHARACTERISTIC_UUID, Vec::new()),
+        ];
+        for (index, (uuid, value)) in characteristics.into_iter().enumerate() {
+            let characteristic = MockCharacteristic {
+                uuid: uuid.to_string().to_uppercase(),
+                value,
+                notifying: false,
+                log: self.log.clone(),
+            };
+            server
+                .at(format!("{}/char{:04x}", service_path, index + 0x11), characteristic)
+                .await?;
+        }
+
+        self.connected = true;
+        self.services_resolved = true;
+        self.connected_changed(&emitter).await?;
+        self.services_resolved_changed(&emitter).await?;
+        Ok(())
+    }
+
+    async fn disconnect(
+        &mut self,
+        #[zbus(object_server)] server: &ObjectServer,
+        #[zbus(signal_emitter)] emitter: SignalEmitter<'_>,
+    ) -> fdo::Result<()> {
+        if !self.connected {
+            return Ok(());
+        }
+        let service_path = format!("{}/service0010", REMOTE_PATH);
+        for index in 0..4 {
+            server
+                .remove::<MockCharacteristic, _>(format!("{}/char{:04x}", service_path, index + 0x11))
+                .await?;
+        }
+        server.remove::<MockService, _>(service_path.as_str()).await?;
+        self.connected = false;
+        self.services_resolved = false;
+        self.connected_changed(&emitter).await?;
+        self.services_resolved_changed(&emitter).await?;
+        Ok(())
+    }
+
+    #[zbus(property)]
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    #[zbus(property, name = "RSSI")]
+    fn rssi(&self) -> i16 {
+        -52
+    }
+
+    #[zbus(property, name = "UUIDs")]
+    fn uuids(&self) -> Vec<String> {
+        vec![NEARCLIP_SERVICE_UUID.to_string()]
+    }
+
+    #[zbus(property)]
+    fn service_data(&self) -> HashMap<String, OwnedValue> {
+        self.service_data
+            .iter()
+            .map(|data| {
+                let value = OwnedValue::try_from(Value::from(data.clone())).unwrap();
+                (NEARCLIP_SERVICE_UUID.to_string(), value)
+            })
+            .collect()
+    }
+
+    #[zbus(property)]
+    fn connected(&self) -> bool {
+        self.connected
+    }
+
+    #[zbus(property)]
+    fn services_resolved(&self) -> bool {
+        self.services_resolved
+    }
+}
+
+struct MockService;
+
+#[interface(name = "org.bluez.GattService1")]
+impl MockService {
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        NEARCLIP_SERVICE_UUID.to_string()
+    }
+}
+
+struct MockCharacteristic {
+    uuid: String,
+    value: Vec<u8>,
+    notifying: bool,
+    log: Log,
+}
+
+#[interface(name = "org.bluez.GattCharacteristic1")]
+impl MockCharacteristic {
+    async fn read_value(&self, _options: HashMap<String, OwnedValue>) -> Vec<u8> {
+        self.value.clone()
+    }
+
+    async fn write_value(&self, value: Vec<u8>, options: HashMap<String, OwnedValue>) {
+        let write_type = match options.get("type").map(|v| &**v) {
+            Some(Value::Str(s)) => s.to_string(),
+            _ => String::new(),
+        };
+        self.log
+            .lock()
+            .unwrap()
+            .writes
+            .push((self.uuid.to_lowercase(), value, write_type));
+    }
+
+    async fn start_notify(&mut self) {
+        self.notifying = true;
+    }
+
+    #[zbus(property, name = "UUID")]
+    fn uuid(&self) -> String {
+        self.uuid.clone()
+    }
+
+    #[zbus(property)]
+    fn value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+
+    #[zbus(property, name = "MTU")]
+    fn mtu(&self) -> u16 {
+        REMOTE_MTU
+    }
+}
+
+/// 启动模拟 BlueZ，返回其连接和调用记录
+async fn start_mock_bluez(address: &str) -> (Connection, Log) {
+    let log = Log::default();
+    let conn = zbus::connection::Builder::address(address)
+        .unwrap()
+        .name("org.bluez")
+        .unwrap()
+        .serve_at("/", ObjectManager)
+        .unwrap()
+        .serve_at(ADAPTER_PATH, MockAdapter { log: log.clone() })
+        .unwrap()
+        .serve_at(ADAPTER_PATH, MockGattManager { log: log.clone() })
+        .unwrap()
+        .serve_at(ADAPTER_PATH, MockAdvertisingManager { log: log.clone() })
+        .unwrap()
+        .build()
+        .await
+        .unwrap();
+    (conn, log)
+}
+
+// ==================== 控制器回调 ====================
+
+#[derive(Default)]
+struct RecordingCallback {
+    discovered: Mutex<Vec<ControllerDiscoveredDevice>>,
+    connected: Mutex<Vec<String>>,
+    disconnected: Mutex<Vec<String>>,
+    data: Mutex<Vec<(String, Vec<u8>)>>,
+}
+
+impl BleControllerCallback for RecordingCallback {
+    fn on_device_discovered(&self, device: ControllerDiscoveredDevice) {
+        self.discovered.lock().unwrap().push(device);
+    }
+
+    fn on_device_lost(&self, _peripheral_uuid: String) {}
+
+    fn on_device_connected(&self, device_id: String) {
+        self.connected.lock().unwrap().push(device_id);
+    }
+
+    fn on_device_disconnected(&self, device_id: String, _reason: String) {
+        self.disconnected.lock().unwrap().push(device_id);
+    }
+
+    fn on_data_received(&self, device_id: String, data: Vec<u8>) {
+        self.data.lock().unwrap().push((device_id, data));
+    }
+
+    fn on_error(&self, _device_id: Option<String>, _error: String) {}
+}
+
+// ==================== 辅助函数 ====================
+
+async fn wait_until(mut condition: impl FnMut() -> bool) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !condition() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("condition should become true");
+}
+
+async fn next_event(events: &mut mpsc::UnboundedReceiver<BleHardwareEvent>) -> BleHardwareEvent {
+    tokio::time::timeout(Duration::from_secs(5), events.recv())
+        .await
+        .expect("event should arrive")
+        .expect("event channel open")
+}
+
+fn config(bus: &TestBus) -> BlueZConfig {
+    BlueZConfig::new("local-device".to_string(), "bG9jYWwtaGFzaA==".to_string())
+        .with_bus_address(bus.address.clone())
+        .with_operation_timeout(Duration::from_secs(5))
+}
+
+macro_rules! require_bus {
+    () => {
+        match TestBus::start() {
+            Some(bus) => bus,
+            None => {
+                eprintln!("dbus-daemon not available, skipping");
+                return;
+            }
+        }
+    };
+}
+
+// ==================== 测试 ====================
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_scan_identify_connect_and_transfer() {
+    let bus = require_bus!();
+    let (mock, log) = start_mock_bluez(&bus.address).await;
+
+    let (hardware, mut events) = BlueZHardware::new(config(&bus)).await.unwrap();
+    let hardware = Arc::new(hardware);
+    let callback = Arc::new(RecordingCallback::default());
+    let controller = Arc::new(BleController::new(
+        hardware.clone(),
+        BleControllerConfig {
+            auto_reconnect: false,
+            ..Default::default()
+        },
+        callback.clone(),
+    ));
+    let forwarder = controller.clone();
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            forwarder.handle_hardware_event(event).await;
+        }
+    });
+
+    controller.connect_with_scan(REMOTE_DEVICE_ID, 5000).await.unwrap();
+
+    // 扫描按 NearClip 服务过滤，发现时读出了设备 ID 和公钥哈希
+    assert_eq!(
+        log.lock().unwrap().discovery_filter_uuids,
+        vec![NEARCLIP_SERVICE_UUID.to_string()]
+    );
+    {
+        let discovered = callback.discovered.lock().unwrap();
+        assert_eq!(discovered[0].peripheral_uuid, REMOTE_ADDRESS);
+        assert_eq!(discovered[0].device_id, REMOTE_DEVICE_ID);
+        assert_eq!(discovered[0].public_key_hash, REMOTE_PUBKEY_HASH);
+        assert_eq!(discovered[0].rssi, -52);
+    }
+    wait_until(|| callback.connected.lock().unwrap().contains(&REMOTE_DEVICE_ID.to_string())).await;
+    assert!(hardware.is_connected(REMOTE_ADDRESS));
+    assert_eq!(hardware.get_mtu(REMOTE_ADDRESS), REMOTE_MTU);
+
+    // 数据分片用 Write Without Response
+    controller.send_data(REMOTE_DEVICE_ID, b"chunk").await.unwrap();
+    assert_eq!(
+        log.lock().unwrap().writes.last().unwrap(),
+        &(DATA_TRANSFER_CHARACTERISTIC_UUID.to_string(), b"chunk".to_vec(), "command".to_string())
+    );
+
+    // 外设通知转为控制器的数据回调
+    let data_path = format!("{}/service0010/char0013", REMOTE_PATH);
+    let characteristic = mock
+        .object_server()
+        .interface::<_, MockCharacteristic>(data_path.as_str())
+        .await
+        .unwrap();
+    {
+        let mut remote = characteristic.get_mut().await;
+        assert!(remote.notifying);
+        remote.value = b"reply".to_vec();
+        remote.value_changed(characteristic.signal_emitter()).await.unwrap();
+    }
+    wait_until(|| !callback.data.lock().unwrap().is_empty()).await;
+    assert_eq!(
+        callback.data.lock().unwrap()[0],
+        (REMOTE_DEVICE_ID.to_string(), b"reply".to_vec())
+    );
+
+    controller.disconnect(REMOTE_DEVICE_ID).await.unwrap();
+    wait_until(|| !callback.disconnected.lock().unwrap().is_empty()).await;
+    assert!(!hardware.is_connected(REMOTE_ADDRESS));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_gatt_read_write_without_controller() {
+    let bus = require_bus!();
+    let (_mock, log) = start_mock_bluez(&bus.address).await;
+    let (hardware, mut events) = BlueZHardware::new(config(&bus)).await.unwrap();
+
+    hardware.start_scan();
+    let discovered = next_event(&mut events).await;
+    assert_eq!(
+        discovered,
+        BleHardwareEvent::DeviceDiscovered {
+            peripheral_id: REMOTE_ADDRESS.to_string(),
+            device_id: REMOTE_DEVICE_ID.to_string(),
+            public_key_hash: REMOTE_PUBKEY_HASH.to_string(),
+            rssi: -52,
+        }
+    );
+    hardware.stop_scan();
+    wait_until(|| !log.lock().unwrap().discovering).await;
+
+    // 识别用的临时连接已断开，不会上报
+    assert!(!hardware.is_connected(REMOTE_ADDRESS));
+    assert!(hardware
+        .read_characteristic(REMOTE_ADDRESS, &DEVICE_ID_CHARACTERISTIC_UUID.to_string())
+        .is_err());
+
+    hardware.connect(REMOTE_ADDRESS);
+    assert_eq!(
+        next_event(&mut events).await,
+        BleHardwareEvent::Connected {
+            peripheral_id: REMOTE_ADDRESS.to_string()
+        }
+    );
+
+    let hardware = Arc::new(hardware);
+    let reader = hardware.clone();
+    let device_id = tokio::task::spawn_blocking(move || {
+        reader.read_characteristic(REMOTE_ADDRESS, &DEVICE_ID_CHARACTERISTIC_UUID.to_string())
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(device_id, REMOTE_DEVICE_ID.as_bytes());
+
+    let writer = hardware.clone();
+    tokio::task::spawn_blocking(move || {
+        writer.write_characteristic(REMOTE_ADDRESS, &DATA_ACK_CHARACTERISTIC_UUID.to_string(), b"ack")
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(
+        log.lock().unwrap().writes.last().unwrap(),
+        &(DATA_ACK_CHARACTERISTIC_UUID.to_string(), b"ack".to_vec(), "request".to_string())
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_scan_reports_identity_token_without_connecting() {
+    let bus = require_bus!();
+    let (_mock, log) = start_mock_bluez(&bus.address).await;
+    let payload = AdvertisementKey::derive(b"remote secret").payload(REMOTE_DEVICE_ID, 1);
+    log.lock().unwrap().remote_service_data = Some(payload.clone());
+    let (hardware, mut events) = BlueZHardware::new(config(&bus)).await.unwrap();
+
+    hardware.start_scan();
+    assert_eq!(
+        next_event(&mut events).await,
+        BleHardwareEvent::AdvertisementReceived {
+            peripheral_id: REMOTE_ADDRESS.to_string(),
+            service_data: payload,
+            rssi: -52,
+        }
+    );
+    hardware.stop_scan();
+    wait_until(|| !log.lock().unwrap().discovering).await;
+    assert_eq!(log.lock().unwrap().remote_connects, 0);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_advertising_exports_gatt_application() {
+    let bus = require_bus!();
+    let (mock, log) = start_mock_bluez(&bus.address).await;
+    let (hardware, mut events) = BlueZHardware::new(config(&bus)).await.unwrap();
+
+    hardware.start_advertising(&[0x01, 0x02]);
+    wait_until(|| log.lock().unwrap().advertisement.is_some()).await;
+    let (owner, application) = log.lock().unwrap().application.clone().unwrap();
+    assert_eq!(application, GATT_APPLICATION_PATH);
+
+    // 广播内容
+    let advertisement = zbus::Proxy::new(&mock, owner.clone(), ADVERTISEMENT_PATH, "org.bluez.LEAdvertisement1")
+        .await
+        .unwrap();
+    assert_eq!(advertisement.get_property::<String>("Type").await.unwrap(), "peripheral");
+    assert_eq!(
+        advertisement.get_property::<Vec<String>>("ServiceUUIDs").await.unwrap(),
+        vec![NEARCLIP_SERVICE_UUID.to_string()]
+    );
+    let service_data: HashMap<String, OwnedValue> = advertisement.get_property("ServiceData").await.unwrap();
+    let data = Vec::<u8>::try_from(service_data[&NEARCLIP_SERVICE_UUID.to_string()].try_clone().unwrap()).unwrap();
+    assert_eq!(data, vec![0x01, 0x02]);
+
+    // 模拟中心设备读取本机特征
+    let objects = fdo::ObjectManagerProxy::builder(&mock)
+        .destination(owner.as_str())
+        .unwrap()
+        .path(GATT_APPLICATION_PATH)
+        .unwrap()
+        .build()
+        .await
+        .unwrap()
+        .get_managed_objects()
+        .await
+        .unwrap();
+    let char_path = |uuid: uuid::Uuid| -> OwnedObjectPath {
+        objects
+            .iter()
+            .find(|(_, interfaces)| {
+                interfaces.iter().any(|(name, props)| {
+                    name.as_str() == "org.bluez.GattCharacteristic1"
+                        && matches!(props.get("UUID").map(|v| &**v), Some(Value::Str(s)) if s.as_str() == uuid.to_string())
+                })
+            })
+            .map(|(path, _)| path.clone())
+            .expect("characteristic exported")
+    };
+    let characteristic = |path: OwnedObjectPath| {
+        let mock = mock.clone();
+        let owner = owner.clone();
+        async move {
+            zbus::Proxy::new(&mock, owner, path, "org.bluez.GattCharacteristic1")
+                .await
+                .unwrap()
+        }
+    };
+
+    let device_id = characteristic(char_path(DEVICE_ID_CHARACTERISTIC_UUID)).await;
+    let value: Vec<u8> = device_id
+        .call("ReadValue", &(HashMap::<&str, Value<'_>>::new(),))
+        .await
+        .unwrap();
+    assert_eq!(value, b"local-device");
+
+    let chunk_version = characteristic(char_path(CHUNK_VERSION_CHARACTERISTIC_UUID)).await;
+    let value: Vec<u8> = chunk_version
+        .call("ReadValue", &(HashMap::<&str, Value<'_>>::new(),))
+        .await
+        .unwrap();
+    assert_eq!(value, [2]);
+
+    let framing = characteristic(char_path(FRAMING_CHARACTERISTIC_UUID)).await;
+    let value: Vec<u8> = framing
+        .call("ReadValue", &(HashMap::<&str, Value<'_>>::new(),))
+        .await
+        .unwrap();
+    assert_eq!(value, [MUX_FRAMING_VERSION]);
+
+    // 中心设备写入数据特征
+    let data_char = characteristic(char_path(DATA_TRANSFER_CHARACTERISTIC_UUID)).await;
+    let options = HashMap::from([("device", Value::from(ObjectPath::try_from(CENTRAL_PATH).unwrap()))]);
+    data_char.call::<_, _, ()>("WriteValue", &(b"hello".to_vec(), options)).await.unwrap();
+    assert_eq!(
+        next_event(&mut events).await,
+        BleHardwareEvent::CentralConnected {
+            peripheral_id: CENTRAL_ADDRESS.to_string()
+        }
+    );
+    assert_eq!(
+        next_event(&mut events).await,
+        BleHardwareEvent::DataReceived {
+            peripheral_id: CENTRAL_ADDRESS.to_string(),
+            char_uuid: DATA_TRANSFER_CHARACTERISTIC_UUID.to_string(),
+            data: b"hello".to_vec(),
+        }
+    );
+    assert!(hardware.is_connected(CENTRAL_ADDRESS));
+
+    // 向中心设备发送：未订阅时失败，订阅后变为特征通知
+    let hardware = Arc::new(hardware);
+    let writer = hardware.clone();
+    let unsubscribed = tokio::task::spawn_blocking(move || {
+        writer.write_characteristic(CENTRAL_ADDRESS, &DATA_ACK_CHARACTERISTIC_UUID.to_string(), b"ack")
+    })
+    .await
+    .unwrap();
+    assert!(unsubscribed.is_err());
+
+    let ack_char = characteristic(char_path(DATA_ACK_CHARACTERISTIC_UUID)).await;
+    ack_char.call::<_, _, ()>("StartNotify", &()).await.unwrap();
+    let writer = hardware.clone();
+    tokio::task::spawn_blocking(move || {
+        writer.write_characteristic(CENTRAL_ADDRESS, &DATA_ACK_CHARACTERISTIC_UUID.to_string(), b"ack")
+    })
+    .await
+    .unwrap()
+    .unwrap();
+    assert_eq!(ack_char.get_property::<Vec<u8>>("Value").await.unwrap(), b"ack");
+
+    hardware.stop_advertising();
+    wait_until(|| {
+        let log = log.lock().unwrap();
+        log.advertisement.is_none() && log.application.is_none()
+    })
+    .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_missing_adapter_fails_initialization() {
+    let bus = require_bus!();
+    let (_mock, _log) = start_mock_bluez(&bus.address).await;
+
+    let result = BlueZHardware::new(config(&bus).with_adapter("hci9")).await;
+    assert!(matches!(result, Err(BleError::Initialization(_))));
+}
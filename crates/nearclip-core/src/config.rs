@@ -16,6 +16,10 @@
 //! ```
 
 use crate::error::NearClipError;
+use crate::outbox::{OutboxPolicy, DEFAULT_OUTBOX_TTL_SECS};
+use crate::proximity::ProximityPolicy;
+use nearclip_net::{ConnectionLimits, InterfaceFilter};
+use std::ops::RangeInclusive;
 use std::time::Duration;
 
 /// 默认设备名称
@@ -30,6 +34,47 @@ pub const DEFAULT_HEARTBEAT_INTERVAL_SECS: u64 = 10;
 /// 默认重试次数
 pub const DEFAULT_MAX_RETRIES: u32 = 3;
 
+/// 默认 ACK 超时（秒）
+pub const DEFAULT_ACK_TIMEOUT_SECS: u64 = 5;
+
+/// 默认首选监听端口范围起点
+pub const DEFAULT_PORT_RANGE_START: u16 = 8765;
+
+/// 默认首选监听端口范围终点
+pub const DEFAULT_PORT_RANGE_END: u16 = 8774;
+
+// ============================================================
+// RelayConfig - 中继服务器配置
+// ============================================================
+
+/// 中继服务器配置
+///
+/// 两台设备既不在同一局域网、也不在 BLE 范围内时，通过自托管的
+/// `nearclip-relay` 转发端到端加密的消息。中继通道优先级最低，只在
+/// 其他通道都不可用时使用。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayConfig {
+    /// 中继地址（`host:port`）
+    pub address: String,
+    /// 固定信任的中继 TLS 证书（DER）
+    pub certificate_der: Vec<u8>,
+}
+
+impl RelayConfig {
+    /// 创建中继配置
+    ///
+    /// # 参数
+    ///
+    /// * `address` - 中继地址（`host:port`）
+    /// * `certificate_der` - 中继的 TLS 证书（DER），只信任该证书
+    pub fn new(address: impl Into<String>, certificate_der: Vec<u8>) -> Self {
+        Self {
+            address: address.into(),
+            certificate_der,
+        }
+    }
+}
+
 // ============================================================
 // NearClipConfig - 配置结构
 // ============================================================
@@ -72,8 +117,28 @@ pub struct NearClipConfig {
     heartbeat_interval: Duration,
     /// 最大重试次数
     max_retries: u32,
+    /// 等待剪贴板同步 ACK 的超时
+    ack_timeout: Duration,
+    /// 离线发件箱保留策略
+    outbox_policy: OutboxPolicy,
+    /// 离线发件箱条目有效期
+    outbox_ttl: Duration,
     /// mDNS 服务名称
     mdns_service_name: String,
+    /// 参与广播、发现和监听的网络接口
+    interface_filter: InterfaceFilter,
+    /// 监视网络接口变化
+    network_monitor: bool,
+    /// WiFi 监听端口的首选范围
+    port_range: RangeInclusive<u16>,
+    /// WiFi 监听端口的连接限制
+    connection_limits: ConnectionLimits,
+    /// 启用 QUIC 通道
+    quic_enabled: bool,
+    /// 基于 BLE RSSI 的距离策略
+    proximity_policy: ProximityPolicy,
+    /// 中继服务器（未配置时不使用中继通道）
+    relay: Option<RelayConfig>,
 }
 
 impl Default for NearClipConfig {
@@ -107,7 +172,17 @@ impl NearClipConfig {
             connection_timeout: Duration::from_secs(DEFAULT_CONNECTION_TIMEOUT_SECS),
             heartbeat_interval: Duration::from_secs(DEFAULT_HEARTBEAT_INTERVAL_SECS),
             max_retries: DEFAULT_MAX_RETRIES,
+            ack_timeout: Duration::from_secs(DEFAULT_ACK_TIMEOUT_SECS),
+            outbox_policy: OutboxPolicy::default(),
+            outbox_ttl: Duration::from_secs(DEFAULT_OUTBOX_TTL_SECS),
             mdns_service_name: "_nearclip._tcp.local.".to_string(),
+            interface_filter: InterfaceFilter::default(),
+            network_monitor: true,
+            port_range: DEFAULT_PORT_RANGE_START..=DEFAULT_PORT_RANGE_END,
+            connection_limits: ConnectionLimits::default(),
+            quic_enabled: false,
+            proximity_policy: ProximityPolicy::default(),
+            relay: None,
         }
     }
 
@@ -157,12 +232,90 @@ impl NearClipConfig {
         self
     }
 
+    /// 设置 ACK 超时
+    pub fn with_ack_timeout(mut self, timeout: Duration) -> Self {
+        self.ack_timeout = timeout;
+        self
+    }
+
+    /// 设置离线发件箱保留策略
+    pub fn with_outbox_policy(mut self, policy: OutboxPolicy) -> Self {
+        self.outbox_policy = policy;
+        self
+    }
+
+    /// 设置离线发件箱条目有效期
+    pub fn with_outbox_ttl(mut self, ttl: Duration) -> Self {
+        self.outbox_ttl = ttl;
+        self
+    }
+
     /// 设置 mDNS 服务名称
     pub fn with_mdns_service_name(mut self, name: impl Into<String>) -> Self {
         self.mdns_service_name = name.into();
         self
     }
 
+    /// 设置参与广播、发现和监听的网络接口
+    ///
+    /// 可以只使用指定接口，或排除 VPN、虚拟网卡。限制了接口时 TCP/QUIC
+    /// 监听器只绑定在允许接口的地址上，接口变化后重新绑定。
+    pub fn with_interface_filter(mut self, filter: InterfaceFilter) -> Self {
+        self.interface_filter = filter;
+        self
+    }
+
+    /// 设置是否监视网络接口变化
+    ///
+    /// 启用后地址变化时会重新广播 mDNS 并重新连接已配对设备。
+    pub fn with_network_monitor(mut self, enabled: bool) -> Self {
+        self.network_monitor = enabled;
+        self
+    }
+
+    /// 设置是否启用 QUIC 通道
+    ///
+    /// 启用后在 WiFi 监听端口的同一 UDP 端口上接受 QUIC 连接，
+    /// 连接已配对设备时优先尝试 QUIC，失败再回退到 TCP。
+    pub fn with_quic_enabled(mut self, enabled: bool) -> Self {
+        self.quic_enabled = enabled;
+        self
+    }
+
+    /// 设置中继服务器
+    ///
+    /// 启动时连接中继，作为优先级最低的通道与已配对设备通信。
+    pub fn with_relay(mut self, relay: RelayConfig) -> Self {
+        self.relay = Some(relay);
+        self
+    }
+
+    /// 设置 WiFi 监听端口的首选范围
+    ///
+    /// 启动时先尝试上次使用的端口，被占用时依次尝试范围内的下一个端口，
+    /// 全部被占用时回退到系统分配的动态端口。
+    pub fn with_port_range(mut self, range: RangeInclusive<u16>) -> Self {
+        self.port_range = range;
+        self
+    }
+
+    /// 设置 WiFi 监听端口的连接限制
+    ///
+    /// 包括每个 IP 的并发连接数、握手超时、认证前消息长度、消息速率和封禁时长。
+    pub fn with_connection_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.connection_limits = limits;
+        self
+    }
+
+    /// 设置距离策略
+    ///
+    /// 控制 RSSI 平滑、默认远近阈值，以及设备远离时是否暂停同步、
+    /// 回到附近时是否自动连接。
+    pub fn with_proximity_policy(mut self, policy: ProximityPolicy) -> Self {
+        self.proximity_policy = policy;
+        self
+    }
+
     /// 获取设备名称
     pub fn device_name(&self) -> &str {
         &self.device_name
@@ -203,11 +356,61 @@ impl NearClipConfig {
         self.max_retries
     }
 
+    /// 获取 ACK 超时
+    pub fn ack_timeout(&self) -> Duration {
+        self.ack_timeout
+    }
+
+    /// 获取离线发件箱保留策略
+    pub fn outbox_policy(&self) -> OutboxPolicy {
+        self.outbox_policy
+    }
+
+    /// 获取离线发件箱条目有效期
+    pub fn outbox_ttl(&self) -> Duration {
+        self.outbox_ttl
+    }
+
     /// 获取 mDNS 服务名称
     pub fn mdns_service_name(&self) -> &str {
         &self.mdns_service_name
     }
 
+    /// 获取网络接口过滤器
+    pub fn interface_filter(&self) -> &InterfaceFilter {
+        &self.interface_filter
+    }
+
+    /// 检查是否监视网络接口变化
+    pub fn network_monitor(&self) -> bool {
+        self.network_monitor
+    }
+
+    /// 检查 QUIC 通道是否启用
+    pub fn quic_enabled(&self) -> bool {
+        self.quic_enabled
+    }
+
+    /// 获取 WiFi 监听端口的首选范围
+    pub fn port_range(&self) -> RangeInclusive<u16> {
+        self.port_range.clone()
+    }
+
+    /// 获取 WiFi 监听端口的连接限制
+    pub fn connection_limits(&self) -> &ConnectionLimits {
+        &self.connection_limits
+    }
+
+    /// 获取距离策略
+    pub fn proximity_policy(&self) -> &ProximityPolicy {
+        &self.proximity_policy
+    }
+
+    /// 获取中继服务器配置
+    pub fn relay(&self) -> Option<&RelayConfig> {
+        self.relay.as_ref()
+    }
+
     /// 检查是否有任何通道启用
     pub fn has_any_channel(&self) -> bool {
         self.wifi_enabled || self.ble_enabled
@@ -221,6 +424,12 @@ impl NearClipConfig {
     /// - 没有启用任何通道
     /// - 连接超时为 0
     /// - 心跳间隔为 0
+    /// - ACK 超时为 0
+    /// - 离线发件箱容量或有效期为 0
+    /// - 端口范围为空或包含 0
+    /// - 连接限制中的并发数、握手超时或消息速率为 0
+    /// - 距离阈值、平滑系数或超时无效
+    /// - 中继地址或证书为空
     ///
     /// # 示例
     ///
@@ -264,6 +473,51 @@ impl NearClipConfig {
             ));
         }
 
+        if self.ack_timeout.is_zero() {
+            return Err(NearClipError::Config(
+                "ack_timeout must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.outbox_policy.capacity() == 0 {
+            return Err(NearClipError::Config(
+                "outbox capacity must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.outbox_ttl.is_zero() {
+            return Err(NearClipError::Config(
+                "outbox_ttl must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.port_range.is_empty() || *self.port_range.start() == 0 {
+            return Err(NearClipError::Config(
+                "port_range must be non-empty and must not include port 0".to_string(),
+            ));
+        }
+
+        let limits = &self.connection_limits;
+        if limits.max_connections_per_ip == 0
+            || limits.max_pending_handshakes == 0
+            || limits.handshake_timeout.is_zero()
+            || limits.messages_per_second == 0
+        {
+            return Err(NearClipError::Config(
+                "connection limits must be greater than 0".to_string(),
+            ));
+        }
+
+        self.proximity_policy.validate()?;
+
+        if let Some(ref relay) = self.relay {
+            if relay.address.trim().is_empty() || relay.certificate_der.is_empty() {
+                return Err(NearClipError::Config(
+                    "relay address and certificate cannot be empty".to_string(),
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -309,6 +563,69 @@ mod tests {
         assert_eq!(config.max_retries(), 5);
     }
 
+    #[test]
+    fn test_config_port_range() {
+        let config = NearClipConfig::new("Device");
+        assert_eq!(config.port_range(), DEFAULT_PORT_RANGE_START..=DEFAULT_PORT_RANGE_END);
+
+        let config = config.with_port_range(9000..=9009);
+        assert_eq!(config.port_range(), 9000..=9009);
+        assert!(config.validate().is_ok());
+
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = NearClipConfig::new("Device").with_port_range(9009..=9000);
+        assert!(empty.validate().is_err());
+        assert!(NearClipConfig::new("Device").with_port_range(0..=10).validate().is_err());
+    }
+
+    #[test]
+    fn test_config_connection_limits() {
+        let config = NearClipConfig::new("Device");
+        assert_eq!(config.connection_limits(), &ConnectionLimits::default());
+
+        let config = config.with_connection_limits(ConnectionLimits::new().with_max_connections_per_ip(2));
+        assert_eq!(config.connection_limits().max_connections_per_ip, 2);
+        assert!(config.validate().is_ok());
+
+        let config = config.with_connection_limits(ConnectionLimits::new().with_message_rate(0, 10));
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_network_interfaces() {
+        let config = NearClipConfig::new("Device");
+        assert!(config.network_monitor());
+        assert!(!config.interface_filter().is_restricted());
+
+        let config = config
+            .with_network_monitor(false)
+            .with_interface_filter(InterfaceFilter::new().with_exclude_virtual(true));
+        assert!(!config.network_monitor());
+        assert!(config.interface_filter().excludes_virtual());
+    }
+
+    #[test]
+    fn test_config_quic_enabled() {
+        let config = NearClipConfig::new("Device");
+        assert!(!config.quic_enabled());
+        assert!(config.with_quic_enabled(true).quic_enabled());
+    }
+
+    #[test]
+    fn test_config_relay() {
+        let config = NearClipConfig::new("Device");
+        assert!(config.relay().is_none());
+
+        let config = config.with_relay(RelayConfig::new("relay.example.com:7890", vec![1, 2, 3]));
+        assert_eq!(config.relay().unwrap().address, "relay.example.com:7890");
+        assert!(config.validate().is_ok());
+
+        let config = NearClipConfig::new("Device").with_relay(RelayConfig::new(" ", vec![1]));
+        assert!(config.validate().is_err());
+        let config = NearClipConfig::new("Device").with_relay(RelayConfig::new("relay:1", vec![]));
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_config_validate_success() {
         let config = NearClipConfig::new("Valid Device");
@@ -379,6 +696,56 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_config_ack_timeout() {
+        let config = NearClipConfig::new("Device");
+        assert_eq!(config.ack_timeout(), Duration::from_secs(DEFAULT_ACK_TIMEOUT_SECS));
+
+        let config = config.with_ack_timeout(Duration::from_millis(500));
+        assert_eq!(config.ack_timeout(), Duration::from_millis(500));
+        assert!(config.validate().is_ok());
+
+        let config = config.with_ack_timeout(Duration::ZERO);
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_config_outbox() {
+        let config = NearClipConfig::new("Device");
+        assert_eq!(config.outbox_policy(), OutboxPolicy::default());
+        assert_eq!(config.outbox_ttl(), Duration::from_secs(DEFAULT_OUTBOX_TTL_SECS));
+
+        let config = config
+            .with_outbox_policy(OutboxPolicy::LatestOnly)
+            .with_outbox_ttl(Duration::from_secs(60));
+        assert_eq!(config.outbox_policy(), OutboxPolicy::LatestOnly);
+        assert!(config.validate().is_ok());
+
+        assert!(config.clone().with_outbox_policy(OutboxPolicy::KeepLatest(0)).validate().is_err());
+        assert!(config.with_outbox_ttl(Duration::ZERO).validate().is_err());
+    }
+
+    #[test]
+    fn test_config_proximity_policy() {
+        let config = NearClipConfig::new("Device");
+        assert!(!config.proximity_policy().sync_only_when_near);
+
+        let policy = ProximityPolicy {
+            sync_only_when_near: true,
+            auto_connect_when_near: true,
+            ..Default::default()
+        };
+        let config = config.with_proximity_policy(policy);
+        assert_eq!(config.proximity_policy(), &policy);
+        assert!(config.validate().is_ok());
+
+        let invalid = ProximityPolicy {
+            thresholds: crate::ProximityThresholds::new(-80, -60),
+            ..policy
+        };
+        assert!(config.with_proximity_policy(invalid).validate().is_err());
+    }
+
     #[test]
     fn test_config_has_any_channel() {
         let config1 = NearClipConfig::new("D")
@@ -17,6 +17,12 @@
 //! assert_eq!(device.status(), DeviceStatus::Disconnected);
 //! ```
 
+use crate::peers::PeerAddress;
+use crate::proximity::ProximityThresholds;
+use nearclip_ble::AdvertisementKey;
+use nearclip_crypto::ConnectionInfo;
+use nearclip_sync::ChannelPreference;
+use std::net::SocketAddr;
 use std::time::Instant;
 
 // ============================================================
@@ -152,6 +158,16 @@ pub struct DeviceInfo {
     status: DeviceStatus,
     /// 最后活动时间
     last_seen: Option<Instant>,
+    /// 通道偏好
+    channel_preference: ChannelPreference,
+    /// 手动配置的地址（mDNS 不可用时使用）
+    static_addresses: Vec<PeerAddress>,
+    /// 最后一次连接成功的地址
+    last_known_address: Option<SocketAddr>,
+    /// 距离阈值（None 时使用配置中的默认值）
+    proximity_thresholds: Option<ProximityThresholds>,
+    /// 对端的 BLE 广播密钥（配对时交换，用于从广播令牌认出设备）
+    advertisement_key: Option<AdvertisementKey>,
 }
 
 impl DeviceInfo {
@@ -177,6 +193,11 @@ impl DeviceInfo {
             platform: DevicePlatform::Unknown,
             status: DeviceStatus::Disconnected,
             last_seen: None,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
         }
     }
 
@@ -192,6 +213,46 @@ impl DeviceInfo {
         self
     }
 
+    /// 设置通道偏好
+    pub fn with_channel_preference(mut self, preference: ChannelPreference) -> Self {
+        self.channel_preference = preference;
+        self
+    }
+
+    /// 设置距离阈值
+    pub fn with_proximity_thresholds(mut self, thresholds: Option<ProximityThresholds>) -> Self {
+        self.proximity_thresholds = thresholds;
+        self
+    }
+
+    /// 设置最后一次连接成功的地址（从持久化存储恢复时使用）
+    pub fn with_last_known_address(mut self, address: Option<SocketAddr>) -> Self {
+        self.last_known_address = address;
+        self
+    }
+
+    /// 设置对端的 BLE 广播密钥
+    pub fn with_advertisement_key(mut self, key: Option<AdvertisementKey>) -> Self {
+        self.advertisement_key = key;
+        self
+    }
+
+    /// 添加静态地址
+    pub fn with_static_address(mut self, address: PeerAddress) -> Self {
+        self.add_static_address(address);
+        self
+    }
+
+    /// 从配对数据的连接信息导入静态地址
+    ///
+    /// 连接信息缺少地址或端口时忽略。
+    pub fn with_connection_info(mut self, info: &ConnectionInfo) -> Self {
+        if let Some(address) = PeerAddress::from_connection_info(info) {
+            self.add_static_address(address);
+        }
+        self
+    }
+
     /// 获取设备 ID
     pub fn id(&self) -> &str {
         &self.id
@@ -217,6 +278,73 @@ impl DeviceInfo {
         self.last_seen
     }
 
+    /// 获取通道偏好
+    pub fn channel_preference(&self) -> ChannelPreference {
+        self.channel_preference
+    }
+
+    /// 获取静态地址
+    pub fn static_addresses(&self) -> &[PeerAddress] {
+        &self.static_addresses
+    }
+
+    /// 获取最后一次连接成功的地址
+    pub fn last_known_address(&self) -> Option<SocketAddr> {
+        self.last_known_address
+    }
+
+    /// 获取距离阈值（None 表示使用默认值）
+    pub fn proximity_thresholds(&self) -> Option<ProximityThresholds> {
+        self.proximity_thresholds
+    }
+
+    /// 获取对端的 BLE 广播密钥
+    pub fn advertisement_key(&self) -> Option<&AdvertisementKey> {
+        self.advertisement_key.as_ref()
+    }
+
+    /// 添加静态地址，已存在时返回 false
+    pub fn add_static_address(&mut self, address: PeerAddress) -> bool {
+        if self.static_addresses.contains(&address) {
+            return false;
+        }
+        self.static_addresses.push(address);
+        true
+    }
+
+    /// 移除静态地址，不存在时返回 false
+    pub fn remove_static_address(&mut self, address: &PeerAddress) -> bool {
+        let before = self.static_addresses.len();
+        self.static_addresses.retain(|a| a != address);
+        self.static_addresses.len() != before
+    }
+
+    /// 记录最后一次连接成功的地址
+    pub fn set_last_known_address(&mut self, address: Option<SocketAddr>) {
+        self.last_known_address = address;
+    }
+
+    /// 记录对端的 BLE 广播密钥
+    pub fn set_advertisement_key(&mut self, key: Option<AdvertisementKey>) {
+        self.advertisement_key = key;
+    }
+
+    /// 沿用旧记录中的地址信息和广播密钥
+    ///
+    /// 平台层重新添加设备或握手更新设备信息时不一定携带这些信息，
+    /// 此时保留已有的静态地址、最后成功地址和广播密钥。
+    pub(crate) fn inherit_persisted(&mut self, previous: &DeviceInfo) {
+        for address in &previous.static_addresses {
+            self.add_static_address(address.clone());
+        }
+        if self.last_known_address.is_none() {
+            self.last_known_address = previous.last_known_address;
+        }
+        if self.advertisement_key.is_none() {
+            self.advertisement_key = previous.advertisement_key.clone();
+        }
+    }
+
     /// 更新状态
     pub fn set_status(&mut self, status: DeviceStatus) {
         self.status = status;
@@ -239,6 +367,16 @@ impl DeviceInfo {
     pub fn set_platform(&mut self, platform: DevicePlatform) {
         self.platform = platform;
     }
+
+    /// 设置通道偏好
+    pub fn set_channel_preference(&mut self, preference: ChannelPreference) {
+        self.channel_preference = preference;
+    }
+
+    /// 设置距离阈值
+    pub fn set_proximity_thresholds(&mut self, thresholds: Option<ProximityThresholds>) {
+        self.proximity_thresholds = thresholds;
+    }
 }
 
 impl PartialEq for DeviceInfo {
@@ -408,6 +546,31 @@ mod tests {
         assert_eq!(device.platform(), DevicePlatform::MacOS);
     }
 
+    #[test]
+    fn test_device_channel_preference() {
+        let device = DeviceInfo::new("id", "name");
+        assert_eq!(device.channel_preference(), ChannelPreference::Auto);
+
+        let mut device = device.with_channel_preference(ChannelPreference::Hybrid);
+        assert_eq!(device.channel_preference(), ChannelPreference::Hybrid);
+
+        device.set_channel_preference(ChannelPreference::BleOnly);
+        assert_eq!(device.channel_preference(), ChannelPreference::BleOnly);
+    }
+
+    #[test]
+    fn test_device_proximity_thresholds() {
+        let device = DeviceInfo::new("id", "name");
+        assert_eq!(device.proximity_thresholds(), None);
+
+        let thresholds = ProximityThresholds::new(-55, -70);
+        let mut device = device.with_proximity_thresholds(Some(thresholds));
+        assert_eq!(device.proximity_thresholds(), Some(thresholds));
+
+        device.set_proximity_thresholds(None);
+        assert_eq!(device.proximity_thresholds(), None);
+    }
+
     #[test]
     fn test_device_equality() {
         let d1 = DeviceInfo::new("same-id", "Device 1");
@@ -447,6 +610,36 @@ mod tests {
         assert_eq!(d1.status(), d2.status());
     }
 
+    #[test]
+    fn test_device_static_addresses() {
+        let address: PeerAddress = "10.0.0.5:8765".parse().unwrap();
+        let mut device = DeviceInfo::new("id", "name")
+            .with_static_address(address.clone())
+            .with_connection_info(&ConnectionInfo::new().with_ip("laptop.vpn").with_port(9000))
+            .with_connection_info(&ConnectionInfo::new().with_port(9000));
+
+        assert_eq!(device.static_addresses().len(), 2);
+        assert!(!device.add_static_address(address.clone()));
+        assert!(device.remove_static_address(&address));
+        assert!(!device.remove_static_address(&address));
+        assert_eq!(device.static_addresses()[0].to_string(), "laptop.vpn:9000");
+    }
+
+    #[test]
+    fn test_device_inherit_persisted() {
+        let mut previous = DeviceInfo::new("id", "old")
+            .with_static_address("10.0.0.5:8765".parse().unwrap())
+            .with_advertisement_key(Some(AdvertisementKey::from_bytes([3u8; 32])));
+        previous.set_last_known_address(Some("10.0.0.5:8765".parse().unwrap()));
+
+        let mut device = DeviceInfo::new("id", "new");
+        device.inherit_persisted(&previous);
+
+        assert_eq!(device.static_addresses(), previous.static_addresses());
+        assert_eq!(device.last_known_address(), previous.last_known_address());
+        assert_eq!(device.advertisement_key(), previous.advertisement_key());
+    }
+
     #[test]
     fn test_device_debug() {
         let device = DeviceInfo::new("id-123", "Test");
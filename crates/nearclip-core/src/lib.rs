@@ -45,6 +45,11 @@ pub mod error;
 pub mod history;
 pub mod logging;
 pub mod manager;
+pub mod outbox;
+pub mod peers;
+pub mod port_store;
+pub mod proximity;
+pub mod session;
 
 // Re-export error types for convenience
 pub use error::{NearClipError, Result};
@@ -57,8 +62,9 @@ pub use device::{DeviceInfo, DevicePlatform, DeviceStatus};
 
 // Re-export config types
 pub use config::{
-    NearClipConfig, DEFAULT_CONNECTION_TIMEOUT_SECS, DEFAULT_DEVICE_NAME,
-    DEFAULT_HEARTBEAT_INTERVAL_SECS, DEFAULT_MAX_RETRIES,
+    NearClipConfig, RelayConfig, DEFAULT_ACK_TIMEOUT_SECS, DEFAULT_CONNECTION_TIMEOUT_SECS, DEFAULT_DEVICE_NAME,
+    DEFAULT_HEARTBEAT_INTERVAL_SECS, DEFAULT_MAX_RETRIES, DEFAULT_PORT_RANGE_END,
+    DEFAULT_PORT_RANGE_START,
 };
 
 // Re-export manager types
@@ -67,6 +73,33 @@ pub use manager::{NearClipCallback, NearClipManager, NoOpCallback};
 // Re-export history types
 pub use history::{HistoryManager, SyncHistoryEntry};
 
+// Re-export outbox types
+pub use outbox::{
+    FlushGuard, OutboxEntry, OutboxManager, OutboxPolicy, DEFAULT_OUTBOX_CAPACITY, DEFAULT_OUTBOX_TTL_SECS,
+};
+
+// Re-export port store
+pub use port_store::PortStore;
+
+// Re-export static peer types
+pub use peers::PeerAddress;
+
+// Re-export proximity types
+pub use proximity::{
+    ProximityEvent, ProximityMonitor, ProximityPolicy, ProximityState, ProximityThresholds,
+    RssiFilter, DEFAULT_FAR_RSSI, DEFAULT_NEAR_RSSI, DEFAULT_PROXIMITY_TIMEOUT_SECS,
+    DEFAULT_RSSI_SMOOTHING,
+};
+
+// Re-export network types used by NearClipConfig
+pub use nearclip_net::{ConnectionLimits, InterfaceFilter};
+
+// Re-export session types
+pub use session::{
+    CloseReason, Session, SessionDirection, SessionMetrics, SessionRegistry, SessionSnapshot,
+    SessionState, HEARTBEAT_MISS_LIMIT,
+};
+
 // Re-export tracing macros for convenience
 // 包含 instrument 宏用于函数级追踪
 pub use tracing::{debug, error, info, instrument, trace, warn};
@@ -32,6 +32,21 @@
 //!     fn on_sync_error(&self, error: &NearClipError) {
 //!         eprintln!("Sync error: {}", error);
 //!     }
+//!     fn on_sync_delivered(&self, device_id: &str, message_id: u64) {
+//!         println!("Clip {} delivered to {}", message_id, device_id);
+//!     }
+//!     fn on_sync_failed(&self, device_id: &str, message_id: u64, error: &NearClipError) {
+//!         eprintln!("Clip {} not delivered to {}: {}", message_id, device_id, error);
+//!     }
+//!     fn on_proximity_entered(&self, device_id: &str, rssi: i16) {
+//!         println!("{} is nearby ({} dBm)", device_id, rssi);
+//!     }
+//!     fn on_proximity_left(&self, device_id: &str) {
+//!         println!("{} walked away", device_id);
+//!     }
+//!     fn on_device_updated(&self, device: &DeviceInfo) {
+//!         println!("Save {}", device.id());
+//!     }
 //! }
 //!
 //! let config = NearClipConfig::new("My Device");
@@ -41,23 +56,56 @@
 //! assert!(!manager.is_running());
 //! ```
 
-use crate::config::NearClipConfig;
+use crate::config::{NearClipConfig, RelayConfig};
 use crate::device::{DeviceInfo, DevicePlatform, DeviceStatus};
 use crate::error::{NearClipError, Result};
-use nearclip_crypto::{TlsCertificate, TlsClientConfig, TlsServerConfig};
+use crate::outbox::OutboxManager;
+use crate::port_store::PortStore;
+use crate::peers::PeerAddress;
+use crate::proximity::{ProximityEvent, ProximityMonitor, ProximityState, ProximityThresholds};
+use crate::session::{
+    CloseReason, Session, SessionDirection, SessionRegistry, SessionSnapshot, SessionState,
+    HEARTBEAT_MISS_LIMIT,
+};
+use nearclip_ble::AdvertisementKey;
+use nearclip_crypto::{EcdhKeyPair, TlsCertificate, TlsClientConfig, TlsServerConfig};
 use nearclip_net::{
-    DiscoveredDevice, MdnsAdvertiser, MdnsDiscovery, MdnsServiceConfig,
-    TcpClient, TcpClientConfig, TcpServer, TcpServerConfig,
+    list_interfaces, ConnectionLimits, DiscoveredDevice, InterfaceAddress, InterfaceFilter,
+    InterfaceMonitor, MdnsAdvertiser, MdnsDiscovery, MdnsServiceConfig, NetError, QuicClient,
+    QuicClientConfig, QuicServer, QuicServerConfig, TcpClient, TcpClientConfig, TcpServer,
+    TcpServerConfig,
+};
+use nearclip_relay::RelayClient;
+use nearclip_sync::{
+    AckWaiter, Channel, ChannelMonitor, ChannelMonitorConfig, ChannelPreference, ChannelStatus,
+    ChannelStatusCallback, DeliveryTracker, FixedDelayStrategy, LoopGuard, LoopGuardConfig,
+    Message, MessageType, PairingPayload, ProtocolPlatform, QualityChannelSelector,
+    QualitySelectorConfig, QualitySnapshot, RetryExecutor, SyncError, DEFAULT_RESEND_DELAY_MS,
+};
+use nearclip_transport::{
+    PeerSecretLookup, QuicTransportConnector, QuicTransportListener, RelayTransportConnector,
+    RelayTransportListener, Transport, TransportCallback, TransportConnector, TransportError,
+    TransportListener, TransportManager, WifiTransport, WifiTransportListener, MUX_ALPN,
 };
-use nearclip_sync::{Channel, Message, MessageType, PairingPayload, ProtocolPlatform};
-use nearclip_transport::{Transport, TransportListener, TransportManager, WifiTransport, WifiTransportListener};
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
 use tokio::sync::Mutex as TokioMutex;
 use tokio::task::JoinHandle;
 
+/// 网络变化后等待 WiFi 会话回应心跳的时间，超时未回应的会话视为已失效
+const NETWORK_CHANGE_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 连接时等待 QUIC 握手的时间，超时后回退到 TCP
+const QUIC_DIAL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// 检查设备距离读数是否超时的间隔
+const PROXIMITY_EXPIRE_INTERVAL: Duration = Duration::from_secs(1);
+
 // ============================================================
 // 平台类型转换辅助函数
 // ============================================================
@@ -71,6 +119,16 @@ fn protocol_platform_to_device(platform: ProtocolPlatform) -> DevicePlatform {
     }
 }
 
+/// 取出配对载荷中对端的广播密钥（旧版本对端不携带，长度不对时忽略）
+fn peer_advertisement_key(payload: &PairingPayload) -> Option<AdvertisementKey> {
+    let bytes = payload.advertisement_key.as_deref()?;
+    let key = AdvertisementKey::from_slice(bytes);
+    if key.is_none() {
+        tracing::warn!(device_id = %payload.device_id, len = bytes.len(), "Ignoring invalid advertisement key");
+    }
+    key
+}
+
 // ============================================================
 // NearClipCallback - 回调接口
 // ============================================================
@@ -105,6 +163,21 @@ fn protocol_platform_to_device(platform: ProtocolPlatform) -> DevicePlatform {
 ///     fn on_sync_error(&self, error: &NearClipError) {
 ///         eprintln!("Error: {}", error);
 ///     }
+///     fn on_sync_delivered(&self, device_id: &str, message_id: u64) {
+///         println!("Delivered {} to {}", message_id, device_id);
+///     }
+///     fn on_sync_failed(&self, device_id: &str, message_id: u64, error: &NearClipError) {
+///         eprintln!("Failed to deliver {} to {}: {}", message_id, device_id, error);
+///     }
+///     fn on_proximity_entered(&self, device_id: &str, rssi: i16) {
+///         println!("Near: {} ({} dBm)", device_id, rssi);
+///     }
+///     fn on_proximity_left(&self, device_id: &str) {
+///         println!("Far: {}", device_id);
+///     }
+///     fn on_device_updated(&self, device: &DeviceInfo) {
+///         println!("Updated: {}", device.id());
+///     }
 /// }
 /// ```
 pub trait NearClipCallback: Send + Sync {
@@ -128,6 +201,32 @@ pub trait NearClipCallback: Send + Sync {
 
     /// 发生同步错误时调用
     fn on_sync_error(&self, error: &NearClipError);
+
+    /// 剪贴板已送达设备时调用
+    ///
+    /// 收到目标设备对该消息的 ACK 后触发，`message_id` 为
+    /// `sync_clipboard` 返回的消息 ID。
+    fn on_sync_delivered(&self, device_id: &str, message_id: u64);
+
+    /// 剪贴板未能送达设备时调用
+    ///
+    /// 发送失败，或 ACK 超时且重试耗尽后触发。
+    fn on_sync_failed(&self, device_id: &str, message_id: u64, error: &NearClipError);
+
+    /// 已配对设备进入附近时调用
+    ///
+    /// `rssi` 为平滑后的信号强度（dBm）。
+    fn on_proximity_entered(&self, device_id: &str, rssi: i16);
+
+    /// 已配对设备离开附近时调用
+    ///
+    /// 平滑后的 RSSI 降到远离阈值以下，或超时没有新的 BLE 读数时触发。
+    fn on_proximity_left(&self, device_id: &str);
+
+    /// 已配对设备需要持久化的信息变化时调用
+    ///
+    /// 目前在最后一次连接成功的地址变化后触发，平台层应保存该设备。
+    fn on_device_updated(&self, device: &DeviceInfo);
 }
 
 // ============================================================
@@ -147,6 +246,11 @@ impl NearClipCallback for NoOpCallback {
     fn on_pairing_rejected(&self, _device_id: &str, _reason: &str) {}
     fn on_clipboard_received(&self, _content: &[u8], _from_device: &str) {}
     fn on_sync_error(&self, _error: &NearClipError) {}
+    fn on_sync_delivered(&self, _device_id: &str, _message_id: u64) {}
+    fn on_sync_failed(&self, _device_id: &str, _message_id: u64, _error: &NearClipError) {}
+    fn on_proximity_entered(&self, _device_id: &str, _rssi: i16) {}
+    fn on_proximity_left(&self, _device_id: &str) {}
+    fn on_device_updated(&self, _device: &DeviceInfo) {}
 }
 
 // ============================================================
@@ -166,12 +270,6 @@ struct ManagerState {
 // NetworkServices - 网络服务组件
 // ============================================================
 
-/// 接收任务句柄
-struct RecvTaskHandle {
-    /// 接收任务句柄
-    task: JoinHandle<()>,
-}
-
 /// 网络服务组件
 ///
 /// 管理 TCP 服务器、mDNS 广播和发现服务。
@@ -185,721 +283,904 @@ struct NetworkServices {
     mdns_advertiser: Option<MdnsAdvertiser>,
     /// mDNS 发现器
     mdns_discovery: Option<MdnsDiscovery>,
-    /// 连接接受任务
-    accept_task: Option<JoinHandle<()>>,
+    /// 各监听地址上的连接接受任务
+    listeners: HashMap<IpAddr, BoundListener>,
+    /// 监听参数（网络接口变化后重新绑定时使用）
+    listener_settings: Option<ListenerSettings>,
+    /// QUIC 连接器（未启用 QUIC 时为 None）
+    quic_connector: Option<Arc<QuicTransportConnector>>,
+    /// 中继连接器（未配置或未连上中继时为 None）
+    relay_connector: Option<Arc<RelayTransportConnector>>,
+    /// 中继入站连接接受任务
+    relay_accept_task: Option<JoinHandle<()>>,
     /// 发现事件处理任务
     discovery_task: Option<JoinHandle<()>>,
     /// 传输管理器 - 统一管理所有连接
     transport_manager: Arc<TransportManager>,
-    /// 接收任务 (device_id -> RecvTaskHandle)
-    recv_tasks: HashMap<String, RecvTaskHandle>,
+    /// 离线发件箱冲刷任务
+    outbox_task: Option<JoinHandle<()>>,
+    /// 网络接口监视器
+    interface_monitor: Option<InterfaceMonitor>,
+    /// 网络变化处理任务
+    network_change_task: Option<JoinHandle<()>>,
+    /// 距离读数超时检查任务
+    proximity_task: Option<JoinHandle<()>>,
 }
 
 impl NetworkServices {
-    fn new(tls_cert: TlsCertificate) -> Self {
+    fn new(
+        tls_cert: TlsCertificate,
+        channel_selector: Arc<QualityChannelSelector>,
+        channel_monitor: Arc<ChannelMonitor>,
+        connected_tx: mpsc::UnboundedSender<String>,
+    ) -> Self {
+        let mut transport_manager = TransportManager::with_selector(Box::new(channel_selector));
+        transport_manager.set_channel_monitor(channel_monitor);
+        transport_manager.set_callback(Arc::new(ConnectionNotifier { connected_tx }));
+
         Self {
             _tls_cert: tls_cert,
             server_port: 0,
             mdns_advertiser: None,
             mdns_discovery: None,
-            accept_task: None,
+            listeners: HashMap::new(),
+            listener_settings: None,
+            quic_connector: None,
+            relay_connector: None,
+            relay_accept_task: None,
             discovery_task: None,
-            transport_manager: Arc::new(TransportManager::new()),
-            recv_tasks: HashMap::new(),
+            transport_manager: Arc::new(transport_manager),
+            outbox_task: None,
+            interface_monitor: None,
+            network_change_task: None,
+            proximity_task: None,
         }
     }
 }
 
-// ============================================================
-// NearClipManager - 核心管理器
-// ============================================================
-
-/// NearClip 核心管理器
-///
-/// 提供统一的 API 管理所有同步功能。
-///
-/// # 生命周期
-///
-/// 1. 创建 `NearClipManager::new(config, callback)`
-/// 2. 启动 `manager.start().await`
-/// 3. 同步剪贴板 `manager.sync_clipboard(content).await`
-/// 4. 停止 `manager.stop().await`
-///
-/// # 示例
+/// 监听参数
 ///
-/// ```
-/// use nearclip_core::{NearClipManager, NearClipConfig, NoOpCallback};
-/// use std::sync::Arc;
-///
-/// let config = NearClipConfig::new("Test Device");
-/// let callback = Arc::new(NoOpCallback);
-/// let manager = NearClipManager::new(config, callback).unwrap();
-///
-/// assert!(!manager.is_running());
-/// assert_eq!(manager.get_connected_devices().len(), 0);
-/// ```
-pub struct NearClipManager {
-    /// 配置
-    config: NearClipConfig,
-    /// 设备 ID (用于 mDNS 广播和消息标识)
-    device_id: String,
-    /// 回调
-    callback: Arc<dyn NearClipCallback>,
-    /// 运行状态
-    running: AtomicBool,
-    /// 内部状态 (Arc 包装以支持共享给后台任务)
-    state: Arc<RwLock<ManagerState>>,
-    /// 网络服务 (需要 async 访问，使用 TokioMutex，Arc 包装以支持共享给后台任务)
-    network: Arc<TokioMutex<Option<NetworkServices>>>,
+/// 接口过滤器生效时，每个允许的地址各绑定一组 TCP/QUIC 监听器，
+/// 端口号都相同。
+#[derive(Clone)]
+struct ListenerSettings {
+    /// TLS 服务端配置
+    tls_server_config: Arc<TlsServerConfig>,
+    /// 连接限制
+    limits: ConnectionLimits,
+    /// 监听端口（TCP 与 QUIC 相同）
+    port: u16,
+    /// 是否同时接受 QUIC 连接
+    quic_enabled: bool,
 }
 
-impl NearClipManager {
-    /// 创建新的管理器实例
-    ///
-    /// # 参数
-    ///
-    /// * `config` - 配置
-    /// * `callback` - 回调实现
-    ///
-    /// # 错误
-    ///
-    /// 如果配置验证失败，返回错误。
-    ///
-    /// # 示例
-    ///
-    /// ```
-    /// use nearclip_core::{NearClipManager, NearClipConfig, NoOpCallback};
-    /// use std::sync::Arc;
-    ///
-    /// let config = NearClipConfig::new("Device");
-    /// let callback = Arc::new(NoOpCallback);
-    /// let manager = NearClipManager::new(config, callback);
-    /// assert!(manager.is_ok());
-    /// ```
-    pub fn new(config: NearClipConfig, callback: Arc<dyn NearClipCallback>) -> Result<Self> {
-        config.validate()?;
-
-        // 使用配置中的设备 ID，如果没有则生成新的
-        let device_id = config
-            .device_id()
-            .map(|id| id.to_string())
-            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string().to_uppercase());
+/// 绑定在单个地址上的连接接受任务
+struct BoundListener {
+    /// TCP 连接接受任务
+    accept_task: JoinHandle<()>,
+    /// QUIC 连接接受任务
+    quic_accept_task: Option<JoinHandle<()>>,
+}
 
-        tracing::info!(
-            device_name = config.device_name(),
-            device_id = %device_id,
-            wifi = config.wifi_enabled(),
-            ble = config.ble_enabled(),
-            "Creating NearClipManager"
-        );
+impl BoundListener {
+    /// 停止接受任务并关闭监听套接字
+    fn abort(&self) {
+        self.accept_task.abort();
+        if let Some(ref task) = self.quic_accept_task {
+            task.abort();
+        }
+    }
+}
 
-        Ok(Self {
-            config,
-            device_id,
-            callback,
-            running: AtomicBool::new(false),
-            state: Arc::new(RwLock::new(ManagerState::default())),
-            network: Arc::new(TokioMutex::new(None)),
-        })
+/// 计算监听地址
+///
+/// 过滤器未限制接口时绑定在 `0.0.0.0`；否则只绑定允许接口上的 IPv4 地址，
+/// 没有可用地址时只监听回环地址，保留端口号直到允许的接口出现。
+fn listen_addrs(filter: &InterfaceFilter, addresses: &[InterfaceAddress]) -> Vec<IpAddr> {
+    if !filter.is_restricted() {
+        return vec![IpAddr::V4(Ipv4Addr::UNSPECIFIED)];
+    }
+    let mut addrs: Vec<IpAddr> = filter
+        .apply(addresses)
+        .into_iter()
+        .map(|address| address.ip)
+        .filter(|ip| ip.is_ipv4())
+        .collect();
+    addrs.sort();
+    addrs.dedup();
+    if addrs.is_empty() {
+        addrs.push(IpAddr::V4(Ipv4Addr::LOCALHOST));
     }
+    addrs
+}
 
-    /// 获取设备 ID
-    pub fn device_id(&self) -> &str {
-        &self.device_id
+/// 创建 QUIC 连接器
+///
+/// 创建失败时只记录警告，出站连接使用 TCP。
+fn quic_connector() -> Option<Arc<QuicTransportConnector>> {
+    // TODO: 与 TCP 相同，待实现 TOFU 后改为验证对端证书
+    let client = TlsClientConfig::new_insecure()
+        .map_err(|e| e.to_string())
+        .and_then(|tls| {
+            QuicClient::with_config(QuicClientConfig::new().with_timeout(QUIC_DIAL_TIMEOUT), tls.config())
+                .map_err(|e| e.to_string())
+        });
+    match client {
+        Ok(client) => Some(Arc::new(QuicTransportConnector::from_client(client))),
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to create QUIC client, outgoing connections use TCP");
+            None
+        }
     }
+}
 
-    /// 启动服务
-    ///
-    /// 启动 mDNS 广播、TCP 服务器、BLE 广播等。
-    ///
-    /// # 示例
-    ///
-    /// ```no_run
-    /// use nearclip_core::{NearClipManager, NearClipConfig, NoOpCallback};
-    /// use std::sync::Arc;
-    ///
-    /// # async fn example() {
-    /// let config = NearClipConfig::new("Device");
-    /// let callback = Arc::new(NoOpCallback);
-    /// let manager = NearClipManager::new(config, callback).unwrap();
-    ///
-    /// manager.start().await.unwrap();
-    /// assert!(manager.is_running());
-    ///
-    /// manager.stop().await;
-    /// assert!(!manager.is_running());
-    /// # }
-    /// ```
-    pub async fn start(&self) -> Result<()> {
-        if self.running.load(Ordering::Acquire) {
-            tracing::warn!("Manager already running");
-            return Ok(());
+/// 在已绑定的 TCP 服务器上接受连接，并在同一地址和端口上接受 QUIC 连接
+///
+/// QUIC 绑定与 TCP 相同的端口号（UDP），mDNS 广播的端口对两者都有效。
+/// 绑定失败时只记录警告，WiFi 通道不受影响。
+fn spawn_listener(
+    ctx: &SessionContext,
+    settings: &ListenerSettings,
+    addr: IpAddr,
+    tcp_server: TcpServer,
+) -> BoundListener {
+    let wifi_listener = Arc::new(WifiTransportListener::new(tcp_server));
+    let accept_task = spawn_accept_task(ctx.clone(), wifi_listener);
+
+    let mut quic_accept_task = None;
+    if settings.quic_enabled {
+        let server_config = QuicServerConfig::new()
+            .with_bind_addr(addr)
+            .with_port(settings.port)
+            .with_limits(settings.limits.clone());
+        match QuicServer::bind(server_config, settings.tls_server_config.config()) {
+            Ok(server) => {
+                let listener = Arc::new(QuicTransportListener::new(server));
+                quic_accept_task = Some(spawn_accept_task(ctx.clone(), listener));
+                tracing::info!(%addr, port = settings.port, "QUIC server started");
+            }
+            Err(e) => {
+                tracing::warn!(%addr, port = settings.port, error = %e, "Failed to bind QUIC server, continuing without QUIC");
+            }
         }
+    }
 
-        tracing::info!("Starting NearClipManager");
+    BoundListener { accept_task, quic_accept_task }
+}
 
-        if self.config.wifi_enabled() {
-            // 1. 生成 TLS 证书
-            let tls_cert = TlsCertificate::generate(&["nearclip.local".to_string()])
-                .map_err(|e| NearClipError::Network(format!("Failed to generate TLS cert: {}", e)))?;
+/// 在指定地址上绑定 TCP/QUIC 监听器
+async fn bind_listener(
+    ctx: &SessionContext,
+    settings: &ListenerSettings,
+    addr: IpAddr,
+) -> std::result::Result<BoundListener, NetError> {
+    let server_config = TcpServerConfig::new()
+        .with_bind_addr(addr)
+        .with_port(settings.port)
+        .with_limits(settings.limits.clone())
+        .with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
+    let tcp_server = TcpServer::bind(server_config, settings.tls_server_config.config()).await?;
+    tracing::info!(%addr, port = settings.port, "TCP server started");
+    Ok(spawn_listener(ctx, settings, addr, tcp_server))
+}
 
-            let tls_server_config = TlsServerConfig::new(&tls_cert)
-                .map_err(|e| NearClipError::Network(format!("Failed to create TLS config: {}", e)))?;
+/// 传输连接通知
+///
+/// 传输管理器在持有连接表写锁时回调，这里只把设备 ID 转发给
+/// 离线发件箱冲刷任务，由后者异步发送。
+/// 连接中继所需的本设备身份
+///
+/// 中继在设备首次认证时绑定设备 ID 与公钥，因此密钥对必须由平台层持久化。
+#[derive(Clone)]
+struct RelayIdentity {
+    /// 本设备密钥对（公钥在配对时交给对端）
+    keypair: EcdhKeyPair,
+    /// 按设备 ID 查找配对共享密钥，用于端到端加密
+    secrets: PeerSecretLookup,
+}
 
-            // 2. 启动 TCP 服务器 (使用动态端口 0)
-            let server_config = TcpServerConfig::new().with_port(0);
-            let tcp_server = TcpServer::bind(server_config, tls_server_config.config())
-                .await
-                .map_err(|e| NearClipError::Network(format!("Failed to bind TCP server: {}", e)))?;
+/// 连接中继并声明允许的已配对设备
+async fn connect_relay(
+    relay: &RelayConfig,
+    device_id: &str,
+    keypair: &EcdhKeyPair,
+    paired_ids: Vec<String>,
+) -> Result<RelayClient> {
+    let relay_addr = tokio::net::lookup_host(relay.address.as_str())
+        .await
+        .map_err(|e| NearClipError::Network(format!("Failed to resolve relay {}: {}", relay.address, e)))?
+        .next()
+        .ok_or_else(|| NearClipError::Network(format!("Relay {} has no address", relay.address)))?;
+    let tls_config = TlsClientConfig::new(&relay.certificate_der)
+        .map_err(|e| NearClipError::Network(format!("Invalid relay certificate: {}", e)))?;
+
+    let client = RelayClient::connect(relay_addr, tls_config.config(), device_id, keypair)
+        .await
+        .map_err(|e| NearClipError::Network(format!("Failed to connect to relay: {}", e)))?;
+    if let Err(e) = client.allow_peers(paired_ids).await {
+        client.close().await;
+        return Err(NearClipError::Network(format!("Failed to register paired devices with relay: {}", e)));
+    }
+    Ok(client)
+}
 
-            let server_port = tcp_server.local_addr()
-                .map_err(|e| NearClipError::Network(format!("Failed to get server address: {}", e)))?
-                .port();
+struct ConnectionNotifier {
+    connected_tx: mpsc::UnboundedSender<String>,
+}
 
-            tracing::info!(port = server_port, "TCP server started");
+impl TransportCallback for ConnectionNotifier {
+    fn on_transport_connected(&self, device_id: &str, _channel: Channel) {
+        let _ = self.connected_tx.send(device_id.to_string());
+    }
 
-            // 3. 启动 mDNS 广播
-            let pubkey_hash = base64::Engine::encode(
-                &base64::engine::general_purpose::STANDARD,
-                &tls_cert.cert_der()[..32.min(tls_cert.cert_der().len())]
-            );
-            let mdns_config = MdnsServiceConfig::new(
-                self.device_id.clone(),
-                pubkey_hash,
-                server_port,
-            );
-            let mut mdns_advertiser = MdnsAdvertiser::new(mdns_config)
-                .map_err(|e| NearClipError::Network(format!("Failed to create mDNS advertiser: {}", e)))?;
-            mdns_advertiser.start().await
-                .map_err(|e| NearClipError::Network(format!("Failed to start mDNS advertiser: {}", e)))?;
+    fn on_transport_disconnected(&self, _device_id: &str, _channel: Channel) {}
 
-            tracing::info!(device_id = %self.device_id, "mDNS advertiser started");
+    fn on_message_received(&self, _device_id: &str, _msg: Message) {}
 
-            // 4. 启动 mDNS 发现
-            let mut mdns_discovery = MdnsDiscovery::new()
-                .map_err(|e| NearClipError::Network(format!("Failed to create mDNS discovery: {}", e)))?;
-            mdns_discovery.start().await
-                .map_err(|e| NearClipError::Network(format!("Failed to start mDNS discovery: {}", e)))?;
+    fn on_transport_error(&self, _device_id: &str, _error: &TransportError) {}
+}
 
-            tracing::info!("mDNS discovery started");
+/// 将设备的离线发件箱按入队顺序发送
+///
+/// 每条消息发送前注册 ACK 等待者，只有在 `ack_timeout` 内收到 ACK 的条目
+/// 才从发件箱删除；发送失败时停止发送，未确认和未发送的条目保留到下次冲刷。
+/// 同一设备已有冲刷在进行时直接返回，避免重复发送。
+///
+/// # 返回
+///
+/// 已确认送达并删除的条目数
+async fn flush_outbox(
+    outbox: &OutboxManager,
+    transport_manager: &TransportManager,
+    delivery_tracker: &DeliveryTracker,
+    ack_timeout: Duration,
+    device_id: &str,
+    my_device_id: &str,
+) -> usize {
+    let Some(_guard) = outbox.begin_flush(device_id) else {
+        tracing::debug!(device_id = %device_id, "Outbox flush already in progress");
+        return 0;
+    };
+
+    let entries = match outbox.pending(device_id) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!(device_id = %device_id, error = %e, "Failed to read outbox");
+            return 0;
+        }
+    };
+
+    // 先全部发出，再统一等待 ACK
+    let mut in_flight = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let msg = Message::clipboard_sync(&entry.content, my_device_id.to_string());
+        let waiter = delivery_tracker.register(device_id, msg.message_id);
+        if let Err(e) = transport_manager.send_to_device(device_id, &msg).await {
+            tracing::warn!(device_id = %device_id, error = %e, "Failed to flush outbox, keeping remaining clips");
+            break;
+        }
+        in_flight.push((entry.id, waiter));
+    }
 
-            // 创建网络服务
-            let mut network_services = NetworkServices::new(tls_cert);
-            network_services.server_port = server_port;
-            network_services.mdns_advertiser = Some(mdns_advertiser);
-            network_services.mdns_discovery = Some(mdns_discovery);
+    let deadline = tokio::time::Instant::now() + ack_timeout;
+    let mut flushed = 0;
+    for (entry_id, waiter) in in_flight {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if let Err(e) = waiter.wait(remaining).await {
+            tracing::debug!(
+                device_id = %device_id,
+                message_id = waiter.message_id(),
+                error = %e,
+                "Outbox clip not acknowledged, keeping it"
+            );
+            continue;
+        }
+        if let Err(e) = outbox.remove(entry_id) {
+            tracing::warn!(device_id = %device_id, error = %e, "Failed to remove flushed outbox entry");
+        }
+        flushed += 1;
+    }
 
-            // 创建 WiFi 传输监听器
-            let wifi_listener = WifiTransportListener::new(tcp_server);
-            let wifi_listener = Arc::new(wifi_listener);
-
-            // 创建 accept 任务
-            let network_for_accept = self.network.clone();
-            let callback_for_accept = self.callback.clone();
-            let state_for_accept = self.state.clone();
-            let _my_device_id_for_accept = self.device_id.clone();
-            let wifi_listener_for_accept = wifi_listener.clone();
-
-            let accept_task = tokio::spawn(async move {
-                tracing::info!("Accept task started");
-                loop {
-                    match wifi_listener_for_accept.accept().await {
-                        Ok(transport) => {
-                            let peer_id = transport.peer_device_id().to_string();
-                            tracing::info!(peer = %peer_id, "Incoming connection accepted");
-
-                            // 使用 peer 地址作为临时标识，收到 PairingRequest 后会更新为真实设备 ID
-                            let temp_device_id = peer_id.clone();
-
-                            // 将 transport 添加到 TransportManager
-                            {
-                                let mut network = network_for_accept.lock().await;
-                                if let Some(ref mut services) = *network {
-                                    services.transport_manager.add_transport(&temp_device_id, transport.clone()).await;
-                                }
-                            }
-
-                            // 启动接收任务
-                            let device_id_for_recv = temp_device_id.clone();
-                            let callback_for_recv = callback_for_accept.clone();
-                            let state_for_recv = state_for_accept.clone();
-                            let network_for_recv = network_for_accept.clone();
-                            let transport_for_recv = transport;
-
-                            let recv_task = tokio::spawn(async move {
-                                let mut actual_device_id = device_id_for_recv.clone();
-                                tracing::info!(device_id = %device_id_for_recv, "Receive task started");
-                                loop {
-                                    match transport_for_recv.recv().await {
-                                        Ok(message) => {
-                                            tracing::debug!(
-                                                device_id = %actual_device_id,
-                                                msg_type = ?message.msg_type,
-                                                from = %message.device_id,
-                                                "Message received"
-                                            );
-
-                                            match message.msg_type {
-                                                MessageType::ClipboardSync => {
-                                                    tracing::info!(
-                                                        from = %message.device_id,
-                                                        size = message.payload.len(),
-                                                        "Clipboard received"
-                                                    );
-                                                    callback_for_recv.on_clipboard_received(
-                                                        &message.payload,
-                                                        &message.device_id,
-                                                    );
-                                                }
-                                                MessageType::PairingRequest => {
-                                                    // 解析配对请求载荷
-                                                    match PairingPayload::deserialize(&message.payload) {
-                                                        Ok(payload) => {
-                                                            tracing::info!(
-                                                                from_id = %payload.device_id,
-                                                                from_name = %payload.device_name,
-                                                                platform = ?payload.platform,
-                                                                "PairingRequest received"
-                                                            );
-
-                                                            // 自动双向配对
-                                                            let is_new_device = {
-                                                                let state = state_for_recv.read().unwrap();
-                                                                !state.paired_devices.contains_key(&payload.device_id)
-                                                            };
-
-                                                            if is_new_device {
-                                                                tracing::info!(
-                                                                    from_id = %payload.device_id,
-                                                                    from_name = %payload.device_name,
-                                                                    "Auto-pairing new device (mutual pairing)"
-                                                                );
-                                                            }
-
-                                                            // 创建设备信息
-                                                            let device = DeviceInfo::new(
-                                                                payload.device_id.clone(),
-                                                                payload.device_name.clone(),
-                                                            )
-                                                            .with_platform(protocol_platform_to_device(payload.platform))
-                                                            .with_status(DeviceStatus::Connected);
-
-                                                            let old_device_id = device_id_for_recv.clone();
-                                                            let new_device_id = payload.device_id.clone();
-
-                                                            // 更新配对设备状态
-                                                            {
-                                                                let mut state = state_for_recv.write().unwrap();
-                                                                state.paired_devices.insert(new_device_id.clone(), device.clone());
-                                                            }
-
-                                                            // 更新 TransportManager 中的设备 ID 映射
-                                                            {
-                                                                let mut network = network_for_recv.lock().await;
-                                                                if let Some(ref mut services) = *network {
-                                                                    // 获取旧 ID 的 transport 并重新添加到新 ID
-                                                                    let transports = services.transport_manager.get_transports(&old_device_id).await;
-                                                                    for t in transports {
-                                                                        services.transport_manager.add_transport(&new_device_id, t).await;
-                                                                    }
-                                                                    services.transport_manager.remove_device(&old_device_id).await;
-
-                                                                    // 更新 recv_tasks 映射
-                                                                    if let Some(task_handle) = services.recv_tasks.remove(&old_device_id) {
-                                                                        services.recv_tasks.insert(new_device_id.clone(), task_handle);
-                                                                    }
-
-                                                                    tracing::info!(
-                                                                        old_id = %old_device_id,
-                                                                        new_id = %new_device_id,
-                                                                        "Connection remapped to real device ID"
-                                                                    );
-                                                                }
-                                                            }
-
-                                                            actual_device_id = new_device_id;
-                                                            callback_for_recv.on_device_connected(&device);
-                                                        }
-                                                        Err(e) => {
-                                                            tracing::warn!(
-                                                                error = %e,
-                                                                "Failed to deserialize PairingRequest payload"
-                                                            );
-                                                        }
-                                                    }
-                                                }
-                                                MessageType::Heartbeat => {
-                                                    tracing::debug!(from = %message.device_id, "Heartbeat received");
-                                                }
-                                                MessageType::Ack => {
-                                                    tracing::debug!(from = %message.device_id, "Ack received");
-                                                }
-                                                MessageType::Unpair => {
-                                                    tracing::info!(
-                                                        from = %message.device_id,
-                                                        "Unpair notification received, removing device"
-                                                    );
-                                                    {
-                                                        let mut state = state_for_recv.write().unwrap();
-                                                        state.paired_devices.remove(&message.device_id);
-                                                    }
-                                                    callback_for_recv.on_device_unpaired(&message.device_id);
-                                                    break;
-                                                }
-                                                _ => {
-                                                    tracing::debug!(
-                                                        msg_type = ?message.msg_type,
-                                                        "Unhandled message type"
-                                                    );
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            tracing::info!(device_id = %actual_device_id, error = %e, "Connection closed or error");
-                                            break;
-                                        }
-                                    }
-                                }
-                                tracing::info!(device_id = %actual_device_id, "Receive task ended");
-                            });
-
-                            // 存储接收任务
-                            {
-                                let mut network = network_for_accept.lock().await;
-                                if let Some(ref mut services) = *network {
-                                    services.recv_tasks.insert(temp_device_id.clone(), RecvTaskHandle { task: recv_task });
-                                    tracing::info!(device_id = %temp_device_id, "Connection stored");
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            tracing::warn!(error = %e, "Failed to accept connection");
-                        }
+    if flushed > 0 {
+        tracing::info!(device_id = %device_id, count = flushed, "Flushed outbox");
+    }
+    flushed
+}
+
+/// 启动接受入站连接的任务
+///
+/// 每个入站连接创建一个 Incoming 会话，收到 PairingRequest 后更新为真实设备 ID。
+fn spawn_accept_task(ctx: SessionContext, listener: Arc<dyn TransportListener>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        tracing::info!(channel = %listener.channel(), "Accept task started");
+        loop {
+            match listener.accept().await {
+                Ok(transport) => {
+                    let peer_id = transport.peer_device_id().to_string();
+                    tracing::info!(peer = %peer_id, channel = %transport.channel(), "Incoming connection accepted");
+
+                    // 使用 peer 地址作为临时标识，收到 PairingRequest 后会更新为真实设备 ID
+                    let session = Arc::new(Session::new(peer_id, transport, SessionDirection::Incoming));
+                    let _ = session.transition(SessionState::Handshaking);
+                    if ctx.register(&session).await {
+                        ctx.spawn_tasks(&session);
                     }
                 }
-            });
-
-            network_services.accept_task = Some(accept_task);
-
-            // 存储网络服务
-            {
-                let mut network = self.network.lock().await;
-                *network = Some(network_services);
+                Err(TransportError::ConnectionClosed) => {
+                    tracing::info!(channel = %listener.channel(), "Listener closed, accept task stopped");
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to accept connection");
+                }
             }
         }
+    })
+}
 
-        self.running.store(true, Ordering::Release);
-
-        // 设置初始通道
-        {
-            let mut state = self.state.write().unwrap();
-            if self.config.wifi_enabled() {
-                state.current_channel = Some(Channel::Wifi);
-            } else if self.config.ble_enabled() {
-                state.current_channel = Some(Channel::Ble);
+/// 启动距离读数超时检查任务
+fn spawn_proximity_task(
+    proximity: Arc<ProximityMonitor>,
+    callback: Arc<dyn NearClipCallback>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PROXIMITY_EXPIRE_INTERVAL);
+        loop {
+            interval.tick().await;
+            for device_id in proximity.expire() {
+                tracing::info!(device_id = %device_id, "Device left proximity (no BLE readings)");
+                callback.on_proximity_left(&device_id);
             }
         }
+    })
+}
 
-        tracing::info!("NearClipManager started");
-        Ok(())
-    }
+// ============================================================
+// SessionContext - 会话处理上下文
+// ============================================================
 
-    /// 停止服务
-    ///
-    /// 停止所有后台服务，断开所有连接。
-    pub async fn stop(&self) {
-        if !self.running.load(Ordering::Acquire) {
-            tracing::warn!("Manager not running");
-            return;
+/// 会话处理上下文
+///
+/// 接收任务、心跳任务和管理器方法共用的依赖集合，所有连接都通过它
+/// 完成握手、消息分发和关闭清理。
+#[derive(Clone)]
+struct SessionContext {
+    /// 本设备 ID
+    my_device_id: String,
+    /// 本设备名称
+    device_name: String,
+    /// 本设备的 BLE 广播密钥（随 PairingRequest 发给对端）
+    advertisement_key: Option<AdvertisementKey>,
+    /// 回调
+    callback: Arc<dyn NearClipCallback>,
+    /// 管理器内部状态
+    state: Arc<RwLock<ManagerState>>,
+    /// 网络服务
+    network: Arc<TokioMutex<Option<NetworkServices>>>,
+    /// 会话表
+    sessions: Arc<SessionRegistry>,
+    /// 消息去重
+    message_guard: Arc<LoopGuard>,
+    /// 送达跟踪
+    delivery_tracker: Arc<DeliveryTracker>,
+    /// 通道质量指标
+    channel_monitor: Arc<ChannelMonitor>,
+    /// 心跳间隔
+    heartbeat_interval: Duration,
+}
+
+impl SessionContext {
+    /// 获取传输管理器（网络服务未启动时返回 None）
+    async fn transport_manager(&self) -> Option<Arc<TransportManager>> {
+        let network = self.network.lock().await;
+        network.as_ref().map(|services| services.transport_manager.clone())
+    }
+
+    /// 连接的发起方设备 ID，用于双方同时连接时的取舍
+    fn initiator<'a>(&'a self, session: &'a Session, device_id: &'a str) -> Option<&'a str> {
+        match session.direction() {
+            SessionDirection::Outgoing => Some(&self.my_device_id),
+            SessionDirection::Incoming => Some(device_id),
+            SessionDirection::External => None,
         }
+    }
 
-        tracing::info!("Stopping NearClipManager");
+    /// 同一设备同一通道已有会话时，新会话是否取代旧会话
+    ///
+    /// 双方同时发起连接时保留发起方设备 ID 较小的一条，两端得出相同结论；
+    /// 其余情况（重连、BLE 等平台层连接）保留新连接。
+    fn should_replace(&self, existing: &Session, new: &Session) -> bool {
+        let device_id = new.device_id();
+        match (self.initiator(existing, &device_id), self.initiator(new, &device_id)) {
+            (Some(old), Some(new)) if old != new => new < old,
+            _ => true,
+        }
+    }
 
-        // 停止网络服务
-        {
-            let mut network = self.network.lock().await;
-            if let Some(ref mut services) = *network {
-                // 1. 停止后台任务
-                if let Some(handle) = services.accept_task.take() {
-                    handle.abort();
-                    tracing::debug!("Accept task stopped");
-                }
-                if let Some(handle) = services.discovery_task.take() {
-                    handle.abort();
-                    tracing::debug!("Discovery task stopped");
-                }
+    /// 注册会话并把传输加入传输管理器
+    ///
+    /// # 返回
+    ///
+    /// 会话被保留时返回 true；与已有连接冲突而被关闭时返回 false。
+    async fn register(&self, session: &Arc<Session>) -> bool {
+        let device_id = session.device_id();
+        if let Some(existing) = self.sessions.get(&device_id, session.channel()) {
+            if !Arc::ptr_eq(&existing, session)
+                && existing.state().is_open()
+                && !self.should_replace(&existing, session)
+            {
+                tracing::info!(
+                    device_id = %device_id,
+                    channel = ?session.channel(),
+                    "Duplicate connection, keeping existing session"
+                );
+                self.close_session(session, CloseReason::Replaced).await;
+                return false;
+            }
+        }
+
+        let replaced = self.sessions.insert(session.clone());
+        if let Some(transport_manager) = self.transport_manager().await {
+            transport_manager.add_transport(&device_id, session.transport()).await;
+        }
+        if let Some(old) = replaced {
+            if !Arc::ptr_eq(&old, session) {
+                self.close_session(&old, CloseReason::Replaced).await;
+            }
+        }
+        true
+    }
 
-                // 2. 停止所有接收任务并关闭连接
-                for (device_id, task_handle) in services.recv_tasks.drain() {
-                    task_handle.task.abort();
-                    tracing::debug!(device_id = %device_id, "Receive task aborted");
+    /// 启动接收任务和心跳任务
+    fn spawn_tasks(&self, session: &Arc<Session>) {
+        let ctx = self.clone();
+        let session_for_recv = session.clone();
+        session.attach_recv_task(tokio::spawn(async move {
+            ctx.run_recv_loop(session_for_recv).await;
+        }));
+
+        let ctx = self.clone();
+        let session_for_heartbeat = session.clone();
+        session.attach_heartbeat_task(tokio::spawn(async move {
+            ctx.run_heartbeat(session_for_heartbeat).await;
+        }));
+    }
+
+    /// 接收循环
+    async fn run_recv_loop(self, session: Arc<Session>) {
+        let transport = session.transport();
+        tracing::info!(device_id = %session.device_id(), direction = ?session.direction(), "Receive task started");
+        loop {
+            let message = match transport.recv().await {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::info!(device_id = %session.device_id(), error = %e, "Connection closed or error");
+                    self.close_session(&session, CloseReason::RemoteClosed).await;
+                    break;
                 }
+            };
+            session.record_received();
 
-                // 3. 关闭 TransportManager 中的所有连接
-                services.transport_manager.close_all().await;
-                tracing::debug!("All transports closed");
+            if let Some(reason) = self.handle_message(&session, message).await {
+                self.close_session(&session, reason).await;
+                break;
+            }
+            if !session.state().is_open() {
+                break;
+            }
+        }
+        tracing::info!(device_id = %session.device_id(), "Receive task ended");
+    }
 
-                // 4. 停止 mDNS 发现
-                if let Some(ref mut discovery) = services.mdns_discovery {
-                    if let Err(e) = discovery.stop().await {
-                        tracing::warn!(error = %e, "Failed to stop mDNS discovery");
+    /// 处理一条消息，需要关闭会话时返回关闭原因
+    async fn handle_message(&self, session: &Arc<Session>, message: Message) -> Option<CloseReason> {
+        tracing::debug!(
+            device_id = %session.device_id(),
+            msg_type = ?message.msg_type,
+            from = %message.device_id,
+            "Message received"
+        );
+
+        match message.msg_type {
+            MessageType::ClipboardSync => {
+                // 重复到达的消息也要回复 ACK（发送方可能在重发）
+                if message.has_message_id() {
+                    let ack = Message::ack_for(message.message_id, self.my_device_id.clone());
+                    if let Err(e) = session.transport().send(&ack).await {
+                        tracing::warn!(error = %e, "Failed to send ack");
                     }
-                    tracing::debug!("mDNS discovery stopped");
                 }
-
-                // 5. 停止 mDNS 广播
-                if let Some(ref mut advertiser) = services.mdns_advertiser {
-                    if let Err(e) = advertiser.stop().await {
-                        tracing::warn!(error = %e, "Failed to stop mDNS advertiser");
+                if self.message_guard.record_message_id(&message.device_id, message.message_id) {
+                    tracing::info!(
+                        from = %message.device_id,
+                        size = message.payload.len(),
+                        "Clipboard received"
+                    );
+                    self.callback.on_clipboard_received(&message.payload, &message.device_id);
+                }
+                None
+            }
+            MessageType::PairingRequest => {
+                if session.direction() == SessionDirection::Incoming
+                    && session.state() == SessionState::Handshaking
+                {
+                    self.complete_handshake(session, &message).await;
+                } else {
+                    tracing::debug!(device_id = %session.device_id(), "Ignoring PairingRequest outside handshake");
+                }
+                None
+            }
+            MessageType::PairingRejection => {
+                let reason = String::from_utf8_lossy(&message.payload).to_string();
+                tracing::warn!(
+                    from = %message.device_id,
+                    reason = %reason,
+                    "Pairing rejected by remote device"
+                );
+                Some(CloseReason::PairingRejected(reason))
+            }
+            MessageType::Unpair => {
+                tracing::info!(from = %message.device_id, "Unpair notification received from remote");
+                Some(CloseReason::Unpaired)
+            }
+            MessageType::Heartbeat => {
+                tracing::debug!(from = %message.device_id, "Heartbeat received");
+                if message.has_message_id() {
+                    let ack = Message::ack_for(message.message_id, self.my_device_id.clone());
+                    if let Err(e) = session.transport().send(&ack).await {
+                        tracing::warn!(error = %e, "Failed to send heartbeat ack");
                     }
-                    tracing::debug!("mDNS advertiser stopped");
                 }
+                None
+            }
+            MessageType::Ack => {
+                tracing::debug!(from = %message.device_id, "Ack received");
+                if let Some(acked_id) = message.acked_message_id() {
+                    match session.on_heartbeat_ack(acked_id) {
+                        Some(rtt) => {
+                            self.channel_monitor.record_rtt(session.channel(), rtt);
+                            self.channel_monitor
+                                .record_device_rtt(&message.device_id, session.channel(), rtt);
+                        }
+                        None => {
+                            self.delivery_tracker.acknowledge(&message.device_id, acked_id);
+                        }
+                    }
+                }
+                None
+            }
+            _ => {
+                tracing::debug!(msg_type = ?message.msg_type, "Unhandled message type");
+                None
             }
-            *network = None;
         }
+    }
 
-        // 断开所有设备（更新状态）
-        // 注意：先收集需要回调的设备 ID，释放锁后再调用回调，避免死锁
-        let disconnected_ids: Vec<String> = {
-            let mut state = self.state.write().unwrap();
-            let ids: Vec<String> = state
-                .paired_devices
-                .iter_mut()
-                .filter_map(|(device_id, device)| {
-                    if device.status().is_connected() {
-                        device.set_status(DeviceStatus::Disconnected);
-                        Some(device_id.clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            state.current_channel = None;
-            ids
+    /// 处理对端发起连接的 PairingRequest，完成握手
+    ///
+    /// 把会话从临时 ID（对端地址）改为真实设备 ID，更新配对设备，
+    /// 并在会话进入 `Active` 后回调 `on_device_connected`。
+    async fn complete_handshake(&self, session: &Arc<Session>, message: &Message) {
+        let payload = match PairingPayload::deserialize(&message.payload) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to deserialize PairingRequest payload");
+                return;
+            }
         };
 
-        // 在锁外调用回调
-        for device_id in disconnected_ids {
-            self.callback.on_device_disconnected(&device_id);
+        tracing::info!(
+            from_id = %payload.device_id,
+            from_name = %payload.device_name,
+            platform = ?payload.platform,
+            "PairingRequest received"
+        );
+
+        // 自动双向配对（保留已有的通道偏好）
+        let device = DeviceInfo::new(payload.device_id.clone(), payload.device_name.clone())
+            .with_platform(protocol_platform_to_device(payload.platform))
+            .with_status(DeviceStatus::Connected)
+            .with_advertisement_key(peer_advertisement_key(&payload));
+        let (device, key_changed) = {
+            let mut state = self.state.write().unwrap();
+            let (device, key_changed) = match state.paired_devices.get(&payload.device_id) {
+                Some(existing) => {
+                    let mut device = device
+                        .with_channel_preference(existing.channel_preference())
+                        .with_proximity_thresholds(existing.proximity_thresholds());
+                    device.inherit_persisted(existing);
+                    let key_changed = device.advertisement_key() != existing.advertisement_key();
+                    (device, key_changed)
+                }
+                None => {
+                    tracing::info!(
+                        from_id = %payload.device_id,
+                        from_name = %payload.device_name,
+                        "Auto-pairing new device (mutual pairing)"
+                    );
+                    let key_changed = device.advertisement_key().is_some();
+                    (device, key_changed)
+                }
+            };
+            state.paired_devices.insert(payload.device_id.clone(), device.clone());
+            (device, key_changed)
+        };
+        if key_changed {
+            // 新的广播密钥交给平台层持久化
+            self.callback.on_device_updated(&device);
         }
 
-        self.running.store(false, Ordering::Release);
+        // 临时 ID 改为真实设备 ID
+        let old_device_id = session.device_id();
+        self.sessions.remove(session);
+        if let Some(transport_manager) = self.transport_manager().await {
+            transport_manager.remove_transport(&old_device_id, session.channel()).await;
+            transport_manager
+                .set_device_preference(device.id(), device.channel_preference())
+                .await;
+        }
+        session.set_device_id(device.id());
+        tracing::info!(
+            old_id = %old_device_id,
+            new_id = %device.id(),
+            "Connection remapped to real device ID"
+        );
 
-        tracing::info!("NearClipManager stopped");
+        if !self.register(session).await {
+            return;
+        }
+        // 握手完成后解除认证前的消息长度限制
+        session.transport().mark_authenticated();
+        if session.transition(SessionState::Active).is_ok() {
+            self.callback.on_device_connected(&device);
+        }
     }
 
-    /// 同步剪贴板内容
+    /// 心跳循环
     ///
-    /// 将剪贴板内容发送到所有已连接设备。
-    ///
-    /// # 参数
+    /// 活跃会话每个间隔发送一次心跳；连续 `HEARTBEAT_MISS_LIMIT` 个间隔
+    /// 没有收到任何消息（包括握手阶段）则关闭会话。
+    async fn run_heartbeat(self, session: Arc<Session>) {
+        let interval = self.heartbeat_interval;
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            if !session.state().is_open() {
+                break;
+            }
+            if session.idle_for() >= interval * HEARTBEAT_MISS_LIMIT {
+                tracing::warn!(device_id = %session.device_id(), "Heartbeat timeout");
+                self.close_session(&session, CloseReason::HeartbeatTimeout).await;
+                break;
+            }
+            if session.state() == SessionState::Active {
+                let heartbeat = session.next_heartbeat(&self.my_device_id);
+                if let Err(e) = session.transport().send(&heartbeat).await {
+                    tracing::warn!(device_id = %session.device_id(), error = %e, "Failed to send heartbeat");
+                    self.close_session(&session, CloseReason::SendFailed).await;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// 关闭会话
     ///
-    /// * `content` - 剪贴板内容
+    /// 所有断开路径的唯一出口：从会话表和传输管理器移除、关闭传输、
+    /// 终止后台任务，并按关闭原因最多触发一次回调。调用方不能持有网络锁。
     ///
-    /// # 错误
+    /// # 返回
     ///
-    /// - 管理器未运行
-    /// - 没有可用通道
-    /// - 发送失败
-    pub async fn sync_clipboard(&self, content: &[u8]) -> Result<()> {
-        tracing::info!(content_size = content.len(), "sync_clipboard called");
+    /// 是否触发了 `on_device_disconnected`
+    async fn close_session(&self, session: &Arc<Session>, reason: CloseReason) -> bool {
+        let previous = match session.begin_drain() {
+            Some(previous) => previous,
+            None => return false,
+        };
+        let device_id = session.device_id();
+        let channel = session.channel();
+        tracing::info!(
+            device_id = %device_id,
+            channel = ?channel,
+            reason = %reason,
+            "Closing session"
+        );
 
-        if !self.running.load(Ordering::Acquire) {
-            tracing::warn!("sync_clipboard: Manager not running");
-            return Err(NearClipError::Sync("Manager not running".to_string()));
+        // 已被新连接取代的会话不能移除新连接的传输
+        if self.sessions.remove(session) {
+            if let Some(transport_manager) = self.transport_manager().await {
+                transport_manager.remove_transport(&device_id, channel).await;
+            }
         }
-
-        tracing::debug!("sync_clipboard: Manager is running, checking channel");
-
-        let state = self.state.read().unwrap();
-        let channel = match state.current_channel {
-            Some(ch) => ch,
-            None => {
-                tracing::warn!("sync_clipboard: No channel available");
-                return Err(NearClipError::Sync("No channel available".to_string()));
+        if let Err(e) = session.transport().close().await {
+            tracing::debug!(device_id = %device_id, error = %e, "Failed to close transport");
+        }
+        session.mark_closed();
+
+        let still_connected = self.sessions.has_active(&device_id);
+        let mut disconnected = false;
+        match reason {
+            CloseReason::Replaced => {}
+            CloseReason::Unpaired => {
+                self.state.write().unwrap().paired_devices.remove(&device_id);
+                self.callback.on_device_unpaired(&device_id);
             }
-        };
-        drop(state);
-
-        tracing::debug!(channel = ?channel, "sync_clipboard: Channel found");
+            CloseReason::PairingRejected(ref rejection) => {
+                if !still_connected {
+                    self.set_disconnected(&device_id);
+                }
+                self.callback.on_pairing_rejected(&device_id, rejection);
+            }
+            _ => {
+                if previous == SessionState::Active && !still_connected {
+                    self.set_disconnected(&device_id);
+                    self.callback.on_device_disconnected(&device_id);
+                    disconnected = true;
+                }
+            }
+        }
 
-        // 创建剪贴板同步消息
-        let msg = Message::clipboard_sync(content, self.device_id.clone());
+        // 关闭流程可能运行在这些任务内部，最后再终止
+        for task in session.take_tasks() {
+            task.abort();
+        }
+        disconnected
+    }
 
-        tracing::debug!("sync_clipboard: Acquiring network lock");
+    /// 更新设备状态为断开
+    fn set_disconnected(&self, device_id: &str) {
+        let mut state = self.state.write().unwrap();
+        if let Some(device) = state.paired_devices.get_mut(device_id) {
+            device.set_status(DeviceStatus::Disconnected);
+        }
+    }
 
-        // 使用 TransportManager 广播到所有连接
+    /// 在被过滤器排除的接口上停止 mDNS 广播和发现
+    async fn apply_interface_filter(&self, filter: &InterfaceFilter, names: Vec<String>) {
+        let names: Vec<String> = names
+            .into_iter()
+            .filter(|name| !filter.allows_name(name))
+            .collect();
+        if names.is_empty() {
+            return;
+        }
         let network = self.network.lock().await;
+        let Some(ref services) = *network else {
+            return;
+        };
+        if let Some(ref advertiser) = services.mdns_advertiser {
+            if let Err(e) = advertiser.disable_interfaces(&names).await {
+                tracing::warn!(error = %e, "Failed to restrict mDNS advertising interfaces");
+            }
+        }
+        if let Some(ref discovery) = services.mdns_discovery {
+            if let Err(e) = discovery.disable_interfaces(&names) {
+                tracing::warn!(error = %e, "Failed to restrict mDNS discovery interfaces");
+            }
+        }
+    }
 
-        tracing::debug!("sync_clipboard: Network lock acquired");
-
-        if let Some(ref services) = *network {
-            let device_ids = services.transport_manager.connected_devices().await;
+    /// 按接口过滤器重新绑定 TCP/QUIC 监听器
+    ///
+    /// 关闭已不在允许接口上的监听器，并在新出现的允许地址上绑定，端口号不变。
+    async fn rebind_listeners(&self, filter: &InterfaceFilter, addresses: &[InterfaceAddress]) {
+        let mut network = self.network.lock().await;
+        let Some(ref mut services) = *network else {
+            return;
+        };
+        let Some(settings) = services.listener_settings.clone() else {
+            return;
+        };
+        let addrs = listen_addrs(filter, addresses);
 
-            if device_ids.is_empty() {
-                tracing::debug!("No active connections, skipping sync");
-                return Ok(());
+        services.listeners.retain(|addr, listener| {
+            let keep = addrs.contains(addr);
+            if !keep {
+                listener.abort();
+                tracing::info!(%addr, "Stopped listening on removed interface address");
+            }
+            keep
+        });
+        for addr in addrs {
+            if services.listeners.contains_key(&addr) {
+                continue;
+            }
+            match bind_listener(self, &settings, addr).await {
+                Ok(listener) => {
+                    services.listeners.insert(addr, listener);
+                }
+                Err(e) => tracing::warn!(%addr, error = %e, "Failed to listen on interface address"),
             }
+        }
+    }
 
-            tracing::info!(
-                content_size = content.len(),
-                channel = ?channel,
-                connection_count = device_ids.len(),
-                "Syncing clipboard"
-            );
+    /// 网络地址变化后的恢复流程
+    ///
+    /// 1. 立即重新广播 mDNS，让对端拿到新地址
+    /// 2. 向所有 WiFi 会话发送心跳，超时未回应的会话关闭（旧地址上的连接不会报错，只会挂起）
+    /// 3. 重新连接未连接的已配对设备
+    async fn recover_after_network_change(&self) {
+        tracing::info!("Recovering connections after network change");
 
-            // 使用 TransportManager 广播
-            let results = services.transport_manager.broadcast(&msg).await;
+        {
+            let network = self.network.lock().await;
+            if let Some(advertiser) = network.as_ref().and_then(|s| s.mdns_advertiser.as_ref()) {
+                if let Err(e) = advertiser.reannounce().await {
+                    tracing::warn!(error = %e, "Failed to re-announce mDNS service");
+                }
+            }
+        }
 
-            drop(network);
+        self.probe_wifi_sessions(NETWORK_CHANGE_PROBE_TIMEOUT).await;
+        self.redial_disconnected().await;
+    }
 
-            // 处理失败的设备
-            let failed_devices: Vec<String> = results
-                .into_iter()
-                .filter_map(|(device_id, result)| {
-                    if result.is_err() {
-                        tracing::error!(device_id = %device_id, "Failed to send clipboard");
-                        Some(device_id)
-                    } else {
-                        tracing::debug!(device_id = %device_id, "Sent clipboard");
-                        None
-                    }
-                })
-                .collect();
+    /// 探测 WiFi 会话是否仍然存活
+    async fn probe_wifi_sessions(&self, timeout: Duration) {
+        let sessions: Vec<Arc<Session>> = self
+            .sessions
+            .all()
+            .into_iter()
+            .filter(|s| s.channel().is_network() && s.state() == SessionState::Active)
+            .collect();
+        if sessions.is_empty() {
+            return;
+        }
 
-            // 移除失败的连接
-            if !failed_devices.is_empty() {
-                let network = self.network.lock().await;
-                if let Some(ref services) = *network {
-                    for device_id in &failed_devices {
-                        // 停止接收任务
-                        // Note: recv_tasks 需要 &mut，但我们只有 &ref，所以这里暂时跳过
-                        // 接收任务会在连接断开时自动结束
-
-                        // 从 TransportManager 移除
-                        services.transport_manager.remove_device(device_id).await;
-
-                        // 更新设备状态
-                        let mut state = self.state.write().unwrap();
-                        if let Some(device) = state.paired_devices.get_mut(device_id) {
-                            device.set_status(DeviceStatus::Disconnected);
-                        }
-                        drop(state);
-                        self.callback.on_device_disconnected(device_id);
-                    }
+        let mut probed = Vec::new();
+        for session in sessions {
+            let heartbeat = session.next_heartbeat(&self.my_device_id);
+            match session.transport().send(&heartbeat).await {
+                Ok(()) => probed.push(session),
+                Err(e) => {
+                    tracing::warn!(device_id = %session.device_id(), error = %e, "WiFi session unusable after network change");
+                    self.close_session(&session, CloseReason::SendFailed).await;
                 }
             }
-        } else {
-            tracing::warn!("sync_clipboard: No network services available");
         }
 
-        tracing::info!("sync_clipboard completed");
-        Ok(())
-    }
+        tokio::time::sleep(timeout).await;
 
-    /// 检查是否正在运行
-    pub fn is_running(&self) -> bool {
-        self.running.load(Ordering::Acquire)
+        for session in probed {
+            if session.state().is_open() && session.idle_for() >= timeout {
+                tracing::warn!(device_id = %session.device_id(), "WiFi session stale after network change");
+                self.close_session(&session, CloseReason::HeartbeatTimeout)
+                    .await;
+            }
+        }
     }
 
-    /// 获取配置
-    pub fn config(&self) -> &NearClipConfig {
-        &self.config
-    }
+    /// 重新连接所有未连接的已配对设备
+    async fn redial_disconnected(&self) {
+        let device_ids: Vec<String> = {
+            let state = self.state.read().unwrap();
+            state
+                .paired_devices
+                .values()
+                .filter(|d| d.status() == DeviceStatus::Disconnected)
+                .map(|d| d.id().to_string())
+                .collect()
+        };
 
-    /// 获取当前通道
-    pub fn current_channel(&self) -> Option<Channel> {
-        self.state.read().unwrap().current_channel
+        for device_id in device_ids {
+            match self.dial(&device_id).await {
+                Ok(()) => {
+                    tracing::info!(device_id = %device_id, "Reconnected after network change")
+                }
+                Err(e) => {
+                    tracing::debug!(device_id = %device_id, error = %e, "Reconnect after network change failed")
+                }
+            }
+        }
     }
 
-    // --------------------------------------------------------
-    // 设备管理
-    // --------------------------------------------------------
-
-    /// 获取已配对设备列表
-    pub fn get_paired_devices(&self) -> Vec<DeviceInfo> {
-        self.state
-            .read()
-            .unwrap()
-            .paired_devices
-            .values()
-            .cloned()
-            .collect()
-    }
-
-    /// 获取已连接设备列表
-    pub fn get_connected_devices(&self) -> Vec<DeviceInfo> {
-        self.state
-            .read()
-            .unwrap()
-            .paired_devices
-            .values()
-            .filter(|d| d.status().is_connected())
-            .cloned()
-            .collect()
-    }
-
-    /// 获取设备状态
-    pub fn get_device_status(&self, device_id: &str) -> Option<DeviceStatus> {
-        self.state
-            .read()
-            .unwrap()
-            .paired_devices
-            .get(device_id)
-            .map(|d| d.status())
-    }
-
-    /// 添加已配对设备
-    ///
-    /// 内部方法，用于添加新配对的设备。
-    pub fn add_paired_device(&self, device: DeviceInfo) {
-        let device_id = device.id().to_string();
-        tracing::info!(device_id = %device_id, "Adding paired device");
-
-        self.state
-            .write()
-            .unwrap()
-            .paired_devices
-            .insert(device_id, device);
-    }
-
-    /// 移除已配对设备
-    pub fn remove_paired_device(&self, device_id: &str) -> Option<DeviceInfo> {
-        tracing::info!(device_id = %device_id, "Removing paired device");
-
-        self.state
-            .write()
-            .unwrap()
-            .paired_devices
-            .remove(device_id)
-    }
-
-    /// 连接设备
-    ///
-    /// 尝试连接到指定设备。
-    ///
-    /// # 参数
-    ///
-    /// * `device_id` - 设备 ID
-    ///
-    /// # 错误
-    ///
-    /// - 管理器未运行
-    /// - 设备未找到
-    /// - 连接失败
-    pub async fn connect_device(&self, device_id: &str) -> Result<()> {
-        if !self.running.load(Ordering::Acquire) {
-            return Err(NearClipError::Sync("Manager not running".to_string()));
-        }
-
-        {
-            let state = self.state.read().unwrap();
-            if !state.paired_devices.contains_key(device_id) {
-                return Err(NearClipError::DeviceNotFound(device_id.to_string()));
-            }
-        }
-
-        tracing::info!(device_id = %device_id, "Connecting to device");
+    /// 连接设备
+    ///
+    /// 依次尝试 mDNS 发现的地址、最后一次成功的地址和静态地址，
+    /// 连接成功后建立会话并发送 PairingRequest。
+    async fn dial(&self, device_id: &str) -> Result<()> {
+        tracing::info!(device_id = %device_id, "Connecting to device");
 
         // 设置为连接中
         {
@@ -909,168 +1190,127 @@ impl NearClipManager {
             }
         }
 
-        // 从 mDNS 发现中获取设备地址
-        tracing::debug!(device_id = %device_id, "Looking up device in mDNS discovery");
-        let discovered_device = {
+        // 候选地址：mDNS 结果、最后一次成功的地址、静态地址
+        let candidates = self.candidate_addresses(device_id).await;
+        let relay_connector = {
             let network = self.network.lock().await;
-            tracing::debug!(device_id = %device_id, has_network = network.is_some(), "Got network lock");
-            if let Some(ref services) = *network {
-                if let Some(ref discovery) = services.mdns_discovery {
-                    tracing::debug!(device_id = %device_id, "Calling discovery.get_device");
-                    let result = discovery.get_device(device_id).await;
-                    tracing::debug!(device_id = %device_id, found = result.is_some(), "Discovery lookup complete");
-                    result
-                } else {
-                    tracing::warn!(device_id = %device_id, "No mDNS discovery service");
-                    None
-                }
-            } else {
-                tracing::warn!(device_id = %device_id, "No network services");
-                None
-            }
+            network
+                .as_ref()
+                .and_then(|services| services.relay_connector.clone())
         };
-
-        let discovered = match discovered_device {
-            Some(d) => d,
-            None => {
-                // 设备未在网络上发现，重置状态
-                let mut state = self.state.write().unwrap();
-                if let Some(device) = state.paired_devices.get_mut(device_id) {
-                    device.set_status(DeviceStatus::Disconnected);
-                }
-                return Err(NearClipError::Network(format!(
-                    "Device {} not discovered on network", device_id
-                )));
-            }
-        };
-
-        // 获取设备的可用地址，优先使用 IPv4
-        // 因为 IPv6 链路本地地址 (fe80::) 在跨设备连接时需要 scope_id
-        let addr = {
-            use std::net::IpAddr;
-
-            // 优先选择 IPv4 地址
-            let ipv4_addr = discovered.addresses.iter()
-                .find(|a| matches!(a, IpAddr::V4(_)));
-
-            // 如果没有 IPv4，尝试找非链路本地的 IPv6 地址
-            let non_link_local_v6 = discovered.addresses.iter()
-                .find(|a| match a {
-                    IpAddr::V6(v6) => !v6.is_loopback() && !is_link_local_v6(v6),
-                    _ => false,
-                });
-
-            ipv4_addr.or(non_link_local_v6).or_else(|| discovered.addresses.iter().next())
-                .ok_or_else(|| {
-                    NearClipError::Network(format!("No address found for device {}", device_id))
-                })?
-        };
-
-        let socket_addr = SocketAddr::new(*addr, discovered.port);
-        tracing::debug!(device_id = %device_id, addr = %socket_addr, "Connecting to device");
+        if candidates.is_empty() && relay_connector.is_none() {
+            self.reset_connecting_status(device_id);
+            return Err(NearClipError::Network(format!(
+                "Device {} not discovered on network and has no static address",
+                device_id
+            )));
+        }
 
         // 创建 TLS 客户端配置
         // TODO: 实现 TOFU 模型 - 配对时保存对端证书并在连接时验证
         // 目前使用不验证证书的配置用于测试
-        let tls_client_config = TlsClientConfig::new_insecure()
-            .map_err(|e| NearClipError::Network(format!("Failed to create TLS client config: {}", e)))?;
-
-        // 建立 TLS 连接
-        let client_config = TcpClientConfig::new(socket_addr);
-        let conn = TcpClient::connect(client_config, tls_client_config.config(), "nearclip.local")
-            .await
-            .map_err(|e| {
-                // 连接失败，重置状态
-                let mut state = self.state.write().unwrap();
-                if let Some(device) = state.paired_devices.get_mut(device_id) {
-                    device.set_status(DeviceStatus::Disconnected);
-                }
-                NearClipError::Network(format!("Failed to connect to device {}: {}", device_id, e))
-            })?;
-
-        tracing::info!(device_id = %device_id, "Connected to device");
+        let tls_client_config = TlsClientConfig::new_insecure().map_err(|e| {
+            NearClipError::Network(format!("Failed to create TLS client config: {}", e))
+        })?;
 
-        // 创建 WifiTransport
-        let transport = Arc::new(WifiTransport::new(device_id.to_string(), conn));
-        let transport_for_recv = transport.clone();
+        let quic_connector = {
+            let network = self.network.lock().await;
+            network
+                .as_ref()
+                .and_then(|services| services.quic_connector.clone())
+        };
 
-        let device_id_for_recv = device_id.to_string();
-        let callback_for_recv = self.callback.clone();
+        // 依次尝试候选地址（启用 QUIC 时先尝试 QUIC），建立 TLS 连接
+        let mut last_error = String::new();
+        let mut connected: Option<(Option<SocketAddr>, Arc<dyn Transport>)> = None;
+        for socket_addr in candidates {
+            if let Some(quic) = &quic_connector {
+                match quic.connect(device_id, &socket_addr.to_string()).await {
+                    Ok(transport) => {
+                        connected = Some((Some(socket_addr), transport));
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::debug!(device_id = %device_id, addr = %socket_addr, error = %e, "QUIC unavailable, trying TCP");
+                    }
+                }
+            }
 
-        // 启动接收任务
-        let recv_task = tokio::spawn(async move {
-            tracing::info!(device_id = %device_id_for_recv, "Receive task started (outgoing connection)");
-            loop {
-                match transport_for_recv.recv().await {
-                    Ok(message) => {
-                        tracing::debug!(
-                            device_id = %device_id_for_recv,
-                            msg_type = ?message.msg_type,
-                            from = %message.device_id,
-                            "Message received"
-                        );
+            tracing::debug!(device_id = %device_id, addr = %socket_addr, "Connecting to device");
+            // 提供多路复用 ALPN；不支持的旧设备会回退到长度前缀帧
+            let client_config = TcpClientConfig::new(socket_addr).with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
+            match TcpClient::connect(client_config, tls_client_config.config(), "nearclip.local")
+                .await
+            {
+                Ok(conn) => {
+                    connected = Some((
+                        Some(socket_addr),
+                        Arc::new(WifiTransport::new(device_id.to_string(), conn)),
+                    ));
+                    break;
+                }
+                Err(e) => {
+                    tracing::debug!(device_id = %device_id, addr = %socket_addr, error = %e, "Address unreachable");
+                    last_error = e.to_string();
+                }
+            }
+        }
 
-                        match message.msg_type {
-                            MessageType::ClipboardSync => {
-                                tracing::info!(
-                                    from = %message.device_id,
-                                    size = message.payload.len(),
-                                    "Clipboard received"
-                                );
-                                callback_for_recv.on_clipboard_received(
-                                    &message.payload,
-                                    &message.device_id,
-                                );
-                            }
-                            MessageType::PairingRejection => {
-                                let reason = String::from_utf8_lossy(&message.payload).to_string();
-                                tracing::warn!(
-                                    from = %message.device_id,
-                                    reason = %reason,
-                                    "Pairing rejected by remote device"
-                                );
-                                callback_for_recv.on_pairing_rejected(&message.device_id, &reason);
-                                break;
-                            }
-                            MessageType::Unpair => {
-                                tracing::info!(
-                                    from = %message.device_id,
-                                    "Unpair notification received from remote"
-                                );
-                                callback_for_recv.on_device_unpaired(&message.device_id);
-                                break;
-                            }
-                            MessageType::Heartbeat => {
-                                tracing::debug!(from = %message.device_id, "Heartbeat received");
-                            }
-                            MessageType::Ack => {
-                                tracing::debug!(from = %message.device_id, "Ack received");
-                            }
-                            _ => {
-                                tracing::debug!(
-                                    msg_type = ?message.msg_type,
-                                    "Unhandled message type"
-                                );
-                            }
-                        }
-                    }
+        // 本地地址都不可达时最后尝试中继
+        if connected.is_none() {
+            if let Some(relay) = &relay_connector {
+                match relay.connect(device_id, "").await {
+                    Ok(transport) => connected = Some((None, transport)),
                     Err(e) => {
-                        tracing::info!(device_id = %device_id_for_recv, error = %e, "Connection closed or error");
-                        break;
+                        tracing::debug!(device_id = %device_id, error = %e, "Relay unavailable");
+                        last_error = e.to_string();
                     }
                 }
             }
-            tracing::info!(device_id = %device_id_for_recv, "Receive task ended");
-        });
+        }
 
-        // 保存连接到 TransportManager
-        {
-            let mut network = self.network.lock().await;
-            if let Some(ref mut services) = *network {
-                services.transport_manager.add_transport(device_id, transport.clone()).await;
-                services.recv_tasks.insert(device_id.to_string(), RecvTaskHandle { task: recv_task });
+        let (socket_addr, transport) = match connected {
+            Some(connected) => connected,
+            None => {
+                // 连接失败，重置状态
+                self.reset_connecting_status(device_id);
+                return Err(NearClipError::Network(format!(
+                    "Failed to connect to device {}: {}",
+                    device_id, last_error
+                )));
+            }
+        };
+
+        // 记住成功的地址，mDNS 找不到设备时回退使用（见 `candidate_addresses`）
+        let updated_device = {
+            let mut state = self.state.write().unwrap();
+            match (socket_addr, state.paired_devices.get_mut(device_id)) {
+                (Some(addr), Some(device)) if device.last_known_address() != Some(addr) => {
+                    device.set_last_known_address(Some(addr));
+                    Some(device.clone())
+                }
+                _ => None,
             }
+        };
+        if let Some(device) = updated_device {
+            self.callback.on_device_updated(&device);
+        }
+
+        tracing::info!(device_id = %device_id, addr = ?socket_addr, channel = %transport.channel(), "Connected to device");
+
+        // 创建会话
+        let session = Arc::new(Session::new(
+            device_id,
+            transport.clone(),
+            SessionDirection::Outgoing,
+        ));
+        let _ = session.transition(SessionState::Handshaking);
+
+        if !self.register(&session).await {
+            // 对端同时发起的连接已被保留
+            return Ok(());
         }
+        self.spawn_tasks(&session);
 
         // 发送 PairingRequest，告诉对方自己的设备信息
         {
@@ -1083,14 +1323,18 @@ impl NearClipManager {
                 ProtocolPlatform::Unknown
             };
 
-            let pairing_payload = PairingPayload::new(
-                self.device_id.clone(),
-                self.config.device_name().to_string(),
+            let mut pairing_payload = PairingPayload::new(
+                self.my_device_id.clone(),
+                self.device_name.clone(),
                 my_platform,
             );
+            if let Some(ref key) = self.advertisement_key {
+                pairing_payload = pairing_payload.with_advertisement_key(key.as_bytes());
+            }
 
             if let Ok(payload_bytes) = pairing_payload.serialize() {
-                let pairing_msg = Message::pairing_request(payload_bytes, self.device_id.clone());
+                let pairing_msg =
+                    Message::pairing_request(payload_bytes, self.my_device_id.clone());
 
                 if let Err(e) = transport.send(&pairing_msg).await {
                     tracing::warn!(device_id = %device_id, error = %e, "Failed to send PairingRequest");
@@ -1100,6 +1344,14 @@ impl NearClipManager {
             }
         }
 
+        // 会话在发送期间可能已被关闭
+        if session.transition(SessionState::Active).is_err() {
+            return Err(NearClipError::Network(format!(
+                "Connection to device {} closed during handshake",
+                device_id
+            )));
+        }
+
         // 更新设备状态
         // 注意：先释放写锁再调用回调，避免回调中调用 get_connected_devices 导致死锁
         let device_for_callback = {
@@ -1120,544 +1372,2642 @@ impl NearClipManager {
         Ok(())
     }
 
-    /// 断开设备连接
-    ///
-    /// # 参数
+    /// 收集设备的候选连接地址（去重，按尝试顺序排列）
     ///
-    /// * `device_id` - 设备 ID
-    pub async fn disconnect_device(&self, device_id: &str) -> Result<()> {
-        {
-            let state = self.state.read().unwrap();
-            if !state.paired_devices.contains_key(device_id) {
-                return Err(NearClipError::DeviceNotFound(device_id.to_string()));
-            }
-        }
-
-        tracing::info!(device_id = %device_id, "Disconnecting device");
+    /// 1. mDNS 发现的地址（优先 IPv4，因为 IPv6 链路本地地址 (fe80::) 跨设备连接时需要 scope_id）
+    /// 2. 最后一次连接成功的地址（可能已因 DHCP 等变化过期，因此排在实时的 mDNS 结果之后）
+    /// 3. 手动配置或从配对数据导入的静态地址
+    async fn candidate_addresses(&self, device_id: &str) -> Vec<SocketAddr> {
+        let mut candidates: Vec<SocketAddr> = Vec::new();
 
-        // 关闭连接
-        {
-            let mut network = self.network.lock().await;
-            if let Some(ref mut services) = *network {
-                // 停止接收任务
-                if let Some(task_handle) = services.recv_tasks.remove(device_id) {
-                    task_handle.task.abort();
-                    tracing::debug!(device_id = %device_id, "Receive task aborted");
+        let discovered = {
+            let network = self.network.lock().await;
+            match network
+                .as_ref()
+                .and_then(|services| services.mdns_discovery.as_ref())
+            {
+                Some(discovery) => discovery.get_device(device_id).await,
+                None => None,
+            }
+        };
+        match discovered {
+            Some(discovered) => {
+                let mut addresses: Vec<IpAddr> = discovered
+                    .addresses
+                    .iter()
+                    .copied()
+                    .filter(|a| match a {
+                        IpAddr::V6(v6) => !v6.is_loopback() && !is_link_local_v6(v6),
+                        IpAddr::V4(_) => true,
+                    })
+                    .collect();
+                addresses.sort_by_key(|a| !a.is_ipv4());
+                if addresses.is_empty() {
+                    addresses.extend(discovered.addresses.iter().next());
                 }
-                // 从 TransportManager 移除并关闭连接
-                services.transport_manager.remove_device(device_id).await;
-                tracing::debug!(device_id = %device_id, "Connection closed");
+                candidates.extend(
+                    addresses
+                        .into_iter()
+                        .map(|ip| SocketAddr::new(ip, discovered.port)),
+                );
             }
+            None => tracing::debug!(device_id = %device_id, "Device not found in mDNS discovery"),
         }
 
-        // 更新设备状态
-        {
-            let mut state = self.state.write().unwrap();
-            if let Some(device) = state.paired_devices.get_mut(device_id) {
-                device.set_status(DeviceStatus::Disconnected);
+        let (last_known, static_addresses) = {
+            let state = self.state.read().unwrap();
+            match state.paired_devices.get(device_id) {
+                Some(device) => (
+                    device.last_known_address(),
+                    device.static_addresses().to_vec(),
+                ),
+                None => (None, Vec::new()),
             }
+        };
+        candidates.extend(last_known);
+        for address in static_addresses {
+            candidates.extend(address.resolve().await);
         }
 
-        self.callback.on_device_disconnected(device_id);
+        let mut seen = std::collections::HashSet::new();
+        candidates.retain(|addr| seen.insert(*addr));
+        candidates
+    }
 
-        Ok(())
+    /// 连接失败时把设备状态从连接中恢复为断开
+    fn reset_connecting_status(&self, device_id: &str) {
+        let mut state = self.state.write().unwrap();
+        if let Some(device) = state.paired_devices.get_mut(device_id) {
+            device.set_status(DeviceStatus::Disconnected);
+        }
     }
+}
 
-    /// 取消配对设备
-    ///
-    /// 向目标设备发送取消配对通知，然后断开连接并移除配对关系。
-    /// 对方设备收到通知后也会删除本设备的配对信息。
+// ============================================================
+// NearClipManager - 核心管理器
+// ============================================================
+
+/// NearClip 核心管理器
+///
+/// 提供统一的 API 管理所有同步功能。
+///
+/// # 生命周期
+///
+/// 1. 创建 `NearClipManager::new(config, callback)`
+/// 2. 启动 `manager.start().await`
+/// 3. 同步剪贴板 `manager.sync_clipboard(content).await`
+/// 4. 停止 `manager.stop().await`
+///
+/// # 示例
+///
+/// ```
+/// use nearclip_core::{NearClipManager, NearClipConfig, NoOpCallback};
+/// use std::sync::Arc;
+///
+/// let config = NearClipConfig::new("Test Device");
+/// let callback = Arc::new(NoOpCallback);
+/// let manager = NearClipManager::new(config, callback).unwrap();
+///
+/// assert!(!manager.is_running());
+/// assert_eq!(manager.get_connected_devices().len(), 0);
+/// ```
+pub struct NearClipManager {
+    /// 配置
+    config: NearClipConfig,
+    /// 设备 ID (用于 mDNS 广播和消息标识)
+    device_id: String,
+    /// 回调
+    callback: Arc<dyn NearClipCallback>,
+    /// 运行状态
+    running: AtomicBool,
+    /// 内部状态 (Arc 包装以支持共享给后台任务)
+    state: Arc<RwLock<ManagerState>>,
+    /// 网络服务 (需要 async 访问，使用 TokioMutex，Arc 包装以支持共享给后台任务)
+    network: Arc<TokioMutex<Option<NetworkServices>>>,
+    /// 消息去重 (混合多路径发送时同一消息会从多个通道到达)
+    message_guard: Arc<LoopGuard>,
+    /// 通道质量指标 (发送结果、吞吐量、RTT、RSSI)
+    channel_monitor: Arc<ChannelMonitor>,
+    /// 基于通道质量的选择器，交给传输管理器使用
+    channel_selector: Arc<QualityChannelSelector>,
+    /// 等待 ACK 的剪贴板消息
+    delivery_tracker: Arc<DeliveryTracker>,
+    /// 离线设备的发件箱（调用 `init_outbox` 后启用）
+    outbox: Arc<RwLock<Option<Arc<OutboxManager>>>>,
+    /// 上次使用的监听端口（调用 `init_port_store` 后启用）
+    port_store: RwLock<Option<PortStore>>,
+    /// 每条连接的会话
+    sessions: Arc<SessionRegistry>,
+    /// 基于 BLE RSSI 的设备距离
+    proximity: Arc<ProximityMonitor>,
+    /// 本设备的 BLE 广播密钥（由平台层持久化后通过 `set_advertisement_key` 设置）
+    advertisement_key: RwLock<Option<AdvertisementKey>>,
+    /// 连接中继的身份（由平台层通过 `set_relay_identity` 设置）
+    relay_identity: RwLock<Option<RelayIdentity>>,
+    /// 中继客户端（已连上中继时存在）
+    relay_client: RwLock<Option<Arc<RelayClient>>>,
+}
+
+/// 通道状态回调占位实现
+///
+/// 核心管理器只使用 `ChannelMonitor` 的质量指标，状态变更由传输管理器跟踪。
+struct NoOpChannelStatusCallback;
+
+impl ChannelStatusCallback for NoOpChannelStatusCallback {
+    fn on_status_changed(&self, _channel: Channel, _old: ChannelStatus, _new: ChannelStatus) {}
+    fn on_all_channels_unavailable(&self) {}
+}
+
+impl NearClipManager {
+    /// 创建新的管理器实例
     ///
     /// # 参数
     ///
-    /// * `device_id` - 设备 ID
-    pub async fn unpair_device(&self, device_id: &str) -> Result<()> {
-        tracing::info!(device_id = %device_id, "Unpairing device");
+    /// * `config` - 配置
+    /// * `callback` - 回调实现
+    ///
+    /// # 错误
+    ///
+    /// 如果配置验证失败，返回错误。
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use nearclip_core::{NearClipManager, NearClipConfig, NoOpCallback};
+    /// use std::sync::Arc;
+    ///
+    /// let config = NearClipConfig::new("Device");
+    /// let callback = Arc::new(NoOpCallback);
+    /// let manager = NearClipManager::new(config, callback);
+    /// assert!(manager.is_ok());
+    /// ```
+    pub fn new(config: NearClipConfig, callback: Arc<dyn NearClipCallback>) -> Result<Self> {
+        config.validate()?;
 
-        // 创建取消配对消息
-        let unpair_msg = Message::unpair(self.device_id.clone());
+        // 使用配置中的设备 ID，如果没有则生成新的
+        let device_id = config
+            .device_id()
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string().to_uppercase());
 
-        // 尝试发送取消配对通知（如果连接存在），带超时保护
-        {
-            let network = self.network.lock().await;
-            if let Some(ref services) = *network {
-                // Use timeout to prevent blocking if device is offline
-                match tokio::time::timeout(
-                    std::time::Duration::from_secs(2),
-                    services.transport_manager.send_to_device(device_id, &unpair_msg)
-                ).await {
-                    Ok(Ok(())) => {
-                        tracing::debug!(device_id = %device_id, "Unpair message sent");
-                    }
-                    Ok(Err(e)) => {
-                        // 发送失败不影响取消配对流程
-                        tracing::warn!(device_id = %device_id, error = %e, "Failed to send unpair message");
-                    }
-                    Err(_) => {
-                        tracing::warn!(device_id = %device_id, "Unpair message send timed out");
-                    }
-                }
-            }
-        }
+        tracing::info!(
+            device_name = config.device_name(),
+            device_id = %device_id,
+            wifi = config.wifi_enabled(),
+            ble = config.ble_enabled(),
+            "Creating NearClipManager"
+        );
 
-        // 断开连接（如果已连接）
-        let _ = self.disconnect_device(device_id).await;
+        let channel_monitor = Arc::new(
+            ChannelMonitor::new(ChannelMonitorConfig::new(), Arc::new(NoOpChannelStatusCallback))
+                .map_err(|e| NearClipError::Config(e.to_string()))?,
+        );
+        let channel_selector = Arc::new(
+            QualityChannelSelector::new(QualitySelectorConfig::new(), channel_monitor.clone())
+                .map_err(|e| NearClipError::Config(e.to_string()))?,
+        );
 
-        // 从配对设备列表中移除
-        self.remove_paired_device(device_id);
+        let proximity = Arc::new(ProximityMonitor::new(config.proximity_policy()));
 
-        tracing::info!(device_id = %device_id, "Device unpaired successfully");
-        Ok(())
+        Ok(Self {
+            config,
+            device_id,
+            callback,
+            running: AtomicBool::new(false),
+            state: Arc::new(RwLock::new(ManagerState::default())),
+            network: Arc::new(TokioMutex::new(None)),
+            message_guard: Arc::new(LoopGuard::new(LoopGuardConfig::new())),
+            channel_monitor,
+            channel_selector,
+            delivery_tracker: Arc::new(DeliveryTracker::new()),
+            outbox: Arc::new(RwLock::new(None)),
+            port_store: RwLock::new(None),
+            sessions: Arc::new(SessionRegistry::new()),
+            proximity,
+            advertisement_key: RwLock::new(None),
+            relay_identity: RwLock::new(None),
+            relay_client: RwLock::new(None),
+        })
     }
 
-    // --------------------------------------------------------
-    // 设备发现方法
-    // --------------------------------------------------------
-
-    /// 获取网络上发现的设备列表
-    ///
-    /// 返回通过 mDNS 发现的所有设备，无论是否已配对。
-    pub async fn get_discovered_devices(&self) -> Vec<DiscoveredDevice> {
-        let network = self.network.lock().await;
-        if let Some(ref services) = *network {
-            if let Some(ref discovery) = services.mdns_discovery {
-                return discovery.get_devices().await;
-            }
-        }
-        Vec::new()
+    /// 获取设备 ID
+    pub fn device_id(&self) -> &str {
+        &self.device_id
     }
 
-    /// 尝试连接所有已配对但未连接的发现设备
+    /// 设置本设备的 BLE 广播密钥
     ///
-    /// 遍历发现的设备，如果设备已配对但未连接，尝试建立连接。
-    /// 返回成功连接的设备数量。
-    pub async fn try_connect_paired_devices(&self) -> usize {
-        if !self.running.load(Ordering::Acquire) {
-            return 0;
-        }
-
-        let discovered = self.get_discovered_devices().await;
-        let paired_ids: Vec<String> = {
-            let state = self.state.read().unwrap();
-            state.paired_devices.keys().cloned().collect()
-        };
-
-        let mut connected = 0;
-        for device in discovered {
-            if paired_ids.contains(&device.device_id) {
-                // 检查是否已连接
-                let already_connected = {
-                    let state = self.state.read().unwrap();
-                    state.paired_devices
-                        .get(&device.device_id)
-                        .map(|d| d.status().is_connected())
-                        .unwrap_or(false)
-                };
-
-                if !already_connected {
-                    tracing::info!(device_id = %device.device_id, "Auto-connecting to discovered paired device");
-                    match self.connect_device(&device.device_id).await {
-                        Ok(_) => {
-                            connected += 1;
-                            tracing::info!(device_id = %device.device_id, "Auto-connected successfully");
-                        }
-                        Err(e) => {
-                            tracing::warn!(device_id = %device.device_id, error = %e, "Auto-connect failed");
-                        }
-                    }
-                }
-            }
-        }
-
-        connected
+    /// 之后建立的连接会在 PairingRequest 中把密钥交给对端，对端据此从
+    /// BLE 广播令牌认出本设备。密钥需要跨重启保持不变，由平台层持久化。
+    pub fn set_advertisement_key(&self, key: AdvertisementKey) {
+        *self.advertisement_key.write().unwrap() = Some(key);
     }
 
-    // --------------------------------------------------------
-    // 内部方法 - 用于底层模块调用
-    // --------------------------------------------------------
+    /// 获取本设备的 BLE 广播密钥
+    pub fn advertisement_key(&self) -> Option<AdvertisementKey> {
+        self.advertisement_key.read().unwrap().clone()
+    }
 
-    /// 处理收到的剪贴板内容
+    /// 记录已配对设备在 BLE 连接中发来的广播密钥
     ///
-    /// 由底层模块调用，当收到远程剪贴板内容时。
-    pub fn handle_clipboard_received(&self, content: &[u8], from_device: &str) {
-        tracing::debug!(
-            content_size = content.len(),
-            from_device = %from_device,
-            "Received clipboard content"
-        );
-
-        // 更新设备活动时间
-        {
+    /// 密钥有变化时回调 `on_device_updated` 以便持久化。
+    ///
+    /// # 返回
+    ///
+    /// 设备未配对或密钥未变化时返回 false
+    pub fn update_device_advertisement_key(&self, device_id: &str, key: AdvertisementKey) -> bool {
+        let device = {
             let mut state = self.state.write().unwrap();
-            if let Some(device) = state.paired_devices.get_mut(from_device) {
-                device.touch();
+            let Some(device) = state.paired_devices.get_mut(device_id) else {
+                return false;
+            };
+            if device.advertisement_key() == Some(&key) {
+                return false;
             }
-        }
-
-        self.callback.on_clipboard_received(content, from_device);
+            device.set_advertisement_key(Some(key));
+            device.clone()
+        };
+        tracing::info!(device_id = %device_id, "Advertisement key updated");
+        self.callback.on_device_updated(&device);
+        true
     }
 
-    /// 处理同步错误
+    /// 设置连接中继使用的身份
     ///
-    /// 由底层模块调用，当发生错误时。
-    pub fn handle_sync_error(&self, error: NearClipError) {
-        tracing::error!(error = %error, "Sync error occurred");
-        self.callback.on_sync_error(&error);
+    /// 配置了中继时，`start` 用该密钥对向中继认证，并通过 `secrets`
+    /// 查找配对共享密钥来加密经中继转发的消息。需在 `start` 之前调用。
+    ///
+    /// # 参数
+    ///
+    /// * `keypair` - 本设备持久化的密钥对（中继按设备 ID 绑定公钥）
+    /// * `secrets` - 按设备 ID 查找配对共享密钥
+    pub fn set_relay_identity(&self, keypair: EcdhKeyPair, secrets: PeerSecretLookup) {
+        *self.relay_identity.write().unwrap() = Some(RelayIdentity { keypair, secrets });
     }
 
-    /// 处理设备连接
+    /// 获取消息去重器
     ///
-    /// 由底层模块调用，当设备连接成功时。
-    pub fn handle_device_connected(&self, device: DeviceInfo) {
-        let device_id = device.id().to_string();
-        tracing::info!(device_id = %device_id, "Device connected");
-
-        {
-            let mut state = self.state.write().unwrap();
-            state.paired_devices.insert(device_id, device.clone());
-        }
-
-        self.callback.on_device_connected(&device);
+    /// 所有接收路径（WiFi 接收任务、FFI 层的 BLE 接收任务）共用同一个去重器，
+    /// 保证经多个通道到达的同一条消息只被处理一次。
+    pub fn message_guard(&self) -> Arc<LoopGuard> {
+        self.message_guard.clone()
     }
 
-    /// 处理设备断开
+    /// 获取送达跟踪器
     ///
-    /// 由底层模块调用，当设备断开连接时。
-    pub fn handle_device_disconnected(&self, device_id: &str) {
-        tracing::info!(device_id = %device_id, "Device disconnected");
-
-        {
-            let mut state = self.state.write().unwrap();
-            if let Some(device) = state.paired_devices.get_mut(device_id) {
-                device.set_status(DeviceStatus::Disconnected);
-            }
-        }
-
-        self.callback.on_device_disconnected(device_id);
+    /// 所有接收路径收到 ACK 后都通过它唤醒等待中的 `sync_clipboard` 送达任务。
+    pub fn delivery_tracker(&self) -> Arc<DeliveryTracker> {
+        self.delivery_tracker.clone()
     }
 
-    // --------------------------------------------------------
-    // BLE Transport 管理方法
-    // --------------------------------------------------------
-
-    /// 添加 BLE 传输通道
+    /// 启用离线发件箱
     ///
-    /// 由 FFI 层调用，当 BLE 连接建立时。
-    /// 将 BLE transport 注册到 TransportManager，使其可用于消息发送。
+    /// 同步时离线的已配对设备会把剪贴板内容写入发件箱，设备重新连接后
+    /// 按顺序发送。保留策略和有效期来自配置。
     ///
     /// # 参数
     ///
-    /// * `device_id` - 设备 ID
-    /// * `transport` - BLE 传输通道
-    pub async fn add_ble_transport(&self, device_id: &str, transport: Arc<dyn Transport>) {
-        tracing::info!(device_id = %device_id, "Adding BLE transport to TransportManager");
-
-        let network = self.network.lock().await;
-        if let Some(ref services) = *network {
-            services.transport_manager.add_transport(device_id, transport).await;
-            tracing::info!(device_id = %device_id, "BLE transport added to TransportManager");
-        } else {
-            tracing::warn!(device_id = %device_id, "Cannot add BLE transport: network services not initialized");
-        }
-
-        // 更新设备状态为已连接
-        {
-            let mut state = self.state.write().unwrap();
-            if let Some(device) = state.paired_devices.get_mut(device_id) {
-                device.set_status(DeviceStatus::Connected);
-            }
-        }
+    /// * `db_path` - SQLite 数据库路径（可以与历史记录共用）
+    pub fn init_outbox(&self, db_path: PathBuf) -> Result<()> {
+        let outbox = OutboxManager::new(db_path, self.config.outbox_policy(), self.config.outbox_ttl())?;
+        outbox.purge_expired()?;
+        *self.outbox.write().unwrap() = Some(Arc::new(outbox));
+        tracing::info!("Outbox initialized");
+        Ok(())
     }
 
-    /// 移除 BLE 传输通道
+    /// 启用监听端口持久化
     ///
-    /// 由 FFI 层调用，当 BLE 连接断开时。
-    /// 从 TransportManager 中移除 BLE transport。
+    /// 启动时优先绑定上次使用的端口，绑定成功后记录实际端口。
+    /// 需要在 `start` 之前调用。
     ///
     /// # 参数
     ///
-    /// * `device_id` - 设备 ID
-    pub async fn remove_ble_transport(&self, device_id: &str) {
-        tracing::info!(device_id = %device_id, "Removing BLE transport from TransportManager");
+    /// * `db_path` - SQLite 数据库路径（可以与历史记录共用）
+    pub fn init_port_store(&self, db_path: PathBuf) -> Result<()> {
+        let store = PortStore::new(db_path)?;
+        *self.port_store.write().unwrap() = Some(store);
+        Ok(())
+    }
 
+    /// 获取 WiFi 监听端口（未启动或未启用 WiFi 时返回 None）
+    ///
+    /// 平台层可以展示该端口，方便用户在防火墙中放行。
+    pub async fn listening_port(&self) -> Option<u16> {
         let network = self.network.lock().await;
-        if let Some(ref services) = *network {
-            services.transport_manager.remove_transport(device_id, Channel::Ble).await;
-            tracing::info!(device_id = %device_id, "BLE transport removed from TransportManager");
-        }
-
-        // 检查是否还有其他传输通道，如果没有则更新状态为断开
-        let has_other_transport = if let Some(ref services) = *network {
-            let channels = services.transport_manager.device_channels(device_id).await;
-            !channels.is_empty()
-        } else {
-            false
-        };
-
-        if !has_other_transport {
-            let mut state = self.state.write().unwrap();
-            if let Some(device) = state.paired_devices.get_mut(device_id) {
-                device.set_status(DeviceStatus::Disconnected);
-            }
-        }
+        network.as_ref().map(|services| services.server_port)
     }
-}
 
-impl std::fmt::Debug for NearClipManager {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("NearClipManager")
-            .field("config", &self.config)
-            .field("running", &self.is_running())
-            .field("paired_devices", &self.get_paired_devices().len())
-            .field("connected_devices", &self.get_connected_devices().len())
-            .finish()
+    /// 获取离线发件箱（未启用时返回 None）
+    pub fn outbox(&self) -> Option<Arc<OutboxManager>> {
+        self.outbox.read().unwrap().clone()
     }
-}
 
-/// 检查 IPv6 地址是否是链路本地地址
-///
-/// 链路本地地址 (fe80::/10) 只在本地链路上有效，
-/// 跨设备连接时需要指定 scope_id，否则会失败。
-fn is_link_local_v6(addr: &std::net::Ipv6Addr) -> bool {
-    // 链路本地地址的前 10 位是 1111 1110 10 (fe80::/10)
-    let segments = addr.segments();
-    (segments[0] & 0xffc0) == 0xfe80
-}
+    /// 获取通道质量监测器
+    ///
+    /// 传输管理器会写入发送结果和吞吐量；平台层可以写入 BLE RSSI，
+    /// 心跳任务可以写入 RTT。通道选择使用按设备记录的指标
+    /// （`record_device_*`），全局指标只作汇总展示。
+    pub fn channel_monitor(&self) -> Arc<ChannelMonitor> {
+        self.channel_monitor.clone()
+    }
 
-// ============================================================
-// 单元测试
-// ============================================================
+    /// 获取通道质量调试快照
+    ///
+    /// 包含各通道的全局指标、每台设备的指标和当前通道，以及最近的选择决策。
+    pub fn channel_quality(&self) -> QualitySnapshot {
+        self.channel_selector.snapshot()
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::device::DevicePlatform;
-    use std::sync::Mutex;
+    /// 获取所有连接会话的快照
+    pub fn sessions(&self) -> Vec<SessionSnapshot> {
+        self.sessions.all().iter().map(|s| s.snapshot()).collect()
+    }
 
-    // 测试回调，记录调用
-    struct TestCallback {
-        connected: Mutex<Vec<String>>,
-        disconnected: Mutex<Vec<String>>,
-        clipboard: Mutex<Vec<(Vec<u8>, String)>>,
-        errors: Mutex<Vec<String>>,
+    /// 获取设备在指定通道上的会话状态（没有会话时返回 None）
+    pub fn session_state(&self, device_id: &str, channel: Channel) -> Option<SessionState> {
+        self.sessions.get(device_id, channel).map(|s| s.state())
     }
 
-    impl TestCallback {
-        fn new() -> Self {
-            Self {
-                connected: Mutex::new(Vec::new()),
-                disconnected: Mutex::new(Vec::new()),
-                clipboard: Mutex::new(Vec::new()),
-                errors: Mutex::new(Vec::new()),
-            }
+    /// 创建会话处理上下文
+    fn session_context(&self) -> SessionContext {
+        SessionContext {
+            my_device_id: self.device_id.clone(),
+            device_name: self.config.device_name().to_string(),
+            advertisement_key: self.advertisement_key(),
+            callback: self.callback.clone(),
+            state: self.state.clone(),
+            network: self.network.clone(),
+            sessions: self.sessions.clone(),
+            message_guard: self.message_guard.clone(),
+            delivery_tracker: self.delivery_tracker.clone(),
+            channel_monitor: self.channel_monitor.clone(),
+            heartbeat_interval: self.config.heartbeat_interval(),
         }
+    }
 
-        fn connected_count(&self) -> usize {
-            self.connected.lock().unwrap().len()
-        }
+    /// 启动服务
+    ///
+    /// 启动 mDNS 广播、TCP 服务器、BLE 广播等；配置了中继时连接中继。
+    ///
+    /// # 示例
+    ///
+    /// ```no_run
+    /// use nearclip_core::{NearClipManager, NearClipConfig, NoOpCallback};
+    /// use std::sync::Arc;
+    ///
+    /// # async fn example() {
+    /// let config = NearClipConfig::new("Device");
+    /// let callback = Arc::new(NoOpCallback);
+    /// let manager = NearClipManager::new(config, callback).unwrap();
+    ///
+    /// manager.start().await.unwrap();
+    /// assert!(manager.is_running());
+    ///
+    /// manager.stop().await;
+    /// assert!(!manager.is_running());
+    /// # }
+    /// ```
+    pub async fn start(&self) -> Result<()> {
+        if self.running.load(Ordering::Acquire) {
+            tracing::warn!("Manager already running");
+            return Ok(());
+        }
 
-        fn disconnected_count(&self) -> usize {
-            self.disconnected.lock().unwrap().len()
+        tracing::info!("Starting NearClipManager");
+
+        if self.config.wifi_enabled() {
+            // 1. 生成 TLS 证书
+            let tls_cert = TlsCertificate::generate(&["nearclip.local".to_string()])
+                .map_err(|e| NearClipError::Network(format!("Failed to generate TLS cert: {}", e)))?;
+
+            let tls_server_config = TlsServerConfig::new(&tls_cert)
+                .map_err(|e| NearClipError::Network(format!("Failed to create TLS config: {}", e)))?;
+
+            // 2. 启动 TCP 服务器（优先使用上次的端口，其次是首选范围内的空闲端口）
+            //    接口过滤器限制了接口时，只绑定允许接口上的地址
+            let filter = self.config.interface_filter().clone();
+            let interfaces = list_interfaces().unwrap_or_default();
+            let addrs = listen_addrs(&filter, &interfaces);
+            let last_port = self.port_store.read().unwrap().as_ref().and_then(|store| {
+                store
+                    .load()
+                    .map_err(|e| tracing::warn!(error = %e, "Failed to load last listening port"))
+                    .ok()
+                    .flatten()
+            });
+            let server_config = TcpServerConfig::new()
+                .with_bind_addr(addrs[0])
+                .with_port(last_port.unwrap_or(0))
+                .with_port_range(self.config.port_range())
+                .with_limits(self.config.connection_limits().clone())
+                .with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
+            let tcp_server = TcpServer::bind(server_config, tls_server_config.config())
+                .await
+                .map_err(|e| NearClipError::Network(format!("Failed to bind TCP server: {}", e)))?;
+
+            let server_port = tcp_server.local_addr()
+                .map_err(|e| NearClipError::Network(format!("Failed to get server address: {}", e)))?
+                .port();
+
+            tracing::info!(addr = %addrs[0], port = server_port, "TCP server started");
+
+            if let Some(store) = self.port_store.read().unwrap().as_ref() {
+                if let Err(e) = store.save(server_port) {
+                    tracing::warn!(error = %e, "Failed to save listening port");
+                }
+            }
+
+            // 3. 启动 mDNS 广播
+            let pubkey_hash = base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                &tls_cert.cert_der()[..32.min(tls_cert.cert_der().len())]
+            );
+            let mdns_config = MdnsServiceConfig::new(
+                self.device_id.clone(),
+                pubkey_hash,
+                server_port,
+            );
+            let mut mdns_advertiser = MdnsAdvertiser::new(mdns_config)
+                .map_err(|e| NearClipError::Network(format!("Failed to create mDNS advertiser: {}", e)))?;
+            mdns_advertiser.start().await
+                .map_err(|e| NearClipError::Network(format!("Failed to start mDNS advertiser: {}", e)))?;
+
+            tracing::info!(device_id = %self.device_id, "mDNS advertiser started");
+
+            // 4. 启动 mDNS 发现
+            let mut mdns_discovery = MdnsDiscovery::new()
+                .map_err(|e| NearClipError::Network(format!("Failed to create mDNS discovery: {}", e)))?;
+            mdns_discovery.start().await
+                .map_err(|e| NearClipError::Network(format!("Failed to start mDNS discovery: {}", e)))?;
+
+            tracing::info!("mDNS discovery started");
+
+            // 创建网络服务
+            let (connected_tx, mut connected_rx) = mpsc::unbounded_channel::<String>();
+            let mut network_services = NetworkServices::new(
+                tls_cert,
+                self.channel_selector.clone(),
+                self.channel_monitor.clone(),
+                connected_tx,
+            );
+
+            // 设备连接后冲刷离线发件箱
+            //
+            // 启用"仅在附近时同步"时，远离的设备连接后不冲刷，
+            // 由 `resume_nearby_device` 在设备回到附近时冲刷。
+            let outbox_for_flush = self.outbox.clone();
+            let transport_manager_for_flush = network_services.transport_manager.clone();
+            let my_device_id_for_flush = self.device_id.clone();
+            let proximity_for_flush = self.proximity.clone();
+            let delivery_tracker_for_flush = self.delivery_tracker.clone();
+            let ack_timeout = self.config.ack_timeout();
+            let sync_only_when_near = self.config.proximity_policy().sync_only_when_near;
+            network_services.outbox_task = Some(tokio::spawn(async move {
+                while let Some(device_id) = connected_rx.recv().await {
+                    if sync_only_when_near && proximity_for_flush.state(&device_id) == ProximityState::Far {
+                        tracing::debug!(device_id = %device_id, "Device out of proximity, deferring outbox flush");
+                        continue;
+                    }
+                    let outbox = outbox_for_flush.read().unwrap().clone();
+                    if let Some(outbox) = outbox {
+                        flush_outbox(
+                            &outbox,
+                            &transport_manager_for_flush,
+                            &delivery_tracker_for_flush,
+                            ack_timeout,
+                            &device_id,
+                            &my_device_id_for_flush,
+                        )
+                        .await;
+                    }
+                }
+            }));
+            network_services.server_port = server_port;
+            network_services.mdns_advertiser = Some(mdns_advertiser);
+            network_services.mdns_discovery = Some(mdns_discovery);
+
+            // 创建 accept 任务，并在同一端口号的 UDP 上接受 QUIC 连接
+            let settings = ListenerSettings {
+                tls_server_config: Arc::new(tls_server_config),
+                limits: self.config.connection_limits().clone(),
+                port: server_port,
+                quic_enabled: self.config.quic_enabled(),
+            };
+            let ctx = self.session_context();
+            let listener = spawn_listener(&ctx, &settings, addrs[0], tcp_server);
+            network_services.listeners.insert(addrs[0], listener);
+            for &addr in &addrs[1..] {
+                match bind_listener(&ctx, &settings, addr).await {
+                    Ok(listener) => {
+                        network_services.listeners.insert(addr, listener);
+                    }
+                    Err(e) => tracing::warn!(%addr, error = %e, "Failed to listen on interface address"),
+                }
+            }
+            network_services.listener_settings = Some(settings);
+
+            if self.config.quic_enabled() {
+                network_services.quic_connector = quic_connector();
+            }
+
+            // 连接中继，作为优先级最低的通道
+            if let Some(relay) = self.config.relay() {
+                self.start_relay(relay, &ctx, &mut network_services).await;
+            }
+
+            // 同步已配对设备的通道偏好到 TransportManager
+            let preferences: Vec<(String, ChannelPreference)> = {
+                let state = self.state.read().unwrap();
+                state.paired_devices
+                    .values()
+                    .map(|d| (d.id().to_string(), d.channel_preference()))
+                    .collect()
+            };
+            for (device_id, preference) in preferences {
+                network_services.transport_manager.set_device_preference(&device_id, preference).await;
+            }
+
+            // 超时没有 BLE 读数的设备视为远离
+            network_services.proximity_task = Some(spawn_proximity_task(
+                self.proximity.clone(),
+                self.callback.clone(),
+            ));
+
+            // 存储网络服务
+            {
+                let mut network = self.network.lock().await;
+                *network = Some(network_services);
+            }
+
+            // 5. 按接口过滤器限制 mDNS，并监听网络变化
+            let names = interfaces.into_iter().map(|a| a.name).collect();
+            ctx.apply_interface_filter(&filter, names).await;
+
+            if self.config.network_monitor() {
+                self.start_interface_monitor(ctx, filter).await;
+            }
         }
 
-        fn clipboard_count(&self) -> usize {
-            self.clipboard.lock().unwrap().len()
+        self.running.store(true, Ordering::Release);
+
+        // 设置初始通道
+        {
+            let mut state = self.state.write().unwrap();
+            if self.config.wifi_enabled() {
+                state.current_channel = Some(Channel::Wifi);
+            } else if self.config.ble_enabled() {
+                state.current_channel = Some(Channel::Ble);
+            }
         }
 
-        fn error_count(&self) -> usize {
-            self.errors.lock().unwrap().len()
+        tracing::info!("NearClipManager started");
+        Ok(())
+    }
+
+    /// 连接中继并注册为优先级最低的通道
+    ///
+    /// 中继不可用时只记录警告，本地通道照常工作。
+    async fn start_relay(&self, relay: &RelayConfig, ctx: &SessionContext, services: &mut NetworkServices) {
+        let Some(identity) = self.relay_identity.read().unwrap().clone() else {
+            tracing::warn!("Relay configured but no relay identity set, skipping relay");
+            return;
+        };
+        let paired_ids: Vec<String> = self.state.read().unwrap().paired_devices.keys().cloned().collect();
+        let client = match connect_relay(relay, &self.device_id, &identity.keypair, paired_ids).await {
+            Ok(client) => Arc::new(client),
+            Err(e) => {
+                tracing::warn!(relay = %relay.address, error = %e, "Relay unavailable");
+                return;
+            }
+        };
+        tracing::info!(relay = %client.relay_addr(), "Relay connected");
+
+        let connector = Arc::new(RelayTransportConnector::new(client.clone(), identity.secrets.clone()));
+        let listener = Arc::new(RelayTransportListener::new(client.clone(), identity.secrets));
+        services.transport_manager.add_connector(connector.clone()).await;
+        services.transport_manager.add_listener(listener.clone()).await;
+        services.relay_connector = Some(connector);
+        services.relay_accept_task = Some(spawn_accept_task(ctx.clone(), listener));
+        *self.relay_client.write().unwrap() = Some(client);
+    }
+
+    /// 向中继更新允许的已配对设备
+    fn refresh_relay_peers(&self) {
+        let Some(client) = self.relay_client.read().unwrap().clone() else {
+            return;
+        };
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let paired_ids: Vec<String> = self.state.read().unwrap().paired_devices.keys().cloned().collect();
+        handle.spawn(async move {
+            if let Err(e) = client.allow_peers(paired_ids).await {
+                tracing::warn!(error = %e, "Failed to update relay peers");
+            }
+        });
+    }
+
+    /// 启动网络接口监听
+    ///
+    /// 接口地址变化时按过滤器重新绑定监听器、重新广播 mDNS、清理失效连接
+    /// 并重新连接已配对设备。
+    async fn start_interface_monitor(&self, ctx: SessionContext, filter: InterfaceFilter) {
+        let mut monitor = InterfaceMonitor::new(filter.clone());
+        let mut changes = monitor.subscribe();
+        if let Err(e) = monitor.start() {
+            tracing::warn!(error = %e, "Failed to start interface monitor");
+            return;
+        }
+
+        let task = tokio::spawn(async move {
+            loop {
+                match changes.recv().await {
+                    Ok(change) => {
+                        tracing::info!(
+                            added = change.added.len(),
+                            removed = change.removed.len(),
+                            "Network interfaces changed"
+                        );
+                        let names = change.added.iter().map(|a| a.name.clone()).collect();
+                        ctx.apply_interface_filter(&filter, names).await;
+                        ctx.rebind_listeners(&filter, &change.current).await;
+                        ctx.recover_after_network_change().await;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let mut network = self.network.lock().await;
+        if let Some(ref mut services) = *network {
+            services.interface_monitor = Some(monitor);
+            services.network_change_task = Some(task);
+        } else {
+            task.abort();
         }
     }
 
-    impl NearClipCallback for TestCallback {
-        fn on_device_connected(&self, device: &DeviceInfo) {
-            self.connected.lock().unwrap().push(device.id().to_string());
+    /// 通知网络发生变化
+    ///
+    /// 平台层收到系统网络变化通知（如 iOS/Android 的网络回调）时调用。
+    /// 立即重新广播 mDNS，清理失效连接并重新连接已配对设备。
+    pub async fn handle_network_change(&self) {
+        if !self.running.load(Ordering::Acquire) {
+            return;
         }
 
-        fn on_device_disconnected(&self, device_id: &str) {
-            self.disconnected.lock().unwrap().push(device_id.to_string());
+        // 若监听器检测到了变化，由监听任务处理，避免重复恢复
+        {
+            let network = self.network.lock().await;
+            if let Some(monitor) = network.as_ref().and_then(|s| s.interface_monitor.as_ref()) {
+                if let Ok(Some(_)) = monitor.check_now() {
+                    return;
+                }
+            }
         }
 
-        fn on_device_unpaired(&self, _device_id: &str) {}
+        self.session_context().recover_after_network_change().await;
+    }
 
-        fn on_pairing_rejected(&self, _device_id: &str, _reason: &str) {}
+    /// 停止服务
+    ///
+    /// 停止所有后台服务，断开所有连接。
+    pub async fn stop(&self) {
+        if !self.running.load(Ordering::Acquire) {
+            tracing::warn!("Manager not running");
+            return;
+        }
 
-        fn on_clipboard_received(&self, content: &[u8], from_device: &str) {
-            self.clipboard
-                .lock()
-                .unwrap()
-                .push((content.to_vec(), from_device.to_string()));
+        tracing::info!("Stopping NearClipManager");
+
+        // 关闭所有会话（在获取网络锁之前，关闭流程内部需要网络锁）
+        let ctx = self.session_context();
+        for session in self.sessions.all() {
+            ctx.close_session(&session, CloseReason::Shutdown).await;
         }
 
-        fn on_sync_error(&self, error: &NearClipError) {
-            self.errors.lock().unwrap().push(error.to_string());
+        // 停止网络服务
+        {
+            let mut network = self.network.lock().await;
+            if let Some(ref mut services) = *network {
+                // 1. 停止后台任务
+                for (_, listener) in services.listeners.drain() {
+                    listener.abort();
+                }
+                tracing::debug!("Accept tasks stopped");
+                if let Some(handle) = services.discovery_task.take() {
+                    handle.abort();
+                    tracing::debug!("Discovery task stopped");
+                }
+                if let Some(handle) = services.outbox_task.take() {
+                    handle.abort();
+                    tracing::debug!("Outbox task stopped");
+                }
+                if let Some(handle) = services.network_change_task.take() {
+                    handle.abort();
+                    tracing::debug!("Network change task stopped");
+                }
+                if let Some(handle) = services.proximity_task.take() {
+                    handle.abort();
+                    tracing::debug!("Proximity task stopped");
+                }
+                if let Some(handle) = services.relay_accept_task.take() {
+                    handle.abort();
+                    tracing::debug!("Relay accept task stopped");
+                }
+                if let Some(ref mut monitor) = services.interface_monitor {
+                    monitor.stop();
+                    tracing::debug!("Interface monitor stopped");
+                }
+
+                // 2. 关闭 TransportManager 中剩余的连接
+                services.transport_manager.close_all().await;
+                tracing::debug!("All transports closed");
+
+                // 3. 停止 mDNS 发现
+                if let Some(ref mut discovery) = services.mdns_discovery {
+                    if let Err(e) = discovery.stop().await {
+                        tracing::warn!(error = %e, "Failed to stop mDNS discovery");
+                    }
+                    tracing::debug!("mDNS discovery stopped");
+                }
+
+                // 4. 停止 mDNS 广播
+                if let Some(ref mut advertiser) = services.mdns_advertiser {
+                    if let Err(e) = advertiser.stop().await {
+                        tracing::warn!(error = %e, "Failed to stop mDNS advertiser");
+                    }
+                    tracing::debug!("mDNS advertiser stopped");
+                }
+            }
+            *network = None;
+        }
+
+        // 断开中继
+        let relay_client = self.relay_client.write().unwrap().take();
+        if let Some(client) = relay_client {
+            client.close().await;
+            tracing::debug!("Relay disconnected");
         }
+
+        // 断开所有设备（更新状态）
+        // 注意：先收集需要回调的设备 ID，释放锁后再调用回调，避免死锁
+        let disconnected_ids: Vec<String> = {
+            let mut state = self.state.write().unwrap();
+            let ids: Vec<String> = state
+                .paired_devices
+                .iter_mut()
+                .filter_map(|(device_id, device)| {
+                    if device.status().is_connected() {
+                        device.set_status(DeviceStatus::Disconnected);
+                        Some(device_id.clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            state.current_channel = None;
+            ids
+        };
+
+        // 在锁外调用回调
+        for device_id in disconnected_ids {
+            self.callback.on_device_disconnected(&device_id);
+        }
+
+        self.running.store(false, Ordering::Release);
+
+        tracing::info!("NearClipManager stopped");
     }
 
-    fn create_manager() -> NearClipManager {
-        let config = NearClipConfig::new("Test Device");
-        let callback = Arc::new(NoOpCallback);
-        NearClipManager::new(config, callback).unwrap()
+    /// 同步剪贴板内容
+    ///
+    /// 将剪贴板内容发送到所有已连接设备，并在后台等待每个设备的 ACK。
+    /// 对方确认后回调 `on_sync_delivered`；发送失败或 ACK 超时且重试
+    /// 耗尽后回调 `on_sync_failed`。
+    ///
+    /// # 参数
+    ///
+    /// * `content` - 剪贴板内容
+    ///
+    /// # 返回
+    ///
+    /// 本次同步的消息 ID，与送达回调中的 `message_id` 对应。
+    ///
+    /// # 错误
+    ///
+    /// - 管理器未运行
+    /// - 没有可用通道
+    pub async fn sync_clipboard(&self, content: &[u8]) -> Result<u64> {
+        tracing::info!(content_size = content.len(), "sync_clipboard called");
+
+        if !self.running.load(Ordering::Acquire) {
+            tracing::warn!("sync_clipboard: Manager not running");
+            return Err(NearClipError::Sync("Manager not running".to_string()));
+        }
+
+        tracing::debug!("sync_clipboard: Manager is running, checking channel");
+
+        let state = self.state.read().unwrap();
+        let channel = match state.current_channel {
+            Some(ch) => ch,
+            None => {
+                tracing::warn!("sync_clipboard: No channel available");
+                return Err(NearClipError::Sync("No channel available".to_string()));
+            }
+        };
+        drop(state);
+
+        tracing::debug!(channel = ?channel, "sync_clipboard: Channel found");
+
+        // 创建剪贴板同步消息
+        let msg = Message::clipboard_sync(content, self.device_id.clone());
+        let message_id = msg.message_id;
+
+        tracing::debug!("sync_clipboard: Acquiring network lock");
+
+        // 通过 TransportManager 发送到已连接设备
+        let network = self.network.lock().await;
+
+        tracing::debug!("sync_clipboard: Network lock acquired");
+
+        if let Some(ref services) = *network {
+            let transport_manager = services.transport_manager.clone();
+            let mut device_ids = transport_manager.connected_devices().await;
+
+            // 远离的设备暂停同步，剪贴板留在发件箱等它回到附近
+            let mut paused = Vec::new();
+            if self.config.proximity_policy().sync_only_when_near {
+                (paused, device_ids) = device_ids
+                    .into_iter()
+                    .partition(|id| self.proximity.state(id) == ProximityState::Far);
+            }
+
+            // 离线的已配对设备写入发件箱
+            let offline: Vec<String> = self
+                .state
+                .read()
+                .unwrap()
+                .paired_devices
+                .keys()
+                .filter(|id| !device_ids.contains(id) && !paused.contains(id))
+                .cloned()
+                .collect();
+            self.queue_in_outbox(&offline, content);
+            if !paused.is_empty() {
+                tracing::debug!(count = paused.len(), "Sync paused for devices out of proximity");
+                self.queue_in_outbox(&paused, content);
+            }
+
+            if device_ids.is_empty() {
+                tracing::debug!("No active connections, skipping sync");
+                return Ok(message_id);
+            }
+
+            tracing::info!(
+                content_size = content.len(),
+                channel = ?channel,
+                connection_count = device_ids.len(),
+                message_id = message_id,
+                "Syncing clipboard"
+            );
+
+            // 先注册 ACK 等待者，避免 ACK 早于注册到达
+            let mut waiters: HashMap<String, AckWaiter> = device_ids
+                .iter()
+                .map(|id| (id.clone(), self.delivery_tracker.register(id, message_id)))
+                .collect();
+
+            // 逐个发送到参与同步的设备
+            let mut results = Vec::with_capacity(device_ids.len());
+            for device_id in &device_ids {
+                let result = transport_manager.send_to_device(device_id, &msg).await;
+                results.push((device_id.clone(), result));
+            }
+
+            drop(network);
+
+            // 处理失败的设备
+            let mut failed_devices = Vec::new();
+            for (device_id, result) in results {
+                match result {
+                    Ok(()) => {
+                        tracing::debug!(device_id = %device_id, "Sent clipboard, awaiting ack");
+                        if let Some(waiter) = waiters.remove(&device_id) {
+                            self.spawn_delivery(waiter, transport_manager.clone(), msg.clone());
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!(device_id = %device_id, error = %e, "Failed to send clipboard");
+                        let error = NearClipError::Network(format!("Failed to send clipboard: {}", e));
+                        self.callback.on_sync_failed(&device_id, message_id, &error);
+                        failed_devices.push(device_id);
+                    }
+                }
+            }
+
+            self.queue_in_outbox(&failed_devices, content);
+
+            // 关闭失败设备的会话（同时停止接收和心跳任务）
+            let ctx = self.session_context();
+            for device_id in &failed_devices {
+                for session in self.sessions.for_device(device_id) {
+                    ctx.close_session(&session, CloseReason::SendFailed).await;
+                }
+            }
+        } else {
+            tracing::warn!("sync_clipboard: No network services available");
+        }
+
+        tracing::info!("sync_clipboard completed");
+        Ok(message_id)
+    }
+
+    /// 为设备写入离线发件箱（发件箱未启用时忽略）
+    fn queue_in_outbox(&self, device_ids: &[String], content: &[u8]) {
+        if device_ids.is_empty() {
+            return;
+        }
+        let outbox = match self.outbox() {
+            Some(outbox) => outbox,
+            None => return,
+        };
+        for device_id in device_ids {
+            match outbox.enqueue(device_id, content) {
+                Ok(_) => tracing::debug!(device_id = %device_id, "Clip queued for offline device"),
+                Err(e) => tracing::warn!(device_id = %device_id, error = %e, "Failed to queue clip"),
+            }
+        }
+    }
+
+    /// 在后台等待单个设备的 ACK
+    ///
+    /// ACK 超时后通过 `RetryExecutor` 重发同一条消息（消息 ID 不变，
+    /// 接收方会去重但仍会回复 ACK），直到收到 ACK 或重试耗尽。
+    fn spawn_delivery(&self, waiter: AckWaiter, transport_manager: Arc<TransportManager>, msg: Message) {
+        let callback = self.callback.clone();
+        let ack_timeout = self.config.ack_timeout();
+        let executor = RetryExecutor::new(FixedDelayStrategy::new(
+            self.config.max_retries(),
+            Duration::from_millis(DEFAULT_RESEND_DELAY_MS),
+        ));
+
+        tokio::spawn(async move {
+            let device_id = waiter.device_id().to_string();
+            let message_id = waiter.message_id();
+
+            let result = waiter
+                .wait_with_retry(&executor, ack_timeout, || {
+                    let transport_manager = transport_manager.clone();
+                    let device_id = device_id.clone();
+                    let msg = msg.clone();
+                    async move {
+                        tracing::debug!(device_id = %device_id, message_id = msg.message_id, "Resending clipboard");
+                        transport_manager
+                            .send_to_device(&device_id, &msg)
+                            .await
+                            .map_err(|e| SyncError::SendFailed(e.to_string()))
+                    }
+                })
+                .await;
+
+            match result {
+                Ok(attempts) => {
+                    tracing::info!(
+                        device_id = %device_id,
+                        message_id = message_id,
+                        attempts = attempts,
+                        "Clipboard delivered"
+                    );
+                    callback.on_sync_delivered(&device_id, message_id);
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        device_id = %device_id,
+                        message_id = message_id,
+                        error = %e,
+                        "Clipboard delivery failed"
+                    );
+                    callback.on_sync_failed(&device_id, message_id, &NearClipError::Sync(e.to_string()));
+                }
+            }
+        });
+    }
+
+    /// 检查是否正在运行
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::Acquire)
+    }
+
+    /// 获取配置
+    pub fn config(&self) -> &NearClipConfig {
+        &self.config
+    }
+
+    /// 获取当前通道
+    pub fn current_channel(&self) -> Option<Channel> {
+        self.state.read().unwrap().current_channel
+    }
+
+    // --------------------------------------------------------
+    // 设备管理
+    // --------------------------------------------------------
+
+    /// 获取已配对设备列表
+    pub fn get_paired_devices(&self) -> Vec<DeviceInfo> {
+        self.state
+            .read()
+            .unwrap()
+            .paired_devices
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    /// 获取已连接设备列表
+    pub fn get_connected_devices(&self) -> Vec<DeviceInfo> {
+        self.state
+            .read()
+            .unwrap()
+            .paired_devices
+            .values()
+            .filter(|d| d.status().is_connected())
+            .cloned()
+            .collect()
+    }
+
+    /// 获取设备状态
+    pub fn get_device_status(&self, device_id: &str) -> Option<DeviceStatus> {
+        self.state
+            .read()
+            .unwrap()
+            .paired_devices
+            .get(device_id)
+            .map(|d| d.status())
+    }
+
+    /// 添加已配对设备
+    ///
+    /// 内部方法，用于添加新配对的设备。
+    pub fn add_paired_device(&self, mut device: DeviceInfo) {
+        let device_id = device.id().to_string();
+        tracing::info!(device_id = %device_id, "Adding paired device");
+
+        let mut state = self.state.write().unwrap();
+        if let Some(existing) = state.paired_devices.get(&device_id) {
+            device.inherit_persisted(existing);
+        }
+        state.paired_devices.insert(device_id, device);
+        drop(state);
+        self.refresh_relay_peers();
+    }
+
+    /// 移除已配对设备
+    pub fn remove_paired_device(&self, device_id: &str) -> Option<DeviceInfo> {
+        tracing::info!(device_id = %device_id, "Removing paired device");
+
+        if let Some(outbox) = self.outbox() {
+            if let Err(e) = outbox.clear_device(device_id) {
+                tracing::warn!(device_id = %device_id, error = %e, "Failed to clear outbox");
+            }
+        }
+        self.proximity.forget(device_id);
+        self.channel_monitor.remove_device(device_id);
+        self.channel_selector.remove_device(device_id);
+
+        let removed = self
+            .state
+            .write()
+            .unwrap()
+            .paired_devices
+            .remove(device_id);
+        self.refresh_relay_peers();
+        removed
+    }
+
+    /// 获取设备的通道偏好
+    pub fn get_device_channel_preference(&self, device_id: &str) -> Option<ChannelPreference> {
+        self.state
+            .read()
+            .unwrap()
+            .paired_devices
+            .get(device_id)
+            .map(|d| d.channel_preference())
+    }
+
+    /// 设置设备的通道偏好
+    ///
+    /// 偏好保存在已配对设备信息中，并立即应用到后续发送。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    /// * `preference` - 通道偏好
+    ///
+    /// # 错误
+    ///
+    /// - 设备未配对
+    pub async fn set_device_channel_preference(
+        &self,
+        device_id: &str,
+        preference: ChannelPreference,
+    ) -> Result<()> {
+        {
+            let mut state = self.state.write().unwrap();
+            let device = state
+                .paired_devices
+                .get_mut(device_id)
+                .ok_or_else(|| NearClipError::DeviceNotFound(device_id.to_string()))?;
+            device.set_channel_preference(preference);
+        }
+
+        tracing::info!(device_id = %device_id, preference = %preference, "Channel preference updated");
+
+        let network = self.network.lock().await;
+        if let Some(ref services) = *network {
+            services.transport_manager.set_device_preference(device_id, preference).await;
+        }
+
+        Ok(())
+    }
+
+    /// 获取设备的距离阈值（None 表示使用配置中的默认值）
+    pub fn get_device_proximity_thresholds(&self, device_id: &str) -> Option<ProximityThresholds> {
+        self.state
+            .read()
+            .unwrap()
+            .paired_devices
+            .get(device_id)
+            .and_then(|d| d.proximity_thresholds())
+    }
+
+    /// 设置设备的距离阈值
+    ///
+    /// 阈值保存在已配对设备信息中，从下一个 RSSI 读数开始生效。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    /// * `thresholds` - 距离阈值，None 表示恢复默认值
+    ///
+    /// # 错误
+    ///
+    /// - 阈值无效
+    /// - 设备未配对
+    pub fn set_device_proximity_thresholds(
+        &self,
+        device_id: &str,
+        thresholds: Option<ProximityThresholds>,
+    ) -> Result<()> {
+        if let Some(ref thresholds) = thresholds {
+            thresholds.validate()?;
+        }
+
+        let mut state = self.state.write().unwrap();
+        let device = state
+            .paired_devices
+            .get_mut(device_id)
+            .ok_or_else(|| NearClipError::DeviceNotFound(device_id.to_string()))?;
+        device.set_proximity_thresholds(thresholds);
+
+        tracing::info!(device_id = %device_id, thresholds = ?thresholds, "Proximity thresholds updated");
+        Ok(())
+    }
+
+    /// 获取设备的距离状态
+    pub fn get_device_proximity(&self, device_id: &str) -> ProximityState {
+        self.proximity.state(device_id)
+    }
+
+    /// 获取设备平滑后的 RSSI
+    pub fn get_device_rssi(&self, device_id: &str) -> Option<i16> {
+        self.proximity.rssi(device_id)
+    }
+
+    /// 处理已配对设备的 BLE RSSI 读数
+    ///
+    /// 由平台层在扫描到设备或读到 BLE 连接的 RSSI 时调用。读数平滑后更新距离状态，进入或离开
+    /// 附近时回调 `on_proximity_entered` / `on_proximity_left`；设备回到
+    /// 附近时按距离策略恢复同步或自动连接。未配对设备的读数被忽略。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    /// * `rssi` - 信号强度（dBm）
+    ///
+    /// # 返回
+    ///
+    /// 更新后的距离状态
+    pub fn handle_rssi(&self, device_id: &str, rssi: i16) -> ProximityState {
+        let thresholds = {
+            let state = self.state.read().unwrap();
+            match state.paired_devices.get(device_id) {
+                Some(device) => device
+                    .proximity_thresholds()
+                    .unwrap_or(self.config.proximity_policy().thresholds),
+                None => return ProximityState::Unknown,
+            }
+        };
+
+        match self.proximity.observe(device_id, rssi, &thresholds) {
+            Some(ProximityEvent::Entered { rssi }) => {
+                tracing::info!(device_id = %device_id, rssi, "Device entered proximity");
+                self.callback.on_proximity_entered(device_id, rssi);
+                self.resume_nearby_device(device_id);
+            }
+            Some(ProximityEvent::Left) => {
+                tracing::info!(device_id = %device_id, "Device left proximity");
+                self.callback.on_proximity_left(device_id);
+            }
+            None => {}
+        }
+        self.proximity.state(device_id)
+    }
+
+    /// 设备回到附近后恢复同步
+    ///
+    /// 已连接时冲刷远离期间积累的发件箱；未连接且启用自动连接时发起连接，
+    /// 连接成功后发件箱由连接通知冲刷。
+    fn resume_nearby_device(&self, device_id: &str) {
+        let policy = *self.config.proximity_policy();
+        if !self.running.load(Ordering::Acquire)
+            || !(policy.sync_only_when_near || policy.auto_connect_when_near)
+        {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            tracing::warn!(device_id = %device_id, "No async runtime, cannot resume nearby device");
+            return;
+        };
+
+        let ctx = self.session_context();
+        let outbox = self.outbox();
+        let ack_timeout = self.config.ack_timeout();
+        let device_id = device_id.to_string();
+        runtime.spawn(async move {
+            let Some(transport_manager) = ctx.transport_manager().await else {
+                return;
+            };
+            if transport_manager.is_device_connected(&device_id).await {
+                if let (true, Some(outbox)) = (policy.sync_only_when_near, outbox) {
+                    flush_outbox(
+                        &outbox,
+                        &transport_manager,
+                        &ctx.delivery_tracker,
+                        ack_timeout,
+                        &device_id,
+                        &ctx.my_device_id,
+                    )
+                    .await;
+                }
+            } else if policy.auto_connect_when_near {
+                tracing::info!(device_id = %device_id, "Auto-connecting to nearby device");
+                if let Err(e) = ctx.dial(&device_id).await {
+                    tracing::warn!(device_id = %device_id, error = %e, "Auto-connect to nearby device failed");
+                }
+            }
+        });
+    }
+
+    /// 连接设备
+    ///
+    /// 依次尝试 mDNS 发现的地址、最后一次成功的地址和静态地址，
+    /// 使用第一个连接成功的地址。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    ///
+    /// # 错误
+    ///
+    /// - 管理器未运行
+    /// - 设备未找到
+    /// - 连接失败
+    pub async fn connect_device(&self, device_id: &str) -> Result<()> {
+        if !self.running.load(Ordering::Acquire) {
+            return Err(NearClipError::Sync("Manager not running".to_string()));
+        }
+
+        {
+            let state = self.state.read().unwrap();
+            if !state.paired_devices.contains_key(device_id) {
+                return Err(NearClipError::DeviceNotFound(device_id.to_string()));
+            }
+        }
+
+        self.session_context().dial(device_id).await
+    }
+
+    /// 为已配对设备添加静态地址
+    ///
+    /// mDNS 被屏蔽（企业网络、VPN）时，连接会尝试这些地址。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    /// * `address` - `host:port`、`ip:port` 或 `[ipv6]:port`
+    ///
+    /// # 错误
+    ///
+    /// - 设备未配对
+    /// - 地址格式无效
+    pub fn add_static_peer(&self, device_id: &str, address: &str) -> Result<()> {
+        let address: PeerAddress = address.parse()?;
+        let mut state = self.state.write().unwrap();
+        let device = state
+            .paired_devices
+            .get_mut(device_id)
+            .ok_or_else(|| NearClipError::DeviceNotFound(device_id.to_string()))?;
+        if device.add_static_address(address.clone()) {
+            tracing::info!(device_id = %device_id, address = %address, "Static peer address added");
+        }
+        Ok(())
+    }
+
+    /// 移除已配对设备的静态地址
+    ///
+    /// # 返回
+    ///
+    /// 地址存在并被移除时返回 true
+    pub fn remove_static_peer(&self, device_id: &str, address: &str) -> Result<bool> {
+        let address: PeerAddress = address.parse()?;
+        let mut state = self.state.write().unwrap();
+        let device = state
+            .paired_devices
+            .get_mut(device_id)
+            .ok_or_else(|| NearClipError::DeviceNotFound(device_id.to_string()))?;
+        Ok(device.remove_static_address(&address))
+    }
+
+    /// 获取已配对设备的静态地址（设备不存在时返回空列表）
+    pub fn get_static_peers(&self, device_id: &str) -> Vec<PeerAddress> {
+        let state = self.state.read().unwrap();
+        state
+            .paired_devices
+            .get(device_id)
+            .map(|d| d.static_addresses().to_vec())
+            .unwrap_or_default()
+    }
+
+    /// 获取设备最后一次连接成功的地址
+    pub fn get_last_known_address(&self, device_id: &str) -> Option<SocketAddr> {
+        let state = self.state.read().unwrap();
+        state.paired_devices.get(device_id).and_then(|d| d.last_known_address())
+    }
+
+    /// 断开设备连接
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    pub async fn disconnect_device(&self, device_id: &str) -> Result<()> {
+        {
+            let state = self.state.read().unwrap();
+            if !state.paired_devices.contains_key(device_id) {
+                return Err(NearClipError::DeviceNotFound(device_id.to_string()));
+            }
+        }
+
+        tracing::info!(device_id = %device_id, "Disconnecting device");
+
+        // 关闭该设备的所有会话，最后一个活跃会话关闭时触发断开回调
+        let ctx = self.session_context();
+        let mut notified = false;
+        for session in self.sessions.for_device(device_id) {
+            notified |= ctx.close_session(&session, CloseReason::Local).await;
+        }
+
+        // 没有会话的设备（状态由平台层维护）直接更新状态
+        if !notified {
+            ctx.set_disconnected(device_id);
+            self.callback.on_device_disconnected(device_id);
+        }
+
+        Ok(())
+    }
+
+    /// 取消配对设备
+    ///
+    /// 向目标设备发送取消配对通知，然后断开连接并移除配对关系。
+    /// 对方设备收到通知后也会删除本设备的配对信息。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    pub async fn unpair_device(&self, device_id: &str) -> Result<()> {
+        tracing::info!(device_id = %device_id, "Unpairing device");
+
+        // 创建取消配对消息
+        let unpair_msg = Message::unpair(self.device_id.clone());
+
+        // 尝试发送取消配对通知（如果连接存在），带超时保护
+        {
+            let network = self.network.lock().await;
+            if let Some(ref services) = *network {
+                // Use timeout to prevent blocking if device is offline
+                match tokio::time::timeout(
+                    std::time::Duration::from_secs(2),
+                    services.transport_manager.send_to_device(device_id, &unpair_msg)
+                ).await {
+                    Ok(Ok(())) => {
+                        tracing::debug!(device_id = %device_id, "Unpair message sent");
+                    }
+                    Ok(Err(e)) => {
+                        // 发送失败不影响取消配对流程
+                        tracing::warn!(device_id = %device_id, error = %e, "Failed to send unpair message");
+                    }
+                    Err(_) => {
+                        tracing::warn!(device_id = %device_id, "Unpair message send timed out");
+                    }
+                }
+            }
+        }
+
+        // 断开连接（如果已连接）
+        let _ = self.disconnect_device(device_id).await;
+
+        // 从配对设备列表中移除
+        self.remove_paired_device(device_id);
+
+        tracing::info!(device_id = %device_id, "Device unpaired successfully");
+        Ok(())
+    }
+
+    // --------------------------------------------------------
+    // 设备发现方法
+    // --------------------------------------------------------
+
+    /// 获取网络上发现的设备列表
+    ///
+    /// 返回通过 mDNS 发现的所有设备，无论是否已配对。这是 mDNS 单一来源的原始列表，
+    /// 合并后的附近设备视图见 `nearclip_device::NearbyDevices`。
+    pub async fn get_discovered_devices(&self) -> Vec<DiscoveredDevice> {
+        let network = self.network.lock().await;
+        if let Some(ref services) = *network {
+            if let Some(ref discovery) = services.mdns_discovery {
+                return discovery.get_devices().await;
+            }
+        }
+        Vec::new()
+    }
+
+    /// 尝试连接所有已配对但未连接的发现设备
+    ///
+    /// 遍历发现的设备，如果设备已配对但未连接，尝试建立连接。
+    /// 返回成功连接的设备数量。
+    pub async fn try_connect_paired_devices(&self) -> usize {
+        if !self.running.load(Ordering::Acquire) {
+            return 0;
+        }
+
+        let discovered = self.get_discovered_devices().await;
+        let paired_ids: Vec<String> = {
+            let state = self.state.read().unwrap();
+            state.paired_devices.keys().cloned().collect()
+        };
+
+        let mut connected = 0;
+        for device in discovered {
+            if paired_ids.contains(&device.device_id) {
+                // 检查是否已连接
+                let already_connected = {
+                    let state = self.state.read().unwrap();
+                    state.paired_devices
+                        .get(&device.device_id)
+                        .map(|d| d.status().is_connected())
+                        .unwrap_or(false)
+                };
+
+                if !already_connected {
+                    tracing::info!(device_id = %device.device_id, "Auto-connecting to discovered paired device");
+                    match self.connect_device(&device.device_id).await {
+                        Ok(_) => {
+                            connected += 1;
+                            tracing::info!(device_id = %device.device_id, "Auto-connected successfully");
+                        }
+                        Err(e) => {
+                            tracing::warn!(device_id = %device.device_id, error = %e, "Auto-connect failed");
+                        }
+                    }
+                }
+            }
+        }
+
+        connected
+    }
+
+    // --------------------------------------------------------
+    // 内部方法 - 用于底层模块调用
+    // --------------------------------------------------------
+
+    /// 处理收到的剪贴板内容
+    ///
+    /// 由底层模块调用，当收到远程剪贴板内容时。
+    pub fn handle_clipboard_received(&self, content: &[u8], from_device: &str) {
+        tracing::debug!(
+            content_size = content.len(),
+            from_device = %from_device,
+            "Received clipboard content"
+        );
+
+        // 更新设备活动时间
+        {
+            let mut state = self.state.write().unwrap();
+            if let Some(device) = state.paired_devices.get_mut(from_device) {
+                device.touch();
+            }
+        }
+
+        self.callback.on_clipboard_received(content, from_device);
+    }
+
+    /// 处理同步错误
+    ///
+    /// 由底层模块调用，当发生错误时。
+    pub fn handle_sync_error(&self, error: NearClipError) {
+        tracing::error!(error = %error, "Sync error occurred");
+        self.callback.on_sync_error(&error);
+    }
+
+    /// 处理设备连接
+    ///
+    /// 由底层模块调用，当设备连接成功时。
+    pub fn handle_device_connected(&self, device: DeviceInfo) {
+        let device_id = device.id().to_string();
+        tracing::info!(device_id = %device_id, "Device connected");
+
+        {
+            let mut state = self.state.write().unwrap();
+            state.paired_devices.insert(device_id, device.clone());
+        }
+
+        self.callback.on_device_connected(&device);
+    }
+
+    /// 处理设备断开
+    ///
+    /// 由底层模块调用，当设备断开连接时。
+    pub fn handle_device_disconnected(&self, device_id: &str) {
+        tracing::info!(device_id = %device_id, "Device disconnected");
+
+        {
+            let mut state = self.state.write().unwrap();
+            if let Some(device) = state.paired_devices.get_mut(device_id) {
+                device.set_status(DeviceStatus::Disconnected);
+            }
+        }
+
+        self.callback.on_device_disconnected(device_id);
+    }
+
+    // --------------------------------------------------------
+    // BLE Transport 管理方法
+    // --------------------------------------------------------
+
+    /// 添加 BLE 传输通道
+    ///
+    /// 由 FFI 层调用，当 BLE 连接建立时。
+    /// 将 BLE transport 注册到 TransportManager，使其可用于消息发送。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    /// * `transport` - BLE 传输通道
+    pub async fn add_ble_transport(&self, device_id: &str, transport: Arc<dyn Transport>) {
+        tracing::info!(device_id = %device_id, "Adding BLE transport to TransportManager");
+
+        // BLE 的收发由平台层驱动，会话只负责生命周期
+        let session = Arc::new(Session::new(device_id, transport, SessionDirection::External));
+        let ctx = self.session_context();
+        if ctx.register(&session).await {
+            let _ = session.transition(SessionState::Active);
+            tracing::info!(device_id = %device_id, "BLE transport added to TransportManager");
+        }
+
+        // 更新设备状态为已连接
+        {
+            let mut state = self.state.write().unwrap();
+            if let Some(device) = state.paired_devices.get_mut(device_id) {
+                device.set_status(DeviceStatus::Connected);
+            }
+        }
+
+        // 连接期间对端通常停止广播，不能因为没有扫描读数就判定远离
+        self.proximity.set_connected(device_id, true);
+    }
+
+    /// 移除 BLE 传输通道
+    ///
+    /// 由 FFI 层调用，当 BLE 连接断开时。
+    /// 关闭 BLE 会话；设备没有其他活跃会话时回调 `on_device_disconnected`。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 设备 ID
+    pub async fn remove_ble_transport(&self, device_id: &str) {
+        tracing::info!(device_id = %device_id, "Removing BLE transport from TransportManager");
+
+        self.proximity.set_connected(device_id, false);
+        if let Some(session) = self.sessions.get(device_id, Channel::Ble) {
+            self.session_context().close_session(&session, CloseReason::RemoteClosed).await;
+            tracing::info!(device_id = %device_id, "BLE transport removed from TransportManager");
+        }
+    }
+}
+
+impl std::fmt::Debug for NearClipManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NearClipManager")
+            .field("config", &self.config)
+            .field("running", &self.is_running())
+            .field("paired_devices", &self.get_paired_devices().len())
+            .field("connected_devices", &self.get_connected_devices().len())
+            .finish()
+    }
+}
+
+/// 检查 IPv6 地址是否是链路本地地址
+///
+/// 链路本地地址 (fe80::/10) 只在本地链路上有效，
+/// 跨设备连接时需要指定 scope_id，否则会失败。
+fn is_link_local_v6(addr: &std::net::Ipv6Addr) -> bool {
+    // 链路本地地址的前 10 位是 1111 1110 10 (fe80::/10)
+    let segments = addr.segments();
+    (segments[0] & 0xffc0) == 0xfe80
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::DevicePlatform;
+    use crate::proximity::ProximityPolicy;
+    use nearclip_transport::{MockConfig, MockTransport};
+    use std::sync::Mutex;
+
+    // 测试回调，记录调用
+    struct TestCallback {
+        connected: Mutex<Vec<String>>,
+        disconnected: Mutex<Vec<String>>,
+        clipboard: Mutex<Vec<(Vec<u8>, String)>>,
+        errors: Mutex<Vec<String>>,
+        delivered: Mutex<Vec<(String, u64)>>,
+        failed: Mutex<Vec<(String, u64)>>,
+        proximity: Mutex<Vec<(String, bool)>>,
+        updated: Mutex<Vec<DeviceInfo>>,
+    }
+
+    impl TestCallback {
+        fn new() -> Self {
+            Self {
+                connected: Mutex::new(Vec::new()),
+                disconnected: Mutex::new(Vec::new()),
+                clipboard: Mutex::new(Vec::new()),
+                errors: Mutex::new(Vec::new()),
+                delivered: Mutex::new(Vec::new()),
+                failed: Mutex::new(Vec::new()),
+                proximity: Mutex::new(Vec::new()),
+                updated: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn connected_count(&self) -> usize {
+            self.connected.lock().unwrap().len()
+        }
+
+        fn disconnected_count(&self) -> usize {
+            self.disconnected.lock().unwrap().len()
+        }
+
+        fn clipboard_count(&self) -> usize {
+            self.clipboard.lock().unwrap().len()
+        }
+
+        fn error_count(&self) -> usize {
+            self.errors.lock().unwrap().len()
+        }
+    }
+
+    impl NearClipCallback for TestCallback {
+        fn on_device_connected(&self, device: &DeviceInfo) {
+            self.connected.lock().unwrap().push(device.id().to_string());
+        }
+
+        fn on_device_disconnected(&self, device_id: &str) {
+            self.disconnected.lock().unwrap().push(device_id.to_string());
+        }
+
+        fn on_device_unpaired(&self, _device_id: &str) {}
+
+        fn on_pairing_rejected(&self, _device_id: &str, _reason: &str) {}
+
+        fn on_clipboard_received(&self, content: &[u8], from_device: &str) {
+            self.clipboard
+                .lock()
+                .unwrap()
+                .push((content.to_vec(), from_device.to_string()));
+        }
+
+        fn on_sync_error(&self, error: &NearClipError) {
+            self.errors.lock().unwrap().push(error.to_string());
+        }
+
+        fn on_sync_delivered(&self, device_id: &str, message_id: u64) {
+            self.delivered.lock().unwrap().push((device_id.to_string(), message_id));
+        }
+
+        fn on_sync_failed(&self, device_id: &str, message_id: u64, _error: &NearClipError) {
+            self.failed.lock().unwrap().push((device_id.to_string(), message_id));
+        }
+
+        fn on_proximity_entered(&self, device_id: &str, _rssi: i16) {
+            self.proximity.lock().unwrap().push((device_id.to_string(), true));
+        }
+
+        fn on_proximity_left(&self, device_id: &str) {
+            self.proximity.lock().unwrap().push((device_id.to_string(), false));
+        }
+
+        fn on_device_updated(&self, device: &DeviceInfo) {
+            self.updated.lock().unwrap().push(device.clone());
+        }
+    }
+
+    /// 轮询等待条件成立（最多 2 秒）
+    async fn wait_until(condition: impl Fn() -> bool) -> bool {
+        for _ in 0..100 {
+            if condition() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        condition()
+    }
+
+    /// 对已发出的消息回复 ACK，直到设备的发件箱清空
+    async fn ack_until_flushed(
+        manager: &NearClipManager,
+        transport: &MockTransport,
+        outbox: &OutboxManager,
+        device_id: &str,
+    ) -> bool {
+        for _ in 0..100 {
+            for msg in transport.get_sent_messages().await {
+                manager.delivery_tracker().acknowledge(device_id, msg.message_id);
+            }
+            if outbox.pending_count(device_id).unwrap() == 0 {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        false
+    }
+
+    fn create_manager() -> NearClipManager {
+        let config = NearClipConfig::new("Test Device");
+        let callback = Arc::new(NoOpCallback);
+        NearClipManager::new(config, callback).unwrap()
+    }
+
+    fn create_manager_with_callback() -> (NearClipManager, Arc<TestCallback>) {
+        let config = NearClipConfig::new("Test Device");
+        let callback = Arc::new(TestCallback::new());
+        let manager = NearClipManager::new(config, callback.clone()).unwrap();
+        (manager, callback)
+    }
+
+    // --------------------------------------------------------
+    // 创建测试
+    // --------------------------------------------------------
+
+    #[test]
+    fn test_manager_new() {
+        let manager = create_manager();
+        assert!(!manager.is_running());
+    }
+
+    #[test]
+    fn test_manager_new_invalid_config() {
+        let config = NearClipConfig::new(""); // 无效名称
+        let callback = Arc::new(NoOpCallback);
+        let result = NearClipManager::new(config, callback);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_manager_config() {
+        let manager = create_manager();
+        assert_eq!(manager.config().device_name(), "Test Device");
+    }
+
+    // --------------------------------------------------------
+    // 生命周期测试
+    // --------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_manager_start_stop() {
+        let manager = create_manager();
+
+        assert!(!manager.is_running());
+
+        manager.start().await.unwrap();
+        assert!(manager.is_running());
+
+        manager.stop().await;
+        assert!(!manager.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_manager_start_twice() {
+        let manager = create_manager();
+
+        manager.start().await.unwrap();
+        // 第二次启动不应该失败
+        manager.start().await.unwrap();
+
+        assert!(manager.is_running());
+    }
+
+    fn interface_address(name: &str, ip: IpAddr) -> InterfaceAddress {
+        InterfaceAddress {
+            name: name.to_string(),
+            ip,
+            prefix_len: 24,
+            broadcast: None,
+        }
+    }
+
+    #[test]
+    fn test_listen_addrs_follow_interface_filter() {
+        let addresses = vec![
+            interface_address("en0", "192.168.1.10".parse().unwrap()),
+            interface_address("en0", "fe80::1".parse().unwrap()),
+            interface_address("utun3", "10.8.0.2".parse().unwrap()),
+        ];
+
+        // 未限制接口时绑定所有地址
+        assert_eq!(
+            listen_addrs(&InterfaceFilter::new(), &addresses),
+            vec![IpAddr::V4(Ipv4Addr::UNSPECIFIED)]
+        );
+
+        // 只绑定允许接口上的 IPv4 地址
+        let filter = InterfaceFilter::new().with_allowed_interface("en*");
+        assert_eq!(listen_addrs(&filter, &addresses), vec!["192.168.1.10".parse::<IpAddr>().unwrap()]);
+        let filter = InterfaceFilter::new().with_exclude_virtual(true);
+        assert_eq!(listen_addrs(&filter, &addresses), vec!["192.168.1.10".parse::<IpAddr>().unwrap()]);
+
+        // 没有允许的地址时只监听回环地址
+        let filter = InterfaceFilter::new().with_allowed_interface("wlan0");
+        assert_eq!(listen_addrs(&filter, &addresses), vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]);
+    }
+
+    #[tokio::test]
+    async fn test_manager_listens_only_on_allowed_interfaces() {
+        let config = NearClipConfig::new("Test")
+            .with_ble_enabled(false)
+            .with_network_monitor(false)
+            .with_interface_filter(InterfaceFilter::new().with_allowed_interface("nearclip-test*"));
+        let manager = NearClipManager::new(config, Arc::new(NoOpCallback)).unwrap();
+        manager.start().await.unwrap();
+        let port = manager.listening_port().await.unwrap();
+
+        let ctx = manager.session_context();
+        let bound = |network: &Option<NetworkServices>| {
+            let mut addrs: Vec<IpAddr> = network.as_ref().unwrap().listeners.keys().copied().collect();
+            addrs.sort();
+            addrs
+        };
+        assert_eq!(bound(&*manager.network.lock().await), vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]);
+        assert!(tokio::net::TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await.is_ok());
+        for address in list_interfaces().unwrap_or_default().iter().filter(|a| a.ip.is_ipv4()) {
+            assert!(tokio::net::TcpStream::connect((address.ip, port)).await.is_err());
+        }
+
+        // 允许的接口出现后改为绑定它的地址，端口号不变
+        let interfaces = list_interfaces().unwrap_or_default();
+        if let Some(address) = interfaces.iter().find(|a| a.ip.is_ipv4()) {
+            let filter = InterfaceFilter::new().with_allowed_interface(address.name.clone());
+            ctx.rebind_listeners(&filter, &interfaces).await;
+            tokio::task::yield_now().await;
+            assert_eq!(bound(&*manager.network.lock().await), listen_addrs(&filter, &interfaces));
+            assert_eq!(manager.listening_port().await, Some(port));
+            assert!(tokio::net::TcpStream::connect((address.ip, port)).await.is_ok());
+            assert!(tokio::net::TcpStream::connect((Ipv4Addr::LOCALHOST, port)).await.is_err());
+        }
+
+        manager.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_manager_stop_when_not_running() {
+        let manager = create_manager();
+        // 未启动时停止不应该失败
+        manager.stop().await;
+        assert!(!manager.is_running());
+    }
+
+    // --------------------------------------------------------
+    // 通道测试
+    // --------------------------------------------------------
+
+    #[tokio::test]
+    async fn test_manager_current_channel_wifi() {
+        let config = NearClipConfig::new("Test")
+            .with_wifi_enabled(true)
+            .with_ble_enabled(false);
+        let callback = Arc::new(NoOpCallback);
+        let manager = NearClipManager::new(config, callback).unwrap();
+
+        manager.start().await.unwrap();
+        assert_eq!(manager.current_channel(), Some(Channel::Wifi));
+    }
+
+    #[tokio::test]
+    async fn test_manager_current_channel_ble() {
+        let config = NearClipConfig::new("Test")
+            .with_wifi_enabled(false)
+            .with_ble_enabled(true);
+        let callback = Arc::new(NoOpCallback);
+        let manager = NearClipManager::new(config, callback).unwrap();
+
+        manager.start().await.unwrap();
+        assert_eq!(manager.current_channel(), Some(Channel::Ble));
+    }
+
+    #[test]
+    fn test_manager_current_channel_not_running() {
+        let manager = create_manager();
+        assert_eq!(manager.current_channel(), None);
+    }
+
+    // --------------------------------------------------------
+    // 设备管理测试
+    // --------------------------------------------------------
+
+    #[test]
+    fn test_manager_add_paired_device() {
+        let manager = create_manager();
+
+        let device = DeviceInfo::new("d1", "Device 1")
+            .with_platform(DevicePlatform::MacOS);
+        manager.add_paired_device(device);
+
+        assert_eq!(manager.get_paired_devices().len(), 1);
+    }
+
+    #[test]
+    fn test_manager_remove_paired_device() {
+        let manager = create_manager();
+
+        let device = DeviceInfo::new("d1", "Device 1");
+        manager.add_paired_device(device);
+        assert_eq!(manager.get_paired_devices().len(), 1);
+
+        let removed = manager.remove_paired_device("d1");
+        assert!(removed.is_some());
+        assert_eq!(manager.get_paired_devices().len(), 0);
+    }
+
+    #[test]
+    fn test_manager_get_device_status() {
+        let manager = create_manager();
+
+        let device = DeviceInfo::new("d1", "Device 1")
+            .with_status(DeviceStatus::Connected);
+        manager.add_paired_device(device);
+
+        assert_eq!(
+            manager.get_device_status("d1"),
+            Some(DeviceStatus::Connected)
+        );
+        assert_eq!(manager.get_device_status("unknown"), None);
+    }
+
+    #[test]
+    fn test_manager_get_connected_devices() {
+        let manager = create_manager();
+
+        let d1 = DeviceInfo::new("d1", "D1").with_status(DeviceStatus::Connected);
+        let d2 = DeviceInfo::new("d2", "D2").with_status(DeviceStatus::Disconnected);
+        let d3 = DeviceInfo::new("d3", "D3").with_status(DeviceStatus::Connected);
+
+        manager.add_paired_device(d1);
+        manager.add_paired_device(d2);
+        manager.add_paired_device(d3);
+
+        assert_eq!(manager.get_paired_devices().len(), 3);
+        assert_eq!(manager.get_connected_devices().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_manager_set_device_channel_preference() {
+        let manager = create_manager();
+        manager.add_paired_device(DeviceInfo::new("d1", "Device 1"));
+
+        assert_eq!(
+            manager.get_device_channel_preference("d1"),
+            Some(ChannelPreference::Auto)
+        );
+
+        manager
+            .set_device_channel_preference("d1", ChannelPreference::Hybrid)
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.get_device_channel_preference("d1"),
+            Some(ChannelPreference::Hybrid)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_manager_set_device_channel_preference_not_found() {
+        let manager = create_manager();
+        let result = manager
+            .set_device_channel_preference("unknown", ChannelPreference::WifiOnly)
+            .await;
+        assert!(matches!(result, Err(NearClipError::DeviceNotFound(_))));
+        assert_eq!(manager.get_device_channel_preference("unknown"), None);
+    }
+
+    #[test]
+    fn test_manager_message_guard_shared() {
+        let manager = create_manager();
+        let guard = manager.message_guard();
+        assert!(guard.record_message_id("d1", 99));
+        assert!(!manager.message_guard().record_message_id("d1", 99));
+    }
+
+    #[tokio::test]
+    async fn test_sync_clipboard_delivered_on_ack() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.start().await.unwrap();
+
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
+
+        let message_id = manager.sync_clipboard(b"hello").await.unwrap();
+        let sent = transport.get_sent_messages().await;
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].message_id, message_id);
+
+        assert!(manager.delivery_tracker().acknowledge("peer-1", message_id));
+        assert!(wait_until(|| !callback.delivered.lock().unwrap().is_empty()).await);
+        assert_eq!(callback.delivered.lock().unwrap()[0], ("peer-1".to_string(), message_id));
+        assert!(callback.failed.lock().unwrap().is_empty());
+
+        manager.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_clipboard_failed_without_ack() {
+        let config = NearClipConfig::new("Test Device")
+            .with_ack_timeout(Duration::from_millis(50))
+            .with_max_retries(1);
+        let callback = Arc::new(TestCallback::new());
+        let manager = NearClipManager::new(config, callback.clone()).unwrap();
+        manager.start().await.unwrap();
+
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
+
+        let message_id = manager.sync_clipboard(b"hello").await.unwrap();
+        assert!(wait_until(|| !callback.failed.lock().unwrap().is_empty()).await);
+        assert_eq!(callback.failed.lock().unwrap()[0], ("peer-1".to_string(), message_id));
+        assert!(callback.delivered.lock().unwrap().is_empty());
+
+        // 原始发送 + 1 次重发，消息 ID 不变
+        let sent = transport.get_sent_messages().await;
+        assert_eq!(sent.len(), 2);
+        assert!(sent.iter().all(|m| m.message_id == message_id));
+        assert_eq!(manager.delivery_tracker().pending_count(), 0);
+
+        manager.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_clipboard_queues_offline_device_and_flushes_on_connect() {
+        let (manager, _callback) = create_manager_with_callback();
+        let db_path = std::env::temp_dir().join(format!("test_manager_outbox_{}.db", uuid::Uuid::new_v4()));
+        manager.init_outbox(db_path.clone()).unwrap();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer").with_platform(DevicePlatform::Android));
+        manager.start().await.unwrap();
+
+        manager.sync_clipboard(b"first").await.unwrap();
+        manager.sync_clipboard(b"second").await.unwrap();
+        manager.sync_clipboard(b"first").await.unwrap();
+        let outbox = manager.outbox().unwrap();
+        assert_eq!(outbox.pending_count("peer-1").unwrap(), 2);
+
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
+
+        assert!(ack_until_flushed(&manager, &transport, &outbox, "peer-1").await);
+        let payloads: Vec<Vec<u8>> = transport
+            .get_sent_messages()
+            .await
+            .into_iter()
+            .map(|m| m.payload)
+            .collect();
+        assert_eq!(payloads, vec![b"second".to_vec(), b"first".to_vec()]);
+
+        manager.stop().await;
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn test_outbox_flush_keeps_unacknowledged_clips() {
+        let config = NearClipConfig::new("Test Device").with_ack_timeout(Duration::from_millis(50));
+        let manager = NearClipManager::new(config, Arc::new(TestCallback::new())).unwrap();
+        let db_path = std::env::temp_dir().join(format!("test_manager_outbox_{}.db", uuid::Uuid::new_v4()));
+        manager.init_outbox(db_path.clone()).unwrap();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
+
+        let outbox = manager.outbox().unwrap();
+        outbox.enqueue("peer-1", b"needs ack").unwrap();
+
+        // 发出但没有 ACK：条目保留
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
+        let sent = loop {
+            let sent = transport.get_sent_messages().await;
+            if !sent.is_empty() {
+                break sent;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        assert_eq!(sent[0].payload, b"needs ack".to_vec());
+        assert!(wait_until(|| manager.delivery_tracker().pending_count() == 0).await);
+        assert_eq!(outbox.pending_count("peer-1").unwrap(), 1);
+
+        // 重新连接后再次冲刷，收到 ACK 才删除
+        manager.remove_ble_transport("peer-1").await;
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
+        assert!(ack_until_flushed(&manager, &transport, &outbox, "peer-1").await);
+        assert_eq!(transport.get_sent_messages().await.len(), 1);
+
+        manager.stop().await;
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_remove_paired_device_clears_outbox() {
+        let manager = create_manager();
+        let db_path = std::env::temp_dir().join(format!("test_manager_outbox_{}.db", uuid::Uuid::new_v4()));
+        manager.init_outbox(db_path.clone()).unwrap();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+
+        let outbox = manager.outbox().unwrap();
+        outbox.enqueue("peer-1", b"queued").unwrap();
+        manager.remove_paired_device("peer-1");
+        assert_eq!(outbox.pending_count("peer-1").unwrap(), 0);
+
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[tokio::test]
+    async fn test_manager_reuses_persisted_port() {
+        let manager = create_manager();
+        let db_path = std::env::temp_dir().join(format!("test_manager_port_{}.db", uuid::Uuid::new_v4()));
+        assert_eq!(manager.listening_port().await, None);
+
+        // 找一个空闲端口作为"上次使用的端口"
+        let free_port = std::net::TcpListener::bind("0.0.0.0:0").unwrap().local_addr().unwrap().port();
+        PortStore::new(db_path.clone()).unwrap().save(free_port).unwrap();
+
+        manager.init_port_store(db_path.clone()).unwrap();
+        manager.start().await.unwrap();
+        assert_eq!(manager.listening_port().await, Some(free_port));
+        manager.stop().await;
+        assert_eq!(manager.listening_port().await, None);
+
+        assert_eq!(PortStore::new(db_path.clone()).unwrap().load().unwrap(), Some(free_port));
+        let _ = std::fs::remove_file(db_path);
     }
 
-    fn create_manager_with_callback() -> (NearClipManager, Arc<TestCallback>) {
-        let config = NearClipConfig::new("Test Device");
-        let callback = Arc::new(TestCallback::new());
-        let manager = NearClipManager::new(config, callback.clone()).unwrap();
-        (manager, callback)
+    #[test]
+    fn test_manager_channel_quality_snapshot() {
+        let manager = create_manager();
+        manager.channel_monitor().record_rssi(Channel::Ble, -70);
+        manager
+            .channel_monitor()
+            .record_device_rssi("device-1", Channel::Ble, -60);
+
+        let snapshot = manager.channel_quality();
+        assert_eq!(snapshot.current, None);
+        assert!(snapshot.decisions.is_empty());
+        let ble = snapshot.metrics.iter().find(|m| m.channel == Channel::Ble).unwrap();
+        assert_eq!(ble.rssi, Some(-70));
+        assert_eq!(snapshot.devices.len(), 1);
+        assert_eq!(snapshot.devices[0].device_id, "device-1");
+        let device_ble = snapshot.devices[0]
+            .metrics
+            .iter()
+            .find(|m| m.channel == Channel::Ble)
+            .unwrap();
+        assert_eq!(device_ble.rssi, Some(-60));
+
+        manager.remove_paired_device("device-1");
+        assert!(manager.channel_quality().devices.is_empty());
     }
 
     // --------------------------------------------------------
-    // 创建测试
+    // 距离感知测试
     // --------------------------------------------------------
 
     #[test]
-    fn test_manager_new() {
-        let manager = create_manager();
-        assert!(!manager.is_running());
-    }
+    fn test_handle_rssi_proximity_callbacks() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+
+        // 未配对设备的读数被忽略
+        assert_eq!(manager.handle_rssi("stranger", -30), ProximityState::Unknown);
+        assert_eq!(manager.get_device_proximity("stranger"), ProximityState::Unknown);
+
+        // 按设备阈值判断：-55dBm 对默认阈值算附近，对 -45/-60 不算
+        manager
+            .set_device_proximity_thresholds("peer-1", Some(ProximityThresholds::new(-45, -60)))
+            .unwrap();
+        assert_eq!(manager.handle_rssi("peer-1", -55), ProximityState::Far);
+        assert_eq!(manager.get_device_rssi("peer-1"), Some(-55));
+        assert!(callback.proximity.lock().unwrap().is_empty());
+
+        while manager.handle_rssi("peer-1", -30) != ProximityState::Near {}
+        assert_eq!(callback.proximity.lock().unwrap().as_slice(), &[("peer-1".to_string(), true)]);
+
+        for _ in 0..20 {
+            if manager.handle_rssi("peer-1", -90) == ProximityState::Far {
+                break;
+            }
+        }
+        assert_eq!(manager.get_device_proximity("peer-1"), ProximityState::Far);
+        assert_eq!(
+            callback.proximity.lock().unwrap().last(),
+            Some(&("peer-1".to_string(), false))
+        );
 
-    #[test]
-    fn test_manager_new_invalid_config() {
-        let config = NearClipConfig::new(""); // 无效名称
-        let callback = Arc::new(NoOpCallback);
-        let result = NearClipManager::new(config, callback);
-        assert!(result.is_err());
+        manager.remove_paired_device("peer-1");
+        assert_eq!(manager.get_device_proximity("peer-1"), ProximityState::Unknown);
     }
 
     #[test]
-    fn test_manager_config() {
+    fn test_set_device_proximity_thresholds() {
         let manager = create_manager();
-        assert_eq!(manager.config().device_name(), "Test Device");
+        manager.add_paired_device(DeviceInfo::new("d1", "Device 1"));
+        assert_eq!(manager.get_device_proximity_thresholds("d1"), None);
+
+        let thresholds = ProximityThresholds::new(-55, -70);
+        manager.set_device_proximity_thresholds("d1", Some(thresholds)).unwrap();
+        assert_eq!(manager.get_device_proximity_thresholds("d1"), Some(thresholds));
+
+        let invalid = ProximityThresholds::new(-70, -55);
+        assert!(matches!(
+            manager.set_device_proximity_thresholds("d1", Some(invalid)),
+            Err(NearClipError::Config(_))
+        ));
+        assert!(matches!(
+            manager.set_device_proximity_thresholds("unknown", None),
+            Err(NearClipError::DeviceNotFound(_))
+        ));
+
+        manager.set_device_proximity_thresholds("d1", None).unwrap();
+        assert_eq!(manager.get_device_proximity_thresholds("d1"), None);
     }
 
-    // --------------------------------------------------------
-    // 生命周期测试
-    // --------------------------------------------------------
-
     #[tokio::test]
-    async fn test_manager_start_stop() {
-        let manager = create_manager();
+    async fn test_sync_paused_while_device_far() {
+        let policy = ProximityPolicy {
+            sync_only_when_near: true,
+            ..Default::default()
+        };
+        let config = NearClipConfig::new("Test Device").with_proximity_policy(policy);
+        let callback = Arc::new(TestCallback::new());
+        let manager = NearClipManager::new(config, callback.clone()).unwrap();
+        let db_path = std::env::temp_dir().join(format!("test_manager_outbox_{}.db", uuid::Uuid::new_v4()));
+        manager.init_outbox(db_path.clone()).unwrap();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
 
-        assert!(!manager.is_running());
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
 
-        manager.start().await.unwrap();
-        assert!(manager.is_running());
+        // 远离时剪贴板写入发件箱
+        assert_eq!(manager.handle_rssi("peer-1", -95), ProximityState::Far);
+        manager.sync_clipboard(b"while away").await.unwrap();
+        assert!(transport.get_sent_messages().await.is_empty());
+        let outbox = manager.outbox().unwrap();
+        assert_eq!(outbox.pending_count("peer-1").unwrap(), 1);
+
+        // 回到附近后冲刷发件箱
+        while manager.handle_rssi("peer-1", -40) != ProximityState::Near {}
+        assert_eq!(callback.proximity.lock().unwrap().as_slice(), &[("peer-1".to_string(), true)]);
+        assert!(ack_until_flushed(&manager, &transport, &outbox, "peer-1").await);
+        let sent = transport.get_sent_messages().await;
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].payload, b"while away".to_vec());
 
         manager.stop().await;
-        assert!(!manager.is_running());
+        let _ = std::fs::remove_file(db_path);
     }
 
     #[tokio::test]
-    async fn test_manager_start_twice() {
-        let manager = create_manager();
-
-        manager.start().await.unwrap();
-        // 第二次启动不应该失败
+    async fn test_connected_device_without_scan_readings_keeps_syncing() {
+        let policy = ProximityPolicy {
+            sync_only_when_near: true,
+            ..Default::default()
+        };
+        let config = NearClipConfig::new("Test Device").with_proximity_policy(policy);
+        let manager = NearClipManager::new(config, Arc::new(TestCallback::new())).unwrap();
+        manager.add_paired_device(DeviceInfo::new("phone", "Phone"));
+        manager.add_paired_device(DeviceInfo::new("laptop", "Laptop"));
         manager.start().await.unwrap();
 
-        assert!(manager.is_running());
+        // phone 被扫描到后连接，laptop 从未被扫描到
+        while manager.handle_rssi("phone", -40) != ProximityState::Near {}
+        let phone = Arc::new(MockTransport::new("phone", MockConfig::new().with_channel(Channel::Ble)));
+        let laptop = Arc::new(MockTransport::new("laptop", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("phone", phone.clone()).await;
+        manager.add_ble_transport("laptop", laptop.clone()).await;
+
+        // 连接后扫描读数停止，超时检查不把它们标记为远离
+        let later = std::time::Instant::now() + policy.timeout * 10;
+        assert!(manager.proximity.expire_at(later).is_empty());
+        assert_eq!(manager.get_device_proximity("phone"), ProximityState::Near);
+        assert_eq!(manager.get_device_proximity("laptop"), ProximityState::Unknown);
+
+        manager.sync_clipboard(b"still here").await.unwrap();
+        assert_eq!(phone.get_sent_messages().await.len(), 1);
+        assert_eq!(laptop.get_sent_messages().await.len(), 1);
+
+        // 断开后重新开始计时
+        manager.remove_ble_transport("phone").await;
+        let later = std::time::Instant::now() + policy.timeout;
+        assert_eq!(manager.proximity.expire_at(later), vec!["phone".to_string()]);
+        assert_eq!(manager.get_device_proximity("phone"), ProximityState::Far);
+
+        manager.stop().await;
     }
 
     #[tokio::test]
-    async fn test_manager_stop_when_not_running() {
-        let manager = create_manager();
-        // 未启动时停止不应该失败
+    async fn test_far_device_connect_does_not_flush_outbox() {
+        let policy = ProximityPolicy {
+            sync_only_when_near: true,
+            ..Default::default()
+        };
+        let config = NearClipConfig::new("Test Device").with_proximity_policy(policy);
+        let manager = NearClipManager::new(config, Arc::new(TestCallback::new())).unwrap();
+        let db_path = std::env::temp_dir().join(format!("test_manager_outbox_{}.db", uuid::Uuid::new_v4()));
+        manager.init_outbox(db_path.clone()).unwrap();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
+
+        let outbox = manager.outbox().unwrap();
+        outbox.enqueue("peer-1", b"queued while away").unwrap();
+        assert_eq!(manager.handle_rssi("peer-1", -95), ProximityState::Far);
+
+        // 远离时连接：发件箱保持不动
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(transport.get_sent_messages().await.is_empty());
+        assert_eq!(outbox.pending_count("peer-1").unwrap(), 1);
+
+        // 回到附近后才冲刷
+        while manager.handle_rssi("peer-1", -40) != ProximityState::Near {}
+        assert!(ack_until_flushed(&manager, &transport, &outbox, "peer-1").await);
+        assert_eq!(transport.get_sent_messages().await.len(), 1);
+
         manager.stop().await;
-        assert!(!manager.is_running());
+        let _ = std::fs::remove_file(db_path);
     }
 
     // --------------------------------------------------------
-    // 通道测试
+    // 会话测试
     // --------------------------------------------------------
 
-    #[tokio::test]
-    async fn test_manager_current_channel_wifi() {
-        let config = NearClipConfig::new("Test")
-            .with_wifi_enabled(true)
-            .with_ble_enabled(false);
-        let callback = Arc::new(NoOpCallback);
-        let manager = NearClipManager::new(config, callback).unwrap();
+    /// 在管理器中创建一个已激活、由核心驱动收发的会话
+    async fn open_core_session(manager: &NearClipManager, transport: Arc<MockTransport>) -> Arc<Session> {
+        let ctx = manager.session_context();
+        let session = Arc::new(Session::new("peer-1", transport, SessionDirection::Outgoing));
+        assert!(ctx.register(&session).await);
+        session.transition(SessionState::Active).unwrap();
+        ctx.spawn_tasks(&session);
+        session
+    }
 
+    #[tokio::test]
+    async fn test_sync_clipboard_send_failure_closes_session_once() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
         manager.start().await.unwrap();
-        assert_eq!(manager.current_channel(), Some(Channel::Wifi));
+
+        let transport = Arc::new(MockTransport::new(
+            "peer-1",
+            MockConfig::new()
+                .with_channel(Channel::Ble)
+                .with_error(TransportError::SendFailed("broken pipe".to_string())),
+        ));
+        manager.add_ble_transport("peer-1", transport).await;
+        assert_eq!(manager.session_state("peer-1", Channel::Ble), Some(SessionState::Active));
+
+        manager.sync_clipboard(b"hello").await.unwrap();
+
+        assert_eq!(callback.disconnected_count(), 1);
+        assert_eq!(callback.failed.lock().unwrap().len(), 1);
+        assert!(manager.sessions().is_empty());
+        assert_eq!(manager.get_device_status("peer-1"), Some(DeviceStatus::Disconnected));
+
+        // 停止时不会再次回调
+        manager.stop().await;
+        assert_eq!(callback.disconnected_count(), 1);
     }
 
     #[tokio::test]
-    async fn test_manager_current_channel_ble() {
-        let config = NearClipConfig::new("Test")
-            .with_wifi_enabled(false)
-            .with_ble_enabled(true);
-        let callback = Arc::new(NoOpCallback);
-        let manager = NearClipManager::new(config, callback).unwrap();
-
+    async fn test_remove_ble_transport_notifies_once() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
         manager.start().await.unwrap();
-        assert_eq!(manager.current_channel(), Some(Channel::Ble));
+
+        let transport = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", transport.clone()).await;
+
+        manager.remove_ble_transport("peer-1").await;
+        manager.remove_ble_transport("peer-1").await;
+        assert_eq!(callback.disconnected_count(), 1);
+        assert!(!transport.is_connected());
+        assert_eq!(manager.session_state("peer-1", Channel::Ble), None);
+
+        manager.stop().await;
+        assert_eq!(callback.disconnected_count(), 1);
     }
 
-    #[test]
-    fn test_manager_current_channel_not_running() {
-        let manager = create_manager();
-        assert_eq!(manager.current_channel(), None);
+    #[tokio::test]
+    async fn test_replaced_ble_session_does_not_notify() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
+
+        let first = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        let second = Arc::new(MockTransport::new("peer-1", MockConfig::new().with_channel(Channel::Ble)));
+        manager.add_ble_transport("peer-1", first.clone()).await;
+        manager.add_ble_transport("peer-1", second.clone()).await;
+
+        assert!(!first.is_connected());
+        assert_eq!(manager.sessions().len(), 1);
+        assert_eq!(callback.disconnected_count(), 0);
+
+        manager.stop().await;
+        assert_eq!(callback.disconnected_count(), 1);
     }
 
-    // --------------------------------------------------------
-    // 设备管理测试
-    // --------------------------------------------------------
+    #[tokio::test]
+    async fn test_disconnect_device_stops_session_tasks() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
 
-    #[test]
-    fn test_manager_add_paired_device() {
-        let manager = create_manager();
+        let transport = Arc::new(MockTransport::with_defaults("peer-1"));
+        let session = open_core_session(&manager, transport.clone()).await;
 
-        let device = DeviceInfo::new("d1", "Device 1")
-            .with_platform(DevicePlatform::MacOS);
-        manager.add_paired_device(device);
+        manager.disconnect_device("peer-1").await.unwrap();
+        assert_eq!(session.state(), SessionState::Closed);
+        assert_eq!(callback.disconnected_count(), 1);
 
-        assert_eq!(manager.get_paired_devices().len(), 1);
+        // 接收任务已停止，之后注入的消息不会被处理
+        transport.inject_message(Message::clipboard_sync(b"late", "peer-1".to_string())).await;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(callback.clipboard_count(), 0);
+
+        manager.stop().await;
+        assert_eq!(callback.disconnected_count(), 1);
     }
 
-    #[test]
-    fn test_manager_remove_paired_device() {
-        let manager = create_manager();
+    #[tokio::test]
+    async fn test_handshake_stores_peer_advertisement_key() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.start().await.unwrap();
 
-        let device = DeviceInfo::new("d1", "Device 1");
-        manager.add_paired_device(device);
-        assert_eq!(manager.get_paired_devices().len(), 1);
+        // 入站会话以临时 ID 开始握手，对端在 PairingRequest 中携带广播密钥
+        let transport = Arc::new(MockTransport::with_defaults("127.0.0.1:50000"));
+        let ctx = manager.session_context();
+        let session = Arc::new(Session::new("127.0.0.1:50000", transport.clone(), SessionDirection::Incoming));
+        session.transition(SessionState::Handshaking).unwrap();
+        assert!(ctx.register(&session).await);
+        ctx.spawn_tasks(&session);
+
+        let payload = PairingPayload::new("peer-1", "Peer", ProtocolPlatform::Android)
+            .with_advertisement_key(&[5u8; 32]);
+        transport
+            .inject_message(Message::pairing_request(payload.serialize().unwrap(), "peer-1".to_string()))
+            .await;
+
+        assert!(wait_until(|| callback.updated.lock().unwrap().len() == 1).await);
+        let expected = AdvertisementKey::from_bytes([5u8; 32]);
+        assert_eq!(callback.updated.lock().unwrap()[0].advertisement_key(), Some(&expected));
+        let device = manager.get_paired_devices().into_iter().find(|d| d.id() == "peer-1").unwrap();
+        assert_eq!(device.advertisement_key(), Some(&expected));
 
-        let removed = manager.remove_paired_device("d1");
-        assert!(removed.is_some());
-        assert_eq!(manager.get_paired_devices().len(), 0);
+        manager.stop().await;
     }
 
-    #[test]
-    fn test_manager_get_device_status() {
-        let manager = create_manager();
+    #[tokio::test]
+    async fn test_session_remote_close_notifies_once() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
 
-        let device = DeviceInfo::new("d1", "Device 1")
-            .with_status(DeviceStatus::Connected);
-        manager.add_paired_device(device);
+        let transport = Arc::new(MockTransport::with_defaults("peer-1"));
+        let session = open_core_session(&manager, transport.clone()).await;
 
-        assert_eq!(
-            manager.get_device_status("d1"),
-            Some(DeviceStatus::Connected)
-        );
-        assert_eq!(manager.get_device_status("unknown"), None);
+        transport.inject_message(Message::clipboard_sync(b"hello", "peer-1".to_string())).await;
+        assert!(wait_until(|| callback.clipboard_count() == 1).await);
+
+        transport.disconnect();
+        assert!(wait_until(|| session.state() == SessionState::Closed).await);
+        assert_eq!(callback.disconnected_count(), 1);
+        assert!(manager.sessions().is_empty());
+
+        manager.stop().await;
+        assert_eq!(callback.disconnected_count(), 1);
     }
 
-    #[test]
-    fn test_manager_get_connected_devices() {
-        let manager = create_manager();
+    #[tokio::test]
+    async fn test_session_heartbeat_records_rtt_and_times_out() {
+        let config = NearClipConfig::new("Test Device").with_heartbeat_interval(Duration::from_millis(30));
+        let callback = Arc::new(TestCallback::new());
+        let manager = NearClipManager::new(config, callback.clone()).unwrap();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
 
-        let d1 = DeviceInfo::new("d1", "D1").with_status(DeviceStatus::Connected);
-        let d2 = DeviceInfo::new("d2", "D2").with_status(DeviceStatus::Disconnected);
-        let d3 = DeviceInfo::new("d3", "D3").with_status(DeviceStatus::Connected);
+        let transport = Arc::new(MockTransport::with_defaults("peer-1"));
+        let session = open_core_session(&manager, transport.clone()).await;
 
-        manager.add_paired_device(d1);
-        manager.add_paired_device(d2);
-        manager.add_paired_device(d3);
+        // 回复第一个心跳
+        let heartbeat = loop {
+            let sent = transport.get_sent_messages().await;
+            if let Some(msg) = sent.into_iter().find(|m| m.msg_type == MessageType::Heartbeat) {
+                break msg;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        };
+        transport
+            .inject_message(Message::ack_for(heartbeat.message_id, "peer-1".to_string()))
+            .await;
+        assert!(wait_until(|| session.metrics().rtt.is_some()).await);
+        let wifi = manager
+            .channel_quality()
+            .metrics
+            .into_iter()
+            .find(|m| m.channel == Channel::Wifi)
+            .unwrap();
+        assert!(wifi.rtt_ms.is_some());
+        assert!(manager
+            .channel_monitor()
+            .device_metrics("peer-1", Channel::Wifi)
+            .rtt_ms
+            .is_some());
+
+        // 之后不再回复，3 个间隔后超时关闭
+        assert!(wait_until(|| session.state() == SessionState::Closed).await);
+        assert_eq!(callback.disconnected_count(), 1);
 
-        assert_eq!(manager.get_paired_devices().len(), 3);
-        assert_eq!(manager.get_connected_devices().len(), 2);
+        manager.stop().await;
+        assert_eq!(callback.disconnected_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_session_replies_to_heartbeat() {
+        let (manager, _callback) = create_manager_with_callback();
+        manager.start().await.unwrap();
+
+        let transport = Arc::new(MockTransport::with_defaults("peer-1"));
+        open_core_session(&manager, transport.clone()).await;
+
+        let heartbeat = Message::heartbeat("peer-1".to_string());
+        transport.inject_message(heartbeat.clone()).await;
+
+        let mut acked = false;
+        for _ in 0..100 {
+            acked = transport
+                .get_sent_messages()
+                .await
+                .iter()
+                .any(|m| m.acked_message_id() == Some(heartbeat.message_id));
+            if acked {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(acked);
+
+        manager.stop().await;
     }
 
     // --------------------------------------------------------
@@ -1846,6 +4196,195 @@ mod tests {
         assert!(debug.contains("NoOpCallback"));
     }
 
+    // --------------------------------------------------------
+    // 静态对端测试
+    // --------------------------------------------------------
+
+    #[test]
+    fn test_static_peer_management() {
+        let manager = create_manager();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+
+        manager.add_static_peer("peer-1", "10.0.0.5:8765").unwrap();
+        manager.add_static_peer("peer-1", "laptop.vpn.example:8765").unwrap();
+        manager.add_static_peer("peer-1", "10.0.0.5:8765").unwrap();
+        assert_eq!(manager.get_static_peers("peer-1").len(), 2);
+
+        assert!(matches!(
+            manager.add_static_peer("peer-1", "not-an-address"),
+            Err(NearClipError::Config(_))
+        ));
+        assert!(matches!(
+            manager.add_static_peer("unknown", "10.0.0.5:8765"),
+            Err(NearClipError::DeviceNotFound(_))
+        ));
+
+        assert!(manager.remove_static_peer("peer-1", "10.0.0.5:8765").unwrap());
+        assert!(!manager.remove_static_peer("peer-1", "10.0.0.5:8765").unwrap());
+        assert_eq!(
+            manager.get_static_peers("peer-1"),
+            vec!["laptop.vpn.example:8765".parse::<PeerAddress>().unwrap()]
+        );
+
+        // 平台重新添加设备时保留静态地址
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer Renamed"));
+        assert_eq!(manager.get_static_peers("peer-1").len(), 1);
+        assert!(manager.get_static_peers("unknown").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_falls_back_to_static_peer() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
+
+        let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+        let tls = TlsServerConfig::new(&cert).unwrap();
+        let server_config = TcpServerConfig::new().with_bind_addr(std::net::Ipv4Addr::LOCALHOST.into());
+        let server = TcpServer::bind(server_config, tls.config()).await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        let accept = tokio::spawn(async move { server.accept().await });
+
+        // 第一个地址不可达，应继续尝试下一个
+        manager.add_static_peer("peer-1", "127.0.0.1:1").unwrap();
+        manager.add_static_peer("peer-1", &format!("127.0.0.1:{}", port)).unwrap();
+
+        manager.connect_device("peer-1").await.unwrap();
+        let _conn = accept.await.unwrap().unwrap();
+
+        assert_eq!(
+            manager.get_last_known_address("peer-1"),
+            Some(SocketAddr::from(([127, 0, 0, 1], port)))
+        );
+        // 新地址通知平台层持久化
+        let updated = callback.updated.lock().unwrap().clone();
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].last_known_address(), Some(SocketAddr::from(([127, 0, 0, 1], port))));
+        assert_eq!(manager.get_device_status("peer-1"), Some(DeviceStatus::Connected));
+        assert_eq!(callback.connected_count(), 1);
+
+        manager.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_handle_network_change_redials_paired_devices() {
+        let (manager, callback) = create_manager_with_callback();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+
+        // 未运行时忽略
+        manager.handle_network_change().await;
+        assert_eq!(callback.connected_count(), 0);
+
+        manager.start().await.unwrap();
+
+        let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+        let tls = TlsServerConfig::new(&cert).unwrap();
+        let server_config = TcpServerConfig::new().with_bind_addr(std::net::Ipv4Addr::LOCALHOST.into());
+        let server = TcpServer::bind(server_config, tls.config()).await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        let accept = tokio::spawn(async move { server.accept().await });
+        manager.add_static_peer("peer-1", &format!("127.0.0.1:{}", port)).unwrap();
+
+        manager.handle_network_change().await;
+        let _conn = accept.await.unwrap().unwrap();
+
+        assert_eq!(manager.get_device_status("peer-1"), Some(DeviceStatus::Connected));
+        assert_eq!(callback.connected_count(), 1);
+
+        manager.stop().await;
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_prefers_quic() {
+        let config = NearClipConfig::new("Test Device").with_quic_enabled(true);
+        let manager = NearClipManager::new(config, Arc::new(NoOpCallback)).unwrap();
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
+
+        let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+        let tls = TlsServerConfig::new(&cert).unwrap();
+        let server_config = QuicServerConfig::new().with_bind_addr(std::net::Ipv4Addr::LOCALHOST.into());
+        let server = QuicServer::bind(server_config, tls.config()).unwrap();
+        let port = server.local_addr().unwrap().port();
+        let accept = tokio::spawn(async move { server.accept().await });
+        manager.add_static_peer("peer-1", &format!("127.0.0.1:{}", port)).unwrap();
+
+        manager.connect_device("peer-1").await.unwrap();
+        let _conn = accept.await.unwrap().unwrap();
+
+        assert_eq!(manager.session_state("peer-1", Channel::Quic), Some(SessionState::Active));
+        assert_eq!(manager.session_state("peer-1", Channel::Wifi), None);
+
+        manager.stop().await;
+    }
+
+    /// 启动本地中继，返回中继配置和服务任务
+    async fn start_test_relay() -> (RelayConfig, JoinHandle<()>) {
+        use nearclip_relay::{RelayServer, RelayServerConfig, RELAY_SERVER_NAME};
+
+        let cert = TlsCertificate::generate(&[RELAY_SERVER_NAME.to_string()]).unwrap();
+        let config = RelayServerConfig::new().with_bind_addr("127.0.0.1:0".parse().unwrap());
+        let server = RelayServer::bind(config, TlsServerConfig::new(&cert).unwrap().config())
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        let task = tokio::spawn(async move { server.run().await });
+        (RelayConfig::new(addr.to_string(), cert.cert_der().to_vec()), task)
+    }
+
+    #[tokio::test]
+    async fn test_connect_device_falls_back_to_relay() {
+        let secret = vec![7u8; 32];
+        let (relay, relay_task) = start_test_relay().await;
+
+        let config = NearClipConfig::new("Test Device").with_relay(relay.clone());
+        let manager = NearClipManager::new(config, Arc::new(NoOpCallback)).unwrap();
+        let manager_secret = secret.clone();
+        manager.set_relay_identity(
+            EcdhKeyPair::generate(),
+            Arc::new(move |id: &str| (id == "peer-1").then(|| manager_secret.clone())),
+        );
+        manager.add_paired_device(DeviceInfo::new("peer-1", "Peer"));
+        manager.start().await.unwrap();
+
+        // 对端只能通过中继访问
+        let my_id = manager.device_id().to_string();
+        let tls = TlsClientConfig::new(&relay.certificate_der).unwrap().config();
+        let addr = relay.address.parse().unwrap();
+        let peer = Arc::new(RelayClient::connect(addr, tls, "peer-1", &EcdhKeyPair::generate()).await.unwrap());
+        peer.allow_peers([my_id.clone()]).await.unwrap();
+        let listener = RelayTransportListener::new(peer.clone(), Arc::new(move |id: &str| (id == my_id).then(|| secret.clone())));
+        assert!(wait_until(|| peer.is_peer_online(manager.device_id())).await);
+
+        manager.connect_device("peer-1").await.unwrap();
+        let transport = tokio::time::timeout(Duration::from_secs(5), listener.accept()).await.unwrap().unwrap();
+        let request = tokio::time::timeout(Duration::from_secs(5), transport.recv()).await.unwrap().unwrap();
+        assert_eq!(request.msg_type, MessageType::PairingRequest);
+
+        assert_eq!(manager.session_state("peer-1", Channel::Relay), Some(SessionState::Active));
+        assert_eq!(manager.get_device_status("peer-1"), Some(DeviceStatus::Connected));
+        // 中继连接没有可记住的本地地址
+        assert_eq!(manager.get_last_known_address("peer-1"), None);
+
+        manager.stop().await;
+        relay_task.abort();
+    }
+
+    #[tokio::test]
+    async fn test_manager_starts_without_reachable_relay() {
+        let (relay, relay_task) = start_test_relay().await;
+        relay_task.abort();
+        let _ = relay_task.await;
+
+        let config = NearClipConfig::new("Test Device").with_relay(relay);
+        let manager = NearClipManager::new(config, Arc::new(NoOpCallback)).unwrap();
+        manager.set_relay_identity(EcdhKeyPair::generate(), Arc::new(|_: &str| None));
+        manager.start().await.unwrap();
+        assert!(manager.is_running());
+
+        manager.stop().await;
+    }
+
     // --------------------------------------------------------
     // Debug 测试
     // --------------------------------------------------------
@@ -0,0 +1,494 @@
+//! Offline Outbox
+//!
+//! Persists clipboard content for paired devices that are offline when a
+//! sync happens, using SQLite. Queued clips are flushed in order when the
+//! device connects again.
+//!
+//! The outbox only creates its own `outbox` table, so it can share the
+//! history database file.
+
+use crate::error::{NearClipError, Result};
+use nearclip_sync::ContentFingerprint;
+use rusqlite::{params, Connection};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default number of clips kept per device
+pub const DEFAULT_OUTBOX_CAPACITY: usize = 10;
+
+/// Default time-to-live of a queued clip (seconds)
+pub const DEFAULT_OUTBOX_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Retention policy for queued clips
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutboxPolicy {
+    /// Keep only the most recent clip per device
+    LatestOnly,
+    /// Keep up to N most recent clips per device
+    KeepLatest(usize),
+}
+
+impl OutboxPolicy {
+    /// Maximum number of clips kept per device
+    pub fn capacity(&self) -> usize {
+        match self {
+            OutboxPolicy::LatestOnly => 1,
+            OutboxPolicy::KeepLatest(n) => *n,
+        }
+    }
+}
+
+impl Default for OutboxPolicy {
+    fn default() -> Self {
+        OutboxPolicy::KeepLatest(DEFAULT_OUTBOX_CAPACITY)
+    }
+}
+
+/// Queued clip waiting for an offline device
+#[derive(Debug, Clone)]
+pub struct OutboxEntry {
+    /// Unique ID (increases in enqueue order)
+    pub id: i64,
+
+    /// Target device ID
+    pub device_id: String,
+
+    /// Clipboard content
+    pub content: Vec<u8>,
+
+    /// Content fingerprint (hex), used to drop duplicates
+    pub fingerprint: String,
+
+    /// Enqueue time (milliseconds since UNIX epoch)
+    pub created_at_ms: i64,
+}
+
+/// Persistent per-device outbox
+pub struct OutboxManager {
+    conn: Arc<Mutex<Connection>>,
+    db_path: PathBuf,
+    policy: OutboxPolicy,
+    ttl: Duration,
+    /// Devices whose outbox is currently being flushed
+    flushing: Mutex<HashSet<String>>,
+}
+
+/// Exclusive right to flush one device's outbox
+///
+/// Returned by [`OutboxManager::begin_flush`]; the device can be flushed
+/// again once the guard is dropped.
+pub struct FlushGuard<'a> {
+    outbox: &'a OutboxManager,
+    device_id: String,
+}
+
+impl Drop for FlushGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut flushing) = self.outbox.flushing.lock() {
+            flushing.remove(&self.device_id);
+        }
+    }
+}
+
+impl OutboxManager {
+    /// Create a new outbox
+    ///
+    /// # Arguments
+    ///
+    /// * `db_path` - Path to SQLite database file
+    /// * `policy` - How many clips to keep per device
+    /// * `ttl` - How long a queued clip stays valid
+    pub fn new(db_path: PathBuf, policy: OutboxPolicy, ttl: Duration) -> Result<Self> {
+        if policy.capacity() == 0 {
+            return Err(NearClipError::Config(
+                "outbox capacity must be greater than 0".to_string(),
+            ));
+        }
+
+        let conn = Connection::open(&db_path)
+            .map_err(|e| NearClipError::Io(format!("Failed to open database: {}", e)))?;
+
+        let manager = Self {
+            conn: Arc::new(Mutex::new(conn)),
+            db_path,
+            policy,
+            ttl,
+            flushing: Mutex::new(HashSet::new()),
+        };
+        manager.init_database()?;
+        Ok(manager)
+    }
+
+    /// Initialize database schema
+    fn init_database(&self) -> Result<()> {
+        let conn = self.lock()?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS outbox (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                device_id TEXT NOT NULL,
+                content BLOB NOT NULL,
+                fingerprint TEXT NOT NULL,
+                created_at_ms INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| NearClipError::Io(format!("Failed to create outbox table: {}", e)))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_outbox_device ON outbox(device_id, id)",
+            [],
+        )
+        .map_err(|e| NearClipError::Io(format!("Failed to create outbox index: {}", e)))?;
+
+        tracing::info!(path = ?self.db_path, policy = ?self.policy, "Outbox database initialized");
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| NearClipError::Io(format!("Failed to lock database: {}", e)))
+    }
+
+    fn now_ms() -> Result<i64> {
+        Ok(SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| NearClipError::Io(e.to_string()))?
+            .as_millis() as i64)
+    }
+
+    fn cutoff_ms(&self) -> Result<i64> {
+        Ok(Self::now_ms()? - self.ttl.as_millis() as i64)
+    }
+
+    /// Retention policy
+    pub fn policy(&self) -> OutboxPolicy {
+        self.policy
+    }
+
+    /// Time-to-live of queued clips
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Queue a clip for a device
+    ///
+    /// An older queued copy of the same content is replaced, and the oldest
+    /// clips beyond the policy capacity are dropped.
+    pub fn enqueue(&self, device_id: &str, content: &[u8]) -> Result<i64> {
+        let fingerprint = ContentFingerprint::from_content(content).to_hex();
+        let now = Self::now_ms()?;
+        let conn = self.lock()?;
+
+        conn.execute(
+            "DELETE FROM outbox WHERE device_id = ? AND fingerprint = ?",
+            params![device_id, fingerprint],
+        )
+        .map_err(|e| NearClipError::Io(format!("Failed to remove duplicate outbox entry: {}", e)))?;
+
+        conn.execute(
+            "INSERT INTO outbox (device_id, content, fingerprint, created_at_ms) VALUES (?, ?, ?, ?)",
+            params![device_id, content, fingerprint, now],
+        )
+        .map_err(|e| NearClipError::Io(format!("Failed to insert outbox entry: {}", e)))?;
+
+        let id = conn.last_insert_rowid();
+
+        let trimmed = conn
+            .execute(
+                "DELETE FROM outbox WHERE device_id = ?1 AND id NOT IN
+                 (SELECT id FROM outbox WHERE device_id = ?1 ORDER BY id DESC LIMIT ?2)",
+                params![device_id, self.policy.capacity() as i64],
+            )
+            .map_err(|e| NearClipError::Io(format!("Failed to trim outbox: {}", e)))?;
+
+        tracing::debug!(
+            id = id,
+            device_id = %device_id,
+            size = content.len(),
+            trimmed = trimmed,
+            "Queued clip in outbox"
+        );
+
+        Ok(id)
+    }
+
+    /// Get unexpired clips for a device, oldest first
+    pub fn pending(&self, device_id: &str) -> Result<Vec<OutboxEntry>> {
+        let cutoff = self.cutoff_ms()?;
+        let conn = self.lock()?;
+
+        let mut stmt = conn
+            .prepare("SELECT id, device_id, content, fingerprint, created_at_ms FROM outbox WHERE device_id = ? AND created_at_ms >= ? ORDER BY id ASC")
+            .map_err(|e| NearClipError::Io(format!("Failed to prepare query: {}", e)))?;
+
+        let entries = stmt
+            .query_map(params![device_id, cutoff], |row| {
+                Ok(OutboxEntry {
+                    id: row.get(0)?,
+                    device_id: row.get(1)?,
+                    content: row.get(2)?,
+                    fingerprint: row.get(3)?,
+                    created_at_ms: row.get(4)?,
+                })
+            })
+            .map_err(|e| NearClipError::Io(format!("Failed to query outbox: {}", e)))?
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| NearClipError::Io(format!("Failed to collect outbox entries: {}", e)))?;
+
+        Ok(entries)
+    }
+
+    /// Number of unexpired clips queued for a device
+    pub fn pending_count(&self, device_id: &str) -> Result<usize> {
+        let cutoff = self.cutoff_ms()?;
+        let conn = self.lock()?;
+
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM outbox WHERE device_id = ? AND created_at_ms >= ?",
+                params![device_id, cutoff],
+                |row| row.get(0),
+            )
+            .map_err(|e| NearClipError::Io(format!("Failed to count outbox: {}", e)))?;
+
+        Ok(count as usize)
+    }
+
+    /// Start flushing a device's outbox
+    ///
+    /// Clips stay queued until the device acknowledges them, so two
+    /// overlapping flushes (e.g. on connect and on returning to proximity)
+    /// would send them twice. Returns `None` while another flush for the
+    /// same device is still running.
+    pub fn begin_flush(&self, device_id: &str) -> Option<FlushGuard<'_>> {
+        let mut flushing = self.flushing.lock().ok()?;
+        if !flushing.insert(device_id.to_string()) {
+            return None;
+        }
+        Some(FlushGuard {
+            outbox: self,
+            device_id: device_id.to_string(),
+        })
+    }
+
+    /// Remove a clip after it has been delivered
+    pub fn remove(&self, id: i64) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute("DELETE FROM outbox WHERE id = ?", params![id])
+            .map_err(|e| NearClipError::Io(format!("Failed to remove outbox entry: {}", e)))?;
+        Ok(())
+    }
+
+    /// Remove all clips queued for a device (e.g. after unpairing)
+    pub fn clear_device(&self, device_id: &str) -> Result<usize> {
+        let conn = self.lock()?;
+        let deleted = conn
+            .execute("DELETE FROM outbox WHERE device_id = ?", params![device_id])
+            .map_err(|e| NearClipError::Io(format!("Failed to clear outbox: {}", e)))?;
+
+        tracing::info!(device_id = %device_id, deleted = deleted, "Cleared outbox for device");
+        Ok(deleted)
+    }
+
+    /// Delete clips older than the TTL
+    pub fn purge_expired(&self) -> Result<usize> {
+        let cutoff = self.cutoff_ms()?;
+        let conn = self.lock()?;
+        let deleted = conn
+            .execute("DELETE FROM outbox WHERE created_at_ms < ?", params![cutoff])
+            .map_err(|e| NearClipError::Io(format!("Failed to purge outbox: {}", e)))?;
+
+        if deleted > 0 {
+            tracing::info!(deleted = deleted, "Purged expired outbox entries");
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn create_outbox(name: &str, policy: OutboxPolicy) -> (OutboxManager, PathBuf) {
+        let db_path = env::temp_dir().join(format!("test_outbox_{}_{}.db", name, uuid::Uuid::new_v4()));
+        let outbox = OutboxManager::new(
+            db_path.clone(),
+            policy,
+            Duration::from_secs(DEFAULT_OUTBOX_TTL_SECS),
+        )
+        .unwrap();
+        (outbox, db_path)
+    }
+
+    #[test]
+    fn test_policy_capacity() {
+        assert_eq!(OutboxPolicy::LatestOnly.capacity(), 1);
+        assert_eq!(OutboxPolicy::KeepLatest(5).capacity(), 5);
+        assert_eq!(OutboxPolicy::default().capacity(), DEFAULT_OUTBOX_CAPACITY);
+    }
+
+    #[test]
+    fn test_zero_capacity_rejected() {
+        let db_path = env::temp_dir().join(format!("test_outbox_zero_{}.db", uuid::Uuid::new_v4()));
+        let result = OutboxManager::new(db_path, OutboxPolicy::KeepLatest(0), Duration::from_secs(60));
+        assert!(matches!(result, Err(NearClipError::Config(_))));
+    }
+
+    #[test]
+    fn test_enqueue_and_pending_in_order() {
+        let (outbox, db_path) = create_outbox("order", OutboxPolicy::default());
+
+        outbox.enqueue("device-1", b"first").unwrap();
+        outbox.enqueue("device-1", b"second").unwrap();
+        outbox.enqueue("device-2", b"other").unwrap();
+
+        let pending = outbox.pending("device-1").unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].content, b"first");
+        assert_eq!(pending[1].content, b"second");
+        assert_eq!(outbox.pending_count("device-2").unwrap(), 1);
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_latest_only_policy() {
+        let (outbox, db_path) = create_outbox("latest", OutboxPolicy::LatestOnly);
+
+        outbox.enqueue("device-1", b"first").unwrap();
+        outbox.enqueue("device-1", b"second").unwrap();
+
+        let pending = outbox.pending("device-1").unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].content, b"second");
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_keep_latest_trims_oldest() {
+        let (outbox, db_path) = create_outbox("trim", OutboxPolicy::KeepLatest(2));
+
+        outbox.enqueue("device-1", b"a").unwrap();
+        outbox.enqueue("device-1", b"b").unwrap();
+        outbox.enqueue("device-1", b"c").unwrap();
+
+        let contents: Vec<_> = outbox
+            .pending("device-1")
+            .unwrap()
+            .into_iter()
+            .map(|e| e.content)
+            .collect();
+        assert_eq!(contents, vec![b"b".to_vec(), b"c".to_vec()]);
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_duplicate_content_moves_to_end() {
+        let (outbox, db_path) = create_outbox("dedupe", OutboxPolicy::default());
+
+        outbox.enqueue("device-1", b"same").unwrap();
+        outbox.enqueue("device-1", b"other").unwrap();
+        outbox.enqueue("device-1", b"same").unwrap();
+
+        let pending = outbox.pending("device-1").unwrap();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(pending[0].content, b"other");
+        assert_eq!(pending[1].content, b"same");
+        assert_eq!(
+            pending[1].fingerprint,
+            ContentFingerprint::from_content(b"same").to_hex()
+        );
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_remove_and_clear_device() {
+        let (outbox, db_path) = create_outbox("remove", OutboxPolicy::default());
+
+        let id = outbox.enqueue("device-1", b"a").unwrap();
+        outbox.enqueue("device-1", b"b").unwrap();
+
+        outbox.remove(id).unwrap();
+        assert_eq!(outbox.pending_count("device-1").unwrap(), 1);
+
+        assert_eq!(outbox.clear_device("device-1").unwrap(), 1);
+        assert_eq!(outbox.pending_count("device-1").unwrap(), 0);
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_expired_entries_hidden_and_purged() {
+        let (outbox, db_path) = create_outbox("ttl", OutboxPolicy::default());
+
+        outbox.enqueue("device-1", b"fresh").unwrap();
+
+        // Add an old entry (manually insert with old timestamp)
+        {
+            let conn = outbox.conn.lock().unwrap();
+            let old_timestamp = OutboxManager::now_ms().unwrap()
+                - (DEFAULT_OUTBOX_TTL_SECS as i64 + 60) * 1000;
+            conn.execute(
+                "INSERT INTO outbox (device_id, content, fingerprint, created_at_ms) VALUES (?, ?, ?, ?)",
+                params!["device-1", b"stale".to_vec(), "stale", old_timestamp],
+            )
+            .unwrap();
+        }
+
+        let pending = outbox.pending("device-1").unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].content, b"fresh");
+
+        assert_eq!(outbox.purge_expired().unwrap(), 1);
+        assert_eq!(outbox.pending_count("device-1").unwrap(), 1);
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_begin_flush_is_exclusive_per_device() {
+        let (outbox, db_path) = create_outbox("flush_guard", OutboxPolicy::default());
+
+        let guard = outbox.begin_flush("device-1").unwrap();
+        assert!(outbox.begin_flush("device-1").is_none());
+        assert!(outbox.begin_flush("device-2").is_some());
+
+        drop(guard);
+        assert!(outbox.begin_flush("device-1").is_some());
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+
+    #[test]
+    fn test_persists_across_reopen() {
+        let (outbox, db_path) = create_outbox("reopen", OutboxPolicy::default());
+        outbox.enqueue("device-1", b"persisted").unwrap();
+        drop(outbox);
+
+        let reopened = OutboxManager::new(
+            db_path.clone(),
+            OutboxPolicy::default(),
+            Duration::from_secs(DEFAULT_OUTBOX_TTL_SECS),
+        )
+        .unwrap();
+        assert_eq!(reopened.pending("device-1").unwrap()[0].content, b"persisted");
+
+        // Cleanup
+        let _ = std::fs::remove_file(db_path);
+    }
+}
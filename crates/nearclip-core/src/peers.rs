@@ -0,0 +1,206 @@
+//! 静态对端地址模块
+//!
+//! 企业 Wi-Fi 和许多 VPN 会屏蔽组播，此时 mDNS 发现不到任何设备。
+//! 已配对设备可以保存手动配置的地址（`host:port` 或主机名），
+//! 也可以从配对二维码的 `ConnectionInfo` 导入。连接时这些地址与
+//! mDNS 结果一起尝试，最后一次连接成功的地址会被记住，下次优先尝试。
+//!
+//! # 示例
+//!
+//! ```
+//! use nearclip_core::PeerAddress;
+//!
+//! let ip: PeerAddress = "192.168.1.20:8765".parse().unwrap();
+//! let host: PeerAddress = "macbook.corp.example:8765".parse().unwrap();
+//! let v6: PeerAddress = "[fd00::1]:8765".parse().unwrap();
+//!
+//! assert_eq!(ip.port(), 8765);
+//! assert_eq!(host.to_string(), "macbook.corp.example:8765");
+//! assert_eq!(v6.to_string(), "[fd00::1]:8765");
+//! assert!("no-port".parse::<PeerAddress>().is_err());
+//! ```
+
+use crate::error::{NearClipError, Result};
+use nearclip_crypto::ConnectionInfo;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+/// 对端地址
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PeerAddress {
+    /// IP 地址和端口
+    Socket(SocketAddr),
+    /// 主机名和端口（连接时解析）
+    Host {
+        /// 主机名
+        host: String,
+        /// 端口
+        port: u16,
+    },
+}
+
+impl PeerAddress {
+    /// 从配对数据的连接信息创建
+    ///
+    /// 需要同时包含 IP（或主机名）和端口，否则返回 None。
+    pub fn from_connection_info(info: &ConnectionInfo) -> Option<Self> {
+        let host = info.ip.as_deref()?.trim();
+        let port = info.port?;
+        if host.is_empty() || port == 0 {
+            return None;
+        }
+        Some(match host.parse::<IpAddr>() {
+            Ok(ip) => PeerAddress::Socket(SocketAddr::new(ip, port)),
+            Err(_) => PeerAddress::Host {
+                host: host.to_string(),
+                port,
+            },
+        })
+    }
+
+    /// 获取端口
+    pub fn port(&self) -> u16 {
+        match self {
+            PeerAddress::Socket(addr) => addr.port(),
+            PeerAddress::Host { port, .. } => *port,
+        }
+    }
+
+    /// 解析为可连接的套接字地址
+    ///
+    /// 主机名解析失败时返回空列表（由调用方继续尝试其他地址）。
+    pub async fn resolve(&self) -> Vec<SocketAddr> {
+        match self {
+            PeerAddress::Socket(addr) => vec![*addr],
+            PeerAddress::Host { host, port } => {
+                match tokio::net::lookup_host((host.as_str(), *port)).await {
+                    Ok(addrs) => addrs.collect(),
+                    Err(e) => {
+                        tracing::debug!(host = %host, error = %e, "Failed to resolve static peer");
+                        Vec::new()
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for PeerAddress {
+    type Err = NearClipError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if let Ok(addr) = s.parse::<SocketAddr>() {
+            if addr.port() == 0 {
+                return Err(NearClipError::Config(format!("Invalid peer address '{}': port cannot be 0", s)));
+            }
+            return Ok(PeerAddress::Socket(addr));
+        }
+
+        let (host, port) = s
+            .rsplit_once(':')
+            .ok_or_else(|| NearClipError::Config(format!("Invalid peer address '{}': expected host:port", s)))?;
+        let port: u16 = port
+            .parse()
+            .map_err(|_| NearClipError::Config(format!("Invalid peer address '{}': bad port", s)))?;
+        if port == 0 {
+            return Err(NearClipError::Config(format!("Invalid peer address '{}': port cannot be 0", s)));
+        }
+        let valid_host = !host.is_empty()
+            && host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.');
+        if !valid_host {
+            return Err(NearClipError::Config(format!("Invalid peer address '{}': bad host name", s)));
+        }
+
+        Ok(PeerAddress::Host {
+            host: host.to_string(),
+            port,
+        })
+    }
+}
+
+impl std::fmt::Display for PeerAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PeerAddress::Socket(addr) => write!(f, "{}", addr),
+            PeerAddress::Host { host, port } => write!(f, "{}:{}", host, port),
+        }
+    }
+}
+
+impl From<SocketAddr> for PeerAddress {
+    fn from(addr: SocketAddr) -> Self {
+        PeerAddress::Socket(addr)
+    }
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_socket_addresses() {
+        let v4: PeerAddress = "10.0.0.5:8765".parse().unwrap();
+        assert_eq!(v4, PeerAddress::Socket("10.0.0.5:8765".parse().unwrap()));
+
+        let v6: PeerAddress = "[fd00::1]:9000".parse().unwrap();
+        assert_eq!(v6.port(), 9000);
+        assert_eq!(v6.to_string(), "[fd00::1]:9000");
+    }
+
+    #[test]
+    fn test_parse_host_name() {
+        let host: PeerAddress = " laptop.vpn.example:8765 ".parse().unwrap();
+        assert_eq!(
+            host,
+            PeerAddress::Host {
+                host: "laptop.vpn.example".to_string(),
+                port: 8765
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for input in ["", "laptop", "laptop:", "laptop:0", "laptop:70000", ":8765", "bad host:1", "1.2.3.4:0"] {
+            let result = input.parse::<PeerAddress>();
+            assert!(matches!(result, Err(NearClipError::Config(_))), "{} should fail", input);
+        }
+    }
+
+    #[test]
+    fn test_from_connection_info() {
+        let info = ConnectionInfo::new().with_ip("192.168.1.20").with_port(8765);
+        assert_eq!(
+            PeerAddress::from_connection_info(&info),
+            Some(PeerAddress::Socket("192.168.1.20:8765".parse().unwrap()))
+        );
+
+        let info = ConnectionInfo::new().with_ip("macbook.local").with_port(8765);
+        assert!(matches!(
+            PeerAddress::from_connection_info(&info),
+            Some(PeerAddress::Host { .. })
+        ));
+
+        assert_eq!(PeerAddress::from_connection_info(&ConnectionInfo::new().with_port(8765)), None);
+        assert_eq!(PeerAddress::from_connection_info(&ConnectionInfo::new().with_ip("10.0.0.1")), None);
+    }
+
+    #[tokio::test]
+    async fn test_resolve() {
+        let addr: PeerAddress = "127.0.0.1:8765".parse().unwrap();
+        assert_eq!(addr.resolve().await, vec!["127.0.0.1:8765".parse().unwrap()]);
+
+        let host: PeerAddress = "localhost:8765".parse().unwrap();
+        assert!(host.resolve().await.iter().all(|a| a.port() == 8765));
+
+        let unknown: PeerAddress = "nearclip-unresolvable.invalid:8765".parse().unwrap();
+        assert!(unknown.resolve().await.is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+//! Listening Port Store
+//!
+//! Remembers the TCP port the WiFi listener used last time, so a restart
+//! binds the same port again. Peers keep working with cached addresses and
+//! firewall rules opened for that port stay valid.
+//!
+//! The store only creates its own `listen_port` table, so it can share the
+//! history database file.
+
+use crate::error::{NearClipError, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Persistent last-used listening port
+pub struct PortStore {
+    conn: Mutex<Connection>,
+}
+
+impl PortStore {
+    /// Open (or create) the store
+    ///
+    /// # Arguments
+    ///
+    /// * `db_path` - Path to SQLite database file
+    pub fn new(db_path: PathBuf) -> Result<Self> {
+        let conn = Connection::open(&db_path)
+            .map_err(|e| NearClipError::Io(format!("Failed to open database: {}", e)))?;
+
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS listen_port (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                port INTEGER NOT NULL
+            )",
+            [],
+        )
+        .map_err(|e| NearClipError::Io(format!("Failed to create listen_port table: {}", e)))?;
+
+        tracing::info!(path = ?db_path, "Port store initialized");
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Last saved port, if any
+    pub fn load(&self) -> Result<Option<u16>> {
+        let conn = self.lock()?;
+        let port: Option<i64> = conn
+            .query_row("SELECT port FROM listen_port WHERE id = 1", [], |row| row.get(0))
+            .optional()
+            .map_err(|e| NearClipError::Io(format!("Failed to read port: {}", e)))?;
+        Ok(port.and_then(|p| u16::try_from(p).ok()).filter(|&p| p != 0))
+    }
+
+    /// Save the port the listener is bound to
+    pub fn save(&self, port: u16) -> Result<()> {
+        let conn = self.lock()?;
+        conn.execute(
+            "INSERT INTO listen_port (id, port) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET port = excluded.port",
+            params![port],
+        )
+        .map_err(|e| NearClipError::Io(format!("Failed to save port: {}", e)))?;
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Connection>> {
+        self.conn
+            .lock()
+            .map_err(|e| NearClipError::Io(format!("Failed to lock database: {}", e)))
+    }
+}
+
+impl std::fmt::Debug for PortStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PortStore").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_db() -> PathBuf {
+        std::env::temp_dir().join(format!("nearclip_port_{}.db", uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = temp_db();
+        let store = PortStore::new(path.clone()).unwrap();
+        assert_eq!(store.load().unwrap(), None);
+
+        store.save(8765).unwrap();
+        store.save(8766).unwrap();
+        assert_eq!(store.load().unwrap(), Some(8766));
+
+        // Survives reopening
+        drop(store);
+        let store = PortStore::new(path.clone()).unwrap();
+        assert_eq!(store.load().unwrap(), Some(8766));
+
+        let _ = std::fs::remove_file(path);
+    }
+}
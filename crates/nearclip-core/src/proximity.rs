@@ -0,0 +1,547 @@
+//! 距离感知模块
+//!
+//! RSSI 读数来自 BLE 扫描到的广播，以及中心设备对已建立连接的周期读取
+//! （外围设备被连接后通常停止广播，扫描不再报告它）。单次读数受遮挡和
+//! 多径影响，抖动 10dBm 以上很常见。[`ProximityMonitor`] 先用指数移动平均（EMA）
+//! 平滑读数，再按带回差的阈值判断设备远近：
+//!
+//! - 平滑后的 RSSI 升到 `near_rssi` 及以上时进入 [`ProximityState::Near`]
+//! - 降到 `far_rssi` 及以下时进入 [`ProximityState::Far`]
+//! - 介于两者之间时保持原状态，避免在边界附近反复切换
+//!
+//! 超过 [`ProximityPolicy::timeout`] 没有新读数的设备视为远离。BLE 连接
+//! 存续期间不会超时（外围一侧读不到连接的 RSSI，而连接本身说明设备仍在
+//! 射程内），断开后重新开始计时。从未通过 BLE 看到的设备处于
+//! [`ProximityState::Unknown`]。
+//!
+//! 管理器按 [`ProximityPolicy`] 使用这些状态：设备远离时暂停向它同步
+//! （剪贴板写入离线发件箱），回到附近时冲刷发件箱，未连接时自动重连。
+//!
+//! # 示例
+//!
+//! ```
+//! use nearclip_core::{ProximityEvent, ProximityMonitor, ProximityPolicy, ProximityState, ProximityThresholds};
+//! use std::time::Instant;
+//!
+//! let monitor = ProximityMonitor::new(&ProximityPolicy::default());
+//! let thresholds = ProximityThresholds::new(-60, -75);
+//! let now = Instant::now();
+//!
+//! let event = monitor.observe_at("phone", -50, &thresholds, now);
+//! assert_eq!(event, Some(ProximityEvent::Entered { rssi: -50 }));
+//! assert_eq!(monitor.state("phone"), ProximityState::Near);
+//!
+//! // 单次弱读数不足以把平滑后的 RSSI 拉到 far_rssi 以下
+//! assert_eq!(monitor.observe_at("phone", -80, &thresholds, now), None);
+//! assert_eq!(monitor.state("phone"), ProximityState::Near);
+//! ```
+
+use crate::error::{NearClipError, Result};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 默认进入附近状态的 RSSI 阈值（dBm）
+pub const DEFAULT_NEAR_RSSI: i16 = -65;
+
+/// 默认离开附近状态的 RSSI 阈值（dBm）
+pub const DEFAULT_FAR_RSSI: i16 = -80;
+
+/// 默认 EMA 平滑系数（新读数的权重）
+pub const DEFAULT_RSSI_SMOOTHING: f64 = 0.3;
+
+/// 默认无读数多久后视为远离（秒）
+pub const DEFAULT_PROXIMITY_TIMEOUT_SECS: u64 = 30;
+
+// ============================================================
+// ProximityState - 距离状态
+// ============================================================
+
+/// 设备距离状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ProximityState {
+    /// 没有 BLE 读数
+    #[default]
+    Unknown,
+    /// 在附近
+    Near,
+    /// 已远离
+    Far,
+}
+
+impl ProximityState {
+    /// 检查是否在附近
+    pub fn is_near(&self) -> bool {
+        matches!(self, ProximityState::Near)
+    }
+}
+
+/// 距离状态变化事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProximityEvent {
+    /// 设备进入附近，`rssi` 为平滑后的读数
+    Entered {
+        /// 平滑后的 RSSI（dBm）
+        rssi: i16,
+    },
+    /// 设备离开附近
+    Left,
+}
+
+// ============================================================
+// ProximityThresholds - 距离阈值
+// ============================================================
+
+/// 距离判断阈值
+///
+/// `near_rssi` 必须大于 `far_rssi`，两者之差即回差区间。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProximityThresholds {
+    /// 平滑后的 RSSI 达到该值时进入附近（dBm）
+    pub near_rssi: i16,
+    /// 平滑后的 RSSI 降到该值时离开附近（dBm）
+    pub far_rssi: i16,
+}
+
+impl ProximityThresholds {
+    /// 创建阈值
+    pub fn new(near_rssi: i16, far_rssi: i16) -> Self {
+        Self { near_rssi, far_rssi }
+    }
+
+    /// 验证阈值
+    ///
+    /// # 错误
+    ///
+    /// `near_rssi` 不大于 `far_rssi` 时返回配置错误。
+    pub fn validate(&self) -> Result<()> {
+        if self.near_rssi <= self.far_rssi {
+            return Err(NearClipError::Config(format!(
+                "near_rssi ({}) must be greater than far_rssi ({})",
+                self.near_rssi, self.far_rssi
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProximityThresholds {
+    fn default() -> Self {
+        Self::new(DEFAULT_NEAR_RSSI, DEFAULT_FAR_RSSI)
+    }
+}
+
+// ============================================================
+// ProximityPolicy - 距离策略
+// ============================================================
+
+/// 距离感知策略
+///
+/// 默认只跟踪状态并发出回调，不改变同步和连接行为。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProximityPolicy {
+    /// 设备远离时暂停向它同步，剪贴板写入离线发件箱
+    pub sync_only_when_near: bool,
+    /// 设备回到附近且未连接时自动连接
+    pub auto_connect_when_near: bool,
+    /// 默认阈值（已配对设备可单独设置）
+    pub thresholds: ProximityThresholds,
+    /// EMA 平滑系数，取值 (0, 1]，越大越跟随最新读数
+    pub smoothing: f64,
+    /// 无读数多久后视为远离
+    pub timeout: Duration,
+}
+
+impl ProximityPolicy {
+    /// 验证策略
+    ///
+    /// # 错误
+    ///
+    /// - 阈值无效
+    /// - 平滑系数不在 (0, 1] 内
+    /// - 超时为 0
+    pub fn validate(&self) -> Result<()> {
+        self.thresholds.validate()?;
+        if !(self.smoothing > 0.0 && self.smoothing <= 1.0) {
+            return Err(NearClipError::Config(
+                "proximity smoothing must be in (0, 1]".to_string(),
+            ));
+        }
+        if self.timeout.is_zero() {
+            return Err(NearClipError::Config(
+                "proximity timeout must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProximityPolicy {
+    fn default() -> Self {
+        Self {
+            sync_only_when_near: false,
+            auto_connect_when_near: false,
+            thresholds: ProximityThresholds::default(),
+            smoothing: DEFAULT_RSSI_SMOOTHING,
+            timeout: Duration::from_secs(DEFAULT_PROXIMITY_TIMEOUT_SECS),
+        }
+    }
+}
+
+// ============================================================
+// RssiFilter - RSSI 平滑
+// ============================================================
+
+/// RSSI 指数移动平均滤波器
+#[derive(Debug, Clone, Copy)]
+pub struct RssiFilter {
+    smoothing: f64,
+    value: Option<f64>,
+}
+
+impl RssiFilter {
+    /// 创建滤波器
+    ///
+    /// `smoothing` 为新读数的权重，取值 (0, 1]。
+    pub fn new(smoothing: f64) -> Self {
+        Self {
+            smoothing: smoothing.clamp(f64::MIN_POSITIVE, 1.0),
+            value: None,
+        }
+    }
+
+    /// 加入一个读数，返回平滑后的值
+    ///
+    /// 第一个读数直接作为初始值。
+    pub fn update(&mut self, rssi: i16) -> i16 {
+        let sample = rssi as f64;
+        let value = match self.value {
+            Some(value) => value + self.smoothing * (sample - value),
+            None => sample,
+        };
+        self.value = Some(value);
+        value.round() as i16
+    }
+
+    /// 获取平滑后的值
+    pub fn value(&self) -> Option<i16> {
+        self.value.map(|value| value.round() as i16)
+    }
+
+    /// 清除历史读数
+    pub fn reset(&mut self) {
+        self.value = None;
+    }
+}
+
+// ============================================================
+// ProximityMonitor - 距离跟踪
+// ============================================================
+
+/// 单个设备的距离跟踪状态
+#[derive(Debug)]
+struct DeviceProximity {
+    filter: RssiFilter,
+    state: ProximityState,
+    last_seen: Instant,
+    /// 是否有 BLE 连接
+    connected: bool,
+}
+
+/// 跟踪每个设备的平滑 RSSI 和距离状态
+///
+/// 方法都是同步的，BLE 回调可以在任意线程写入读数。
+#[derive(Debug)]
+pub struct ProximityMonitor {
+    smoothing: f64,
+    timeout: Duration,
+    devices: Mutex<HashMap<String, DeviceProximity>>,
+}
+
+impl ProximityMonitor {
+    /// 按策略创建
+    pub fn new(policy: &ProximityPolicy) -> Self {
+        Self {
+            smoothing: policy.smoothing,
+            timeout: policy.timeout,
+            devices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 记录设备的一个 RSSI 读数
+    ///
+    /// # 返回
+    ///
+    /// 设备进入或离开附近时返回对应事件。首个读数就判定为远离时
+    /// 只记录状态，不产生 [`ProximityEvent::Left`]。
+    pub fn observe(&self, device_id: &str, rssi: i16, thresholds: &ProximityThresholds) -> Option<ProximityEvent> {
+        self.observe_at(device_id, rssi, thresholds, Instant::now())
+    }
+
+    /// 在指定时间记录 RSSI 读数
+    pub fn observe_at(
+        &self,
+        device_id: &str,
+        rssi: i16,
+        thresholds: &ProximityThresholds,
+        now: Instant,
+    ) -> Option<ProximityEvent> {
+        let mut devices = self.devices.lock().unwrap();
+        let device = devices
+            .entry(device_id.to_string())
+            .or_insert_with(|| self.untracked(now));
+        device.last_seen = now;
+        let smoothed = device.filter.update(rssi);
+
+        let next = if smoothed >= thresholds.near_rssi {
+            ProximityState::Near
+        } else if smoothed <= thresholds.far_rssi {
+            ProximityState::Far
+        } else if device.state == ProximityState::Unknown {
+            // 首个读数落在回差区间内时按较近的一侧判断
+            if smoothed - thresholds.far_rssi >= thresholds.near_rssi - smoothed {
+                ProximityState::Near
+            } else {
+                ProximityState::Far
+            }
+        } else {
+            device.state
+        };
+
+        let previous = std::mem::replace(&mut device.state, next);
+        match (previous, next) {
+            (ProximityState::Near, ProximityState::Near) => None,
+            (_, ProximityState::Near) => {
+                tracing::debug!(device_id, rssi = smoothed, "Device entered proximity");
+                Some(ProximityEvent::Entered { rssi: smoothed })
+            }
+            (ProximityState::Near, _) => {
+                tracing::debug!(device_id, rssi = smoothed, "Device left proximity");
+                Some(ProximityEvent::Left)
+            }
+            _ => None,
+        }
+    }
+
+    /// 记录设备 BLE 连接的建立或断开
+    ///
+    /// 连接存续期间设备不会超时；断开时从当前时间重新计时。
+    pub fn set_connected(&self, device_id: &str, connected: bool) {
+        self.set_connected_at(device_id, connected, Instant::now());
+    }
+
+    /// 在指定时间记录 BLE 连接变化
+    pub fn set_connected_at(&self, device_id: &str, connected: bool, now: Instant) {
+        let mut devices = self.devices.lock().unwrap();
+        let device = devices
+            .entry(device_id.to_string())
+            .or_insert_with(|| self.untracked(now));
+        device.connected = connected;
+        if !connected {
+            device.last_seen = now;
+        }
+    }
+
+    fn untracked(&self, now: Instant) -> DeviceProximity {
+        DeviceProximity {
+            filter: RssiFilter::new(self.smoothing),
+            state: ProximityState::Unknown,
+            last_seen: now,
+            connected: false,
+        }
+    }
+
+    /// 把超时没有读数且没有 BLE 连接的设备标记为远离
+    ///
+    /// # 返回
+    ///
+    /// 因此离开附近的设备 ID
+    pub fn expire(&self) -> Vec<String> {
+        self.expire_at(Instant::now())
+    }
+
+    /// 在指定时间检查超时
+    pub fn expire_at(&self, now: Instant) -> Vec<String> {
+        let mut devices = self.devices.lock().unwrap();
+        let mut left = Vec::new();
+        for (device_id, device) in devices.iter_mut() {
+            if matches!(device.state, ProximityState::Far | ProximityState::Unknown)
+                || device.connected
+                || now.saturating_duration_since(device.last_seen) < self.timeout
+            {
+                continue;
+            }
+            if device.state.is_near() {
+                tracing::debug!(device_id = %device_id, "Device proximity timed out");
+                left.push(device_id.clone());
+            }
+            device.state = ProximityState::Far;
+            device.filter.reset();
+        }
+        left
+    }
+
+    /// 获取设备的距离状态
+    pub fn state(&self, device_id: &str) -> ProximityState {
+        self.devices
+            .lock()
+            .unwrap()
+            .get(device_id)
+            .map(|device| device.state)
+            .unwrap_or_default()
+    }
+
+    /// 获取设备平滑后的 RSSI
+    pub fn rssi(&self, device_id: &str) -> Option<i16> {
+        self.devices
+            .lock()
+            .unwrap()
+            .get(device_id)
+            .and_then(|device| device.filter.value())
+    }
+
+    /// 忘记设备（例如取消配对后）
+    pub fn forget(&self, device_id: &str) {
+        self.devices.lock().unwrap().remove(device_id);
+    }
+
+    /// 忘记所有设备
+    pub fn clear(&self) {
+        self.devices.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor() -> ProximityMonitor {
+        ProximityMonitor::new(&ProximityPolicy::default())
+    }
+
+    #[test]
+    fn test_rssi_filter_smooths_readings() {
+        let mut filter = RssiFilter::new(0.5);
+        assert_eq!(filter.value(), None);
+        assert_eq!(filter.update(-60), -60);
+        assert_eq!(filter.update(-80), -70);
+        assert_eq!(filter.update(-80), -75);
+
+        filter.reset();
+        assert_eq!(filter.value(), None);
+    }
+
+    #[test]
+    fn test_thresholds_validate() {
+        assert!(ProximityThresholds::default().validate().is_ok());
+        assert!(ProximityThresholds::new(-70, -70).validate().is_err());
+        assert!(ProximityThresholds::new(-80, -60).validate().is_err());
+
+        let policy = ProximityPolicy {
+            smoothing: 0.0,
+            ..Default::default()
+        };
+        assert!(policy.validate().is_err());
+        let policy = ProximityPolicy {
+            timeout: Duration::ZERO,
+            ..Default::default()
+        };
+        assert!(policy.validate().is_err());
+        assert!(ProximityPolicy::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_hysteresis() {
+        let monitor = monitor();
+        let thresholds = ProximityThresholds::new(-60, -75);
+        let now = Instant::now();
+
+        // 首个读数较弱：记录为远离，但不产生离开事件
+        assert_eq!(monitor.observe_at("phone", -85, &thresholds, now), None);
+        assert_eq!(monitor.state("phone"), ProximityState::Far);
+
+        // 逐步靠近，平滑后的值越过 near_rssi 才进入
+        let mut entered = None;
+        for _ in 0..10 {
+            if let Some(event) = monitor.observe_at("phone", -50, &thresholds, now) {
+                entered = Some(event);
+                break;
+            }
+            assert_eq!(monitor.state("phone"), ProximityState::Far);
+        }
+        assert!(matches!(entered, Some(ProximityEvent::Entered { rssi }) if rssi >= -60));
+
+        // 回差区间内保持附近
+        for _ in 0..10 {
+            assert_eq!(monitor.observe_at("phone", -68, &thresholds, now), None);
+        }
+        assert!(monitor.state("phone").is_near());
+
+        // 持续变弱后离开
+        let mut left = false;
+        for _ in 0..20 {
+            if monitor.observe_at("phone", -90, &thresholds, now) == Some(ProximityEvent::Left) {
+                left = true;
+                break;
+            }
+        }
+        assert!(left);
+        assert_eq!(monitor.state("phone"), ProximityState::Far);
+        assert!(monitor.rssi("phone").unwrap() <= -75);
+    }
+
+    #[test]
+    fn test_expire_marks_silent_devices_far() {
+        let monitor = monitor();
+        let thresholds = ProximityThresholds::default();
+        let now = Instant::now();
+
+        monitor.observe_at("phone", -40, &thresholds, now);
+        monitor.observe_at("tablet", -95, &thresholds, now);
+        assert!(monitor.expire_at(now + Duration::from_secs(5)).is_empty());
+
+        let later = now + Duration::from_secs(DEFAULT_PROXIMITY_TIMEOUT_SECS);
+        assert_eq!(monitor.expire_at(later), vec!["phone".to_string()]);
+        assert_eq!(monitor.state("phone"), ProximityState::Far);
+        assert_eq!(monitor.rssi("phone"), None);
+        assert!(monitor.expire_at(later).is_empty());
+
+        // 回来后重新进入
+        assert_eq!(
+            monitor.observe_at("phone", -50, &thresholds, later),
+            Some(ProximityEvent::Entered { rssi: -50 })
+        );
+
+        monitor.forget("phone");
+        assert_eq!(monitor.state("phone"), ProximityState::Unknown);
+        monitor.clear();
+        assert_eq!(monitor.state("tablet"), ProximityState::Unknown);
+    }
+
+    #[test]
+    fn test_connected_device_does_not_expire() {
+        let monitor = monitor();
+        let thresholds = ProximityThresholds::default();
+        let now = Instant::now();
+        let timeout = Duration::from_secs(DEFAULT_PROXIMITY_TIMEOUT_SECS);
+
+        // 连接后不再有扫描读数，状态保持
+        monitor.observe_at("phone", -40, &thresholds, now);
+        monitor.set_connected_at("phone", true, now);
+        assert!(monitor.expire_at(now + timeout * 10).is_empty());
+        assert_eq!(monitor.state("phone"), ProximityState::Near);
+
+        // 从未扫描到的已连接设备保持未知
+        monitor.set_connected_at("laptop", true, now);
+        monitor.set_connected_at("laptop", false, now + timeout);
+        assert!(monitor.expire_at(now + timeout * 3).is_empty());
+        assert_eq!(monitor.state("laptop"), ProximityState::Unknown);
+
+        // 断开后从断开时刻重新计时
+        let disconnected = now + timeout * 10;
+        monitor.set_connected_at("phone", false, disconnected);
+        assert!(monitor.expire_at(disconnected + timeout / 2).is_empty());
+        assert_eq!(monitor.expire_at(disconnected + timeout), vec!["phone".to_string()]);
+        assert_eq!(monitor.state("phone"), ProximityState::Far);
+    }
+}
@@ -0,0 +1,591 @@
+//! 连接会话模块
+//!
+//! 每条传输连接对应一个 [`Session`]，统一持有传输通道、接收任务、
+//! 心跳任务和连接指标，并通过状态机管理生命周期：
+//!
+//! ```text
+//! Connecting → Handshaking → Active → Draining → Closed
+//! ```
+//!
+//! 任何未关闭的状态都可以直接进入 `Draining`。进入 `Draining` 只会成功一次，
+//! 因此无论断开由哪条路径触发（接收错误、发送失败、心跳超时、主动断开、
+//! 停止服务），清理和断开回调都只执行一次。
+//!
+//! # 示例
+//!
+//! ```
+//! use nearclip_core::{Session, SessionDirection, SessionState};
+//! use nearclip_transport::MockTransport;
+//! use std::sync::Arc;
+//!
+//! let transport = Arc::new(MockTransport::with_defaults("device-b"));
+//! let session = Session::new("device-b", transport, SessionDirection::Outgoing);
+//! assert_eq!(session.state(), SessionState::Connecting);
+//!
+//! session.transition(SessionState::Handshaking).unwrap();
+//! session.transition(SessionState::Active).unwrap();
+//!
+//! // 只有第一次进入 Draining 会成功
+//! assert_eq!(session.begin_drain(), Some(SessionState::Active));
+//! assert_eq!(session.begin_drain(), None);
+//! ```
+
+use crate::error::{NearClipError, Result};
+use nearclip_sync::{Channel, Message};
+use nearclip_transport::Transport;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// 连续多少个心跳间隔没有收到任何消息视为连接失效
+pub const HEARTBEAT_MISS_LIMIT: u32 = 3;
+
+// ============================================================
+// SessionState - 会话状态
+// ============================================================
+
+/// 会话生命周期状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SessionState {
+    /// 正在建立传输连接
+    Connecting,
+    /// 传输已建立，等待交换设备身份
+    Handshaking,
+    /// 身份已确认，可以收发剪贴板
+    Active,
+    /// 正在关闭，释放资源中
+    Draining,
+    /// 已关闭
+    Closed,
+}
+
+impl SessionState {
+    /// 获取状态名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionState::Connecting => "connecting",
+            SessionState::Handshaking => "handshaking",
+            SessionState::Active => "active",
+            SessionState::Draining => "draining",
+            SessionState::Closed => "closed",
+        }
+    }
+
+    /// 会话是否仍然打开（未进入关闭流程）
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self,
+            SessionState::Connecting | SessionState::Handshaking | SessionState::Active
+        )
+    }
+
+    /// 是否允许转换到目标状态
+    pub fn can_transition_to(&self, next: SessionState) -> bool {
+        use SessionState::*;
+        match (self, next) {
+            (Connecting, Handshaking) | (Connecting, Active) => true,
+            (Handshaking, Active) => true,
+            (Draining, Closed) => true,
+            (current, Draining) => current.is_open(),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// ============================================================
+// CloseReason - 关闭原因
+// ============================================================
+
+/// 会话关闭原因
+///
+/// 决定关闭时触发哪个回调：`Unpaired` 触发取消配对回调，
+/// `PairingRejected` 触发配对拒绝回调，其余在设备最后一个活跃会话
+/// 关闭时触发断开回调。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CloseReason {
+    /// 本地主动断开
+    Local,
+    /// 对端关闭连接或接收出错
+    RemoteClosed,
+    /// 发送失败
+    SendFailed,
+    /// 心跳超时
+    HeartbeatTimeout,
+    /// 对端取消配对
+    Unpaired,
+    /// 对端拒绝配对
+    PairingRejected(String),
+    /// 被同一设备同一通道的另一条连接取代
+    Replaced,
+    /// 管理器停止
+    Shutdown,
+}
+
+impl CloseReason {
+    /// 获取原因名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CloseReason::Local => "local",
+            CloseReason::RemoteClosed => "remote_closed",
+            CloseReason::SendFailed => "send_failed",
+            CloseReason::HeartbeatTimeout => "heartbeat_timeout",
+            CloseReason::Unpaired => "unpaired",
+            CloseReason::PairingRejected(_) => "pairing_rejected",
+            CloseReason::Replaced => "replaced",
+            CloseReason::Shutdown => "shutdown",
+        }
+    }
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// 连接发起方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionDirection {
+    /// 本设备发起
+    Outgoing,
+    /// 对端发起
+    Incoming,
+    /// 由平台层建立（如 BLE），接收任务不归会话管理
+    External,
+}
+
+// ============================================================
+// SessionMetrics - 会话指标
+// ============================================================
+
+/// 会话指标
+#[derive(Debug, Clone)]
+pub struct SessionMetrics {
+    /// 会话创建时间
+    pub created_at: Instant,
+    /// 最近一次收到消息的时间
+    pub last_activity: Instant,
+    /// 已发送的心跳数
+    pub heartbeats_sent: u64,
+    /// 收到的消息数
+    pub messages_received: u64,
+    /// 最近一次心跳往返时间
+    pub rtt: Option<Duration>,
+}
+
+/// 会话调试快照
+#[derive(Debug, Clone)]
+pub struct SessionSnapshot {
+    /// 设备 ID
+    pub device_id: String,
+    /// 传输通道
+    pub channel: Channel,
+    /// 发起方向
+    pub direction: SessionDirection,
+    /// 当前状态
+    pub state: SessionState,
+    /// 指标
+    pub metrics: SessionMetrics,
+}
+
+// ============================================================
+// Session - 连接会话
+// ============================================================
+
+/// 单条连接的会话
+pub struct Session {
+    device_id: RwLock<String>,
+    transport: Arc<dyn Transport>,
+    direction: SessionDirection,
+    state: Mutex<SessionState>,
+    metrics: Mutex<SessionMetrics>,
+    /// 等待 ACK 的心跳 (message_id, 发送时间)
+    pending_heartbeat: Mutex<Option<(u64, Instant)>>,
+    recv_task: Mutex<Option<JoinHandle<()>>>,
+    heartbeat_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Session {
+    /// 创建新会话，初始状态为 `Connecting`
+    pub fn new(
+        device_id: impl Into<String>,
+        transport: Arc<dyn Transport>,
+        direction: SessionDirection,
+    ) -> Self {
+        let now = Instant::now();
+        Self {
+            device_id: RwLock::new(device_id.into()),
+            transport,
+            direction,
+            state: Mutex::new(SessionState::Connecting),
+            metrics: Mutex::new(SessionMetrics {
+                created_at: now,
+                last_activity: now,
+                heartbeats_sent: 0,
+                messages_received: 0,
+                rtt: None,
+            }),
+            pending_heartbeat: Mutex::new(None),
+            recv_task: Mutex::new(None),
+            heartbeat_task: Mutex::new(None),
+        }
+    }
+
+    /// 设备 ID
+    ///
+    /// 对端发起的连接在握手完成前使用临时 ID（对端地址）。
+    pub fn device_id(&self) -> String {
+        self.device_id.read().unwrap().clone()
+    }
+
+    /// 握手完成后更新为真实设备 ID
+    pub(crate) fn set_device_id(&self, device_id: &str) {
+        *self.device_id.write().unwrap() = device_id.to_string();
+    }
+
+    /// 传输通道
+    pub fn channel(&self) -> Channel {
+        self.transport.channel()
+    }
+
+    /// 传输连接
+    pub fn transport(&self) -> Arc<dyn Transport> {
+        self.transport.clone()
+    }
+
+    /// 发起方向
+    pub fn direction(&self) -> SessionDirection {
+        self.direction
+    }
+
+    /// 当前状态
+    pub fn state(&self) -> SessionState {
+        *self.state.lock().unwrap()
+    }
+
+    /// 转换状态
+    ///
+    /// # 错误
+    ///
+    /// 状态机不允许的转换返回 `NearClipError::Sync`
+    pub fn transition(&self, next: SessionState) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.can_transition_to(next) {
+            return Err(NearClipError::Sync(format!(
+                "Invalid session transition: {} -> {}",
+                *state, next
+            )));
+        }
+        tracing::debug!(
+            device_id = %self.device_id(),
+            from = %*state,
+            to = %next,
+            "Session state changed"
+        );
+        *state = next;
+        Ok(())
+    }
+
+    /// 进入 `Draining`
+    ///
+    /// # 返回
+    ///
+    /// 本次调用开始关闭流程时返回之前的状态；会话已在关闭时返回 None。
+    pub fn begin_drain(&self) -> Option<SessionState> {
+        let mut state = self.state.lock().unwrap();
+        if !state.is_open() {
+            return None;
+        }
+        let previous = *state;
+        *state = SessionState::Draining;
+        Some(previous)
+    }
+
+    /// 完成关闭
+    pub(crate) fn mark_closed(&self) {
+        *self.state.lock().unwrap() = SessionState::Closed;
+    }
+
+    /// 指标快照
+    pub fn metrics(&self) -> SessionMetrics {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    /// 调试快照
+    pub fn snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            device_id: self.device_id(),
+            channel: self.channel(),
+            direction: self.direction,
+            state: self.state(),
+            metrics: self.metrics(),
+        }
+    }
+
+    /// 距离最近一次收到消息的时间
+    pub fn idle_for(&self) -> Duration {
+        self.metrics.lock().unwrap().last_activity.elapsed()
+    }
+
+    /// 记录收到一条消息
+    pub(crate) fn record_received(&self) {
+        let mut metrics = self.metrics.lock().unwrap();
+        metrics.messages_received += 1;
+        metrics.last_activity = Instant::now();
+    }
+
+    /// 生成下一条心跳消息并记录为等待 ACK
+    pub(crate) fn next_heartbeat(&self, my_device_id: &str) -> Message {
+        let msg = Message::heartbeat(my_device_id.to_string());
+        *self.pending_heartbeat.lock().unwrap() = Some((msg.message_id, Instant::now()));
+        self.metrics.lock().unwrap().heartbeats_sent += 1;
+        msg
+    }
+
+    /// 处理 ACK，如果确认的是当前心跳则返回往返时间
+    pub(crate) fn on_heartbeat_ack(&self, acked_id: u64) -> Option<Duration> {
+        let mut pending = self.pending_heartbeat.lock().unwrap();
+        match *pending {
+            Some((id, sent_at)) if id == acked_id => {
+                *pending = None;
+                let rtt = sent_at.elapsed();
+                self.metrics.lock().unwrap().rtt = Some(rtt);
+                Some(rtt)
+            }
+            _ => None,
+        }
+    }
+
+    /// 绑定接收任务
+    pub(crate) fn attach_recv_task(&self, task: JoinHandle<()>) {
+        *self.recv_task.lock().unwrap() = Some(task);
+    }
+
+    /// 绑定心跳任务
+    pub(crate) fn attach_heartbeat_task(&self, task: JoinHandle<()>) {
+        *self.heartbeat_task.lock().unwrap() = Some(task);
+    }
+
+    /// 取出后台任务句柄
+    ///
+    /// 关闭流程可能运行在这些任务内部，因此由调用方在清理完成后再终止它们。
+    pub(crate) fn take_tasks(&self) -> Vec<JoinHandle<()>> {
+        let mut tasks = Vec::new();
+        if let Some(task) = self.heartbeat_task.lock().unwrap().take() {
+            tasks.push(task);
+        }
+        if let Some(task) = self.recv_task.lock().unwrap().take() {
+            tasks.push(task);
+        }
+        tasks
+    }
+}
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Session")
+            .field("device_id", &self.device_id())
+            .field("channel", &self.channel())
+            .field("direction", &self.direction)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+// ============================================================
+// SessionRegistry - 会话表
+// ============================================================
+
+/// 会话表
+///
+/// 以 `(device_id, channel)` 为键，每个设备每个通道最多一个会话。
+#[derive(Debug, Default)]
+pub struct SessionRegistry {
+    sessions: Mutex<HashMap<(String, Channel), Arc<Session>>>,
+}
+
+impl SessionRegistry {
+    /// 创建空的会话表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册会话，返回被替换的旧会话
+    pub fn insert(&self, session: Arc<Session>) -> Option<Arc<Session>> {
+        let key = (session.device_id(), session.channel());
+        self.sessions.lock().unwrap().insert(key, session)
+    }
+
+    /// 获取会话
+    pub fn get(&self, device_id: &str, channel: Channel) -> Option<Arc<Session>> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(&(device_id.to_string(), channel))
+            .cloned()
+    }
+
+    /// 移除会话
+    ///
+    /// 只有表中登记的正是该会话时才移除（已被新连接替换的旧会话不会误删新会话）。
+    pub fn remove(&self, session: &Arc<Session>) -> bool {
+        let key = (session.device_id(), session.channel());
+        let mut sessions = self.sessions.lock().unwrap();
+        match sessions.get(&key) {
+            Some(existing) if Arc::ptr_eq(existing, session) => {
+                sessions.remove(&key);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 设备的所有会话
+    pub fn for_device(&self, device_id: &str) -> Vec<Arc<Session>> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|((id, _), _)| id == device_id)
+            .map(|(_, session)| session.clone())
+            .collect()
+    }
+
+    /// 设备是否还有活跃会话
+    pub fn has_active(&self, device_id: &str) -> bool {
+        self.for_device(device_id)
+            .iter()
+            .any(|s| s.state() == SessionState::Active)
+    }
+
+    /// 所有会话
+    pub fn all(&self) -> Vec<Arc<Session>> {
+        self.sessions.lock().unwrap().values().cloned().collect()
+    }
+
+    /// 会话数
+    pub fn len(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    /// 是否为空
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// ============================================================
+// 单元测试
+// ============================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nearclip_transport::{MockConfig, MockTransport};
+
+    fn create_session(device_id: &str, channel: Channel) -> Arc<Session> {
+        let transport = Arc::new(MockTransport::new(device_id, MockConfig::new().with_channel(channel)));
+        Arc::new(Session::new(device_id, transport, SessionDirection::Outgoing))
+    }
+
+    #[test]
+    fn test_state_transitions() {
+        use SessionState::*;
+        assert!(Connecting.can_transition_to(Handshaking));
+        assert!(Connecting.can_transition_to(Active));
+        assert!(Handshaking.can_transition_to(Active));
+        assert!(Active.can_transition_to(Draining));
+        assert!(Handshaking.can_transition_to(Draining));
+        assert!(Draining.can_transition_to(Closed));
+
+        assert!(!Active.can_transition_to(Handshaking));
+        assert!(!Active.can_transition_to(Closed));
+        assert!(!Draining.can_transition_to(Draining));
+        assert!(!Closed.can_transition_to(Active));
+    }
+
+    #[test]
+    fn test_invalid_transition_error() {
+        let session = create_session("d1", Channel::Wifi);
+        session.transition(SessionState::Active).unwrap();
+        let result = session.transition(SessionState::Handshaking);
+        assert!(matches!(result, Err(NearClipError::Sync(_))));
+        assert_eq!(session.state(), SessionState::Active);
+    }
+
+    #[test]
+    fn test_begin_drain_only_once() {
+        let session = create_session("d1", Channel::Wifi);
+        assert_eq!(session.begin_drain(), Some(SessionState::Connecting));
+        assert_eq!(session.begin_drain(), None);
+        session.mark_closed();
+        assert_eq!(session.state(), SessionState::Closed);
+        assert_eq!(session.begin_drain(), None);
+    }
+
+    #[test]
+    fn test_heartbeat_ack_measures_rtt() {
+        let session = create_session("d1", Channel::Wifi);
+        let heartbeat = session.next_heartbeat("me");
+        assert_eq!(session.metrics().heartbeats_sent, 1);
+
+        assert_eq!(session.on_heartbeat_ack(heartbeat.message_id + 1), None);
+        assert!(session.on_heartbeat_ack(heartbeat.message_id).is_some());
+        assert!(session.metrics().rtt.is_some());
+
+        // 同一个心跳只计算一次
+        assert_eq!(session.on_heartbeat_ack(heartbeat.message_id), None);
+    }
+
+    #[test]
+    fn test_record_received_updates_activity() {
+        let session = create_session("d1", Channel::Wifi);
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(session.idle_for() >= Duration::from_millis(10));
+
+        session.record_received();
+        assert_eq!(session.metrics().messages_received, 1);
+        assert!(session.idle_for() < Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_registry_insert_and_replace() {
+        let registry = SessionRegistry::new();
+        let first = create_session("d1", Channel::Wifi);
+        let second = create_session("d1", Channel::Wifi);
+
+        assert!(registry.insert(first.clone()).is_none());
+        let replaced = registry.insert(second.clone()).unwrap();
+        assert!(Arc::ptr_eq(&replaced, &first));
+
+        // 旧会话不能移除新会话
+        assert!(!registry.remove(&first));
+        assert!(registry.remove(&second));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_registry_per_channel() {
+        let registry = SessionRegistry::new();
+        let wifi = create_session("d1", Channel::Wifi);
+        let ble = create_session("d1", Channel::Ble);
+        registry.insert(wifi.clone());
+        registry.insert(ble.clone());
+        registry.insert(create_session("d2", Channel::Wifi));
+
+        assert_eq!(registry.len(), 3);
+        assert_eq!(registry.for_device("d1").len(), 2);
+        assert!(registry.get("d1", Channel::Ble).is_some());
+
+        assert!(!registry.has_active("d1"));
+        ble.transition(SessionState::Active).unwrap();
+        assert!(registry.has_active("d1"));
+    }
+}
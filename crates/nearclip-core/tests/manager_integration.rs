@@ -91,6 +91,18 @@ impl NearClipCallback for TestCallback {
     fn on_sync_error(&self, error: &NearClipError) {
         self.errors.lock().unwrap().push(error.to_string());
     }
+
+    fn on_sync_delivered(&self, _device_id: &str, _message_id: u64) {}
+
+    fn on_sync_failed(&self, _device_id: &str, _message_id: u64, error: &NearClipError) {
+        self.errors.lock().unwrap().push(error.to_string());
+    }
+
+    fn on_proximity_entered(&self, _device_id: &str, _rssi: i16) {}
+
+    fn on_proximity_left(&self, _device_id: &str) {}
+
+    fn on_device_updated(&self, _device: &DeviceInfo) {}
 }
 
 // ============================================================
@@ -21,14 +21,13 @@
 //! ```
 
 use aes_gcm::{
-    aead::{Aead, AeadCore, OsRng},
-    Aes256Gcm as Aes256GcmImpl, Nonce,
+    aead::{Aead, AeadCore, AeadInPlace, OsRng},
+    Aes256Gcm as Aes256GcmImpl, Nonce, Tag,
 };
 use hkdf::Hkdf;
 use sha2::Sha256;
 use thiserror::Error;
 use tracing::{debug, instrument};
-use zeroize::Zeroize;
 
 /// 加密错误类型
 #[derive(Debug, Error, Clone, PartialEq)]
@@ -227,7 +226,8 @@ impl Aes256Gcm {
 
     /// 就地加密（重用缓冲区）
     ///
-    /// 此方法会就地修改输入缓冲区，避免额外的内存分配。
+    /// 此方法会就地修改输入缓冲区，避免额外的内存分配
+    /// （容量不足时仅扩容一次以容纳 nonce 和 tag）。
     ///
     /// # Arguments
     ///
@@ -238,26 +238,22 @@ impl Aes256Gcm {
     /// 成功返回加密后密文的长度
     #[instrument(skip(self, buffer), fields(buffer_len = buffer.len()))]
     pub fn encrypt_in_place(&self, buffer: &mut Vec<u8>) -> Result<usize, CipherError> {
-        let mut plaintext = buffer.clone();
+        let plaintext_len = buffer.len();
 
         // 生成随机 nonce
         let nonce = Aes256GcmImpl::generate_nonce(&mut OsRng);
 
         // 加密数据
-        let ciphertext = self.cipher
-            .encrypt(&nonce, &*plaintext)
+        let tag = self.cipher
+            .encrypt_in_place_detached(&nonce, b"", buffer)
             .map_err(|e| CipherError::EncryptionFailed(e.to_string()))?;
 
-        // 替换缓冲区内容
-        buffer.clear();
-        buffer.reserve(Self::NONCE_SIZE + ciphertext.len());
-        buffer.extend_from_slice(&nonce);
-        buffer.extend_from_slice(&ciphertext);
+        // 组合：nonce + ciphertext + tag
+        buffer.reserve_exact(Self::NONCE_SIZE + Self::TAG_SIZE);
+        buffer.splice(0..0, nonce.iter().copied());
+        buffer.extend_from_slice(&tag);
 
-        // 清零明文副本
-        plaintext.zeroize();
-
-        debug!("Encrypted in-place: {} bytes to {} bytes", plaintext.len(), buffer.len());
+        debug!("Encrypted in-place: {} bytes to {} bytes", plaintext_len, buffer.len());
         Ok(buffer.len())
     }
 
@@ -274,32 +270,50 @@ impl Aes256Gcm {
     /// 成功返回解密后明文的长度
     #[instrument(skip(self, buffer), fields(buffer_len = buffer.len()))]
     pub fn decrypt_in_place(&self, buffer: &mut Vec<u8>) -> Result<usize, CipherError> {
+        let ciphertext_len = buffer.len();
+        let plaintext_len = self.decrypt_slice_in_place(buffer)?.len();
+
+        // 去掉 tag 和 nonce，明文前移
+        buffer.truncate(ciphertext_len - Self::TAG_SIZE);
+        buffer.drain(..Self::NONCE_SIZE);
+
+        debug!("Decrypted in-place: {} bytes to {} bytes", ciphertext_len, plaintext_len);
+        Ok(plaintext_len)
+    }
+
+    /// 在任意可写切片上就地解密
+    ///
+    /// 不移动数据也不分配内存：明文覆盖 nonce 与 tag 之间的密文，
+    /// 返回指向该区域的切片。适合解密后直接反序列化的接收路径。
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - 加密的数据，格式为：`nonce + ciphertext + tag`
+    ///
+    /// # Returns
+    ///
+    /// `buffer` 中的明文部分。认证失败时明文区域内容未定义。
+    pub fn decrypt_slice_in_place<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a mut [u8], CipherError> {
         if buffer.len() < Self::NONCE_SIZE + Self::TAG_SIZE {
             return Err(CipherError::InvalidCiphertext(
                 format!("Too short: {} bytes", buffer.len())
             ));
         }
 
-        // 复制 nonce
-        let nonce_bytes: [u8; Self::NONCE_SIZE] = buffer[..Self::NONCE_SIZE]
-            .try_into()
+        // 分离 nonce、密文和 tag
+        let (nonce_bytes, rest) = buffer.split_at_mut(Self::NONCE_SIZE);
+        let (encrypted_data, tag) = rest.split_at_mut(rest.len() - Self::TAG_SIZE);
+        let nonce_array: [u8; Self::NONCE_SIZE] = (&*nonce_bytes).try_into()
             .map_err(|_| CipherError::InvalidCiphertext("Invalid nonce length".to_string()))?;
-        let nonce = Nonce::from(nonce_bytes);
-
-        // 复制加密数据
-        let encrypted_data = buffer[Self::NONCE_SIZE..].to_vec();
+        let tag_array: [u8; Self::TAG_SIZE] = (&*tag).try_into()
+            .map_err(|_| CipherError::InvalidCiphertext("Invalid tag length".to_string()))?;
 
         // 解密
-        let plaintext = self.cipher
-            .decrypt(&nonce, encrypted_data.as_slice())
+        self.cipher
+            .decrypt_in_place_detached(&Nonce::from(nonce_array), b"", encrypted_data, &Tag::from(tag_array))
             .map_err(|e| CipherError::DecryptionFailed(e.to_string()))?;
 
-        // 替换缓冲区内容
-        buffer.clear();
-        buffer.extend_from_slice(&plaintext);
-
-        debug!("Decrypted in-place: {} bytes to {} bytes", encrypted_data.len(), buffer.len());
-        Ok(buffer.len())
+        Ok(encrypted_data)
     }
 }
 
@@ -415,6 +429,29 @@ mod tests {
         assert_eq!(buffer, b"Hello, in-place!");
     }
 
+    #[test]
+    fn test_in_place_compatible_with_copying_api() {
+        let cipher = create_test_cipher();
+
+        let mut buffer = b"interop".to_vec();
+        cipher.encrypt_in_place(&mut buffer).unwrap();
+        assert_eq!(cipher.decrypt(&buffer).unwrap(), b"interop");
+
+        let mut encrypted = cipher.encrypt(b"interop").unwrap();
+        let plaintext = cipher.decrypt_slice_in_place(&mut encrypted).unwrap();
+        assert_eq!(plaintext, b"interop");
+    }
+
+    #[test]
+    fn test_decrypt_slice_in_place_detects_tampering() {
+        let cipher = create_test_cipher();
+        let mut encrypted = cipher.encrypt(b"secret").unwrap();
+        encrypted[Aes256Gcm::NONCE_SIZE] ^= 1;
+
+        let result = cipher.decrypt_slice_in_place(&mut encrypted);
+        assert!(matches!(result, Err(CipherError::DecryptionFailed(_))));
+    }
+
     #[test]
     fn test_from_raw_key() {
         let key = [1u8; 32];
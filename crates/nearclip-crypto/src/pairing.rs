@@ -61,6 +61,11 @@ pub struct PairingData {
     /// 连接信息（可选）
     #[serde(skip_serializing_if = "Option::is_none")]
     pub connection_info: Option<ConnectionInfo>,
+    /// BLE 广播密钥（Base64 编码，可选）
+    ///
+    /// 扫码方保存它，之后仅凭 BLE 广播令牌就能认出本设备。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub advertisement_key: Option<String>,
 }
 
 /// 连接信息
@@ -158,6 +163,7 @@ impl PairingData {
             device_id,
             public_key,
             connection_info: None,
+            advertisement_key: None,
         }
     }
 
@@ -180,6 +186,22 @@ impl PairingData {
         self
     }
 
+    /// 附带 BLE 广播密钥
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nearclip_crypto::PairingData;
+    ///
+    /// let pairing_data = PairingData::new("device".to_string(), &[0x04; 65])
+    ///     .with_advertisement_key(&[7u8; 32]);
+    /// assert_eq!(pairing_data.advertisement_key_bytes().unwrap(), Some(vec![7u8; 32]));
+    /// ```
+    pub fn with_advertisement_key(mut self, key: &[u8]) -> Self {
+        self.advertisement_key = Some(STANDARD.encode(key));
+        self
+    }
+
     /// 转换为 JSON 字符串
     ///
     /// # Returns
@@ -252,6 +274,14 @@ impl PairingData {
             )));
         }
 
+        // 验证广播密钥（可选，32 字节）
+        if self.advertisement_key_bytes()?.is_some_and(|key| key.len() != 32) {
+            warn!("Invalid advertisement key length");
+            return Err(CryptoError::InvalidPairingData(
+                "Invalid advertisement key length (expected 32)".to_string(),
+            ));
+        }
+
         debug!("Pairing data validation passed");
         Ok(())
     }
@@ -270,6 +300,23 @@ impl PairingData {
             .decode(&self.public_key)
             .map_err(|e| CryptoError::InvalidPairingData(format!("Invalid Base64: {}", e)))
     }
+
+    /// 获取解码后的 BLE 广播密钥
+    ///
+    /// # Returns
+    ///
+    /// 广播密钥字节，二维码未携带时返回 None
+    ///
+    /// # Errors
+    ///
+    /// 返回 `CryptoError::InvalidPairingData` 如果 Base64 解码失败
+    pub fn advertisement_key_bytes(&self) -> Result<Option<Vec<u8>>, CryptoError> {
+        self.advertisement_key
+            .as_ref()
+            .map(|key| STANDARD.decode(key))
+            .transpose()
+            .map_err(|e| CryptoError::InvalidPairingData(format!("Invalid Base64: {}", e)))
+    }
 }
 
 /// 二维码纠错级别
@@ -948,6 +995,21 @@ mod tests {
         assert_eq!(original_bytes, decoded);
     }
 
+    #[test]
+    fn test_pairing_data_advertisement_key() {
+        let data = PairingData::new("device".to_string(), &[0x04; 65]);
+        assert!(!data.to_json().unwrap().contains("advertisement_key"));
+        assert_eq!(data.advertisement_key_bytes().unwrap(), None);
+
+        let data = data.with_advertisement_key(&[9u8; 32]);
+        let parsed = PairingData::from_json(&data.to_json().unwrap()).unwrap();
+        assert!(parsed.validate().is_ok());
+        assert_eq!(parsed.advertisement_key_bytes().unwrap(), Some(vec![9u8; 32]));
+
+        let short = PairingData::new("device".to_string(), &[0x04; 65]).with_advertisement_key(&[9u8; 16]);
+        assert!(matches!(short.validate(), Err(CryptoError::InvalidPairingData(_))));
+    }
+
     #[test]
     fn test_connection_info_builder() {
         let info = ConnectionInfo::new()
@@ -121,6 +121,19 @@ impl TlsCertificate {
         })
     }
 
+    /// 从已保存的 DER 字节恢复证书
+    ///
+    /// 用于需要跨重启保持同一证书的服务（对端固定了该证书）。
+    /// 字节的有效性在创建 [`TlsServerConfig`] 时检查。
+    ///
+    /// # Arguments
+    ///
+    /// * `cert_der` - [`TlsCertificate::cert_der`] 保存的证书
+    /// * `key_der` - [`TlsCertificate::key_der`] 保存的 PKCS#8 私钥
+    pub fn from_der(cert_der: Vec<u8>, key_der: Vec<u8>) -> Self {
+        Self { cert_der, key_der }
+    }
+
     /// 获取证书 DER 编码字节
     ///
     /// 返回 X.509 证书的 DER 编码，用于传输给对端或存储。
@@ -438,6 +451,15 @@ mod tests {
         assert_eq!(cert1.key_der(), cert2.key_der());
     }
 
+    #[test]
+    fn test_certificate_from_der_roundtrip() {
+        let cert = TlsCertificate::generate(&["localhost".to_string()]).unwrap();
+        let restored = TlsCertificate::from_der(cert.cert_der().to_vec(), cert.key_der().to_vec());
+
+        assert_eq!(restored.cert_der(), cert.cert_der());
+        assert!(TlsServerConfig::new(&restored).is_ok());
+    }
+
     #[test]
     fn test_certificate_debug_no_key_leak() {
         let cert = TlsCertificate::generate(&["localhost".to_string()]).unwrap();
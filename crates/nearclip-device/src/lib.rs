@@ -7,9 +7,11 @@ mod models;
 mod store;
 mod manager;
 mod pairing;
+mod nearby;
 
 pub use error::DeviceError;
-pub use models::{PairedDevice, DiscoveredDevice, DevicePlatform, DiscoveryChannel};
+pub use models::{PairedDevice, DiscoveredDevice, DevicePlatform, DiscoveryChannel, DiscoveryChannelKind};
+pub use nearby::NearbyDevices;
 pub use store::DeviceStore;
 pub use manager::DeviceManager;
 pub use pairing::{PairingManager, PairingState, PairingError};
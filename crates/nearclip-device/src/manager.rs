@@ -1,6 +1,6 @@
 //! Device manager for handling device discovery, pairing, and connection state
 
-use crate::{DeviceError, PairedDevice, DiscoveredDevice};
+use crate::{DeviceError, PairedDevice, DiscoveredDevice, NearbyDevices};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -9,7 +9,7 @@ use tracing::{info, debug};
 /// Device manager handles all device-related operations
 pub struct DeviceManager {
     store: crate::DeviceStore,
-    discovered: Arc<RwLock<Vec<DiscoveredDevice>>>,
+    discovered: Arc<NearbyDevices>,
     paired: Arc<RwLock<Vec<PairedDevice>>>,
     connected: Arc<RwLock<Vec<String>>>, // device_ids
 }
@@ -31,7 +31,7 @@ impl DeviceManager {
 
         Ok(Self {
             store,
-            discovered: Arc::new(RwLock::new(Vec::new())),
+            discovered: Arc::new(NearbyDevices::new()),
             paired: Arc::new(RwLock::new(paired)),
             connected: Arc::new(RwLock::new(Vec::new())),
         })
@@ -39,24 +39,25 @@ impl DeviceManager {
 
     // ========== Device Discovery ==========
 
-    /// Add a newly discovered device
+    /// Add a discovered device, merging it with earlier sightings of the same device
     pub async fn add_discovered_device(&self, device: DiscoveredDevice) {
         debug!(device_id = %device.device_id, "Adding discovered device");
-
-        let mut discovered = self.discovered.write().await;
-        if !discovered.iter().any(|d| d.device_id == device.device_id) {
-            discovered.push(device);
-        }
+        self.discovered.observe(device);
     }
 
-    /// Get all discovered devices
+    /// Get all discovered devices, most recently seen first
     pub async fn get_discovered_devices(&self) -> Vec<DiscoveredDevice> {
-        self.discovered.read().await.clone()
+        self.discovered.devices()
+    }
+
+    /// Get the nearby device registry fed by discovery
+    pub fn nearby_devices(&self) -> Arc<NearbyDevices> {
+        self.discovered.clone()
     }
 
     /// Clear discovered devices list
     pub async fn clear_discovered_devices(&self) {
-        self.discovered.write().await.clear();
+        self.discovered.clear();
     }
 
     // ========== Pairing ==========
@@ -164,6 +165,7 @@ mod tests {
             paired_at: 1703577600000,
             last_connected: None,
             last_seen: None,
+            advertisement_key: None,
         }
     }
 
@@ -226,12 +228,13 @@ mod tests {
         let discovered = DiscoveredDevice {
             device_id: "discovered-1".to_string(),
             device_name: "Discovered Device".to_string(),
-            platform: crate::DevicePlatform::Android,
+            platform: Some(crate::DevicePlatform::Android),
             public_key_hash: "hash123".to_string(),
-            channel: crate::DiscoveryChannel::BLE {
+            channels: vec![crate::DiscoveryChannel::BLE {
                 peripheral_id: "peripheral-1".to_string(),
                 rssi: -50,
-            },
+            }],
+            last_seen: 1703577600000,
         };
 
         manager.add_discovered_device(discovered.clone()).await;
@@ -243,6 +246,23 @@ mod tests {
         manager.add_discovered_device(discovered.clone()).await;
         assert_eq!(manager.get_discovered_devices().await.len(), 1);
 
+        // A sighting on another channel is merged into the same record
+        let wifi = DiscoveredDevice::new(
+            "discovered-1",
+            "hash123",
+            crate::DiscoveryChannel::WiFi {
+                ip: "192.168.1.20".to_string(),
+                port: 5000,
+            },
+            1703577601000,
+        );
+        manager.add_discovered_device(wifi).await;
+        let merged = manager.get_discovered_devices().await;
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].channels.len(), 2);
+        assert_eq!(merged[0].device_name, "Discovered Device");
+        assert_eq!(manager.nearby_devices().len(), 1);
+
         // Clear
         manager.clear_discovered_devices().await;
         assert_eq!(manager.get_discovered_devices().await.len(), 0);
@@ -21,6 +21,9 @@ pub struct PairedDevice {
     pub last_connected: Option<i64>,
     /// Last seen timestamp (milliseconds) via discovery
     pub last_seen: Option<i64>,
+    /// The device's BLE advertisement key, exchanged during pairing
+    #[serde(default)]
+    pub advertisement_key: Option<Vec<u8>>,
 }
 
 /// Platform/OS enum
@@ -33,23 +36,123 @@ pub enum DevicePlatform {
     Ios,
 }
 
-/// A device discovered via mDNS or BLE scanning
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// A nearby device discovered via mDNS and/or BLE scanning
+///
+/// One record per physical device: sightings on different channels are
+/// merged by device ID with [`DiscoveredDevice::merge`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DiscoveredDevice {
     /// Unique device identifier
     pub device_id: String,
-    /// Human-readable device name
+    /// Human-readable device name (empty if no channel reported one)
     pub device_name: String,
-    /// Platform/OS of the device
-    pub platform: DevicePlatform,
+    /// Platform/OS of the device, if a channel reported it
+    pub platform: Option<DevicePlatform>,
     /// Hash of the device's public key (for verification)
     pub public_key_hash: String,
-    /// Channel through which this device was discovered
-    pub channel: DiscoveryChannel,
+    /// Channels through which this device is currently reachable
+    pub channels: Vec<DiscoveryChannel>,
+    /// Unix timestamp (milliseconds) when the device was last seen on any channel
+    pub last_seen: i64,
+}
+
+impl DiscoveredDevice {
+    /// Create a record for a sighting on one channel
+    pub fn new(
+        device_id: impl Into<String>,
+        public_key_hash: impl Into<String>,
+        channel: DiscoveryChannel,
+        last_seen: i64,
+    ) -> Self {
+        Self {
+            device_id: device_id.into(),
+            device_name: String::new(),
+            platform: None,
+            public_key_hash: public_key_hash.into(),
+            channels: vec![channel],
+            last_seen,
+        }
+    }
+
+    /// Strongest BLE signal, if the device is reachable over BLE
+    pub fn rssi(&self) -> Option<i16> {
+        self.channels
+            .iter()
+            .filter_map(|channel| match channel {
+                DiscoveryChannel::BLE { rssi, .. } => Some(*rssi),
+                DiscoveryChannel::WiFi { .. } => None,
+            })
+            .max()
+    }
+
+    /// Check whether the device is reachable through a kind of channel
+    pub fn has_channel(&self, kind: DiscoveryChannelKind) -> bool {
+        self.channels.iter().any(|channel| channel.kind() == kind)
+    }
+
+    /// Merge a newer sighting of the same device into this record
+    ///
+    /// Channels of a kind present in `sighting` replace this record's
+    /// channels of that kind; other channels are kept. Non-empty name,
+    /// platform and public key hash take precedence.
+    pub fn merge(&mut self, sighting: DiscoveredDevice) {
+        for kind in [DiscoveryChannelKind::WiFi, DiscoveryChannelKind::BLE] {
+            if sighting.has_channel(kind) {
+                self.channels.retain(|channel| channel.kind() != kind);
+            }
+        }
+        self.channels.extend(sighting.channels);
+        if !sighting.device_name.is_empty() {
+            self.device_name = sighting.device_name;
+        }
+        if sighting.platform.is_some() {
+            self.platform = sighting.platform;
+        }
+        if !sighting.public_key_hash.is_empty() {
+            self.public_key_hash = sighting.public_key_hash;
+        }
+        self.last_seen = self.last_seen.max(sighting.last_seen);
+    }
+
+    /// Convert an mDNS browser entry into a WiFi sighting
+    ///
+    /// `now` is the current Unix time in milliseconds; the entry's
+    /// monotonic `last_seen` is mapped onto it. Every resolved address
+    /// becomes one WiFi channel, in address order.
+    pub fn from_mdns(device: &nearclip_net::DiscoveredDevice, now: i64) -> Self {
+        let mut addresses: Vec<_> = device.addresses.iter().collect();
+        addresses.sort();
+        let age = i64::try_from(device.last_seen.elapsed().as_millis()).unwrap_or(i64::MAX);
+        Self {
+            device_id: device.device_id.clone(),
+            device_name: String::new(),
+            platform: None,
+            public_key_hash: device.public_key_hash.clone(),
+            channels: addresses
+                .into_iter()
+                .map(|ip| DiscoveryChannel::WiFi {
+                    ip: ip.to_string(),
+                    port: device.port,
+                })
+                .collect(),
+            last_seen: now.saturating_sub(age),
+        }
+    }
+}
+
+impl From<nearclip_ble::ControllerDiscoveredDevice> for DiscoveredDevice {
+    /// Convert a BLE controller scan result into a BLE sighting
+    fn from(device: nearclip_ble::ControllerDiscoveredDevice) -> Self {
+        let channel = DiscoveryChannel::BLE {
+            peripheral_id: device.peripheral_uuid,
+            rssi: device.rssi.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+        };
+        Self::new(device.device_id, device.public_key_hash, channel, device.last_seen_ms)
+    }
 }
 
 /// Discovery channel type
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DiscoveryChannel {
     /// Discovered via WiFi/mDNS
     WiFi { ip: String, port: u16 },
@@ -57,6 +160,23 @@ pub enum DiscoveryChannel {
     BLE { peripheral_id: String, rssi: i16 },
 }
 
+impl DiscoveryChannel {
+    /// Kind of this channel
+    pub fn kind(&self) -> DiscoveryChannelKind {
+        match self {
+            Self::WiFi { .. } => DiscoveryChannelKind::WiFi,
+            Self::BLE { .. } => DiscoveryChannelKind::BLE,
+        }
+    }
+}
+
+/// Kind of a [`DiscoveryChannel`], without its address
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DiscoveryChannelKind {
+    WiFi,
+    BLE,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,10 +200,74 @@ mod tests {
             paired_at: 1703577600000,
             last_connected: Some(1703577600000),
             last_seen: Some(1703577600000),
+            advertisement_key: None,
         };
 
         let serialized = serde_json::to_string(&device).unwrap();
         let deserialized: PairedDevice = serde_json::from_str(&serialized).unwrap();
         assert_eq!(device, deserialized);
     }
+
+    #[test]
+    fn test_discovered_device_merge_by_channel_kind() {
+        let ble = |peripheral_id: &str, rssi| DiscoveryChannel::BLE {
+            peripheral_id: peripheral_id.to_string(),
+            rssi,
+        };
+        let wifi = |ip: &str| DiscoveryChannel::WiFi {
+            ip: ip.to_string(),
+            port: 5000,
+        };
+
+        let mut device = DiscoveredDevice::new("device-1", "hash", wifi("192.168.1.2"), 100);
+        device.merge(DiscoveredDevice::new("device-1", "", ble("AA:BB", -60), 200));
+        assert_eq!(device.channels, vec![wifi("192.168.1.2"), ble("AA:BB", -60)]);
+        assert_eq!(device.public_key_hash, "hash");
+        assert_eq!(device.rssi(), Some(-60));
+        assert_eq!(device.last_seen, 200);
+
+        // A newer BLE sighting replaces the old one; an older timestamp keeps last_seen
+        device.merge(DiscoveredDevice::new("device-1", "hash", ble("CC:DD", -40), 150));
+        assert_eq!(device.channels, vec![wifi("192.168.1.2"), ble("CC:DD", -40)]);
+        assert_eq!(device.last_seen, 200);
+        assert!(device.has_channel(DiscoveryChannelKind::WiFi));
+    }
+
+    #[test]
+    fn test_discovered_device_from_source_sightings() {
+        let ble = nearclip_ble::ControllerDiscoveredDevice {
+            peripheral_uuid: "AA:BB".to_string(),
+            device_id: "device-1".to_string(),
+            public_key_hash: "hash".to_string(),
+            rssi: -100_000,
+            last_seen_ms: 500,
+        };
+        let device = DiscoveredDevice::from(ble);
+        assert_eq!(device.device_id, "device-1");
+        assert_eq!(device.rssi(), Some(i16::MIN));
+        assert_eq!(device.last_seen, 500);
+
+        let now = std::time::Instant::now();
+        let mdns = nearclip_net::DiscoveredDevice {
+            device_id: "device-1".to_string(),
+            public_key_hash: "hash".to_string(),
+            addresses: ["192.168.1.9", "192.168.1.2"]
+                .iter()
+                .map(|ip| ip.parse().unwrap())
+                .collect(),
+            port: 5000,
+            fullname: "device-1._nearclip._tcp.local.".to_string(),
+            discovered_at: now,
+            last_seen: now,
+        };
+        let device = DiscoveredDevice::from_mdns(&mdns, 10_000);
+        assert_eq!(
+            device.channels,
+            vec![
+                DiscoveryChannel::WiFi { ip: "192.168.1.2".to_string(), port: 5000 },
+                DiscoveryChannel::WiFi { ip: "192.168.1.9".to_string(), port: 5000 },
+            ]
+        );
+        assert!(device.last_seen <= 10_000 && device.last_seen > 9_000);
+    }
 }
@@ -0,0 +1,249 @@
+//! Nearby device registry
+//!
+//! mDNS and BLE each report the devices they see, keyed by their own
+//! addresses. [`NearbyDevices`] merges those sightings by device ID so
+//! callers get one [`DiscoveredDevice`] per physical device with all of its
+//! reachable channels.
+
+use crate::{DiscoveredDevice, DiscoveryChannel, DiscoveryChannelKind};
+use std::collections::HashMap;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use tracing::debug;
+
+/// Devices currently seen on any discovery channel
+///
+/// Methods are synchronous so discovery callbacks can feed the registry
+/// from any thread.
+#[derive(Debug, Default)]
+pub struct NearbyDevices {
+    devices: RwLock<HashMap<String, DiscoveredDevice>>,
+}
+
+impl NearbyDevices {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, HashMap<String, DiscoveredDevice>> {
+        self.devices.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, HashMap<String, DiscoveredDevice>> {
+        self.devices.write().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Record a sighting, merging it into the device's existing record
+    pub fn observe(&self, sighting: DiscoveredDevice) {
+        let mut devices = self.write();
+        match devices.get_mut(&sighting.device_id) {
+            Some(device) => device.merge(sighting),
+            None => {
+                debug!(device_id = %sighting.device_id, "Nearby device appeared");
+                devices.insert(sighting.device_id.clone(), sighting);
+            }
+        }
+    }
+
+    /// Replace every channel of one kind with a source's current snapshot
+    ///
+    /// For sources that report a full list rather than individual events
+    /// (e.g. the mDNS browser). Devices left without channels are removed.
+    pub fn sync_channel(&self, kind: DiscoveryChannelKind, sightings: Vec<DiscoveredDevice>) {
+        let mut devices = self.write();
+        for device in devices.values_mut() {
+            device.channels.retain(|channel| channel.kind() != kind);
+        }
+        for mut sighting in sightings {
+            sighting.channels.retain(|channel| channel.kind() == kind);
+            match devices.get_mut(&sighting.device_id) {
+                Some(device) => device.merge(sighting),
+                None => {
+                    devices.insert(sighting.device_id.clone(), sighting);
+                }
+            }
+        }
+        devices.retain(|_, device| !device.channels.is_empty());
+    }
+
+    /// Replace the WiFi channels with the mDNS browser's current device list
+    ///
+    /// `now` is the current Unix time in milliseconds.
+    pub fn sync_mdns(&self, mdns_devices: &[nearclip_net::DiscoveredDevice], now: i64) {
+        let sightings = mdns_devices
+            .iter()
+            .map(|device| DiscoveredDevice::from_mdns(device, now))
+            .collect();
+        self.sync_channel(DiscoveryChannelKind::WiFi, sightings);
+    }
+
+    /// Remove the BLE channel with a peripheral ID
+    ///
+    /// # Returns
+    /// The device ID the peripheral belonged to, if any
+    pub fn remove_ble_peripheral(&self, peripheral_id: &str) -> Option<String> {
+        let mut devices = self.write();
+        let device = devices.values_mut().find(|device| {
+            device.channels.iter().any(|channel| {
+                matches!(channel, DiscoveryChannel::BLE { peripheral_id: id, .. } if id == peripheral_id)
+            })
+        })?;
+        device.channels.retain(|channel| channel.kind() != DiscoveryChannelKind::BLE);
+        let device_id = device.device_id.clone();
+        if device.channels.is_empty() {
+            debug!(device_id = %device_id, "Nearby device lost");
+            devices.remove(&device_id);
+        }
+        Some(device_id)
+    }
+
+    /// Remove a device's channels of one kind
+    pub fn remove_channel(&self, device_id: &str, kind: DiscoveryChannelKind) {
+        let mut devices = self.write();
+        if let Some(device) = devices.get_mut(device_id) {
+            device.channels.retain(|channel| channel.kind() != kind);
+            if device.channels.is_empty() {
+                debug!(device_id, "Nearby device lost");
+                devices.remove(device_id);
+            }
+        }
+    }
+
+    /// Remove devices not seen since `now - max_age_ms`
+    ///
+    /// # Returns
+    /// IDs of the removed devices
+    pub fn expire(&self, now: i64, max_age_ms: i64) -> Vec<String> {
+        let mut devices = self.write();
+        let expired: Vec<String> = devices
+            .values()
+            .filter(|device| now - device.last_seen > max_age_ms)
+            .map(|device| device.device_id.clone())
+            .collect();
+        for device_id in &expired {
+            devices.remove(device_id);
+        }
+        expired
+    }
+
+    /// Get a device by ID
+    pub fn get(&self, device_id: &str) -> Option<DiscoveredDevice> {
+        self.read().get(device_id).cloned()
+    }
+
+    /// All nearby devices, most recently seen first
+    pub fn devices(&self) -> Vec<DiscoveredDevice> {
+        let mut devices: Vec<DiscoveredDevice> = self.read().values().cloned().collect();
+        devices.sort_by(|a, b| {
+            b.last_seen
+                .cmp(&a.last_seen)
+                .then_with(|| a.device_id.cmp(&b.device_id))
+        });
+        devices
+    }
+
+    /// Number of nearby devices
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    /// Check whether no device is nearby
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+
+    /// Forget all devices
+    pub fn clear(&self) {
+        self.write().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ble(device_id: &str, peripheral_id: &str, rssi: i16, seen: i64) -> DiscoveredDevice {
+        let channel = DiscoveryChannel::BLE {
+            peripheral_id: peripheral_id.to_string(),
+            rssi,
+        };
+        DiscoveredDevice::new(device_id, format!("{}-hash", device_id), channel, seen)
+    }
+
+    fn wifi(device_id: &str, ip: &str, seen: i64) -> DiscoveredDevice {
+        let channel = DiscoveryChannel::WiFi {
+            ip: ip.to_string(),
+            port: 5000,
+        };
+        DiscoveredDevice::new(device_id, format!("{}-hash", device_id), channel, seen)
+    }
+
+    #[test]
+    fn test_observe_merges_channels_by_device_id() {
+        let nearby = NearbyDevices::new();
+        nearby.observe(wifi("device-1", "10.0.0.2", 100));
+        nearby.observe(ble("device-1", "AA:BB", -55, 200));
+        nearby.observe(ble("device-2", "CC:DD", -70, 150));
+
+        assert_eq!(nearby.len(), 2);
+        let devices = nearby.devices();
+        assert_eq!(devices[0].device_id, "device-1");
+        assert_eq!(devices[0].channels.len(), 2);
+        assert_eq!(devices[0].rssi(), Some(-55));
+        assert_eq!(devices[0].last_seen, 200);
+        assert_eq!(devices[1].device_id, "device-2");
+        assert!(!devices[1].has_channel(DiscoveryChannelKind::WiFi));
+    }
+
+    #[test]
+    fn test_sync_channel_replaces_snapshot() {
+        let nearby = NearbyDevices::new();
+        nearby.observe(ble("device-1", "AA:BB", -55, 100));
+        nearby.observe(wifi("device-1", "10.0.0.2", 100));
+        nearby.observe(wifi("device-2", "10.0.0.3", 100));
+
+        // device-2 left the network; device-1 changed address
+        let mut snapshot = wifi("device-1", "10.0.0.9", 300);
+        snapshot.channels.push(DiscoveryChannel::WiFi {
+            ip: "fe80::1".to_string(),
+            port: 5000,
+        });
+        nearby.sync_channel(DiscoveryChannelKind::WiFi, vec![snapshot]);
+
+        assert!(nearby.get("device-2").is_none());
+        let device = nearby.get("device-1").unwrap();
+        assert_eq!(device.channels.len(), 3);
+        assert!(device.has_channel(DiscoveryChannelKind::BLE));
+        assert_eq!(device.last_seen, 300);
+    }
+
+    #[test]
+    fn test_remove_channels() {
+        let nearby = NearbyDevices::new();
+        nearby.observe(ble("device-1", "AA:BB", -55, 100));
+        nearby.observe(wifi("device-1", "10.0.0.2", 100));
+        nearby.observe(ble("device-2", "CC:DD", -70, 100));
+
+        assert_eq!(nearby.remove_ble_peripheral("AA:BB"), Some("device-1".to_string()));
+        assert!(!nearby.get("device-1").unwrap().has_channel(DiscoveryChannelKind::BLE));
+        assert_eq!(nearby.remove_ble_peripheral("unknown"), None);
+
+        nearby.remove_channel("device-1", DiscoveryChannelKind::WiFi);
+        assert!(nearby.get("device-1").is_none());
+        assert_eq!(nearby.remove_ble_peripheral("CC:DD"), Some("device-2".to_string()));
+        assert!(nearby.is_empty());
+    }
+
+    #[test]
+    fn test_expire_stale_devices() {
+        let nearby = NearbyDevices::new();
+        nearby.observe(ble("device-1", "AA:BB", -55, 1_000));
+        nearby.observe(ble("device-2", "CC:DD", -70, 9_000));
+
+        assert_eq!(nearby.expire(10_000, 5_000), vec!["device-1".to_string()]);
+        assert_eq!(nearby.len(), 1);
+
+        nearby.clear();
+        assert!(nearby.is_empty());
+    }
+}
@@ -3,17 +3,22 @@
 //! Handles the bidirectional pairing process between devices.
 
 use crate::{DeviceManager, PairedDevice, DeviceError};
+use nearclip_ble::BlePairingChannel;
 use nearclip_crypto::EcdhKeyPair;
 use nearclip_protocol::{
     PairingMessage, PairingRequest, PairingResponse, PairingConfirm,
     PairingRejected,
 };
 use nearclip_protocol::pairing::DevicePlatform;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::info;
 
+type HmacSha256 = Hmac<Sha256>;
+
 /// Transport abstraction for sending/receiving pairing messages
 pub trait Transport: Send + Sync {
     /// Send a message to a device
@@ -23,6 +28,26 @@ pub trait Transport: Send + Sync {
     fn recv_timeout(&self, timeout_ms: u64) -> Result<Vec<u8>, String>;
 }
 
+/// Pairing over the BLE pairing characteristic
+///
+/// Lets two devices without a shared network pair with the same
+/// `PairingManager` flow (and the same `PairedDevice` persistence) as the
+/// WiFi path. The channel already targets a single peer, so `device_id` is
+/// ignored.
+impl Transport for BlePairingChannel {
+    fn send(&self, _device_id: &str, data: Vec<u8>) -> Result<(), String> {
+        let message: PairingMessage = rmp_serde::from_slice(&data)
+            .map_err(|e| format!("Failed to deserialize: {}", e))?;
+        BlePairingChannel::send(self, &message).map_err(|e| e.to_string())
+    }
+
+    fn recv_timeout(&self, timeout_ms: u64) -> Result<Vec<u8>, String> {
+        let message = BlePairingChannel::recv_timeout(self, Duration::from_millis(timeout_ms))
+            .map_err(|e| e.to_string())?;
+        rmp_serde::to_vec(&message).map_err(|e| format!("Failed to serialize: {}", e))
+    }
+}
+
 /// Pairing state for tracking active pairing sessions
 #[derive(Debug, Clone)]
 pub enum PairingState {
@@ -144,6 +169,7 @@ pub struct PairingManager {
     local_device_name: String,
     local_platform: DevicePlatform,
     local_keypair: EcdhKeyPair,
+    local_advertisement_key: Option<Vec<u8>>,
     pairing_timeout_ms: u64,
 }
 
@@ -165,6 +191,7 @@ impl PairingManager {
             local_device_name,
             local_platform,
             local_keypair,
+            local_advertisement_key: None,
             pairing_timeout_ms: 30_000, // 30 seconds default
         }
     }
@@ -175,6 +202,15 @@ impl PairingManager {
         self
     }
 
+    /// Set the local BLE advertisement key sent to the peer during pairing
+    ///
+    /// The peer stores it in its `PairedDevice` and uses it to recognize
+    /// this device's advertisements.
+    pub fn with_advertisement_key(mut self, key: Vec<u8>) -> Self {
+        self.local_advertisement_key = Some(key);
+        self
+    }
+
     /// Get current pairing state
     pub async fn get_state(&self) -> PairingState {
         self.state.read().await.clone()
@@ -202,6 +238,7 @@ impl PairingManager {
             platform: self.local_platform.clone(),
             public_key: self.local_keypair.public_key_bytes(),
             nonce,
+            advertisement_key: self.local_advertisement_key.clone(),
         });
 
         // Serialize and send
@@ -232,12 +269,23 @@ impl PairingManager {
 
                 info!(remote_device_id = %resp.device_id, "Received pairing response");
 
-                // TODO: Verify signature (requires crypto integration)
-                // TODO: Derive shared secret (requires crypto integration)
+                // Compute shared secret using ECDH
+                let shared_secret = self.local_keypair
+                    .compute_shared_secret(&resp.public_key)
+                    .map_err(|e| PairingError::ProtocolError(format!("Failed to compute shared secret: {}", e)))?;
+
+                // The responder proves it holds the matching private key
+                if !verify_nonce(&shared_secret, &nonce, &resp.signature) {
+                    *self.state.write().await = PairingState::Failed {
+                        reason: "Invalid signature".to_string(),
+                        failed_at: now_millis(),
+                    };
+                    return Err(PairingError::InvalidSignature);
+                }
 
                 // Send confirmation
                 let confirm = PairingMessage::PairingConfirm(PairingConfirm {
-                    signature: vec![], // TODO: actual signature
+                    signature: sign_nonce(&shared_secret, &resp.nonce),
                 });
 
                 let confirm_data = rmp_serde::to_vec(&confirm)
@@ -246,11 +294,6 @@ impl PairingManager {
                 self.transport.send(target_device_id, confirm_data)
                     .map_err(PairingError::TransportError)?;
 
-                // Compute shared secret using ECDH
-                let shared_secret = self.local_keypair
-                    .compute_shared_secret(&resp.public_key)
-                    .map_err(|e| PairingError::ProtocolError(format!("Failed to compute shared secret: {}", e)))?;
-
                 // Create paired device
                 let now = now_millis() as i64;
                 let device = PairedDevice {
@@ -262,6 +305,7 @@ impl PairingManager {
                     paired_at: now,
                     last_connected: Some(now),
                     last_seen: Some(now),
+                    advertisement_key: resp.advertisement_key.clone(),
                 };
 
                 // Save to device manager
@@ -315,8 +359,10 @@ impl PairingManager {
         // Generate nonce for response
         let nonce = self.generate_nonce();
 
-        // TODO: Verify signature (requires crypto integration)
-        // TODO: Derive shared secret (requires crypto integration)
+        // Compute shared secret using ECDH
+        let shared_secret = self.local_keypair
+            .compute_shared_secret(&request.public_key)
+            .map_err(|e| PairingError::ProtocolError(format!("Failed to compute shared secret: {}", e)))?;
 
         // Create response
         let response = PairingMessage::PairingResponse(PairingResponse {
@@ -325,7 +371,8 @@ impl PairingManager {
             platform: self.local_platform.clone(),
             public_key: self.local_keypair.public_key_bytes(),
             nonce,
-            signature: vec![], // TODO: actual signature
+            signature: sign_nonce(&shared_secret, &request.nonce),
+            advertisement_key: self.local_advertisement_key.clone(),
         });
 
         // Send response
@@ -346,15 +393,17 @@ impl PairingManager {
             .map_err(|e| PairingError::ProtocolError(format!("Failed to deserialize: {}", e)))?;
 
         match confirm {
-            PairingMessage::PairingConfirm(_) => {
+            PairingMessage::PairingConfirm(confirm) => {
                 info!("Received pairing confirmation");
 
-                // TODO: Verify signature
-
-                // Compute shared secret using ECDH
-                let shared_secret = self.local_keypair
-                    .compute_shared_secret(&request.public_key)
-                    .map_err(|e| PairingError::ProtocolError(format!("Failed to compute shared secret: {}", e)))?;
+                // The initiator proves it holds the matching private key
+                if !verify_nonce(&shared_secret, &nonce, &confirm.signature) {
+                    *self.state.write().await = PairingState::Failed {
+                        reason: "Invalid signature".to_string(),
+                        failed_at: now_millis(),
+                    };
+                    return Err(PairingError::InvalidSignature);
+                }
 
                 // Create paired device
                 let now = now_millis() as i64;
@@ -367,6 +416,7 @@ impl PairingManager {
                     paired_at: now,
                     last_connected: Some(now),
                     last_seen: Some(now),
+                    advertisement_key: request.advertisement_key.clone(),
                 };
 
                 // Save to device manager
@@ -402,6 +452,24 @@ impl PairingManager {
         }
     }
 
+    /// Wait for a pairing request on the transport and handle it as the responder
+    ///
+    /// Used when the transport is dedicated to pairing (e.g. the BLE pairing
+    /// characteristic), so the request arrives through `recv_timeout` rather
+    /// than being dispatched by the caller.
+    pub async fn accept_incoming_request(&self) -> Result<PairedDevice, PairingError> {
+        let request_data = self.transport.recv_timeout(self.pairing_timeout_ms)
+            .map_err(|_| PairingError::Timeout)?;
+
+        let request: PairingMessage = rmp_serde::from_slice(&request_data)
+            .map_err(|e| PairingError::ProtocolError(format!("Failed to deserialize: {}", e)))?;
+
+        match request {
+            PairingMessage::PairingRequest(request) => self.handle_incoming_request(request).await,
+            _ => Err(PairingError::ProtocolError("Unexpected message type".to_string())),
+        }
+    }
+
     /// Reject a pairing request
     pub async fn reject_pairing(&self, device_id: &str, reason: String) {
         let rejected = PairingMessage::PairingRejected(PairingRejected::new(reason));
@@ -441,6 +509,25 @@ fn to_device_platform(platform: DevicePlatform) -> crate::DevicePlatform {
     }
 }
 
+/// Sign the peer's nonce with the ECDH shared secret (HMAC-SHA256)
+///
+/// Only the holder of the matching private key can derive the secret, so a
+/// valid signature proves identity without a separate signing key.
+fn sign_nonce(shared_secret: &[u8], nonce: &[u8; 32]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(shared_secret)
+        .expect("HMAC accepts keys of any length");
+    mac.update(nonce);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Verify a signature produced by [`sign_nonce`] in constant time
+fn verify_nonce(shared_secret: &[u8], nonce: &[u8; 32], signature: &[u8]) -> bool {
+    let mut mac = HmacSha256::new_from_slice(shared_secret)
+        .expect("HMAC accepts keys of any length");
+    mac.update(nonce);
+    mac.verify_slice(signature).is_ok()
+}
+
 /// Get current time in milliseconds since Unix epoch
 fn now_millis() -> u64 {
     SystemTime::now()
@@ -554,6 +641,25 @@ mod tests {
         assert_eq!(nonce1.len(), 32);
     }
 
+    #[test]
+    fn test_nonce_signature() {
+        let alice = EcdhKeyPair::generate();
+        let bob = EcdhKeyPair::generate();
+        let alice_secret = alice.compute_shared_secret(&bob.public_key_bytes()).unwrap();
+        let bob_secret = bob.compute_shared_secret(&alice.public_key_bytes()).unwrap();
+        let nonce = [7u8; 32];
+
+        let signature = sign_nonce(&alice_secret, &nonce);
+        assert!(verify_nonce(&bob_secret, &nonce, &signature));
+        assert!(!verify_nonce(&bob_secret, &[8u8; 32], &signature));
+        assert!(!verify_nonce(&bob_secret, &nonce, &[]));
+
+        // A third party cannot derive the same secret
+        let mallory = EcdhKeyPair::generate();
+        let mallory_secret = mallory.compute_shared_secret(&bob.public_key_bytes()).unwrap();
+        assert!(!verify_nonce(&bob_secret, &nonce, &sign_nonce(&mallory_secret, &nonce)));
+    }
+
     #[tokio::test]
     async fn test_pairing_manager_get_state() {
         use tempfile::tempdir;
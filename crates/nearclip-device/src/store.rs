@@ -41,11 +41,20 @@ impl DeviceStore {
                 shared_secret BLOB NOT NULL,
                 paired_at INTEGER NOT NULL,
                 last_connected INTEGER,
-                last_seen INTEGER
+                last_seen INTEGER,
+                advertisement_key BLOB
             )",
             [],
         )?;
 
+        // Databases created before the advertisement key column existed
+        let has_advertisement_key = conn
+            .prepare("SELECT advertisement_key FROM devices LIMIT 0")
+            .is_ok();
+        if !has_advertisement_key {
+            conn.execute("ALTER TABLE devices ADD COLUMN advertisement_key BLOB", [])?;
+        }
+
         // Create indexes for common queries
         conn.execute(
             "CREATE INDEX IF NOT EXISTS idx_devices_platform ON devices(platform)",
@@ -71,8 +80,8 @@ impl DeviceStore {
         let platform_str = serialize_platform(&device.platform);
 
         db.execute(
-            "INSERT OR REPLACE INTO devices (id, name, platform, public_key, shared_secret, paired_at, last_connected, last_seen)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT OR REPLACE INTO devices (id, name, platform, public_key, shared_secret, paired_at, last_connected, last_seen, advertisement_key)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
             params![
                 device.device_id,
                 device.device_name,
@@ -82,6 +91,7 @@ impl DeviceStore {
                 device.paired_at,
                 device.last_connected,
                 device.last_seen,
+                device.advertisement_key,
             ],
         )?;
 
@@ -110,7 +120,7 @@ impl DeviceStore {
 
         let db = self.db.read().await;
         let mut stmt = db.prepare(
-            "SELECT id, name, platform, public_key, shared_secret, paired_at, last_connected, last_seen
+            "SELECT id, name, platform, public_key, shared_secret, paired_at, last_connected, last_seen, advertisement_key
              FROM devices"
         )?;
 
@@ -124,6 +134,7 @@ impl DeviceStore {
                 paired_at: row.get(5)?,
                 last_connected: row.get(6)?,
                 last_seen: row.get(7)?,
+                advertisement_key: row.get(8)?,
             })
         })?
         .collect::<Result<Vec<_>, rusqlite::Error>>()?;
@@ -138,7 +149,7 @@ impl DeviceStore {
 
         let db = self.db.read().await;
         let mut stmt = db.prepare(
-            "SELECT id, name, platform, public_key, shared_secret, paired_at, last_connected, last_seen
+            "SELECT id, name, platform, public_key, shared_secret, paired_at, last_connected, last_seen, advertisement_key
              FROM devices WHERE id = ?1"
         )?;
 
@@ -154,6 +165,7 @@ impl DeviceStore {
                 paired_at: row.get(5)?,
                 last_connected: row.get(6)?,
                 last_seen: row.get(7)?,
+                advertisement_key: row.get(8)?,
             };
             Ok(Some(device))
         } else {
@@ -198,6 +210,7 @@ mod tests {
             paired_at: 1703577600000,
             last_connected: None,
             last_seen: None,
+            advertisement_key: None,
         }
     }
 
@@ -292,4 +305,36 @@ mod tests {
         assert_eq!(loaded.len(), 1); // Still only 1 device
         assert_eq!(loaded[0].last_connected, Some(1703577700000));
     }
+
+    #[tokio::test]
+    async fn test_advertisement_key_column_added_to_old_database() {
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+
+        // Schema from before advertisement keys were stored
+        Connection::open(&db_path)
+            .unwrap()
+            .execute(
+                "CREATE TABLE devices (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    platform TEXT NOT NULL,
+                    public_key BLOB NOT NULL,
+                    shared_secret BLOB NOT NULL,
+                    paired_at INTEGER NOT NULL,
+                    last_connected INTEGER,
+                    last_seen INTEGER
+                )",
+                [],
+            )
+            .unwrap();
+
+        let store = DeviceStore::new(db_path).unwrap();
+        let mut device = create_test_device("device-1");
+        device.advertisement_key = Some(vec![7u8; 32]);
+        store.save_device(&device).await.unwrap();
+
+        let loaded = store.get_device("device-1").await.unwrap().unwrap();
+        assert_eq!(loaded.advertisement_key, Some(vec![7u8; 32]));
+    }
 }
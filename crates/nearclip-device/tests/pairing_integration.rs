@@ -5,10 +5,16 @@
 //! - Shared secret computation and storage
 //! - Paired device persistence
 //! - Unpair functionality
+//! - Pairing over the BLE pairing characteristic (no shared network)
 
 use nearclip_crypto::{EcdhKeyPair, PairingData, PairingSession, QrCodeGenerator, QrCodeParser};
-use nearclip_device::{DeviceManager, PairedDevice, DevicePlatform};
+use nearclip_ble::sim::{SimAir, SimAirConfig, SimDeviceConfig};
+use nearclip_ble::{BleHardware, BleHardwareEvent, BlePairingChannel, BlePairingRole, BlePairingState};
+use nearclip_device::{DeviceManager, PairedDevice, DevicePlatform, PairingManager};
+use nearclip_protocol::pairing::DevicePlatform as ProtocolPlatform;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::UnboundedReceiver;
 use tempfile::TempDir;
 
 /// Helper to get current Unix timestamp in milliseconds as i64
@@ -84,6 +90,7 @@ async fn test_qr_code_pairing_flow() {
         paired_at: timestamp_now(),
         last_connected: None,
         last_seen: Some(timestamp_now()),
+        advertisement_key: None,
     };
 
     let paired_a = PairedDevice {
@@ -95,6 +102,7 @@ async fn test_qr_code_pairing_flow() {
         paired_at: timestamp_now(),
         last_connected: None,
         last_seen: Some(timestamp_now()),
+        advertisement_key: None,
     };
 
     // Save pairing to device managers
@@ -150,6 +158,7 @@ async fn test_paired_device_persistence() {
             paired_at: timestamp_now(),
             last_connected: None,
             last_seen: Some(timestamp_now()),
+            advertisement_key: None,
         };
 
         manager.pair_device(device).await.unwrap();
@@ -191,6 +200,7 @@ async fn test_unpair_device() {
         paired_at: timestamp_now(),
         last_connected: None,
         last_seen: Some(timestamp_now()),
+        advertisement_key: None,
     };
 
     let device2 = PairedDevice {
@@ -202,6 +212,7 @@ async fn test_unpair_device() {
         paired_at: timestamp_now(),
         last_connected: None,
         last_seen: Some(timestamp_now()),
+        advertisement_key: None,
     };
 
     manager.pair_device(device1).await.unwrap();
@@ -242,6 +253,7 @@ async fn test_multiple_sequential_pairings() {
             paired_at: timestamp_now(),
             last_connected: None,
             last_seen: Some(timestamp_now()),
+            advertisement_key: None,
         };
 
         manager.pair_device(device).await.unwrap();
@@ -279,6 +291,7 @@ async fn test_check_device_paired() {
         paired_at: timestamp_now(),
         last_connected: None,
         last_seen: Some(timestamp_now()),
+        advertisement_key: None,
     };
 
     manager.pair_device(device.clone()).await.unwrap();
@@ -291,3 +304,112 @@ async fn test_check_device_paired() {
     // Other device should not exist
     assert!(manager.get_device("other_device").await.is_none());
 }
+
+/// Forward a device's simulated BLE events to its pairing channel
+fn pump(channel: Arc<BlePairingChannel>, mut events: UnboundedReceiver<BleHardwareEvent>) {
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            channel.handle_hardware_event(event);
+        }
+    });
+}
+
+/// Test 3.7: Pairing over BLE only
+///
+/// Both sides run the regular `PairingManager` flow over the BLE pairing
+/// characteristic and persist the same `PairedDevice` as the WiFi path.
+/// `recv_timeout` blocks, so each side needs its own worker thread.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+#[allow(clippy::arc_with_non_send_sync)]
+async fn test_ble_only_pairing_flow() {
+    let air = SimAir::new(SimAirConfig::new());
+    let (mac, mut mac_events) = air.add_device(SimDeviceConfig::new("mac".into(), "hash-m".into()));
+    let (phone, mut phone_events) =
+        air.add_device(SimDeviceConfig::new("phone".into(), "hash-p".into()));
+    phone.start_advertising(&[1]);
+    mac.connect(phone.address());
+    mac_events.recv().await.unwrap();
+    phone_events.recv().await.unwrap();
+
+    // The Mac is the central and initiates; the phone responds via notifications
+    let mac_address = mac.address().to_string();
+    let phone_address = phone.address().to_string();
+    let mac_channel = Arc::new(BlePairingChannel::new(
+        Arc::new(mac),
+        &phone_address,
+        BlePairingRole::Initiator,
+    ));
+    let phone_channel = Arc::new(BlePairingChannel::new(
+        Arc::new(phone),
+        &mac_address,
+        BlePairingRole::Responder,
+    ));
+    mac_channel.subscribe().unwrap();
+    pump(mac_channel.clone(), mac_events);
+    pump(phone_channel.clone(), phone_events);
+
+    // `DeviceManager` is not `Send`, so the phone runs on its own thread
+    let temp_dir = TempDir::new().unwrap();
+    let phone_db = temp_dir.path().join("phone.db");
+    let responder_channel = phone_channel.clone();
+    let responder = std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async move {
+            let phone_devices = Arc::new(DeviceManager::new(phone_db).await.unwrap());
+            let phone_pairing = PairingManager::new(
+                phone_devices.clone(),
+                responder_channel,
+                "phone-1".to_string(),
+                "Pixel".to_string(),
+                ProtocolPlatform::Android,
+                EcdhKeyPair::generate(),
+            )
+            .with_timeout(5_000)
+            .with_advertisement_key(vec![2u8; 32]);
+
+            let paired = phone_pairing.accept_incoming_request().await.unwrap();
+            assert_eq!(phone_devices.get_device("mac-1").await, Some(paired.clone()));
+            paired
+        })
+    });
+
+    let mac_devices = Arc::new(DeviceManager::new(temp_dir.path().join("mac.db")).await.unwrap());
+    let mac_pairing = PairingManager::new(
+        mac_devices.clone(),
+        mac_channel.clone(),
+        "mac-1".to_string(),
+        "MacBook".to_string(),
+        ProtocolPlatform::MacOS,
+        EcdhKeyPair::generate(),
+    )
+    .with_timeout(5_000)
+    .with_advertisement_key(vec![1u8; 32]);
+
+    let mac_paired = mac_pairing.initiate_pairing("phone-1").await.unwrap();
+    let phone_paired = responder.join().unwrap();
+
+    assert_eq!(mac_paired.device_id, "phone-1");
+    assert_eq!(mac_paired.platform, DevicePlatform::Android);
+    assert_eq!(phone_paired.device_id, "mac-1");
+    assert_eq!(phone_paired.device_name, "MacBook");
+    assert_eq!(mac_paired.shared_secret, phone_paired.shared_secret);
+
+    // Each side keeps the other's advertisement key for BLE recognition
+    assert_eq!(mac_paired.advertisement_key, Some(vec![2u8; 32]));
+    assert_eq!(phone_paired.advertisement_key, Some(vec![1u8; 32]));
+
+    // Persisted exactly like the WiFi path
+    assert_eq!(mac_devices.get_device("phone-1").await, Some(mac_paired));
+
+    assert_eq!(phone_channel.state(), BlePairingState::Completed);
+    for _ in 0..100 {
+        if mac_channel.state().is_completed() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(mac_channel.state(), BlePairingState::Completed);
+}
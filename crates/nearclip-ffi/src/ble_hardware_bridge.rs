@@ -3,7 +3,7 @@
 //! Adapts FFI BleHardware interface to Rust BleHardware trait
 
 use std::sync::Arc;
-use nearclip_ble::BleHardware;
+use nearclip_ble::{BleHardware, BlePhy, ConnectionPriority};
 
 // FfiBleHardware trait is defined in lib.rs
 use super::FfiBleHardware;
@@ -110,4 +110,69 @@ impl BleHardware for BleHardwareBridge {
     fn get_mtu(&self, peripheral_id: &str) -> u16 {
         self.ffi_hardware.get_mtu(peripheral_id.to_string()) as u16
     }
+
+    // ========== L2CAP ==========
+
+    fn open_l2cap_channel(&self, peripheral_id: &str, psm: u16) -> Result<(), String> {
+        let error = self
+            .ffi_hardware
+            .open_l2cap_channel(peripheral_id.to_string(), psm);
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn write_l2cap(&self, peripheral_id: &str, data: &[u8]) -> Result<(), String> {
+        let error = self
+            .ffi_hardware
+            .write_l2cap(peripheral_id.to_string(), data.to_vec());
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn close_l2cap_channel(&self, peripheral_id: &str) {
+        self.ffi_hardware.close_l2cap_channel(peripheral_id.to_string());
+    }
+
+    // ========== Link Tuning ==========
+
+    fn request_connection_priority(
+        &self,
+        peripheral_id: &str,
+        priority: ConnectionPriority,
+    ) -> Result<(), String> {
+        let error = self
+            .ffi_hardware
+            .request_connection_priority(peripheral_id.to_string(), priority.into());
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn request_phy(&self, peripheral_id: &str, phy: BlePhy) -> Result<(), String> {
+        let error = self.ffi_hardware.request_phy(peripheral_id.to_string(), phy.into());
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn request_data_length(&self, peripheral_id: &str, octets: u16) -> Result<(), String> {
+        let error = self
+            .ffi_hardware
+            .request_data_length(peripheral_id.to_string(), octets);
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
 }
@@ -5,14 +5,14 @@
 //! on_ble_connection_changed.
 
 use std::sync::Arc;
-use nearclip_sync::{MessageType, PairingPayload};
+use nearclip_sync::{ChannelPreference, Message, MessageType, PairingPayload};
 use nearclip_transport::Transport;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 
 use crate::{FfiNearClipCallback, FfiDeviceInfo};
-use nearclip_core::{DevicePlatform, DeviceStatus};
-use nearclip_ble::BleController;
+use nearclip_core::{DevicePlatform, DeviceStatus, NearClipManager};
+use nearclip_ble::{AdvertisementKey, BleController};
 
 /// Spawn a BLE receive task with optional BleController for device ID remapping
 ///
@@ -21,7 +21,8 @@ use nearclip_ble::BleController;
 ///
 /// When a PairingRequest is received, the real device_id from the message is used
 /// to update the BleController mappings. This is important in peripheral mode
-/// where the initial device_id is just the central's MAC address.
+/// where the initial device_id is just the central's MAC address. An
+/// advertisement key carried by the request is recorded on the paired device.
 ///
 /// # Arguments
 ///
@@ -29,6 +30,9 @@ use nearclip_ble::BleController;
 /// * `callback` - The FFI callback to notify
 /// * `device_id` - The initial device ID (may be a MAC address in peripheral mode)
 /// * `ble_controller` - Optional BleController for updating device mappings
+/// * `manager` - Core manager. Its shared deduplicator delivers clips raced
+///   over WiFi and BLE once, its delivery tracker is woken when the peer
+///   acknowledges a clip, and it records advertisement keys the peer sends
 ///
 /// # Returns
 ///
@@ -38,7 +42,11 @@ pub fn spawn_ble_recv_task_with_controller(
     callback: Arc<dyn FfiNearClipCallback>,
     device_id: String,
     ble_controller: Option<Arc<RwLock<Option<Arc<BleController>>>>>,
+    manager: Arc<NearClipManager>,
 ) -> JoinHandle<()> {
+    let message_guard = manager.message_guard();
+    let delivery_tracker = manager.delivery_tracker();
+    let local_device_id = manager.device_id().to_string();
     tokio::spawn(async move {
         tracing::info!(device_id = %device_id, "BLE receive task started");
         let mut current_device_id = device_id.clone();
@@ -79,6 +87,19 @@ pub fn spawn_ble_recv_task_with_controller(
                                         "Received pairing info, updating device ID mapping"
                                     );
 
+                                    if let Some(ref key) = pairing_info.advertisement_key {
+                                        match AdvertisementKey::from_slice(key) {
+                                            Some(key) => {
+                                                manager.update_device_advertisement_key(&real_device_id, key);
+                                            }
+                                            None => tracing::warn!(
+                                                device_id = %real_device_id,
+                                                len = key.len(),
+                                                "Ignoring invalid advertisement key"
+                                            ),
+                                        }
+                                    }
+
                                     // Update BleController mapping if the device_id is different
                                     // (happens in peripheral mode where initial device_id is MAC address)
                                     if current_device_id != real_device_id {
@@ -106,6 +127,11 @@ pub fn spawn_ble_recv_task_with_controller(
                                             name: device_name,
                                             platform,
                                             status: DeviceStatus::Connected,
+                                            channel_preference: ChannelPreference::Auto,
+                                            static_addresses: Vec::new(),
+                                            last_known_address: None,
+                                            proximity_thresholds: None,
+                                            advertisement_key: None,
                                         };
                                         callback.on_device_connected(device_info);
 
@@ -121,6 +147,16 @@ pub fn spawn_ble_recv_task_with_controller(
                             }
                         }
                         MessageType::ClipboardSync => {
+                            // Acknowledge duplicates too - the sender may be retrying
+                            if message.has_message_id() {
+                                let ack = Message::ack_for(message.message_id, local_device_id.clone());
+                                if let Err(e) = transport.send(&ack).await {
+                                    tracing::warn!(error = %e, "Failed to send BLE ack");
+                                }
+                            }
+                            if !message_guard.record_message_id(&message.device_id, message.message_id) {
+                                continue;
+                            }
                             tracing::info!(
                                 from = %message.device_id,
                                 size = message.payload.len(),
@@ -131,6 +167,11 @@ pub fn spawn_ble_recv_task_with_controller(
                                 message.device_id.clone(),
                             );
                         }
+                        MessageType::Ack => {
+                            if let Some(acked_id) = message.acked_message_id() {
+                                delivery_tracker.acknowledge(&message.device_id, acked_id);
+                            }
+                        }
                         MessageType::Unpair => {
                             tracing::info!(
                                 from = %message.device_id,
@@ -21,13 +21,15 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 use std::sync::RwLock as StdRwLock;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use nearclip_core::{
-    DeviceInfo, DevicePlatform, DeviceStatus, HistoryManager, NearClipCallback, NearClipConfig,
-    NearClipError, NearClipManager, SyncHistoryEntry,
+    DeviceInfo, DevicePlatform, DeviceStatus, HistoryManager, InterfaceFilter, NearClipCallback,
+    NearClipConfig, NearClipError, NearClipManager, PeerAddress, ProximityPolicy, ProximityState,
+    ProximityThresholds, RelayConfig, SyncHistoryEntry, DEFAULT_FAR_RSSI, DEFAULT_NEAR_RSSI,
+    DEFAULT_PORT_RANGE_END, DEFAULT_PORT_RANGE_START, DEFAULT_PROXIMITY_TIMEOUT_SECS,
 };
-use nearclip_sync::{Message, PairingPayload, ProtocolPlatform};
+use nearclip_sync::{Channel, ChannelPreference, Message, PairingPayload, ProtocolPlatform};
 
 /// 安全截断 UTF-8 字符串，确保不会在字符中间切断
 fn truncate_utf8(s: &str, max_chars: usize) -> &str {
@@ -36,8 +38,15 @@ fn truncate_utf8(s: &str, max_chars: usize) -> &str {
         None => s,
     }
 }
-use nearclip_transport::{BleTransport, BleSender, Transport};
-use nearclip_ble::{BleController, BleControllerCallback, BleControllerConfig, ControllerDiscoveredDevice};
+use nearclip_transport::{BleTransport, BleSender, PeerSecretLookup, Transport};
+use nearclip_ble::{
+    AdvertisementKey, BleController, BleControllerCallback, BleControllerConfig, BlePairingChannel,
+    BlePairingRole, BlePhy, ConnectionPriority, ControllerDiscoveredDevice, LinkParameters,
+};
+use nearclip_device::{
+    DeviceManager, DiscoveredDevice, DiscoveryChannel, NearbyDevices, PairedDevice, PairingError,
+    PairingManager,
+};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 
@@ -46,6 +55,12 @@ mod ble_recv_task;
 use ble_hardware_bridge::BleHardwareBridge;
 use ble_recv_task::spawn_ble_recv_task_with_controller;
 
+/// How often a central requests the RSSI of its BLE connections
+///
+/// A connected peripheral usually stops advertising, so scans no longer
+/// report it; these readings keep its proximity current.
+const CONNECTION_RSSI_INTERVAL: Duration = Duration::from_secs(5);
+
 // ============================================================
 // FFI Types (must be defined before uniffi scaffolding)
 // ============================================================
@@ -59,6 +74,59 @@ pub struct FfiDiscoveredDevice {
     pub public_key_hash: Option<String>,
 }
 
+/// A nearby device seen over mDNS and/or BLE, one entry per device ID
+#[derive(Debug, Clone)]
+pub struct FfiNearbyDevice {
+    pub device_id: String,
+    /// Name of the paired device, empty for unpaired devices
+    pub device_name: String,
+    pub public_key_hash: String,
+    /// Reachable WiFi addresses as "ip:port"
+    pub wifi_addresses: Vec<String>,
+    pub ble_peripheral_uuid: Option<String>,
+    pub rssi: Option<i16>,
+    pub last_seen_ms: i64,
+    pub paired: bool,
+}
+
+impl From<DiscoveredDevice> for FfiNearbyDevice {
+    fn from(device: DiscoveredDevice) -> Self {
+        let mut wifi_addresses = Vec::new();
+        let mut ble_peripheral_uuid = None;
+        for channel in &device.channels {
+            match channel {
+                DiscoveryChannel::WiFi { ip, port } => {
+                    let address = match ip.parse::<std::net::IpAddr>() {
+                        Ok(ip) => std::net::SocketAddr::new(ip, *port).to_string(),
+                        Err(_) => format!("{}:{}", ip, port),
+                    };
+                    wifi_addresses.push(address);
+                }
+                DiscoveryChannel::BLE { peripheral_id, .. } => {
+                    ble_peripheral_uuid = Some(peripheral_id.clone());
+                }
+            }
+        }
+        Self {
+            rssi: device.rssi(),
+            device_id: device.device_id,
+            device_name: device.device_name,
+            public_key_hash: device.public_key_hash,
+            wifi_addresses,
+            ble_peripheral_uuid,
+            last_seen_ms: device.last_seen,
+            paired: false,
+        }
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
 /// BLE Controller configuration
 #[derive(Debug, Clone)]
 pub struct FfiBleControllerConfig {
@@ -69,6 +137,11 @@ pub struct FfiBleControllerConfig {
     pub reconnect_base_delay_ms: u64,
     pub health_check_interval_ms: u64,
     pub connection_timeout_ms: u64,
+    pub connection_priority: ConnectionPriority,
+    pub preferred_phy: BlePhy,
+    pub data_length: u16,
+    pub advertisement_rotation_ms: u64,
+    pub discover_unpaired: bool,
 }
 
 impl Default for FfiBleControllerConfig {
@@ -81,10 +154,103 @@ impl Default for FfiBleControllerConfig {
             reconnect_base_delay_ms: 1000,
             health_check_interval_ms: 30000,
             connection_timeout_ms: 300000, // 5 minutes - allow longer idle periods for BLE
+            connection_priority: ConnectionPriority::Balanced,
+            preferred_phy: BlePhy::Le2M,
+            data_length: nearclip_ble::MAX_DATA_LENGTH,
+            advertisement_rotation_ms: nearclip_ble::DEFAULT_ADVERTISEMENT_ROTATION_SECS * 1000,
+            discover_unpaired: true,
+        }
+    }
+}
+
+/// Requested BLE connection interval range for FFI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiBleConnectionPriority {
+    LowPower,
+    Balanced,
+    High,
+}
+
+impl From<ConnectionPriority> for FfiBleConnectionPriority {
+    fn from(priority: ConnectionPriority) -> Self {
+        match priority {
+            ConnectionPriority::LowPower => Self::LowPower,
+            ConnectionPriority::Balanced => Self::Balanced,
+            ConnectionPriority::High => Self::High,
+        }
+    }
+}
+
+/// BLE physical layer for FFI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FfiBlePhy {
+    Le1M,
+    Le2M,
+    LeCoded,
+}
+
+impl From<BlePhy> for FfiBlePhy {
+    fn from(phy: BlePhy) -> Self {
+        match phy {
+            BlePhy::Le1M => Self::Le1M,
+            BlePhy::Le2M => Self::Le2M,
+            BlePhy::LeCoded => Self::LeCoded,
+        }
+    }
+}
+
+impl From<FfiBlePhy> for BlePhy {
+    fn from(phy: FfiBlePhy) -> Self {
+        match phy {
+            FfiBlePhy::Le1M => Self::Le1M,
+            FfiBlePhy::Le2M => Self::Le2M,
+            FfiBlePhy::LeCoded => Self::LeCoded,
+        }
+    }
+}
+
+/// Negotiated BLE link parameters for FFI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FfiBleLinkParameters {
+    pub mtu: u32,
+    pub connection_interval_us: u32,
+    pub phy: FfiBlePhy,
+    pub data_length: u16,
+}
+
+impl From<LinkParameters> for FfiBleLinkParameters {
+    fn from(link: LinkParameters) -> Self {
+        Self {
+            mtu: link.mtu as u32,
+            connection_interval_us: link.connection_interval.as_micros() as u32,
+            phy: link.phy.into(),
+            data_length: link.data_length,
         }
     }
 }
 
+impl From<FfiBleLinkParameters> for LinkParameters {
+    fn from(ffi: FfiBleLinkParameters) -> Self {
+        LinkParameters::new()
+            .with_mtu(ffi.mtu.min(u16::MAX as u32) as u16)
+            .with_connection_interval(Duration::from_micros(ffi.connection_interval_us as u64))
+            .with_phy(ffi.phy.into())
+            .with_data_length(ffi.data_length)
+    }
+}
+
+/// BLE link statistics for a connected device
+#[derive(Debug, Clone)]
+pub struct FfiBleLinkStats {
+    pub parameters: FfiBleLinkParameters,
+    /// Payload bytes per chunk
+    pub chunk_size: u32,
+    /// Throughput the link parameters allow (bytes per second)
+    pub estimated_throughput: u32,
+    /// Measured throughput of acknowledged messages (bytes per second)
+    pub effective_throughput: Option<u32>,
+}
+
 /// Sync history entry for FFI
 #[derive(Debug, Clone)]
 pub struct FfiSyncHistoryEntry {
@@ -106,7 +272,8 @@ pub struct FfiSyncHistoryEntry {
 /// Bridge that adapts BleControllerCallback to FFI callback
 struct BleControllerCallbackBridge {
     ffi_callback: Arc<dyn FfiNearClipCallback>,
-    discovered_devices: Arc<RwLock<HashMap<String, FfiDiscoveredDevice>>>,
+    nearby_devices: Arc<NearbyDevices>,
+    manager: Arc<NearClipManager>,
 }
 
 impl BleControllerCallback for BleControllerCallbackBridge {
@@ -118,26 +285,19 @@ impl BleControllerCallback for BleControllerCallbackBridge {
             public_key_hash: Some(device.public_key_hash.clone()),
         };
 
-        // Store in discovered devices
-        let discovered = Arc::clone(&self.discovered_devices);
-        let ffi_device_clone = ffi_device.clone();
-        let peripheral_uuid = device.peripheral_uuid.clone();
-        tokio::spawn(async move {
-            let mut devices = discovered.write().await;
-            devices.insert(peripheral_uuid, ffi_device_clone);
-        });
+        // Merge into the nearby devices list and track proximity
+        let sighting = DiscoveredDevice::from(device);
+        if let Some(rssi) = sighting.rssi() {
+            self.manager.handle_rssi(&sighting.device_id, rssi);
+        }
+        self.nearby_devices.observe(sighting);
 
         // Notify platform via callback
         self.ffi_callback.on_device_discovered(ffi_device);
     }
 
     fn on_device_lost(&self, peripheral_uuid: String) {
-        let discovered = Arc::clone(&self.discovered_devices);
-        let peripheral_uuid_clone = peripheral_uuid.clone();
-        tokio::spawn(async move {
-            let mut devices = discovered.write().await;
-            devices.remove(&peripheral_uuid_clone);
-        });
+        self.nearby_devices.remove_ble_peripheral(&peripheral_uuid);
 
         // Notify platform via callback
         self.ffi_callback.on_device_lost(peripheral_uuid);
@@ -149,6 +309,11 @@ impl BleControllerCallback for BleControllerCallbackBridge {
             name: format!("BLE Device {}", truncate_utf8(&device_id, 8)),
             platform: DevicePlatform::Unknown,
             status: DeviceStatus::Connected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
         };
         self.ffi_callback.on_device_connected(device_info);
     }
@@ -220,6 +385,15 @@ pub struct FfiDeviceInfo {
     pub name: String,
     pub platform: DevicePlatform,
     pub status: DeviceStatus,
+    pub channel_preference: ChannelPreference,
+    /// Static peer addresses (`host:port`) tried when mDNS finds nothing
+    pub static_addresses: Vec<String>,
+    /// Address (`ip:port`) of the last successful connection, tried after mDNS
+    pub last_known_address: Option<String>,
+    /// Proximity thresholds for this device (None = config defaults)
+    pub proximity_thresholds: Option<ProximityThresholds>,
+    /// The device's BLE advertisement key (32 bytes), exchanged during pairing
+    pub advertisement_key: Option<Vec<u8>>,
 }
 
 impl From<DeviceInfo> for FfiDeviceInfo {
@@ -229,15 +403,52 @@ impl From<DeviceInfo> for FfiDeviceInfo {
             name: device.name().to_string(),
             platform: device.platform(),
             status: device.status(),
+            channel_preference: device.channel_preference(),
+            static_addresses: device
+                .static_addresses()
+                .iter()
+                .map(|address| address.to_string())
+                .collect(),
+            last_known_address: device.last_known_address().map(|address| address.to_string()),
+            proximity_thresholds: device.proximity_thresholds(),
+            advertisement_key: device.advertisement_key().map(|key| key.as_bytes().to_vec()),
         }
     }
 }
 
 impl From<FfiDeviceInfo> for DeviceInfo {
     fn from(ffi: FfiDeviceInfo) -> Self {
-        DeviceInfo::new(ffi.id, ffi.name)
+        let device = DeviceInfo::new(ffi.id, ffi.name)
             .with_platform(ffi.platform)
             .with_status(ffi.status)
+            .with_channel_preference(ffi.channel_preference)
+            .with_proximity_thresholds(ffi.proximity_thresholds)
+            .with_advertisement_key(ffi.advertisement_key.as_deref().and_then(|key| {
+                let parsed = AdvertisementKey::from_slice(key);
+                if parsed.is_none() {
+                    tracing::warn!(len = key.len(), "Ignoring invalid advertisement key");
+                }
+                parsed
+            }))
+            .with_last_known_address(ffi.last_known_address.as_deref().and_then(|address| {
+                match address.parse::<std::net::SocketAddr>() {
+                    Ok(address) => Some(address),
+                    Err(e) => {
+                        tracing::warn!(address = %address, error = %e, "Ignoring invalid last known address");
+                        None
+                    }
+                }
+            }));
+        ffi.static_addresses
+            .iter()
+            .filter_map(|address| match address.parse::<PeerAddress>() {
+                Ok(address) => Some(address),
+                Err(e) => {
+                    tracing::warn!(address = %address, error = %e, "Ignoring invalid static address");
+                    None
+                }
+            })
+            .fold(device, |device, address| device.with_static_address(address))
     }
 }
 
@@ -258,11 +469,63 @@ pub struct FfiNearClipConfig {
     pub connection_timeout_secs: u64,
     pub heartbeat_interval_secs: u64,
     pub max_retries: u32,
+    /// Interfaces used for discovery and listening (empty = all); a trailing `*` matches a prefix
+    ///
+    /// When any interface setting is given, the TCP/QUIC listeners bind only
+    /// to the addresses of the allowed interfaces.
+    pub allowed_interfaces: Vec<String>,
+    /// Interfaces never used for discovery or listening; a trailing `*` matches a prefix
+    pub excluded_interfaces: Vec<String>,
+    /// Skip VPN, tunnel and virtual interfaces
+    pub exclude_virtual_interfaces: bool,
+    /// Watch for interface changes and recover connections automatically
+    pub network_monitor: bool,
+    /// First port of the preferred listening port range
+    pub port_range_start: u16,
+    /// Last port of the preferred listening port range
+    pub port_range_end: u16,
+    /// Also listen and dial over QUIC (preferred over TCP when both work)
+    pub quic_enabled: bool,
+    /// Pause syncing to devices whose BLE signal says they walked away
+    pub sync_only_when_near: bool,
+    /// Connect to paired devices when they come back nearby
+    pub auto_connect_when_near: bool,
+    /// Smoothed RSSI (dBm) at or above which a device is nearby
+    pub near_rssi: i16,
+    /// Smoothed RSSI (dBm) at or below which a device is away
+    pub far_rssi: i16,
+    /// Seconds without BLE readings before a device counts as away
+    pub proximity_timeout_secs: u64,
+    /// Relay address (`host:port`) used when no local channel reaches a paired device
+    pub relay_address: Option<String>,
+    /// The relay's TLS certificate (DER); the only certificate trusted for the relay
+    pub relay_certificate: Option<Vec<u8>>,
 }
 
 impl From<FfiNearClipConfig> for NearClipConfig {
     fn from(ffi: FfiNearClipConfig) -> Self {
-        NearClipConfig::new(ffi.device_name)
+        let filter = ffi
+            .allowed_interfaces
+            .into_iter()
+            .fold(InterfaceFilter::new(), |filter, name| filter.with_allowed_interface(name));
+        let filter = ffi
+            .excluded_interfaces
+            .into_iter()
+            .fold(filter, |filter, name| filter.with_excluded_interface(name))
+            .with_exclude_virtual(ffi.exclude_virtual_interfaces);
+
+        let config = match (ffi.relay_address, ffi.relay_certificate) {
+            (Some(address), Some(certificate)) => {
+                NearClipConfig::new(ffi.device_name).with_relay(RelayConfig::new(address, certificate))
+            }
+            (None, None) => NearClipConfig::new(ffi.device_name),
+            _ => {
+                tracing::warn!("Relay needs both an address and a certificate, relay disabled");
+                NearClipConfig::new(ffi.device_name)
+            }
+        };
+
+        config
             .with_device_id(ffi.device_id)
             .with_wifi_enabled(ffi.wifi_enabled)
             .with_ble_enabled(ffi.ble_enabled)
@@ -270,6 +533,17 @@ impl From<FfiNearClipConfig> for NearClipConfig {
             .with_connection_timeout(Duration::from_secs(ffi.connection_timeout_secs))
             .with_heartbeat_interval(Duration::from_secs(ffi.heartbeat_interval_secs))
             .with_max_retries(ffi.max_retries)
+            .with_interface_filter(filter)
+            .with_network_monitor(ffi.network_monitor)
+            .with_port_range(ffi.port_range_start..=ffi.port_range_end)
+            .with_quic_enabled(ffi.quic_enabled)
+            .with_proximity_policy(ProximityPolicy {
+                sync_only_when_near: ffi.sync_only_when_near,
+                auto_connect_when_near: ffi.auto_connect_when_near,
+                thresholds: ProximityThresholds::new(ffi.near_rssi, ffi.far_rssi),
+                timeout: Duration::from_secs(ffi.proximity_timeout_secs),
+                ..Default::default()
+            })
     }
 }
 
@@ -284,6 +558,20 @@ impl Default for FfiNearClipConfig {
             connection_timeout_secs: 30,
             heartbeat_interval_secs: 10,
             max_retries: 3,
+            allowed_interfaces: Vec::new(),
+            excluded_interfaces: Vec::new(),
+            exclude_virtual_interfaces: false,
+            network_monitor: true,
+            port_range_start: DEFAULT_PORT_RANGE_START,
+            port_range_end: DEFAULT_PORT_RANGE_END,
+            quic_enabled: false,
+            sync_only_when_near: false,
+            auto_connect_when_near: false,
+            near_rssi: DEFAULT_NEAR_RSSI,
+            far_rssi: DEFAULT_FAR_RSSI,
+            proximity_timeout_secs: DEFAULT_PROXIMITY_TIMEOUT_SECS,
+            relay_address: None,
+            relay_certificate: None,
         }
     }
 }
@@ -317,11 +605,29 @@ pub trait FfiNearClipCallback: Send + Sync {
     /// Called when a sync error occurs
     fn on_sync_error(&self, error_message: String);
 
+    /// Called when a device acknowledges a synced clip
+    ///
+    /// `message_id` matches the value returned by `sync_clipboard`.
+    fn on_sync_delivered(&self, device_id: String, message_id: u64);
+
+    /// Called when a clip could not be delivered to a device
+    ///
+    /// Fired when sending fails or no acknowledgement arrives after all retries.
+    fn on_sync_failed(&self, device_id: String, message_id: u64, error_message: String);
+
     /// Called when a BLE device is discovered during scanning
     fn on_device_discovered(&self, device: FfiDiscoveredDevice);
 
     /// Called when a previously discovered BLE device is lost
     fn on_device_lost(&self, peripheral_uuid: String);
+
+    /// Called when a paired device comes nearby
+    ///
+    /// `rssi` is the smoothed signal strength in dBm.
+    fn on_proximity_entered(&self, device_id: String, rssi: i16);
+
+    /// Called when a paired device walks away or stops being seen over BLE
+    fn on_proximity_left(&self, device_id: String);
 }
 
 // ============================================================
@@ -392,7 +698,9 @@ pub trait FfiBleHardware: Send + Sync {
 
     /// Start BLE advertising (peripheral mode)
     ///
-    /// The `service_data` contains the device info to broadcast
+    /// Called by Rust with this device's rotating identity token as the
+    /// NearClip service data, again each time the token rotates. Each call
+    /// replaces the current advertisement.
     fn start_advertising(&self, service_data: Vec<u8>);
 
     /// Stop BLE advertising
@@ -405,6 +713,51 @@ pub trait FfiBleHardware: Send + Sync {
 
     /// Get the negotiated MTU for a peripheral
     fn get_mtu(&self, peripheral_uuid: String) -> u32;
+
+    // ========== L2CAP (optional) ==========
+
+    /// Open an L2CAP connection-oriented channel on the peer's PSM
+    ///
+    /// The outcome is reported with `on_ble_l2cap_state_changed`.
+    /// Returns empty string if the open was started, error message if
+    /// L2CAP is unsupported (data then stays on GATT)
+    fn open_l2cap_channel(&self, peripheral_uuid: String, psm: u16) -> String;
+
+    /// Write data to the open L2CAP channel
+    ///
+    /// Returns empty string on success, error message on failure
+    fn write_l2cap(&self, peripheral_uuid: String, data: Vec<u8>) -> String;
+
+    /// Close the L2CAP channel to a peripheral, if open
+    fn close_l2cap_channel(&self, peripheral_uuid: String);
+
+    // ========== Link Tuning (optional) ==========
+
+    /// Request a connection interval range
+    ///
+    /// Returns empty string if the request was started, error message if unsupported
+    fn request_connection_priority(
+        &self,
+        peripheral_uuid: String,
+        priority: FfiBleConnectionPriority,
+    ) -> String;
+
+    /// Request a PHY for both directions of the connection
+    ///
+    /// Returns empty string if the request was started, error message if unsupported
+    fn request_phy(&self, peripheral_uuid: String, phy: FfiBlePhy) -> String;
+
+    /// Request data length extension (link-layer payload in bytes, 27-251)
+    ///
+    /// Returns empty string if the request was started, error message if unsupported
+    fn request_data_length(&self, peripheral_uuid: String, octets: u16) -> String;
+
+    // ========== Connection RSSI (optional) ==========
+
+    /// Request the RSSI of a connection, reported with `on_ble_connection_rssi`
+    ///
+    /// Returns empty string if the read was started, error message if unsupported
+    fn read_rssi(&self, peripheral_uuid: String) -> String;
 }
 
 // ============================================================
@@ -427,6 +780,30 @@ pub trait FfiDeviceStorage: Send + Sync {
     /// Load all paired devices from persistent storage
     /// Called by Rust during initialization
     fn load_all_devices(&self) -> Vec<FfiDeviceInfo>;
+
+    /// Load this device's BLE advertisement key (None if never saved)
+    /// Called by Rust during initialization
+    fn load_advertisement_key(&self) -> Option<Vec<u8>>;
+
+    /// Save this device's BLE advertisement key
+    /// Called by Rust the first time a key is generated
+    fn save_advertisement_key(&self, key: Vec<u8>);
+
+    /// Load this device's identity private key (None if never saved)
+    /// Called by Rust during initialization
+    fn load_identity_key(&self) -> Option<Vec<u8>>;
+
+    /// Save this device's identity private key
+    /// Called by Rust the first time a key is generated; the relay binds its public key to this device ID
+    fn save_identity_key(&self, key: Vec<u8>);
+
+    /// Load the shared secret for a paired device (None if never saved)
+    /// Called by Rust during initialization for every loaded device
+    fn load_shared_secret(&self, device_id: String) -> Option<Vec<u8>>;
+
+    /// Save the shared secret computed while pairing with a device
+    /// Removed by the platform together with the device in `remove_device`
+    fn save_shared_secret(&self, device_id: String, secret: Vec<u8>);
 }
 
 /// Bridge that adapts FfiBleHardware to the transport layer's BleSender trait
@@ -434,11 +811,25 @@ pub trait FfiDeviceStorage: Send + Sync {
 /// This allows BleTransport to use FfiBleHardware for data transmission.
 struct BleHardwareSenderBridge {
     hardware: Arc<dyn FfiBleHardware>,
+    /// Link parameters reported by the platform, keyed by device_id
+    link_parameters: StdRwLock<HashMap<String, LinkParameters>>,
 }
 
 impl BleHardwareSenderBridge {
     fn new(hardware: Arc<dyn FfiBleHardware>) -> Self {
-        Self { hardware }
+        Self {
+            hardware,
+            link_parameters: StdRwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Record (or forget, on disconnect) the link parameters of a device
+    fn set_link_parameters(&self, device_id: &str, link: Option<LinkParameters>) {
+        let mut link_parameters = self.link_parameters.write().unwrap();
+        match link {
+            Some(link) => link_parameters.insert(device_id.to_string(), link),
+            None => link_parameters.remove(device_id),
+        };
     }
 }
 
@@ -489,6 +880,70 @@ impl BleSender for BleHardwareSenderBridge {
         }
     }
 
+    fn send_chunk_report(&self, device_id: &str, report: &[u8]) -> Result<(), String> {
+        // Chunk reports share DATA_ACK with message ACKs; the peer tells them apart by length
+        let char_uuid = nearclip_ble::DATA_ACK_CHARACTERISTIC_UUID.to_string();
+
+        let error = self.hardware.write_characteristic(
+            device_id.to_string(),
+            char_uuid,
+            report.to_vec(),
+        );
+
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn open_l2cap(&self, device_id: &str) -> Result<(), String> {
+        // The peripheral publishes its PSM only if it listens for L2CAP channels
+        let char_uuid = nearclip_ble::L2CAP_PSM_CHARACTERISTIC_UUID.to_string();
+        let value = self.hardware.read_characteristic(device_id.to_string(), char_uuid);
+        let psm = nearclip_ble::parse_l2cap_psm(&value)
+            .ok_or("Peer does not publish an L2CAP PSM")?;
+
+        let error = self.hardware.open_l2cap_channel(device_id.to_string(), psm);
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn send_l2cap_data(&self, device_id: &str, data: &[u8]) -> Result<(), String> {
+        let error = self.hardware.write_l2cap(device_id.to_string(), data.to_vec());
+        if error.is_empty() {
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    fn link_parameters(&self, device_id: &str) -> Option<LinkParameters> {
+        // The MTU may have changed since the last report; the platform always knows it
+        let link = *self.link_parameters.read().unwrap().get(device_id)?;
+        match self.hardware.get_mtu(device_id.to_string()) {
+            0 => Some(link),
+            mtu => Some(link.with_mtu(mtu.min(u16::MAX as u32) as u16)),
+        }
+    }
+
+    fn peer_chunk_version(&self, device_id: &str) -> nearclip_ble::ChunkVersion {
+        // Empty on error: peers without the characteristic only understand v1
+        let char_uuid = nearclip_ble::CHUNK_VERSION_CHARACTERISTIC_UUID.to_string();
+        let value = self.hardware.read_characteristic(device_id.to_string(), char_uuid);
+        nearclip_ble::parse_chunk_version(&value)
+    }
+
+    fn peer_supports_mux(&self, device_id: &str) -> bool {
+        // Empty on error: peers without the characteristic expect whole messages
+        let char_uuid = nearclip_ble::FRAMING_CHARACTERISTIC_UUID.to_string();
+        let value = self.hardware.read_characteristic(device_id.to_string(), char_uuid);
+        nearclip_ble::parse_mux_framing(&value)
+    }
+
     fn subscribe_ack(&self, device_id: &str) -> Result<(), String> {
         // Subscribe to DATA_ACK_CHARACTERISTIC_UUID for ACK notifications
         let char_uuid = nearclip_ble::DATA_ACK_CHARACTERISTIC_UUID.to_string();
@@ -506,14 +961,52 @@ impl BleSender for BleHardwareSenderBridge {
     }
 }
 
+/// Shared slot for the platform device storage
+type DeviceStorageSlot = Arc<RwLock<Option<Arc<dyn FfiDeviceStorage>>>>;
+
+/// Shared slot for the BLE controller (created once BLE hardware is set)
+type BleControllerSlot = Arc<RwLock<Option<Arc<BleController>>>>;
+
 /// Bridge callback that converts between FFI and core callbacks
 struct CallbackBridge {
     ffi_callback: Arc<dyn FfiNearClipCallback>,
+    /// Device storage, used to persist devices the core updates
+    device_storage: DeviceStorageSlot,
+    /// BLE controller, told about advertisement keys learned by the core
+    ble_controller: BleControllerSlot,
 }
 
 impl CallbackBridge {
-    fn new(ffi_callback: Arc<dyn FfiNearClipCallback>) -> Self {
-        Self { ffi_callback }
+    fn new(
+        ffi_callback: Arc<dyn FfiNearClipCallback>,
+        device_storage: DeviceStorageSlot,
+        ble_controller: BleControllerSlot,
+    ) -> Self {
+        Self {
+            ffi_callback,
+            device_storage,
+            ble_controller,
+        }
+    }
+
+    /// Run an update against the BLE controller, if one is set
+    ///
+    /// Called from core tasks, so the update is spawned instead of awaited.
+    fn update_ble_controller<F, Fut>(&self, device_id: &str, update: F)
+    where
+        F: FnOnce(Arc<BleController>) -> Fut,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let controller = match self.ble_controller.try_read() {
+            Ok(controller) => controller.clone(),
+            Err(_) => {
+                tracing::warn!(device_id = %device_id, "BLE controller busy, peer key not updated");
+                return;
+            }
+        };
+        if let (Some(controller), Ok(handle)) = (controller, tokio::runtime::Handle::try_current()) {
+            handle.spawn(update(controller));
+        }
     }
 }
 
@@ -527,6 +1020,10 @@ impl NearClipCallback for CallbackBridge {
     }
 
     fn on_device_unpaired(&self, device_id: &str) {
+        let id = device_id.to_string();
+        self.update_ble_controller(device_id, |controller| async move {
+            controller.remove_paired_device(&id).await;
+        });
         self.ffi_callback.on_device_unpaired(device_id.to_string());
     }
 
@@ -538,33 +1035,95 @@ impl NearClipCallback for CallbackBridge {
         self.ffi_callback.on_sync_error(error.to_string());
     }
 
+    fn on_sync_delivered(&self, device_id: &str, message_id: u64) {
+        self.ffi_callback.on_sync_delivered(device_id.to_string(), message_id);
+    }
+
+    fn on_sync_failed(&self, device_id: &str, message_id: u64, error: &NearClipError) {
+        self.ffi_callback
+            .on_sync_failed(device_id.to_string(), message_id, error.to_string());
+    }
+
     fn on_pairing_rejected(&self, device_id: &str, reason: &str) {
         self.ffi_callback.on_pairing_rejected(device_id.to_string(), reason.to_string());
     }
+
+    fn on_proximity_entered(&self, device_id: &str, rssi: i16) {
+        self.ffi_callback.on_proximity_entered(device_id.to_string(), rssi);
+    }
+
+    fn on_proximity_left(&self, device_id: &str) {
+        self.ffi_callback.on_proximity_left(device_id.to_string());
+    }
+
+    fn on_device_updated(&self, device: &DeviceInfo) {
+        // Called from core tasks, so the storage lock must not block
+        match self.device_storage.try_read() {
+            Ok(storage) => {
+                if let Some(ref storage) = *storage {
+                    storage.save_device(FfiDeviceInfo::from(device.clone()));
+                    tracing::debug!(device_id = %device.id(), "Updated device saved to storage");
+                }
+            }
+            Err(_) => tracing::warn!(device_id = %device.id(), "Device storage busy, update not saved"),
+        }
+
+        // A key learned during the handshake lets the controller recognize the peer's advertisements
+        if let Some(key) = device.advertisement_key().cloned() {
+            let id = device.id().to_string();
+            self.update_ble_controller(device.id(), |controller| async move {
+                controller.add_paired_device(&id, key).await;
+            });
+        }
+    }
 }
 
 // ============================================================
 // FFI Manager
 // ============================================================
 
+/// Periodically request the RSSI of a BLE connection
+///
+/// Stops at the first refused request: the platform cannot read connection
+/// RSSI, and proximity falls back to scan sightings.
+fn spawn_connection_rssi_task(
+    hardware: Arc<dyn FfiBleHardware>,
+    device_id: String,
+    peripheral_uuid: String,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(CONNECTION_RSSI_INTERVAL);
+        loop {
+            interval.tick().await;
+            let error = hardware.read_rssi(peripheral_uuid.clone());
+            if !error.is_empty() {
+                tracing::debug!(device_id = %device_id, error = %error, "Connection RSSI unavailable");
+                break;
+            }
+        }
+    })
+}
+
 /// Main NearClip manager for FFI
 ///
 /// This is the main entry point for platform clients.
 pub struct FfiNearClipManager {
-    inner: NearClipManager,
+    inner: Arc<NearClipManager>,
     runtime: tokio::runtime::Runtime,
     /// BLE hardware interface (set by platform)
     ble_hardware: RwLock<Option<Arc<dyn FfiBleHardware>>>,
     /// BLE hardware sender bridge for BleTransport
     ble_hardware_sender: RwLock<Option<Arc<BleHardwareSenderBridge>>>,
-    /// BLE controller (manages BLE logic)
-    ble_controller: Arc<RwLock<Option<Arc<BleController>>>>,
+    /// BLE controller (manages BLE logic, shared with the callback bridge)
+    ble_controller: BleControllerSlot,
     /// BLE transports per device
     ble_transports: RwLock<HashMap<String, Arc<BleTransport>>>,
     /// BLE receive tasks per device
     ble_recv_tasks: RwLock<HashMap<String, JoinHandle<()>>>,
-    /// Discovered BLE devices (keyed by peripheral_uuid)
-    discovered_devices: Arc<RwLock<HashMap<String, FfiDiscoveredDevice>>>,
+    /// Connection RSSI polling tasks per device (central role only)
+    ble_rssi_tasks: RwLock<HashMap<String, JoinHandle<()>>>,
+    /// Devices seen over mDNS or BLE, merged by device ID
+    nearby_devices: Arc<NearbyDevices>,
     /// Mapping: peripheral_uuid -> device_id (reserved for BleController integration)
     #[allow(dead_code)]
     peripheral_to_device: RwLock<HashMap<String, String>>,
@@ -578,13 +1137,21 @@ pub struct FfiNearClipManager {
     discovery_active: AtomicBool,
     /// History manager for sync history
     history_manager: StdRwLock<Option<Arc<HistoryManager>>>,
-    /// Device storage interface (set by platform)
-    device_storage: RwLock<Option<Arc<dyn FfiDeviceStorage>>>,
+    /// Device storage interface (set by platform, shared with the callback bridge)
+    device_storage: DeviceStorageSlot,
     /// In-memory cache of device shared secrets for encryption
-    /// Maps device_id -> shared_secret (32 bytes)
-    device_secrets: RwLock<HashMap<String, Vec<u8>>>,
-    /// Local ECDH keypair for pairing (persistent across sessions)
-    local_keypair: nearclip_crypto::EcdhKeyPair,
+    /// Maps device_id -> shared_secret (32 bytes); also read by the relay transport
+    device_secrets: Arc<StdRwLock<HashMap<String, Vec<u8>>>>,
+    /// Local ECDH keypair for pairing and relay authentication (loaded from device storage)
+    local_keypair: StdRwLock<nearclip_crypto::EcdhKeyPair>,
+    /// BLE pairing in progress (fed by on_ble_pairing_data_received)
+    ble_pairing: StdRwLock<Option<Arc<BlePairingChannel>>>,
+}
+
+/// Relay secret lookup backed by the shared secrets cached at pairing
+fn secret_lookup(secrets: &Arc<StdRwLock<HashMap<String, Vec<u8>>>>) -> PeerSecretLookup {
+    let secrets = secrets.clone();
+    Arc::new(move |device_id: &str| secrets.read().unwrap().get(device_id).cloned())
 }
 
 impl FfiNearClipManager {
@@ -606,35 +1173,45 @@ impl FfiNearClipManager {
 
         // Wrap callback in Arc for sharing
         let callback: Arc<dyn FfiNearClipCallback> = callback.into();
-        let bridge = Arc::new(CallbackBridge::new(callback.clone()));
+        let device_storage: DeviceStorageSlot = Arc::new(RwLock::new(None));
+        let ble_controller: BleControllerSlot = Arc::new(RwLock::new(None));
+        let bridge = Arc::new(CallbackBridge::new(
+            callback.clone(),
+            device_storage.clone(),
+            ble_controller.clone(),
+        ));
 
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()
             .map_err(|e| NearClipError::Io(e.to_string()))?;
 
-        let inner = NearClipManager::new(core_config, bridge)?;
+        let inner = Arc::new(NearClipManager::new(core_config, bridge)?);
 
-        // Generate local ECDH keypair for pairing
+        // Generate local ECDH keypair for pairing (replaced by the stored one in set_device_storage)
         let local_keypair = nearclip_crypto::EcdhKeyPair::generate();
+        let device_secrets = Arc::new(StdRwLock::new(HashMap::new()));
+        inner.set_relay_identity(local_keypair.clone(), secret_lookup(&device_secrets));
 
         Ok(Self {
             inner,
             runtime,
             ble_hardware: RwLock::new(None),
             ble_hardware_sender: RwLock::new(None),
-            ble_controller: Arc::new(RwLock::new(None)),
+            ble_controller,
             ble_transports: RwLock::new(HashMap::new()),
             ble_recv_tasks: RwLock::new(HashMap::new()),
-            discovered_devices: Arc::new(RwLock::new(HashMap::new())),
+            ble_rssi_tasks: RwLock::new(HashMap::new()),
+            nearby_devices: Arc::new(NearbyDevices::new()),
             peripheral_to_device: RwLock::new(HashMap::new()),
             device_to_peripheral: RwLock::new(HashMap::new()),
             callback,
             discovery_active: AtomicBool::new(false),
             history_manager: StdRwLock::new(None),
-            device_storage: RwLock::new(None),
-            device_secrets: RwLock::new(HashMap::new()),
-            local_keypair,
+            device_storage,
+            device_secrets,
+            local_keypair: StdRwLock::new(local_keypair),
+            ble_pairing: StdRwLock::new(None),
         })
     }
 
@@ -662,7 +1239,12 @@ impl FfiNearClipManager {
     /// # Arguments
     ///
     /// * `content` - Clipboard content bytes
-    pub fn sync_clipboard(&self, content: Vec<u8>) -> Result<(), NearClipError> {
+    ///
+    /// # Returns
+    ///
+    /// The message ID of this sync. Delivery is reported asynchronously via
+    /// `on_sync_delivered` / `on_sync_failed` with the same ID.
+    pub fn sync_clipboard(&self, content: Vec<u8>) -> Result<u64, NearClipError> {
         tracing::info!(content_size = content.len(), "FFI sync_clipboard called");
         let result = self.runtime.block_on(async { self.inner.sync_clipboard(&content).await });
         match &result {
@@ -681,6 +1263,33 @@ impl FfiNearClipManager {
             .collect()
     }
 
+    /// Get devices currently nearby over mDNS or BLE
+    ///
+    /// One entry per device ID with every channel it was seen on, most
+    /// recently seen first. Paired devices carry their stored name.
+    pub fn get_nearby_devices(&self) -> Vec<FfiNearbyDevice> {
+        let mdns_devices = self.runtime.block_on(self.inner.get_discovered_devices());
+        self.nearby_devices.sync_mdns(&mdns_devices, now_ms());
+
+        let paired: HashMap<String, String> = self
+            .inner
+            .get_paired_devices()
+            .into_iter()
+            .map(|device| (device.id().to_string(), device.name().to_string()))
+            .collect();
+        self.nearby_devices
+            .devices()
+            .into_iter()
+            .map(|device| {
+                let name = paired.get(&device.device_id).cloned();
+                let mut nearby = FfiNearbyDevice::from(device);
+                nearby.paired = name.is_some();
+                nearby.device_name = name.unwrap_or(nearby.device_name);
+                nearby
+            })
+            .collect()
+    }
+
     /// Get list of connected devices
     pub fn get_connected_devices(&self) -> Vec<FfiDeviceInfo> {
         tracing::info!("FFI get_connected_devices called");
@@ -758,7 +1367,9 @@ impl FfiNearClipManager {
     ///
     /// * `device` - Device information to add
     pub fn add_paired_device(&self, device: FfiDeviceInfo) {
-        self.inner.add_paired_device(device.into());
+        let device: DeviceInfo = device.into();
+        self.runtime.block_on(self.register_ble_peer(&device));
+        self.inner.add_paired_device(device);
     }
 
     /// Remove a paired device
@@ -769,8 +1380,10 @@ impl FfiNearClipManager {
     pub fn remove_paired_device(&self, device_id: String) {
         self.inner.remove_paired_device(&device_id);
 
-        // Also remove from persistent storage
+        // Also remove from the BLE controller and persistent storage
         self.runtime.block_on(async {
+            self.unregister_ble_peer(&device_id).await;
+
             let storage = self.device_storage.read().await;
             if let Some(ref storage) = *storage {
                 storage.remove_device(device_id.clone());
@@ -790,8 +1403,10 @@ impl FfiNearClipManager {
     pub fn unpair_device(&self, device_id: String) -> Result<(), NearClipError> {
         let result = self.runtime.block_on(async { self.inner.unpair_device(&device_id).await });
 
-        // Also remove from persistent storage (regardless of unpair result)
+        // Also remove from the BLE controller and persistent storage (regardless of unpair result)
         self.runtime.block_on(async {
+            self.unregister_ble_peer(&device_id).await;
+
             let storage = self.device_storage.read().await;
             if let Some(ref storage) = *storage {
                 storage.remove_device(device_id.clone());
@@ -802,63 +1417,236 @@ impl FfiNearClipManager {
         result
     }
 
-    /// Get the status of a device
+    /// Set the channel preference for a paired device
+    ///
+    /// The preference is applied immediately and persisted through the
+    /// device storage interface.
     ///
     /// # Arguments
     ///
     /// * `device_id` - ID of the device
-    ///
-    /// # Returns
-    ///
-    /// Device status if found, None otherwise.
-    pub fn get_device_status(&self, device_id: String) -> Option<DeviceStatus> {
-        self.inner.get_device_status(&device_id)
+    /// * `preference` - Channel preference
+    pub fn set_device_channel_preference(
+        &self,
+        device_id: String,
+        preference: ChannelPreference,
+    ) -> Result<(), NearClipError> {
+        self.runtime.block_on(async {
+            self.inner.set_device_channel_preference(&device_id, preference).await?;
+
+            let device = self
+                .inner
+                .get_paired_devices()
+                .into_iter()
+                .find(|d| d.id() == device_id);
+            let storage = self.device_storage.read().await;
+            if let (Some(storage), Some(device)) = (storage.as_ref(), device) {
+                storage.save_device(FfiDeviceInfo::from(device));
+                tracing::info!(device_id = %device_id, "Channel preference saved to storage");
+            }
+            Ok(())
+        })
     }
 
-    /// Get this device's unique ID
-    ///
-    /// # Returns
-    ///
-    /// The device ID used for mDNS advertising and message identification.
-    pub fn get_device_id(&self) -> String {
-        self.inner.device_id().to_string()
+    /// Get the channel preference for a paired device
+    pub fn get_device_channel_preference(&self, device_id: String) -> Option<ChannelPreference> {
+        self.inner.get_device_channel_preference(&device_id)
     }
 
-    /// Try to connect to all discovered paired devices
+    /// Set the proximity thresholds for a paired device
     ///
-    /// Scans for paired devices on the network and attempts to connect.
+    /// `None` restores the defaults from the config. The thresholds are
+    /// persisted through the device storage.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// Number of devices successfully connected.
-    pub fn try_connect_paired_devices(&self) -> u32 {
-        self.runtime.block_on(async { self.inner.try_connect_paired_devices().await }) as u32
+    /// * `device_id` - ID of the device
+    /// * `thresholds` - Near/far RSSI thresholds; `near_rssi` must be above `far_rssi`
+    pub fn set_device_proximity_thresholds(
+        &self,
+        device_id: String,
+        thresholds: Option<ProximityThresholds>,
+    ) -> Result<(), NearClipError> {
+        self.inner.set_device_proximity_thresholds(&device_id, thresholds)?;
+        self.save_paired_device(&device_id);
+        Ok(())
     }
 
-    // ============================================================
-    // BLE Methods
-    // ============================================================
+    /// Get the proximity thresholds for a paired device (None = config defaults)
+    pub fn get_device_proximity_thresholds(&self, device_id: String) -> Option<ProximityThresholds> {
+        self.inner.get_device_proximity_thresholds(&device_id)
+    }
 
-    /// Get shared secret for a paired device (private helper)
+    /// Get whether a paired device is nearby, based on its smoothed BLE RSSI
+    pub fn get_device_proximity(&self, device_id: String) -> ProximityState {
+        self.inner.get_device_proximity(&device_id)
+    }
+
+    /// Get the smoothed BLE RSSI of a paired device
+    pub fn get_device_rssi(&self, device_id: String) -> Option<i16> {
+        self.inner.get_device_rssi(&device_id)
+    }
+
+    /// Add a static peer address for a paired device
     ///
-    /// Returns the ECDH shared secret for encryption if the device is paired.
-    async fn get_shared_secret(&self, device_id: &str) -> Option<Vec<u8>> {
-        let secrets = self.device_secrets.read().await;
-        let secret = secrets.get(device_id).cloned();
+    /// Used when multicast is blocked (corporate Wi-Fi, VPNs) and mDNS cannot
+    /// find the device. The address is persisted through the device storage.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - ID of the device
+    /// * `address` - `host:port`, `ip:port` or `[ipv6]:port`
+    pub fn add_static_peer(&self, device_id: String, address: String) -> Result<(), NearClipError> {
+        self.inner.add_static_peer(&device_id, &address)?;
+        self.save_paired_device(&device_id);
+        Ok(())
+    }
 
-        if secret.is_some() {
-            tracing::debug!(
-                device_id = %device_id,
-                "Retrieved shared secret from cache"
-            );
-        } else {
-            tracing::debug!(
-                device_id = %device_id,
-                "No shared secret found in cache"
-            );
+    /// Remove a static peer address from a paired device
+    ///
+    /// Returns true if the address was present.
+    pub fn remove_static_peer(&self, device_id: String, address: String) -> Result<bool, NearClipError> {
+        let removed = self.inner.remove_static_peer(&device_id, &address)?;
+        if removed {
+            self.save_paired_device(&device_id);
         }
+        Ok(removed)
+    }
 
-        secret
+    /// Get the static peer addresses of a paired device
+    pub fn get_static_peers(&self, device_id: String) -> Vec<String> {
+        self.inner
+            .get_static_peers(&device_id)
+            .iter()
+            .map(|address| address.to_string())
+            .collect()
+    }
+
+    /// Get the address of the last successful connection to a device
+    pub fn get_last_known_address(&self, device_id: String) -> Option<String> {
+        self.inner
+            .get_last_known_address(&device_id)
+            .map(|address| address.to_string())
+    }
+
+    /// Notify the manager that the system network changed
+    ///
+    /// Platforms without interface monitoring (or with their own reachability callbacks)
+    /// call this to re-announce mDNS, drop stale connections and re-dial paired devices.
+    pub fn on_network_changed(&self) {
+        self.runtime.block_on(async { self.inner.handle_network_change().await })
+    }
+
+    /// Get the TCP port the WiFi listener is bound to
+    ///
+    /// Returns None when the manager is not running or WiFi is disabled.
+    /// Show it to users so they can open it in their firewall.
+    pub fn get_listening_port(&self) -> Option<u16> {
+        self.runtime.block_on(async { self.inner.listening_port().await })
+    }
+
+    /// Write the current state of a paired device to storage
+    fn save_paired_device(&self, device_id: &str) {
+        let device = self
+            .inner
+            .get_paired_devices()
+            .into_iter()
+            .find(|d| d.id() == device_id);
+        self.runtime.block_on(async {
+            let storage = self.device_storage.read().await;
+            if let (Some(storage), Some(device)) = (storage.as_ref(), device) {
+                storage.save_device(FfiDeviceInfo::from(device));
+                tracing::debug!(device_id = %device_id, "Device saved to storage");
+            }
+        });
+    }
+
+    /// Register a paired device's advertisement key with the BLE controller
+    async fn register_ble_peer(&self, device: &DeviceInfo) {
+        let controller = self.ble_controller.read().await;
+        if let (Some(controller), Some(key)) = (controller.as_ref(), device.advertisement_key()) {
+            controller.add_paired_device(device.id(), key.clone()).await;
+        }
+    }
+
+    /// Forget a device's advertisement key in the BLE controller
+    async fn unregister_ble_peer(&self, device_id: &str) {
+        let controller = self.ble_controller.read().await;
+        if let Some(ref controller) = *controller {
+            controller.remove_paired_device(device_id).await;
+        }
+    }
+
+    /// Hand every paired device's advertisement key to the BLE controller
+    /// and advertise this device's rotating token
+    ///
+    /// Runs once both the controller and the local key are available.
+    async fn sync_ble_advertising(&self) {
+        for device in self.inner.get_paired_devices() {
+            self.register_ble_peer(&device).await;
+        }
+        let controller = self.ble_controller.read().await;
+        if let (Some(controller), Some(key)) = (controller.as_ref(), self.inner.advertisement_key()) {
+            controller.start_advertising(self.inner.device_id(), key).await;
+        }
+    }
+
+    /// Get the status of a device
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - ID of the device
+    ///
+    /// # Returns
+    ///
+    /// Device status if found, None otherwise.
+    pub fn get_device_status(&self, device_id: String) -> Option<DeviceStatus> {
+        self.inner.get_device_status(&device_id)
+    }
+
+    /// Get this device's unique ID
+    ///
+    /// # Returns
+    ///
+    /// The device ID used for mDNS advertising and message identification.
+    pub fn get_device_id(&self) -> String {
+        self.inner.device_id().to_string()
+    }
+
+    /// Try to connect to all discovered paired devices
+    ///
+    /// Scans for paired devices on the network and attempts to connect.
+    ///
+    /// # Returns
+    ///
+    /// Number of devices successfully connected.
+    pub fn try_connect_paired_devices(&self) -> u32 {
+        self.runtime.block_on(async { self.inner.try_connect_paired_devices().await }) as u32
+    }
+
+    // ============================================================
+    // BLE Methods
+    // ============================================================
+
+    /// Get shared secret for a paired device (private helper)
+    ///
+    /// Returns the ECDH shared secret for encryption if the device is paired.
+    async fn get_shared_secret(&self, device_id: &str) -> Option<Vec<u8>> {
+        let secret = self.device_secrets.read().unwrap().get(device_id).cloned();
+
+        if secret.is_some() {
+            tracing::debug!(
+                device_id = %device_id,
+                "Retrieved shared secret from cache"
+            );
+        } else {
+            tracing::debug!(
+                device_id = %device_id,
+                "No shared secret found in cache"
+            );
+        }
+
+        secret
     }
 
     /// Set the BLE hardware interface
@@ -884,7 +1672,8 @@ impl FfiNearClipManager {
             let config = FfiBleControllerConfig::default();
             let callback = Arc::new(BleControllerCallbackBridge {
                 ffi_callback: self.callback.clone(),
-                discovered_devices: Arc::clone(&self.discovered_devices),
+                nearby_devices: Arc::clone(&self.nearby_devices),
+                manager: Arc::clone(&self.inner),
             });
 
             let controller = Arc::new(BleController::new(
@@ -897,6 +1686,11 @@ impl FfiNearClipManager {
                     reconnect_base_delay_ms: config.reconnect_base_delay_ms,
                     health_check_interval_ms: config.health_check_interval_ms,
                     connection_timeout_ms: config.connection_timeout_ms,
+                    connection_priority: config.connection_priority,
+                    preferred_phy: config.preferred_phy,
+                    data_length: config.data_length,
+                    advertisement_rotation_ms: config.advertisement_rotation_ms,
+                    discover_unpaired: config.discover_unpaired,
                 },
                 callback,
             ));
@@ -907,6 +1701,9 @@ impl FfiNearClipManager {
             // Store controller
             let mut ble_controller = self.ble_controller.write().await;
             *ble_controller = Some(controller);
+            drop(ble_controller);
+
+            self.sync_ble_advertising().await;
         });
         tracing::info!("BLE hardware interface set and controller initialized");
     }
@@ -915,6 +1712,9 @@ impl FfiNearClipManager {
     ///
     /// Platform clients call this to provide persistent storage (Keychain/SharedPreferences).
     /// Must be called before start() to load existing paired devices.
+    ///
+    /// Also loads this device's BLE advertisement key, generating and saving
+    /// one on first launch. Peers learn it during pairing.
     pub fn set_device_storage(&self, storage: Box<dyn FfiDeviceStorage>) {
         let storage: Arc<dyn FfiDeviceStorage> = storage.into();
         self.runtime.block_on(async {
@@ -923,13 +1723,47 @@ impl FfiNearClipManager {
             *device_storage = Some(storage.clone());
             drop(device_storage);
 
+            let key = match storage.load_advertisement_key().as_deref().map(AdvertisementKey::from_slice) {
+                Some(Some(key)) => key,
+                stored => {
+                    if stored.is_some() {
+                        tracing::warn!("Stored advertisement key is invalid, generating a new one");
+                    }
+                    let key = AdvertisementKey::generate();
+                    storage.save_advertisement_key(key.as_bytes().to_vec());
+                    tracing::info!("Generated BLE advertisement key");
+                    key
+                }
+            };
+            self.inner.set_advertisement_key(key);
+
+            let keypair = match storage.load_identity_key().map(|key| nearclip_crypto::EcdhKeyPair::from_private_key_bytes(&key)) {
+                Some(Ok(keypair)) => keypair,
+                stored => {
+                    if stored.is_some() {
+                        tracing::warn!("Stored identity key is invalid, generating a new one");
+                    }
+                    let keypair = nearclip_crypto::EcdhKeyPair::generate();
+                    storage.save_identity_key(keypair.private_key_bytes());
+                    tracing::info!("Generated identity key");
+                    keypair
+                }
+            };
+            *self.local_keypair.write().unwrap() = keypair.clone();
+            self.inner.set_relay_identity(keypair, secret_lookup(&self.device_secrets));
+
             // Load existing paired devices from storage
             let devices = storage.load_all_devices();
             tracing::info!(count = devices.len(), "Loading paired devices from storage");
 
             for device in devices {
+                if let Some(secret) = storage.load_shared_secret(device.id.clone()) {
+                    self.device_secrets.write().unwrap().insert(device.id.clone(), secret);
+                }
                 self.inner.add_paired_device(device.into());
             }
+
+            self.sync_ble_advertising().await;
         });
         tracing::info!("Device storage interface set and devices loaded");
     }
@@ -1012,6 +1846,8 @@ impl FfiNearClipManager {
             Ok(true) => {
                 // Step 3: Connection succeeded, save to persistent storage
                 self.runtime.block_on(async {
+                    self.register_ble_peer(&device.clone().into()).await;
+
                     let storage = self.device_storage.read().await;
                     if let Some(ref storage) = *storage {
                         storage.save_device(device);
@@ -1114,6 +1950,7 @@ impl FfiNearClipManager {
                         self.callback.clone(),
                         device_id.clone(),
                         Some(self.ble_controller.clone()),
+                        Arc::clone(&self.inner),
                     );
 
                     let mut transports = self.ble_transports.write().await;
@@ -1133,45 +1970,149 @@ impl FfiNearClipManager {
 
     /// Called by platform when a BLE ACK notification is received
     ///
-    /// Platform clients call this when they receive an ACK notification from
-    /// the DATA_ACK characteristic. This signals that the remote device has
-    /// received a complete message.
+    /// Platform clients call this with every notification from the DATA_ACK
+    /// characteristic. A 2-byte value signals that the remote device has
+    /// received a complete message; a longer value is a chunk report listing
+    /// the chunks it is missing.
     ///
     /// # Arguments
     ///
     /// * `device_id` - The device ID that sent the ACK
-    /// * `data` - Raw bytes containing the message_id (2 bytes, little-endian)
+    /// * `data` - Raw bytes: a message_id (2 bytes, little-endian) or a chunk report
     pub fn on_ble_ack_received(&self, device_id: String, data: Vec<u8>) {
-        if data.len() < 2 {
-            tracing::warn!(
-                device_id = %device_id,
-                data_len = data.len(),
-                "Received ACK with invalid length (expected 2 bytes)"
-            );
-            return;
-        }
-
-        let message_id = u16::from_le_bytes([data[0], data[1]]);
         tracing::debug!(
             device_id = %device_id,
-            message_id,
+            data_len = data.len(),
             "on_ble_ack_received"
         );
 
         self.runtime.block_on(async {
             let transports = self.ble_transports.read().await;
             if let Some(transport) = transports.get(&device_id) {
-                transport.on_ack_received(message_id).await;
+                transport.on_ack_data_received(&data).await;
             } else {
                 tracing::debug!(
                     device_id = %device_id,
-                    message_id,
                     "Received ACK but no transport found for device"
                 );
             }
         });
     }
 
+    /// Called by platform when a BLE L2CAP channel opens or closes
+    ///
+    /// Platform clients call this when a channel opened by `open_l2cap_channel`
+    /// (or by the peer) becomes ready, and when it closes. While a channel is
+    /// open, messages to the device are written to it instead of GATT.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - The device ID
+    /// * `open` - Whether the channel is now open
+    pub fn on_ble_l2cap_state_changed(&self, device_id: String, open: bool) {
+        tracing::debug!(device_id = %device_id, open = open, "on_ble_l2cap_state_changed");
+
+        self.runtime.block_on(async {
+            let transports = self.ble_transports.read().await;
+            if let Some(transport) = transports.get(&device_id) {
+                transport.on_l2cap_state_changed(open);
+            } else {
+                tracing::debug!(
+                    device_id = %device_id,
+                    "L2CAP state changed but no transport found for device"
+                );
+            }
+        });
+    }
+
+    /// Called by platform when bytes are read from a BLE L2CAP channel
+    ///
+    /// The bytes may hold any part of the frame stream; the transport
+    /// reassembles frames across calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - The device ID that sent the data
+    /// * `data` - Raw bytes read from the channel
+    pub fn on_ble_l2cap_data_received(&self, device_id: String, data: Vec<u8>) {
+        self.runtime.block_on(async {
+            let transports = self.ble_transports.read().await;
+            if let Some(transport) = transports.get(&device_id) {
+                transport.on_l2cap_data_received(&data).await;
+            } else {
+                tracing::warn!(
+                    device_id = %device_id,
+                    "Received L2CAP data but no transport found for device"
+                );
+            }
+        });
+    }
+
+    /// Called by platform when the link parameters of a BLE connection change
+    ///
+    /// Platform clients call this after the connection interval, PHY, data
+    /// length or MTU is (re)negotiated, including in response to the
+    /// `request_*` calls on `FfiBleHardware`. Chunks to the device are sized
+    /// from the reported values.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - The device ID
+    /// * `parameters` - The negotiated values
+    pub fn on_ble_link_parameters_changed(&self, device_id: String, parameters: FfiBleLinkParameters) {
+        tracing::debug!(device_id = %device_id, ?parameters, "on_ble_link_parameters_changed");
+        let link: LinkParameters = parameters.into();
+
+        self.runtime.block_on(async {
+            if let Some(ref sender) = *self.ble_hardware_sender.read().await {
+                sender.set_link_parameters(&device_id, Some(link));
+            }
+
+            let controller = self.ble_controller.read().await;
+            if let Some(ref controller) = *controller {
+                let peripheral_uuid = controller
+                    .get_peripheral_uuid(&device_id)
+                    .await
+                    .unwrap_or_else(|| device_id.clone());
+                controller.handle_link_parameters_changed(&peripheral_uuid, link).await;
+            }
+        });
+    }
+
+    /// Called by platform with the RSSI of a BLE connection
+    ///
+    /// Answers `FfiBleHardware::read_rssi`. The reading updates proximity the
+    /// same way as a scan sighting.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - The device ID
+    /// * `rssi` - Signal strength in dBm
+    pub fn on_ble_connection_rssi(&self, device_id: String, rssi: i16) {
+        tracing::trace!(device_id = %device_id, rssi, "on_ble_connection_rssi");
+        let _guard = self.runtime.enter();
+        self.inner.handle_rssi(&device_id, rssi);
+    }
+
+    /// Get BLE link statistics for a connected device
+    ///
+    /// Returns the negotiated link parameters, the chunk size and throughput
+    /// they allow, and the throughput measured on acknowledged messages.
+    /// Returns `None` if there is no BLE transport for the device.
+    pub fn get_ble_link_stats(&self, device_id: String) -> Option<FfiBleLinkStats> {
+        self.runtime.block_on(async {
+            let transports = self.ble_transports.read().await;
+            let transport = transports.get(&device_id)?;
+            let link = transport.link_parameters();
+            Some(FfiBleLinkStats {
+                parameters: link.into(),
+                chunk_size: link.chunk_payload_size() as u32,
+                estimated_throughput: link.estimated_throughput(),
+                effective_throughput: transport.effective_throughput(),
+            })
+        })
+    }
+
     /// Called by platform when BLE connection state changes
     ///
     /// Platform clients call this when a BLE connection is established or lost.
@@ -1252,6 +2193,25 @@ impl FfiNearClipManager {
                             if !error.is_empty() {
                                 tracing::warn!(device_id = %device_id, char_uuid = %ack_char, error = %error, "Failed to subscribe to DATA_ACK");
                             }
+
+                            // Use the v2 chunk header if the peripheral supports it
+                            let chunk_version = transport.negotiate_chunk_version();
+                            tracing::debug!(device_id = %device_id, ?chunk_version, "BLE chunk header version");
+
+                            // Multiplex frames only if the peripheral understands them
+                            let framing = transport.negotiate_framing();
+                            tracing::debug!(device_id = %device_id, ?framing, "BLE framing");
+
+                            // Move bulk data to L2CAP if the peripheral supports it
+                            if let Err(e) = transport.open_l2cap() {
+                                tracing::debug!(device_id = %device_id, error = %e, "Using GATT for BLE data");
+                            }
+
+                            // Keep proximity current while the peripheral no longer advertises
+                            let rssi_task = spawn_connection_rssi_task(hw.clone(), device_id.clone(), peripheral_uuid.clone());
+                            if let Some(old) = self.ble_rssi_tasks.write().await.insert(device_id.clone(), rssi_task) {
+                                old.abort();
+                            }
                         } else {
                             // We are Peripheral - don't subscribe, the Central will subscribe to us
                             tracing::info!(device_id = %device_id, "Peripheral mode detected, skipping subscription (Central will subscribe to us)");
@@ -1266,6 +2226,7 @@ impl FfiNearClipManager {
                         self.callback.clone(),
                         device_id.clone(),
                         Some(self.ble_controller.clone()),
+                        Arc::clone(&self.inner),
                     );
 
                     let mut transports = self.ble_transports.write().await;
@@ -1306,11 +2267,14 @@ impl FfiNearClipManager {
                     let my_device_id = self.inner.device_id().to_string();
                     let my_device_name = self.inner.config().device_name().to_string();
 
-                    let pairing_payload = PairingPayload::new(
+                    let mut pairing_payload = PairingPayload::new(
                         my_device_id.clone(),
                         my_device_name,
                         my_platform,
                     );
+                    if let Some(key) = self.inner.advertisement_key() {
+                        pairing_payload = pairing_payload.with_advertisement_key(key.as_bytes());
+                    }
 
                     if let Ok(payload_bytes) = pairing_payload.serialize() {
                         let pairing_msg = Message::pairing_request(payload_bytes, my_device_id);
@@ -1343,17 +2307,23 @@ impl FfiNearClipManager {
                     task.abort();
                     tracing::debug!(device_id = %device_id, "BLE receive task aborted");
                 }
+                if let Some(task) = self.ble_rssi_tasks.write().await.remove(&device_id) {
+                    task.abort();
+                }
 
                 // Remove transport
                 let mut transports = self.ble_transports.write().await;
                 transports.remove(&device_id);
                 tracing::info!(device_id = %device_id, "BLE transport removed");
 
-                // Remove from core manager's TransportManager
-                self.inner.remove_ble_transport(&device_id).await;
+                // Link parameters are renegotiated on the next connection
+                if let Some(ref sender) = *self.ble_hardware_sender.read().await {
+                    sender.set_link_parameters(&device_id, None);
+                }
 
-                // Notify callback
-                self.callback.on_device_disconnected(device_id);
+                // Close the core session; it notifies `on_device_disconnected`
+                // once the device has no other active connection
+                self.inner.remove_ble_transport(&device_id).await;
             }
         });
     }
@@ -1369,7 +2339,7 @@ impl FfiNearClipManager {
     /// * `peripheral_uuid` - The platform-specific peripheral identifier (e.g., MAC address)
     /// * `device_id` - The NearClip device ID read from the GATT characteristic
     /// * `public_key_hash` - The public key hash (optional, can be empty string)
-    /// * `rssi` - Signal strength
+    /// * `rssi` - Signal strength, recorded as BLE channel quality for paired devices only
     pub fn on_ble_device_discovered(
         &self,
         peripheral_uuid: String,
@@ -1384,6 +2354,15 @@ impl FfiNearClipManager {
             "on_ble_device_discovered"
         );
 
+        // Feed the signal strength into that device's channel quality
+        // metrics; advertisements from unpaired devices are not ours to score
+        if self.inner.get_device_status(&device_id).is_some() {
+            let rssi_dbm = rssi.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+            self.inner
+                .channel_monitor()
+                .record_device_rssi(&device_id, Channel::Ble, rssi_dbm);
+        }
+
         self.runtime.block_on(async {
             let controller = self.ble_controller.read().await;
             if let Some(ref controller) = *controller {
@@ -1399,6 +2378,34 @@ impl FfiNearClipManager {
         });
     }
 
+    /// Called by platform with the NearClip service data of a scanned advertisement
+    ///
+    /// Lets the BLE controller recognize paired devices from their rotating
+    /// identity token without connecting. Advertisements that do not resolve
+    /// to a paired device are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `peripheral_uuid` - The platform-specific peripheral identifier
+    /// * `service_data` - Service data advertised for the NearClip service UUID
+    /// * `rssi` - Signal strength
+    pub fn on_ble_advertisement_received(&self, peripheral_uuid: String, service_data: Vec<u8>, rssi: i32) {
+        tracing::debug!(
+            peripheral_uuid = %peripheral_uuid,
+            rssi = rssi,
+            "on_ble_advertisement_received"
+        );
+
+        self.runtime.block_on(async {
+            let controller = self.ble_controller.read().await;
+            if let Some(ref controller) = *controller {
+                controller
+                    .handle_advertisement(&peripheral_uuid, &service_data, rssi)
+                    .await;
+            }
+        });
+    }
+
     // ============================================================
     // History Management Methods
     // ============================================================
@@ -1569,6 +2576,52 @@ impl FfiNearClipManager {
         Ok(count as u64)
     }
 
+    // ============================================================
+    // Offline Outbox Methods
+    // ============================================================
+
+    /// Enable the offline outbox
+    ///
+    /// Clips synced while a paired device is offline are stored here and
+    /// sent in order when the device reconnects. The database may be the
+    /// same file as the history database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db_path` - Path to SQLite database file
+    pub fn init_outbox(&self, db_path: String) -> Result<(), NearClipError> {
+        self.inner.init_outbox(std::path::PathBuf::from(db_path))
+    }
+
+    /// Remember the listening port across restarts
+    ///
+    /// The next `start` binds the last used port again when it is free,
+    /// so peers' cached addresses and firewall rules stay valid. Call
+    /// before `start`. The database may be the same file as the history
+    /// database.
+    ///
+    /// # Arguments
+    ///
+    /// * `db_path` - Path to SQLite database file
+    pub fn init_port_store(&self, db_path: String) -> Result<(), NearClipError> {
+        self.inner.init_port_store(std::path::PathBuf::from(db_path))
+    }
+
+    /// Get the number of clips queued for a device
+    ///
+    /// # Errors
+    ///
+    /// Returns error if the outbox is not initialized or database operation fails
+    pub fn get_outbox_count(&self, device_id: String) -> Result<u64, NearClipError> {
+        let outbox = self
+            .inner
+            .outbox()
+            .ok_or_else(|| NearClipError::NotInitialized("Outbox not initialized".to_string()))?;
+
+        let count = outbox.pending_count(&device_id)?;
+        Ok(count as u64)
+    }
+
     // ============================================================
     // QR Code Pairing Methods
     // ============================================================
@@ -1600,13 +2653,16 @@ impl FfiNearClipManager {
         tracing::info!("Generating QR code for pairing");
 
         // Use persistent local keypair
-        let public_key_bytes = self.local_keypair.public_key_bytes();
+        let public_key_bytes = self.local_keypair.read().unwrap().public_key_bytes();
 
         // Get device ID from manager
         let device_id = self.inner.device_id().to_string();
 
         // Create pairing data
-        let pairing_data = PairingData::new(device_id, &public_key_bytes);
+        let mut pairing_data = PairingData::new(device_id, &public_key_bytes);
+        if let Some(key) = self.inner.advertisement_key() {
+            pairing_data = pairing_data.with_advertisement_key(key.as_bytes());
+        }
 
         // Serialize to JSON
         let json = pairing_data.to_json()
@@ -1669,7 +2725,7 @@ impl FfiNearClipManager {
             .map_err(|e| NearClipError::Crypto(format!("Failed to decode public key: {}", e)))?;
 
         // Compute shared secret using ECDH
-        let shared_secret = self.local_keypair.compute_shared_secret(&peer_public_key)
+        let shared_secret = self.local_keypair.read().unwrap().compute_shared_secret(&peer_public_key)
             .map_err(|e| NearClipError::Crypto(format!("Failed to compute shared secret: {}", e)))?;
 
         tracing::info!(
@@ -1679,14 +2735,14 @@ impl FfiNearClipManager {
         );
 
         // Store shared secret in cache for encryption
-        self.runtime.block_on(async {
-            let mut secrets = self.device_secrets.write().await;
-            secrets.insert(pairing_data.device_id.clone(), shared_secret);
-            tracing::debug!(
-                device_id = %pairing_data.device_id,
-                "Stored shared secret in cache"
-            );
-        });
+        self.device_secrets
+            .write()
+            .unwrap()
+            .insert(pairing_data.device_id.clone(), shared_secret.clone());
+        tracing::debug!(
+            device_id = %pairing_data.device_id,
+            "Stored shared secret in cache"
+        );
 
         // Create device info from pairing data
         // Note: We don't know the actual platform yet, will be determined during connection
@@ -1695,18 +2751,36 @@ impl FfiNearClipManager {
             name: format!("Device {}", truncate_utf8(&pairing_data.device_id, 8)),
             platform: DevicePlatform::Unknown,
             status: DeviceStatus::Disconnected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: pairing_data
+                .connection_info
+                .as_ref()
+                .and_then(PeerAddress::from_connection_info)
+                .map(|address| vec![address.to_string()])
+                .unwrap_or_default(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            // Length already checked by validate()
+            advertisement_key: pairing_data.advertisement_key_bytes().ok().flatten(),
         };
 
         // Use pair_device to add and connect
         let paired = self.pair_device(device_info.clone())?;
 
         if paired {
+            // Persist the secret so relay and BLE encryption keep working after a restart
+            self.runtime.block_on(async {
+                if let Some(ref storage) = *self.device_storage.read().await {
+                    storage.save_shared_secret(device_info.id.clone(), shared_secret);
+                }
+            });
             tracing::info!(
                 device_id = %device_info.id,
                 "QR code pairing successful"
             );
             Ok(device_info)
         } else {
+            self.device_secrets.write().unwrap().remove(&device_info.id);
             tracing::warn!(
                 device_id = %device_info.id,
                 "QR code pairing failed - connection unsuccessful"
@@ -1717,6 +2791,196 @@ impl FfiNearClipManager {
             )))
         }
     }
+
+    /// Pair with a connected BLE peripheral
+    ///
+    /// For devices without a shared network. Runs the pairing handshake over
+    /// the pairing characteristic as the initiator (central), then saves the
+    /// device and its shared secret like `pair_with_qr_code`. The platform
+    /// must forward pairing characteristic notifications to
+    /// `on_ble_pairing_data_received` while this call blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `peripheral_uuid` - The connected peripheral running `accept_ble_pairing`
+    ///
+    /// # Returns
+    ///
+    /// Device info on successful pairing
+    ///
+    /// # Errors
+    ///
+    /// Returns error if BLE hardware is not set, the peer rejects the request,
+    /// the handshake times out or a signature does not verify.
+    pub fn pair_with_ble(&self, peripheral_uuid: String) -> Result<FfiDeviceInfo, NearClipError> {
+        self.run_ble_pairing(&peripheral_uuid, BlePairingRole::Initiator)
+    }
+
+    /// Accept a BLE pairing request from a connected central
+    ///
+    /// The responder side of `pair_with_ble`: waits for the request written to
+    /// the pairing characteristic, answers it and saves the device like
+    /// `pair_with_qr_code`. The platform must forward pairing characteristic
+    /// writes to `on_ble_pairing_data_received` while this call blocks.
+    ///
+    /// # Arguments
+    ///
+    /// * `peripheral_uuid` - The connected central that will send the request
+    ///
+    /// # Returns
+    ///
+    /// Device info on successful pairing
+    ///
+    /// # Errors
+    ///
+    /// Returns error if BLE hardware is not set, no request arrives in time or
+    /// a signature does not verify.
+    pub fn accept_ble_pairing(&self, peripheral_uuid: String) -> Result<FfiDeviceInfo, NearClipError> {
+        self.run_ble_pairing(&peripheral_uuid, BlePairingRole::Responder)
+    }
+
+    /// Called by platform when data is written to or notified on the pairing characteristic
+    ///
+    /// Data from devices other than the one in `pair_with_ble` /
+    /// `accept_ble_pairing` is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `peripheral_uuid` - The peer that sent the data
+    /// * `data` - Raw bytes of one pairing chunk
+    pub fn on_ble_pairing_data_received(&self, peripheral_uuid: String, data: Vec<u8>) {
+        let channel = self.ble_pairing.read().unwrap().clone();
+        match channel {
+            Some(channel) if channel.peripheral_id() == peripheral_uuid => {
+                if let Err(e) = channel.handle_data(&data) {
+                    tracing::warn!(peripheral_uuid = %peripheral_uuid, error = %e, "Invalid BLE pairing data");
+                }
+            }
+            _ => tracing::debug!(peripheral_uuid = %peripheral_uuid, "No BLE pairing in progress, dropping data"),
+        }
+    }
+
+    /// Run the pairing handshake over BLE and save the paired device (private helper)
+    fn run_ble_pairing(&self, peripheral_uuid: &str, role: BlePairingRole) -> Result<FfiDeviceInfo, NearClipError> {
+        tracing::info!(peripheral_uuid = %peripheral_uuid, role = ?role, "Starting BLE pairing");
+
+        let hardware = self.runtime.block_on(async { self.ble_hardware.read().await.clone() })
+            .ok_or_else(|| NearClipError::Bluetooth("BLE hardware not set".to_string()))?;
+        let channel = Arc::new(BlePairingChannel::new(
+            Arc::new(BleHardwareBridge::new(hardware)),
+            peripheral_uuid,
+            role,
+        ));
+        // The initiator is the central; the responder answers through notifications
+        if role == BlePairingRole::Initiator {
+            channel.subscribe().map_err(|e| NearClipError::Bluetooth(e.to_string()))?;
+        }
+        *self.ble_pairing.write().unwrap() = Some(channel.clone());
+
+        let result = self.runtime.block_on(async {
+            // PairingManager records the device in its own store; a throwaway
+            // in-memory one used only for this handshake suffices because the
+            // device is persisted through FfiDeviceStorage below
+            #[allow(clippy::arc_with_non_send_sync)]
+            let device_manager = Arc::new(
+                DeviceManager::new(PathBuf::from(":memory:"))
+                    .await
+                    .map_err(|e| NearClipError::Io(e.to_string()))?,
+            );
+            let keypair = self.local_keypair.read().unwrap().clone();
+            let mut pairing = PairingManager::new(
+                device_manager,
+                channel,
+                self.inner.device_id().to_string(),
+                self.inner.config().device_name().to_string(),
+                local_pairing_platform(),
+                keypair,
+            );
+            if let Some(key) = self.inner.advertisement_key() {
+                pairing = pairing.with_advertisement_key(key.as_bytes().to_vec());
+            }
+
+            match role {
+                BlePairingRole::Initiator => pairing.initiate_pairing(peripheral_uuid).await,
+                BlePairingRole::Responder => pairing.accept_incoming_request().await,
+            }
+            .map_err(pairing_error)
+        });
+        *self.ble_pairing.write().unwrap() = None;
+
+        let paired = result?;
+        let device_info = self.save_ble_paired_device(paired);
+        tracing::info!(device_id = %device_info.id, "BLE pairing successful");
+        Ok(device_info)
+    }
+
+    /// Save a device paired over BLE the same way as a QR code pairing (private helper)
+    fn save_ble_paired_device(&self, paired: PairedDevice) -> FfiDeviceInfo {
+        let device_info = FfiDeviceInfo {
+            id: paired.device_id.clone(),
+            name: paired.device_name,
+            platform: paired_device_platform(&paired.platform),
+            status: DeviceStatus::Disconnected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: paired.advertisement_key,
+        };
+
+        self.device_secrets
+            .write()
+            .unwrap()
+            .insert(paired.device_id.clone(), paired.shared_secret.clone());
+        self.inner.add_paired_device(device_info.clone().into());
+
+        self.runtime.block_on(async {
+            self.register_ble_peer(&device_info.clone().into()).await;
+
+            if let Some(ref storage) = *self.device_storage.read().await {
+                storage.save_device(device_info.clone());
+                storage.save_shared_secret(paired.device_id, paired.shared_secret);
+                tracing::info!(device_id = %device_info.id, "BLE pairing: Device saved to storage");
+            } else {
+                tracing::warn!(device_id = %device_info.id, "BLE pairing: No storage interface, device not persisted");
+            }
+        });
+        device_info
+    }
+}
+
+/// This device's platform as sent in pairing messages
+fn local_pairing_platform() -> nearclip_protocol::pairing::DevicePlatform {
+    if cfg!(target_os = "android") {
+        nearclip_protocol::pairing::DevicePlatform::Android
+    } else if cfg!(target_os = "ios") {
+        nearclip_protocol::pairing::DevicePlatform::Ios
+    } else if cfg!(target_os = "windows") {
+        nearclip_protocol::pairing::DevicePlatform::Windows
+    } else if cfg!(target_os = "linux") {
+        nearclip_protocol::pairing::DevicePlatform::Linux
+    } else {
+        nearclip_protocol::pairing::DevicePlatform::MacOS
+    }
+}
+
+/// Convert the platform of a device paired over BLE
+fn paired_device_platform(platform: &nearclip_device::DevicePlatform) -> DevicePlatform {
+    match platform {
+        nearclip_device::DevicePlatform::MacOS => DevicePlatform::MacOS,
+        nearclip_device::DevicePlatform::Android => DevicePlatform::Android,
+        _ => DevicePlatform::Unknown,
+    }
+}
+
+/// Convert a BLE pairing failure
+fn pairing_error(error: PairingError) -> NearClipError {
+    match error {
+        PairingError::InvalidSignature | PairingError::InvalidData(_) => {
+            NearClipError::Crypto(format!("BLE pairing failed: {}", error))
+        }
+        _ => NearClipError::Bluetooth(format!("BLE pairing failed: {}", error)),
+    }
 }
 
 // ============================================================
@@ -1726,6 +2990,7 @@ impl FfiNearClipManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nearclip_ble::{advertisement_epoch, AdvertisementResolver};
     #[allow(unused_imports)]
     use std::sync::Mutex;
 
@@ -1769,6 +3034,14 @@ mod tests {
             self.errors.lock().unwrap().push(error_message);
         }
 
+        fn on_sync_delivered(&self, _device_id: String, _message_id: u64) {
+            // Not tracked in tests
+        }
+
+        fn on_sync_failed(&self, _device_id: String, _message_id: u64, error_message: String) {
+            self.errors.lock().unwrap().push(error_message);
+        }
+
         fn on_pairing_rejected(&self, device_id: String, _reason: String) {
             // Treat rejection as a form of disconnect for test purposes
             self.disconnected.lock().unwrap().push(device_id);
@@ -1781,6 +3054,10 @@ mod tests {
         fn on_device_lost(&self, _peripheral_uuid: String) {
             // Not tracked in tests
         }
+
+        fn on_proximity_entered(&self, _device_id: String, _rssi: i16) {}
+
+        fn on_proximity_left(&self, _device_id: String) {}
     }
 
     #[test]
@@ -1799,6 +3076,11 @@ mod tests {
             name: "Test Device".to_string(),
             platform: DevicePlatform::MacOS,
             status: DeviceStatus::Connected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
         };
 
         let core: DeviceInfo = ffi.clone().into();
@@ -1823,6 +3105,20 @@ mod tests {
             connection_timeout_secs: 60,
             heartbeat_interval_secs: 15,
             max_retries: 5,
+            allowed_interfaces: vec!["en*".to_string()],
+            excluded_interfaces: vec!["en5".to_string()],
+            exclude_virtual_interfaces: true,
+            network_monitor: false,
+            port_range_start: 8765,
+            port_range_end: 8774,
+            quic_enabled: true,
+            sync_only_when_near: true,
+            auto_connect_when_near: true,
+            near_rssi: -55,
+            far_rssi: -70,
+            proximity_timeout_secs: 10,
+            relay_address: Some("relay.example.com:7890".to_string()),
+            relay_certificate: Some(vec![1, 2, 3]),
         };
 
         let core: NearClipConfig = ffi.into();
@@ -1834,6 +3130,18 @@ mod tests {
         assert_eq!(core.connection_timeout(), Duration::from_secs(60));
         assert_eq!(core.heartbeat_interval(), Duration::from_secs(15));
         assert_eq!(core.max_retries(), 5);
+        assert!(core.interface_filter().allows_name("en0"));
+        assert!(!core.interface_filter().allows_name("en5"));
+        assert!(!core.interface_filter().allows_name("utun3"));
+        assert!(!core.network_monitor());
+        assert_eq!(core.port_range(), 8765..=8774);
+        assert!(core.quic_enabled());
+        let proximity = core.proximity_policy();
+        assert!(proximity.sync_only_when_near);
+        assert!(proximity.auto_connect_when_near);
+        assert_eq!(proximity.thresholds, ProximityThresholds::new(-55, -70));
+        assert_eq!(proximity.timeout, Duration::from_secs(10));
+        assert_eq!(core.relay(), Some(&RelayConfig::new("relay.example.com:7890", vec![1, 2, 3])));
     }
 
     #[test]
@@ -1848,6 +3156,23 @@ mod tests {
         assert_eq!(core.device_id(), None); // 空字符串转换为 None
     }
 
+    #[test]
+    fn test_ffi_ble_link_parameters_conversion() {
+        let link = LinkParameters::new()
+            .with_mtu(247)
+            .with_connection_interval(Duration::from_micros(7_500))
+            .with_phy(BlePhy::Le2M)
+            .with_data_length(251);
+
+        let ffi: FfiBleLinkParameters = link.into();
+        assert_eq!(ffi.mtu, 247);
+        assert_eq!(ffi.connection_interval_us, 7_500);
+        assert_eq!(ffi.phy, FfiBlePhy::Le2M);
+
+        let back: LinkParameters = ffi.into();
+        assert_eq!(back, link);
+    }
+
     #[test]
     fn test_log_level_conversion() {
         // Test all log levels
@@ -1898,6 +3223,237 @@ mod tests {
         assert!(!manager.is_running());
     }
 
+    /// Device storage whose clones share state, so tests can inspect it after handing it over
+    #[derive(Default, Clone)]
+    struct TestStorage {
+        saved: Arc<Mutex<Vec<FfiDeviceInfo>>>,
+        devices: Vec<FfiDeviceInfo>,
+        advertisement_key: Arc<Mutex<Option<Vec<u8>>>>,
+        identity_key: Arc<Mutex<Option<Vec<u8>>>>,
+        secrets: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    }
+
+    impl FfiDeviceStorage for TestStorage {
+        fn save_device(&self, device: FfiDeviceInfo) {
+            self.saved.lock().unwrap().push(device);
+        }
+
+        fn remove_device(&self, _device_id: String) {}
+
+        fn load_all_devices(&self) -> Vec<FfiDeviceInfo> {
+            self.devices.clone()
+        }
+
+        fn load_advertisement_key(&self) -> Option<Vec<u8>> {
+            self.advertisement_key.lock().unwrap().clone()
+        }
+
+        fn save_advertisement_key(&self, key: Vec<u8>) {
+            *self.advertisement_key.lock().unwrap() = Some(key);
+        }
+
+        fn load_identity_key(&self) -> Option<Vec<u8>> {
+            self.identity_key.lock().unwrap().clone()
+        }
+
+        fn save_identity_key(&self, key: Vec<u8>) {
+            *self.identity_key.lock().unwrap() = Some(key);
+        }
+
+        fn load_shared_secret(&self, device_id: String) -> Option<Vec<u8>> {
+            self.secrets.lock().unwrap().get(&device_id).cloned()
+        }
+
+        fn save_shared_secret(&self, device_id: String, secret: Vec<u8>) {
+            self.secrets.lock().unwrap().insert(device_id, secret);
+        }
+    }
+
+    /// BLE hardware that records advertisements, forwards pairing writes and ignores everything else
+    #[derive(Default, Clone)]
+    struct TestBleHardware {
+        advertised: Arc<Mutex<Vec<Vec<u8>>>>,
+        pairing_writes: Option<std::sync::mpsc::Sender<Vec<u8>>>,
+    }
+
+    impl FfiBleHardware for TestBleHardware {
+        fn start_scan(&self) {}
+        fn stop_scan(&self) {}
+        fn connect(&self, _peripheral_uuid: String) {}
+        fn disconnect(&self, _peripheral_uuid: String) {}
+        fn read_characteristic(&self, _peripheral_uuid: String, _char_uuid: String) -> Vec<u8> {
+            Vec::new()
+        }
+        fn write_characteristic(&self, _peripheral_uuid: String, char_uuid: String, data: Vec<u8>) -> String {
+            if let Some(ref tx) = self.pairing_writes {
+                if char_uuid == nearclip_ble::PAIRING_CHARACTERISTIC_UUID.to_string() {
+                    let _ = tx.send(data);
+                }
+            }
+            String::new()
+        }
+        fn subscribe_characteristic(&self, _peripheral_uuid: String, _char_uuid: String) -> String {
+            String::new()
+        }
+        fn start_advertising(&self, service_data: Vec<u8>) {
+            self.advertised.lock().unwrap().push(service_data);
+        }
+        fn stop_advertising(&self) {}
+        fn is_connected(&self, _peripheral_uuid: String) -> bool {
+            false
+        }
+        fn get_mtu(&self, _peripheral_uuid: String) -> u32 {
+            23
+        }
+        fn open_l2cap_channel(&self, _peripheral_uuid: String, _psm: u16) -> String {
+            "unsupported".to_string()
+        }
+        fn write_l2cap(&self, _peripheral_uuid: String, _data: Vec<u8>) -> String {
+            "unsupported".to_string()
+        }
+        fn close_l2cap_channel(&self, _peripheral_uuid: String) {}
+        fn request_connection_priority(&self, _peripheral_uuid: String, _priority: FfiBleConnectionPriority) -> String {
+            "unsupported".to_string()
+        }
+        fn request_phy(&self, _peripheral_uuid: String, _phy: FfiBlePhy) -> String {
+            "unsupported".to_string()
+        }
+        fn request_data_length(&self, _peripheral_uuid: String, _octets: u16) -> String {
+            "unsupported".to_string()
+        }
+        fn read_rssi(&self, _peripheral_uuid: String) -> String {
+            "unsupported".to_string()
+        }
+    }
+
+    #[test]
+    fn test_callback_bridge_persists_updated_device() {
+        let storage = Arc::new(TestStorage::default());
+        let slot: DeviceStorageSlot = Arc::new(RwLock::new(None));
+        let bridge = CallbackBridge::new(Arc::new(TestCallback::new()), slot.clone(), Arc::new(RwLock::new(None)));
+        let mut device = DeviceInfo::new("d1", "Device 1");
+        device.set_last_known_address(Some("192.168.1.20:8765".parse().unwrap()));
+
+        // No storage yet: nothing to save
+        bridge.on_device_updated(&device);
+
+        *slot.try_write().unwrap() = Some(storage.clone());
+        bridge.on_device_updated(&device);
+
+        let saved = storage.saved.lock().unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].id, "d1");
+        assert_eq!(saved[0].last_known_address.as_deref(), Some("192.168.1.20:8765"));
+    }
+
+    #[test]
+    fn test_ffi_advertisement_key_generated_once() {
+        let storage = TestStorage::default();
+
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        manager.set_device_storage(Box::new(storage.clone()));
+        let saved = storage.advertisement_key.lock().unwrap().clone().expect("key saved on first launch");
+        assert_eq!(manager.inner.advertisement_key().unwrap().as_bytes().as_slice(), saved.as_slice());
+
+        // The next launch reuses the stored key
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        manager.set_device_storage(Box::new(storage.clone()));
+        assert_eq!(manager.inner.advertisement_key().unwrap().as_bytes().as_slice(), saved.as_slice());
+    }
+
+    #[test]
+    fn test_ffi_identity_key_and_secrets_restored() {
+        let storage = TestStorage {
+            devices: vec![FfiDeviceInfo::from(DeviceInfo::new("d1", "Device 1"))],
+            ..Default::default()
+        };
+        storage.secrets.lock().unwrap().insert("d1".to_string(), vec![9u8; 32]);
+
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        manager.set_device_storage(Box::new(storage.clone()));
+        let saved = storage.identity_key.lock().unwrap().clone().expect("key saved on first launch");
+        assert_eq!(manager.local_keypair.read().unwrap().private_key_bytes(), saved);
+
+        // The relay looks up secrets of devices loaded from storage
+        let lookup = secret_lookup(&manager.device_secrets);
+        assert_eq!(lookup("d1"), Some(vec![9u8; 32]));
+        assert_eq!(lookup("d2"), None);
+
+        // The next launch keeps the same identity, so the relay still accepts it
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        manager.set_device_storage(Box::new(storage.clone()));
+        assert_eq!(manager.local_keypair.read().unwrap().private_key_bytes(), saved);
+    }
+
+    #[test]
+    fn test_ffi_ble_advertising_uses_exchanged_keys() {
+        let peer_key = AdvertisementKey::from_bytes([7u8; 32]);
+        let storage = TestStorage {
+            devices: vec![FfiDeviceInfo {
+                id: "peer".to_string(),
+                name: "Peer".to_string(),
+                platform: DevicePlatform::Android,
+                status: DeviceStatus::Disconnected,
+                channel_preference: ChannelPreference::Auto,
+                static_addresses: Vec::new(),
+                last_known_address: None,
+                proximity_thresholds: None,
+                advertisement_key: Some(peer_key.as_bytes().to_vec()),
+            }],
+            ..Default::default()
+        };
+        let hardware = TestBleHardware::default();
+
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        manager.set_device_storage(Box::new(storage));
+        manager.set_ble_hardware(Box::new(hardware.clone()));
+
+        // This device advertises a token the peer resolves with our key
+        let period = Duration::from_secs(nearclip_ble::DEFAULT_ADVERTISEMENT_ROTATION_SECS);
+        let local_key = manager.inner.advertisement_key().unwrap();
+        let mut resolver = AdvertisementResolver::new(period);
+        resolver.add_peer(manager.get_device_id(), local_key);
+        let advertised = hardware.advertised.lock().unwrap().last().cloned().expect("advertising started");
+        assert_eq!(resolver.resolve(&advertised), Some(manager.get_device_id()));
+
+        // The peer's token is recognized from the key stored at pairing
+        let service_data = peer_key.payload("peer", advertisement_epoch(period));
+        manager.on_ble_advertisement_received("uuid-peer".to_string(), service_data, -50);
+        let nearby = manager.get_nearby_devices();
+        assert_eq!(nearby.len(), 1);
+        assert_eq!(nearby[0].device_id, "peer");
+
+        // A stranger's token is ignored
+        let stranger = AdvertisementKey::from_bytes([9u8; 32]).payload("stranger", advertisement_epoch(period));
+        manager.on_ble_advertisement_received("uuid-stranger".to_string(), stranger, -40);
+        assert_eq!(manager.get_nearby_devices().len(), 1);
+    }
+
+    #[test]
+    fn test_ffi_ble_discovery_records_rssi_for_paired_devices_only() {
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        manager.add_paired_device(FfiDeviceInfo {
+            id: "paired".to_string(),
+            name: "Paired".to_string(),
+            platform: DevicePlatform::MacOS,
+            status: DeviceStatus::Disconnected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
+        });
+
+        manager.on_ble_device_discovered("uuid-1".to_string(), "paired".to_string(), String::new(), -60);
+        manager.on_ble_device_discovered("uuid-2".to_string(), "stranger".to_string(), String::new(), -40);
+
+        let monitor = manager.inner.channel_monitor();
+        assert_eq!(monitor.device_metrics("paired", Channel::Ble).rssi, Some(-60));
+        assert!(!monitor.device_metrics("stranger", Channel::Ble).has_samples());
+        assert!(!monitor.metrics(Channel::Ble).has_samples());
+        assert_eq!(monitor.devices(), vec!["paired".to_string()]);
+    }
+
     #[test]
     fn test_ffi_manager_device_management() {
         let config = FfiNearClipConfig::default();
@@ -1910,6 +3466,11 @@ mod tests {
             name: "Device 1".to_string(),
             platform: DevicePlatform::MacOS,
             status: DeviceStatus::Disconnected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
         };
         manager.add_paired_device(device);
 
@@ -1928,6 +3489,208 @@ mod tests {
         assert_eq!(devices.len(), 0);
     }
 
+    #[test]
+    fn test_ffi_nearby_device_conversion() {
+        let mut device = DiscoveredDevice::new(
+            "d1",
+            "hash",
+            DiscoveryChannel::WiFi {
+                ip: "fe80::1".to_string(),
+                port: 5000,
+            },
+            1000,
+        );
+        device.merge(DiscoveredDevice::new(
+            "d1",
+            "hash",
+            DiscoveryChannel::BLE {
+                peripheral_id: "AA:BB".to_string(),
+                rssi: -61,
+            },
+            2000,
+        ));
+
+        let nearby = FfiNearbyDevice::from(device);
+        assert_eq!(nearby.wifi_addresses, vec!["[fe80::1]:5000".to_string()]);
+        assert_eq!(nearby.ble_peripheral_uuid.as_deref(), Some("AA:BB"));
+        assert_eq!(nearby.rssi, Some(-61));
+        assert_eq!(nearby.last_seen_ms, 2000);
+        assert!(!nearby.paired);
+    }
+
+    #[test]
+    fn test_ffi_manager_nearby_devices() {
+        let config = FfiNearClipConfig::default();
+        let callback = Box::new(TestCallback::new());
+        let manager = FfiNearClipManager::new(config, callback).unwrap();
+        manager.add_paired_device(FfiDeviceInfo {
+            id: "d1".to_string(),
+            name: "Device 1".to_string(),
+            platform: DevicePlatform::MacOS,
+            status: DeviceStatus::Disconnected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
+        });
+
+        let ble = |device_id: &str, peripheral_id: &str, seen| {
+            DiscoveredDevice::new(
+                device_id,
+                "hash",
+                DiscoveryChannel::BLE {
+                    peripheral_id: peripheral_id.to_string(),
+                    rssi: -50,
+                },
+                seen,
+            )
+        };
+        manager.nearby_devices.observe(ble("d1", "AA:BB", 2000));
+        manager.nearby_devices.observe(ble("d2", "CC:DD", 1000));
+
+        // mDNS is not running, so only the BLE sightings are listed
+        let nearby = manager.get_nearby_devices();
+        assert_eq!(nearby.len(), 2);
+        assert_eq!(nearby[0].device_id, "d1");
+        assert_eq!(nearby[0].device_name, "Device 1");
+        assert!(nearby[0].paired);
+        assert!(!nearby[1].paired);
+        assert!(nearby[1].wifi_addresses.is_empty());
+    }
+
+    #[test]
+    fn test_ffi_manager_proximity_from_ble_discovery() {
+        let config = FfiNearClipConfig::default();
+        let callback = Box::new(TestCallback::new());
+        let manager = FfiNearClipManager::new(config, callback).unwrap();
+        manager.add_paired_device(FfiDeviceInfo {
+            id: "d1".to_string(),
+            name: "Device 1".to_string(),
+            platform: DevicePlatform::Android,
+            status: DeviceStatus::Disconnected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
+        });
+
+        let thresholds = ProximityThresholds::new(-50, -70);
+        manager
+            .set_device_proximity_thresholds("d1".to_string(), Some(thresholds))
+            .unwrap();
+        assert_eq!(manager.get_device_proximity_thresholds("d1".to_string()), Some(thresholds));
+        assert!(manager
+            .set_device_proximity_thresholds("d1".to_string(), Some(ProximityThresholds::new(-70, -50)))
+            .is_err());
+        assert_eq!(manager.get_device_proximity("d1".to_string()), ProximityState::Unknown);
+
+        let bridge = BleControllerCallbackBridge {
+            ffi_callback: manager.callback.clone(),
+            nearby_devices: Arc::clone(&manager.nearby_devices),
+            manager: Arc::clone(&manager.inner),
+        };
+        bridge.on_device_discovered(ControllerDiscoveredDevice {
+            peripheral_uuid: "AA:BB".to_string(),
+            device_id: "d1".to_string(),
+            public_key_hash: "hash".to_string(),
+            rssi: -45,
+            last_seen_ms: 1000,
+        });
+
+        assert_eq!(manager.get_device_proximity("d1".to_string()), ProximityState::Near);
+        assert_eq!(manager.get_device_rssi("d1".to_string()), Some(-45));
+        assert_eq!(manager.get_nearby_devices()[0].rssi, Some(-45));
+    }
+
+    #[test]
+    fn test_ffi_connection_rssi_updates_proximity() {
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        manager.add_paired_device(FfiDeviceInfo {
+            id: "d1".to_string(),
+            name: "Device 1".to_string(),
+            platform: DevicePlatform::Android,
+            status: DeviceStatus::Connected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
+        });
+
+        manager.on_ble_connection_rssi("d1".to_string(), -40);
+        assert_eq!(manager.get_device_proximity("d1".to_string()), ProximityState::Near);
+        assert_eq!(manager.get_device_rssi("d1".to_string()), Some(-40));
+    }
+
+    /// Manager with its own storage whose pairing writes arrive at `writes`
+    fn ble_pairing_peer(device_id: &str) -> (Arc<FfiNearClipManager>, TestStorage, std::sync::mpsc::Receiver<Vec<u8>>) {
+        let config = FfiNearClipConfig {
+            device_name: device_id.to_string(),
+            device_id: device_id.to_string(),
+            ..Default::default()
+        };
+        let manager = Arc::new(FfiNearClipManager::new(config, Box::new(TestCallback::new())).unwrap());
+        let storage = TestStorage::default();
+        manager.set_device_storage(Box::new(storage.clone()));
+        let (tx, writes) = std::sync::mpsc::channel();
+        manager.set_ble_hardware(Box::new(TestBleHardware {
+            pairing_writes: Some(tx),
+            ..Default::default()
+        }));
+        (manager, storage, writes)
+    }
+
+    #[test]
+    fn test_ffi_ble_pairing_saves_both_devices() {
+        let (mac, mac_storage, mac_writes) = ble_pairing_peer("mac");
+        let (phone, phone_storage, phone_writes) = ble_pairing_peer("phone");
+
+        // The air between the two devices
+        let to_phone = phone.clone();
+        std::thread::spawn(move || {
+            for data in mac_writes {
+                to_phone.on_ble_pairing_data_received("central-mac".to_string(), data);
+            }
+        });
+        let to_mac = mac.clone();
+        std::thread::spawn(move || {
+            for data in phone_writes {
+                to_mac.on_ble_pairing_data_received("peripheral-phone".to_string(), data);
+            }
+        });
+
+        let responder = phone.clone();
+        let accepted = std::thread::spawn(move || responder.accept_ble_pairing("central-mac".to_string()));
+        while phone.ble_pairing.read().unwrap().is_none() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let paired = mac.pair_with_ble("peripheral-phone".to_string()).unwrap();
+        let accepted = accepted.join().unwrap().unwrap();
+        assert_eq!(paired.id, "phone");
+        assert_eq!(accepted.id, "mac");
+
+        // Persisted like a QR code pairing, with the same secret on both sides
+        assert_eq!(mac_storage.saved.lock().unwrap()[0].id, "phone");
+        assert_eq!(phone_storage.saved.lock().unwrap()[0].id, "mac");
+        let mac_secret = mac_storage.secrets.lock().unwrap().get("phone").cloned().unwrap();
+        assert_eq!(phone_storage.secrets.lock().unwrap().get("mac"), Some(&mac_secret));
+        assert_eq!(mac.get_paired_devices().len(), 1);
+        assert!(paired.advertisement_key.is_some());
+    }
+
+    #[test]
+    fn test_ffi_ble_pairing_requires_hardware() {
+        let manager = FfiNearClipManager::new(FfiNearClipConfig::default(), Box::new(TestCallback::new())).unwrap();
+        let result = manager.pair_with_ble("peripheral".to_string());
+        assert!(matches!(result, Err(NearClipError::Bluetooth(_))));
+
+        // Data without a pairing in progress is dropped
+        manager.on_ble_pairing_data_received("peripheral".to_string(), vec![1, 2, 3]);
+    }
+
     #[test]
     fn test_flush_logs() {
         init_logging(LogLevel::Debug);
@@ -17,8 +17,11 @@ pub struct MockCallback {
     rejected_pairings: Arc<Mutex<Vec<(String, String)>>>,
     received_clipboard: Arc<Mutex<Vec<(Vec<u8>, String)>>>,
     sync_errors: Arc<Mutex<Vec<String>>>,
+    delivered: Arc<Mutex<Vec<(String, u64)>>>,
+    failed: Arc<Mutex<Vec<(String, u64, String)>>>,
     discovered_devices: Arc<Mutex<Vec<FfiDiscoveredDevice>>>,
     lost_devices: Arc<Mutex<Vec<String>>>,
+    proximity_events: Arc<Mutex<Vec<(String, bool)>>>,
 }
 
 impl MockCallback {
@@ -31,8 +34,11 @@ impl MockCallback {
             rejected_pairings: Arc::new(Mutex::new(Vec::new())),
             received_clipboard: Arc::new(Mutex::new(Vec::new())),
             sync_errors: Arc::new(Mutex::new(Vec::new())),
+            delivered: Arc::new(Mutex::new(Vec::new())),
+            failed: Arc::new(Mutex::new(Vec::new())),
             discovered_devices: Arc::new(Mutex::new(Vec::new())),
             lost_devices: Arc::new(Mutex::new(Vec::new())),
+            proximity_events: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
@@ -91,11 +97,26 @@ impl MockCallback {
         self.sync_errors.lock().unwrap().clone()
     }
 
+    /// Get all delivery confirmations
+    pub fn get_delivered(&self) -> Vec<(String, u64)> {
+        self.delivered.lock().unwrap().clone()
+    }
+
+    /// Get all delivery failures
+    pub fn get_failed(&self) -> Vec<(String, u64, String)> {
+        self.failed.lock().unwrap().clone()
+    }
+
     /// Get discovered devices
     pub fn get_discovered_devices(&self) -> Vec<FfiDiscoveredDevice> {
         self.discovered_devices.lock().unwrap().clone()
     }
 
+    /// Get proximity changes as (device_id, entered)
+    pub fn get_proximity_events(&self) -> Vec<(String, bool)> {
+        self.proximity_events.lock().unwrap().clone()
+    }
+
     /// Reset all tracked data
     pub fn reset(&self) {
         self.calls.lock().unwrap().clear();
@@ -105,8 +126,11 @@ impl MockCallback {
         self.rejected_pairings.lock().unwrap().clear();
         self.received_clipboard.lock().unwrap().clear();
         self.sync_errors.lock().unwrap().clear();
+        self.delivered.lock().unwrap().clear();
+        self.failed.lock().unwrap().clear();
         self.discovered_devices.lock().unwrap().clear();
         self.lost_devices.lock().unwrap().clear();
+        self.proximity_events.lock().unwrap().clear();
     }
 }
 
@@ -165,6 +189,25 @@ impl FfiNearClipCallback for MockCallback {
         self.sync_errors.lock().unwrap().push(error_message);
     }
 
+    fn on_sync_delivered(&self, device_id: String, message_id: u64) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push("on_sync_delivered".to_string());
+        self.delivered.lock().unwrap().push((device_id, message_id));
+    }
+
+    fn on_sync_failed(&self, device_id: String, message_id: u64, error_message: String) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push("on_sync_failed".to_string());
+        self.failed
+            .lock()
+            .unwrap()
+            .push((device_id, message_id, error_message));
+    }
+
     fn on_device_discovered(&self, device: FfiDiscoveredDevice) {
         self.calls
             .lock()
@@ -180,6 +223,22 @@ impl FfiNearClipCallback for MockCallback {
             .push("on_device_lost".to_string());
         self.lost_devices.lock().unwrap().push(peripheral_uuid);
     }
+
+    fn on_proximity_entered(&self, device_id: String, _rssi: i16) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push("on_proximity_entered".to_string());
+        self.proximity_events.lock().unwrap().push((device_id, true));
+    }
+
+    fn on_proximity_left(&self, device_id: String) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push("on_proximity_left".to_string());
+        self.proximity_events.lock().unwrap().push((device_id, false));
+    }
 }
 
 impl Default for MockCallback {
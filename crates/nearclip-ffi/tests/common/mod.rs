@@ -6,6 +6,7 @@ use std::sync::Arc;
 pub use mock_callback::MockCallback;
 use nearclip_ffi::*;
 use nearclip_core::{DevicePlatform, DeviceStatus};
+use nearclip_sync::ChannelPreference;
 
 /// Create a test configuration with default values
 pub fn create_test_config() -> FfiNearClipConfig {
@@ -18,6 +19,20 @@ pub fn create_test_config() -> FfiNearClipConfig {
         connection_timeout_secs: 30,
         heartbeat_interval_secs: 10,
         max_retries: 3,
+        allowed_interfaces: vec![],
+        excluded_interfaces: vec![],
+        exclude_virtual_interfaces: false,
+        network_monitor: true,
+        port_range_start: 8765,
+        port_range_end: 8774,
+        quic_enabled: false,
+        sync_only_when_near: false,
+        auto_connect_when_near: false,
+        near_rssi: -65,
+        far_rssi: -80,
+        proximity_timeout_secs: 30,
+        relay_address: None,
+        relay_certificate: None,
     }
 }
 
@@ -28,6 +43,11 @@ pub fn create_test_device_info(id: &str) -> FfiDeviceInfo {
         name: format!("Test Device {}", id),
         platform: DevicePlatform::MacOS,
         status: DeviceStatus::Disconnected,
+        channel_preference: ChannelPreference::Auto,
+        static_addresses: Vec::new(),
+        last_known_address: None,
+        proximity_thresholds: None,
+        advertisement_key: None,
     }
 }
 
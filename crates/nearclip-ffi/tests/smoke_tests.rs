@@ -232,3 +232,55 @@ fn test_ffi_unpair_device() {
         "Device should be removed after unpair"
     );
 }
+
+/// Test 1.13: Channel preference is stored with the paired device
+#[test]
+fn test_ffi_device_channel_preference() {
+    use nearclip_sync::ChannelPreference;
+
+    let manager = create_test_manager();
+
+    let device = create_test_device_info("pref-device");
+    manager.add_paired_device(device.clone());
+    assert_eq!(
+        manager.get_device_channel_preference(device.id.clone()),
+        Some(ChannelPreference::Auto)
+    );
+
+    manager
+        .set_device_channel_preference(device.id.clone(), ChannelPreference::Hybrid)
+        .expect("Setting preference on a paired device should succeed");
+
+    let paired = manager.get_paired_devices();
+    assert_eq!(paired[0].channel_preference, ChannelPreference::Hybrid);
+
+    // Unknown devices are rejected
+    let result = manager.set_device_channel_preference(
+        "unknown".to_string(),
+        ChannelPreference::BleOnly,
+    );
+    assert!(result.is_err());
+}
+
+/// Test 1.14: Clips synced while a paired device is offline are queued in the outbox
+#[test]
+fn test_ffi_outbox_queues_offline_device() {
+    let manager = create_test_manager();
+    assert!(manager.get_outbox_count("offline-device".to_string()).is_err());
+
+    let db_path = std::env::temp_dir().join(format!("ffi_outbox_{}.db", std::process::id()));
+    manager
+        .init_outbox(db_path.to_string_lossy().to_string())
+        .expect("Outbox initialization should succeed");
+
+    manager.add_paired_device(create_test_device_info("offline-device"));
+    manager.start().expect("Manager should start");
+
+    manager
+        .sync_clipboard(b"queued clip".to_vec())
+        .expect("Sync with no connections should succeed");
+    assert_eq!(manager.get_outbox_count("offline-device".to_string()).unwrap(), 1);
+
+    manager.stop();
+    let _ = std::fs::remove_file(db_path);
+}
@@ -6,7 +6,8 @@ mod common;
 
 use common::*;
 use nearclip_ffi::*;
-use nearclip_core::{DeviceInfo, DevicePlatform, DeviceStatus, NearClipConfig};
+use nearclip_core::{DeviceInfo, DevicePlatform, DeviceStatus, NearClipConfig, ProximityThresholds};
+use nearclip_sync::ChannelPreference;
 use std::time::Duration;
 
 /// Test 2.1: FfiDeviceInfo conversion (FFI → Core → FFI)
@@ -17,6 +18,11 @@ fn test_ffi_device_info_conversion() {
         name: "Test Device".to_string(),
         platform: DevicePlatform::MacOS,
         status: DeviceStatus::Connected,
+        channel_preference: ChannelPreference::Auto,
+        static_addresses: Vec::new(),
+        last_known_address: None,
+        proximity_thresholds: None,
+        advertisement_key: None,
     };
 
     // Convert FFI → Core
@@ -49,6 +55,11 @@ fn test_ffi_device_info_conversion_platforms() {
             name: "Test Device".to_string(),
             platform,
             status: DeviceStatus::Disconnected,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
         };
 
         let device: DeviceInfo = ffi_device.clone().into();
@@ -75,6 +86,11 @@ fn test_ffi_device_info_conversion_statuses() {
             name: "Test Device".to_string(),
             platform: DevicePlatform::MacOS,
             status,
+            channel_preference: ChannelPreference::Auto,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
         };
 
         let device: DeviceInfo = ffi_device.clone().into();
@@ -85,6 +101,85 @@ fn test_ffi_device_info_conversion_statuses() {
     }
 }
 
+/// Test 2.3b: FfiDeviceInfo conversion keeps the channel preference
+#[test]
+fn test_ffi_device_info_conversion_channel_preference() {
+    let preferences = vec![
+        ChannelPreference::Auto,
+        ChannelPreference::WifiOnly,
+        ChannelPreference::BleOnly,
+        ChannelPreference::Hybrid,
+    ];
+
+    for preference in preferences {
+        let ffi_device = FfiDeviceInfo {
+            id: "test-id".to_string(),
+            name: "Test Device".to_string(),
+            platform: DevicePlatform::MacOS,
+            status: DeviceStatus::Disconnected,
+            channel_preference: preference,
+            static_addresses: Vec::new(),
+            last_known_address: None,
+            proximity_thresholds: None,
+            advertisement_key: None,
+        };
+
+        let device: DeviceInfo = ffi_device.into();
+        assert_eq!(device.channel_preference(), preference);
+
+        let ffi_device2: FfiDeviceInfo = device.into();
+        assert_eq!(ffi_device2.channel_preference, preference);
+    }
+}
+
+/// Test 2.3c: FfiDeviceInfo conversion keeps valid static addresses
+#[test]
+fn test_ffi_device_info_conversion_static_addresses() {
+    let ffi_device = FfiDeviceInfo {
+        id: "test-id".to_string(),
+        name: "Test Device".to_string(),
+        platform: DevicePlatform::MacOS,
+        status: DeviceStatus::Disconnected,
+        channel_preference: ChannelPreference::Auto,
+        static_addresses: vec![
+            "10.0.0.5:8765".to_string(),
+            "not-an-address".to_string(),
+            "laptop.vpn.example:8765".to_string(),
+        ],
+        last_known_address: None,
+        proximity_thresholds: Some(ProximityThresholds::new(-55, -70)),
+        advertisement_key: None,
+    };
+
+    let device: DeviceInfo = ffi_device.into();
+    assert_eq!(device.static_addresses().len(), 2);
+    assert_eq!(device.proximity_thresholds(), Some(ProximityThresholds::new(-55, -70)));
+
+    let ffi_device2: FfiDeviceInfo = device.into();
+    assert_eq!(
+        ffi_device2.static_addresses,
+        vec!["10.0.0.5:8765".to_string(), "laptop.vpn.example:8765".to_string()]
+    );
+}
+
+/// Test 2.3d: FfiDeviceInfo conversion round-trips the last known address
+#[test]
+fn test_ffi_device_info_conversion_last_known_address() {
+    let mut ffi_device = create_test_device_info("test-id");
+    ffi_device.last_known_address = Some("192.168.1.20:8765".to_string());
+
+    let device: DeviceInfo = ffi_device.clone().into();
+    assert_eq!(device.last_known_address(), Some("192.168.1.20:8765".parse().unwrap()));
+
+    let ffi_device2: FfiDeviceInfo = device.into();
+    assert_eq!(ffi_device2.last_known_address, Some("192.168.1.20:8765".to_string()));
+
+    // Invalid stored values are dropped instead of failing the load
+    ffi_device.last_known_address = Some("not-an-address".to_string());
+    let device: DeviceInfo = ffi_device.into();
+    assert_eq!(device.last_known_address(), None);
+}
+
 /// Test 2.4: FfiNearClipConfig conversion with all fields
 #[test]
 fn test_ffi_config_conversion_full() {
@@ -97,6 +192,20 @@ fn test_ffi_config_conversion_full() {
         connection_timeout_secs: 30,
         heartbeat_interval_secs: 10,
         max_retries: 3,
+        allowed_interfaces: vec![],
+        excluded_interfaces: vec![],
+        exclude_virtual_interfaces: false,
+        network_monitor: true,
+        port_range_start: 8765,
+        port_range_end: 8774,
+        quic_enabled: false,
+        sync_only_when_near: false,
+        auto_connect_when_near: false,
+        near_rssi: -65,
+        far_rssi: -80,
+        proximity_timeout_secs: 30,
+        relay_address: None,
+        relay_certificate: None,
     };
 
     let config: NearClipConfig = ffi_config.clone().into();
@@ -122,6 +231,20 @@ fn test_ffi_config_conversion_disabled_features() {
         connection_timeout_secs: 60,
         heartbeat_interval_secs: 20,
         max_retries: 5,
+        allowed_interfaces: vec![],
+        excluded_interfaces: vec![],
+        exclude_virtual_interfaces: false,
+        network_monitor: true,
+        port_range_start: 8765,
+        port_range_end: 8774,
+        quic_enabled: false,
+        sync_only_when_near: false,
+        auto_connect_when_near: false,
+        near_rssi: -65,
+        far_rssi: -80,
+        proximity_timeout_secs: 30,
+        relay_address: None,
+        relay_certificate: None,
     };
 
     let config: NearClipConfig = ffi_config.into();
@@ -145,6 +268,20 @@ fn test_ffi_config_conversion_empty_device_id() {
         connection_timeout_secs: 30,
         heartbeat_interval_secs: 10,
         max_retries: 3,
+        allowed_interfaces: vec![],
+        excluded_interfaces: vec![],
+        exclude_virtual_interfaces: false,
+        network_monitor: true,
+        port_range_start: 8765,
+        port_range_end: 8774,
+        quic_enabled: false,
+        sync_only_when_near: false,
+        auto_connect_when_near: false,
+        near_rssi: -65,
+        far_rssi: -80,
+        proximity_timeout_secs: 30,
+        relay_address: None,
+        relay_certificate: None,
     };
 
     let config: NearClipConfig = ffi_config.into();
@@ -6,7 +6,10 @@
 //! # Modules
 //!
 //! - [`mdns`] - mDNS service discovery and advertising
+//! - [`netif`] - Network interface filtering and address-change monitoring
+//! - [`quic`] - QUIC server and client with separate streams and 0-RTT resumption
 //! - [`tcp`] - TLS-encrypted TCP server and connections
+//! - [`udp`] - Authenticated UDP unicast/broadcast discovery for networks that block multicast
 //! - [`error`] - Network error types
 //!
 //! # mDNS Example
@@ -81,12 +84,30 @@
 
 pub mod error;
 pub mod mdns;
+pub mod netif;
+pub mod quic;
 pub mod tcp;
+pub mod udp;
 
 // Re-export main types
 pub use error::NetError;
 pub use mdns::{
-    DiscoveredDevice, DiscoveryEvent, MdnsAdvertiser, MdnsDiscovery, MdnsServiceConfig,
-    SERVICE_TYPE, TXT_DEVICE_ID, TXT_PUBKEY_HASH,
+    current_epoch, DiscoveredDevice, DiscoveryEvent, DiscoveryKey, DiscoverySink, MdnsAdvertiser, MdnsDiscovery,
+    MdnsServiceConfig, PrivateAdvertising, PrivateTokenResolver, DEFAULT_ROTATION_PERIOD_SECS,
+    MAX_PRIVATE_TOKENS, SERVICE_TYPE, TXT_DEVICE_ID, TXT_PRIVATE_TOKENS, TXT_PUBKEY_HASH,
+};
+pub use netif::{
+    is_virtual_interface, list_interfaces, InterfaceAddress, InterfaceChange, InterfaceFilter,
+    InterfaceMonitor, DEFAULT_INTERFACE_POLL_INTERVAL_SECS,
+};
+pub use quic::{
+    QuicClient, QuicClientConfig, QuicConnection, QuicServer, QuicServerConfig, DEFAULT_QUIC_IDLE_TIMEOUT_SECS, QUIC_ALPN,
+};
+pub use tcp::{
+    ConnectionGuard, ConnectionLimits, ConnectionPermit, RejectReason, TcpClient, TcpClientConfig,
+    TcpConnection, TcpReadHalf, TcpServer, TcpServerConfig, TcpWriteHalf, TokenBucket,
+};
+pub use udp::{
+    UdpProber, UdpProberConfig, UdpResponder, UdpResponderConfig, DEFAULT_PROBE_INTERVAL_SECS,
+    DEFAULT_UDP_DISCOVERY_PORT, MIN_SWEEP_PREFIX_LEN,
 };
-pub use tcp::{TcpClient, TcpClientConfig, TcpConnection, TcpReadHalf, TcpServer, TcpServerConfig, TcpWriteHalf};
@@ -3,9 +3,11 @@
 //! 提供设备在局域网上广播自己存在的功能。
 
 use crate::error::NetError;
-use mdns_sd::{ServiceDaemon, ServiceInfo};
+use crate::mdns::privacy::{current_epoch, until_next_epoch, PrivateAdvertising, TXT_PRIVATE_TOKENS};
+use mdns_sd::{IfKind, ServiceDaemon, ServiceInfo};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
 use tracing::{debug, info, instrument, warn};
 
 /// NearClip mDNS 服务类型
@@ -47,6 +49,8 @@ pub struct MdnsServiceConfig {
     port: u16,
     /// 可选的主机名（不含 .local. 后缀）
     hostname: Option<String>,
+    /// 隐私广播配置（启用后不发布设备 ID 和公钥哈希）
+    private: Option<PrivateAdvertising>,
 }
 
 impl MdnsServiceConfig {
@@ -75,6 +79,7 @@ impl MdnsServiceConfig {
             public_key_hash,
             port,
             hostname: None,
+            private: None,
         }
     }
 
@@ -91,6 +96,25 @@ impl MdnsServiceConfig {
         self
     }
 
+    /// 启用隐私广播
+    ///
+    /// TXT 记录只包含每个已配对设备的轮换令牌，服务实例名和主机名
+    /// 每个轮换周期随机更换，自定义主机名会被忽略。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nearclip_net::{DiscoveryKey, MdnsServiceConfig, PrivateAdvertising};
+    ///
+    /// let config = MdnsServiceConfig::new("my-device".to_string(), "aGFzaA==".to_string(), 8080)
+    ///     .with_private_advertising(PrivateAdvertising::new(vec![DiscoveryKey::derive(b"secret")]));
+    /// assert!(config.is_private());
+    /// ```
+    pub fn with_private_advertising(mut self, private: PrivateAdvertising) -> Self {
+        self.private = Some(private);
+        self
+    }
+
     /// 验证主机名格式是否有效
     ///
     /// DNS 主机名规则：
@@ -157,6 +181,16 @@ impl MdnsServiceConfig {
         self.hostname.as_deref()
     }
 
+    /// 获取隐私广播配置
+    pub fn private_advertising(&self) -> Option<&PrivateAdvertising> {
+        self.private.as_ref()
+    }
+
+    /// 是否启用隐私广播
+    pub fn is_private(&self) -> bool {
+        self.private.is_some()
+    }
+
     /// 验证配置有效性
     pub fn validate(&self) -> Result<(), NetError> {
         if self.device_id.is_empty() {
@@ -180,6 +214,10 @@ impl MdnsServiceConfig {
             Self::validate_hostname(hostname)?;
         }
 
+        if let Some(ref private) = self.private {
+            private.validate()?;
+        }
+
         // 验证 TXT 记录长度不超过 DNS 限制
         // DNS TXT 记录格式: 每条记录 = 1字节长度前缀 + "key=value"
         // 单条 TXT 记录最大 255 字节（不含长度前缀）
@@ -203,11 +241,64 @@ impl MdnsServiceConfig {
     }
 
     /// 构建 TXT 记录属性数组
+    ///
+    /// 隐私模式下只包含当前周期的令牌列表。
     pub(crate) fn build_txt_properties(&self) -> Vec<(String, String)> {
-        vec![
-            (TXT_DEVICE_ID.to_string(), self.device_id.clone()),
-            (TXT_PUBKEY_HASH.to_string(), self.public_key_hash.clone()),
-        ]
+        match self.private {
+            Some(ref private) => vec![(
+                TXT_PRIVATE_TOKENS.to_string(),
+                private.tokens_value(&self.device_id, current_epoch(private.rotation_period())),
+            )],
+            None => vec![
+                (TXT_DEVICE_ID.to_string(), self.device_id.clone()),
+                (TXT_PUBKEY_HASH.to_string(), self.public_key_hash.clone()),
+            ],
+        }
+    }
+
+    /// 构建 mDNS 服务信息
+    ///
+    /// 隐私模式下实例名和主机名使用随机标签，不包含设备 ID。
+    fn build_service_info(&self) -> Result<ServiceInfo, NetError> {
+        let (instance_name, hostname) = if self.private.is_some() {
+            let label = format!("nc-{}", hex::encode(rand::random::<[u8; 8]>()));
+            (label.clone(), label)
+        } else {
+            (
+                self.device_id.clone(),
+                self.hostname.clone().unwrap_or_else(|| self.device_id.clone()),
+            )
+        };
+
+        // 确保主机名以 .local. 结尾
+        let hostname = if hostname.ends_with(".local.") {
+            hostname
+        } else if hostname.ends_with(".local") {
+            format!("{}.", hostname)
+        } else {
+            format!("{}.local.", hostname)
+        };
+
+        // 构建 TXT 属性
+        let properties = self.build_txt_properties();
+        let properties_refs: Vec<(&str, &str)> = properties
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+
+        // 创建服务信息
+        let service_info = ServiceInfo::new(
+            SERVICE_TYPE,
+            &instance_name,
+            &hostname,
+            (),
+            self.port,
+            &properties_refs[..],
+        )
+        .map_err(|e| NetError::ServiceRegistration(format!("Failed to create service info: {}", e)))?
+        .enable_addr_auto();  // 启用地址自动发现
+
+        Ok(service_info)
     }
 }
 
@@ -241,8 +332,10 @@ pub struct MdnsAdvertiser {
     daemon: Arc<Mutex<ServiceDaemon>>,
     /// 服务配置
     config: MdnsServiceConfig,
-    /// 已注册服务的完整名称
-    service_fullname: Option<String>,
+    /// 已注册服务的完整名称（隐私模式下由轮换任务更新）
+    service_fullname: Arc<std::sync::Mutex<Option<String>>>,
+    /// 隐私模式的令牌轮换任务
+    rotation_task: Option<JoinHandle<()>>,
 }
 
 impl MdnsAdvertiser {
@@ -263,18 +356,20 @@ impl MdnsAdvertiser {
         let daemon =
             ServiceDaemon::new().map_err(|e| NetError::Mdns(format!("Failed to create daemon: {}", e)))?;
 
-        debug!(device_id = %config.device_id, "Created mDNS advertiser");
+        debug!(device_id = %config.device_id, private = config.is_private(), "Created mDNS advertiser");
 
         Ok(Self {
             daemon: Arc::new(Mutex::new(daemon)),
             config,
-            service_fullname: None,
+            service_fullname: Arc::new(std::sync::Mutex::new(None)),
+            rotation_task: None,
         })
     }
 
     /// 启动服务广播
     ///
     /// 在局域网上注册 mDNS 服务，使其他设备可以发现。
+    /// 隐私模式下还会启动令牌轮换任务，每个周期开始时重新注册服务。
     ///
     /// # Returns
     ///
@@ -282,64 +377,104 @@ impl MdnsAdvertiser {
     #[instrument(skip(self), fields(device_id = %self.config.device_id))]
     pub async fn start(&mut self) -> Result<(), NetError> {
         // 如果已经在广播，先停止
-        if self.service_fullname.is_some() {
+        if self.is_advertising() {
             warn!("Service already advertising, stopping first");
             self.stop().await?;
         }
 
-        let daemon = self.daemon.lock().await;
-
-        // 构建主机名
-        let hostname = self
-            .config
-            .hostname
-            .clone()
-            .unwrap_or_else(|| format!("{}.local.", self.config.device_id));
-
-        // 确保主机名以 .local. 结尾
-        let hostname = if hostname.ends_with(".local.") {
-            hostname
-        } else if hostname.ends_with(".local") {
-            format!("{}.", hostname)
-        } else {
-            format!("{}.local.", hostname)
-        };
-
-        // 构建 TXT 属性
-        let properties = self.config.build_txt_properties();
-        let properties_refs: Vec<(&str, &str)> = properties
-            .iter()
-            .map(|(k, v)| (k.as_str(), v.as_str()))
-            .collect();
-
-        // 创建服务信息
-        let service_info = ServiceInfo::new(
-            SERVICE_TYPE,
-            &self.config.device_id,
-            &hostname,
-            (),
-            self.config.port,
-            &properties_refs[..],
-        )
-        .map_err(|e| NetError::ServiceRegistration(format!("Failed to create service info: {}", e)))?
-        .enable_addr_auto();  // 启用地址自动发现
-
+        let service_info = self.config.build_service_info()?;
         let fullname = service_info.get_fullname().to_string();
 
         // 注册服务
-        daemon
-            .register(service_info)
-            .map_err(|e| NetError::ServiceRegistration(format!("Failed to register service: {}", e)))?;
+        {
+            let daemon = self.daemon.lock().await;
+            daemon
+                .register(service_info)
+                .map_err(|e| NetError::ServiceRegistration(format!("Failed to register service: {}", e)))?;
+        }
 
-        self.service_fullname = Some(fullname.clone());
+        *self.service_fullname.lock().unwrap() = Some(fullname.clone());
 
         info!(
             service_name = %fullname,
             port = self.config.port,
             device_id = %self.config.device_id,
+            private = self.config.is_private(),
             "mDNS service registered"
         );
 
+        if let Some(ref private) = self.config.private {
+            let rotation_period = private.rotation_period();
+            let daemon = self.daemon.clone();
+            let config = self.config.clone();
+            let service_fullname = self.service_fullname.clone();
+            self.rotation_task = Some(tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(until_next_epoch(rotation_period)).await;
+                    if let Err(e) = Self::rotate(&daemon, &config, &service_fullname).await {
+                        warn!(error = %e, "Failed to rotate private mDNS advertisement");
+                    }
+                }
+            }));
+        }
+
+        Ok(())
+    }
+
+    /// 重新广播服务
+    ///
+    /// 网络接口或地址变化后调用，立即以当前地址重新注册服务，
+    /// 不必等待 mDNS 守护进程自身的周期性接口检查。未在广播时什么也不做。
+    #[instrument(skip(self), fields(device_id = %self.config.device_id))]
+    pub async fn reannounce(&self) -> Result<(), NetError> {
+        if !self.is_advertising() {
+            debug!("Not advertising, skip re-announce");
+            return Ok(());
+        }
+        Self::rotate(&self.daemon, &self.config, &self.service_fullname).await?;
+        info!("mDNS service re-announced");
+        Ok(())
+    }
+
+    /// 停止在指定网络接口上广播
+    ///
+    /// 用于排除 VPN、虚拟网卡等不应暴露服务的接口。
+    pub async fn disable_interfaces(&self, names: &[String]) -> Result<(), NetError> {
+        if names.is_empty() {
+            return Ok(());
+        }
+        let kinds: Vec<IfKind> = names.iter().cloned().map(IfKind::Name).collect();
+        self.daemon
+            .lock()
+            .await
+            .disable_interface(kinds)
+            .map_err(|e| NetError::Mdns(format!("Failed to disable interfaces: {}", e)))?;
+        debug!(interfaces = ?names, "mDNS advertising disabled on interfaces");
+        Ok(())
+    }
+
+    /// 重新注册服务（隐私模式下使用新的实例名和令牌）
+    async fn rotate(
+        daemon: &Mutex<ServiceDaemon>,
+        config: &MdnsServiceConfig,
+        service_fullname: &std::sync::Mutex<Option<String>>,
+    ) -> Result<(), NetError> {
+        let service_info = config.build_service_info()?;
+        let fullname = service_info.get_fullname().to_string();
+
+        let daemon = daemon.lock().await;
+        let old = service_fullname.lock().unwrap().take();
+        if let Some(old) = old {
+            if let Err(e) = daemon.unregister(&old) {
+                warn!(service_name = %old, error = %e, "Failed to unregister rotated service");
+            }
+        }
+        daemon
+            .register(service_info)
+            .map_err(|e| NetError::ServiceRegistration(format!("Failed to register service: {}", e)))?;
+        *service_fullname.lock().unwrap() = Some(fullname.clone());
+
+        debug!(service_name = %fullname, "mDNS service re-registered");
         Ok(())
     }
 
@@ -352,7 +487,12 @@ impl MdnsAdvertiser {
     /// 成功返回 `Ok(())`，失败返回 `NetError`
     #[instrument(skip(self), fields(device_id = %self.config.device_id))]
     pub async fn stop(&mut self) -> Result<(), NetError> {
-        if let Some(fullname) = self.service_fullname.take() {
+        if let Some(task) = self.rotation_task.take() {
+            task.abort();
+        }
+
+        let fullname = self.service_fullname.lock().unwrap().take();
+        if let Some(fullname) = fullname {
             let daemon = self.daemon.lock().await;
 
             daemon
@@ -373,16 +513,16 @@ impl MdnsAdvertiser {
     ///
     /// 如果服务正在广播返回 `true`
     pub fn is_advertising(&self) -> bool {
-        self.service_fullname.is_some()
+        self.service_fullname.lock().unwrap().is_some()
     }
 
     /// 获取服务完整名称
     ///
     /// # Returns
     ///
-    /// 如果正在广播，返回服务的完整 DNS 名称
-    pub fn service_fullname(&self) -> Option<&str> {
-        self.service_fullname.as_deref()
+    /// 如果正在广播，返回服务的完整 DNS 名称（隐私模式下每个周期变化）
+    pub fn service_fullname(&self) -> Option<String> {
+        self.service_fullname.lock().unwrap().clone()
     }
 
     /// 获取当前配置
@@ -393,9 +533,14 @@ impl MdnsAdvertiser {
 
 impl Drop for MdnsAdvertiser {
     fn drop(&mut self) {
+        if let Some(task) = self.rotation_task.take() {
+            task.abort();
+        }
+
         // 如果服务仍在广播，尝试注销
-        if let Some(fullname) = self.service_fullname.take() {
-            // 尝试同步��取锁并注销服务
+        let fullname = self.service_fullname.lock().unwrap().take();
+        if let Some(fullname) = fullname {
+            // 尝试同步获取锁并注销服务
             // 注意：Drop 不能是 async，所以使用 try_lock
             if let Ok(daemon) = self.daemon.try_lock() {
                 if let Err(e) = daemon.unregister(&fullname) {
@@ -575,6 +720,46 @@ mod tests {
         assert!(properties.iter().any(|(k, v)| k == TXT_PUBKEY_HASH && v == "my-hash"));
     }
 
+    #[test]
+    fn test_build_txt_properties_private() {
+        use crate::mdns::privacy::DiscoveryKey;
+
+        let key = DiscoveryKey::derive(b"secret");
+        let config = MdnsServiceConfig::new("my-device".to_string(), "my-hash".to_string(), 8080)
+            .with_private_advertising(PrivateAdvertising::new(vec![key]));
+
+        let properties = config.build_txt_properties();
+
+        assert_eq!(properties.len(), 1);
+        assert_eq!(properties[0].0, TXT_PRIVATE_TOKENS);
+        assert!(!properties[0].1.contains("my-device"));
+        assert!(!properties.iter().any(|(k, _)| k == TXT_DEVICE_ID || k == TXT_PUBKEY_HASH));
+    }
+
+    #[test]
+    fn test_private_service_info_hides_device_id() {
+        let config = MdnsServiceConfig::new("my-device".to_string(), "my-hash".to_string(), 8080)
+            .with_hostname("my-laptop".to_string())
+            .with_private_advertising(PrivateAdvertising::new(vec![]));
+
+        let first = config.build_service_info().unwrap();
+        let second = config.build_service_info().unwrap();
+
+        assert!(!first.get_fullname().contains("my-device"));
+        assert!(!first.get_hostname().contains("my-laptop"));
+        assert_ne!(first.get_fullname(), second.get_fullname());
+    }
+
+    #[test]
+    fn test_service_config_validate_private() {
+        use crate::mdns::privacy::DiscoveryKey;
+
+        let keys = vec![DiscoveryKey::from_bytes([1u8; 32]); 15];
+        let config = MdnsServiceConfig::new("device".to_string(), "hash".to_string(), 12345)
+            .with_private_advertising(PrivateAdvertising::new(keys));
+        assert!(config.validate().is_err());
+    }
+
     #[test]
     fn test_service_type_format() {
         assert_eq!(SERVICE_TYPE, "_nearclip._tcp.local.");
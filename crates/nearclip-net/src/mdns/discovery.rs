@@ -4,7 +4,8 @@
 
 use crate::error::NetError;
 use crate::mdns::advertise::{SERVICE_TYPE, TXT_DEVICE_ID, TXT_PUBKEY_HASH};
-use mdns_sd::{ServiceDaemon, ServiceEvent};
+use crate::mdns::privacy::{PrivateTokenResolver, TXT_PRIVATE_TOKENS};
+use mdns_sd::{IfKind, ServiceDaemon, ServiceEvent};
 use std::collections::{HashMap, HashSet};
 use std::net::IpAddr;
 use std::sync::Arc;
@@ -15,7 +16,9 @@ use tracing::{debug, info, instrument, warn};
 
 /// 发现的设备信息
 ///
-/// 包含从 mDNS 服务发现中获取的设备详细信息。
+/// 包含从 mDNS 服务发现中获取的设备详细信息。这只是 mDNS 单一来源的条目；
+/// 需要展示附近设备时，交给 `nearclip_device::NearbyDevices::sync_mdns` 与 BLE
+/// 扫描结果合并，不要另外维护一份列表。
 ///
 /// # Example
 ///
@@ -111,6 +114,35 @@ impl DiscoveredDevice {
         })
     }
 
+    /// 从隐私模式广播的 ServiceInfo 创建 DiscoveredDevice
+    ///
+    /// 用本地已配对设备的发现密钥解析令牌。隐私模式不发布公钥哈希，
+    /// `public_key_hash` 为空字符串。
+    ///
+    /// # Returns
+    ///
+    /// 令牌匹配某个已配对设备时返回 `DiscoveredDevice`，否则返回 None
+    pub fn from_private_service_info(
+        info: &mdns_sd::ServiceInfo,
+        resolver: &PrivateTokenResolver,
+    ) -> Option<Self> {
+        let tokens = info.get_property_val_str(TXT_PRIVATE_TOKENS)?;
+        let device_id = resolver.resolve(tokens)?;
+
+        let addresses: HashSet<IpAddr> = info.get_addresses().iter().copied().collect();
+        let now = Instant::now();
+
+        Some(Self {
+            device_id,
+            public_key_hash: String::new(),
+            addresses,
+            port: info.get_port(),
+            fullname: info.get_fullname().to_string(),
+            discovered_at: now,
+            last_seen: now,
+        })
+    }
+
     /// 更新设备信息
     ///
     /// 保留原始发现时间，更新其他字段
@@ -145,6 +177,142 @@ pub enum DiscoveryEvent {
 /// 事件广播通道容量
 const EVENT_CHANNEL_CAPACITY: usize = 64;
 
+/// 发现结果汇聚点
+///
+/// mDNS 浏览任务和其他发现方式（如 UDP 单播探测）都通过它写入同一份
+/// 设备列表并发送 [`DiscoveryEvent`]，上层无需区分结果来源。
+/// 通过 [`MdnsDiscovery::sink`] 获取与发现器共享的实例。
+#[derive(Clone)]
+pub struct DiscoverySink {
+    /// 已发现设备列表 (device_id -> DiscoveredDevice)
+    devices: Arc<RwLock<HashMap<String, DiscoveredDevice>>>,
+    /// fullname 到 device_id 的反向查找表
+    fullname_to_id: Arc<RwLock<HashMap<String, String>>>,
+    /// 事件广播通道发送端
+    event_tx: broadcast::Sender<DiscoveryEvent>,
+}
+
+impl DiscoverySink {
+    /// 创建独立的汇聚点（不与 mDNS 发现器共享）
+    pub fn new() -> Self {
+        let (event_tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            devices: Arc::new(RwLock::new(HashMap::new())),
+            fullname_to_id: Arc::new(RwLock::new(HashMap::new())),
+            event_tx,
+        }
+    }
+
+    /// 订阅发现事件
+    pub fn subscribe(&self) -> broadcast::Receiver<DiscoveryEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// 获取特定设备
+    pub async fn get_device(&self, device_id: &str) -> Option<DiscoveredDevice> {
+        self.devices.read().await.get(device_id).cloned()
+    }
+
+    /// 上报发现的设备
+    ///
+    /// 新设备发送 `DeviceFound`，已知设备更新信息后发送 `DeviceUpdated`。
+    pub async fn report(&self, device: DiscoveredDevice) {
+        let mut devices_guard = self.devices.write().await;
+        let device_id = device.device_id.clone();
+
+        if let Some(existing) = devices_guard.get_mut(&device_id) {
+            // 设备已存在，更新信息
+            existing.update_from(&device);
+            let updated = existing.clone();
+            drop(devices_guard);
+
+            // 隐私模式轮换或来源切换后服务名会变化
+            self.fullname_to_id
+                .write()
+                .await
+                .insert(updated.fullname.clone(), device_id.clone());
+
+            debug!(
+                device_id = %device_id,
+                addresses = ?updated.addresses,
+                "Device updated"
+            );
+
+            // 发送更新事件（忽略发送失败，可能没有订阅者）
+            let _ = self.event_tx.send(DiscoveryEvent::DeviceUpdated(updated));
+        } else {
+            // 新设备
+            let fullname = device.fullname.clone();
+            devices_guard.insert(device_id.clone(), device.clone());
+            drop(devices_guard);
+
+            // M3 Fix: 更新反向查找表
+            self.fullname_to_id.write().await.insert(fullname, device_id.clone());
+
+            info!(
+                device_id = %device_id,
+                port = device.port,
+                addresses = ?device.addresses,
+                "Device discovered"
+            );
+
+            let _ = self.event_tx.send(DiscoveryEvent::DeviceFound(device));
+        }
+    }
+
+    /// 按服务名移除设备
+    ///
+    /// 只有设备当前的服务名被移除才算离线（隐私模式轮换、来源切换时
+    /// 旧服务名会先失效），此时发送 `DeviceLost`。
+    ///
+    /// # Returns
+    ///
+    /// 设备被移除时返回 true
+    pub async fn remove(&self, fullname: &str) -> bool {
+        // M3 Fix: 使用反向查找表 O(1) 查找
+        let removed_device_id = self.fullname_to_id.write().await.remove(fullname);
+        let Some(device_id) = removed_device_id else {
+            return false;
+        };
+
+        let mut devices_guard = self.devices.write().await;
+        let is_current = devices_guard
+            .get(&device_id)
+            .map(|d| d.fullname == fullname)
+            .unwrap_or(false);
+        if !is_current {
+            debug!(device_id = %device_id, fullname = %fullname, "Stale service name removed");
+            return false;
+        }
+        devices_guard.remove(&device_id);
+        drop(devices_guard);
+
+        info!(
+            device_id = %device_id,
+            fullname = %fullname,
+            "Device lost"
+        );
+
+        let _ = self.event_tx.send(DiscoveryEvent::DeviceLost {
+            device_id,
+            fullname: fullname.to_string(),
+        });
+        true
+    }
+}
+
+impl Default for DiscoverySink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::fmt::Debug for DiscoverySink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiscoverySink").finish_non_exhaustive()
+    }
+}
+
 /// mDNS 设备发现器
 ///
 /// 在局域网上发现其他 NearClip 设备。
@@ -184,6 +352,8 @@ pub struct MdnsDiscovery {
     devices: Arc<RwLock<HashMap<String, DiscoveredDevice>>>,
     /// M3 Fix: fullname 到 device_id 的反向查找表
     fullname_to_id: Arc<RwLock<HashMap<String, String>>>,
+    /// 隐私模式令牌解析器（未设置时忽略隐私模式广播）
+    private_resolver: Arc<RwLock<Option<PrivateTokenResolver>>>,
     /// 事件广播通道发送端
     event_tx: broadcast::Sender<DiscoveryEvent>,
     /// 浏览任务句柄
@@ -220,6 +390,7 @@ impl MdnsDiscovery {
             daemon: Arc::new(daemon),
             devices: Arc::new(RwLock::new(HashMap::new())),
             fullname_to_id: Arc::new(RwLock::new(HashMap::new())),
+            private_resolver: Arc::new(RwLock::new(None)),
             event_tx,
             browse_handle: None,
             is_browsing: false,
@@ -246,9 +417,8 @@ impl MdnsDiscovery {
             .browse(SERVICE_TYPE)
             .map_err(|e| NetError::Mdns(format!("Failed to start browsing: {}", e)))?;
 
-        let devices = Arc::clone(&self.devices);
-        let fullname_to_id = Arc::clone(&self.fullname_to_id);
-        let event_tx = self.event_tx.clone();
+        let sink = self.sink();
+        let private_resolver = Arc::clone(&self.private_resolver);
 
         // 启动异步任务处理事件
         let handle = tokio::spawn(async move {
@@ -262,69 +432,18 @@ impl MdnsDiscovery {
                     Ok(Ok(service_event)) => {
                         match service_event {
                             ServiceEvent::ServiceResolved(info) => {
-                                if let Some(device) = DiscoveredDevice::from_service_info(&info)
-                                {
-                                    let mut devices_guard = devices.write().await;
-                                    let device_id = device.device_id.clone();
-
-                                    if let Some(existing) = devices_guard.get_mut(&device_id) {
-                                        // 设备已存在，更新信息
-                                        existing.update_from(&device);
-                                        let updated = existing.clone();
-                                        drop(devices_guard);
-
-                                        debug!(
-                                            device_id = %device_id,
-                                            addresses = ?updated.addresses,
-                                            "Device updated"
-                                        );
-
-                                        // 发送更新事件（忽略发送失败，可能没有订阅者）
-                                        let _ = event_tx.send(DiscoveryEvent::DeviceUpdated(updated));
-                                    } else {
-                                        // 新设备
-                                        let fullname = device.fullname.clone();
-                                        devices_guard.insert(device_id.clone(), device.clone());
-                                        drop(devices_guard);
-
-                                        // M3 Fix: 更新反向查找表
-                                        let mut fullname_guard = fullname_to_id.write().await;
-                                        fullname_guard.insert(fullname, device_id.clone());
-                                        drop(fullname_guard);
-
-                                        info!(
-                                            device_id = %device_id,
-                                            port = device.port,
-                                            addresses = ?device.addresses,
-                                            "Device discovered"
-                                        );
-
-                                        let _ = event_tx.send(DiscoveryEvent::DeviceFound(device));
-                                    }
+                                let device = match DiscoveredDevice::from_service_info(&info) {
+                                    Some(device) => Some(device),
+                                    None => private_resolver.read().await.as_ref().and_then(|resolver| {
+                                        DiscoveredDevice::from_private_service_info(&info, resolver)
+                                    }),
+                                };
+                                if let Some(device) = device {
+                                    sink.report(device).await;
                                 }
                             }
                             ServiceEvent::ServiceRemoved(_service_type, fullname) => {
-                                // M3 Fix: 使用反向查找表 O(1) 查找
-                                let mut fullname_guard = fullname_to_id.write().await;
-                                let removed_device_id = fullname_guard.remove(&fullname);
-                                drop(fullname_guard);
-
-                                if let Some(device_id) = removed_device_id {
-                                    let mut devices_guard = devices.write().await;
-                                    devices_guard.remove(&device_id);
-                                    drop(devices_guard);
-
-                                    info!(
-                                        device_id = %device_id,
-                                        fullname = %fullname,
-                                        "Device lost"
-                                    );
-
-                                    let _ = event_tx.send(DiscoveryEvent::DeviceLost {
-                                        device_id,
-                                        fullname,
-                                    });
-                                }
+                                sink.remove(&fullname).await;
                             }
                             ServiceEvent::SearchStarted(service_type) => {
                                 debug!(service_type = %service_type, "Search started");
@@ -402,6 +521,18 @@ impl MdnsDiscovery {
         self.event_tx.subscribe()
     }
 
+    /// 获取与发现器共享的结果汇聚点
+    ///
+    /// 其他发现方式通过它上报的设备会出现在 [`get_devices`](Self::get_devices)
+    /// 中，并通过 [`subscribe`](Self::subscribe) 的事件流发送。
+    pub fn sink(&self) -> DiscoverySink {
+        DiscoverySink {
+            devices: Arc::clone(&self.devices),
+            fullname_to_id: Arc::clone(&self.fullname_to_id),
+            event_tx: self.event_tx.clone(),
+        }
+    }
+
     /// 获取当前设备列表
     ///
     /// 返回所有已发现设备的快照。
@@ -428,6 +559,29 @@ impl MdnsDiscovery {
         devices.get(device_id).cloned()
     }
 
+    /// 设置隐私模式令牌解析器
+    ///
+    /// 设置后，只发布轮换令牌的广播会用本地已配对设备的发现密钥解析；
+    /// 配对设备列表变化时应重新设置。传入 None 则忽略隐私模式广播。
+    pub async fn set_private_resolver(&self, resolver: Option<PrivateTokenResolver>) {
+        *self.private_resolver.write().await = resolver;
+    }
+
+    /// 停止在指定网络接口上发现设备
+    ///
+    /// 用于排除 VPN、虚拟网卡等不应参与发现的接口。
+    pub fn disable_interfaces(&self, names: &[String]) -> Result<(), NetError> {
+        if names.is_empty() {
+            return Ok(());
+        }
+        let kinds: Vec<IfKind> = names.iter().cloned().map(IfKind::Name).collect();
+        self.daemon
+            .disable_interface(kinds)
+            .map_err(|e| NetError::Mdns(format!("Failed to disable interfaces: {}", e)))?;
+        debug!(interfaces = ?names, "mDNS discovery disabled on interfaces");
+        Ok(())
+    }
+
     /// M1 Fix: 清除所有已发现的设备
     ///
     /// 用于在重新开始发现前清理陈旧数据
@@ -598,6 +752,53 @@ mod tests {
         }
     }
 
+    fn private_service_info(tokens: &str) -> mdns_sd::ServiceInfo {
+        mdns_sd::ServiceInfo::new(
+            SERVICE_TYPE,
+            "nc-0011223344556677",
+            "nc-0011223344556677.local.",
+            "192.168.1.20",
+            8080,
+            &[(TXT_PRIVATE_TOKENS, tokens)][..],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_discovered_device_from_private_service_info() {
+        use crate::mdns::privacy::{current_epoch, DiscoveryKey, DEFAULT_ROTATION_PERIOD_SECS};
+        use std::time::Duration;
+
+        let period = Duration::from_secs(DEFAULT_ROTATION_PERIOD_SECS);
+        let key = DiscoveryKey::derive(b"secret");
+        let info = private_service_info(&key.token("device-a", current_epoch(period)));
+
+        // 公开模式解析不到设备 ID
+        assert!(DiscoveredDevice::from_service_info(&info).is_none());
+
+        let mut resolver = PrivateTokenResolver::new(period);
+        assert!(DiscoveredDevice::from_private_service_info(&info, &resolver).is_none());
+
+        resolver.add_peer("device-a", key);
+        let device = DiscoveredDevice::from_private_service_info(&info, &resolver).unwrap();
+        assert_eq!(device.device_id, "device-a");
+        assert!(device.public_key_hash.is_empty());
+        assert_eq!(device.port, 8080);
+        assert_eq!(device.addresses.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_mdns_discovery_set_private_resolver() {
+        let discovery = MdnsDiscovery::new().unwrap();
+        discovery
+            .set_private_resolver(Some(PrivateTokenResolver::new(std::time::Duration::from_secs(60))))
+            .await;
+        assert!(discovery.private_resolver.read().await.is_some());
+
+        discovery.set_private_resolver(None).await;
+        assert!(discovery.private_resolver.read().await.is_none());
+    }
+
     #[test]
     fn test_mdns_discovery_new_success() {
         let discovery = MdnsDiscovery::new();
@@ -4,8 +4,13 @@
 
 mod advertise;
 mod discovery;
+pub(crate) mod privacy;
 
 pub use advertise::{
     MdnsAdvertiser, MdnsServiceConfig, SERVICE_TYPE, TXT_DEVICE_ID, TXT_PUBKEY_HASH,
 };
-pub use discovery::{DiscoveredDevice, DiscoveryEvent, MdnsDiscovery};
+pub use discovery::{DiscoveredDevice, DiscoveryEvent, DiscoverySink, MdnsDiscovery};
+pub use privacy::{
+    current_epoch, DiscoveryKey, PrivateAdvertising, PrivateTokenResolver,
+    DEFAULT_ROTATION_PERIOD_SECS, MAX_PRIVATE_TOKENS, TXT_PRIVATE_TOKENS,
+};
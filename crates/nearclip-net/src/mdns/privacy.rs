@@ -0,0 +1,375 @@
+//! mDNS 隐私发现模块
+//!
+//! 默认的 mDNS 广播在 TXT 记录中明文发布设备 ID 和公钥哈希，
+//! 同一网络中的任何人都可以据此长期追踪设备。隐私模式下广播方只发布
+//! 轮换令牌：
+//!
+//! ```text
+//! token = HMAC-SHA256(discovery_key, "nearclip-mdns" || device_id || epoch)[..8]
+//! epoch = unix_time / rotation_period
+//! ```
+//!
+//! 每个配对关系一个发现密钥（由配对共享密钥派生），广播方为每个已配对设备
+//! 各发布一个令牌。发现方用本地已配对设备列表逐一计算并比对令牌，
+//! 未配对的观察者只能看到每个周期都会变化的随机值。
+
+use crate::error::NetError;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub(crate) type HmacSha256 = Hmac<Sha256>;
+
+/// TXT 记录键：隐私模式令牌列表
+pub const TXT_PRIVATE_TOKENS: &str = "t";
+
+/// 默认令牌轮换周期（15 分钟）
+pub const DEFAULT_ROTATION_PERIOD_SECS: u64 = 15 * 60;
+
+/// 单个令牌长度（字节，编码后为两倍长度的十六进制）
+pub const TOKEN_LEN: usize = 8;
+
+/// 单条 TXT 记录最多容纳的令牌数
+///
+/// `t=` 前缀 2 字节，每个令牌 16 个十六进制字符加 1 个分隔符，
+/// 单条 TXT 记录不超过 255 字节。
+pub const MAX_PRIVATE_TOKENS: usize = 14;
+
+/// 令牌计算的域分隔前缀
+const TOKEN_DOMAIN: &[u8] = b"nearclip-mdns";
+
+/// 发现密钥派生的域分隔前缀
+const KEY_DOMAIN: &[u8] = b"nearclip-mdns-discovery-key";
+
+/// 令牌分隔符
+const TOKEN_SEPARATOR: char = ',';
+
+/// 配对关系的发现密钥
+///
+/// 双方从同一个配对共享密钥派生出相同的发现密钥。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::DiscoveryKey;
+///
+/// let a = DiscoveryKey::derive(b"shared pairing secret");
+/// let b = DiscoveryKey::derive(b"shared pairing secret");
+/// assert_eq!(a, b);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct DiscoveryKey([u8; 32]);
+
+impl DiscoveryKey {
+    /// 从原始字节创建
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// 从配对共享密钥派生发现密钥
+    ///
+    /// 使用独立的域分隔前缀，发现密钥泄露不会影响配对共享密钥本身。
+    pub fn derive(pairing_secret: &[u8]) -> Self {
+        let mut mac = HmacSha256::new_from_slice(pairing_secret).expect("HMAC accepts any key length");
+        mac.update(KEY_DOMAIN);
+        Self(mac.finalize().into_bytes().into())
+    }
+
+    /// 计算设备在指定周期的令牌（十六进制）
+    pub fn token(&self, device_id: &str, epoch: u64) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.0).expect("HMAC accepts any key length");
+        mac.update(TOKEN_DOMAIN);
+        mac.update(device_id.as_bytes());
+        mac.update(&epoch.to_be_bytes());
+        hex::encode(&mac.finalize().into_bytes()[..TOKEN_LEN])
+    }
+
+    /// 以发现密钥为键的 HMAC 实例（供其他发现方式复用）
+    pub(crate) fn hmac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.0).expect("HMAC accepts any key length")
+    }
+}
+
+impl std::fmt::Debug for DiscoveryKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密钥内容
+        f.write_str("DiscoveryKey(..)")
+    }
+}
+
+/// 计算当前时间所在的轮换周期
+pub fn current_epoch(rotation_period: Duration) -> u64 {
+    epoch_at(SystemTime::now(), rotation_period)
+}
+
+/// 计算指定时间所在的轮换周期
+pub fn epoch_at(time: SystemTime, rotation_period: Duration) -> u64 {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    secs / rotation_period.as_secs().max(1)
+}
+
+/// 距离下一个轮换周期开始的时间
+pub fn until_next_epoch(rotation_period: Duration) -> Duration {
+    let period = rotation_period.as_secs().max(1);
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let elapsed_in_epoch = Duration::from_secs(now.as_secs() % period) + Duration::from_nanos(now.subsec_nanos() as u64);
+    Duration::from_secs(period).saturating_sub(elapsed_in_epoch)
+}
+
+/// 隐私广播配置
+///
+/// 启用后 TXT 记录只包含轮换令牌，服务实例名和主机名每个周期随机更换。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::{DiscoveryKey, PrivateAdvertising};
+/// use std::time::Duration;
+///
+/// let privacy = PrivateAdvertising::new(vec![DiscoveryKey::derive(b"secret")])
+///     .with_rotation_period(Duration::from_secs(600));
+/// assert_eq!(privacy.keys().len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrivateAdvertising {
+    /// 每个已配对设备的发现密钥
+    keys: Vec<DiscoveryKey>,
+    /// 令牌轮换周期
+    rotation_period: Duration,
+}
+
+impl PrivateAdvertising {
+    /// 创建隐私广播配置
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - 每个已配对设备的发现密钥
+    pub fn new(keys: Vec<DiscoveryKey>) -> Self {
+        Self {
+            keys,
+            rotation_period: Duration::from_secs(DEFAULT_ROTATION_PERIOD_SECS),
+        }
+    }
+
+    /// 设置令牌轮换周期
+    pub fn with_rotation_period(mut self, period: Duration) -> Self {
+        self.rotation_period = period;
+        self
+    }
+
+    /// 获取发现密钥
+    pub fn keys(&self) -> &[DiscoveryKey] {
+        &self.keys
+    }
+
+    /// 获取轮换周期
+    pub fn rotation_period(&self) -> Duration {
+        self.rotation_period
+    }
+
+    /// 验证配置
+    pub fn validate(&self) -> Result<(), NetError> {
+        if self.rotation_period.as_secs() == 0 {
+            return Err(NetError::Configuration(
+                "rotation_period must be at least 1 second".to_string(),
+            ));
+        }
+        if self.keys.len() > MAX_PRIVATE_TOKENS {
+            return Err(NetError::Configuration(format!(
+                "private advertising supports at most {} paired devices, got {}",
+                MAX_PRIVATE_TOKENS,
+                self.keys.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// 构建指定周期的令牌列表 TXT 值
+    pub(crate) fn tokens_value(&self, device_id: &str, epoch: u64) -> String {
+        self.keys
+            .iter()
+            .map(|key| key.token(device_id, epoch))
+            .collect::<Vec<_>>()
+            .join(&TOKEN_SEPARATOR.to_string())
+    }
+}
+
+/// 隐私令牌解析器
+///
+/// 由发现方持有，用本地已配对设备的发现密钥把令牌还原为设备 ID。
+/// 为容忍设备间的时钟偏差，会同时比对前后相邻周期的令牌。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::{current_epoch, DiscoveryKey, PrivateTokenResolver};
+/// use std::time::Duration;
+///
+/// let key = DiscoveryKey::derive(b"secret");
+/// let period = Duration::from_secs(900);
+/// let token = key.token("device-a", current_epoch(period));
+///
+/// let mut resolver = PrivateTokenResolver::new(period);
+/// resolver.add_peer("device-a", key);
+/// assert_eq!(resolver.resolve(&token), Some("device-a".to_string()));
+/// assert_eq!(resolver.resolve("0011223344556677"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrivateTokenResolver {
+    /// (设备 ID, 发现密钥)
+    peers: Vec<(String, DiscoveryKey)>,
+    /// 令牌轮换周期
+    rotation_period: Duration,
+}
+
+impl PrivateTokenResolver {
+    /// 创建空解析器
+    pub fn new(rotation_period: Duration) -> Self {
+        Self {
+            peers: Vec::new(),
+            rotation_period,
+        }
+    }
+
+    /// 添加已配对设备（同一设备重复添加时替换密钥）
+    pub fn add_peer(&mut self, device_id: impl Into<String>, key: DiscoveryKey) {
+        let device_id = device_id.into();
+        self.peers.retain(|(id, _)| *id != device_id);
+        self.peers.push((device_id, key));
+    }
+
+    /// 移除已配对设备
+    pub fn remove_peer(&mut self, device_id: &str) {
+        self.peers.retain(|(id, _)| id != device_id);
+    }
+
+    /// 已配对设备数
+    pub fn peer_count(&self) -> usize {
+        self.peers.len()
+    }
+
+    /// 解析 TXT 令牌列表
+    ///
+    /// # Returns
+    ///
+    /// 匹配到的已配对设备 ID；没有匹配时返回 None
+    pub fn resolve(&self, tokens: &str) -> Option<String> {
+        self.resolve_at(tokens, current_epoch(self.rotation_period))
+    }
+
+    /// 以指定周期为基准解析令牌列表
+    pub fn resolve_at(&self, tokens: &str, epoch: u64) -> Option<String> {
+        let tokens: Vec<&str> = tokens
+            .split(TOKEN_SEPARATOR)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let epochs = [epoch, epoch.saturating_sub(1), epoch.saturating_add(1)];
+        self.peers.iter().find_map(|(device_id, key)| {
+            epochs
+                .iter()
+                .any(|&e| {
+                    let expected = key.token(device_id, e);
+                    tokens.iter().any(|t| *t == expected)
+                })
+                .then(|| device_id.clone())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discovery_key_derive_deterministic() {
+        assert_eq!(DiscoveryKey::derive(b"secret"), DiscoveryKey::derive(b"secret"));
+        assert_ne!(DiscoveryKey::derive(b"secret"), DiscoveryKey::derive(b"other"));
+    }
+
+    #[test]
+    fn test_discovery_key_debug_hides_bytes() {
+        let key = DiscoveryKey::from_bytes([7u8; 32]);
+        assert_eq!(format!("{:?}", key), "DiscoveryKey(..)");
+    }
+
+    #[test]
+    fn test_token_rotates_and_binds_device() {
+        let key = DiscoveryKey::derive(b"secret");
+        let token = key.token("device-a", 100);
+
+        assert_eq!(token.len(), TOKEN_LEN * 2);
+        assert_eq!(token, key.token("device-a", 100));
+        assert_ne!(token, key.token("device-a", 101));
+        assert_ne!(token, key.token("device-b", 100));
+    }
+
+    #[test]
+    fn test_epoch_at() {
+        let period = Duration::from_secs(900);
+        let t = UNIX_EPOCH + Duration::from_secs(900 * 5 + 10);
+        assert_eq!(epoch_at(t, period), 5);
+        assert!(until_next_epoch(period) <= period);
+    }
+
+    #[test]
+    fn test_private_advertising_validate() {
+        assert!(PrivateAdvertising::new(vec![]).validate().is_ok());
+
+        let zero = PrivateAdvertising::new(vec![]).with_rotation_period(Duration::ZERO);
+        assert!(zero.validate().is_err());
+
+        let keys = (0..=MAX_PRIVATE_TOKENS)
+            .map(|i| DiscoveryKey::from_bytes([i as u8; 32]))
+            .collect();
+        let too_many = PrivateAdvertising::new(keys);
+        assert!(too_many.validate().unwrap_err().to_string().contains("at most"));
+    }
+
+    #[test]
+    fn test_tokens_value_fits_txt_record() {
+        let keys = (0..MAX_PRIVATE_TOKENS)
+            .map(|i| DiscoveryKey::from_bytes([i as u8; 32]))
+            .collect();
+        let privacy = PrivateAdvertising::new(keys);
+        let value = privacy.tokens_value("device-a", 1);
+        assert!(TXT_PRIVATE_TOKENS.len() + 1 + value.len() <= 255);
+        assert_eq!(value.split(TOKEN_SEPARATOR).count(), MAX_PRIVATE_TOKENS);
+    }
+
+    #[test]
+    fn test_resolver_matches_any_listed_token() {
+        let key_a = DiscoveryKey::derive(b"secret-a");
+        let key_b = DiscoveryKey::derive(b"secret-b");
+        let privacy = PrivateAdvertising::new(vec![key_b.clone(), key_a.clone()]);
+        let value = privacy.tokens_value("device-x", 42);
+
+        let mut resolver = PrivateTokenResolver::new(Duration::from_secs(900));
+        resolver.add_peer("device-x", key_a);
+        assert_eq!(resolver.resolve_at(&value, 42), Some("device-x".to_string()));
+
+        // 相邻周期容忍时钟偏差，更远的周期不匹配
+        assert_eq!(resolver.resolve_at(&value, 43), Some("device-x".to_string()));
+        assert_eq!(resolver.resolve_at(&value, 41), Some("device-x".to_string()));
+        assert_eq!(resolver.resolve_at(&value, 44), None);
+    }
+
+    #[test]
+    fn test_resolver_rejects_unknown_and_own_tokens() {
+        let key = DiscoveryKey::derive(b"secret");
+        let mut resolver = PrivateTokenResolver::new(Duration::from_secs(900));
+        resolver.add_peer("device-b", key.clone());
+
+        // 本设备用同一密钥广播的令牌不会被解析为对端
+        assert_eq!(resolver.resolve_at(&key.token("device-a", 7), 7), None);
+        assert_eq!(resolver.resolve_at("", 7), None);
+
+        resolver.remove_peer("device-b");
+        assert_eq!(resolver.peer_count(), 0);
+        assert_eq!(resolver.resolve_at(&key.token("device-b", 7), 7), None);
+    }
+}
@@ -0,0 +1,189 @@
+//! 网络接口过滤
+
+use crate::netif::InterfaceAddress;
+
+/// 常见 VPN、隧道和虚拟网卡的接口名前缀
+const VIRTUAL_INTERFACE_PREFIXES: &[&str] = &[
+    "utun", "tun", "tap", "wg", "ppp", "ipsec", "gif", "stf", "zt", "tailscale", "docker", "veth",
+    "br-", "virbr", "vmnet", "vboxnet", "vnic", "vEthernet", "awdl", "llw", "anpi",
+];
+
+/// 接口名是否属于 VPN、隧道或虚拟网卡
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::is_virtual_interface;
+///
+/// assert!(is_virtual_interface("utun3"));
+/// assert!(is_virtual_interface("docker0"));
+/// assert!(!is_virtual_interface("en0"));
+/// assert!(!is_virtual_interface("wlan0"));
+/// ```
+pub fn is_virtual_interface(name: &str) -> bool {
+    VIRTUAL_INTERFACE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+/// 网络接口过滤器
+///
+/// 名称支持精确匹配或以 `*` 结尾的前缀匹配（如 `en*`）。
+/// 未指定允许列表时允许所有接口；排除列表优先于允许列表。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::InterfaceFilter;
+///
+/// let filter = InterfaceFilter::new()
+///     .with_allowed_interface("en*")
+///     .with_excluded_interface("en5")
+///     .with_exclude_virtual(true);
+///
+/// assert!(filter.allows_name("en0"));
+/// assert!(!filter.allows_name("en5"));
+/// assert!(!filter.allows_name("wlan0"));
+/// assert!(!filter.allows_name("utun2"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InterfaceFilter {
+    /// 允许的接口（为空表示全部）
+    allowed: Vec<String>,
+    /// 排除的接口
+    excluded: Vec<String>,
+    /// 是否排除 VPN 和虚拟网卡
+    exclude_virtual: bool,
+}
+
+impl InterfaceFilter {
+    /// 创建不做任何限制的过滤器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加允许的接口
+    pub fn with_allowed_interface(mut self, pattern: impl Into<String>) -> Self {
+        self.allowed.push(pattern.into());
+        self
+    }
+
+    /// 添加排除的接口
+    pub fn with_excluded_interface(mut self, pattern: impl Into<String>) -> Self {
+        self.excluded.push(pattern.into());
+        self
+    }
+
+    /// 设置是否排除 VPN 和虚拟网卡
+    pub fn with_exclude_virtual(mut self, exclude: bool) -> Self {
+        self.exclude_virtual = exclude;
+        self
+    }
+
+    /// 允许的接口
+    pub fn allowed(&self) -> &[String] {
+        &self.allowed
+    }
+
+    /// 排除的接口
+    pub fn excluded(&self) -> &[String] {
+        &self.excluded
+    }
+
+    /// 是否排除 VPN 和虚拟网卡
+    pub fn excludes_virtual(&self) -> bool {
+        self.exclude_virtual
+    }
+
+    /// 是否有任何限制
+    pub fn is_restricted(&self) -> bool {
+        !self.allowed.is_empty() || !self.excluded.is_empty() || self.exclude_virtual
+    }
+
+    /// 是否允许指定名称的接口
+    pub fn allows_name(&self, name: &str) -> bool {
+        if self.excluded.iter().any(|p| matches_pattern(p, name)) {
+            return false;
+        }
+        if self.exclude_virtual && is_virtual_interface(name) {
+            return false;
+        }
+        self.allowed.is_empty() || self.allowed.iter().any(|p| matches_pattern(p, name))
+    }
+
+    /// 是否允许指定接口地址
+    pub fn allows(&self, address: &InterfaceAddress) -> bool {
+        !address.ip.is_loopback() && self.allows_name(&address.name)
+    }
+
+    /// 过滤接口地址列表
+    pub fn apply(&self, addresses: &[InterfaceAddress]) -> Vec<InterfaceAddress> {
+        addresses.iter().filter(|a| self.allows(a)).cloned().collect()
+    }
+
+    /// 列表中被排除的接口名（去重）
+    pub fn rejected_names(&self, addresses: &[InterfaceAddress]) -> Vec<String> {
+        let mut names: Vec<String> = addresses
+            .iter()
+            .filter(|a| !a.ip.is_loopback() && !self.allows_name(&a.name))
+            .map(|a| a.name.clone())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+}
+
+fn matches_pattern(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::IpAddr;
+
+    fn address(name: &str, ip: &str) -> InterfaceAddress {
+        InterfaceAddress {
+            name: name.to_string(),
+            ip: ip.parse::<IpAddr>().unwrap(),
+            prefix_len: 24,
+            broadcast: None,
+        }
+    }
+
+    #[test]
+    fn test_default_filter_allows_everything_but_loopback() {
+        let filter = InterfaceFilter::new();
+        assert!(!filter.is_restricted());
+        assert!(filter.allows(&address("utun0", "10.8.0.2")));
+        assert!(!filter.allows(&address("lo", "127.0.0.1")));
+    }
+
+    #[test]
+    fn test_apply_and_rejected_names() {
+        let filter = InterfaceFilter::new().with_exclude_virtual(true);
+        let addresses = vec![
+            address("en0", "192.168.1.5"),
+            address("en0", "fd00::5"),
+            address("utun3", "10.8.0.2"),
+            address("utun3", "fd10::2"),
+        ];
+
+        let allowed = filter.apply(&addresses);
+        assert_eq!(allowed.len(), 2);
+        assert!(allowed.iter().all(|a| a.name == "en0"));
+        assert_eq!(filter.rejected_names(&addresses), vec!["utun3".to_string()]);
+    }
+
+    #[test]
+    fn test_exact_and_prefix_patterns() {
+        assert!(matches_pattern("en0", "en0"));
+        assert!(!matches_pattern("en0", "en01"));
+        assert!(matches_pattern("wl*", "wlp3s0"));
+        assert!(matches_pattern("*", "anything"));
+    }
+}
@@ -0,0 +1,73 @@
+//! 网络接口模块
+//!
+//! 笔记本在不同网络间切换、VPN 连接或断开时，本机地址会发生变化：
+//! mDNS 广播携带过期地址，已有连接也会悄无声息地失效。本模块提供：
+//!
+//! - [`InterfaceFilter`] 选择参与广播和发现的接口（指定接口、排除 VPN/虚拟网卡）
+//! - [`InterfaceMonitor`] 监听接口地址变化（Linux 使用 netlink 通知，其他平台轮询）
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nearclip_net::{InterfaceFilter, InterfaceMonitor};
+//!
+//! # async fn example() -> Result<(), nearclip_net::NetError> {
+//! let filter = InterfaceFilter::new().with_exclude_virtual(true);
+//! let mut monitor = InterfaceMonitor::new(filter);
+//! let mut changes = monitor.subscribe();
+//! monitor.start()?;
+//!
+//! while let Ok(change) = changes.recv().await {
+//!     println!("added: {:?}, removed: {:?}", change.added, change.removed);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod filter;
+mod monitor;
+
+use crate::error::NetError;
+use std::net::{IpAddr, Ipv4Addr};
+
+pub use filter::{is_virtual_interface, InterfaceFilter};
+pub use monitor::{InterfaceChange, InterfaceMonitor, DEFAULT_INTERFACE_POLL_INTERVAL_SECS};
+
+/// 网络接口上的一个地址
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct InterfaceAddress {
+    /// 接口名（如 `en0`、`wlan0`）
+    pub name: String,
+    /// 地址
+    pub ip: IpAddr,
+    /// 前缀长度
+    pub prefix_len: u8,
+    /// IPv4 子网广播地址
+    pub broadcast: Option<Ipv4Addr>,
+}
+
+/// 列出本机所有非回环接口地址
+pub fn list_interfaces() -> Result<Vec<InterfaceAddress>, NetError> {
+    let interfaces = if_addrs::get_if_addrs()?;
+    let mut addresses: Vec<InterfaceAddress> = interfaces
+        .into_iter()
+        .filter(|iface| !iface.is_loopback())
+        .map(|iface| match iface.addr {
+            if_addrs::IfAddr::V4(v4) => InterfaceAddress {
+                name: iface.name,
+                ip: IpAddr::V4(v4.ip),
+                prefix_len: v4.prefixlen,
+                broadcast: v4.broadcast,
+            },
+            if_addrs::IfAddr::V6(v6) => InterfaceAddress {
+                name: iface.name,
+                ip: IpAddr::V6(v6.ip),
+                prefix_len: v6.prefixlen,
+                broadcast: None,
+            },
+        })
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    Ok(addresses)
+}
@@ -0,0 +1,370 @@
+//! 网络接口监视器
+//!
+//! 定期（Linux 上还会在收到 netlink 通知时）重新枚举接口地址，
+//! 与上一次结果比较，有变化时广播 [`InterfaceChange`]。
+
+use crate::error::NetError;
+use crate::netif::{list_interfaces, InterfaceAddress, InterfaceFilter};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::{broadcast, Notify};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// 默认轮询间隔（5 秒）
+pub const DEFAULT_INTERFACE_POLL_INTERVAL_SECS: u64 = 5;
+
+/// 默认去抖时间：收到通知后等待地址配置稳定再枚举
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 事件广播通道容量
+const EVENT_CHANNEL_CAPACITY: usize = 16;
+
+/// 接口地址枚举函数
+type InterfaceSource = Arc<dyn Fn() -> Result<Vec<InterfaceAddress>, NetError> + Send + Sync>;
+
+/// 接口地址变化
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceChange {
+    /// 新增的地址
+    pub added: Vec<InterfaceAddress>,
+    /// 移除的地址
+    pub removed: Vec<InterfaceAddress>,
+    /// 变化后的全部地址
+    pub current: Vec<InterfaceAddress>,
+}
+
+impl InterfaceChange {
+    /// 比较两次枚举结果，没有变化时返回 None
+    fn between(previous: &[InterfaceAddress], current: &[InterfaceAddress]) -> Option<Self> {
+        let before: BTreeSet<&InterfaceAddress> = previous.iter().collect();
+        let after: BTreeSet<&InterfaceAddress> = current.iter().collect();
+        let added: Vec<InterfaceAddress> = after.difference(&before).map(|a| (*a).clone()).collect();
+        let removed: Vec<InterfaceAddress> = before.difference(&after).map(|a| (*a).clone()).collect();
+        if added.is_empty() && removed.is_empty() {
+            return None;
+        }
+        Some(Self {
+            added,
+            removed,
+            current: current.to_vec(),
+        })
+    }
+}
+
+/// 监视器共享状态
+struct MonitorState {
+    filter: InterfaceFilter,
+    source: InterfaceSource,
+    current: RwLock<Vec<InterfaceAddress>>,
+    event_tx: broadcast::Sender<InterfaceChange>,
+}
+
+impl MonitorState {
+    /// 重新枚举接口，有变化时更新快照并广播
+    fn check(&self) -> Result<Option<InterfaceChange>, NetError> {
+        let mut addresses = self.filter.apply(&(self.source)()?);
+        addresses.sort();
+        addresses.dedup();
+
+        let mut current = self.current.write().unwrap();
+        let change = InterfaceChange::between(&current, &addresses);
+        if let Some(ref change) = change {
+            *current = addresses;
+            drop(current);
+            info!(
+                added = ?change.added.iter().map(|a| a.ip).collect::<Vec<_>>(),
+                removed = ?change.removed.iter().map(|a| a.ip).collect::<Vec<_>>(),
+                "Network interfaces changed"
+            );
+            let _ = self.event_tx.send(change.clone());
+        }
+        Ok(change)
+    }
+}
+
+/// 网络接口监视器
+///
+/// 只报告通过 [`InterfaceFilter`] 的接口地址。启动时的初始枚举不产生事件。
+pub struct InterfaceMonitor {
+    /// 共享状态
+    state: Arc<MonitorState>,
+    /// 轮询间隔
+    poll_interval: Duration,
+    /// 去抖时间
+    debounce: Duration,
+    /// 立即检查信号
+    trigger: Arc<Notify>,
+    /// 监视任务
+    task: Option<JoinHandle<()>>,
+    /// 系统通知线程的停止标志
+    watcher_stop: Option<Arc<AtomicBool>>,
+}
+
+impl InterfaceMonitor {
+    /// 创建监视器
+    pub fn new(filter: InterfaceFilter) -> Self {
+        Self::with_source(filter, Arc::new(list_interfaces))
+    }
+
+    /// 使用自定义枚举函数创建监视器
+    fn with_source(filter: InterfaceFilter, source: InterfaceSource) -> Self {
+        let (event_tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            state: Arc::new(MonitorState {
+                filter,
+                source,
+                current: RwLock::new(Vec::new()),
+                event_tx,
+            }),
+            poll_interval: Duration::from_secs(DEFAULT_INTERFACE_POLL_INTERVAL_SECS),
+            debounce: DEFAULT_DEBOUNCE,
+            trigger: Arc::new(Notify::new()),
+            task: None,
+            watcher_stop: None,
+        }
+    }
+
+    /// 设置轮询间隔
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// 设置去抖时间
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// 获取过滤器
+    pub fn filter(&self) -> &InterfaceFilter {
+        &self.state.filter
+    }
+
+    /// 订阅接口变化事件
+    pub fn subscribe(&self) -> broadcast::Receiver<InterfaceChange> {
+        self.state.event_tx.subscribe()
+    }
+
+    /// 当前通过过滤器的接口地址
+    pub fn current(&self) -> Vec<InterfaceAddress> {
+        self.state.current.read().unwrap().clone()
+    }
+
+    /// 开始监视
+    ///
+    /// 立即枚举一次作为基准，然后周期性检查；Linux 上还会订阅 netlink
+    /// 地址变化通知，变化发生后去抖再检查。
+    pub fn start(&mut self) -> Result<(), NetError> {
+        if self.task.is_some() {
+            warn!("Interface monitor already running");
+            return Ok(());
+        }
+        if self.poll_interval.is_zero() {
+            return Err(NetError::Configuration("poll_interval cannot be 0".to_string()));
+        }
+
+        // 初始快照（不产生事件）
+        {
+            let mut addresses = self.state.filter.apply(&(self.state.source)()?);
+            addresses.sort();
+            addresses.dedup();
+            *self.state.current.write().unwrap() = addresses;
+        }
+
+        self.watcher_stop = spawn_os_watcher(self.trigger.clone());
+
+        let state = self.state.clone();
+        let trigger = self.trigger.clone();
+        let poll_interval = self.poll_interval;
+        let debounce = self.debounce;
+        self.task = Some(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(poll_interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            ticker.tick().await;
+            loop {
+                tokio::select! {
+                    _ = ticker.tick() => {}
+                    _ = trigger.notified() => {
+                        tokio::time::sleep(debounce).await;
+                    }
+                }
+                if let Err(e) = state.check() {
+                    debug!(error = %e, "Failed to enumerate network interfaces");
+                }
+            }
+        }));
+
+        info!(
+            addresses = self.state.current.read().unwrap().len(),
+            os_notifications = self.watcher_stop.is_some(),
+            "Interface monitor started"
+        );
+        Ok(())
+    }
+
+    /// 立即检查接口变化
+    ///
+    /// 平台层收到系统网络变化通知时可以调用，不必等待下一次轮询。
+    pub fn check_now(&self) -> Result<Option<InterfaceChange>, NetError> {
+        self.state.check()
+    }
+
+    /// 请求监视任务尽快检查（去抖后执行）
+    pub fn notify_changed(&self) {
+        self.trigger.notify_one();
+    }
+
+    /// 停止监视
+    pub fn stop(&mut self) {
+        if let Some(stop) = self.watcher_stop.take() {
+            stop.store(true, Ordering::Release);
+        }
+        if let Some(task) = self.task.take() {
+            task.abort();
+            info!("Interface monitor stopped");
+        }
+    }
+
+    /// 是否正在监视
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+impl Drop for InterfaceMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// 启动系统接口变化通知线程（netlink），返回停止标志
+///
+/// 通知只用于唤醒监视任务，具体变化仍由重新枚举得出。
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn spawn_os_watcher(trigger: Arc<Notify>) -> Option<Arc<AtomicBool>> {
+    let mut notifier = match if_addrs::IfChangeNotifier::new() {
+        Ok(notifier) => notifier,
+        Err(e) => {
+            warn!(error = %e, "Netlink notifications unavailable, falling back to polling");
+            return None;
+        }
+    };
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let stop_for_thread = stop.clone();
+    let spawned = std::thread::Builder::new()
+        .name("nearclip-netif".to_string())
+        .spawn(move || {
+            while !stop_for_thread.load(Ordering::Acquire) {
+                match notifier.wait(Some(Duration::from_secs(1))) {
+                    Ok(_) => trigger.notify_one(),
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                    Err(e) => {
+                        warn!(error = %e, "Netlink notifier failed, falling back to polling");
+                        break;
+                    }
+                }
+            }
+        });
+    match spawned {
+        Ok(_) => Some(stop),
+        Err(e) => {
+            warn!(error = %e, "Failed to spawn netlink watcher thread");
+            None
+        }
+    }
+}
+
+/// 其他平台只使用轮询
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn spawn_os_watcher(_trigger: Arc<Notify>) -> Option<Arc<AtomicBool>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::IpAddr;
+    use std::sync::Mutex;
+
+    fn address(name: &str, ip: &str) -> InterfaceAddress {
+        InterfaceAddress {
+            name: name.to_string(),
+            ip: ip.parse::<IpAddr>().unwrap(),
+            prefix_len: 24,
+            broadcast: None,
+        }
+    }
+
+    /// 返回可控接口列表的监视器
+    fn scripted_monitor(filter: InterfaceFilter) -> (InterfaceMonitor, Arc<Mutex<Vec<InterfaceAddress>>>) {
+        let addresses = Arc::new(Mutex::new(vec![address("en0", "192.168.1.5")]));
+        let source_addresses = addresses.clone();
+        let monitor = InterfaceMonitor::with_source(
+            filter,
+            Arc::new(move || Ok(source_addresses.lock().unwrap().clone())),
+        );
+        (monitor, addresses)
+    }
+
+    #[test]
+    fn test_change_between() {
+        let before = vec![address("en0", "192.168.1.5")];
+        let after = vec![address("en0", "10.0.0.7")];
+
+        assert_eq!(InterfaceChange::between(&before, &before), None);
+        let change = InterfaceChange::between(&before, &after).unwrap();
+        assert_eq!(change.added, after);
+        assert_eq!(change.removed, before);
+    }
+
+    #[tokio::test]
+    async fn test_check_now_reports_changes_once() {
+        let (mut monitor, addresses) = scripted_monitor(InterfaceFilter::new());
+        let mut events = monitor.subscribe();
+        monitor.start().unwrap();
+        assert_eq!(monitor.current(), vec![address("en0", "192.168.1.5")]);
+
+        // 无变化
+        assert_eq!(monitor.check_now().unwrap(), None);
+
+        *addresses.lock().unwrap() = vec![address("en0", "10.0.0.7")];
+        let change = monitor.check_now().unwrap().unwrap();
+        assert_eq!(change.added, vec![address("en0", "10.0.0.7")]);
+        assert_eq!(events.try_recv().unwrap(), change);
+        assert_eq!(monitor.current(), vec![address("en0", "10.0.0.7")]);
+
+        monitor.stop();
+        assert!(!monitor.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_filtered_interfaces_do_not_trigger_changes() {
+        let (mut monitor, addresses) = scripted_monitor(InterfaceFilter::new().with_exclude_virtual(true));
+        monitor.start().unwrap();
+
+        // VPN 连接不影响通过过滤器的接口
+        addresses.lock().unwrap().push(address("utun4", "10.8.0.2"));
+        assert_eq!(monitor.check_now().unwrap(), None);
+        assert_eq!(monitor.current().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_polling_detects_changes() {
+        let (monitor, addresses) = scripted_monitor(InterfaceFilter::new());
+        let mut monitor = monitor.with_poll_interval(Duration::from_millis(20));
+        let mut events = monitor.subscribe();
+        monitor.start().unwrap();
+
+        addresses.lock().unwrap().push(address("wlan0", "172.16.0.9"));
+        let change = tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .expect("change should be detected by polling")
+            .unwrap();
+        assert_eq!(change.added, vec![address("wlan0", "172.16.0.9")]);
+    }
+}
@@ -0,0 +1,182 @@
+//! QUIC 客户端
+//!
+//! 持有一个长期存在的 quinn 端点。TLS 会话票据缓存在端点的 TLS 配置中，
+//! 因此同一个 [`QuicClient`] 再次连接同一服务端时可以使用 0-RTT。
+
+use crate::NetError;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+use tracing::{debug, info, instrument, warn};
+
+use super::server::transport_config;
+use super::{QuicConnection, DEFAULT_QUIC_IDLE_TIMEOUT_SECS, QUIC_ALPN};
+
+/// 默认连接超时时间（10 秒）
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// QUIC 客户端配置
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::quic::QuicClientConfig;
+/// use std::time::Duration;
+///
+/// let config = QuicClientConfig::new().with_timeout(Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct QuicClientConfig {
+    /// 本地绑定地址（端口动态分配）
+    pub bind_addr: IpAddr,
+    /// 连接超时时间（不使用 0-RTT 时等待握手完成的时间）
+    pub connect_timeout: Duration,
+    /// 连接空闲超时
+    pub idle_timeout: Duration,
+}
+
+impl Default for QuicClientConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            idle_timeout: Duration::from_secs(DEFAULT_QUIC_IDLE_TIMEOUT_SECS),
+        }
+    }
+}
+
+impl QuicClientConfig {
+    /// 创建默认配置
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置本地绑定地址
+    pub fn with_bind_addr(mut self, addr: IpAddr) -> Self {
+        self.bind_addr = addr;
+        self
+    }
+
+    /// 设置连接超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// 设置连接空闲超时
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+}
+
+/// QUIC 客户端
+///
+/// 与 [`TcpClient`](crate::tcp::TcpClient) 使用相同的固定证书 TLS 配置。
+/// 首次连接完成完整握手并获得会话票据；之后的连接使用 0-RTT，
+/// 在握手完成前即可发送数据。
+pub struct QuicClient {
+    endpoint: quinn::Endpoint,
+    connect_timeout: Duration,
+}
+
+impl QuicClient {
+    /// 使用默认配置创建客户端
+    ///
+    /// 需要在 tokio 运行时中调用。
+    ///
+    /// # Arguments
+    ///
+    /// * `tls_config` - TLS 客户端配置（包含信任的服务端证书）
+    pub fn new(tls_config: Arc<rustls::ClientConfig>) -> Result<Self, NetError> {
+        Self::with_config(QuicClientConfig::default(), tls_config)
+    }
+
+    /// 使用指定配置创建客户端
+    pub fn with_config(
+        config: QuicClientConfig,
+        tls_config: Arc<rustls::ClientConfig>,
+    ) -> Result<Self, NetError> {
+        let mut tls = (*tls_config).clone();
+        tls.alpn_protocols = vec![QUIC_ALPN.to_vec()];
+        tls.enable_early_data = true;
+
+        let crypto = quinn::crypto::rustls::QuicClientConfig::try_from(tls)
+            .map_err(|e| NetError::Configuration(format!("Invalid QUIC TLS config: {}", e)))?;
+        let mut client_config = quinn::ClientConfig::new(Arc::new(crypto));
+        client_config.transport_config(Arc::new(transport_config(config.idle_timeout)?));
+
+        let mut endpoint = quinn::Endpoint::client(SocketAddr::new(config.bind_addr, 0))
+            .map_err(|e| NetError::ConnectionFailed(format!("Failed to bind QUIC endpoint: {}", e)))?;
+        endpoint.set_default_client_config(client_config);
+
+        Ok(Self {
+            endpoint,
+            connect_timeout: config.connect_timeout,
+        })
+    }
+
+    /// 连接到目标服务端
+    ///
+    /// 有可用的会话票据时立即返回 0-RTT 连接（[`QuicConnection::is_zero_rtt`]），
+    /// 否则等待完整握手完成。
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - 目标服务端地址
+    /// * `server_name` - 服务端名称（用于 TLS SNI 和证书验证）
+    ///
+    /// # Errors
+    ///
+    /// - `ConnectionTimeout` - 握手超时
+    /// - `ConnectionFailed` - 无法发起连接
+    /// - `TlsHandshake` - 握手失败（通常是证书不匹配）
+    #[instrument(skip(self))]
+    pub async fn connect(&self, addr: SocketAddr, server_name: &str) -> Result<QuicConnection, NetError> {
+        let connecting = self.endpoint.connect(addr, server_name).map_err(|e| {
+            NetError::ConnectionFailed(format!("Failed to connect to {}: {}", addr, e))
+        })?;
+
+        match connecting.into_0rtt() {
+            Ok((conn, accepted)) => {
+                debug!("QUIC 0-RTT connection to {}", addr);
+                Ok(QuicConnection::new_client(conn, Some(accepted)))
+            }
+            Err(connecting) => {
+                let conn = timeout(self.connect_timeout, connecting)
+                    .await
+                    .map_err(|_| {
+                        NetError::ConnectionTimeout(format!(
+                            "Connection to {} timed out after {:?}",
+                            addr, self.connect_timeout
+                        ))
+                    })?
+                    .map_err(|e| {
+                        warn!("QUIC handshake failed with {}: {}", addr, e);
+                        NetError::TlsHandshake(format!("Handshake failed with {}: {}", addr, e))
+                    })?;
+                info!("QUIC connection established with {}", addr);
+                Ok(QuicConnection::new_client(conn, None))
+            }
+        }
+    }
+
+    /// 本地端点地址
+    pub fn local_addr(&self) -> Result<SocketAddr, NetError> {
+        self.endpoint.local_addr().map_err(NetError::Io)
+    }
+
+    /// 关闭端点，断开所有连接
+    pub fn close(&self) {
+        self.endpoint.close(0u32.into(), b"client closed");
+    }
+}
+
+impl std::fmt::Debug for QuicClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuicClient")
+            .field("local_addr", &self.endpoint.local_addr().ok())
+            .finish()
+    }
+}
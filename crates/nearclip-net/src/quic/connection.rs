@@ -0,0 +1,89 @@
+//! QUIC 连接封装
+
+use crate::tcp::ConnectionPermit;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// QUIC 连接
+///
+/// 包装 `quinn::Connection`，附带服务端连接许可和 0-RTT 状态。
+/// 流的打开和读写直接通过 [`QuicConnection::connection`] 进行。
+pub struct QuicConnection {
+    conn: quinn::Connection,
+    peer_addr: SocketAddr,
+    permit: Option<Arc<ConnectionPermit>>,
+    zero_rtt: Option<quinn::ZeroRttAccepted>,
+}
+
+impl QuicConnection {
+    /// 创建服务端连接对象（内部使用）
+    pub(crate) fn new(conn: quinn::Connection, permit: ConnectionPermit) -> Self {
+        Self {
+            peer_addr: conn.remote_address(),
+            conn,
+            permit: Some(Arc::new(permit)),
+            zero_rtt: None,
+        }
+    }
+
+    /// 创建客户端连接对象（内部使用）
+    ///
+    /// `zero_rtt` 为 Some 表示握手尚未完成，连接正在使用 0-RTT 数据。
+    pub(crate) fn new_client(conn: quinn::Connection, zero_rtt: Option<quinn::ZeroRttAccepted>) -> Self {
+        Self {
+            peer_addr: conn.remote_address(),
+            conn,
+            permit: None,
+            zero_rtt,
+        }
+    }
+
+    /// 底层 quinn 连接
+    pub fn connection(&self) -> &quinn::Connection {
+        &self.conn
+    }
+
+    /// 获取对端地址
+    pub fn peer_addr(&self) -> SocketAddr {
+        self.peer_addr
+    }
+
+    /// 服务端连接许可（客户端发起的连接为 None）
+    ///
+    /// 携带连接限制配置，可用于封禁行为异常的对端。
+    pub fn permit(&self) -> Option<&ConnectionPermit> {
+        self.permit.as_deref()
+    }
+
+    /// 共享的连接许可（供需要长期持有许可的上层使用）
+    pub fn shared_permit(&self) -> Option<Arc<ConnectionPermit>> {
+        self.permit.clone()
+    }
+
+    /// 连接是否以 0-RTT 建立且尚未确认
+    pub fn is_zero_rtt(&self) -> bool {
+        self.zero_rtt.is_some()
+    }
+
+    /// 取出 0-RTT 确认 future
+    ///
+    /// 握手完成后 resolve：`true` 表示服务端接受了 0-RTT 数据，
+    /// `false` 表示被拒绝，此前在 0-RTT 中发送的数据需要重发。
+    pub fn take_zero_rtt_accepted(&mut self) -> Option<quinn::ZeroRttAccepted> {
+        self.zero_rtt.take()
+    }
+
+    /// 关闭连接
+    pub fn close(&self, reason: &str) {
+        self.conn.close(0u32.into(), reason.as_bytes());
+    }
+}
+
+impl std::fmt::Debug for QuicConnection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuicConnection")
+            .field("peer_addr", &self.peer_addr)
+            .field("zero_rtt", &self.zero_rtt.is_some())
+            .finish()
+    }
+}
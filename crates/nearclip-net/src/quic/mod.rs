@@ -0,0 +1,48 @@
+//! QUIC 通信模块
+//!
+//! 基于 quinn 提供 QUIC 服务端和客户端，与 TCP/TLS 使用同一套固定证书
+//! （`TlsServerConfig` / `TlsClientConfig`）。相比 TCP：
+//!
+//! - 一条连接上可以并行打开多个流，控制消息和大块数据互不阻塞
+//! - 客户端复用同一个 [`QuicClient`] 重连时支持 0-RTT 恢复，首包即可携带数据
+//! - 连接不绑定四元组，网络切换后由 QUIC 自身完成迁移
+//!
+//! 服务端同样使用 [`ConnectionLimits`](crate::tcp::ConnectionLimits) 做每 IP
+//! 并发限制、握手超时和封禁。
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nearclip_net::quic::{QuicClient, QuicServer, QuicServerConfig};
+//! use nearclip_crypto::{TlsCertificate, TlsClientConfig, TlsServerConfig};
+//!
+//! # async fn example() -> Result<(), nearclip_net::NetError> {
+//! let cert = TlsCertificate::generate(&["localhost".to_string()]).unwrap();
+//!
+//! // 服务端
+//! let server_tls = TlsServerConfig::new(&cert).unwrap();
+//! let server = QuicServer::bind(QuicServerConfig::new(), server_tls.config())?;
+//! let addr = server.local_addr()?;
+//!
+//! // 客户端（保留实例以便后续 0-RTT 重连）
+//! let client_tls = TlsClientConfig::new(cert.cert_der()).unwrap();
+//! let client = QuicClient::new(client_tls.config())?;
+//! let conn = client.connect(addr, "localhost").await?;
+//! println!("Connected to {:?}", conn.peer_addr());
+//! # Ok(())
+//! # }
+//! ```
+
+mod client;
+mod connection;
+mod server;
+
+pub use client::{QuicClient, QuicClientConfig};
+pub use connection::QuicConnection;
+pub use server::{QuicServer, QuicServerConfig};
+
+/// QUIC ALPN 协议标识
+pub const QUIC_ALPN: &[u8] = b"nearclip/1";
+
+/// 默认连接空闲超时（秒）
+pub const DEFAULT_QUIC_IDLE_TIMEOUT_SECS: u64 = 30;
@@ -0,0 +1,252 @@
+//! QUIC 服务端
+//!
+//! 提供基于 quinn 的 QUIC 服务端，复用 TCP 服务端的连接防护。
+
+use crate::tcp::{ConnectionGuard, ConnectionLimits};
+use crate::NetError;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tracing::{debug, info, instrument, warn};
+
+use super::{QuicConnection, DEFAULT_QUIC_IDLE_TIMEOUT_SECS, QUIC_ALPN};
+
+/// QUIC 服务端配置
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::quic::QuicServerConfig;
+/// use std::time::Duration;
+///
+/// // 与 TCP 监听器使用相同的端口号（UDP）
+/// let config = QuicServerConfig::new()
+///     .with_port(8765)
+///     .with_idle_timeout(Duration::from_secs(60));
+/// ```
+#[derive(Debug, Clone)]
+pub struct QuicServerConfig {
+    /// 绑定地址
+    pub bind_addr: IpAddr,
+    /// 绑定端口（0 表示动态分配）
+    pub port: u16,
+    /// 连接空闲超时
+    pub idle_timeout: Duration,
+    /// 连接限制
+    pub limits: ConnectionLimits,
+}
+
+impl Default for QuicServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: 0,
+            idle_timeout: Duration::from_secs(DEFAULT_QUIC_IDLE_TIMEOUT_SECS),
+            limits: ConnectionLimits::default(),
+        }
+    }
+}
+
+impl QuicServerConfig {
+    /// 创建默认配置
+    ///
+    /// 默认绑定所有网络接口（0.0.0.0），端口动态分配。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置绑定端口
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// 设置绑定地址
+    pub fn with_bind_addr(mut self, addr: IpAddr) -> Self {
+        self.bind_addr = addr;
+        self
+    }
+
+    /// 设置连接空闲超时
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+
+    /// 设置连接限制
+    pub fn with_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 获取完整的 socket 地址
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.bind_addr, self.port)
+    }
+}
+
+/// 根据空闲超时创建 QUIC 传输参数
+pub(crate) fn transport_config(idle_timeout: Duration) -> Result<quinn::TransportConfig, NetError> {
+    let idle = quinn::IdleTimeout::try_from(idle_timeout)
+        .map_err(|e| NetError::Configuration(format!("Invalid idle timeout: {}", e)))?;
+    let mut transport = quinn::TransportConfig::default();
+    transport.max_idle_timeout(Some(idle));
+    Ok(transport)
+}
+
+/// QUIC 服务端
+///
+/// 使用与 [`TcpServer`](crate::tcp::TcpServer) 相同的 TLS 服务端配置，
+/// 额外开启 0-RTT（`max_early_data_size`）。握手在后台并发进行，
+/// 并且只有握手完成后才返回连接：0-RTT 数据会被缓存到握手确认之后才交给上层，
+/// 因此被重放的 0-RTT 数据包不会产生效果。
+pub struct QuicServer {
+    endpoint: quinn::Endpoint,
+    guard: ConnectionGuard,
+    handshakes: Mutex<JoinSet<Result<QuicConnection, NetError>>>,
+}
+
+impl QuicServer {
+    /// 创建并绑定 QUIC 服务端
+    ///
+    /// 需要在 tokio 运行时中调用。
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - 服务端配置
+    /// * `tls_config` - TLS 服务端配置（须支持 TLS 1.3）
+    #[instrument(skip(tls_config), fields(addr = %config.socket_addr()))]
+    pub fn bind(
+        config: QuicServerConfig,
+        tls_config: Arc<rustls::ServerConfig>,
+    ) -> Result<Self, NetError> {
+        let mut tls = (*tls_config).clone();
+        tls.alpn_protocols = vec![QUIC_ALPN.to_vec()];
+        tls.max_early_data_size = u32::MAX;
+
+        let crypto = quinn::crypto::rustls::QuicServerConfig::try_from(tls)
+            .map_err(|e| NetError::Configuration(format!("Invalid QUIC TLS config: {}", e)))?;
+        let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+        server_config.transport_config(Arc::new(transport_config(config.idle_timeout)?));
+
+        let endpoint = quinn::Endpoint::server(server_config, config.socket_addr()).map_err(|e| {
+            NetError::TcpServer(format!("Failed to bind QUIC endpoint to {}: {}", config.socket_addr(), e))
+        })?;
+        info!("QUIC server bound to {:?}", endpoint.local_addr());
+
+        Ok(Self {
+            endpoint,
+            guard: ConnectionGuard::new(config.limits),
+            handshakes: Mutex::new(JoinSet::new()),
+        })
+    }
+
+    /// 获取实际监听地址
+    pub fn local_addr(&self) -> Result<SocketAddr, NetError> {
+        self.endpoint.local_addr().map_err(|e| {
+            NetError::TcpServer(format!("Failed to get local address: {}", e))
+        })
+    }
+
+    /// 连接防护（可用于查询或手动封禁 IP）
+    pub fn guard(&self) -> &ConnectionGuard {
+        &self.guard
+    }
+
+    /// 接受新的客户端连接
+    ///
+    /// 阻塞直到有连接完成握手。被封禁或超过并发上限的连接直接拒绝，
+    /// 不会返回给调用方。
+    ///
+    /// # Returns
+    ///
+    /// 已完成握手的连接；握手失败或超时时返回错误，端点关闭时返回 `ConnectionClosed`
+    #[instrument(skip(self))]
+    pub async fn accept(&self) -> Result<QuicConnection, NetError> {
+        let mut handshakes = self.handshakes.lock().await;
+
+        loop {
+            let incoming = tokio::select! {
+                Some(finished) = handshakes.join_next(), if !handshakes.is_empty() => {
+                    match finished {
+                        Ok(result) => return result,
+                        Err(e) => {
+                            warn!("Handshake task failed: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                incoming = self.endpoint.accept() => incoming,
+            };
+
+            let incoming = incoming
+                .ok_or_else(|| NetError::ConnectionClosed("QUIC endpoint closed".to_string()))?;
+            let peer_addr = incoming.remote_address();
+
+            if handshakes.len() >= self.guard.limits().max_pending_handshakes {
+                debug!("Too many pending handshakes, refusing connection from {}", peer_addr);
+                incoming.refuse();
+                continue;
+            }
+
+            let permit = match self.guard.admit(peer_addr.ip()) {
+                Ok(permit) => permit,
+                Err(reason) => {
+                    debug!("Rejected connection from {}: {:?}", peer_addr, reason);
+                    incoming.refuse();
+                    continue;
+                }
+            };
+
+            let connecting = match incoming.accept() {
+                Ok(connecting) => connecting,
+                Err(e) => {
+                    debug!("Failed to accept QUIC connection from {}: {}", peer_addr, e);
+                    continue;
+                }
+            };
+
+            debug!("QUIC connection from {}, starting handshake", peer_addr);
+
+            let guard = self.guard.clone();
+            let timeout = self.guard.limits().handshake_timeout;
+            handshakes.spawn(async move {
+                match tokio::time::timeout(timeout, connecting).await {
+                    Ok(Ok(conn)) => {
+                        guard.record_handshake_success(peer_addr.ip());
+                        info!("QUIC connection established with {}", peer_addr);
+                        Ok(QuicConnection::new(conn, permit))
+                    }
+                    Ok(Err(e)) => {
+                        warn!("QUIC handshake failed with {}: {}", peer_addr, e);
+                        guard.record_handshake_failure(peer_addr.ip());
+                        Err(NetError::TlsHandshake(format!("Handshake failed with {}: {}", peer_addr, e)))
+                    }
+                    Err(_) => {
+                        warn!("QUIC handshake with {} timed out after {:?}", peer_addr, timeout);
+                        guard.record_handshake_failure(peer_addr.ip());
+                        Err(NetError::ConnectionTimeout(format!(
+                            "Handshake with {} timed out after {:?}",
+                            peer_addr, timeout
+                        )))
+                    }
+                }
+            });
+        }
+    }
+
+    /// 关闭端点，断开所有连接
+    pub fn close(&self) {
+        self.endpoint.close(0u32.into(), b"server closed");
+    }
+}
+
+impl std::fmt::Debug for QuicServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QuicServer")
+            .field("local_addr", &self.endpoint.local_addr().ok())
+            .finish()
+    }
+}
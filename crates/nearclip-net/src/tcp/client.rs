@@ -67,6 +67,8 @@ pub struct TcpClientConfig {
     pub target_addr: SocketAddr,
     /// 连接超时时间
     pub connect_timeout: Duration,
+    /// TLS 握手中提供的 ALPN 协议（空表示沿用 TLS 配置）
+    pub alpn_protocols: Vec<Vec<u8>>,
 }
 
 impl TcpClientConfig {
@@ -81,6 +83,7 @@ impl TcpClientConfig {
         Self {
             target_addr,
             connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            alpn_protocols: Vec::new(),
         }
     }
 
@@ -93,6 +96,14 @@ impl TcpClientConfig {
         self.connect_timeout = timeout;
         self
     }
+
+    /// 设置 ALPN 协议
+    ///
+    /// 按优先级排列。服务端不支持 ALPN 时仍可连接，协商结果为 `None`。
+    pub fn with_alpn_protocols(mut self, protocols: Vec<Vec<u8>>) -> Self {
+        self.alpn_protocols = protocols;
+        self
+    }
 }
 
 /// TLS TCP 客户端
@@ -190,6 +201,13 @@ impl TcpClient {
         debug!("TCP connection established to {}", config.target_addr);
 
         // 2. 执行 TLS 握手
+        let tls_config = if config.alpn_protocols.is_empty() {
+            tls_config
+        } else {
+            let mut tls = (*tls_config).clone();
+            tls.alpn_protocols = config.alpn_protocols.clone();
+            Arc::new(tls)
+        };
         let connector = TlsConnector::from(tls_config);
         let server_name_owned = rustls::pki_types::ServerName::try_from(server_name.to_string())
             .map_err(|_| NetError::TlsHandshake(format!("Invalid server name: {}", server_name)))?;
@@ -3,9 +3,11 @@
 //! 提供连接抽象，封装 TLS 流的读写操作。
 //! 支持服务端和客户端两种连接类型。
 
+use super::ConnectionPermit;
 use crate::NetError;
 use std::net::SocketAddr;
 use std::pin::Pin;
+use std::sync::Arc;
 use std::task::{Context, Poll};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
 use tokio::net::TcpStream;
@@ -91,6 +93,8 @@ impl AsyncWrite for TlsStreamWrapper {
 pub struct TcpConnection {
     stream: TlsStreamWrapper,
     peer_addr: SocketAddr,
+    permit: Option<Arc<ConnectionPermit>>,
+    alpn_protocol: Option<Vec<u8>>,
 }
 
 impl TcpConnection {
@@ -99,9 +103,12 @@ impl TcpConnection {
         stream: tokio_rustls::server::TlsStream<TcpStream>,
         peer_addr: SocketAddr,
     ) -> Self {
+        let alpn_protocol = stream.get_ref().1.alpn_protocol().map(<[u8]>::to_vec);
         Self {
             stream: TlsStreamWrapper::Server(stream),
             peer_addr,
+            permit: None,
+            alpn_protocol,
         }
     }
 
@@ -110,12 +117,21 @@ impl TcpConnection {
         stream: tokio_rustls::client::TlsStream<TcpStream>,
         peer_addr: SocketAddr,
     ) -> Self {
+        let alpn_protocol = stream.get_ref().1.alpn_protocol().map(<[u8]>::to_vec);
         Self {
             stream: TlsStreamWrapper::Client(stream),
             peer_addr,
+            permit: None,
+            alpn_protocol,
         }
     }
 
+    /// 附加服务端连接许可（内部使用）
+    pub(crate) fn with_permit(mut self, permit: ConnectionPermit) -> Self {
+        self.permit = Some(Arc::new(permit));
+        self
+    }
+
     /// 获取对端地址
     ///
     /// 返回连接的远程端地址。
@@ -123,6 +139,20 @@ impl TcpConnection {
         self.peer_addr
     }
 
+    /// 服务端连接许可（客户端发起的连接为 None）
+    ///
+    /// 携带连接限制配置，可用于封禁行为异常的对端。
+    pub fn permit(&self) -> Option<&ConnectionPermit> {
+        self.permit.as_deref()
+    }
+
+    /// TLS 握手协商出的 ALPN 协议
+    ///
+    /// 任一方未提供 ALPN 或没有共同协议时为 `None`。
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.alpn_protocol.as_deref()
+    }
+
     /// 读取数据
     ///
     /// 从连接中读取数据到缓冲区。
@@ -245,8 +275,17 @@ impl TcpConnection {
         let (read, write) = tokio::io::split(self.stream);
         let peer_addr = self.peer_addr;
         (
-            TcpReadHalf { read, peer_addr },
-            TcpWriteHalf { write, peer_addr },
+            TcpReadHalf {
+                read,
+                peer_addr,
+                permit: self.permit.clone(),
+                alpn_protocol: self.alpn_protocol,
+            },
+            TcpWriteHalf {
+                write,
+                peer_addr,
+                _permit: self.permit,
+            },
         )
     }
 }
@@ -258,6 +297,8 @@ impl TcpConnection {
 pub struct TcpReadHalf {
     read: tokio::io::ReadHalf<TlsStreamWrapper>,
     peer_addr: SocketAddr,
+    permit: Option<Arc<ConnectionPermit>>,
+    alpn_protocol: Option<Vec<u8>>,
 }
 
 impl TcpReadHalf {
@@ -266,6 +307,16 @@ impl TcpReadHalf {
         self.peer_addr
     }
 
+    /// 服务端连接许可（客户端发起的连接为 None）
+    pub fn permit(&self) -> Option<&ConnectionPermit> {
+        self.permit.as_deref()
+    }
+
+    /// TLS 握手协商出的 ALPN 协议
+    pub fn alpn_protocol(&self) -> Option<&[u8]> {
+        self.alpn_protocol.as_deref()
+    }
+
     /// 读取数据
     ///
     /// 从连接中读取数据到缓冲区。
@@ -288,6 +339,26 @@ impl TcpReadHalf {
         })?;
         Ok(n)
     }
+
+    /// 读取数据到可增长缓冲区
+    ///
+    /// 数据追加到 `buf` 末尾的空闲容量中，不会预先清零或重新分配；
+    /// 调用方负责预留容量。
+    ///
+    /// # Returns
+    ///
+    /// 实际读取的字节数，0 表示连接已关闭（或 `buf` 没有剩余容量）
+    pub async fn read_buf<B: bytes::BufMut + ?Sized>(&mut self, buf: &mut B) -> Result<usize, NetError> {
+        use tokio::io::AsyncReadExt;
+        let n = self.read.read_buf(buf).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                NetError::ConnectionClosed(format!("Peer {} disconnected", self.peer_addr))
+            } else {
+                NetError::Io(e)
+            }
+        })?;
+        Ok(n)
+    }
 }
 
 /// TLS 连接的只写半连接
@@ -297,6 +368,8 @@ impl TcpReadHalf {
 pub struct TcpWriteHalf {
     write: tokio::io::WriteHalf<TlsStreamWrapper>,
     peer_addr: SocketAddr,
+    // 两个半连接都持有许可，全部 drop 后才释放并发计数
+    _permit: Option<Arc<ConnectionPermit>>,
 }
 
 impl TcpWriteHalf {
@@ -351,6 +424,17 @@ impl TcpWriteHalf {
             }
         })
     }
+
+    /// 关闭写方向
+    ///
+    /// 发送 TLS close_notify，对端读取将返回 EOF。
+    pub async fn close(&mut self) -> Result<(), NetError> {
+        use tokio::io::AsyncWriteExt;
+        self.write.shutdown().await.map_err(|e| {
+            debug!("Shutdown error (may be expected): {}", e);
+            NetError::Io(e)
+        })
+    }
 }
 
 impl std::fmt::Debug for TcpConnection {
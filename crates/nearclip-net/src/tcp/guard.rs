@@ -0,0 +1,427 @@
+//! 连接防护
+//!
+//! 监听端口对局域网内所有主机开放，握手完成前无法确认对端身份。
+//! 本模块限制单个 IP 能消耗的资源：
+//!
+//! - 每个 IP 的并发连接数上限
+//! - TLS 握手超时，握手失败次数过多的 IP 被临时封禁
+//! - 认证前单条消息的最大长度
+//! - 每个连接的消息速率（令牌桶），超限的 IP 被临时封禁
+//!
+//! # Example
+//!
+//! ```
+//! use nearclip_net::tcp::{ConnectionLimits, TcpServerConfig};
+//! use std::time::Duration;
+//!
+//! let limits = ConnectionLimits::new()
+//!     .with_max_connections_per_ip(4)
+//!     .with_handshake_timeout(Duration::from_secs(5))
+//!     .with_ban_duration(Duration::from_secs(300));
+//!
+//! let config = TcpServerConfig::new().with_limits(limits);
+//! ```
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// 默认每个 IP 的最大并发连接数
+pub const DEFAULT_MAX_CONNECTIONS_PER_IP: usize = 8;
+
+/// 默认同时进行中的最大握手数
+pub const DEFAULT_MAX_PENDING_HANDSHAKES: usize = 64;
+
+/// 默认 TLS 握手超时（秒）
+pub const DEFAULT_HANDSHAKE_TIMEOUT_SECS: u64 = 10;
+
+/// 默认认证前单条消息最大长度（64 KiB）
+pub const DEFAULT_MAX_UNAUTHENTICATED_MESSAGE_SIZE: u32 = 64 * 1024;
+
+/// 默认每秒消息数（令牌桶补充速率）
+pub const DEFAULT_MESSAGES_PER_SECOND: u32 = 100;
+
+/// 默认令牌桶容量
+pub const DEFAULT_MESSAGE_BURST: u32 = 200;
+
+/// 默认封禁前允许的握手失败次数
+pub const DEFAULT_MAX_HANDSHAKE_FAILURES: u32 = 5;
+
+/// 默认封禁时长（秒）
+pub const DEFAULT_BAN_DURATION_SECS: u64 = 60;
+
+/// 连接限制配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConnectionLimits {
+    /// 每个 IP 的最大并发连接数（包括握手中的连接）
+    pub max_connections_per_ip: usize,
+    /// 同时进行中的最大握手数
+    pub max_pending_handshakes: usize,
+    /// TLS 握手超时
+    pub handshake_timeout: Duration,
+    /// 认证前单条消息最大长度（字节）
+    pub max_unauthenticated_message_size: u32,
+    /// 每秒允许的消息数
+    pub messages_per_second: u32,
+    /// 允许的突发消息数
+    pub message_burst: u32,
+    /// 封禁前允许的握手失败次数
+    pub max_handshake_failures: u32,
+    /// 封禁时长
+    pub ban_duration: Duration,
+}
+
+impl Default for ConnectionLimits {
+    fn default() -> Self {
+        Self {
+            max_connections_per_ip: DEFAULT_MAX_CONNECTIONS_PER_IP,
+            max_pending_handshakes: DEFAULT_MAX_PENDING_HANDSHAKES,
+            handshake_timeout: Duration::from_secs(DEFAULT_HANDSHAKE_TIMEOUT_SECS),
+            max_unauthenticated_message_size: DEFAULT_MAX_UNAUTHENTICATED_MESSAGE_SIZE,
+            messages_per_second: DEFAULT_MESSAGES_PER_SECOND,
+            message_burst: DEFAULT_MESSAGE_BURST,
+            max_handshake_failures: DEFAULT_MAX_HANDSHAKE_FAILURES,
+            ban_duration: Duration::from_secs(DEFAULT_BAN_DURATION_SECS),
+        }
+    }
+}
+
+impl ConnectionLimits {
+    /// 创建默认限制
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置每个 IP 的最大并发连接数
+    pub fn with_max_connections_per_ip(mut self, max: usize) -> Self {
+        self.max_connections_per_ip = max;
+        self
+    }
+
+    /// 设置同时进行中的最大握手数
+    pub fn with_max_pending_handshakes(mut self, max: usize) -> Self {
+        self.max_pending_handshakes = max;
+        self
+    }
+
+    /// 设置 TLS 握手超时
+    pub fn with_handshake_timeout(mut self, timeout: Duration) -> Self {
+        self.handshake_timeout = timeout;
+        self
+    }
+
+    /// 设置认证前单条消息最大长度
+    pub fn with_max_unauthenticated_message_size(mut self, size: u32) -> Self {
+        self.max_unauthenticated_message_size = size;
+        self
+    }
+
+    /// 设置消息速率限制
+    pub fn with_message_rate(mut self, per_second: u32, burst: u32) -> Self {
+        self.messages_per_second = per_second;
+        self.message_burst = burst;
+        self
+    }
+
+    /// 设置封禁前允许的握手失败次数
+    pub fn with_max_handshake_failures(mut self, max: u32) -> Self {
+        self.max_handshake_failures = max;
+        self
+    }
+
+    /// 设置封禁时长
+    pub fn with_ban_duration(mut self, duration: Duration) -> Self {
+        self.ban_duration = duration;
+        self
+    }
+
+    /// 为单个连接创建令牌桶
+    pub fn rate_limiter(&self) -> TokenBucket {
+        TokenBucket::new(self.messages_per_second, self.message_burst)
+    }
+}
+
+/// 令牌桶限速器
+///
+/// 每条消息消耗一个令牌，令牌按固定速率补充，最多积累 `burst` 个。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::tcp::TokenBucket;
+///
+/// let mut bucket = TokenBucket::new(10, 2);
+/// assert!(bucket.try_acquire());
+/// assert!(bucket.try_acquire());
+/// assert!(!bucket.try_acquire());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// 创建令牌桶（初始为满）
+    pub fn new(per_second: u32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            rate: f64::from(per_second),
+            burst,
+            tokens: burst,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// 尝试消耗一个令牌
+    pub fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// 连接被拒绝的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectReason {
+    /// IP 处于封禁期
+    Banned,
+    /// IP 的并发连接数已达上限
+    TooManyConnections,
+}
+
+#[derive(Debug, Default)]
+struct IpState {
+    active: usize,
+    handshake_failures: u32,
+    banned_until: Option<Instant>,
+}
+
+impl IpState {
+    fn is_banned(&self, now: Instant) -> bool {
+        self.banned_until.is_some_and(|until| until > now)
+    }
+
+    fn is_idle(&self, now: Instant) -> bool {
+        self.active == 0 && self.handshake_failures == 0 && !self.is_banned(now)
+    }
+}
+
+#[derive(Debug)]
+struct GuardInner {
+    limits: ConnectionLimits,
+    ips: Mutex<HashMap<IpAddr, IpState>>,
+}
+
+/// 连接防护
+///
+/// 由 [`TcpServer`](super::TcpServer) 持有，按 IP 记录并发连接数、握手失败次数和封禁状态。
+/// 可以克隆，所有克隆共享同一份状态。
+#[derive(Debug, Clone)]
+pub struct ConnectionGuard {
+    inner: Arc<GuardInner>,
+}
+
+impl ConnectionGuard {
+    /// 创建连接防护
+    pub fn new(limits: ConnectionLimits) -> Self {
+        Self {
+            inner: Arc::new(GuardInner {
+                limits,
+                ips: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+
+    /// 连接限制配置
+    pub fn limits(&self) -> &ConnectionLimits {
+        &self.inner.limits
+    }
+
+    /// 准入检查
+    ///
+    /// 通过时返回许可，许可释放（所有持有者 drop）时并发计数减一。
+    pub fn admit(&self, ip: IpAddr) -> Result<ConnectionPermit, RejectReason> {
+        let now = Instant::now();
+        let mut ips = self.inner.ips.lock().unwrap();
+        ips.retain(|_, state| !state.is_idle(now));
+
+        let state = ips.entry(ip).or_default();
+        if state.is_banned(now) {
+            return Err(RejectReason::Banned);
+        }
+        if state.active >= self.inner.limits.max_connections_per_ip {
+            return Err(RejectReason::TooManyConnections);
+        }
+        state.active += 1;
+
+        Ok(ConnectionPermit {
+            guard: self.clone(),
+            ip,
+        })
+    }
+
+    /// 临时封禁 IP
+    pub fn ban(&self, ip: IpAddr) {
+        let until = Instant::now() + self.inner.limits.ban_duration;
+        let mut ips = self.inner.ips.lock().unwrap();
+        ips.entry(ip).or_default().banned_until = Some(until);
+        warn!(%ip, duration = ?self.inner.limits.ban_duration, "Peer temporarily banned");
+    }
+
+    /// IP 是否处于封禁期
+    pub fn is_banned(&self, ip: IpAddr) -> bool {
+        let ips = self.inner.ips.lock().unwrap();
+        ips.get(&ip).is_some_and(|state| state.is_banned(Instant::now()))
+    }
+
+    /// IP 当前的并发连接数
+    pub fn active_connections(&self, ip: IpAddr) -> usize {
+        let ips = self.inner.ips.lock().unwrap();
+        ips.get(&ip).map(|state| state.active).unwrap_or(0)
+    }
+
+    /// 记录握手成功（清零失败计数）
+    pub(crate) fn record_handshake_success(&self, ip: IpAddr) {
+        let mut ips = self.inner.ips.lock().unwrap();
+        if let Some(state) = ips.get_mut(&ip) {
+            state.handshake_failures = 0;
+        }
+    }
+
+    /// 记录握手失败，失败次数达到上限时封禁
+    pub(crate) fn record_handshake_failure(&self, ip: IpAddr) {
+        let exceeded = {
+            let mut ips = self.inner.ips.lock().unwrap();
+            let state = ips.entry(ip).or_default();
+            state.handshake_failures += 1;
+            if state.handshake_failures >= self.inner.limits.max_handshake_failures {
+                state.handshake_failures = 0;
+                true
+            } else {
+                false
+            }
+        };
+        if exceeded {
+            self.ban(ip);
+        }
+    }
+
+    fn release(&self, ip: IpAddr) {
+        let mut ips = self.inner.ips.lock().unwrap();
+        if let Some(state) = ips.get_mut(&ip) {
+            state.active = state.active.saturating_sub(1);
+        }
+    }
+}
+
+/// 连接许可
+///
+/// 随服务端接受的连接一起传递，连接关闭时释放；
+/// 上层发现对端行为异常时可以通过它封禁对端 IP。
+#[derive(Debug)]
+pub struct ConnectionPermit {
+    guard: ConnectionGuard,
+    ip: IpAddr,
+}
+
+impl ConnectionPermit {
+    /// 对端 IP
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+
+    /// 连接限制配置
+    pub fn limits(&self) -> &ConnectionLimits {
+        self.guard.limits()
+    }
+
+    /// 封禁对端 IP
+    pub fn ban(&self) {
+        self.guard.ban(self.ip);
+    }
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.guard.release(self.ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const PEER: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+
+    #[test]
+    fn test_per_ip_connection_cap() {
+        let guard = ConnectionGuard::new(ConnectionLimits::new().with_max_connections_per_ip(2));
+
+        let first = guard.admit(PEER).unwrap();
+        let _second = guard.admit(PEER).unwrap();
+        assert_eq!(guard.admit(PEER).unwrap_err(), RejectReason::TooManyConnections);
+        assert_eq!(guard.active_connections(PEER), 2);
+
+        // 其他 IP 不受影响
+        assert!(guard.admit(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 21))).is_ok());
+
+        drop(first);
+        assert!(guard.admit(PEER).is_ok());
+    }
+
+    #[test]
+    fn test_ban_after_handshake_failures() {
+        let limits = ConnectionLimits::new()
+            .with_max_handshake_failures(2)
+            .with_ban_duration(Duration::from_millis(50));
+        let guard = ConnectionGuard::new(limits);
+
+        guard.record_handshake_failure(PEER);
+        assert!(!guard.is_banned(PEER));
+        guard.record_handshake_failure(PEER);
+        assert!(guard.is_banned(PEER));
+        assert_eq!(guard.admit(PEER).unwrap_err(), RejectReason::Banned);
+
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(!guard.is_banned(PEER));
+        assert!(guard.admit(PEER).is_ok());
+    }
+
+    #[test]
+    fn test_permit_ban() {
+        let guard = ConnectionGuard::new(ConnectionLimits::new());
+        let permit = guard.admit(PEER).unwrap();
+        assert_eq!(permit.ip(), PEER);
+
+        permit.ban();
+        drop(permit);
+        assert!(guard.is_banned(PEER));
+        assert_eq!(guard.active_connections(PEER), 0);
+    }
+
+    #[test]
+    fn test_token_bucket_refills() {
+        let mut bucket = TokenBucket::new(1000, 3);
+        assert!(bucket.try_acquire());
+        assert!(bucket.try_acquire());
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(bucket.try_acquire());
+    }
+}
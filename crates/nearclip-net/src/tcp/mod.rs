@@ -50,8 +50,15 @@
 
 mod client;
 mod connection;
+mod guard;
 mod server;
 
 pub use client::{TcpClient, TcpClientConfig};
 pub use connection::{TcpConnection, TcpReadHalf, TcpWriteHalf};
+pub use guard::{
+    ConnectionGuard, ConnectionLimits, ConnectionPermit, RejectReason, TokenBucket,
+    DEFAULT_BAN_DURATION_SECS, DEFAULT_HANDSHAKE_TIMEOUT_SECS, DEFAULT_MAX_CONNECTIONS_PER_IP,
+    DEFAULT_MAX_HANDSHAKE_FAILURES, DEFAULT_MAX_PENDING_HANDSHAKES,
+    DEFAULT_MAX_UNAUTHENTICATED_MESSAGE_SIZE, DEFAULT_MESSAGES_PER_SECOND, DEFAULT_MESSAGE_BURST,
+};
 pub use server::{TcpServer, TcpServerConfig};
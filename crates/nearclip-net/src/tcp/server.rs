@@ -22,12 +22,15 @@
 
 use crate::NetError;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::ops::RangeInclusive;
 use std::sync::Arc;
 use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
 use tokio_rustls::TlsAcceptor;
 use tracing::{debug, info, instrument, warn};
 
-use super::TcpConnection;
+use super::{ConnectionGuard, ConnectionLimits, TcpConnection};
 
 /// TCP 服务端配置
 ///
@@ -49,6 +52,11 @@ use super::TcpConnection;
 /// let config = TcpServerConfig::new()
 ///     .with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))
 ///     .with_port(8765);
+///
+/// // 优先使用 8765，被占用时依次尝试范围内的下一个端口
+/// let config = TcpServerConfig::new()
+///     .with_port(8765)
+///     .with_port_range(8765..=8774);
 /// ```
 #[derive(Debug, Clone)]
 pub struct TcpServerConfig {
@@ -56,6 +64,12 @@ pub struct TcpServerConfig {
     pub bind_addr: IpAddr,
     /// 绑定端口（0 表示动态分配）
     pub port: u16,
+    /// 首选端口范围（`port` 被占用时依次尝试）
+    pub port_range: Option<RangeInclusive<u16>>,
+    /// 连接限制
+    pub limits: ConnectionLimits,
+    /// TLS 握手中提供的 ALPN 协议（空表示沿用 TLS 配置）
+    pub alpn_protocols: Vec<Vec<u8>>,
 }
 
 impl Default for TcpServerConfig {
@@ -63,6 +77,9 @@ impl Default for TcpServerConfig {
         Self {
             bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
             port: 0,
+            port_range: None,
+            limits: ConnectionLimits::default(),
+            alpn_protocols: Vec::new(),
         }
     }
 }
@@ -89,15 +106,71 @@ impl TcpServerConfig {
         self
     }
 
+    /// 设置首选端口范围
+    ///
+    /// `port` 为 0 或被占用时，从 `port` 的下一个端口开始在范围内循环查找空闲端口；
+    /// 范围内全部被占用时回退到动态分配。
+    pub fn with_port_range(mut self, range: RangeInclusive<u16>) -> Self {
+        self.port_range = Some(range);
+        self
+    }
+
+    /// 设置连接限制
+    pub fn with_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// 设置 ALPN 协议
+    ///
+    /// 客户端提供其中之一时，协商结果可从 [`TcpConnection::alpn_protocol`] 读取；
+    /// 不支持 ALPN 的客户端仍可连接，协商结果为 `None`。
+    pub fn with_alpn_protocols(mut self, protocols: Vec<Vec<u8>>) -> Self {
+        self.alpn_protocols = protocols;
+        self
+    }
+
     /// 获取完整的 socket 地址
     pub fn socket_addr(&self) -> SocketAddr {
         SocketAddr::new(self.bind_addr, self.port)
     }
+
+    /// 按尝试顺序列出候选端口
+    ///
+    /// 依次为 `port`、范围内 `port` 之后的端口（循环）、最后是 0（动态分配）。
+    pub fn candidate_ports(&self) -> Vec<u16> {
+        let mut ports = Vec::new();
+        if self.port != 0 {
+            ports.push(self.port);
+        }
+
+        if let Some(range) = self.port_range.as_ref().filter(|r| !r.is_empty()) {
+            let in_range: Vec<u16> = range.clone().filter(|&p| p != 0).collect();
+            let start = in_range
+                .iter()
+                .position(|&p| p == self.port)
+                .map(|i| i + 1)
+                .unwrap_or(0);
+            ports.extend(
+                in_range[start..]
+                    .iter()
+                    .chain(&in_range[..start])
+                    .filter(|&&p| p != self.port),
+            );
+        }
+
+        if ports.is_empty() || self.port_range.is_some() {
+            ports.push(0);
+        }
+        ports
+    }
 }
 
 /// TLS 加密的 TCP 服务端
 ///
 /// 提供 TLS 1.3 加密的 TCP 服务端功能，支持多个并发连接。
+/// TLS 握手在后台并发进行，慢速或恶意的握手不会阻塞其他连接；
+/// 每个 IP 的并发连接数、握手超时和封禁由 [`ConnectionLimits`] 控制。
 ///
 /// # Example
 ///
@@ -125,6 +198,8 @@ impl TcpServerConfig {
 pub struct TcpServer {
     listener: TcpListener,
     tls_acceptor: TlsAcceptor,
+    guard: ConnectionGuard,
+    handshakes: Mutex<JoinSet<Result<TcpConnection, NetError>>>,
 }
 
 impl TcpServer {
@@ -161,11 +236,7 @@ impl TcpServer {
         config: TcpServerConfig,
         tls_config: Arc<rustls::ServerConfig>,
     ) -> Result<Self, NetError> {
-        let addr = config.socket_addr();
-
-        let listener = TcpListener::bind(addr).await.map_err(|e| {
-            NetError::TcpServer(format!("Failed to bind to {}: {}", addr, e))
-        })?;
+        let listener = Self::bind_listener(&config).await?;
 
         let local_addr = listener.local_addr().map_err(|e| {
             NetError::TcpServer(format!("Failed to get local address: {}", e))
@@ -173,14 +244,42 @@ impl TcpServer {
 
         info!("TCP server bound to {}", local_addr);
 
+        let tls_config = if config.alpn_protocols.is_empty() {
+            tls_config
+        } else {
+            let mut tls = (*tls_config).clone();
+            tls.alpn_protocols = config.alpn_protocols.clone();
+            Arc::new(tls)
+        };
         let tls_acceptor = TlsAcceptor::from(tls_config);
 
         Ok(Self {
             listener,
             tls_acceptor,
+            guard: ConnectionGuard::new(config.limits),
+            handshakes: Mutex::new(JoinSet::new()),
         })
     }
 
+    /// 按候选端口顺序绑定，返回第一个成功的监听器
+    async fn bind_listener(config: &TcpServerConfig) -> Result<TcpListener, NetError> {
+        let mut last_error = None;
+        for port in config.candidate_ports() {
+            let addr = SocketAddr::new(config.bind_addr, port);
+            if port == 0 && config.port_range.is_some() {
+                warn!("No free port in preferred range, falling back to a dynamic port");
+            }
+            match TcpListener::bind(addr).await {
+                Ok(listener) => return Ok(listener),
+                Err(e) => {
+                    debug!("Failed to bind to {}: {}", addr, e);
+                    last_error = Some(NetError::TcpServer(format!("Failed to bind to {}: {}", addr, e)));
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| NetError::TcpServer("No port to bind".to_string())))
+    }
+
     /// 获取实际监听地址
     ///
     /// 当使用端口 0（动态分配）时，可通过此方法获取实际分配的端口。
@@ -194,13 +293,19 @@ impl TcpServer {
         })
     }
 
+    /// 连接防护（可用于查询或手动封禁 IP）
+    pub fn guard(&self) -> &ConnectionGuard {
+        &self.guard
+    }
+
     /// 接受新的客户端连接
     ///
-    /// 此方法会阻塞直到有新连接到达，完成 TLS 握手后返回连接对象。
+    /// 此方法会阻塞直到有连接完成 TLS 握手。被封禁或超过并发上限的连接
+    /// 直接关闭，不会返回给调用方。
     ///
     /// # Returns
     ///
-    /// TLS 加密的连接对象，或错误
+    /// TLS 加密的连接对象；握手失败或超时时返回错误
     ///
     /// # Example
     ///
@@ -219,22 +324,67 @@ impl TcpServer {
     /// ```
     #[instrument(skip(self))]
     pub async fn accept(&self) -> Result<TcpConnection, NetError> {
-        // 等待 TCP 连接
-        let (tcp_stream, peer_addr) = self.listener.accept().await.map_err(|e| {
-            NetError::TcpServer(format!("Failed to accept connection: {}", e))
-        })?;
+        let mut handshakes = self.handshakes.lock().await;
+        loop {
+            let accepted = tokio::select! {
+                Some(finished) = handshakes.join_next(), if !handshakes.is_empty() => {
+                    match finished {
+                        Ok(result) => return result,
+                        Err(e) => {
+                            warn!("Handshake task failed: {}", e);
+                            continue;
+                        }
+                    }
+                }
+                accepted = self.listener.accept() => accepted,
+            };
 
-        debug!("TCP connection from {}, starting TLS handshake", peer_addr);
+            // 等待 TCP 连接
+            let (tcp_stream, peer_addr) = accepted.map_err(|e| {
+                NetError::TcpServer(format!("Failed to accept connection: {}", e))
+            })?;
 
-        // 执行 TLS 握手
-        let tls_stream = self.tls_acceptor.accept(tcp_stream).await.map_err(|e| {
-            warn!("TLS handshake failed with {}: {}", peer_addr, e);
-            NetError::TlsHandshake(format!("Handshake failed with {}: {}", peer_addr, e))
-        })?;
+            if handshakes.len() >= self.guard.limits().max_pending_handshakes {
+                debug!("Too many pending handshakes, dropping connection from {}", peer_addr);
+                continue;
+            }
+            let permit = match self.guard.admit(peer_addr.ip()) {
+                Ok(permit) => permit,
+                Err(reason) => {
+                    debug!("Rejected connection from {}: {:?}", peer_addr, reason);
+                    continue;
+                }
+            };
 
-        info!("TLS connection established with {}", peer_addr);
+            debug!("TCP connection from {}, starting TLS handshake", peer_addr);
 
-        Ok(TcpConnection::new(tls_stream, peer_addr))
+            // 在后台执行 TLS 握手
+            let acceptor = self.tls_acceptor.clone();
+            let guard = self.guard.clone();
+            let timeout = self.guard.limits().handshake_timeout;
+            handshakes.spawn(async move {
+                match tokio::time::timeout(timeout, acceptor.accept(tcp_stream)).await {
+                    Ok(Ok(tls_stream)) => {
+                        guard.record_handshake_success(peer_addr.ip());
+                        info!("TLS connection established with {}", peer_addr);
+                        Ok(TcpConnection::new(tls_stream, peer_addr).with_permit(permit))
+                    }
+                    Ok(Err(e)) => {
+                        warn!("TLS handshake failed with {}: {}", peer_addr, e);
+                        guard.record_handshake_failure(peer_addr.ip());
+                        Err(NetError::TlsHandshake(format!("Handshake failed with {}: {}", peer_addr, e)))
+                    }
+                    Err(_) => {
+                        warn!("TLS handshake with {} timed out", peer_addr);
+                        guard.record_handshake_failure(peer_addr.ip());
+                        Err(NetError::ConnectionTimeout(format!(
+                            "TLS handshake with {} timed out after {:?}",
+                            peer_addr, timeout
+                        )))
+                    }
+                }
+            });
+        }
     }
 }
 
@@ -296,4 +446,20 @@ mod tests {
         assert_eq!(config.bind_addr, IpAddr::V4(Ipv4Addr::new(192, 168, 1, 100)));
         assert_eq!(config.port, 12345);
     }
+
+    #[test]
+    fn test_tcp_server_config_candidate_ports() {
+        assert_eq!(TcpServerConfig::new().candidate_ports(), vec![0]);
+        assert_eq!(TcpServerConfig::new().with_port(8765).candidate_ports(), vec![8765]);
+
+        // 上次端口在范围内：从它开始循环，最后回退到动态端口
+        let config = TcpServerConfig::new()
+            .with_port(8767)
+            .with_port_range(8765..=8768);
+        assert_eq!(config.candidate_ports(), vec![8767, 8768, 8765, 8766, 0]);
+
+        // 没有上次端口：从范围起点开始
+        let config = TcpServerConfig::new().with_port_range(8765..=8766);
+        assert_eq!(config.candidate_ports(), vec![8765, 8766, 0]);
+    }
 }
@@ -0,0 +1,52 @@
+//! UDP 单播发现模块
+//!
+//! 部分网络允许单播但过滤组播，此时 mDNS 发现不到任何设备。
+//! 本模块提供轻量的替代发现方式：
+//!
+//! - [`UdpResponder`] 在固定端口应答已配对设备发来的认证探测（"谁是设备 X？"）
+//! - [`UdpProber`] 通过广播、子网扫描或指定地址发送探测，结果通过
+//!   [`DiscoverySink`](crate::DiscoverySink) 进入与 mDNS 相同的 `DiscoveryEvent` 事件流
+//!
+//! 探测和应答都用配对双方共享的 [`DiscoveryKey`](crate::DiscoveryKey) 认证，
+//! 报文中不包含明文设备标识。
+//!
+//! # Example
+//!
+//! ```no_run
+//! use nearclip_net::{DiscoveryKey, MdnsDiscovery, UdpProber, UdpProberConfig, UdpResponder, UdpResponderConfig};
+//! use std::net::Ipv4Addr;
+//!
+//! # async fn example() -> Result<(), nearclip_net::NetError> {
+//! let key = DiscoveryKey::derive(b"pairing secret");
+//!
+//! // 设备 A：应答探测
+//! let mut responder = UdpResponder::new(UdpResponderConfig::new("device-a".to_string(), 8765))?;
+//! responder.add_peer("device-b", key.clone()).await;
+//! responder.start().await?;
+//!
+//! // 设备 B：探测设备 A
+//! let discovery = MdnsDiscovery::new()?;
+//! let config = UdpProberConfig::new().with_subnet_sweep(Ipv4Addr::new(192, 168, 1, 0), 24);
+//! let mut prober = UdpProber::new(config, discovery.sink())?;
+//! prober.add_peer("device-a", key).await;
+//! prober.start().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+mod packet;
+mod prober;
+mod responder;
+
+use crate::mdns::DiscoveryKey;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+pub use prober::{UdpProber, UdpProberConfig, DEFAULT_PROBE_INTERVAL_SECS, MIN_SWEEP_PREFIX_LEN};
+pub use responder::{UdpResponder, UdpResponderConfig};
+
+/// 默认 UDP 发现端口
+pub const DEFAULT_UDP_DISCOVERY_PORT: u16 = 8766;
+
+/// 已配对设备的发现密钥列表 (device_id, key)
+type PeerKeys = Arc<RwLock<Vec<(String, DiscoveryKey)>>>;
@@ -0,0 +1,263 @@
+//! UDP 发现报文
+//!
+//! 报文格式（大端序）：
+//!
+//! ```text
+//! Probe:    "NCUD" | version(1) | kind=1 | nonce(16) | timestamp(8) | tag(16)
+//! Response: "NCUD" | version(1) | kind=2 | nonce(16) | port(2)      | tag(16)
+//!
+//! probe tag    = HMAC-SHA256(key, "nearclip-udp-probe"    || nonce || timestamp || target_id)[..16]
+//! response tag = HMAC-SHA256(key, "nearclip-udp-response" || nonce || responder_id || port)[..16]
+//! ```
+//!
+//! `key` 是配对双方共享的 [`DiscoveryKey`]。探测报文不包含任何明文设备标识，
+//! 只有持有密钥的已配对设备能构造出被应答的探测，也只有它能验证应答。
+
+use crate::mdns::DiscoveryKey;
+use hmac::Mac;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 报文魔数
+const MAGIC: &[u8; 4] = b"NCUD";
+
+/// 报文版本
+const VERSION: u8 = 1;
+
+/// 报文类型：探测
+const KIND_PROBE: u8 = 1;
+
+/// 报文类型：应答
+const KIND_RESPONSE: u8 = 2;
+
+/// 随机数长度
+pub(crate) const NONCE_LEN: usize = 16;
+
+/// 截断后的认证标签长度
+pub(crate) const TAG_LEN: usize = 16;
+
+/// 探测报文的域分隔前缀
+const PROBE_DOMAIN: &[u8] = b"nearclip-udp-probe";
+
+/// 应答报文的域分隔前缀
+const RESPONSE_DOMAIN: &[u8] = b"nearclip-udp-response";
+
+/// 允许的最大时钟偏差（秒）
+pub(crate) const MAX_CLOCK_SKEW_SECS: u64 = 120;
+
+/// 报文头长度（魔数 + 版本 + 类型）
+const HEADER_LEN: usize = 6;
+
+/// 探测报文长度
+const PROBE_LEN: usize = HEADER_LEN + NONCE_LEN + 8 + TAG_LEN;
+
+/// 应答报文长度
+const RESPONSE_LEN: usize = HEADER_LEN + NONCE_LEN + 2 + TAG_LEN;
+
+/// 最大报文长度（接收缓冲区大小）
+pub(crate) const MAX_PACKET_LEN: usize = PROBE_LEN;
+
+/// UDP 发现报文
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Packet {
+    /// "谁是设备 X？"
+    Probe {
+        nonce: [u8; NONCE_LEN],
+        timestamp: u64,
+        tag: [u8; TAG_LEN],
+    },
+    /// "我是，TCP 服务在这个端口"
+    Response {
+        nonce: [u8; NONCE_LEN],
+        port: u16,
+        tag: [u8; TAG_LEN],
+    },
+}
+
+impl Packet {
+    /// 构造发给目标设备的探测报文
+    pub(crate) fn probe(key: &DiscoveryKey, target_id: &str, nonce: [u8; NONCE_LEN], timestamp: u64) -> Self {
+        let tag = truncate(probe_mac(key, target_id, &nonce, timestamp).finalize().into_bytes());
+        Packet::Probe { nonce, timestamp, tag }
+    }
+
+    /// 构造应答报文
+    pub(crate) fn response(key: &DiscoveryKey, responder_id: &str, nonce: [u8; NONCE_LEN], port: u16) -> Self {
+        let tag = truncate(response_mac(key, responder_id, &nonce, port).finalize().into_bytes());
+        Packet::Response { nonce, port, tag }
+    }
+
+    /// 验证探测报文是否由持有 `key` 的设备发给 `target_id`
+    pub(crate) fn verify_probe(&self, key: &DiscoveryKey, target_id: &str) -> bool {
+        match self {
+            Packet::Probe { nonce, timestamp, tag } => probe_mac(key, target_id, nonce, *timestamp)
+                .verify_truncated_left(tag)
+                .is_ok(),
+            Packet::Response { .. } => false,
+        }
+    }
+
+    /// 验证应答报文是否来自持有 `key` 的 `responder_id`
+    pub(crate) fn verify_response(&self, key: &DiscoveryKey, responder_id: &str) -> bool {
+        match self {
+            Packet::Response { nonce, port, tag } => response_mac(key, responder_id, nonce, *port)
+                .verify_truncated_left(tag)
+                .is_ok(),
+            Packet::Probe { .. } => false,
+        }
+    }
+
+    /// 编码为字节
+    pub(crate) fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(MAX_PACKET_LEN);
+        buf.extend_from_slice(MAGIC);
+        buf.push(VERSION);
+        match self {
+            Packet::Probe { nonce, timestamp, tag } => {
+                buf.push(KIND_PROBE);
+                buf.extend_from_slice(nonce);
+                buf.extend_from_slice(&timestamp.to_be_bytes());
+                buf.extend_from_slice(tag);
+            }
+            Packet::Response { nonce, port, tag } => {
+                buf.push(KIND_RESPONSE);
+                buf.extend_from_slice(nonce);
+                buf.extend_from_slice(&port.to_be_bytes());
+                buf.extend_from_slice(tag);
+            }
+        }
+        buf
+    }
+
+    /// 从字节解码
+    ///
+    /// 魔数、版本、类型或长度不符时返回 None（非 NearClip 流量直接忽略）。
+    pub(crate) fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() < HEADER_LEN || &data[..4] != MAGIC || data[4] != VERSION {
+            return None;
+        }
+        let body = &data[HEADER_LEN..];
+        let nonce: [u8; NONCE_LEN] = body.get(..NONCE_LEN)?.try_into().ok()?;
+        match data[5] {
+            KIND_PROBE if data.len() == PROBE_LEN => {
+                let timestamp = u64::from_be_bytes(body[NONCE_LEN..NONCE_LEN + 8].try_into().ok()?);
+                let tag = body[NONCE_LEN + 8..].try_into().ok()?;
+                Some(Packet::Probe { nonce, timestamp, tag })
+            }
+            KIND_RESPONSE if data.len() == RESPONSE_LEN => {
+                let port = u16::from_be_bytes(body[NONCE_LEN..NONCE_LEN + 2].try_into().ok()?);
+                let tag = body[NONCE_LEN + 2..].try_into().ok()?;
+                Some(Packet::Response { nonce, port, tag })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// 当前 Unix 时间（秒）
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 时间戳是否在允许的时钟偏差内
+pub(crate) fn is_fresh(timestamp: u64, now: u64) -> bool {
+    timestamp.abs_diff(now) <= MAX_CLOCK_SKEW_SECS
+}
+
+fn probe_mac(
+    key: &DiscoveryKey,
+    target_id: &str,
+    nonce: &[u8; NONCE_LEN],
+    timestamp: u64,
+) -> crate::mdns::privacy::HmacSha256 {
+    let mut mac = key.hmac();
+    mac.update(PROBE_DOMAIN);
+    mac.update(nonce);
+    mac.update(&timestamp.to_be_bytes());
+    mac.update(target_id.as_bytes());
+    mac
+}
+
+fn response_mac(
+    key: &DiscoveryKey,
+    responder_id: &str,
+    nonce: &[u8; NONCE_LEN],
+    port: u16,
+) -> crate::mdns::privacy::HmacSha256 {
+    let mut mac = key.hmac();
+    mac.update(RESPONSE_DOMAIN);
+    mac.update(nonce);
+    mac.update(responder_id.as_bytes());
+    mac.update(&port.to_be_bytes());
+    mac
+}
+
+fn truncate(full: impl AsRef<[u8]>) -> [u8; TAG_LEN] {
+    let mut tag = [0u8; TAG_LEN];
+    tag.copy_from_slice(&full.as_ref()[..TAG_LEN]);
+    tag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> DiscoveryKey {
+        DiscoveryKey::derive(b"pairing secret")
+    }
+
+    #[test]
+    fn test_probe_roundtrip_and_verify() {
+        let probe = Packet::probe(&key(), "device-b", [1u8; NONCE_LEN], 1_700_000_000);
+        let decoded = Packet::decode(&probe.encode()).unwrap();
+
+        assert_eq!(decoded, probe);
+        assert!(decoded.verify_probe(&key(), "device-b"));
+        assert!(!decoded.verify_probe(&key(), "device-c"));
+        assert!(!decoded.verify_probe(&DiscoveryKey::derive(b"other"), "device-b"));
+        assert!(!decoded.verify_response(&key(), "device-b"));
+    }
+
+    #[test]
+    fn test_response_roundtrip_and_verify() {
+        let response = Packet::response(&key(), "device-b", [2u8; NONCE_LEN], 8765);
+        let decoded = Packet::decode(&response.encode()).unwrap();
+
+        assert_eq!(decoded, response);
+        assert!(decoded.verify_response(&key(), "device-b"));
+        assert!(!decoded.verify_response(&key(), "device-a"));
+    }
+
+    #[test]
+    fn test_tampered_response_rejected() {
+        let mut bytes = Packet::response(&key(), "device-b", [2u8; NONCE_LEN], 8765).encode();
+        // 修改端口
+        bytes[HEADER_LEN + NONCE_LEN] ^= 0xFF;
+        let decoded = Packet::decode(&bytes).unwrap();
+        assert!(!decoded.verify_response(&key(), "device-b"));
+    }
+
+    #[test]
+    fn test_decode_rejects_garbage() {
+        assert_eq!(Packet::decode(b""), None);
+        assert_eq!(Packet::decode(b"hello world"), None);
+
+        let mut bytes = Packet::probe(&key(), "device-b", [1u8; NONCE_LEN], 1).encode();
+        bytes.pop();
+        assert_eq!(Packet::decode(&bytes), None);
+
+        let mut bytes = Packet::probe(&key(), "device-b", [1u8; NONCE_LEN], 1).encode();
+        bytes[4] = VERSION + 1;
+        assert_eq!(Packet::decode(&bytes), None);
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        assert!(is_fresh(1000, 1000));
+        assert!(is_fresh(1000 - MAX_CLOCK_SKEW_SECS, 1000));
+        assert!(is_fresh(1000 + MAX_CLOCK_SKEW_SECS, 1000));
+        assert!(!is_fresh(1000 - MAX_CLOCK_SKEW_SECS - 1, 1000));
+    }
+}
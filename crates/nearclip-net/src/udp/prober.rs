@@ -0,0 +1,455 @@
+//! UDP 发现探测端
+//!
+//! 定期向广播地址、子网内的每个主机或手动指定的地址发送认证探测，
+//! 收到已配对设备的应答后通过 [`DiscoverySink`] 上报，与 mDNS 结果进入
+//! 同一个事件流。连续多个探测周期没有应答的设备会被报告为离线。
+
+use crate::error::NetError;
+use crate::mdns::{DiscoveredDevice, DiscoveryKey, DiscoverySink};
+use crate::udp::packet::{unix_now, Packet, MAX_PACKET_LEN, NONCE_LEN};
+use crate::udp::{PeerKeys, DEFAULT_UDP_DISCOVERY_PORT};
+use rand::RngCore;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::sync::{Notify, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, instrument, warn};
+
+/// 默认探测间隔（30 秒）
+pub const DEFAULT_PROBE_INTERVAL_SECS: u64 = 30;
+
+/// 子网扫描允许的最短前缀（/22，最多 1022 个主机）
+pub const MIN_SWEEP_PREFIX_LEN: u8 = 22;
+
+/// 连续多少个探测周期无应答后判定设备离线
+const STALE_AFTER_PROBES: u32 = 3;
+
+/// 同时接受应答的探测轮数（当前轮和上一轮）
+const ACCEPTED_ROUNDS: usize = 2;
+
+/// 探测目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProbeTarget {
+    /// 广播地址（受限广播或子网定向广播）
+    Broadcast(Ipv4Addr),
+    /// 逐个探测子网内的主机
+    Sweep { network: Ipv4Addr, prefix_len: u8 },
+    /// 单个地址
+    Unicast(SocketAddr),
+}
+
+/// UDP 发现探测端配置
+///
+/// 未添加任何目标时向受限广播地址 `255.255.255.255` 探测。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::UdpProberConfig;
+/// use std::net::Ipv4Addr;
+///
+/// let config = UdpProberConfig::new()
+///     .with_broadcast(Ipv4Addr::new(192, 168, 1, 255))
+///     .with_subnet_sweep(Ipv4Addr::new(10, 0, 0, 0), 24);
+///
+/// assert_eq!(config.targets().unwrap().len(), 1 + 254);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UdpProberConfig {
+    /// 应答端端口
+    port: u16,
+    /// 探测间隔
+    probe_interval: Duration,
+    /// 探测目标
+    targets: Vec<ProbeTarget>,
+}
+
+impl UdpProberConfig {
+    /// 创建默认配置
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置应答端端口
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// 设置探测间隔
+    pub fn with_probe_interval(mut self, interval: Duration) -> Self {
+        self.probe_interval = interval;
+        self
+    }
+
+    /// 添加广播地址（如子网定向广播 `192.168.1.255`）
+    pub fn with_broadcast(mut self, addr: Ipv4Addr) -> Self {
+        self.targets.push(ProbeTarget::Broadcast(addr));
+        self
+    }
+
+    /// 添加子网扫描（广播被过滤时逐个主机单播探测）
+    pub fn with_subnet_sweep(mut self, network: Ipv4Addr, prefix_len: u8) -> Self {
+        self.targets.push(ProbeTarget::Sweep { network, prefix_len });
+        self
+    }
+
+    /// 添加单个探测地址
+    pub fn with_target(mut self, addr: SocketAddr) -> Self {
+        self.targets.push(ProbeTarget::Unicast(addr));
+        self
+    }
+
+    /// 获取应答端端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// 获取探测间隔
+    pub fn probe_interval(&self) -> Duration {
+        self.probe_interval
+    }
+
+    /// 验证配置
+    pub fn validate(&self) -> Result<(), NetError> {
+        if self.port == 0 {
+            return Err(NetError::Configuration("port cannot be 0".to_string()));
+        }
+        if self.probe_interval.is_zero() {
+            return Err(NetError::Configuration("probe_interval cannot be 0".to_string()));
+        }
+        for target in &self.targets {
+            if let ProbeTarget::Sweep { prefix_len, .. } = target {
+                if !(MIN_SWEEP_PREFIX_LEN..=30).contains(prefix_len) {
+                    return Err(NetError::Configuration(format!(
+                        "Subnet sweep prefix must be between /{} and /30, got /{}",
+                        MIN_SWEEP_PREFIX_LEN, prefix_len
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// 展开为具体的探测地址（去重）
+    pub fn targets(&self) -> Result<Vec<SocketAddr>, NetError> {
+        self.validate()?;
+
+        let mut addrs = Vec::new();
+        if self.targets.is_empty() {
+            addrs.push(SocketAddr::new(IpAddr::V4(Ipv4Addr::BROADCAST), self.port));
+        }
+        for target in &self.targets {
+            match target {
+                ProbeTarget::Broadcast(addr) => addrs.push(SocketAddr::new(IpAddr::V4(*addr), self.port)),
+                ProbeTarget::Sweep { network, prefix_len } => {
+                    let mask = u32::MAX << (32 - u32::from(*prefix_len));
+                    let first = u32::from(*network) & mask;
+                    let last = first | !mask;
+                    addrs.extend(
+                        (first + 1..last).map(|host| SocketAddr::new(IpAddr::V4(Ipv4Addr::from(host)), self.port)),
+                    );
+                }
+                ProbeTarget::Unicast(addr) => addrs.push(*addr),
+            }
+        }
+
+        let mut seen = HashSet::new();
+        addrs.retain(|addr| seen.insert(*addr));
+        Ok(addrs)
+    }
+}
+
+impl Default for UdpProberConfig {
+    fn default() -> Self {
+        Self {
+            port: DEFAULT_UDP_DISCOVERY_PORT,
+            probe_interval: Duration::from_secs(DEFAULT_PROBE_INTERVAL_SECS),
+            targets: Vec::new(),
+        }
+    }
+}
+
+/// UDP 发现探测端
+///
+/// # Example
+///
+/// ```no_run
+/// use nearclip_net::{DiscoveryKey, MdnsDiscovery, UdpProber, UdpProberConfig};
+///
+/// # async fn example() -> Result<(), nearclip_net::NetError> {
+/// let discovery = MdnsDiscovery::new()?;
+/// let mut events = discovery.subscribe();
+///
+/// // 探测结果与 mDNS 结果进入同一个事件流
+/// let mut prober = UdpProber::new(UdpProberConfig::new(), discovery.sink())?;
+/// prober.add_peer("device-002", DiscoveryKey::derive(b"pairing secret")).await;
+/// prober.start().await?;
+///
+/// let event = events.recv().await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct UdpProber {
+    /// 配置
+    config: UdpProberConfig,
+    /// 结果汇聚点
+    sink: DiscoverySink,
+    /// 已配对设备的发现密钥
+    peers: PeerKeys,
+    /// 立即探测信号
+    trigger: Arc<Notify>,
+    /// 探测任务句柄
+    task: Option<JoinHandle<()>>,
+}
+
+impl UdpProber {
+    /// 创建探测端
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - 探测配置
+    /// * `sink` - 结果汇聚点，通常来自 [`MdnsDiscovery::sink`](crate::MdnsDiscovery::sink)
+    pub fn new(config: UdpProberConfig, sink: DiscoverySink) -> Result<Self, NetError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            sink,
+            peers: Arc::new(RwLock::new(Vec::new())),
+            trigger: Arc::new(Notify::new()),
+            task: None,
+        })
+    }
+
+    /// 添加要寻找的已配对设备（同一设备重复添加时替换密钥）
+    pub async fn add_peer(&self, device_id: impl Into<String>, key: DiscoveryKey) {
+        let device_id = device_id.into();
+        let mut peers = self.peers.write().await;
+        peers.retain(|(id, _)| *id != device_id);
+        peers.push((device_id, key));
+    }
+
+    /// 移除已配对设备
+    pub async fn remove_peer(&self, device_id: &str) {
+        self.peers.write().await.retain(|(id, _)| id != device_id);
+    }
+
+    /// 开始周期探测（启动后立即探测一轮）
+    #[instrument(skip(self))]
+    pub async fn start(&mut self) -> Result<(), NetError> {
+        if self.task.is_some() {
+            warn!("UDP prober already running");
+            return Ok(());
+        }
+
+        let targets = self.config.targets()?;
+        let socket = UdpSocket::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)).await?;
+        socket.set_broadcast(true)?;
+
+        let target_count = targets.len();
+        let prober = ProbeLoop {
+            socket,
+            targets,
+            probe_interval: self.config.probe_interval,
+            peers: Arc::clone(&self.peers),
+            sink: self.sink.clone(),
+            trigger: Arc::clone(&self.trigger),
+        };
+        self.task = Some(tokio::spawn(prober.run()));
+
+        info!(targets = target_count, "UDP discovery prober started");
+        Ok(())
+    }
+
+    /// 立即发起一轮探测（如网络变化后）
+    pub fn probe_now(&self) {
+        self.trigger.notify_one();
+    }
+
+    /// 停止探测
+    pub async fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+            let _ = task.await;
+            info!("UDP discovery prober stopped");
+        }
+    }
+
+    /// 是否正在探测
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+impl Drop for UdpProber {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// 通过 UDP 发现的设备使用的服务名
+///
+/// 与 mDNS 服务名区分，设备改由 mDNS 上报后 UDP 的离线判定不会误删设备。
+fn udp_fullname(device_id: &str) -> String {
+    format!("{}._nearclip._udp.", device_id)
+}
+
+/// 探测任务状态
+struct ProbeLoop {
+    socket: UdpSocket,
+    targets: Vec<SocketAddr>,
+    probe_interval: Duration,
+    peers: PeerKeys,
+    sink: DiscoverySink,
+    trigger: Arc<Notify>,
+}
+
+impl ProbeLoop {
+    async fn run(self) {
+        let mut ticker = tokio::time::interval(self.probe_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        let stale_after = self.probe_interval * STALE_AFTER_PROBES;
+        let mut nonces: Vec<[u8; NONCE_LEN]> = Vec::with_capacity(ACCEPTED_ROUNDS);
+        let mut last_seen: HashMap<String, Instant> = HashMap::new();
+        let mut buf = [0u8; MAX_PACKET_LEN + 1];
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = self.trigger.notified() => {}
+                received = self.socket.recv_from(&mut buf) => {
+                    match received {
+                        Ok((len, from)) => {
+                            if let Some(device_id) = self.handle_response(&buf[..len], from, &nonces).await {
+                                last_seen.insert(device_id, Instant::now());
+                            }
+                        }
+                        Err(e) => debug!(error = %e, "UDP discovery receive error"),
+                    }
+                    continue;
+                }
+            }
+
+            // 长时间无应答的设备判定离线
+            let lost: Vec<String> = last_seen
+                .iter()
+                .filter(|(_, seen)| seen.elapsed() > stale_after)
+                .map(|(id, _)| id.clone())
+                .collect();
+            for device_id in lost {
+                last_seen.remove(&device_id);
+                self.sink.remove(&udp_fullname(&device_id)).await;
+            }
+
+            let mut nonce = [0u8; NONCE_LEN];
+            rand::thread_rng().fill_bytes(&mut nonce);
+            if nonces.len() == ACCEPTED_ROUNDS {
+                nonces.remove(0);
+            }
+            nonces.push(nonce);
+
+            self.send_probes(nonce).await;
+        }
+    }
+
+    /// 向所有目标发送针对每个已配对设备的探测
+    async fn send_probes(&self, nonce: [u8; NONCE_LEN]) {
+        let peers = self.peers.read().await.clone();
+        if peers.is_empty() {
+            return;
+        }
+
+        let timestamp = unix_now();
+        for (device_id, key) in &peers {
+            let probe = Packet::probe(key, device_id, nonce, timestamp).encode();
+            for target in &self.targets {
+                if let Err(e) = self.socket.send_to(&probe, target).await {
+                    debug!(target = %target, error = %e, "Failed to send UDP discovery probe");
+                }
+            }
+        }
+        debug!(peers = peers.len(), targets = self.targets.len(), "UDP discovery probes sent");
+    }
+
+    /// 处理应答，认证通过时上报设备并返回设备 ID
+    async fn handle_response(
+        &self,
+        data: &[u8],
+        from: SocketAddr,
+        nonces: &[[u8; NONCE_LEN]],
+    ) -> Option<String> {
+        let response = Packet::decode(data)?;
+        let Packet::Response { nonce, port, .. } = response else {
+            return None;
+        };
+        if port == 0 || !nonces.contains(&nonce) {
+            return None;
+        }
+
+        let device_id = {
+            let peers = self.peers.read().await;
+            peers
+                .iter()
+                .find(|(id, key)| response.verify_response(key, id))
+                .map(|(id, _)| id.clone())?
+        };
+
+        let now = Instant::now();
+        self.sink
+            .report(DiscoveredDevice {
+                device_id: device_id.clone(),
+                public_key_hash: String::new(),
+                addresses: HashSet::from([from.ip()]),
+                port,
+                fullname: udp_fullname(&device_id),
+                discovered_at: now,
+                last_seen: now,
+            })
+            .await;
+        Some(device_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_targets_use_limited_broadcast() {
+        let targets = UdpProberConfig::new().with_port(9000).targets().unwrap();
+        assert_eq!(targets, vec!["255.255.255.255:9000".parse().unwrap()]);
+    }
+
+    #[test]
+    fn test_subnet_sweep_excludes_network_and_broadcast() {
+        let targets = UdpProberConfig::new()
+            .with_port(9000)
+            .with_subnet_sweep(Ipv4Addr::new(192, 168, 1, 77), 30)
+            .with_target("192.168.1.1:9000".parse().unwrap())
+            .targets()
+            .unwrap();
+        assert_eq!(
+            targets,
+            vec![
+                "192.168.1.77:9000".parse::<SocketAddr>().unwrap(),
+                "192.168.1.78:9000".parse().unwrap(),
+            ]
+            .into_iter()
+            .chain(["192.168.1.1:9000".parse().unwrap()])
+            .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_invalid_config_rejected() {
+        assert!(UdpProberConfig::new().with_subnet_sweep(Ipv4Addr::new(10, 0, 0, 0), 16).validate().is_err());
+        assert!(UdpProberConfig::new().with_port(0).validate().is_err());
+        assert!(UdpProberConfig::new().with_probe_interval(Duration::ZERO).validate().is_err());
+    }
+}
@@ -0,0 +1,246 @@
+//! UDP 发现应答端
+//!
+//! 在固定端口监听探测报文，只应答已配对设备发来的、针对本设备的认证探测。
+//! 未通过认证的报文一律静默丢弃，不暴露本设备的存在。
+
+use crate::error::NetError;
+use crate::mdns::DiscoveryKey;
+use crate::udp::packet::{is_fresh, unix_now, Packet, MAX_PACKET_LEN};
+use crate::udp::{PeerKeys, DEFAULT_UDP_DISCOVERY_PORT};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tokio::net::UdpSocket;
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, instrument, warn};
+
+/// UDP 发现应答端配置
+///
+/// # Example
+///
+/// ```
+/// use nearclip_net::UdpResponderConfig;
+///
+/// let config = UdpResponderConfig::new("device-001".to_string(), 8765).with_port(0);
+///
+/// assert_eq!(config.device_id(), "device-001");
+/// assert_eq!(config.service_port(), 8765);
+/// ```
+#[derive(Debug, Clone)]
+pub struct UdpResponderConfig {
+    /// 本设备 ID
+    device_id: String,
+    /// 应答中告知的 TCP 服务端口
+    service_port: u16,
+    /// 绑定地址
+    bind_addr: IpAddr,
+    /// 监听端口
+    port: u16,
+}
+
+impl UdpResponderConfig {
+    /// 创建配置
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - 本设备 ID
+    /// * `service_port` - 本设备 TCP 服务端口
+    pub fn new(device_id: String, service_port: u16) -> Self {
+        Self {
+            device_id,
+            service_port,
+            bind_addr: IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            port: DEFAULT_UDP_DISCOVERY_PORT,
+        }
+    }
+
+    /// 设置监听端口（0 表示动态分配，仅用于测试）
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// 设置绑定地址
+    pub fn with_bind_addr(mut self, addr: IpAddr) -> Self {
+        self.bind_addr = addr;
+        self
+    }
+
+    /// 获取设备 ID
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// 获取 TCP 服务端口
+    pub fn service_port(&self) -> u16 {
+        self.service_port
+    }
+
+    /// 获取监听端口
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// 验证配置
+    pub fn validate(&self) -> Result<(), NetError> {
+        if self.device_id.is_empty() {
+            return Err(NetError::Configuration("device_id cannot be empty".to_string()));
+        }
+        if self.service_port == 0 {
+            return Err(NetError::Configuration("service_port cannot be 0".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// UDP 发现应答端
+///
+/// # Example
+///
+/// ```no_run
+/// use nearclip_net::{DiscoveryKey, UdpResponder, UdpResponderConfig};
+///
+/// # async fn example() -> Result<(), nearclip_net::NetError> {
+/// let mut responder = UdpResponder::new(UdpResponderConfig::new("device-001".to_string(), 8765))?;
+/// responder.add_peer("device-002", DiscoveryKey::derive(b"pairing secret")).await;
+///
+/// responder.start().await?;
+/// // ... 已配对设备可以通过单播或广播探测找到本设备 ...
+/// responder.stop().await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct UdpResponder {
+    /// 配置
+    config: UdpResponderConfig,
+    /// 已配对设备的发现密钥
+    peers: PeerKeys,
+    /// 实际监听地址
+    local_addr: Option<SocketAddr>,
+    /// 应答任务句柄
+    task: Option<JoinHandle<()>>,
+}
+
+impl UdpResponder {
+    /// 创建应答端
+    ///
+    /// # Returns
+    ///
+    /// 配置无效时返回 `NetError::Configuration`
+    pub fn new(config: UdpResponderConfig) -> Result<Self, NetError> {
+        config.validate()?;
+        Ok(Self {
+            config,
+            peers: Arc::new(RwLock::new(Vec::new())),
+            local_addr: None,
+            task: None,
+        })
+    }
+
+    /// 添加已配对设备（同一设备重复添加时替换密钥）
+    pub async fn add_peer(&self, device_id: impl Into<String>, key: DiscoveryKey) {
+        let device_id = device_id.into();
+        let mut peers = self.peers.write().await;
+        peers.retain(|(id, _)| *id != device_id);
+        peers.push((device_id, key));
+    }
+
+    /// 移除已配对设备
+    pub async fn remove_peer(&self, device_id: &str) {
+        self.peers.write().await.retain(|(id, _)| id != device_id);
+    }
+
+    /// 开始监听
+    ///
+    /// # Returns
+    ///
+    /// 端口绑定失败时返回 `NetError::Io`
+    #[instrument(skip(self), fields(device_id = %self.config.device_id))]
+    pub async fn start(&mut self) -> Result<(), NetError> {
+        if self.task.is_some() {
+            warn!("UDP responder already running");
+            return Ok(());
+        }
+
+        let socket = UdpSocket::bind(SocketAddr::new(self.config.bind_addr, self.config.port)).await?;
+        let local_addr = socket.local_addr()?;
+
+        let device_id = self.config.device_id.clone();
+        let service_port = self.config.service_port;
+        let peers = Arc::clone(&self.peers);
+        self.task = Some(tokio::spawn(async move {
+            run_responder(socket, device_id, service_port, peers).await;
+        }));
+        self.local_addr = Some(local_addr);
+
+        info!(addr = %local_addr, "UDP discovery responder started");
+        Ok(())
+    }
+
+    /// 停止监听
+    pub async fn stop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+            let _ = task.await;
+            info!("UDP discovery responder stopped");
+        }
+        self.local_addr = None;
+    }
+
+    /// 实际监听地址（未启动时为 None）
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    /// 是否正在监听
+    pub fn is_running(&self) -> bool {
+        self.task.is_some()
+    }
+}
+
+impl Drop for UdpResponder {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+/// 应答循环
+async fn run_responder(socket: UdpSocket, device_id: String, service_port: u16, peers: PeerKeys) {
+    let mut buf = [0u8; MAX_PACKET_LEN + 1];
+    loop {
+        let (len, from) = match socket.recv_from(&mut buf).await {
+            Ok(received) => received,
+            Err(e) => {
+                // ICMP 端口不可达等错误会在部分平台上从 recv 返回，忽略即可
+                debug!(error = %e, "UDP discovery receive error");
+                continue;
+            }
+        };
+
+        let Some(probe @ Packet::Probe { nonce, timestamp, .. }) = Packet::decode(&buf[..len]) else {
+            continue;
+        };
+        if !is_fresh(timestamp, unix_now()) {
+            debug!(from = %from, "Ignoring stale UDP discovery probe");
+            continue;
+        }
+
+        let response = {
+            let peers = peers.read().await;
+            peers
+                .iter()
+                .find(|(_, key)| probe.verify_probe(key, &device_id))
+                .map(|(peer_id, key)| (peer_id.clone(), Packet::response(key, &device_id, nonce, service_port)))
+        };
+        let Some((peer_id, response)) = response else {
+            continue;
+        };
+
+        match socket.send_to(&response.encode(), from).await {
+            Ok(_) => debug!(peer_id = %peer_id, to = %from, "Answered UDP discovery probe"),
+            Err(e) => debug!(peer_id = %peer_id, to = %from, error = %e, "Failed to answer UDP discovery probe"),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! QUIC 服务端/客户端集成测试
+//!
+//! 验证固定证书握手、证书不匹配时失败，以及同一客户端重连时使用 0-RTT。
+
+use nearclip_crypto::{TlsCertificate, TlsClientConfig, TlsServerConfig};
+use nearclip_net::{QuicClient, QuicServer, QuicServerConfig};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+fn start_server(cert: &TlsCertificate) -> (Arc<QuicServer>, SocketAddr) {
+    let server_config = TlsServerConfig::new(cert).unwrap().config();
+    let config = QuicServerConfig::new().with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let server = Arc::new(QuicServer::bind(config, server_config).unwrap());
+    let addr = server.local_addr().unwrap();
+    (server, addr)
+}
+
+/// 客户端打开一个双向流写入数据，服务端读回
+async fn echo_once(server: &QuicServer, client: &QuicClient, addr: SocketAddr) -> bool {
+    let (conn, accepted) = tokio::join!(client.connect(addr, "localhost"), server.accept());
+    let (mut conn, accepted) = (conn.unwrap(), accepted.unwrap());
+    let zero_rtt = conn.take_zero_rtt_accepted();
+    let is_zero_rtt = zero_rtt.is_some();
+
+    let (mut send, _recv) = conn.connection().open_bi().await.unwrap();
+    send.write_all(b"hello").await.unwrap();
+    send.finish().unwrap();
+
+    let (_send, mut recv) = accepted.connection().accept_bi().await.unwrap();
+    assert_eq!(recv.read_to_end(64).await.unwrap(), b"hello");
+
+    if let Some(zero_rtt) = zero_rtt {
+        assert!(zero_rtt.await, "server should accept 0-RTT data");
+    }
+    conn.close("done");
+    is_zero_rtt
+}
+
+#[tokio::test]
+async fn test_quic_reconnect_uses_zero_rtt() {
+    let cert = TlsCertificate::generate(&["localhost".to_string()]).unwrap();
+    let (server, addr) = start_server(&cert);
+    let client = QuicClient::new(TlsClientConfig::new(cert.cert_der()).unwrap().config()).unwrap();
+
+    // 首次连接完成完整握手并获得会话票据
+    assert!(!echo_once(&server, &client, addr).await);
+    // 重连使用 0-RTT
+    assert!(echo_once(&server, &client, addr).await);
+}
+
+#[tokio::test]
+async fn test_quic_connect_fails_with_wrong_cert() {
+    let cert = TlsCertificate::generate(&["localhost".to_string()]).unwrap();
+    let other = TlsCertificate::generate(&["localhost".to_string()]).unwrap();
+    let (server, addr) = start_server(&cert);
+
+    let accept = tokio::spawn(async move { server.accept().await });
+    let client = QuicClient::new(TlsClientConfig::new(other.cert_der()).unwrap().config()).unwrap();
+    assert!(client.connect(addr, "localhost").await.is_err());
+    assert!(accept.await.unwrap().is_err());
+}
@@ -350,3 +350,53 @@ async fn test_tcp_client_config_debug() {
     assert!(debug_str.contains("TcpClientConfig"));
     assert!(debug_str.contains("127.0.0.1:8765"));
 }
+
+#[tokio::test]
+async fn test_tcp_alpn_negotiated() {
+    let (_, server_config, client_config) = create_test_tls_configs();
+
+    let server_cfg = TcpServerConfig::new()
+        .with_port(0)
+        .with_alpn_protocols(vec![b"proto/2".to_vec(), b"proto/1".to_vec()]);
+    let server = TcpServer::bind(server_cfg, server_config).await.unwrap();
+    let addr = server.local_addr().unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        let conn = server.accept().await.unwrap();
+        conn.alpn_protocol().map(<[u8]>::to_vec)
+    });
+
+    let client_cfg = TcpClientConfig::new(addr).with_alpn_protocols(vec![b"proto/1".to_vec()]);
+    let conn = TcpClient::connect(client_cfg, client_config, "localhost")
+        .await
+        .unwrap();
+
+    assert_eq!(conn.alpn_protocol(), Some(&b"proto/1"[..]));
+    let (reader, _writer) = conn.into_split();
+    assert_eq!(reader.alpn_protocol(), Some(&b"proto/1"[..]));
+    assert_eq!(server_handle.await.unwrap(), Some(b"proto/1".to_vec()));
+}
+
+#[tokio::test]
+async fn test_tcp_alpn_absent_on_one_side() {
+    let (_, server_config, client_config) = create_test_tls_configs();
+
+    // 服务端提供 ALPN，客户端不提供：连接照常建立，没有协商结果
+    let server_cfg = TcpServerConfig::new()
+        .with_port(0)
+        .with_alpn_protocols(vec![b"proto/1".to_vec()]);
+    let server = TcpServer::bind(server_cfg, server_config).await.unwrap();
+    let addr = server.local_addr().unwrap();
+
+    let server_handle = tokio::spawn(async move {
+        let conn = server.accept().await.unwrap();
+        conn.alpn_protocol().map(<[u8]>::to_vec)
+    });
+
+    let conn = TcpClient::connect(TcpClientConfig::new(addr), client_config, "localhost")
+        .await
+        .unwrap();
+
+    assert_eq!(conn.alpn_protocol(), None);
+    assert_eq!(server_handle.await.unwrap(), None);
+}
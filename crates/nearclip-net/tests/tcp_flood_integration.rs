@@ -0,0 +1,125 @@
+//! TCP 服务端连接防护集成测试
+//!
+//! 使用本地洪泛客户端（从 127.0.0.2 发起、只建立 TCP 不握手）验证
+//! 并发上限、握手超时、封禁，以及洪泛期间正常客户端仍能连接。
+
+use nearclip_crypto::{TlsCertificate, TlsClientConfig, TlsServerConfig};
+use nearclip_net::{ConnectionLimits, TcpClient, TcpClientConfig, TcpServer, TcpServerConfig};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+use tokio::net::{TcpSocket, TcpStream};
+
+const FLOOD_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 2));
+
+fn create_test_tls_configs() -> (Arc<rustls::ServerConfig>, Arc<rustls::ClientConfig>) {
+    let cert = TlsCertificate::generate(&["localhost".to_string()]).unwrap();
+    let server_config = TlsServerConfig::new(&cert).unwrap().config();
+    let client_config = TlsClientConfig::new(cert.cert_der()).unwrap().config();
+    (server_config, client_config)
+}
+
+async fn start_server(limits: ConnectionLimits) -> (Arc<TcpServer>, SocketAddr, Arc<rustls::ClientConfig>) {
+    let (server_config, client_config) = create_test_tls_configs();
+    let config = TcpServerConfig::new()
+        .with_bind_addr(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+        .with_limits(limits);
+    let server = Arc::new(TcpServer::bind(config, server_config).await.unwrap());
+    let port = server.local_addr().unwrap().port();
+    (server, SocketAddr::from(([127, 0, 0, 1], port)), client_config)
+}
+
+/// 从洪泛地址建立一个不做 TLS 握手的 TCP 连接
+async fn flood_connect(port: u16) -> TcpStream {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.bind(SocketAddr::new(FLOOD_IP, 0)).unwrap();
+    socket.connect(SocketAddr::new(FLOOD_IP, port)).await.unwrap()
+}
+
+/// 连接是否被服务端关闭
+async fn is_closed_by_server(stream: &mut TcpStream) -> bool {
+    let mut buf = [0u8; 1];
+    matches!(
+        tokio::time::timeout(Duration::from_secs(2), stream.read(&mut buf)).await,
+        Ok(Ok(0)) | Ok(Err(_))
+    )
+}
+
+#[tokio::test]
+async fn test_flood_does_not_block_legitimate_client() {
+    let limits = ConnectionLimits::new()
+        .with_max_connections_per_ip(3)
+        .with_handshake_timeout(Duration::from_millis(300))
+        .with_max_handshake_failures(100);
+    let (server, addr, client_config) = start_server(limits).await;
+
+    let accept_server = server.clone();
+    let accept = tokio::spawn(async move {
+        loop {
+            if let Ok(conn) = accept_server.accept().await {
+                return conn;
+            }
+        }
+    });
+
+    // 洪泛：超出上限的连接被立即关闭
+    let mut flood = Vec::new();
+    for _ in 0..6 {
+        flood.push(flood_connect(addr.port()).await);
+    }
+    let mut extra = flood.pop().unwrap();
+    assert!(is_closed_by_server(&mut extra).await);
+    assert_eq!(server.guard().active_connections(FLOOD_IP), 3);
+
+    // 洪泛连接卡在握手阶段，正常客户端仍能立即完成握手
+    let client = tokio::time::timeout(
+        Duration::from_secs(2),
+        TcpClient::connect(TcpClientConfig::new(addr), client_config, "localhost"),
+    )
+    .await
+    .expect("legitimate client should not wait for flood handshakes")
+    .unwrap();
+    let conn = accept.await.unwrap();
+    assert_eq!(conn.peer_addr().ip(), IpAddr::V4(Ipv4Addr::LOCALHOST));
+    drop(client);
+}
+
+#[tokio::test]
+async fn test_handshake_timeouts_lead_to_ban() {
+    let limits = ConnectionLimits::new()
+        .with_max_connections_per_ip(10)
+        .with_handshake_timeout(Duration::from_millis(100))
+        .with_max_handshake_failures(2)
+        .with_ban_duration(Duration::from_secs(30));
+    let (server, addr, _) = start_server(limits).await;
+
+    let accept_server = server.clone();
+    let accept = tokio::spawn(async move {
+        let mut errors = 0;
+        while errors < 2 {
+            if accept_server.accept().await.is_err() {
+                errors += 1;
+            }
+        }
+    });
+
+    let mut first = flood_connect(addr.port()).await;
+    let mut second = flood_connect(addr.port()).await;
+    tokio::time::timeout(Duration::from_secs(2), accept)
+        .await
+        .expect("handshakes should time out")
+        .unwrap();
+
+    assert!(is_closed_by_server(&mut first).await);
+    assert!(is_closed_by_server(&mut second).await);
+    assert!(server.guard().is_banned(FLOOD_IP));
+    assert!(!server.guard().is_banned(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+
+    // 封禁期间的新连接被直接关闭
+    let accept_server = server.clone();
+    let _accept = tokio::spawn(async move { accept_server.accept().await });
+    let mut banned = flood_connect(addr.port()).await;
+    assert!(is_closed_by_server(&mut banned).await);
+    assert_eq!(server.guard().active_connections(FLOOD_IP), 0);
+}
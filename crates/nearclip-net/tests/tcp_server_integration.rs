@@ -50,6 +50,31 @@ async fn test_tcp_server_bind_specific_port() {
     assert!(addr.port() > 0);
 }
 
+#[tokio::test]
+async fn test_tcp_server_port_range_skips_busy_port() {
+    let (server_config, _) = create_test_tls_configs();
+    let localhost = std::net::IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+    let busy = TcpServer::bind(TcpServerConfig::new().with_bind_addr(localhost), server_config.clone())
+        .await
+        .unwrap();
+    let busy_port = busy.local_addr().unwrap().port();
+
+    // 首选端口被占用，范围内没有其他端口：回退到动态端口
+    let config = TcpServerConfig::new()
+        .with_bind_addr(localhost)
+        .with_port(busy_port)
+        .with_port_range(busy_port..=busy_port);
+    let server = TcpServer::bind(config, server_config.clone()).await.unwrap();
+    let port = server.local_addr().unwrap().port();
+    assert_ne!(port, 0);
+    assert_ne!(port, busy_port);
+
+    // 没有范围时首选端口被占用直接报错
+    let config = TcpServerConfig::new().with_bind_addr(localhost).with_port(busy_port);
+    assert!(TcpServer::bind(config, server_config).await.is_err());
+}
+
 #[tokio::test]
 async fn test_tcp_server_accept_tls_connection() {
     let (server_config, client_config) = create_test_tls_configs();
@@ -0,0 +1,109 @@
+//! UDP 单播发现集成测试
+//!
+//! 在回环地址上验证探测、应答和事件上报的完整流程。
+
+use nearclip_net::{
+    DiscoveryEvent, DiscoveryKey, DiscoverySink, MdnsDiscovery, UdpProber, UdpProberConfig,
+    UdpResponder, UdpResponderConfig,
+};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+const SERVICE_PORT: u16 = 8765;
+
+async fn start_responder(device_id: &str, peer_id: &str, key: DiscoveryKey) -> UdpResponder {
+    let config = UdpResponderConfig::new(device_id.to_string(), SERVICE_PORT)
+        .with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        .with_port(0);
+    let mut responder = UdpResponder::new(config).unwrap();
+    responder.add_peer(peer_id, key).await;
+    responder.start().await.unwrap();
+    responder
+}
+
+fn prober_config(responder: &UdpResponder, interval: Duration) -> UdpProberConfig {
+    let addr = responder.local_addr().unwrap();
+    UdpProberConfig::new()
+        .with_port(addr.port())
+        .with_target(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), addr.port()))
+        .with_probe_interval(interval)
+}
+
+async fn next_event(events: &mut broadcast::Receiver<DiscoveryEvent>, timeout: Duration) -> Option<DiscoveryEvent> {
+    tokio::time::timeout(timeout, events.recv()).await.ok()?.ok()
+}
+
+#[tokio::test]
+async fn test_probe_finds_paired_device() {
+    let key = DiscoveryKey::derive(b"pairing secret");
+    let responder = start_responder("device-a", "device-b", key.clone()).await;
+
+    let sink = DiscoverySink::new();
+    let mut events = sink.subscribe();
+    let mut prober = UdpProber::new(prober_config(&responder, Duration::from_secs(30)), sink.clone()).unwrap();
+    prober.add_peer("device-a", key).await;
+    prober.start().await.unwrap();
+
+    match next_event(&mut events, Duration::from_secs(5)).await {
+        Some(DiscoveryEvent::DeviceFound(device)) => {
+            assert_eq!(device.device_id, "device-a");
+            assert_eq!(device.port, SERVICE_PORT);
+            assert!(device.addresses.contains(&IpAddr::V4(Ipv4Addr::LOCALHOST)));
+        }
+        other => panic!("Expected DeviceFound, got {:?}", other),
+    }
+    assert!(sink.get_device("device-a").await.is_some());
+
+    prober.stop().await;
+    assert!(!prober.is_running());
+}
+
+#[tokio::test]
+async fn test_probe_with_wrong_key_is_ignored() {
+    let responder = start_responder("device-a", "device-b", DiscoveryKey::derive(b"pairing secret")).await;
+
+    let sink = DiscoverySink::new();
+    let mut events = sink.subscribe();
+    let mut prober =
+        UdpProber::new(prober_config(&responder, Duration::from_millis(100)), sink).unwrap();
+    prober.add_peer("device-a", DiscoveryKey::derive(b"someone else")).await;
+    prober.start().await.unwrap();
+
+    assert!(next_event(&mut events, Duration::from_millis(500)).await.is_none());
+}
+
+#[tokio::test]
+async fn test_results_feed_mdns_discovery_stream() {
+    let key = DiscoveryKey::derive(b"pairing secret");
+    let mut responder = start_responder("device-a", "device-b", key.clone()).await;
+
+    let discovery = MdnsDiscovery::new().unwrap();
+    let mut events = discovery.subscribe();
+    let mut prober =
+        UdpProber::new(prober_config(&responder, Duration::from_millis(100)), discovery.sink()).unwrap();
+    prober.add_peer("device-a", key).await;
+    prober.start().await.unwrap();
+
+    assert!(matches!(
+        next_event(&mut events, Duration::from_secs(5)).await,
+        Some(DiscoveryEvent::DeviceFound(ref device)) if device.device_id == "device-a"
+    ));
+    assert_eq!(discovery.get_device("device-a").await.unwrap().port, SERVICE_PORT);
+
+    // 应答端停止后，连续几个周期无应答，设备被报告为离线
+    responder.stop().await;
+    let lost = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            match events.recv().await {
+                Ok(DiscoveryEvent::DeviceLost { device_id, .. }) => break device_id,
+                Ok(_) => continue,
+                Err(e) => panic!("event stream closed: {}", e),
+            }
+        }
+    })
+    .await
+    .expect("device should be reported lost");
+    assert_eq!(lost, "device-a");
+    assert!(discovery.get_device("device-a").await.is_none());
+}
@@ -58,6 +58,11 @@ pub struct PairingRequest {
 
     /// Random nonce for freshness verification
     pub nonce: [u8; 32],
+
+    /// BLE advertisement key, so the responder can recognize the
+    /// initiator's advertisements (absent from older peers)
+    #[serde(default)]
+    pub advertisement_key: Option<Vec<u8>>,
 }
 
 /// Response to pairing request from responder
@@ -81,7 +86,16 @@ pub struct PairingResponse {
     pub nonce: [u8; 32],
 
     /// Signature of initiator's nonce (proves identity)
+    ///
+    /// HMAC-SHA256 of the initiator's nonce keyed by the ECDH shared
+    /// secret. Must be non-empty; peers that still send an empty
+    /// signature are rejected.
     pub signature: Vec<u8>,
+
+    /// BLE advertisement key, so the initiator can recognize the
+    /// responder's advertisements (absent from older peers)
+    #[serde(default)]
+    pub advertisement_key: Option<Vec<u8>>,
 }
 
 /// Final confirmation from initiator
@@ -90,6 +104,9 @@ pub struct PairingResponse {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PairingConfirm {
     /// Signature of responder's nonce
+    ///
+    /// HMAC-SHA256 of the responder's nonce keyed by the ECDH shared
+    /// secret, verified before the responder completes pairing.
     pub signature: Vec<u8>,
 }
 
@@ -125,9 +142,16 @@ impl PairingRequest {
             platform,
             public_key,
             nonce,
+            advertisement_key: None,
         }
     }
 
+    /// Attach the initiator's BLE advertisement key
+    pub fn with_advertisement_key(mut self, key: Vec<u8>) -> Self {
+        self.advertisement_key = Some(key);
+        self
+    }
+
     /// Validate the request
     pub fn validate(&self) -> Result<(), PairingError> {
         if self.device_id.is_empty() {
@@ -160,9 +184,16 @@ impl PairingResponse {
             public_key,
             nonce,
             signature,
+            advertisement_key: None,
         }
     }
 
+    /// Attach the responder's BLE advertisement key
+    pub fn with_advertisement_key(mut self, key: Vec<u8>) -> Self {
+        self.advertisement_key = Some(key);
+        self
+    }
+
     /// Validate the response
     pub fn validate(&self) -> Result<(), PairingError> {
         if self.device_id.is_empty() {
@@ -250,6 +281,7 @@ mod tests {
             platform: DevicePlatform::MacOS,
             public_key: vec![1, 2, 3, 4],
             nonce: create_test_nonce(),
+            advertisement_key: None,
         }
     }
 
@@ -289,6 +321,7 @@ mod tests {
             public_key: vec![5, 6, 7, 8],
             nonce: create_test_nonce(),
             signature: vec![9, 10, 11, 12],
+            advertisement_key: None,
         };
         assert!(response.validate().is_ok());
     }
@@ -302,6 +335,7 @@ mod tests {
             public_key: vec![5, 6, 7, 8],
             nonce: create_test_nonce(),
             signature: vec![9, 10, 11, 12],
+            advertisement_key: None,
         };
         response.signature = Vec::new();
         assert!(response.validate().is_err());
@@ -357,6 +391,7 @@ mod tests {
             platform: DevicePlatform::MacOS,
             public_key: vec![1, 2, 3, 4],
             nonce,
+            advertisement_key: Some(vec![3u8; 32]),
         });
 
         let response = PairingMessage::PairingResponse(PairingResponse {
@@ -366,6 +401,7 @@ mod tests {
             public_key: vec![5, 6, 7, 8],
             nonce,
             signature: vec![9, 10],
+            advertisement_key: None,
         });
 
         let confirm = PairingMessage::PairingConfirm(PairingConfirm {
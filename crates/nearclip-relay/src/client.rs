@@ -0,0 +1,283 @@
+//! Relay client
+//!
+//! One authenticated connection to a relay, shared by all peers of a device.
+//! Payloads from each peer are delivered to that peer's inbox; payloads from
+//! a peer without an open inbox are announced through [`RelayClient::accept`].
+
+use nearclip_crypto::EcdhKeyPair;
+use nearclip_net::{TcpClient, TcpClientConfig, TcpReadHalf, TcpWriteHalf};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex as TokioMutex};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, instrument, warn};
+
+use crate::error::RelayError;
+use crate::protocol::{auth_proof, read_frame, write_frame, Frame, MAX_FRAME_SIZE};
+
+/// TLS server name relays use in their certificate
+pub const RELAY_SERVER_NAME: &str = "nearclip-relay";
+
+/// Payloads buffered per peer inbox
+const INBOX_SIZE: usize = 64;
+
+/// Default time allowed for connecting and authenticating
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Receiving end of a peer inbox
+pub type RelayInbox = mpsc::Receiver<Vec<u8>>;
+
+/// State shared with the reader task
+#[derive(Default)]
+struct ClientShared {
+    inboxes: Mutex<HashMap<String, mpsc::Sender<Vec<u8>>>>,
+    /// Inboxes created by incoming payloads, waiting for `accept`
+    pending: Mutex<HashMap<String, RelayInbox>>,
+    online: Mutex<HashSet<String>>,
+    connected: AtomicBool,
+}
+
+/// Relay client
+///
+/// # Example
+///
+/// ```no_run
+/// use nearclip_relay::RelayClient;
+/// use nearclip_crypto::{EcdhKeyPair, TlsClientConfig};
+///
+/// # async fn example(relay_cert_der: &[u8]) -> Result<(), nearclip_relay::RelayError> {
+/// let keypair = EcdhKeyPair::generate();
+/// let tls = TlsClientConfig::new(relay_cert_der).unwrap();
+/// let client = RelayClient::connect(
+///     "203.0.113.5:7878".parse().unwrap(),
+///     tls.config(),
+///     "my-device",
+///     &keypair,
+/// ).await?;
+///
+/// client.allow_peers(["paired-device".to_string()]).await?;
+/// client.send("paired-device", b"encrypted bytes".to_vec()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RelayClient {
+    device_id: String,
+    relay_addr: SocketAddr,
+    writer: TokioMutex<TcpWriteHalf>,
+    shared: Arc<ClientShared>,
+    accept_rx: TokioMutex<mpsc::UnboundedReceiver<String>>,
+    reader_task: JoinHandle<()>,
+}
+
+impl RelayClient {
+    /// Connect to a relay and authenticate with the device key
+    ///
+    /// # Arguments
+    /// * `relay_addr` - Relay address
+    /// * `tls_config` - TLS client config pinning the relay certificate
+    /// * `device_id` - This device's ID
+    /// * `keypair` - This device's key pair (the public key is shared with peers at pairing)
+    #[instrument(skip(tls_config, keypair))]
+    pub async fn connect(
+        relay_addr: SocketAddr,
+        tls_config: Arc<rustls::ClientConfig>,
+        device_id: &str,
+        keypair: &EcdhKeyPair,
+    ) -> Result<Self, RelayError> {
+        tokio::time::timeout(DEFAULT_CONNECT_TIMEOUT, Self::connect_inner(relay_addr, tls_config, device_id, keypair))
+            .await
+            .map_err(|_| RelayError::Timeout(format!("Connecting to relay {} timed out", relay_addr)))?
+    }
+
+    async fn connect_inner(
+        relay_addr: SocketAddr,
+        tls_config: Arc<rustls::ClientConfig>,
+        device_id: &str,
+        keypair: &EcdhKeyPair,
+    ) -> Result<Self, RelayError> {
+        let conn = TcpClient::connect(TcpClientConfig::new(relay_addr), tls_config, RELAY_SERVER_NAME).await?;
+        let (mut reader, mut writer) = conn.into_split();
+
+        write_frame(&mut writer, &Frame::Hello {
+            device_id: device_id.to_string(),
+            public_key: keypair.public_key_bytes(),
+        })
+        .await?;
+
+        let (nonce, relay_key) = match read_frame(&mut reader, MAX_FRAME_SIZE).await? {
+            Frame::Challenge { nonce, relay_key } => (nonce, relay_key),
+            Frame::Error { reason } => return Err(RelayError::Authentication(reason)),
+            other => return Err(RelayError::Protocol(format!("Expected Challenge, got {:?}", other))),
+        };
+        let shared_secret = keypair
+            .compute_shared_secret(&relay_key)
+            .map_err(|e| RelayError::Crypto(e.to_string()))?;
+        write_frame(&mut writer, &Frame::Auth { proof: auth_proof(&shared_secret, &nonce, device_id) }).await?;
+
+        match read_frame(&mut reader, MAX_FRAME_SIZE).await? {
+            Frame::Welcome => {}
+            Frame::Error { reason } => return Err(RelayError::Authentication(reason)),
+            other => return Err(RelayError::Protocol(format!("Expected Welcome, got {:?}", other))),
+        }
+        info!("Connected to relay {} as {}", relay_addr, device_id);
+
+        let shared = Arc::new(ClientShared::default());
+        shared.connected.store(true, Ordering::SeqCst);
+        let (accept_tx, accept_rx) = mpsc::unbounded_channel();
+        let reader_task = tokio::spawn(run_reader(shared.clone(), reader, accept_tx));
+
+        Ok(Self {
+            device_id: device_id.to_string(),
+            relay_addr,
+            writer: TokioMutex::new(writer),
+            shared,
+            accept_rx: TokioMutex::new(accept_rx),
+            reader_task,
+        })
+    }
+
+    /// This device's ID
+    pub fn device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    /// Relay address
+    pub fn relay_addr(&self) -> SocketAddr {
+        self.relay_addr
+    }
+
+    /// Whether the relay connection is still up
+    pub fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::SeqCst)
+    }
+
+    /// Whether a peer is connected to the relay and allowed this device too
+    pub fn is_peer_online(&self, device_id: &str) -> bool {
+        self.shared.online.lock().unwrap().contains(device_id)
+    }
+
+    /// Set the peers this device accepts payloads from (its paired devices)
+    pub async fn allow_peers(&self, device_ids: impl IntoIterator<Item = String>) -> Result<(), RelayError> {
+        self.write(&Frame::AllowPeers { device_ids: device_ids.into_iter().collect() }).await
+    }
+
+    /// Forward an (already encrypted) payload to a peer
+    pub async fn send(&self, to: &str, payload: Vec<u8>) -> Result<(), RelayError> {
+        self.write(&Frame::Send { to: to.to_string(), payload }).await
+    }
+
+    /// Open the inbox for a peer, replacing any previous one
+    pub fn open(&self, device_id: &str) -> RelayInbox {
+        if let Some(inbox) = self.shared.pending.lock().unwrap().remove(device_id) {
+            return inbox;
+        }
+        let (tx, rx) = mpsc::channel(INBOX_SIZE);
+        self.shared.inboxes.lock().unwrap().insert(device_id.to_string(), tx);
+        rx
+    }
+
+    /// Wait for a payload from a peer without an open inbox
+    ///
+    /// Returns the peer ID and its inbox (holding that first payload), or
+    /// `None` once the relay connection is closed.
+    pub async fn accept(&self) -> Option<(String, RelayInbox)> {
+        let mut accept_rx = self.accept_rx.lock().await;
+        loop {
+            let device_id = accept_rx.recv().await?;
+            if let Some(inbox) = self.shared.pending.lock().unwrap().remove(&device_id) {
+                return Some((device_id, inbox));
+            }
+        }
+    }
+
+    /// Close the relay connection
+    pub async fn close(&self) {
+        self.shared.connected.store(false, Ordering::SeqCst);
+        let _ = self.writer.lock().await.close().await;
+        self.reader_task.abort();
+    }
+
+    async fn write(&self, frame: &Frame) -> Result<(), RelayError> {
+        if !self.is_connected() {
+            return Err(RelayError::ConnectionClosed);
+        }
+        let mut writer = self.writer.lock().await;
+        write_frame(&mut writer, frame).await.inspect_err(|_| {
+            self.shared.connected.store(false, Ordering::SeqCst);
+        })
+    }
+}
+
+impl Drop for RelayClient {
+    fn drop(&mut self) {
+        self.reader_task.abort();
+    }
+}
+
+impl std::fmt::Debug for RelayClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RelayClient")
+            .field("device_id", &self.device_id)
+            .field("relay_addr", &self.relay_addr)
+            .field("connected", &self.is_connected())
+            .finish()
+    }
+}
+
+/// Dispatch frames from the relay until the connection closes
+async fn run_reader(shared: Arc<ClientShared>, mut reader: TcpReadHalf, accept_tx: mpsc::UnboundedSender<String>) {
+    loop {
+        let frame = match read_frame(&mut reader, MAX_FRAME_SIZE).await {
+            Ok(frame) => frame,
+            Err(e) => {
+                debug!("Relay connection closed: {}", e);
+                break;
+            }
+        };
+
+        match frame {
+            Frame::Deliver { from, payload } => {
+                let inbox = shared.inboxes.lock().unwrap().get(&from).filter(|tx| !tx.is_closed()).cloned();
+                let inbox = match inbox {
+                    Some(tx) => tx,
+                    None => {
+                        // First payload from this peer: create an inbox and announce it
+                        let (tx, rx) = mpsc::channel(INBOX_SIZE);
+                        shared.inboxes.lock().unwrap().insert(from.clone(), tx.clone());
+                        shared.pending.lock().unwrap().insert(from.clone(), rx);
+                        let _ = accept_tx.send(from.clone());
+                        tx
+                    }
+                };
+                if inbox.send(payload).await.is_err() {
+                    debug!("Inbox for {} closed, dropping payload", from);
+                }
+            }
+            Frame::Presence { device_id, online } => {
+                debug!("Relay peer {} is {}", device_id, if online { "online" } else { "offline" });
+                let mut peers = shared.online.lock().unwrap();
+                if online {
+                    peers.insert(device_id);
+                } else {
+                    peers.remove(&device_id);
+                }
+            }
+            Frame::Undeliverable { to, reason } => {
+                debug!("Relay could not deliver to {}: {}", to, reason);
+            }
+            Frame::Error { reason } => {
+                warn!("Relay closed the connection: {}", reason);
+                break;
+            }
+            other => warn!("Unexpected frame from relay: {:?}", other),
+        }
+    }
+
+    shared.connected.store(false, Ordering::SeqCst);
+    shared.online.lock().unwrap().clear();
+    // Dropping the senders ends every peer inbox
+    shared.inboxes.lock().unwrap().clear();
+}
@@ -0,0 +1,50 @@
+//! Relay error types
+
+use nearclip_net::NetError;
+use thiserror::Error;
+
+/// Relay errors
+#[derive(Debug, Error)]
+pub enum RelayError {
+    /// Network error (TCP/TLS)
+    #[error("Network error: {0}")]
+    Network(#[from] NetError),
+
+    /// IO error
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Malformed or unexpected frame
+    #[error("Protocol error: {0}")]
+    Protocol(String),
+
+    /// Device authentication failed
+    #[error("Authentication failed: {0}")]
+    Authentication(String),
+
+    /// Key or certificate error
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+
+    /// Connection to the relay is closed
+    #[error("Connection closed")]
+    ConnectionClosed,
+
+    /// Operation timed out
+    #[error("Timeout: {0}")]
+    Timeout(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            RelayError::Authentication("bad proof".to_string()).to_string(),
+            "Authentication failed: bad proof"
+        );
+        assert_eq!(RelayError::ConnectionClosed.to_string(), "Connection closed");
+    }
+}
@@ -0,0 +1,23 @@
+//! NearClip Relay
+//!
+//! A small self-hostable rendezvous server for devices that share neither a
+//! LAN nor BLE range. Paired devices connect out to the relay over TLS,
+//! prove possession of their device key, and exchange payloads that are
+//! already encrypted end to end (`EncryptedTransport`), so the relay never
+//! sees plaintext.
+//!
+//! - [`RelayServer`] - the relay itself (also shipped as the `nearclip-relay` binary)
+//! - [`RelayClient`] - one device's connection to a relay
+//! - [`protocol`] - wire format and authentication proof
+//!
+//! `nearclip-transport` builds `RelayTransport` on top of [`RelayClient`].
+
+mod client;
+mod error;
+pub mod protocol;
+mod server;
+
+pub use client::{RelayClient, RelayInbox, RELAY_SERVER_NAME};
+pub use error::RelayError;
+pub use protocol::Frame;
+pub use server::{RelayServer, RelayServerConfig, DEFAULT_RELAY_PORT};
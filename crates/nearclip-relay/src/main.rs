@@ -0,0 +1,115 @@
+//! `nearclip-relay` binary
+//!
+//! ```text
+//! nearclip-relay [--bind 0.0.0.0:7878] [--data-dir ./relay-data]
+//! ```
+//!
+//! The TLS certificate is generated on first start and stored in the data
+//! directory. Devices pin it, so keep the directory across restarts; its
+//! SHA-256 fingerprint is logged at startup.
+
+use nearclip_crypto::{TlsCertificate, TlsServerConfig};
+use nearclip_relay::{RelayServer, RelayServerConfig, DEFAULT_RELAY_PORT, RELAY_SERVER_NAME};
+use sha2::{Digest, Sha256};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use tracing::{error, info};
+use tracing_subscriber::EnvFilter;
+
+const CERT_FILE: &str = "cert.der";
+const KEY_FILE: &str = "key.der";
+
+const USAGE: &str = "Usage: nearclip-relay [--bind ADDR] [--data-dir DIR]";
+
+struct Args {
+    bind_addr: SocketAddr,
+    data_dir: PathBuf,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        bind_addr: SocketAddr::from(([0, 0, 0, 0], DEFAULT_RELAY_PORT)),
+        data_dir: PathBuf::from("relay-data"),
+    };
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--bind" => {
+                let value = iter.next().ok_or("--bind needs an address")?;
+                args.bind_addr = value.parse().map_err(|e| format!("Invalid --bind {}: {}", value, e))?;
+            }
+            "--data-dir" => {
+                args.data_dir = iter.next().ok_or("--data-dir needs a directory")?.into();
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other => return Err(format!("Unknown argument {}\n{}", other, USAGE)),
+        }
+    }
+    Ok(args)
+}
+
+/// Load the relay certificate, generating and storing one on first start
+fn load_or_generate_certificate(data_dir: &Path) -> Result<TlsCertificate, String> {
+    let cert_path = data_dir.join(CERT_FILE);
+    let key_path = data_dir.join(KEY_FILE);
+
+    if cert_path.exists() && key_path.exists() {
+        let cert = std::fs::read(&cert_path).map_err(|e| format!("Failed to read {:?}: {}", cert_path, e))?;
+        let key = std::fs::read(&key_path).map_err(|e| format!("Failed to read {:?}: {}", key_path, e))?;
+        return Ok(TlsCertificate::from_der(cert, key));
+    }
+
+    let cert = TlsCertificate::generate(&[RELAY_SERVER_NAME.to_string()])
+        .map_err(|e| format!("Failed to generate certificate: {}", e))?;
+    std::fs::create_dir_all(data_dir).map_err(|e| format!("Failed to create {:?}: {}", data_dir, e))?;
+    std::fs::write(&cert_path, cert.cert_der()).map_err(|e| format!("Failed to write {:?}: {}", cert_path, e))?;
+    std::fs::write(&key_path, cert.key_der()).map_err(|e| format!("Failed to write {:?}: {}", key_path, e))?;
+    info!("Generated relay certificate in {:?}", data_dir);
+    Ok(cert)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .init();
+
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(msg) => {
+            eprintln!("{}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let cert = match load_or_generate_certificate(&args.data_dir) {
+        Ok(cert) => cert,
+        Err(msg) => {
+            error!("{}", msg);
+            return ExitCode::FAILURE;
+        }
+    };
+    info!("Certificate fingerprint (SHA-256): {}", hex::encode(Sha256::digest(cert.cert_der())));
+
+    let tls = match TlsServerConfig::new(&cert) {
+        Ok(tls) => tls,
+        Err(e) => {
+            error!("Invalid certificate: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let server = match RelayServer::bind(RelayServerConfig::new().with_bind_addr(args.bind_addr), tls.config()).await {
+        Ok(server) => server,
+        Err(e) => {
+            error!("Failed to start relay: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    tokio::select! {
+        _ = server.run() => {}
+        _ = tokio::signal::ctrl_c() => info!("Shutting down"),
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,168 @@
+//! Relay wire protocol
+//!
+//! Frames are MessagePack-encoded [`Frame`] values with a 4-byte big-endian
+//! length prefix, sent over TLS.
+//!
+//! # Handshake
+//!
+//! ```text
+//! client                                   relay
+//!   │ Hello { device_id, public_key }        │
+//!   │───────────────────────────────────────▶│
+//!   │      Challenge { nonce, relay_key }    │  relay_key: fresh P-256 key
+//!   │◀───────────────────────────────────────│
+//!   │ Auth { proof }                         │  proof = HMAC-SHA256(ECDH(device, relay_key),
+//!   │───────────────────────────────────────▶│          DOMAIN || nonce || device_id)
+//!   │                Welcome                 │
+//!   │◀───────────────────────────────────────│
+//! ```
+//!
+//! The proof shows the client holds the private key for `public_key`. The
+//! relay binds each device ID to the first public key it sees, so another
+//! client cannot take over a device ID later.
+//!
+//! After the handshake a device lists the peers it is paired with
+//! (`AllowPeers`). Payloads are only forwarded between two devices that
+//! allowed each other. Payloads are opaque to the relay: they are
+//! serialized `Message`s already encrypted end to end by the devices.
+
+use hmac::{Hmac, Mac};
+use nearclip_net::{TcpReadHalf, TcpWriteHalf};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::error::RelayError;
+
+/// Domain separation prefix for the authentication proof
+const AUTH_DOMAIN: &[u8] = b"nearclip-relay-auth-v1";
+
+/// Maximum frame size (16 MB payload plus framing overhead)
+pub const MAX_FRAME_SIZE: u32 = 16 * 1024 * 1024 + 4096;
+
+/// Nonce length in bytes
+pub const NONCE_LEN: usize = 32;
+
+/// A relay protocol frame
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Frame {
+    /// Client introduces itself (client → relay)
+    Hello {
+        device_id: String,
+        /// Uncompressed P-256 public key of the device
+        public_key: Vec<u8>,
+    },
+    /// Authentication challenge (relay → client)
+    Challenge {
+        nonce: Vec<u8>,
+        /// Fresh relay public key for this connection
+        relay_key: Vec<u8>,
+    },
+    /// Proof of key possession (client → relay)
+    Auth { proof: Vec<u8> },
+    /// Authentication succeeded (relay → client)
+    Welcome,
+    /// Devices this client accepts payloads from (client → relay)
+    AllowPeers { device_ids: Vec<String> },
+    /// Forward a payload to a peer (client → relay)
+    Send { to: String, payload: Vec<u8> },
+    /// Payload from a peer (relay → client)
+    Deliver { from: String, payload: Vec<u8> },
+    /// A mutually allowed peer came online or went offline (relay → client)
+    Presence { device_id: String, online: bool },
+    /// A `Send` could not be forwarded (relay → client)
+    Undeliverable { to: String, reason: String },
+    /// Fatal error, the connection is closed afterwards (relay → client)
+    Error { reason: String },
+}
+
+impl Frame {
+    /// Encode the frame with its length prefix
+    pub fn encode(&self) -> Result<Vec<u8>, RelayError> {
+        let data = rmp_serde::to_vec(self).map_err(|e| RelayError::Protocol(e.to_string()))?;
+        let mut buf = Vec::with_capacity(4 + data.len());
+        buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&data);
+        Ok(buf)
+    }
+
+    /// Decode a frame body (without the length prefix)
+    pub fn decode(data: &[u8]) -> Result<Self, RelayError> {
+        rmp_serde::from_slice(data).map_err(|e| RelayError::Protocol(e.to_string()))
+    }
+}
+
+/// Compute the authentication proof for a device
+pub fn auth_proof(shared_secret: &[u8], nonce: &[u8], device_id: &str) -> Vec<u8> {
+    auth_mac(shared_secret, nonce, device_id).finalize().into_bytes().to_vec()
+}
+
+/// Check an authentication proof in constant time
+pub fn verify_auth_proof(shared_secret: &[u8], nonce: &[u8], device_id: &str, proof: &[u8]) -> bool {
+    auth_mac(shared_secret, nonce, device_id).verify_slice(proof).is_ok()
+}
+
+fn auth_mac(shared_secret: &[u8], nonce: &[u8], device_id: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(shared_secret).expect("HMAC accepts any key length");
+    mac.update(AUTH_DOMAIN);
+    mac.update(nonce);
+    mac.update(device_id.as_bytes());
+    mac
+}
+
+/// Read one frame, rejecting frames larger than `max_size`
+pub(crate) async fn read_frame(reader: &mut TcpReadHalf, max_size: u32) -> Result<Frame, RelayError> {
+    let mut len_buf = [0u8; 4];
+    read_exact(reader, &mut len_buf).await?;
+    let len = u32::from_be_bytes(len_buf);
+    if len > max_size {
+        return Err(RelayError::Protocol(format!("Frame too large: {} bytes (max {})", len, max_size)));
+    }
+    let mut data = vec![0u8; len as usize];
+    read_exact(reader, &mut data).await?;
+    Frame::decode(&data)
+}
+
+/// Write one frame and flush
+pub(crate) async fn write_frame(writer: &mut TcpWriteHalf, frame: &Frame) -> Result<(), RelayError> {
+    writer.write_all(&frame.encode()?).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+async fn read_exact(reader: &mut TcpReadHalf, buf: &mut [u8]) -> Result<(), RelayError> {
+    let mut total = 0;
+    while total < buf.len() {
+        let n = reader.read(&mut buf[total..]).await?;
+        if n == 0 {
+            return Err(RelayError::ConnectionClosed);
+        }
+        total += n;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let frame = Frame::Send { to: "peer".to_string(), payload: vec![1, 2, 3] };
+        let encoded = frame.encode().unwrap();
+        let len = u32::from_be_bytes(encoded[..4].try_into().unwrap()) as usize;
+        assert_eq!(len, encoded.len() - 4);
+        assert_eq!(Frame::decode(&encoded[4..]).unwrap(), frame);
+    }
+
+    #[test]
+    fn test_auth_proof_binds_device_and_nonce() {
+        let secret = [7u8; 32];
+        let nonce = [1u8; NONCE_LEN];
+        let proof = auth_proof(&secret, &nonce, "device-a");
+
+        assert!(verify_auth_proof(&secret, &nonce, "device-a", &proof));
+        assert!(!verify_auth_proof(&secret, &nonce, "device-b", &proof));
+        assert!(!verify_auth_proof(&secret, &[2u8; NONCE_LEN], "device-a", &proof));
+        assert!(!verify_auth_proof(&[8u8; 32], &nonce, "device-a", &proof));
+    }
+}
@@ -0,0 +1,333 @@
+//! Relay server
+//!
+//! Accepts TLS connections from devices, authenticates them and forwards
+//! payloads between mutually allowed peers. The server never decrypts
+//! payloads and keeps no message history: a payload for an offline peer is
+//! answered with `Undeliverable`.
+
+use nearclip_crypto::EcdhKeyPair;
+use nearclip_net::{ConnectionLimits, TcpConnection, TcpServer, TcpServerConfig};
+use rand_core::{OsRng, RngCore};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tracing::{debug, info, instrument, warn};
+
+use crate::error::RelayError;
+use crate::protocol::{read_frame, verify_auth_proof, write_frame, Frame, MAX_FRAME_SIZE, NONCE_LEN};
+
+/// Default relay port
+pub const DEFAULT_RELAY_PORT: u16 = 7878;
+
+/// Frames queued per device before new payloads are refused
+const OUTGOING_QUEUE_SIZE: usize = 256;
+
+/// Relay server configuration
+#[derive(Debug, Clone)]
+pub struct RelayServerConfig {
+    /// Address to listen on
+    pub bind_addr: SocketAddr,
+    /// Per-IP limits, handshake timeout and message rate
+    pub limits: ConnectionLimits,
+}
+
+impl Default for RelayServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: SocketAddr::from(([0, 0, 0, 0], DEFAULT_RELAY_PORT)),
+            limits: ConnectionLimits::default(),
+        }
+    }
+}
+
+impl RelayServerConfig {
+    /// Create a default configuration (all interfaces, port 7878)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the listen address
+    pub fn with_bind_addr(mut self, addr: SocketAddr) -> Self {
+        self.bind_addr = addr;
+        self
+    }
+
+    /// Set the connection limits
+    pub fn with_limits(mut self, limits: ConnectionLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+/// An authenticated device connection
+struct Peer {
+    conn_id: u64,
+    allowed: HashSet<String>,
+    tx: mpsc::Sender<Frame>,
+}
+
+/// Shared relay state
+#[derive(Default)]
+struct RelayState {
+    peers: Mutex<HashMap<String, Peer>>,
+    /// Device ID → public key, bound on first authentication
+    identities: Mutex<HashMap<String, Vec<u8>>>,
+    next_conn_id: AtomicU64,
+}
+
+impl RelayState {
+    /// Whether both devices are online and allowed each other
+    fn linked(peers: &HashMap<String, Peer>, a: &str, b: &str) -> bool {
+        match (peers.get(a), peers.get(b)) {
+            (Some(pa), Some(pb)) => pa.allowed.contains(b) && pb.allowed.contains(a),
+            _ => false,
+        }
+    }
+
+    /// Tell both sides of each pair whether they can reach each other
+    fn announce(peers: &HashMap<String, Peer>, device_id: &str, others: impl IntoIterator<Item = String>) {
+        for other in others {
+            let online = Self::linked(peers, device_id, &other);
+            if let (Some(me), Some(them)) = (peers.get(device_id), peers.get(&other)) {
+                let _ = me.tx.try_send(Frame::Presence { device_id: other.clone(), online });
+                let _ = them.tx.try_send(Frame::Presence { device_id: device_id.to_string(), online });
+            }
+        }
+    }
+}
+
+/// Relay server
+///
+/// # Example
+///
+/// ```no_run
+/// use nearclip_relay::{RelayServer, RelayServerConfig};
+/// use nearclip_crypto::{TlsCertificate, TlsServerConfig};
+///
+/// # async fn example() -> Result<(), nearclip_relay::RelayError> {
+/// let cert = TlsCertificate::generate(&[nearclip_relay::RELAY_SERVER_NAME.to_string()]).unwrap();
+/// let tls = TlsServerConfig::new(&cert).unwrap();
+///
+/// let server = RelayServer::bind(RelayServerConfig::new(), tls.config()).await?;
+/// server.run().await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct RelayServer {
+    server: TcpServer,
+    limits: ConnectionLimits,
+    state: Arc<RelayState>,
+}
+
+impl RelayServer {
+    /// Bind the relay server
+    #[instrument(skip(tls_config), fields(addr = %config.bind_addr))]
+    pub async fn bind(
+        config: RelayServerConfig,
+        tls_config: Arc<rustls::ServerConfig>,
+    ) -> Result<Self, RelayError> {
+        let server_config = TcpServerConfig::new()
+            .with_bind_addr(config.bind_addr.ip())
+            .with_port(config.bind_addr.port())
+            .with_limits(config.limits.clone());
+        let server = TcpServer::bind(server_config, tls_config).await?;
+        info!("Relay listening on {:?}", server.local_addr());
+        Ok(Self {
+            server,
+            limits: config.limits,
+            state: Arc::new(RelayState::default()),
+        })
+    }
+
+    /// Actual listen address
+    pub fn local_addr(&self) -> Result<SocketAddr, RelayError> {
+        Ok(self.server.local_addr()?)
+    }
+
+    /// Number of authenticated devices currently connected
+    pub fn online_devices(&self) -> usize {
+        self.state.peers.lock().unwrap().len()
+    }
+
+    /// Accept and serve connections until the task is cancelled
+    pub async fn run(&self) {
+        loop {
+            match self.server.accept().await {
+                Ok(conn) => {
+                    let state = self.state.clone();
+                    let limits = self.limits.clone();
+                    tokio::spawn(async move {
+                        let peer_addr = conn.peer_addr();
+                        if let Err(e) = serve(state, limits, conn).await {
+                            debug!("Relay connection from {} ended: {}", peer_addr, e);
+                        }
+                    });
+                }
+                Err(e) => debug!("Failed to accept relay connection: {}", e),
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for RelayServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RelayServer")
+            .field("local_addr", &self.server.local_addr().ok())
+            .field("online_devices", &self.online_devices())
+            .finish()
+    }
+}
+
+/// Whether `conn_id` is no longer the registered connection for the device
+fn superseded_by_newer(peers: &HashMap<String, Peer>, device_id: &str, conn_id: u64) -> bool {
+    peers.get(device_id).is_none_or(|p| p.conn_id != conn_id)
+}
+
+/// Serve one device connection
+async fn serve(state: Arc<RelayState>, limits: ConnectionLimits, conn: TcpConnection) -> Result<(), RelayError> {
+    let peer_addr = conn.peer_addr();
+    let (mut reader, mut writer) = conn.into_split();
+    let ban = |reader: &nearclip_net::TcpReadHalf| {
+        if let Some(permit) = reader.permit() {
+            permit.ban();
+        }
+    };
+
+    // 1. Authenticate within the handshake timeout
+    let auth = tokio::time::timeout(limits.handshake_timeout, async {
+        let (device_id, public_key) = match read_frame(&mut reader, limits.max_unauthenticated_message_size).await? {
+            Frame::Hello { device_id, public_key } if !device_id.is_empty() => (device_id, public_key),
+            _ => return Err(RelayError::Protocol("Expected Hello".to_string())),
+        };
+
+        let relay_key = EcdhKeyPair::generate();
+        let shared_secret = relay_key
+            .compute_shared_secret(&public_key)
+            .map_err(|e| RelayError::Authentication(format!("Invalid public key: {}", e)))?;
+        let mut nonce = vec![0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut nonce);
+        write_frame(&mut writer, &Frame::Challenge { nonce: nonce.clone(), relay_key: relay_key.public_key_bytes() }).await?;
+
+        let proof = match read_frame(&mut reader, limits.max_unauthenticated_message_size).await? {
+            Frame::Auth { proof } => proof,
+            _ => return Err(RelayError::Protocol("Expected Auth".to_string())),
+        };
+        if !verify_auth_proof(&shared_secret, &nonce, &device_id, &proof) {
+            ban(&reader);
+            return Err(RelayError::Authentication("Invalid proof".to_string()));
+        }
+
+        let mut identities = state.identities.lock().unwrap();
+        match identities.get(&device_id) {
+            Some(bound) if *bound != public_key => {
+                return Err(RelayError::Authentication(format!("Device ID {} is bound to another key", device_id)));
+            }
+            Some(_) => {}
+            None => {
+                identities.insert(device_id.clone(), public_key);
+            }
+        }
+        Ok(device_id)
+    })
+    .await;
+
+    let device_id = match auth {
+        Ok(Ok(device_id)) => device_id,
+        Ok(Err(e)) => {
+            warn!("Relay authentication from {} failed: {}", peer_addr, e);
+            let _ = write_frame(&mut writer, &Frame::Error { reason: e.to_string() }).await;
+            return Err(e);
+        }
+        Err(_) => {
+            ban(&reader);
+            return Err(RelayError::Timeout(format!("Authentication from {} timed out", peer_addr)));
+        }
+    };
+    write_frame(&mut writer, &Frame::Welcome).await?;
+    info!("Device {} connected to relay from {}", device_id, peer_addr);
+
+    // 2. Register; a newer connection for the same device replaces the old one
+    let conn_id = state.next_conn_id.fetch_add(1, Ordering::Relaxed);
+    let (tx, mut rx) = mpsc::channel::<Frame>(OUTGOING_QUEUE_SIZE);
+    state.peers.lock().unwrap().insert(
+        device_id.clone(),
+        Peer { conn_id, allowed: HashSet::new(), tx },
+    );
+
+    let writer_task = tokio::spawn(async move {
+        while let Some(frame) = rx.recv().await {
+            if write_frame(&mut writer, &frame).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    // 3. Forward frames until the device disconnects
+    let mut rate_limiter = limits.rate_limiter();
+    let result = loop {
+        let frame = match read_frame(&mut reader, MAX_FRAME_SIZE).await {
+            Ok(frame) => frame,
+            Err(e) => break Err(e),
+        };
+        if !rate_limiter.try_acquire() {
+            ban(&reader);
+            break Err(RelayError::Protocol("Message rate limit exceeded".to_string()));
+        }
+
+        if superseded_by_newer(&state.peers.lock().unwrap(), &device_id, conn_id) {
+            break Ok(());
+        }
+
+        match frame {
+            Frame::AllowPeers { device_ids } => {
+                let mut peers = state.peers.lock().unwrap();
+                let Some(me) = peers.get_mut(&device_id) else {
+                    break Ok(());
+                };
+                let allowed: HashSet<String> = device_ids.into_iter().collect();
+                let changed: Vec<String> = me.allowed.symmetric_difference(&allowed).cloned().collect();
+                let affected: Vec<String> = allowed.iter().cloned().chain(changed).collect();
+                me.allowed = allowed;
+                RelayState::announce(&peers, &device_id, affected);
+            }
+            Frame::Send { to, payload } => {
+                let peers = state.peers.lock().unwrap();
+                let reply = if !RelayState::linked(&peers, &device_id, &to) {
+                    Some("peer offline".to_string())
+                } else {
+                    let target = &peers[&to];
+                    target
+                        .tx
+                        .try_send(Frame::Deliver { from: device_id.clone(), payload })
+                        .err()
+                        .map(|_| "peer busy".to_string())
+                };
+                if let (Some(reason), Some(me)) = (reply, peers.get(&device_id)) {
+                    let _ = me.tx.try_send(Frame::Undeliverable { to, reason });
+                }
+            }
+            other => {
+                break Err(RelayError::Protocol(format!("Unexpected frame: {:?}", other)));
+            }
+        }
+    };
+
+    // 4. Unregister (unless a newer connection already replaced this one)
+    {
+        let mut peers = state.peers.lock().unwrap();
+        if !superseded_by_newer(&peers, &device_id, conn_id) {
+            let allowed: Vec<String> = peers[&device_id].allowed.iter().cloned().collect();
+            peers.remove(&device_id);
+            for other in allowed {
+                if let Some(them) = peers.get(&other) {
+                    let _ = them.tx.try_send(Frame::Presence { device_id: device_id.clone(), online: false });
+                }
+            }
+        }
+    }
+    writer_task.abort();
+    info!("Device {} disconnected from relay", device_id);
+    result
+}
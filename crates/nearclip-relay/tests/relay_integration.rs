@@ -0,0 +1,111 @@
+//! Relay server/client integration tests
+//!
+//! Two paired devices exchange payloads through a local relay; unpaired
+//! devices and identity takeovers are refused.
+
+use nearclip_crypto::{EcdhKeyPair, TlsCertificate, TlsClientConfig, TlsServerConfig};
+use nearclip_relay::{RelayClient, RelayError, RelayServer, RelayServerConfig, RELAY_SERVER_NAME};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct TestRelay {
+    addr: SocketAddr,
+    tls: Arc<rustls::ClientConfig>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for TestRelay {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn start_relay() -> TestRelay {
+    let cert = TlsCertificate::generate(&[RELAY_SERVER_NAME.to_string()]).unwrap();
+    let config = RelayServerConfig::new().with_bind_addr("127.0.0.1:0".parse().unwrap());
+    let server = RelayServer::bind(config, TlsServerConfig::new(&cert).unwrap().config())
+        .await
+        .unwrap();
+    let addr = server.local_addr().unwrap();
+    let task = tokio::spawn(async move { server.run().await });
+    TestRelay {
+        addr,
+        tls: TlsClientConfig::new(cert.cert_der()).unwrap().config(),
+        task,
+    }
+}
+
+async fn connect(relay: &TestRelay, device_id: &str, keypair: &EcdhKeyPair) -> Result<RelayClient, RelayError> {
+    RelayClient::connect(relay.addr, relay.tls.clone(), device_id, keypair).await
+}
+
+async fn wait_online(client: &RelayClient, peer: &str, online: bool) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while client.is_peer_online(peer) != online {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("presence update");
+}
+
+#[tokio::test]
+async fn test_paired_devices_exchange_payloads() {
+    let relay = start_relay().await;
+    let (key_a, key_b) = (EcdhKeyPair::generate(), EcdhKeyPair::generate());
+    let a = connect(&relay, "device-a", &key_a).await.unwrap();
+    let b = connect(&relay, "device-b", &key_b).await.unwrap();
+
+    a.allow_peers(["device-b".to_string()]).await.unwrap();
+    b.allow_peers(["device-a".to_string()]).await.unwrap();
+    wait_online(&a, "device-b", true).await;
+    wait_online(&b, "device-a", true).await;
+
+    // First payload from a new peer arrives through accept()
+    a.send("device-b", b"hello".to_vec()).await.unwrap();
+    let (from, mut inbox_b) = b.accept().await.unwrap();
+    assert_eq!(from, "device-a");
+    assert_eq!(inbox_b.recv().await.unwrap(), b"hello");
+
+    // Replies go to the inbox opened for the peer
+    let mut inbox_a = a.open("device-b");
+    b.send("device-a", b"world".to_vec()).await.unwrap();
+    assert_eq!(inbox_a.recv().await.unwrap(), b"world");
+
+    // Disconnecting is announced to the peer
+    b.close().await;
+    wait_online(&a, "device-b", false).await;
+}
+
+#[tokio::test]
+async fn test_payloads_require_mutual_allow() {
+    let relay = start_relay().await;
+    let (key_a, key_b) = (EcdhKeyPair::generate(), EcdhKeyPair::generate());
+    let a = connect(&relay, "device-a", &key_a).await.unwrap();
+    let b = connect(&relay, "device-b", &key_b).await.unwrap();
+
+    // Only device-a allows the other side
+    a.allow_peers(["device-b".to_string()]).await.unwrap();
+    let mut inbox_b = b.open("device-a");
+    a.send("device-b", b"unsolicited".to_vec()).await.unwrap();
+
+    let received = tokio::time::timeout(Duration::from_millis(200), inbox_b.recv()).await;
+    assert!(received.is_err(), "relay must not forward to a device that did not allow the sender");
+    assert!(!a.is_peer_online("device-b"));
+}
+
+#[tokio::test]
+async fn test_device_id_bound_to_first_key() {
+    let relay = start_relay().await;
+    let owner = EcdhKeyPair::generate();
+    let first = connect(&relay, "device-a", &owner).await.unwrap();
+    first.close().await;
+
+    let intruder = EcdhKeyPair::generate();
+    let result = connect(&relay, "device-a", &intruder).await;
+    assert!(matches!(result, Err(RelayError::Authentication(_))), "got {:?}", result);
+
+    // The real owner can still reconnect
+    assert!(connect(&relay, "device-a", &owner).await.is_ok());
+}
@@ -1,11 +1,12 @@
 //! 通信通道抽象
 //!
-//! 定义 WiFi 和 BLE 通道的抽象接口和选择策略。
+//! 定义 WiFi、QUIC 和 BLE 通道的抽象接口和选择策略。
 //!
 //! # 通道类型
 //!
 //! | 通道 | 优先级 | 特点 |
 //! |------|--------|------|
+//! | QUIC | 最高 | 高速、无队头阻塞、漫游后快速恢复、需要同一局域网 |
 //! | WiFi | 高 | 高速、低延迟、需要同一局域网 |
 //! | BLE | 低 | 低速、高延迟、不依赖网络 |
 //!
@@ -29,7 +30,7 @@ use std::fmt;
 
 /// 通信通道类型
 ///
-/// NearClip 支持两种通信通道，WiFi 优先，BLE 备选。
+/// NearClip 支持三种通信通道：局域网内优先 QUIC，其次 TLS-over-TCP，BLE 备选。
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum Channel {
     /// WiFi 通道 (TCP/TLS)
@@ -44,6 +45,18 @@ pub enum Channel {
     /// 低速、高延迟，适合小数据量传输。
     /// 不依赖 WiFi 网络，适合户外场景。
     Ble,
+
+    /// QUIC 通道 (UDP/TLS 1.3)
+    ///
+    /// 与 WiFi 通道使用相同的局域网和证书，控制消息和大数据使用独立的流，
+    /// 没有队头阻塞，切换网络后可以通过 0-RTT 快速恢复。
+    Quic,
+
+    /// 中继通道 (经自建中继服务器转发)
+    ///
+    /// 设备不在同一局域网、也不在 BLE 范围内时使用。
+    /// 消息端到端加密，中继只转发密文；优先级最低。
+    Relay,
 }
 
 impl Channel {
@@ -52,20 +65,29 @@ impl Channel {
         match self {
             Channel::Wifi => "wifi",
             Channel::Ble => "ble",
+            Channel::Quic => "quic",
+            Channel::Relay => "relay",
         }
     }
 
     /// 获取通道优先级（数值越大优先级越高）
     pub fn priority(&self) -> u8 {
         match self {
+            Channel::Quic => 12,
             Channel::Wifi => 10,
             Channel::Ble => 5,
+            Channel::Relay => 1,
         }
     }
 
     /// 是否是高速通道
     pub fn is_high_speed(&self) -> bool {
-        matches!(self, Channel::Wifi)
+        matches!(self, Channel::Wifi | Channel::Quic)
+    }
+
+    /// 是否是局域网 IP 通道（WiFi 或 QUIC）
+    pub fn is_network(&self) -> bool {
+        matches!(self, Channel::Wifi | Channel::Quic)
     }
 }
 
@@ -162,6 +184,28 @@ pub trait ChannelSelector: Send + Sync {
     ///
     /// 选中的通道，如果没有可用通道则返回 None
     fn select(&self, channels: &[ChannelInfo]) -> Option<Channel>;
+
+    /// 为指定设备选择最佳可用通道
+    ///
+    /// 需要按设备维护状态的选择器（例如质量选择器）应覆盖此方法，
+    /// 默认实现与 [`select`](Self::select) 相同。
+    fn select_for_device(&self, device_id: &str, channels: &[ChannelInfo]) -> Option<Channel> {
+        let _ = device_id;
+        self.select(channels)
+    }
+}
+
+/// 共享选择器
+///
+/// 便于在外部保留选择器引用（例如读取调试信息）的同时交给传输层使用。
+impl<T: ChannelSelector + ?Sized> ChannelSelector for std::sync::Arc<T> {
+    fn select(&self, channels: &[ChannelInfo]) -> Option<Channel> {
+        (**self).select(channels)
+    }
+
+    fn select_for_device(&self, device_id: &str, channels: &[ChannelInfo]) -> Option<Channel> {
+        (**self).select_for_device(device_id, channels)
+    }
 }
 
 /// 默认通道选择器
@@ -183,7 +227,7 @@ impl ChannelSelector for PriorityChannelSelector {
 
 /// 仅 WiFi 通道选择器
 ///
-/// 只选择 WiFi 通道，忽略 BLE。
+/// 只选择局域网通道（QUIC 优先于 TLS-over-TCP），忽略 BLE。
 #[derive(Debug, Clone, Copy, Default)]
 pub struct WifiOnlyChannelSelector;
 
@@ -191,7 +235,8 @@ impl ChannelSelector for WifiOnlyChannelSelector {
     fn select(&self, channels: &[ChannelInfo]) -> Option<Channel> {
         channels
             .iter()
-            .find(|info| info.channel == Channel::Wifi && info.can_send())
+            .filter(|info| info.channel.is_network() && info.can_send())
+            .max_by_key(|info| info.channel.priority())
             .map(|info| info.channel)
     }
 }
@@ -211,6 +256,88 @@ impl ChannelSelector for BleOnlyChannelSelector {
     }
 }
 
+/// 设备级通道偏好
+///
+/// 随已配对设备一起保存，决定向该设备发送消息时如何选择通道。
+/// 自身实现了 [`ChannelSelector`]，可以直接用于选择通道。
+///
+/// # Example
+///
+/// ```
+/// use nearclip_sync::{Channel, ChannelInfo, ChannelPreference, ChannelSelector, ChannelStatus};
+///
+/// let channels = vec![
+///     ChannelInfo::new(Channel::Wifi, ChannelStatus::Available),
+///     ChannelInfo::new(Channel::Ble, ChannelStatus::Available),
+/// ];
+///
+/// assert_eq!(ChannelPreference::Auto.select(&channels), Some(Channel::Wifi));
+/// assert_eq!(ChannelPreference::BleOnly.select(&channels), Some(Channel::Ble));
+/// assert!(ChannelPreference::Hybrid.is_multipath());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChannelPreference {
+    /// 自动选择（按优先级，QUIC > WiFi > BLE > 中继）
+    #[default]
+    Auto,
+
+    /// 仅使用局域网（WiFi 或 QUIC）
+    WifiOnly,
+
+    /// 仅使用 BLE
+    BleOnly,
+
+    /// 混合多路径
+    ///
+    /// 小消息同时经所有可用通道发送，接收方按消息 ID 去重；
+    /// 大消息仍按优先级选择单一通道。
+    Hybrid,
+}
+
+impl ChannelPreference {
+    /// 获取偏好名称
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChannelPreference::Auto => "auto",
+            ChannelPreference::WifiOnly => "wifi_only",
+            ChannelPreference::BleOnly => "ble_only",
+            ChannelPreference::Hybrid => "hybrid",
+        }
+    }
+
+    /// 是否允许多路径同时发送
+    pub fn is_multipath(&self) -> bool {
+        matches!(self, ChannelPreference::Hybrid)
+    }
+
+    /// 该偏好是否允许使用指定通道
+    pub fn allows(&self, channel: Channel) -> bool {
+        match self {
+            ChannelPreference::Auto | ChannelPreference::Hybrid => true,
+            ChannelPreference::WifiOnly => channel.is_network(),
+            ChannelPreference::BleOnly => channel == Channel::Ble,
+        }
+    }
+}
+
+impl fmt::Display for ChannelPreference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl ChannelSelector for ChannelPreference {
+    fn select(&self, channels: &[ChannelInfo]) -> Option<Channel> {
+        match self {
+            ChannelPreference::Auto | ChannelPreference::Hybrid => {
+                PriorityChannelSelector.select(channels)
+            }
+            ChannelPreference::WifiOnly => WifiOnlyChannelSelector.select(channels),
+            ChannelPreference::BleOnly => BleOnlyChannelSelector.select(channels),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,17 +346,24 @@ mod tests {
     fn test_channel_as_str() {
         assert_eq!(Channel::Wifi.as_str(), "wifi");
         assert_eq!(Channel::Ble.as_str(), "ble");
+        assert_eq!(Channel::Quic.as_str(), "quic");
+        assert_eq!(Channel::Relay.as_str(), "relay");
     }
 
     #[test]
     fn test_channel_priority() {
+        assert!(Channel::Quic.priority() > Channel::Wifi.priority());
         assert!(Channel::Wifi.priority() > Channel::Ble.priority());
+        assert!(Channel::Ble.priority() > Channel::Relay.priority());
     }
 
     #[test]
     fn test_channel_is_high_speed() {
         assert!(Channel::Wifi.is_high_speed());
+        assert!(Channel::Quic.is_high_speed());
         assert!(!Channel::Ble.is_high_speed());
+        assert!(!Channel::Relay.is_high_speed());
+        assert!(!Channel::Relay.is_network());
     }
 
     #[test]
@@ -348,6 +482,13 @@ mod tests {
             ChannelInfo::new(Channel::Wifi, ChannelStatus::Unavailable),
         ];
         assert_eq!(selector.select(&channels), None);
+
+        // QUIC preferred over WiFi
+        let channels = vec![
+            ChannelInfo::new(Channel::Wifi, ChannelStatus::Available),
+            ChannelInfo::new(Channel::Quic, ChannelStatus::Available),
+        ];
+        assert_eq!(selector.select(&channels), Some(Channel::Quic));
     }
 
     #[test]
@@ -414,4 +555,46 @@ mod tests {
         let debug_str = format!("{:?}", selector);
         assert!(debug_str.contains("PriorityChannelSelector"));
     }
+
+    #[test]
+    fn test_channel_preference_default() {
+        assert_eq!(ChannelPreference::default(), ChannelPreference::Auto);
+    }
+
+    #[test]
+    fn test_channel_preference_select() {
+        let channels = vec![
+            ChannelInfo::new(Channel::Wifi, ChannelStatus::Available),
+            ChannelInfo::new(Channel::Ble, ChannelStatus::Available),
+        ];
+
+        assert_eq!(ChannelPreference::Auto.select(&channels), Some(Channel::Wifi));
+        assert_eq!(ChannelPreference::Hybrid.select(&channels), Some(Channel::Wifi));
+        assert_eq!(ChannelPreference::WifiOnly.select(&channels), Some(Channel::Wifi));
+        assert_eq!(ChannelPreference::BleOnly.select(&channels), Some(Channel::Ble));
+
+        let wifi_down = vec![
+            ChannelInfo::new(Channel::Wifi, ChannelStatus::Unavailable),
+            ChannelInfo::new(Channel::Ble, ChannelStatus::Available),
+        ];
+        assert_eq!(ChannelPreference::WifiOnly.select(&wifi_down), None);
+        assert_eq!(ChannelPreference::Hybrid.select(&wifi_down), Some(Channel::Ble));
+    }
+
+    #[test]
+    fn test_channel_preference_allows() {
+        assert!(ChannelPreference::Auto.allows(Channel::Ble));
+        assert!(ChannelPreference::Hybrid.allows(Channel::Wifi));
+        assert!(ChannelPreference::WifiOnly.allows(Channel::Wifi));
+        assert!(!ChannelPreference::WifiOnly.allows(Channel::Ble));
+        assert!(!ChannelPreference::BleOnly.allows(Channel::Wifi));
+    }
+
+    #[test]
+    fn test_channel_preference_as_str() {
+        assert_eq!(ChannelPreference::Auto.as_str(), "auto");
+        assert_eq!(ChannelPreference::Hybrid.to_string(), "hybrid");
+        assert!(ChannelPreference::Hybrid.is_multipath());
+        assert!(!ChannelPreference::Auto.is_multipath());
+    }
 }
@@ -0,0 +1,319 @@
+//! 消息送达确认
+//!
+//! 跟踪等待 ACK 的消息，并在超时后通过 [`RetryExecutor`] 重发。
+//!
+//! 发送方为每个目标设备注册一个 [`AckWaiter`]，接收方收到带消息 ID 的
+//! `ClipboardSync` 后回复 [`Message::ack_for`](crate::Message::ack_for)，
+//! 发送方收到 ACK 后调用 [`DeliveryTracker::acknowledge`] 唤醒等待者。
+//!
+//! # Example
+//!
+//! ```
+//! use nearclip_sync::{DeliveryTracker, FixedDelayStrategy, RetryExecutor};
+//! use std::time::Duration;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let tracker = DeliveryTracker::new();
+//! let waiter = tracker.register("device-b", 42);
+//!
+//! // 接收方的 ACK 到达
+//! assert!(tracker.acknowledge("device-b", 42));
+//!
+//! let executor = RetryExecutor::new(FixedDelayStrategy::new(2, Duration::from_millis(10)));
+//! let attempts = waiter
+//!     .wait_with_retry(&executor, Duration::from_millis(100), || async { Ok(()) })
+//!     .await
+//!     .unwrap();
+//! assert_eq!(attempts, 1);
+//! # }
+//! ```
+
+use crate::retry::{RetryExecutor, RetryStrategy};
+use crate::sender::SyncError;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// 默认 ACK 超时后的重发间隔（毫秒）
+pub const DEFAULT_RESEND_DELAY_MS: u64 = 500;
+
+/// 等待中的 ACK
+#[derive(Debug, Default)]
+struct PendingAck {
+    acked: AtomicBool,
+    notify: Notify,
+}
+
+type PendingMap = HashMap<(String, u64), Arc<PendingAck>>;
+
+/// 送达跟踪器
+///
+/// 以 `(device_id, message_id)` 为键记录等待 ACK 的消息。
+/// 多个接收路径（WiFi、BLE）可以共享同一个跟踪器。
+#[derive(Debug, Default)]
+pub struct DeliveryTracker {
+    pending: Arc<Mutex<PendingMap>>,
+}
+
+impl DeliveryTracker {
+    /// 创建空的跟踪器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册一条等待 ACK 的消息
+    ///
+    /// 返回的等待者被丢弃时自动注销。
+    pub fn register(&self, device_id: &str, message_id: u64) -> AckWaiter {
+        let key = (device_id.to_string(), message_id);
+        let pending = Arc::new(PendingAck::default());
+        self.pending.lock().unwrap().insert(key.clone(), pending.clone());
+
+        AckWaiter {
+            registry: self.pending.clone(),
+            key,
+            pending,
+        }
+    }
+
+    /// 处理收到的 ACK
+    ///
+    /// # Returns
+    ///
+    /// 如果有对应的等待者返回 true；迟到或未知的 ACK 返回 false。
+    pub fn acknowledge(&self, device_id: &str, message_id: u64) -> bool {
+        let pending = self
+            .pending
+            .lock()
+            .unwrap()
+            .get(&(device_id.to_string(), message_id))
+            .cloned();
+
+        match pending {
+            Some(pending) => {
+                pending.acked.store(true, Ordering::Release);
+                pending.notify.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 当前等待 ACK 的消息数
+    pub fn pending_count(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+}
+
+/// ACK 等待者
+///
+/// 由 [`DeliveryTracker::register`] 创建。
+#[derive(Debug)]
+pub struct AckWaiter {
+    registry: Arc<Mutex<PendingMap>>,
+    key: (String, u64),
+    pending: Arc<PendingAck>,
+}
+
+impl AckWaiter {
+    /// 目标设备 ID
+    pub fn device_id(&self) -> &str {
+        &self.key.0
+    }
+
+    /// 等待的消息 ID
+    pub fn message_id(&self) -> u64 {
+        self.key.1
+    }
+
+    /// 是否已收到 ACK
+    pub fn is_acked(&self) -> bool {
+        self.pending.acked.load(Ordering::Acquire)
+    }
+
+    /// 在超时时间内等待 ACK
+    ///
+    /// 超时返回 `SyncError::AckTimeout`。
+    pub async fn wait(&self, timeout: Duration) -> Result<(), SyncError> {
+        if self.is_acked() {
+            return Ok(());
+        }
+
+        match tokio::time::timeout(timeout, self.pending.notify.notified()).await {
+            Ok(()) => Ok(()),
+            Err(_) if self.is_acked() => Ok(()),
+            Err(_) => Err(SyncError::AckTimeout(self.key.0.clone())),
+        }
+    }
+
+    /// 等待 ACK，超时后重发并继续等待
+    ///
+    /// 调用前消息应已发送一次。每次超时后由 `executor` 的策略决定是否
+    /// 调用 `resend` 重发；之前发送的副本迟到的 ACK 同样有效。
+    ///
+    /// # Returns
+    ///
+    /// 收到 ACK 时返回总尝试次数，重试耗尽时返回最后一次错误
+    pub async fn wait_with_retry<S, F, Fut>(
+        &self,
+        executor: &RetryExecutor<S>,
+        timeout: Duration,
+        mut resend: F,
+    ) -> Result<u32, SyncError>
+    where
+        S: RetryStrategy,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(), SyncError>>,
+    {
+        let mut first_attempt = true;
+        let result = executor
+            .execute(|| {
+                let resend = if first_attempt { None } else { Some(resend()) };
+                first_attempt = false;
+                async move {
+                    if let Some(resend) = resend {
+                        resend.await?;
+                    }
+                    self.wait(timeout).await
+                }
+            })
+            .await?;
+
+        Ok(result.attempts)
+    }
+}
+
+impl Drop for AckWaiter {
+    fn drop(&mut self) {
+        if let Ok(mut registry) = self.registry.lock() {
+            registry.remove(&self.key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retry::FixedDelayStrategy;
+    use std::sync::atomic::AtomicU32;
+
+    fn executor(max_retries: u32) -> RetryExecutor<FixedDelayStrategy> {
+        RetryExecutor::new(FixedDelayStrategy::new(max_retries, Duration::from_millis(1)))
+    }
+
+    #[test]
+    fn test_register_and_drop() {
+        let tracker = DeliveryTracker::new();
+        let waiter = tracker.register("device-a", 7);
+        assert_eq!(waiter.device_id(), "device-a");
+        assert_eq!(waiter.message_id(), 7);
+        assert_eq!(tracker.pending_count(), 1);
+
+        drop(waiter);
+        assert_eq!(tracker.pending_count(), 0);
+        assert!(!tracker.acknowledge("device-a", 7));
+    }
+
+    #[test]
+    fn test_acknowledge_matches_device_and_id() {
+        let tracker = DeliveryTracker::new();
+        let waiter = tracker.register("device-a", 7);
+
+        assert!(!tracker.acknowledge("device-b", 7));
+        assert!(!tracker.acknowledge("device-a", 8));
+        assert!(!waiter.is_acked());
+
+        assert!(tracker.acknowledge("device-a", 7));
+        assert!(waiter.is_acked());
+    }
+
+    #[tokio::test]
+    async fn test_wait_acked_before_wait() {
+        let tracker = DeliveryTracker::new();
+        let waiter = tracker.register("device-a", 1);
+        tracker.acknowledge("device-a", 1);
+        assert!(waiter.wait(Duration::from_millis(10)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wait_acked_during_wait() {
+        let tracker = Arc::new(DeliveryTracker::new());
+        let waiter = tracker.register("device-a", 1);
+
+        let tracker_clone = tracker.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            tracker_clone.acknowledge("device-a", 1);
+        });
+
+        assert!(waiter.wait(Duration::from_secs(2)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_wait_timeout() {
+        let tracker = DeliveryTracker::new();
+        let waiter = tracker.register("device-a", 1);
+        let result = waiter.wait(Duration::from_millis(10)).await;
+        assert!(matches!(result, Err(SyncError::AckTimeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_wait_with_retry_resends_until_acked() {
+        let tracker = Arc::new(DeliveryTracker::new());
+        let waiter = tracker.register("device-a", 1);
+        let resends = Arc::new(AtomicU32::new(0));
+
+        let attempts = waiter
+            .wait_with_retry(&executor(3), Duration::from_millis(20), || {
+                let resends = resends.clone();
+                let tracker = tracker.clone();
+                async move {
+                    // 第二次重发后对端回复 ACK
+                    if resends.fetch_add(1, Ordering::SeqCst) == 1 {
+                        tracker.acknowledge("device-a", 1);
+                    }
+                    Ok(())
+                }
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(attempts, 3);
+        assert_eq!(resends.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wait_with_retry_exhausted() {
+        let tracker = DeliveryTracker::new();
+        let waiter = tracker.register("device-a", 1);
+        let resends = AtomicU32::new(0);
+
+        let result = waiter
+            .wait_with_retry(&executor(2), Duration::from_millis(5), || {
+                resends.fetch_add(1, Ordering::SeqCst);
+                async { Ok(()) }
+            })
+            .await;
+
+        assert!(matches!(result, Err(SyncError::AckTimeout(_))));
+        assert_eq!(resends.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_wait_with_retry_resend_failure() {
+        let tracker = DeliveryTracker::new();
+        let waiter = tracker.register("device-a", 1);
+
+        let result = waiter
+            .wait_with_retry(&executor(1), Duration::from_millis(5), || async {
+                Err(SyncError::SendFailed("link down".to_string()))
+            })
+            .await;
+
+        assert_eq!(result, Err(SyncError::SendFailed("link down".to_string())));
+    }
+}
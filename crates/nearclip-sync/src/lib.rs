@@ -66,9 +66,11 @@
 //! ```
 
 pub mod channel;
+pub mod delivery;
 pub mod loop_guard;
 pub mod monitor;
 pub mod protocol;
+pub mod quality;
 pub mod receiver;
 pub mod retry;
 pub mod sender;
@@ -79,7 +81,7 @@ pub use protocol::{Message, MessageType, PairingPayload, ProtocolError, Protocol
 
 // Re-export channel types
 pub use channel::{
-    BleOnlyChannelSelector, Channel, ChannelInfo, ChannelSelector, ChannelStatus,
+    BleOnlyChannelSelector, Channel, ChannelInfo, ChannelPreference, ChannelSelector, ChannelStatus,
     PriorityChannelSelector, WifiOnlyChannelSelector,
 };
 
@@ -97,8 +99,14 @@ pub use receiver::{
 
 // Re-export monitor types
 pub use monitor::{
-    ChannelMonitor, ChannelMonitorConfig, ChannelSnapshot, ChannelStatusCallback,
-    DEFAULT_CHECK_INTERVAL_SECS, DEFAULT_STATUS_TIMEOUT_SECS,
+    ChannelMetrics, ChannelMonitor, ChannelMonitorConfig, ChannelSnapshot, ChannelStatusCallback,
+    DEFAULT_CHECK_INTERVAL_SECS, DEFAULT_METRICS_SMOOTHING, DEFAULT_METRICS_WINDOW,
+    DEFAULT_STATUS_TIMEOUT_SECS,
+};
+
+pub use quality::{
+    ChannelScore, DeviceQuality, QualityChannelSelector, QualitySelectorConfig, QualitySnapshot,
+    SelectionDecision, DEFAULT_DECISION_HISTORY, DEFAULT_HYSTERESIS_MARGIN, DEFAULT_MIN_DWELL_SECS,
 };
 
 // Re-export switcher types
@@ -114,6 +122,9 @@ pub use retry::{
     DEFAULT_MAX_RETRIES, DEFAULT_RETRY_DELAY_SECS,
 };
 
+// Re-export delivery types
+pub use delivery::{AckWaiter, DeliveryTracker, DEFAULT_RESEND_DELAY_MS};
+
 // Re-export loop guard types
 pub use loop_guard::{
     ContentFingerprint, ContentOrigin, LoopGuard, LoopGuardConfig, LoopGuardError,
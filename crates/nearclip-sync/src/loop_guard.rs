@@ -348,6 +348,8 @@ pub struct LoopGuard {
     history: RwLock<HashMap<ContentFingerprint, HistoryEntry>>,
     /// 插入顺序追踪（用于 LRU 淘汰）
     insertion_order: RwLock<Vec<ContentFingerprint>>,
+    /// 已处理的消息 ID（(发送方设备 ID, 消息 ID) -> 首次到达时间）
+    seen_messages: RwLock<HashMap<(String, u64), Instant>>,
 }
 
 impl LoopGuard {
@@ -361,6 +363,7 @@ impl LoopGuard {
             config,
             history: RwLock::new(HashMap::new()),
             insertion_order: RwLock::new(Vec::new()),
+            seen_messages: RwLock::new(HashMap::new()),
         }
     }
 
@@ -441,6 +444,69 @@ impl LoopGuard {
         );
     }
 
+    /// 记录收到的消息 ID，判断是否为首次到达
+    ///
+    /// 混合多路径发送时，同一条消息会经 WiFi 和 BLE 各到达一次。
+    /// 接收方在处理消息前调用此方法，只处理首次到达的副本。
+    ///
+    /// 消息 ID 为 0（旧版本对端未携带 ID）时无法去重，始终返回 `true`。
+    /// 记录的过期时间与历史大小沿用内容历史的配置。
+    ///
+    /// # 参数
+    ///
+    /// * `device_id` - 发送方设备 ID
+    /// * `message_id` - 消息 ID
+    ///
+    /// # 返回
+    ///
+    /// - `true`: 首次到达，应该处理
+    /// - `false`: 重复到达，应该丢弃
+    ///
+    /// # 示例
+    ///
+    /// ```
+    /// use nearclip_sync::{LoopGuard, LoopGuardConfig};
+    ///
+    /// let guard = LoopGuard::new(LoopGuardConfig::new());
+    ///
+    /// assert!(guard.record_message_id("device-1", 42));
+    /// assert!(!guard.record_message_id("device-1", 42)); // 另一通道上的副本
+    /// assert!(guard.record_message_id("device-2", 42)); // 不同发送方
+    /// ```
+    pub fn record_message_id(&self, device_id: &str, message_id: u64) -> bool {
+        if message_id == 0 {
+            return true;
+        }
+
+        let expiry = self.config.expiry_duration;
+        let mut seen = self.seen_messages.write().unwrap();
+        seen.retain(|_, at| at.elapsed() <= expiry);
+
+        let key = (device_id.to_string(), message_id);
+        if seen.contains_key(&key) {
+            tracing::debug!(
+                device_id = %device_id,
+                message_id = message_id,
+                "Duplicate message dropped"
+            );
+            return false;
+        }
+
+        // 超出容量时淘汰最早的记录
+        if seen.len() >= self.config.history_size {
+            if let Some(oldest) = seen
+                .iter()
+                .min_by_key(|(_, at)| **at)
+                .map(|(k, _)| k.clone())
+            {
+                seen.remove(&oldest);
+            }
+        }
+
+        seen.insert(key, Instant::now());
+        true
+    }
+
     /// 判断内容是否应该同步
     ///
     /// 检查给定内容是否应该发送到其他设备：
@@ -619,6 +685,7 @@ impl LoopGuard {
 
         history.clear();
         order.clear();
+        self.seen_messages.write().unwrap().clear();
 
         tracing::debug!("Loop guard history cleared");
     }
@@ -1046,4 +1113,47 @@ mod tests {
         assert!(debug.contains("LoopGuard"));
         assert!(debug.contains("history_count"));
     }
+
+    // --------------------------------------------------------
+    // 消息 ID 去重测试
+    // --------------------------------------------------------
+
+    #[test]
+    fn test_record_message_id_dedup() {
+        let guard = LoopGuard::new(LoopGuardConfig::new());
+        assert!(guard.record_message_id("d1", 7));
+        assert!(!guard.record_message_id("d1", 7));
+        assert!(guard.record_message_id("d1", 8));
+        assert!(guard.record_message_id("d2", 7));
+    }
+
+    #[test]
+    fn test_record_message_id_zero_never_deduped() {
+        let guard = LoopGuard::new(LoopGuardConfig::new());
+        assert!(guard.record_message_id("d1", 0));
+        assert!(guard.record_message_id("d1", 0));
+    }
+
+    #[test]
+    fn test_record_message_id_expires() {
+        let config = LoopGuardConfig::new().with_expiry_duration(Duration::from_millis(20));
+        let guard = LoopGuard::new(config);
+        assert!(guard.record_message_id("d1", 1));
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(guard.record_message_id("d1", 1));
+    }
+
+    #[test]
+    fn test_record_message_id_capacity() {
+        let config = LoopGuardConfig::new().with_history_size(2);
+        let guard = LoopGuard::new(config);
+        assert!(guard.record_message_id("d1", 1));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(guard.record_message_id("d1", 2));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(guard.record_message_id("d1", 3));
+        // 最早的 1 已被淘汰
+        assert!(guard.record_message_id("d1", 1));
+        assert!(!guard.record_message_id("d1", 3));
+    }
 }
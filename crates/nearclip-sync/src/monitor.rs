@@ -32,7 +32,7 @@
 
 use crate::channel::{Channel, ChannelStatus};
 use crate::sender::SyncError;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
@@ -42,6 +42,12 @@ pub const DEFAULT_CHECK_INTERVAL_SECS: u64 = 5;
 /// 默认状态超时时间（30 秒）
 pub const DEFAULT_STATUS_TIMEOUT_SECS: u64 = 30;
 
+/// 默认发送结果统计窗口（最近 20 次发送）
+pub const DEFAULT_METRICS_WINDOW: usize = 20;
+
+/// 默认指标平滑系数（EMA alpha）
+pub const DEFAULT_METRICS_SMOOTHING: f64 = 0.3;
+
 /// 通道状态变更回调
 ///
 /// 接收通道状态变更的通知。
@@ -83,6 +89,10 @@ pub struct ChannelMonitorConfig {
     pub check_interval: Duration,
     /// 状态超时时间
     pub timeout: Duration,
+    /// 发送结果统计窗口大小（用于计算失败率）
+    pub metrics_window: usize,
+    /// RTT / 吞吐量的指数平滑系数，取值 (0, 1]，越大越偏向最新样本
+    pub metrics_smoothing: f64,
 }
 
 impl ChannelMonitorConfig {
@@ -91,6 +101,8 @@ impl ChannelMonitorConfig {
         Self {
             check_interval: Duration::from_secs(DEFAULT_CHECK_INTERVAL_SECS),
             timeout: Duration::from_secs(DEFAULT_STATUS_TIMEOUT_SECS),
+            metrics_window: DEFAULT_METRICS_WINDOW,
+            metrics_smoothing: DEFAULT_METRICS_SMOOTHING,
         }
     }
 
@@ -106,6 +118,18 @@ impl ChannelMonitorConfig {
         self
     }
 
+    /// 设置发送结果统计窗口大小
+    pub fn with_metrics_window(mut self, window: usize) -> Self {
+        self.metrics_window = window;
+        self
+    }
+
+    /// 设置指标平滑系数
+    pub fn with_metrics_smoothing(mut self, smoothing: f64) -> Self {
+        self.metrics_smoothing = smoothing;
+        self
+    }
+
     /// 验证配置
     pub fn validate(&self) -> Result<(), SyncError> {
         if self.check_interval.is_zero() {
@@ -120,6 +144,18 @@ impl ChannelMonitorConfig {
             ));
         }
 
+        if self.metrics_window == 0 {
+            return Err(SyncError::Configuration(
+                "Metrics window must be greater than zero".to_string(),
+            ));
+        }
+
+        if !(self.metrics_smoothing > 0.0 && self.metrics_smoothing <= 1.0) {
+            return Err(SyncError::Configuration(
+                "Metrics smoothing must be in (0, 1]".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -180,6 +216,91 @@ impl ChannelSnapshot {
     }
 }
 
+/// 通道质量指标
+///
+/// 由心跳 RTT、发送结果、BLE RSSI 和吞吐量采样汇总而来，
+/// 供质量感知的通道选择策略使用。未采样的指标为 `None`。
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelMetrics {
+    /// 通道类型
+    pub channel: Channel,
+    /// 平滑后的往返时延（毫秒）
+    pub rtt_ms: Option<f64>,
+    /// 最近一次 RSSI（dBm，仅 BLE）
+    pub rssi: Option<i16>,
+    /// 平滑后的吞吐量（字节/秒）
+    pub throughput_bps: Option<f64>,
+    /// 统计窗口内的发送次数
+    pub recent_sends: u32,
+    /// 统计窗口内的失败次数
+    pub recent_failures: u32,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 最后更新时间戳（毫秒，0 表示从未更新）
+    pub last_updated: u64,
+}
+
+impl ChannelMetrics {
+    /// 创建空指标
+    pub fn new(channel: Channel) -> Self {
+        Self {
+            channel,
+            rtt_ms: None,
+            rssi: None,
+            throughput_bps: None,
+            recent_sends: 0,
+            recent_failures: 0,
+            consecutive_failures: 0,
+            last_updated: 0,
+        }
+    }
+
+    /// 统计窗口内的失败率（无发送记录时为 0）
+    pub fn failure_rate(&self) -> f64 {
+        if self.recent_sends == 0 {
+            0.0
+        } else {
+            self.recent_failures as f64 / self.recent_sends as f64
+        }
+    }
+
+    /// 是否有任何采样数据
+    pub fn has_samples(&self) -> bool {
+        self.last_updated != 0
+    }
+}
+
+/// 内部指标条目
+#[derive(Debug, Clone)]
+struct MetricsEntry {
+    metrics: ChannelMetrics,
+    outcomes: VecDeque<bool>,
+}
+
+impl MetricsEntry {
+    fn new(channel: Channel) -> Self {
+        Self {
+            metrics: ChannelMetrics::new(channel),
+            outcomes: VecDeque::new(),
+        }
+    }
+
+    fn touch(&mut self) {
+        self.metrics.last_updated = ChannelSnapshot::now_ms();
+    }
+}
+
+/// 指标键：设备 ID（`None` 为全局指标）和通道
+type MetricsKey = (Option<String>, Channel);
+
+/// 指数平滑
+fn smooth(previous: Option<f64>, sample: f64, alpha: f64) -> f64 {
+    match previous {
+        Some(prev) => prev + alpha * (sample - prev),
+        None => sample,
+    }
+}
+
 /// 内部状态条目
 #[derive(Debug, Clone)]
 struct StatusEntry {
@@ -237,6 +358,7 @@ pub struct ChannelMonitor {
     config: ChannelMonitorConfig,
     callback: Arc<dyn ChannelStatusCallback>,
     states: RwLock<HashMap<Channel, StatusEntry>>,
+    metrics: RwLock<HashMap<MetricsKey, MetricsEntry>>,
     running: RwLock<bool>,
 }
 
@@ -265,6 +387,7 @@ impl ChannelMonitor {
             config,
             callback,
             states: RwLock::new(initial_states),
+            metrics: RwLock::new(HashMap::new()),
             running: RwLock::new(false),
         })
     }
@@ -361,6 +484,158 @@ impl ChannelMonitor {
         states.values().any(|e| e.status == ChannelStatus::Available)
     }
 
+    /// 记录往返时延（通常来自心跳）
+    pub fn record_rtt(&self, channel: Channel, rtt: Duration) {
+        self.update_rtt(None, channel, rtt);
+    }
+
+    /// 记录一次发送结果
+    ///
+    /// 只保留最近 `metrics_window` 次结果用于计算失败率。
+    pub fn record_send_result(&self, channel: Channel, success: bool) {
+        self.update_send_result(None, channel, success);
+    }
+
+    /// 记录信号强度（来自 BLE 扫描结果）
+    pub fn record_rssi(&self, channel: Channel, rssi: i16) {
+        self.update_rssi(None, channel, rssi);
+    }
+
+    /// 记录吞吐量采样
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - 传输的字节数
+    /// * `elapsed` - 传输耗时
+    pub fn record_throughput(&self, channel: Channel, bytes: usize, elapsed: Duration) {
+        self.update_throughput(None, channel, bytes, elapsed);
+    }
+
+    /// 获取通道质量指标
+    pub fn metrics(&self, channel: Channel) -> ChannelMetrics {
+        self.lookup(None, channel)
+    }
+
+    /// 获取所有通道的质量指标（WiFi、BLE 顺序）
+    pub fn all_metrics(&self) -> Vec<ChannelMetrics> {
+        vec![self.metrics(Channel::Wifi), self.metrics(Channel::Ble)]
+    }
+
+    /// 记录某设备在通道上的往返时延
+    ///
+    /// 设备指标与全局指标相互独立，供按设备选择通道使用。
+    pub fn record_device_rtt(&self, device_id: &str, channel: Channel, rtt: Duration) {
+        self.update_rtt(Some(device_id), channel, rtt);
+    }
+
+    /// 记录发往某设备的一次发送结果
+    pub fn record_device_send_result(&self, device_id: &str, channel: Channel, success: bool) {
+        self.update_send_result(Some(device_id), channel, success);
+    }
+
+    /// 记录某设备的信号强度
+    pub fn record_device_rssi(&self, device_id: &str, channel: Channel, rssi: i16) {
+        self.update_rssi(Some(device_id), channel, rssi);
+    }
+
+    /// 记录发往某设备的吞吐量采样
+    pub fn record_device_throughput(&self, device_id: &str, channel: Channel, bytes: usize, elapsed: Duration) {
+        self.update_throughput(Some(device_id), channel, bytes, elapsed);
+    }
+
+    /// 获取某设备在通道上的质量指标
+    pub fn device_metrics(&self, device_id: &str, channel: Channel) -> ChannelMetrics {
+        self.lookup(Some(device_id), channel)
+    }
+
+    /// 有设备指标的设备 ID（已排序）
+    pub fn devices(&self) -> Vec<String> {
+        let mut devices: Vec<String> = self
+            .metrics
+            .read()
+            .unwrap()
+            .keys()
+            .filter_map(|(device_id, _)| device_id.clone())
+            .collect();
+        devices.sort();
+        devices.dedup();
+        devices
+    }
+
+    /// 清除某设备的指标（例如取消配对后）
+    pub fn remove_device(&self, device_id: &str) {
+        self.metrics
+            .write()
+            .unwrap()
+            .retain(|(id, _), _| id.as_deref() != Some(device_id));
+    }
+
+    fn update_rtt(&self, device_id: Option<&str>, channel: Channel, rtt: Duration) {
+        let alpha = self.config.metrics_smoothing;
+        self.update(device_id, channel, |entry| {
+            let sample = rtt.as_secs_f64() * 1000.0;
+            entry.metrics.rtt_ms = Some(smooth(entry.metrics.rtt_ms, sample, alpha));
+        });
+    }
+
+    fn update_send_result(&self, device_id: Option<&str>, channel: Channel, success: bool) {
+        let window = self.config.metrics_window;
+        self.update(device_id, channel, |entry| {
+            entry.outcomes.push_back(success);
+            while entry.outcomes.len() > window {
+                entry.outcomes.pop_front();
+            }
+
+            entry.metrics.recent_sends = entry.outcomes.len() as u32;
+            entry.metrics.recent_failures = entry.outcomes.iter().filter(|ok| !**ok).count() as u32;
+            entry.metrics.consecutive_failures = if success {
+                0
+            } else {
+                entry.metrics.consecutive_failures.saturating_add(1)
+            };
+        });
+    }
+
+    fn update_rssi(&self, device_id: Option<&str>, channel: Channel, rssi: i16) {
+        self.update(device_id, channel, |entry| entry.metrics.rssi = Some(rssi));
+    }
+
+    fn update_throughput(&self, device_id: Option<&str>, channel: Channel, bytes: usize, elapsed: Duration) {
+        if bytes == 0 || elapsed.is_zero() {
+            return;
+        }
+
+        let alpha = self.config.metrics_smoothing;
+        self.update(device_id, channel, |entry| {
+            let sample = bytes as f64 / elapsed.as_secs_f64();
+            entry.metrics.throughput_bps = Some(smooth(entry.metrics.throughput_bps, sample, alpha));
+        });
+    }
+
+    /// 更新一条指标并刷新时间戳
+    fn update(&self, device_id: Option<&str>, channel: Channel, f: impl FnOnce(&mut MetricsEntry)) {
+        let mut metrics = self.metrics.write().unwrap();
+        let entry = metrics
+            .entry((device_id.map(str::to_string), channel))
+            .or_insert_with(|| MetricsEntry::new(channel));
+        f(entry);
+        entry.touch();
+    }
+
+    fn lookup(&self, device_id: Option<&str>, channel: Channel) -> ChannelMetrics {
+        self.metrics
+            .read()
+            .unwrap()
+            .get(&(device_id.map(str::to_string), channel))
+            .map(|e| e.metrics.clone())
+            .unwrap_or_else(|| ChannelMetrics::new(channel))
+    }
+
+    /// 清除通道质量指标（包括所有设备的指标）
+    pub fn reset_metrics(&self) {
+        self.metrics.write().unwrap().clear();
+    }
+
     /// 重置所有通道状态为不可用
     pub fn reset(&self) {
         let old_snapshot = self.snapshot();
@@ -394,6 +669,7 @@ impl std::fmt::Debug for ChannelMonitor {
             .field("config", &self.config)
             .field("running", &*self.running.read().unwrap())
             .field("snapshot", &self.snapshot())
+            .field("metrics", &self.all_metrics())
             .finish()
     }
 }
@@ -764,4 +1040,92 @@ mod tests {
         assert_eq!(changes[2], (Channel::Wifi, ChannelStatus::Available, ChannelStatus::Busy));
         assert_eq!(changes[3], (Channel::Wifi, ChannelStatus::Busy, ChannelStatus::Unavailable));
     }
+
+    // Metrics tests
+    #[test]
+    fn test_config_validate_metrics() {
+        assert!(ChannelMonitorConfig::new().with_metrics_window(0).validate().is_err());
+        assert!(ChannelMonitorConfig::new().with_metrics_smoothing(0.0).validate().is_err());
+        assert!(ChannelMonitorConfig::new().with_metrics_smoothing(1.5).validate().is_err());
+        assert!(ChannelMonitorConfig::new().with_metrics_smoothing(1.0).validate().is_ok());
+    }
+
+    #[test]
+    fn test_metrics_initially_empty() {
+        let monitor = ChannelMonitor::new(ChannelMonitorConfig::new(), Arc::new(TestCallback::new())).unwrap();
+        let metrics = monitor.metrics(Channel::Wifi);
+        assert_eq!(metrics, ChannelMetrics::new(Channel::Wifi));
+        assert!(!metrics.has_samples());
+        assert_eq!(metrics.failure_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_metrics_rtt_smoothing() {
+        let config = ChannelMonitorConfig::new().with_metrics_smoothing(0.5);
+        let monitor = ChannelMonitor::new(config, Arc::new(TestCallback::new())).unwrap();
+
+        monitor.record_rtt(Channel::Wifi, Duration::from_millis(100));
+        assert_eq!(monitor.metrics(Channel::Wifi).rtt_ms, Some(100.0));
+
+        monitor.record_rtt(Channel::Wifi, Duration::from_millis(200));
+        assert_eq!(monitor.metrics(Channel::Wifi).rtt_ms, Some(150.0));
+        assert!(monitor.metrics(Channel::Wifi).has_samples());
+        assert_eq!(monitor.metrics(Channel::Ble).rtt_ms, None);
+    }
+
+    #[test]
+    fn test_metrics_send_window() {
+        let config = ChannelMonitorConfig::new().with_metrics_window(4);
+        let monitor = ChannelMonitor::new(config, Arc::new(TestCallback::new())).unwrap();
+
+        monitor.record_send_result(Channel::Ble, false);
+        monitor.record_send_result(Channel::Ble, false);
+        let metrics = monitor.metrics(Channel::Ble);
+        assert_eq!(metrics.recent_failures, 2);
+        assert_eq!(metrics.consecutive_failures, 2);
+        assert_eq!(metrics.failure_rate(), 1.0);
+
+        for _ in 0..4 {
+            monitor.record_send_result(Channel::Ble, true);
+        }
+        let metrics = monitor.metrics(Channel::Ble);
+        assert_eq!(metrics.recent_sends, 4);
+        assert_eq!(metrics.recent_failures, 0);
+        assert_eq!(metrics.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_metrics_rssi_and_throughput() {
+        let monitor = ChannelMonitor::new(ChannelMonitorConfig::new(), Arc::new(TestCallback::new())).unwrap();
+
+        monitor.record_rssi(Channel::Ble, -62);
+        monitor.record_throughput(Channel::Ble, 2000, Duration::from_secs(2));
+        monitor.record_throughput(Channel::Ble, 0, Duration::from_secs(1));
+
+        let metrics = monitor.metrics(Channel::Ble);
+        assert_eq!(metrics.rssi, Some(-62));
+        assert_eq!(metrics.throughput_bps, Some(1000.0));
+
+        monitor.reset_metrics();
+        assert!(!monitor.metrics(Channel::Ble).has_samples());
+    }
+
+    #[test]
+    fn test_device_metrics_are_isolated() {
+        let monitor = ChannelMonitor::new(ChannelMonitorConfig::new(), Arc::new(TestCallback::new())).unwrap();
+
+        monitor.record_device_send_result("device-a", Channel::Wifi, false);
+        monitor.record_device_rssi("device-b", Channel::Ble, -55);
+
+        assert_eq!(monitor.device_metrics("device-a", Channel::Wifi).consecutive_failures, 1);
+        assert!(!monitor.device_metrics("device-b", Channel::Wifi).has_samples());
+        assert_eq!(monitor.device_metrics("device-b", Channel::Ble).rssi, Some(-55));
+        assert!(!monitor.metrics(Channel::Wifi).has_samples());
+        assert!(!monitor.metrics(Channel::Ble).has_samples());
+        assert_eq!(monitor.devices(), vec!["device-a".to_string(), "device-b".to_string()]);
+
+        monitor.remove_device("device-a");
+        assert!(!monitor.device_metrics("device-a", Channel::Wifi).has_samples());
+        assert_eq!(monitor.devices(), vec!["device-b".to_string()]);
+    }
 }
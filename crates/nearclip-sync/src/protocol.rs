@@ -10,7 +10,7 @@
 //! | `PairingRequest` | 设备配对请求 |
 //! | `PairingResponse` | 配对响应 |
 //! | `Heartbeat` | 心跳保活 |
-//! | `Ack` | 确认收到 |
+//! | `Ack` | 确认收到（payload 为被确认消息的 ID） |
 //!
 //! # 使用示例
 //!
@@ -70,6 +70,11 @@ pub struct PairingPayload {
     pub device_name: String,
     /// 设备平台
     pub platform: ProtocolPlatform,
+    /// 设备的 BLE 广播密钥（32 字节），对端用它从广播令牌认出本设备
+    ///
+    /// 旧版本对端不携带此字段，反序列化时取默认值 None。
+    #[serde(default)]
+    pub advertisement_key: Option<Vec<u8>>,
 }
 
 impl PairingPayload {
@@ -79,9 +84,16 @@ impl PairingPayload {
             device_id: device_id.into(),
             device_name: device_name.into(),
             platform,
+            advertisement_key: None,
         }
     }
 
+    /// 附带 BLE 广播密钥
+    pub fn with_advertisement_key(mut self, key: &[u8]) -> Self {
+        self.advertisement_key = Some(key.to_vec());
+        self
+    }
+
     /// 序列化为 MessagePack 字节
     pub fn serialize(&self) -> Result<Vec<u8>, ProtocolError> {
         rmp_serde::to_vec(self).map_err(|e| ProtocolError::Serialization(e.to_string()))
@@ -181,6 +193,7 @@ impl MessageType {
 /// - `payload`: 消息载荷，已使用 MessagePack 序列化
 /// - `timestamp`: 消息创建时间（Unix 毫秒时间戳）
 /// - `device_id`: 发送方设备的唯一标识
+/// - `message_id`: 消息唯一标识，接收方据此对多路径重复到达的消息去重
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Message {
     /// 消息类型
@@ -194,6 +207,12 @@ pub struct Message {
 
     /// 发送方设备 ID
     pub device_id: String,
+
+    /// 消息唯一标识（随机 64 位）
+    ///
+    /// 旧版本对端不携带此字段，反序列化时取默认值 0。
+    #[serde(default)]
+    pub message_id: u64,
 }
 
 impl Message {
@@ -222,9 +241,27 @@ impl Message {
             payload,
             timestamp: Self::timestamp_now(),
             device_id,
+            message_id: Self::generate_message_id(),
+        }
+    }
+
+    /// 生成新的消息 ID
+    ///
+    /// 使用随机数，保证不为 0（0 表示对端未提供消息 ID）。
+    pub fn generate_message_id() -> u64 {
+        loop {
+            let id = rand::random::<u64>();
+            if id != 0 {
+                return id;
+            }
         }
     }
 
+    /// 消息是否携带有效的消息 ID
+    pub fn has_message_id(&self) -> bool {
+        self.message_id != 0
+    }
+
     /// 获取当前 Unix 毫秒时间戳
     ///
     /// 如果系统时间早于 Unix 纪元（极端罕见），返回 0。
@@ -316,6 +353,34 @@ impl Message {
         Self::new(MessageType::Ack, payload, device_id)
     }
 
+    /// 创建确认指定消息的 ACK
+    ///
+    /// payload 为被确认消息 ID 的 8 字节大端编码。
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use nearclip_sync::Message;
+    ///
+    /// let clip = Message::clipboard_sync(b"text", "device-a".to_string());
+    /// let ack = Message::ack_for(clip.message_id, "device-b".to_string());
+    /// assert_eq!(ack.acked_message_id(), Some(clip.message_id));
+    /// ```
+    pub fn ack_for(message_id: u64, device_id: String) -> Self {
+        Self::ack_with_payload(message_id.to_be_bytes().to_vec(), device_id)
+    }
+
+    /// 获取 ACK 所确认的消息 ID
+    ///
+    /// 非 ACK 消息或未携带消息 ID 的 ACK 返回 None。
+    pub fn acked_message_id(&self) -> Option<u64> {
+        if self.msg_type != MessageType::Ack {
+            return None;
+        }
+        let bytes: [u8; 8] = self.payload.as_slice().try_into().ok()?;
+        Some(u64::from_be_bytes(bytes)).filter(|id| *id != 0)
+    }
+
     /// 创建取消配对消息
     ///
     /// # Arguments
@@ -506,6 +571,66 @@ mod tests {
         assert_eq!(original.payload, deserialized.payload);
         assert_eq!(original.device_id, deserialized.device_id);
         assert_eq!(original.timestamp, deserialized.timestamp);
+        assert_eq!(original.message_id, deserialized.message_id);
+    }
+
+    #[test]
+    fn test_message_id_unique() {
+        let a = Message::clipboard_sync(b"same", "device-1".to_string());
+        let b = Message::clipboard_sync(b"same", "device-1".to_string());
+        assert!(a.has_message_id());
+        assert!(b.has_message_id());
+        assert_ne!(a.message_id, b.message_id);
+    }
+
+    #[test]
+    fn test_message_without_id_deserializes() {
+        // 旧版本消息只有 4 个字段
+        #[derive(Serialize)]
+        struct LegacyMessage {
+            msg_type: MessageType,
+            payload: Vec<u8>,
+            timestamp: u64,
+            device_id: String,
+        }
+
+        let legacy = LegacyMessage {
+            msg_type: MessageType::ClipboardSync,
+            payload: b"legacy".to_vec(),
+            timestamp: 1,
+            device_id: "old-device".to_string(),
+        };
+        let bytes = rmp_serde::to_vec(&legacy).unwrap();
+        let decoded = Message::deserialize(&bytes).unwrap();
+
+        assert_eq!(decoded.payload, b"legacy".to_vec());
+        assert_eq!(decoded.message_id, 0);
+        assert!(!decoded.has_message_id());
+    }
+
+    #[test]
+    fn test_pairing_payload_advertisement_key() {
+        let payload = PairingPayload::new("mac-1", "MacBook", ProtocolPlatform::MacOS)
+            .with_advertisement_key(&[7u8; 32]);
+        let decoded = PairingPayload::deserialize(&payload.serialize().unwrap()).unwrap();
+        assert_eq!(decoded.advertisement_key, Some(vec![7u8; 32]));
+
+        // 旧版本载荷没有广播密钥
+        #[derive(Serialize)]
+        struct LegacyPairingPayload {
+            device_id: String,
+            device_name: String,
+            platform: ProtocolPlatform,
+        }
+
+        let legacy = LegacyPairingPayload {
+            device_id: "old-device".to_string(),
+            device_name: "Old".to_string(),
+            platform: ProtocolPlatform::Android,
+        };
+        let decoded = PairingPayload::deserialize(&rmp_serde::to_vec(&legacy).unwrap()).unwrap();
+        assert_eq!(decoded.device_id, "old-device");
+        assert_eq!(decoded.advertisement_key, None);
     }
 
     #[test]
@@ -556,6 +681,26 @@ mod tests {
         assert_eq!(msg.device_id, "device-abc");
     }
 
+    #[test]
+    fn test_ack_for_message_id() {
+        let ack = Message::ack_for(0x0102_0304_0506_0708, "device-abc".to_string());
+        assert_eq!(ack.msg_type, MessageType::Ack);
+        assert_eq!(ack.payload, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(ack.acked_message_id(), Some(0x0102_0304_0506_0708));
+
+        let decoded = Message::deserialize(&ack.serialize().unwrap()).unwrap();
+        assert_eq!(decoded.acked_message_id(), Some(0x0102_0304_0506_0708));
+    }
+
+    #[test]
+    fn test_acked_message_id_none() {
+        assert_eq!(Message::ack("device-abc".to_string()).acked_message_id(), None);
+        assert_eq!(Message::ack_for(0, "device-abc".to_string()).acked_message_id(), None);
+
+        let clip = Message::clipboard_sync(&[0u8; 8], "device-abc".to_string());
+        assert_eq!(clip.acked_message_id(), None);
+    }
+
     #[test]
     fn test_ack_with_payload() {
         let payload = b"message_id_123".to_vec();
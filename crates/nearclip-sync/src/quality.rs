@@ -0,0 +1,769 @@
+//! 质量感知的通道选择
+//!
+//! 基于 [`ChannelMonitor`] 采集的实时指标（心跳 RTT、发送失败、BLE RSSI、吞吐量）
+//! 为每个通道打分，并通过迟滞（分差阈值 + 最短驻留时间）避免通道来回切换。
+//!
+//! # 评分
+//!
+//! | 指标 | 权重 | 说明 |
+//! |------|------|------|
+//! | RTT | 40 | 0ms 满分，`rtt_ceiling` 及以上为 0 |
+//! | 失败率 | 30 | 统计窗口内无失败为满分 |
+//! | 吞吐量 | 20 | 达到 `throughput_reference` 为满分 |
+//! | RSSI | 10 | -50dBm 及以上满分，-90dBm 及以下为 0 |
+//!
+//! 缺少采样的指标取中间值，最后加上通道优先级带来的少量加分，
+//! 因此没有任何指标时的选择结果与 [`PriorityChannelSelector`](crate::PriorityChannelSelector) 一致。
+//!
+//! # 按设备选择
+//!
+//! 通过 [`ChannelSelector::select_for_device`] 选择时，评分只使用该设备的指标
+//! （[`ChannelMonitor::record_device_rtt`] 等），当前通道、驻留时间和迟滞也按设备
+//! 分别维护，一台设备的切换不会影响另一台设备。
+//!
+//! # Example
+//!
+//! ```
+//! use nearclip_sync::{
+//!     Channel, ChannelInfo, ChannelMonitor, ChannelMonitorConfig, ChannelSelector,
+//!     ChannelStatus, ChannelStatusCallback, QualityChannelSelector, QualitySelectorConfig,
+//! };
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! struct NoopCallback;
+//! impl ChannelStatusCallback for NoopCallback {
+//!     fn on_status_changed(&self, _: Channel, _: ChannelStatus, _: ChannelStatus) {}
+//!     fn on_all_channels_unavailable(&self) {}
+//! }
+//!
+//! let monitor = Arc::new(ChannelMonitor::new(ChannelMonitorConfig::new(), Arc::new(NoopCallback)).unwrap());
+//! let selector = QualityChannelSelector::new(
+//!     QualitySelectorConfig::new().with_min_dwell(Duration::ZERO),
+//!     monitor.clone(),
+//! ).unwrap();
+//!
+//! let channels = vec![
+//!     ChannelInfo::new(Channel::Wifi, ChannelStatus::Available),
+//!     ChannelInfo::new(Channel::Ble, ChannelStatus::Available),
+//! ];
+//! assert_eq!(selector.select(&channels), Some(Channel::Wifi));
+//!
+//! // WiFi 持续失败后切换到 BLE
+//! for _ in 0..10 {
+//!     monitor.record_send_result(Channel::Wifi, false);
+//! }
+//! monitor.record_rtt(Channel::Wifi, Duration::from_millis(900));
+//! assert_eq!(selector.select(&channels), Some(Channel::Ble));
+//! ```
+
+use crate::channel::{Channel, ChannelInfo, ChannelSelector};
+use crate::monitor::{ChannelMetrics, ChannelMonitor};
+use crate::sender::SyncError;
+use crate::switcher::{SwitchReason, SwitchStrategy};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+/// 默认切换分差阈值
+pub const DEFAULT_HYSTERESIS_MARGIN: f64 = 15.0;
+
+/// 默认最短驻留时间（10 秒）
+pub const DEFAULT_MIN_DWELL_SECS: u64 = 10;
+
+/// 默认保留的决策记录数
+pub const DEFAULT_DECISION_HISTORY: usize = 32;
+
+const RTT_WEIGHT: f64 = 40.0;
+const RELIABILITY_WEIGHT: f64 = 30.0;
+const THROUGHPUT_WEIGHT: f64 = 20.0;
+const RSSI_WEIGHT: f64 = 10.0;
+const RSSI_STRONG_DBM: f64 = -50.0;
+const RSSI_WEAK_DBM: f64 = -90.0;
+
+/// 质量选择器配置
+///
+/// # Example
+///
+/// ```
+/// use nearclip_sync::QualitySelectorConfig;
+/// use std::time::Duration;
+///
+/// let config = QualitySelectorConfig::new()
+///     .with_hysteresis_margin(20.0)
+///     .with_min_dwell(Duration::from_secs(5));
+/// assert!(config.validate().is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct QualitySelectorConfig {
+    /// 新通道分数需要超过当前通道的最小分差
+    pub hysteresis_margin: f64,
+    /// 切换后至少保持的时间（当前通道不可用时不受限制）
+    pub min_dwell: Duration,
+    /// RTT 评分上限，超过该值 RTT 得分为 0
+    pub rtt_ceiling: Duration,
+    /// 吞吐量满分参考值（字节/秒）
+    pub throughput_reference: f64,
+    /// 每个优先级点带来的加分
+    pub priority_bonus: f64,
+    /// 保留的决策记录数
+    pub decision_history: usize,
+}
+
+impl QualitySelectorConfig {
+    /// 创建默认配置
+    pub fn new() -> Self {
+        Self {
+            hysteresis_margin: DEFAULT_HYSTERESIS_MARGIN,
+            min_dwell: Duration::from_secs(DEFAULT_MIN_DWELL_SECS),
+            rtt_ceiling: Duration::from_secs(1),
+            throughput_reference: 1024.0 * 1024.0,
+            priority_bonus: 0.5,
+            decision_history: DEFAULT_DECISION_HISTORY,
+        }
+    }
+
+    /// 设置切换分差阈值
+    pub fn with_hysteresis_margin(mut self, margin: f64) -> Self {
+        self.hysteresis_margin = margin;
+        self
+    }
+
+    /// 设置最短驻留时间
+    pub fn with_min_dwell(mut self, dwell: Duration) -> Self {
+        self.min_dwell = dwell;
+        self
+    }
+
+    /// 设置 RTT 评分上限
+    pub fn with_rtt_ceiling(mut self, ceiling: Duration) -> Self {
+        self.rtt_ceiling = ceiling;
+        self
+    }
+
+    /// 设置吞吐量满分参考值
+    pub fn with_throughput_reference(mut self, bytes_per_sec: f64) -> Self {
+        self.throughput_reference = bytes_per_sec;
+        self
+    }
+
+    /// 设置保留的决策记录数
+    pub fn with_decision_history(mut self, size: usize) -> Self {
+        self.decision_history = size;
+        self
+    }
+
+    /// 验证配置
+    pub fn validate(&self) -> Result<(), SyncError> {
+        if self.hysteresis_margin.is_nan() || self.hysteresis_margin < 0.0 {
+            return Err(SyncError::Configuration(
+                "Hysteresis margin must not be negative".to_string(),
+            ));
+        }
+
+        if self.rtt_ceiling.is_zero() {
+            return Err(SyncError::Configuration(
+                "RTT ceiling must be greater than zero".to_string(),
+            ));
+        }
+
+        if self.throughput_reference.is_nan() || self.throughput_reference <= 0.0 {
+            return Err(SyncError::Configuration(
+                "Throughput reference must be greater than zero".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for QualitySelectorConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 通道评分
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelScore {
+    /// 通道类型
+    pub channel: Channel,
+    /// 综合得分（越高越好）
+    pub score: f64,
+    /// 评分时使用的指标
+    pub metrics: ChannelMetrics,
+}
+
+/// 选择决策记录
+///
+/// 每次选中的通道发生变化，或切换被迟滞抑制时记录一条。
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectionDecision {
+    /// 决策时间戳（毫秒）
+    pub timestamp: u64,
+    /// 决策对应的设备（`None` 为全局选择）
+    pub device_id: Option<String>,
+    /// 决策前的通道
+    pub previous: Option<Channel>,
+    /// 选中的通道
+    pub selected: Option<Channel>,
+    /// 得分最高的通道（被迟滞抑制时与 `selected` 不同）
+    pub best: Option<Channel>,
+    /// 切换原因
+    pub reason: SwitchReason,
+    /// 是否因迟滞保持了当前通道
+    pub suppressed: bool,
+    /// 各可用通道的评分
+    pub scores: Vec<ChannelScore>,
+}
+
+/// 单个设备的通道质量
+#[derive(Debug, Clone)]
+pub struct DeviceQuality {
+    /// 设备 ID
+    pub device_id: String,
+    /// 该设备当前选中的通道
+    pub current: Option<Channel>,
+    /// 该设备各通道的最新指标（WiFi、BLE 顺序）
+    pub metrics: Vec<ChannelMetrics>,
+}
+
+/// 质量选择器调试快照
+#[derive(Debug, Clone)]
+pub struct QualitySnapshot {
+    /// 当前选中的通道（全局选择）
+    pub current: Option<Channel>,
+    /// 各通道的最新全局指标
+    pub metrics: Vec<ChannelMetrics>,
+    /// 各设备的通道质量（按设备 ID 排序）
+    pub devices: Vec<DeviceQuality>,
+    /// 最近的决策记录（从旧到新）
+    pub decisions: Vec<SelectionDecision>,
+    /// 被迟滞抑制的切换次数
+    pub suppressed_switches: u64,
+}
+
+/// 单个选择目标（全局或某设备）的切换状态
+#[derive(Debug, Default)]
+struct TargetState {
+    current: Option<Channel>,
+    switched_at: Option<Instant>,
+    /// 上次被抑制的目标通道，避免重复记录同一次抑制
+    last_suppressed: Option<Channel>,
+}
+
+/// 内部状态
+#[derive(Debug, Default)]
+struct SelectorState {
+    /// 按设备 ID 维护的切换状态（`None` 为全局选择）
+    targets: HashMap<Option<String>, TargetState>,
+    decisions: VecDeque<SelectionDecision>,
+    suppressed_switches: u64,
+}
+
+/// 质量感知的通道选择器
+///
+/// 同时实现 [`ChannelSelector`] 和 [`SwitchStrategy`]。指标由外部写入共享的
+/// [`ChannelMonitor`]，选择器本身只读取指标并按设备维护当前通道和决策记录。
+pub struct QualityChannelSelector {
+    config: QualitySelectorConfig,
+    monitor: Arc<ChannelMonitor>,
+    state: RwLock<SelectorState>,
+}
+
+impl QualityChannelSelector {
+    /// 创建新的选择器
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - 选择器配置
+    /// * `monitor` - 提供通道指标的监测器
+    pub fn new(config: QualitySelectorConfig, monitor: Arc<ChannelMonitor>) -> Result<Self, SyncError> {
+        config.validate()?;
+
+        Ok(Self {
+            config,
+            monitor,
+            state: RwLock::new(SelectorState::default()),
+        })
+    }
+
+    /// 获取配置引用
+    pub fn config(&self) -> &QualitySelectorConfig {
+        &self.config
+    }
+
+    /// 获取指标来源
+    pub fn monitor(&self) -> &Arc<ChannelMonitor> {
+        &self.monitor
+    }
+
+    /// 获取当前选中的通道（全局选择）
+    pub fn current_channel(&self) -> Option<Channel> {
+        self.current(None)
+    }
+
+    /// 获取某设备当前选中的通道
+    pub fn current_channel_for(&self, device_id: &str) -> Option<Channel> {
+        self.current(Some(device_id))
+    }
+
+    /// 计算通道得分（全局指标）
+    pub fn score(&self, channel: Channel) -> ChannelScore {
+        self.score_metrics(channel, self.monitor.metrics(channel))
+    }
+
+    /// 使用某设备的指标计算通道得分
+    pub fn score_for_device(&self, device_id: &str, channel: Channel) -> ChannelScore {
+        self.score_metrics(channel, self.monitor.device_metrics(device_id, channel))
+    }
+
+    fn current(&self, device_id: Option<&str>) -> Option<Channel> {
+        self.state
+            .read()
+            .unwrap()
+            .targets
+            .get(&device_id.map(str::to_string))
+            .and_then(|t| t.current)
+    }
+
+    fn score_metrics(&self, channel: Channel, metrics: ChannelMetrics) -> ChannelScore {
+
+        let rtt = match metrics.rtt_ms {
+            Some(rtt) => 1.0 - (rtt / (self.config.rtt_ceiling.as_secs_f64() * 1000.0)).min(1.0),
+            None => 0.5,
+        };
+        let reliability = 1.0 - metrics.failure_rate();
+        let throughput = match metrics.throughput_bps {
+            Some(bps) => (bps / self.config.throughput_reference).min(1.0),
+            None => 0.5,
+        };
+        let rssi = match metrics.rssi {
+            Some(dbm) => ((dbm as f64 - RSSI_WEAK_DBM) / (RSSI_STRONG_DBM - RSSI_WEAK_DBM)).clamp(0.0, 1.0),
+            None => 0.5,
+        };
+
+        let score = rtt * RTT_WEIGHT
+            + reliability * RELIABILITY_WEIGHT
+            + throughput * THROUGHPUT_WEIGHT
+            + rssi * RSSI_WEIGHT
+            + channel.priority() as f64 * self.config.priority_bonus;
+
+        ChannelScore { channel, score, metrics }
+    }
+
+    /// 获取最近的决策记录（从旧到新）
+    pub fn recent_decisions(&self) -> Vec<SelectionDecision> {
+        self.state.read().unwrap().decisions.iter().cloned().collect()
+    }
+
+    /// 获取调试快照
+    pub fn snapshot(&self) -> QualitySnapshot {
+        let state = self.state.read().unwrap();
+
+        let mut device_ids = self.monitor.devices();
+        device_ids.extend(state.targets.keys().flatten().cloned());
+        device_ids.sort();
+        device_ids.dedup();
+
+        let devices = device_ids
+            .into_iter()
+            .map(|device_id| DeviceQuality {
+                current: state.targets.get(&Some(device_id.clone())).and_then(|t| t.current),
+                metrics: vec![
+                    self.monitor.device_metrics(&device_id, Channel::Wifi),
+                    self.monitor.device_metrics(&device_id, Channel::Ble),
+                ],
+                device_id,
+            })
+            .collect();
+
+        QualitySnapshot {
+            current: state.targets.get(&None).and_then(|t| t.current),
+            metrics: self.monitor.all_metrics(),
+            devices,
+            decisions: state.decisions.iter().cloned().collect(),
+            suppressed_switches: state.suppressed_switches,
+        }
+    }
+
+    /// 重置所有设备的当前通道和决策记录
+    pub fn reset(&self) {
+        *self.state.write().unwrap() = SelectorState::default();
+    }
+
+    /// 清除某设备的切换状态（例如取消配对后）
+    pub fn remove_device(&self, device_id: &str) {
+        self.state
+            .write()
+            .unwrap()
+            .targets
+            .remove(&Some(device_id.to_string()));
+    }
+
+    /// 在可用通道中做出选择
+    fn decide(&self, device_id: Option<&str>, available: &[Channel], current: Option<Channel>) -> Option<Channel> {
+        let mut scores: Vec<ChannelScore> = available
+            .iter()
+            .map(|c| match device_id {
+                Some(id) => self.score_for_device(id, *c),
+                None => self.score(*c),
+            })
+            .collect();
+        scores.sort_by(|a, b| b.score.total_cmp(&a.score));
+        let best = scores.first().map(|s| s.channel);
+
+        let mut guard = self.state.write().unwrap();
+        let SelectorState {
+            targets,
+            decisions,
+            suppressed_switches,
+        } = &mut *guard;
+        let state = targets.entry(device_id.map(str::to_string)).or_default();
+        let current_score = current
+            .filter(|c| available.contains(c))
+            .and_then(|c| scores.iter().find(|s| s.channel == c))
+            .map(|s| s.score);
+
+        let (selected, reason, suppressed) = match (current_score, best) {
+            (_, None) => (None, SwitchReason::Unavailable, false),
+            (None, Some(best)) => {
+                let reason = if current.is_some() {
+                    SwitchReason::Unavailable
+                } else {
+                    SwitchReason::Initial
+                };
+                (Some(best), reason, false)
+            }
+            (Some(current_score), Some(best)) => {
+                let curr = current.unwrap();
+                if best == curr {
+                    (Some(curr), SwitchReason::Initial, false)
+                } else {
+                    let best_score = scores[0].score;
+                    let dwelling = state
+                        .switched_at
+                        .map(|at| at.elapsed() < self.config.min_dwell)
+                        .unwrap_or(false);
+                    if best_score - current_score < self.config.hysteresis_margin || dwelling {
+                        (Some(curr), SwitchReason::BetterQuality, true)
+                    } else {
+                        (Some(best), SwitchReason::BetterQuality, false)
+                    }
+                }
+            }
+        };
+
+        let changed = selected != state.current;
+        let newly_suppressed = suppressed && state.last_suppressed != best;
+        state.last_suppressed = if suppressed { best } else { None };
+
+        if suppressed {
+            *suppressed_switches += 1;
+        }
+
+        if changed || newly_suppressed {
+            if changed {
+                tracing::debug!(
+                    device_id = ?device_id,
+                    from = ?state.current,
+                    to = ?selected,
+                    reason = %reason,
+                    "Quality selector switched channel"
+                );
+                state.switched_at = Some(Instant::now());
+            }
+
+            let decision = SelectionDecision {
+                timestamp: now_ms(),
+                device_id: device_id.map(str::to_string),
+                previous: state.current,
+                selected,
+                best,
+                reason,
+                suppressed,
+                scores,
+            };
+            decisions.push_back(decision);
+            while decisions.len() > self.config.decision_history {
+                decisions.pop_front();
+            }
+        }
+
+        state.current = selected;
+        selected
+    }
+}
+
+impl ChannelSelector for QualityChannelSelector {
+    fn select(&self, channels: &[ChannelInfo]) -> Option<Channel> {
+        let current = self.current_channel();
+        self.decide(None, &available_channels(channels), current)
+    }
+
+    fn select_for_device(&self, device_id: &str, channels: &[ChannelInfo]) -> Option<Channel> {
+        let current = self.current_channel_for(device_id);
+        self.decide(Some(device_id), &available_channels(channels), current)
+    }
+}
+
+impl SwitchStrategy for QualityChannelSelector {
+    fn select(&self, wifi_available: bool, ble_available: bool, current: Option<Channel>) -> Option<Channel> {
+        let mut available = Vec::with_capacity(2);
+        if wifi_available {
+            available.push(Channel::Wifi);
+        }
+        if ble_available {
+            available.push(Channel::Ble);
+        }
+        self.decide(None, &available, current)
+    }
+}
+
+impl std::fmt::Debug for QualityChannelSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QualityChannelSelector")
+            .field("config", &self.config)
+            .field("current", &self.current_channel())
+            .finish()
+    }
+}
+
+/// 可发送数据的通道
+fn available_channels(channels: &[ChannelInfo]) -> Vec<Channel> {
+    channels
+        .iter()
+        .filter(|info| info.can_send())
+        .map(|info| info.channel)
+        .collect()
+}
+
+/// 获取当前时间戳（毫秒）
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::ChannelStatus;
+    use crate::monitor::{ChannelMonitorConfig, ChannelStatusCallback};
+
+    struct NoopCallback;
+
+    impl ChannelStatusCallback for NoopCallback {
+        fn on_status_changed(&self, _: Channel, _: ChannelStatus, _: ChannelStatus) {}
+        fn on_all_channels_unavailable(&self) {}
+    }
+
+    fn create_selector(config: QualitySelectorConfig) -> (QualityChannelSelector, Arc<ChannelMonitor>) {
+        let monitor = Arc::new(ChannelMonitor::new(ChannelMonitorConfig::new(), Arc::new(NoopCallback)).unwrap());
+        let selector = QualityChannelSelector::new(config, monitor.clone()).unwrap();
+        (selector, monitor)
+    }
+
+    fn both_available() -> Vec<ChannelInfo> {
+        vec![
+            ChannelInfo::new(Channel::Wifi, ChannelStatus::Available),
+            ChannelInfo::new(Channel::Ble, ChannelStatus::Available),
+        ]
+    }
+
+    fn degrade(monitor: &ChannelMonitor, channel: Channel) {
+        for _ in 0..10 {
+            monitor.record_send_result(channel, false);
+        }
+        monitor.record_rtt(channel, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn test_config_validate() {
+        assert!(QualitySelectorConfig::new().validate().is_ok());
+        assert!(QualitySelectorConfig::new().with_hysteresis_margin(-1.0).validate().is_err());
+        assert!(QualitySelectorConfig::new().with_rtt_ceiling(Duration::ZERO).validate().is_err());
+        assert!(QualitySelectorConfig::new().with_throughput_reference(0.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_no_metrics_prefers_priority() {
+        let (selector, _) = create_selector(QualitySelectorConfig::new());
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+        assert!(selector.score(Channel::Wifi).score > selector.score(Channel::Ble).score);
+    }
+
+    #[test]
+    fn test_no_available_channel() {
+        let (selector, _) = create_selector(QualitySelectorConfig::new());
+        let channels = vec![ChannelInfo::new(Channel::Wifi, ChannelStatus::Unavailable)];
+        assert_eq!(ChannelSelector::select(&selector, &channels), None);
+    }
+
+    #[test]
+    fn test_switches_on_degraded_quality() {
+        let config = QualitySelectorConfig::new().with_min_dwell(Duration::ZERO);
+        let (selector, monitor) = create_selector(config);
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+
+        degrade(&monitor, Channel::Wifi);
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Ble));
+
+        let decisions = selector.recent_decisions();
+        let last = decisions.last().unwrap();
+        assert_eq!(last.previous, Some(Channel::Wifi));
+        assert_eq!(last.selected, Some(Channel::Ble));
+        assert_eq!(last.reason, SwitchReason::BetterQuality);
+        assert_eq!(last.scores.len(), 2);
+    }
+
+    #[test]
+    fn test_hysteresis_margin_prevents_flapping() {
+        let config = QualitySelectorConfig::new()
+            .with_min_dwell(Duration::ZERO)
+            .with_hysteresis_margin(15.0);
+        let (selector, monitor) = create_selector(config);
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+
+        // 轻微劣化：BLE 略好但不足以跨过阈值
+        monitor.record_send_result(Channel::Wifi, false);
+        monitor.record_send_result(Channel::Wifi, true);
+        assert!(selector.score(Channel::Ble).score > selector.score(Channel::Wifi).score);
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+
+        let snapshot = selector.snapshot();
+        assert_eq!(snapshot.suppressed_switches, 2);
+        // 同一次抑制只记录一条决策
+        assert_eq!(snapshot.decisions.iter().filter(|d| d.suppressed).count(), 1);
+    }
+
+    #[test]
+    fn test_min_dwell_prevents_flapping() {
+        let config = QualitySelectorConfig::new().with_min_dwell(Duration::from_secs(60));
+        let (selector, monitor) = create_selector(config);
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+
+        degrade(&monitor, Channel::Wifi);
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+    }
+
+    #[test]
+    fn test_unavailable_current_switches_immediately() {
+        let config = QualitySelectorConfig::new().with_min_dwell(Duration::from_secs(60));
+        let (selector, _) = create_selector(config);
+        assert_eq!(ChannelSelector::select(&selector, &both_available()), Some(Channel::Wifi));
+
+        let channels = vec![
+            ChannelInfo::new(Channel::Wifi, ChannelStatus::Unavailable),
+            ChannelInfo::new(Channel::Ble, ChannelStatus::Available),
+        ];
+        assert_eq!(ChannelSelector::select(&selector, &channels), Some(Channel::Ble));
+        assert_eq!(selector.recent_decisions().last().unwrap().reason, SwitchReason::Unavailable);
+    }
+
+    #[test]
+    fn test_rssi_affects_ble_score() {
+        let (selector, monitor) = create_selector(QualitySelectorConfig::new());
+        monitor.record_rssi(Channel::Ble, -45);
+        let strong = selector.score(Channel::Ble).score;
+        monitor.record_rssi(Channel::Ble, -95);
+        let weak = selector.score(Channel::Ble).score;
+        assert!(strong - weak >= RSSI_WEIGHT - f64::EPSILON);
+    }
+
+    #[test]
+    fn test_switch_strategy_uses_given_current() {
+        let config = QualitySelectorConfig::new().with_min_dwell(Duration::ZERO);
+        let (selector, _) = create_selector(config);
+
+        // 当前在 BLE，WiFi 无指标优势不足以跨过阈值
+        assert_eq!(SwitchStrategy::select(&selector, true, true, Some(Channel::Ble)), Some(Channel::Ble));
+        assert_eq!(SwitchStrategy::select(&selector, true, false, Some(Channel::Ble)), Some(Channel::Wifi));
+        assert_eq!(SwitchStrategy::select(&selector, false, false, Some(Channel::Wifi)), None);
+    }
+
+    #[test]
+    fn test_devices_switch_independently() {
+        let config = QualitySelectorConfig::new().with_min_dwell(Duration::from_secs(60));
+        let (selector, monitor) = create_selector(config);
+        let channels = both_available();
+
+        // 设备 B 先选中 BLE（WiFi 不可用），进入驻留期
+        let ble_only = vec![
+            ChannelInfo::new(Channel::Wifi, ChannelStatus::Unavailable),
+            ChannelInfo::new(Channel::Ble, ChannelStatus::Available),
+        ];
+        assert_eq!(selector.select_for_device("device-b", &ble_only), Some(Channel::Ble));
+
+        // 设备 A 首次选择不受 B 的驻留期影响
+        assert_eq!(selector.select_for_device("device-a", &channels), Some(Channel::Wifi));
+
+        // A 的 WiFi 劣化只影响 A 的评分，B 保持 BLE（驻留期内不切回 WiFi）
+        for _ in 0..10 {
+            monitor.record_device_send_result("device-a", Channel::Wifi, false);
+        }
+        monitor.record_device_rtt("device-a", Channel::Wifi, Duration::from_secs(2));
+        assert!(!monitor.metrics(Channel::Wifi).has_samples());
+        assert!(selector.score_for_device("device-b", Channel::Wifi).score > selector.score_for_device("device-b", Channel::Ble).score);
+        assert_eq!(selector.select_for_device("device-b", &channels), Some(Channel::Ble));
+
+        // A 仍在驻留期内，劣化的切换被抑制；B 的抑制不会记到 A 上
+        assert_eq!(selector.select_for_device("device-a", &channels), Some(Channel::Wifi));
+        assert_eq!(selector.current_channel_for("device-a"), Some(Channel::Wifi));
+        assert_eq!(selector.current_channel_for("device-b"), Some(Channel::Ble));
+        assert_eq!(selector.current_channel(), None);
+
+        let decisions = selector.recent_decisions();
+        let suppressed: Vec<_> = decisions.iter().filter(|d| d.suppressed).collect();
+        assert_eq!(suppressed.len(), 2);
+        assert!(suppressed.iter().any(|d| d.device_id.as_deref() == Some("device-a")));
+        assert!(suppressed.iter().any(|d| d.device_id.as_deref() == Some("device-b")));
+
+        let snapshot = selector.snapshot();
+        let ids: Vec<_> = snapshot.devices.iter().map(|d| d.device_id.as_str()).collect();
+        assert_eq!(ids, vec!["device-a", "device-b"]);
+        assert_eq!(snapshot.devices[1].current, Some(Channel::Ble));
+    }
+
+    #[test]
+    fn test_device_switch_does_not_force_other_device() {
+        let config = QualitySelectorConfig::new().with_min_dwell(Duration::ZERO);
+        let (selector, monitor) = create_selector(config);
+        let channels = both_available();
+
+        assert_eq!(selector.select_for_device("device-a", &channels), Some(Channel::Wifi));
+        assert_eq!(selector.select_for_device("device-b", &channels), Some(Channel::Wifi));
+
+        for _ in 0..10 {
+            monitor.record_device_send_result("device-a", Channel::Wifi, false);
+        }
+        monitor.record_device_rtt("device-a", Channel::Wifi, Duration::from_secs(2));
+
+        assert_eq!(selector.select_for_device("device-a", &channels), Some(Channel::Ble));
+        assert_eq!(selector.select_for_device("device-b", &channels), Some(Channel::Wifi));
+
+        selector.remove_device("device-a");
+        assert_eq!(selector.current_channel_for("device-a"), None);
+        assert_eq!(selector.current_channel_for("device-b"), Some(Channel::Wifi));
+    }
+
+    #[test]
+    fn test_decision_history_bounded() {
+        let config = QualitySelectorConfig::new().with_decision_history(2);
+        let (selector, _) = create_selector(config);
+        let wifi = vec![ChannelInfo::new(Channel::Wifi, ChannelStatus::Available)];
+        let ble = vec![ChannelInfo::new(Channel::Ble, ChannelStatus::Available)];
+
+        for _ in 0..3 {
+            ChannelSelector::select(&selector, &wifi);
+            ChannelSelector::select(&selector, &ble);
+        }
+        assert_eq!(selector.recent_decisions().len(), 2);
+
+        selector.reset();
+        assert!(selector.recent_decisions().is_empty());
+        assert_eq!(selector.current_channel(), None);
+    }
+}
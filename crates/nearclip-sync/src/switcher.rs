@@ -46,6 +46,8 @@ pub enum SwitchReason {
     Manual,
     /// 初始选择
     Initial,
+    /// 其他通道质量明显更好
+    BetterQuality,
 }
 
 impl SwitchReason {
@@ -56,6 +58,7 @@ impl SwitchReason {
             SwitchReason::HigherPriority => "higher_priority",
             SwitchReason::Manual => "manual",
             SwitchReason::Initial => "initial",
+            SwitchReason::BetterQuality => "better_quality",
         }
     }
 }
@@ -145,6 +148,8 @@ impl ChannelSwitcherConfig {
         match self.preferred_channel {
             Channel::Wifi => Channel::Ble,
             Channel::Ble => Channel::Wifi,
+            Channel::Quic => Channel::Wifi,
+            Channel::Relay => Channel::Wifi,
         }
     }
 }
@@ -161,6 +166,8 @@ struct SwitcherState {
     current_channel: Option<Channel>,
     wifi_status: ChannelStatus,
     ble_status: ChannelStatus,
+    quic_status: ChannelStatus,
+    relay_status: ChannelStatus,
 }
 
 impl SwitcherState {
@@ -169,6 +176,8 @@ impl SwitcherState {
             current_channel: None,
             wifi_status: ChannelStatus::Unavailable,
             ble_status: ChannelStatus::Unavailable,
+            quic_status: ChannelStatus::Unavailable,
+            relay_status: ChannelStatus::Unavailable,
         }
     }
 
@@ -176,6 +185,8 @@ impl SwitcherState {
         match channel {
             Channel::Wifi => self.wifi_status,
             Channel::Ble => self.ble_status,
+            Channel::Quic => self.quic_status,
+            Channel::Relay => self.relay_status,
         }
     }
 
@@ -183,6 +194,8 @@ impl SwitcherState {
         match channel {
             Channel::Wifi => self.wifi_status = status,
             Channel::Ble => self.ble_status = status,
+            Channel::Quic => self.quic_status = status,
+            Channel::Relay => self.relay_status = status,
         }
     }
 
@@ -191,7 +204,7 @@ impl SwitcherState {
     }
 
     fn has_any_available(&self) -> bool {
-        self.wifi_status == ChannelStatus::Available || self.ble_status == ChannelStatus::Available
+        [self.wifi_status, self.ble_status, self.quic_status, self.relay_status].contains(&ChannelStatus::Available)
     }
 }
 
@@ -551,6 +564,9 @@ mod tests {
 
         let config = ChannelSwitcherConfig::new().with_preferred_channel(Channel::Ble);
         assert_eq!(config.fallback_channel(), Channel::Wifi);
+
+        let config = ChannelSwitcherConfig::new().with_preferred_channel(Channel::Relay);
+        assert_eq!(config.fallback_channel(), Channel::Wifi);
     }
 
     #[test]
@@ -586,6 +602,7 @@ mod tests {
         assert_eq!(SwitchReason::HigherPriority.as_str(), "higher_priority");
         assert_eq!(SwitchReason::Manual.as_str(), "manual");
         assert_eq!(SwitchReason::Initial.as_str(), "initial");
+        assert_eq!(SwitchReason::BetterQuality.as_str(), "better_quality");
     }
 
     #[test]
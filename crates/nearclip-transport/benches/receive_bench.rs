@@ -0,0 +1,202 @@
+//! Receive path allocation benchmarks
+//!
+//! Compares the previous receive pipeline (one buffer sized from the length
+//! prefix, copying AES-GCM decryption) with the current one (reusable
+//! `FrameDecoder` buffer, multiplexer reassembly, in-place decryption) for
+//! an encrypted clipboard message as `EncryptedTransport` puts it on the wire.
+//!
+//! Allocation counts are exact, so they are printed as a table before the
+//! criterion timings: heap allocations, bytes requested and peak live heap
+//! per received message.
+//!
+//! Run with `cargo bench -p nearclip-transport --bench receive_bench`.
+
+use criterion::{black_box, BenchmarkId, Criterion};
+use nearclip_crypto::Aes256Gcm;
+use nearclip_sync::{Message, MessageType};
+use nearclip_transport::{FrameDecoder, Multiplexer, MuxConfig, MuxFrame, DEFAULT_MAX_FRAME_PAYLOAD};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Bytes copied per simulated socket read in the copying pipeline
+const READ_SIZE: usize = 64 * 1024;
+
+/// Clipboard payload sizes under test
+const SIZES: [usize; 3] = [64 * 1024, 1024 * 1024, 4 * 1024 * 1024];
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+static LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+static PEAK_LIVE_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// System allocator that counts allocations and tracks the live heap
+struct CountingAllocator;
+
+impl CountingAllocator {
+    fn record(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE_BYTES.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK_LIVE_BYTES.fetch_max(live, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::record(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::record(new_size);
+        LIVE_BYTES.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// Heap usage of one call
+struct AllocationStats {
+    allocations: usize,
+    allocated_bytes: usize,
+    peak_bytes: usize,
+}
+
+fn measure<T>(f: impl FnOnce() -> T) -> AllocationStats {
+    let allocations = ALLOCATIONS.load(Ordering::SeqCst);
+    let allocated_bytes = ALLOCATED_BYTES.load(Ordering::SeqCst);
+    let live = LIVE_BYTES.load(Ordering::SeqCst);
+    PEAK_LIVE_BYTES.store(live, Ordering::SeqCst);
+
+    drop(black_box(f()));
+
+    AllocationStats {
+        allocations: ALLOCATIONS.load(Ordering::SeqCst) - allocations,
+        allocated_bytes: ALLOCATED_BYTES.load(Ordering::SeqCst) - allocated_bytes,
+        peak_bytes: PEAK_LIVE_BYTES.load(Ordering::SeqCst) - live,
+    }
+}
+
+/// An encrypted clipboard message as serialized by `EncryptedTransport`
+fn encrypted_wire_message(cipher: &Aes256Gcm, size: usize) -> Vec<u8> {
+    let inner = Message::clipboard_sync(&vec![0x5a; size], "bench".to_string());
+    let ciphertext = cipher.encrypt(&inner.serialize().unwrap()).unwrap();
+    Message::new(MessageType::Heartbeat, ciphertext, "bench".to_string())
+        .serialize()
+        .unwrap()
+}
+
+/// The same message split into multiplexed data frames
+fn mux_wire(message: &[u8]) -> Vec<u8> {
+    let chunks: Vec<&[u8]> = message.chunks(DEFAULT_MAX_FRAME_PAYLOAD).collect();
+    chunks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, chunk)| MuxFrame::data(1, chunk.to_vec(), i + 1 == chunks.len()).encode())
+        .collect()
+}
+
+/// Previous pipeline: length-sized buffer, then copying decryption
+fn receive_copying(cipher: &Aes256Gcm, wire: &[u8]) -> Message {
+    let mut data = vec![0u8; wire.len()];
+    for (dst, src) in data.chunks_mut(READ_SIZE).zip(wire.chunks(READ_SIZE)) {
+        dst.copy_from_slice(src);
+    }
+    let wrapper = Message::deserialize(&data).unwrap();
+    let plaintext = cipher.decrypt(&wrapper.payload).unwrap();
+    Message::deserialize(&plaintext).unwrap()
+}
+
+/// Current pipeline: per-connection decoder and multiplexer, in-place decryption
+fn receive_in_place(cipher: &Aes256Gcm, decoder: &mut FrameDecoder, mux: &mut Multiplexer, wire: &[u8]) -> Message {
+    let mut completed = None;
+    let mut remaining = wire;
+    while !remaining.is_empty() {
+        // Like a socket read, fill at most the buffer's spare capacity
+        let buf = decoder.read_buf();
+        let n = (buf.capacity() - buf.len()).min(remaining.len());
+        buf.extend_from_slice(&remaining[..n]);
+        remaining = &remaining[n..];
+
+        while let Some(frame) = decoder.decode().unwrap() {
+            if let Some(msg) = mux.receive(frame).unwrap() {
+                completed = Some(msg.data);
+            }
+        }
+    }
+    // Window updates would go back to the peer
+    while mux.next_frame().is_some() {}
+
+    let mut wrapper = Message::deserialize(&completed.unwrap()).unwrap();
+    let plaintext = cipher.decrypt_slice_in_place(&mut wrapper.payload).unwrap();
+    Message::deserialize(plaintext).unwrap()
+}
+
+/// Print allocations per received message for both pipelines
+fn report_allocations() {
+    let cipher = Aes256Gcm::new(&[0u8; 32]).unwrap();
+    println!("receive allocations per message");
+    println!("{:<10} {:<10} {:>8} {:>14} {:>14}", "size", "pipeline", "allocs", "alloc bytes", "peak bytes");
+
+    for size in SIZES {
+        let message = encrypted_wire_message(&cipher, size);
+        let framed = mux_wire(&message);
+        let mut decoder = FrameDecoder::new();
+        let mut mux = Multiplexer::new(MuxConfig::new());
+        // Warm the per-connection buffers, as on a long-lived connection
+        receive_in_place(&cipher, &mut decoder, &mut mux, &framed);
+
+        let copying = measure(|| receive_copying(&cipher, &message));
+        let in_place = measure(|| receive_in_place(&cipher, &mut decoder, &mut mux, &framed));
+        for (name, stats) in [("copying", copying), ("in_place", in_place)] {
+            println!(
+                "{:<10} {:<10} {:>8} {:>14} {:>14}",
+                format!("{}KB", size / 1024),
+                name,
+                stats.allocations,
+                stats.allocated_bytes,
+                stats.peak_bytes
+            );
+        }
+    }
+    println!();
+}
+
+fn bench_receive_time(c: &mut Criterion) {
+    let cipher = Aes256Gcm::new(&[0u8; 32]).unwrap();
+    let mut group = c.benchmark_group("receive");
+    group.sample_size(10);
+
+    for size in SIZES {
+        let message = encrypted_wire_message(&cipher, size);
+        let framed = mux_wire(&message);
+        let label = format!("{}KB", size / 1024);
+
+        group.bench_with_input(BenchmarkId::new("copying", &label), &message, |b, wire| {
+            b.iter(|| receive_copying(&cipher, black_box(wire)))
+        });
+
+        let mut decoder = FrameDecoder::new();
+        let mut mux = Multiplexer::new(MuxConfig::new());
+        group.bench_with_input(BenchmarkId::new("in_place", &label), &framed, |b, wire| {
+            b.iter(|| receive_in_place(&cipher, &mut decoder, &mut mux, black_box(wire)))
+        });
+    }
+
+    group.finish();
+}
+
+fn main() {
+    report_allocations();
+
+    let mut criterion = Criterion::default().configure_from_args();
+    bench_receive_time(&mut criterion);
+    criterion.final_summary();
+}
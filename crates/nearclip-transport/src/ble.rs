@@ -3,25 +3,76 @@
 //! BLE transport works differently from WiFi - the actual BLE operations
 //! are performed by platform-native code (Swift/Kotlin), and this module
 //! provides the bridge between the Rust transport layer and the platform.
+//!
+//! Messages are carried in multiplexed frames (see [`crate::mux`]); each
+//! frame is chunked to the MTU on its own, so a heartbeat or ack is at most
+//! one frame behind an in-progress clipboard transfer.
+//!
+//! Chunks go through a [`SendWindow`]: the receiver reports missing chunks
+//! over DATA_ACK and only those are retransmitted. DATA_ACK therefore carries
+//! two kinds of payload, told apart by length: a 2-byte message ACK and a
+//! [`ChunkReport`] of at least [`CHUNK_REPORT_HEADER_SIZE`] bytes.
+//!
+//! When both sides support it, frames go over an L2CAP connection-oriented
+//! channel instead: [`BleTransport::open_l2cap`] reads the peer's PSM
+//! characteristic and asks the platform to open the channel, and once it is
+//! reported open, encoded frames are written to it whole. The channel is a
+//! reliable stream, so chunking and reports are GATT-only; message ACKs still
+//! use DATA_ACK. If the peer has no PSM or an L2CAP write fails, the
+//! transport keeps using GATT chunking.
+//!
+//! Chunks are sized from the negotiated [`LinkParameters`] when the sender
+//! knows them, so each chunk fills whole link-layer packets. The time from
+//! a message's first frame to its ACK is measured as the effective
+//! throughput of the link.
+//!
+//! Chunks start out with the v1 header. [`BleTransport::negotiate_chunk_version`]
+//! reads the peer's chunk version characteristic and switches to the v2
+//! header (32-bit message IDs, per-chunk CRC32) if the peer supports it;
+//! receiving a valid v2 chunk switches too. Once the peer is known to send
+//! v2, every chunk is parsed as v2 and chunks failing the CRC are dropped
+//! and retransmitted like lost ones.
+//!
+//! Peers from before multiplexing chunk each (encrypted) message whole and
+//! ACK it with the chunk message ID. Framing therefore starts out as
+//! [`Framing::LengthPrefixed`]: [`BleTransport::negotiate_framing`] reads the
+//! peer's framing characteristic, and receiving a chunk flagged
+//! [`ChunkFlags::MUX`] switches too. Mux frames always travel in v2 chunks
+//! with that flag, so each reassembled message says how to decode it. Whole
+//! messages bypass the send window, as such peers send no chunk reports.
 
 use async_trait::async_trait;
-use nearclip_ble::{ChunkHeader, Chunker, Reassembler, DEFAULT_BLE_MTU, DEFAULT_REASSEMBLE_TIMEOUT, CHUNK_HEADER_SIZE};
+use nearclip_ble::{
+    parse_chunk_version, parse_l2cap_psm, parse_mux_framing, BleHardware, ChunkFlags, ChunkHeader, ChunkReport,
+    ChunkVersion, Chunker, LinkParameters, Reassembler, SendWindow, ThroughputMeter,
+    ATT_HEADER_SIZE, CHUNK_HEADER_SIZE, CHUNK_REPORT_HEADER_SIZE, CHUNK_VERSION_CHARACTERISTIC_UUID,
+    DATA_ACK_CHARACTERISTIC_UUID, DATA_TRANSFER_CHARACTERISTIC_UUID, DEFAULT_BLE_MTU,
+    DEFAULT_REASSEMBLE_TIMEOUT, FRAMING_CHARACTERISTIC_UUID, L2CAP_PSM_CHARACTERISTIC_UUID,
+};
 use nearclip_crypto::Aes256Gcm;
 use nearclip_sync::{Channel, Message};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{Mutex, Notify, oneshot};
+use tokio::time::Instant;
 use std::collections::VecDeque;
 use tracing::{debug, warn, instrument};
 
 use crate::error::TransportError;
+use crate::mux::{is_control, FrameDecoder, FrameType, Framing, MuxConfig, MuxFrame, Multiplexer};
 use crate::traits::Transport;
 
 /// Default ACK wait timeout in milliseconds
 const DEFAULT_ACK_TIMEOUT_MS: u64 = 5000;
 
+/// Retransmission timer period in milliseconds
+const RETRANSMIT_INTERVAL_MS: u64 = 500;
+
+/// Recently completed BLE messages remembered to answer late duplicates
+const COMPLETED_HISTORY: usize = 16;
+
 /// BLE sender interface - platform must implement this
 ///
 /// This trait is implemented by platform-native code (Swift/Kotlin)
@@ -78,39 +129,449 @@ pub trait BleSender: Send + Sync {
         let _ = device_id;
         Ok(())
     }
+
+    /// Send a chunk report for a partially received BLE message
+    ///
+    /// Written to the DATA_ACK characteristic like [`send_ack`](Self::send_ack);
+    /// the peer tells the two apart by length.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device that sent the chunks
+    /// * `report` - Encoded [`ChunkReport`]
+    ///
+    /// # Returns
+    /// * `Ok(())` if the report was sent successfully
+    /// * `Err(String)` with error message if failed
+    fn send_chunk_report(&self, device_id: &str, report: &[u8]) -> Result<(), String> {
+        // Default implementation does nothing - platforms can override
+        let _ = device_id;
+        let _ = report;
+        Ok(())
+    }
+
+    /// Start opening an L2CAP channel to a device
+    ///
+    /// Reads the peer's L2CAP PSM characteristic and opens a channel on it.
+    /// The platform reports the outcome through
+    /// [`BleTransport::on_l2cap_state_changed`].
+    ///
+    /// # Returns
+    /// * `Ok(())` if the open was started
+    /// * `Err(String)` if L2CAP is unsupported on either side
+    fn open_l2cap(&self, device_id: &str) -> Result<(), String> {
+        // Default implementation: L2CAP unsupported, data stays on GATT
+        let _ = device_id;
+        Err("L2CAP is not supported".to_string())
+    }
+
+    /// Write encoded frames to the open L2CAP channel of a device
+    ///
+    /// # Arguments
+    /// * `device_id` - The target device ID
+    /// * `data` - One or more whole frames (not chunked)
+    ///
+    /// # Returns
+    /// * `Ok(())` if the write was successful
+    /// * `Err(String)` with error message if failed
+    fn send_l2cap_data(&self, device_id: &str, data: &[u8]) -> Result<(), String> {
+        let _ = device_id;
+        let _ = data;
+        Err("L2CAP is not supported".to_string())
+    }
+
+    /// Get the negotiated link parameters for a device
+    ///
+    /// # Returns
+    /// * `Some(params)` if the platform reports them
+    /// * `None` to size chunks from [`get_mtu`](Self::get_mtu) alone
+    fn link_parameters(&self, device_id: &str) -> Option<LinkParameters> {
+        // Default implementation: only the MTU is known
+        let _ = device_id;
+        None
+    }
+
+    /// Get the highest chunk header version the peer can receive
+    ///
+    /// Read from the peer's chunk version characteristic. Peers without
+    /// the characteristic only understand v1.
+    fn peer_chunk_version(&self, device_id: &str) -> ChunkVersion {
+        // Default implementation: assume an old peer
+        let _ = device_id;
+        ChunkVersion::V1
+    }
+
+    /// Whether the peer accepts multiplexed frames
+    ///
+    /// Read from the peer's framing characteristic. Peers without the
+    /// characteristic expect each message chunked whole.
+    fn peer_supports_mux(&self, device_id: &str) -> bool {
+        // Default implementation: assume an old peer
+        let _ = device_id;
+        false
+    }
+}
+
+/// [`BleSender`] backed by an in-process [`BleHardware`]
+///
+/// Used with Rust backends such as BlueZ or the simulated air, where there is
+/// no platform bridge. `device_id` is passed through as the peripheral ID.
+/// Data goes to DATA_TRANSFER; ACKs go to DATA_ACK as a little-endian u16.
+/// L2CAP is used if the hardware supports it and the peer publishes a PSM.
+pub struct BleHardwareSender {
+    hardware: Arc<dyn BleHardware>,
+}
+
+impl BleHardwareSender {
+    /// Create a sender over the given hardware
+    pub fn new(hardware: Arc<dyn BleHardware>) -> Self {
+        Self { hardware }
+    }
+}
+
+impl BleSender for BleHardwareSender {
+    fn send_ble_data(&self, device_id: &str, data: &[u8]) -> Result<(), String> {
+        self.hardware.write_characteristic(
+            device_id,
+            &DATA_TRANSFER_CHARACTERISTIC_UUID.to_string(),
+            data,
+        )
+    }
+
+    fn is_ble_connected(&self, device_id: &str) -> bool {
+        self.hardware.is_connected(device_id)
+    }
+
+    fn get_mtu(&self, device_id: &str) -> usize {
+        self.hardware.get_mtu(device_id) as usize
+    }
+
+    fn send_ack(&self, device_id: &str, message_id: u16) -> Result<(), String> {
+        self.hardware.write_characteristic(
+            device_id,
+            &DATA_ACK_CHARACTERISTIC_UUID.to_string(),
+            &message_id.to_le_bytes(),
+        )
+    }
+
+    fn subscribe_ack(&self, device_id: &str) -> Result<(), String> {
+        self.hardware
+            .subscribe_characteristic(device_id, &DATA_ACK_CHARACTERISTIC_UUID.to_string())
+    }
+
+    fn send_chunk_report(&self, device_id: &str, report: &[u8]) -> Result<(), String> {
+        self.hardware
+            .write_characteristic(device_id, &DATA_ACK_CHARACTERISTIC_UUID.to_string(), report)
+    }
+
+    fn open_l2cap(&self, device_id: &str) -> Result<(), String> {
+        let value = self
+            .hardware
+            .read_characteristic(device_id, &L2CAP_PSM_CHARACTERISTIC_UUID.to_string())?;
+        let psm = parse_l2cap_psm(&value).ok_or("Peer does not publish an L2CAP PSM")?;
+        self.hardware.open_l2cap_channel(device_id, psm)
+    }
+
+    fn send_l2cap_data(&self, device_id: &str, data: &[u8]) -> Result<(), String> {
+        self.hardware.write_l2cap(device_id, data)
+    }
+
+    fn link_parameters(&self, device_id: &str) -> Option<LinkParameters> {
+        Some(self.hardware.get_link_parameters(device_id))
+    }
+
+    fn peer_chunk_version(&self, device_id: &str) -> ChunkVersion {
+        self.hardware
+            .read_characteristic(device_id, &CHUNK_VERSION_CHARACTERISTIC_UUID.to_string())
+            .map_or(ChunkVersion::V1, |value| parse_chunk_version(&value))
+    }
+
+    fn peer_supports_mux(&self, device_id: &str) -> bool {
+        self.hardware
+            .read_characteristic(device_id, &FRAMING_CHARACTERISTIC_UUID.to_string())
+            .is_ok_and(|value| parse_mux_framing(&value))
+    }
+}
+
+/// ACK ID for a message: the low 16 bits of its stream ID
+fn ack_id(stream_id: u32) -> u16 {
+    stream_id as u16
+}
+
+/// Send side of a BLE connection, shared with platform callbacks
+struct BleLink {
+    device_id: String,
+    sender: Arc<dyn BleSender>,
+    mux: std::sync::Mutex<Multiplexer>,
+    /// Chunks in flight, retransmitted from peer reports
+    window: std::sync::Mutex<SendWindow>,
+    /// Stream of each data frame in the window, by BLE message ID
+    ///
+    /// A retransmitted frame can complete after a later one, so each stream
+    /// has at most one data frame in the window to keep its frames in order.
+    in_flight: std::sync::Mutex<HashMap<u32, u32>>,
+    /// BLE message ID counter for chunking (one BLE message per frame)
+    ///
+    /// v1 headers carry only the low 16 bits.
+    message_id_counter: AtomicU32,
+    /// Chunk header version used for sending
+    chunk_version: std::sync::Mutex<ChunkVersion>,
+    /// Whether the peer accepts multiplexed frames
+    mux_framing: AtomicBool,
+    /// Last retransmission timer tick
+    last_tick: std::sync::Mutex<Instant>,
+    /// Last time a peer report acknowledged chunks
+    last_progress: std::sync::Mutex<Instant>,
+    /// Whether an L2CAP channel is open to the peer
+    l2cap: AtomicBool,
+}
+
+impl BleLink {
+    fn link_parameters(&self) -> LinkParameters {
+        self.sender.link_parameters(&self.device_id).unwrap_or_else(|| {
+            let mtu = match self.sender.get_mtu(&self.device_id) {
+                0 => DEFAULT_BLE_MTU,
+                mtu => mtu,
+            };
+            LinkParameters::new().with_mtu(mtu.min(u16::MAX as usize) as u16)
+        })
+    }
+
+    /// MTU used for chunking: whole link-layer packets per chunk
+    fn mtu(&self) -> usize {
+        self.link_parameters().chunk_mtu()
+    }
+
+    fn chunk_version(&self) -> ChunkVersion {
+        *self.chunk_version.lock().unwrap()
+    }
+
+    /// Send chunks with the v2 header from now on
+    fn upgrade_chunk_version(&self) {
+        let mut version = self.chunk_version.lock().unwrap();
+        if *version != ChunkVersion::V2 {
+            debug!(device_id = %self.device_id, "Peer supports chunk header v2");
+            *version = ChunkVersion::V2;
+        }
+    }
+
+    fn framing(&self) -> Framing {
+        if self.mux_framing.load(Ordering::SeqCst) {
+            Framing::Mux
+        } else {
+            Framing::LengthPrefixed
+        }
+    }
+
+    /// Send multiplexed frames (in v2 chunks) from now on
+    fn upgrade_framing(&self) {
+        self.upgrade_chunk_version();
+        if !self.mux_framing.swap(true, Ordering::SeqCst) {
+            debug!(device_id = %self.device_id, "Peer supports multiplexed framing");
+        }
+    }
+
+    /// Chunk a whole message and send all of it, for peers without mux framing
+    ///
+    /// The peer ACKs the chunk message ID. It sends no chunk reports, so the
+    /// chunks bypass the send window.
+    fn send_whole(&self, message_id: u16, data: &[u8]) -> Result<(), TransportError> {
+        let chunks = Chunker::chunk_versioned(
+            data,
+            message_id as u32,
+            self.mtu(),
+            self.chunk_version(),
+            ChunkFlags::empty(),
+        )
+        .map_err(|e| TransportError::Ble(e.to_string()))?;
+        debug!(device_id = %self.device_id, message_id, chunks = chunks.len(), "Sending whole BLE message");
+        for chunk in chunks {
+            self.sender.send_ble_data(&self.device_id, &chunk).map_err(|e| {
+                TransportError::SendFailed(format!("BLE send failed: {}", e))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Send every chunk that is currently sendable
+    ///
+    /// New data frames are taken from the multiplexer only when the send
+    /// window has room; control frames and window updates are taken at once.
+    /// Bulk streams stop when their window is used up and resume when the
+    /// peer's window update is received.
+    ///
+    /// With an L2CAP channel open, frames are written to it whole once the
+    /// chunks already in the window are through, so the frames of a stream
+    /// arrive in order.
+    fn pump(&self) -> Result<(), TransportError> {
+        let mut window = self.window.lock().unwrap();
+        loop {
+            if let Some(chunk) = window.next_chunk() {
+                self.sender.send_ble_data(&self.device_id, &chunk).map_err(|e| {
+                    TransportError::SendFailed(format!("BLE send failed: {}", e))
+                })?;
+                continue;
+            }
+
+            if self.l2cap.load(Ordering::SeqCst) && window.is_idle() {
+                let Some(frame) = self.mux.lock().unwrap().next_frame() else {
+                    break;
+                };
+                match self.sender.send_l2cap_data(&self.device_id, &frame.encode()) {
+                    Ok(()) => {
+                        debug!(
+                            device_id = %self.device_id,
+                            stream_id = frame.stream_id,
+                            frame_type = ?frame.frame_type,
+                            "Sent BLE frame over L2CAP"
+                        );
+                    }
+                    Err(e) => {
+                        warn!(device_id = %self.device_id, error = %e, "L2CAP send failed, falling back to GATT");
+                        self.l2cap.store(false, Ordering::SeqCst);
+                        self.queue_chunks(&mut window, &frame)?;
+                    }
+                }
+                continue;
+            }
+
+            let frame = {
+                let mut mux = self.mux.lock().unwrap();
+                if !(mux.has_urgent() || window.can_accept()) {
+                    break;
+                }
+                let in_flight = self.in_flight.lock().unwrap();
+                match mux.next_frame_skipping(|id| in_flight.values().any(|s| *s == id)) {
+                    Some(frame) => frame,
+                    None => break,
+                }
+            };
+            self.queue_chunks(&mut window, &frame)?;
+        }
+        Ok(())
+    }
+
+    /// Chunk a frame to the MTU and queue it in the send window
+    fn queue_chunks(&self, window: &mut SendWindow, frame: &MuxFrame) -> Result<(), TransportError> {
+        let version = self.chunk_version();
+        let message_id = match version {
+            ChunkVersion::V1 => self.message_id_counter.fetch_add(1, Ordering::SeqCst) as u16 as u32,
+            ChunkVersion::V2 => self.message_id_counter.fetch_add(1, Ordering::SeqCst),
+        };
+        let chunks =
+            Chunker::chunk_versioned(&frame.encode(), message_id, self.mtu(), version, ChunkFlags::MUX)
+                .map_err(|e| TransportError::Ble(e.to_string()))?;
+        debug!(
+            device_id = %self.device_id,
+            stream_id = frame.stream_id,
+            frame_type = ?frame.frame_type,
+            message_id,
+            chunks = chunks.len(),
+            "Queued BLE frame"
+        );
+        let data = frame.frame_type == FrameType::Data;
+        if data {
+            self.in_flight.lock().unwrap().insert(message_id, frame.stream_id);
+        }
+        window.push(message_id, chunks, !data);
+        Ok(())
+    }
+
+    /// Apply a chunk report from the peer and send what it unblocked
+    fn on_report(&self, report: &ChunkReport) -> Result<(), TransportError> {
+        {
+            let mut window = self.window.lock().unwrap();
+            let in_flight = window.in_flight();
+            let done = window.on_report(report);
+            if done {
+                self.in_flight.lock().unwrap().remove(&report.message_id);
+            }
+            if done || window.in_flight() < in_flight {
+                *self.last_progress.lock().unwrap() = Instant::now();
+            }
+        }
+        self.pump()
+    }
+
+    /// Run the retransmission timer if a period has passed since the last tick
+    fn tick(&self) -> Result<(), TransportError> {
+        {
+            let mut last_tick = self.last_tick.lock().unwrap();
+            if last_tick.elapsed() < Duration::from_millis(RETRANSMIT_INTERVAL_MS) {
+                return Ok(());
+            }
+            *last_tick = Instant::now();
+        }
+        if let Some(message_id) = self.window.lock().unwrap().on_tick() {
+            self.in_flight.lock().unwrap().remove(&message_id);
+            warn!(device_id = %self.device_id, message_id, "Dropped BLE frame after repeated retransmissions");
+        }
+        self.pump()
+    }
+
+    /// Send a chunk report to the peer
+    fn send_report(&self, report: &ChunkReport) {
+        let encoded = report.encode(self.mtu() - ATT_HEADER_SIZE);
+        if let Err(e) = self.sender.send_chunk_report(&self.device_id, &encoded) {
+            warn!(message_id = report.message_id, error = %e, "Failed to send chunk report");
+        }
+    }
+}
+
+/// Receive-side chunk state
+#[derive(Default)]
+struct Reassembly {
+    /// Reassemblers for incoming chunked frames
+    reassemblers: HashMap<u32, Reassembler>,
+    /// Recently completed BLE messages: (message_id, total_chunks)
+    completed: VecDeque<(u32, u16)>,
+    /// Chunk header version the peer sends, once it has sent a valid v2 chunk
+    peer_version: ChunkVersion,
 }
 
-/// Result of processing a chunk - may contain a complete message and its ID
-struct ProcessChunkResult {
-    /// The complete message, if reassembly finished
-    message: Option<Message>,
-    /// The message ID from the chunk (for ACK purposes)
-    message_id: u16,
+/// A reassembled BLE message
+struct Reassembled {
+    message_id: u32,
+    data: Vec<u8>,
+    /// Flagged [`ChunkFlags::MUX`]: `data` is a frame, not a whole message
+    mux: bool,
 }
 
-/// Process a received BLE chunk and return a complete message if reassembly is done
+/// Reassemble a received BLE chunk, if it completes a message
 ///
-/// This is the core chunk processing logic shared by async and sync code paths.
-fn process_chunk(
-    data: &[u8],
-    reassemblers: &mut HashMap<u16, Reassembler>,
-    encryption: Option<&Aes256Gcm>,
-) -> Option<ProcessChunkResult> {
+/// Sends the chunk reports the reassembler asks for. A chunk of a message
+/// that was already completed means the peer missed the final report, so
+/// that report is sent again. Only mux senders retransmit from reports, so
+/// chunks of whole messages are never reported.
+///
+/// The first valid v2 chunk marks the peer as a v2 sender: from then on
+/// chunks are parsed strictly as v2, so a corrupted one fails its CRC
+/// instead of being mistaken for a v1 chunk, and this side sends v2 too.
+/// Likewise the first valid mux chunk switches this side to mux framing.
+fn reassemble_chunk(link: &BleLink, data: &[u8], reassembly: &mut Reassembly) -> Option<Reassembled> {
     if data.len() < CHUNK_HEADER_SIZE {
         warn!("Received BLE data too short: {} bytes", data.len());
         return None;
     }
 
     // Parse chunk header
-    let header = match ChunkHeader::from_bytes(data) {
+    let parsed = match reassembly.peer_version {
+        ChunkVersion::V1 => ChunkHeader::from_bytes(data),
+        version => ChunkHeader::parse(data, version),
+    };
+    let header = match parsed {
         Ok(h) => h,
         Err(e) => {
             warn!("Failed to parse chunk header: {}", e);
             return None;
         }
     };
+    if header.version == ChunkVersion::V2 && reassembly.peer_version == ChunkVersion::V1 {
+        reassembly.peer_version = ChunkVersion::V2;
+        link.upgrade_chunk_version();
+    }
 
-    let payload = data[CHUNK_HEADER_SIZE..].to_vec();
+    let payload = data[header.header_size()..].to_vec();
+    let mux = header.flags.contains(ChunkFlags::MUX);
 
     // Validate payload length
     if payload.len() != header.payload_length as usize {
@@ -122,8 +583,25 @@ fn process_chunk(
         return None;
     }
 
-    // Get or create reassembler for this message
-    let reassembler = reassemblers
+    if !reassembly.reassemblers.contains_key(&header.message_id)
+        && reassembly.completed.contains(&(header.message_id, header.total_chunks))
+    {
+        debug!(message_id = header.message_id, "Duplicate chunk for completed message");
+        if mux {
+            link.send_report(&ChunkReport {
+                version: header.version,
+                message_id: header.message_id,
+                next_expected: header.total_chunks,
+                highest: header.total_chunks - 1,
+                missing: Vec::new(),
+            });
+        }
+        return None;
+    }
+
+    // Get or create reassembler for this frame
+    let reassembler = reassembly
+        .reassemblers
         .entry(header.message_id)
         .or_insert_with(|| {
             debug!(
@@ -143,56 +621,36 @@ fn process_chunk(
         warn!("Failed to add chunk: {}", e);
         return None;
     }
+    if mux {
+        link.upgrade_framing();
+    }
+    if let Some(report) = reassembler.take_report().filter(|_| mux) {
+        link.send_report(&report);
+    }
 
-    // Check if message is complete
-    let message = if reassembler.is_complete() {
-        // Remove reassembler and assemble message
-        if let Some(reassembler) = reassemblers.remove(&header.message_id) {
-            match reassembler.assemble() {
-                Ok(data) => {
-                    // Decrypt data if encryption is enabled
-                    let data = if let Some(cipher) = encryption {
-                        debug!(message_id = header.message_id, "Decrypting reassembled message");
-                        match cipher.decrypt(&data) {
-                            Ok(decrypted) => decrypted,
-                            Err(e) => {
-                                warn!("Failed to decrypt BLE message: {}", e);
-                                return None;
-                            }
-                        }
-                    } else {
-                        data
-                    };
-
-                    // Deserialize message
-                    match Message::deserialize(&data) {
-                        Ok(msg) => {
-                            debug!(
-                                message_id = header.message_id,
-                                "BLE message reassembled and queued"
-                            );
-                            Some(msg)
-                        }
-                        Err(e) => {
-                            warn!("Failed to deserialize BLE message: {}", e);
-                            None
-                        }
-                    }
-                }
-                Err(e) => {
-                    warn!("Failed to assemble BLE message: {}", e);
-                    None
-                }
+    let reassembled = if reassembler.is_complete() {
+        if reassembly.completed.len() == COMPLETED_HISTORY {
+            reassembly.completed.pop_front();
+        }
+        reassembly.completed.push_back((header.message_id, header.total_chunks));
+        match reassembly.reassemblers.remove(&header.message_id).map(|r| r.assemble()) {
+            Some(Ok(data)) => Some(Reassembled {
+                message_id: header.message_id,
+                data,
+                mux,
+            }),
+            Some(Err(e)) => {
+                warn!("Failed to assemble BLE message: {}", e);
+                None
             }
-        } else {
-            None
+            None => None,
         }
     } else {
         None
     };
 
     // Clean up expired reassemblers
-    reassemblers.retain(|id, r| {
+    reassembly.reassemblers.retain(|id, r| {
         if r.is_expired() {
             warn!(message_id = id, "Reassembler expired, dropping");
             false
@@ -201,11 +659,86 @@ fn process_chunk(
         }
     });
 
-    // Return result with message ID for ACK purposes
-    Some(ProcessChunkResult {
-        message,
-        message_id: header.message_id,
-    })
+    reassembled
+}
+
+/// Process a received BLE chunk and return a complete message if one is done
+///
+/// This is the core receive logic shared by the async and sync code paths:
+/// chunk reassembly, then [`process_frame`] for a mux frame or [`deliver`]
+/// for a whole message.
+fn process_chunk(
+    link: &BleLink,
+    data: &[u8],
+    reassembly: &mut Reassembly,
+    encryption: Option<&Aes256Gcm>,
+) -> Option<Message> {
+    let reassembled = reassemble_chunk(link, data, reassembly)?;
+    if !reassembled.mux {
+        return deliver(link, reassembled.data, reassembled.message_id as u16, encryption);
+    }
+    let frame = match MuxFrame::decode(&reassembled.data) {
+        Ok(frame) => frame,
+        Err(e) => {
+            warn!("Failed to decode BLE frame: {}", e);
+            return None;
+        }
+    };
+    process_frame(link, frame, encryption)
+}
+
+/// Process a received frame and return a complete message if one is done
+///
+/// Frame demultiplexing, then [`deliver`] for a completed message.
+/// Window updates from the peer resume blocked streams.
+fn process_frame(link: &BleLink, frame: MuxFrame, encryption: Option<&Aes256Gcm>) -> Option<Message> {
+    let completed = link.mux.lock().unwrap().receive(frame);
+    // Send window updates and anything the peer's credit unblocked
+    if let Err(e) = link.pump() {
+        warn!(error = %e, "Failed to send BLE frames");
+    }
+    match completed {
+        Ok(Some(completed)) => deliver(link, Vec::from(completed.data), ack_id(completed.stream_id), encryption),
+        Ok(None) => None,
+        Err(e) => {
+            warn!("Invalid BLE frame: {}", e);
+            None
+        }
+    }
+}
+
+/// Decrypt and deserialize a complete message and ACK it with `message_id`
+fn deliver(link: &BleLink, mut data: Vec<u8>, message_id: u16, encryption: Option<&Aes256Gcm>) -> Option<Message> {
+    // Decrypt data in place if encryption is enabled
+    let plaintext: &[u8] = if let Some(cipher) = encryption {
+        debug!(message_id, "Decrypting reassembled message");
+        match cipher.decrypt_slice_in_place(&mut data) {
+            Ok(decrypted) => decrypted,
+            Err(e) => {
+                warn!("Failed to decrypt BLE message: {}", e);
+                return None;
+            }
+        }
+    } else {
+        &data
+    };
+
+    // Deserialize message
+    let msg = match Message::deserialize(plaintext) {
+        Ok(msg) => msg,
+        Err(e) => {
+            warn!("Failed to deserialize BLE message: {}", e);
+            return None;
+        }
+    };
+
+    // Send ACK for complete message
+    if let Err(e) = link.sender.send_ack(&link.device_id, message_id) {
+        warn!(message_id, error = %e, "Failed to send ACK");
+    } else {
+        debug!(message_id, "ACK sent for received message");
+    }
+    Some(msg)
 }
 
 /// BLE transport - bridges to platform BLE via FFI callbacks
@@ -214,23 +747,24 @@ fn process_chunk(
 /// Send operations call into platform code via `BleSender`.
 /// Receive operations are handled by platform calling `on_data_received`.
 pub struct BleTransport {
-    device_id: String,
-    /// Platform BLE sender
-    sender: Arc<dyn BleSender>,
+    /// Platform sender and multiplexer
+    link: Arc<BleLink>,
     /// Receive queue - platform callbacks push messages here
     recv_queue: Arc<Mutex<VecDeque<Message>>>,
     /// Notifier for new messages
     recv_notify: Arc<Notify>,
     /// Connection state
     connected: AtomicBool,
-    /// Message ID counter for chunking
-    message_id_counter: AtomicU16,
-    /// Reassemblers for incoming chunked messages
-    reassemblers: Arc<Mutex<HashMap<u16, Reassembler>>>,
+    /// Reassemblers for incoming chunked frames
+    reassembly: Arc<Mutex<Reassembly>>,
+    /// Frame decoder for the L2CAP byte stream
+    l2cap_decoder: std::sync::Mutex<FrameDecoder>,
     /// Pending ACK waiters - maps message_id to oneshot sender
-    pending_acks: Arc<Mutex<HashMap<u16, oneshot::Sender<()>>>>,
+    pending_acks: Arc<std::sync::Mutex<HashMap<u16, oneshot::Sender<()>>>>,
     /// Optional encryption cipher for end-to-end encryption
     encryption: Option<Aes256Gcm>,
+    /// Effective throughput of acknowledged messages
+    throughput: std::sync::Mutex<ThroughputMeter>,
 }
 
 impl BleTransport {
@@ -252,53 +786,44 @@ impl BleTransport {
         };
 
         Ok(Self {
-            device_id,
-            sender,
+            link: Arc::new(BleLink {
+                device_id,
+                sender,
+                mux: std::sync::Mutex::new(Multiplexer::new(MuxConfig::ble())),
+                window: std::sync::Mutex::new(SendWindow::new()),
+                in_flight: std::sync::Mutex::new(HashMap::new()),
+                message_id_counter: AtomicU32::new(0),
+                chunk_version: std::sync::Mutex::new(ChunkVersion::V1),
+                mux_framing: AtomicBool::new(false),
+                last_tick: std::sync::Mutex::new(Instant::now()),
+                last_progress: std::sync::Mutex::new(Instant::now()),
+                l2cap: AtomicBool::new(false),
+            }),
             recv_queue: Arc::new(Mutex::new(VecDeque::new())),
             recv_notify: Arc::new(Notify::new()),
             connected: AtomicBool::new(true),
-            message_id_counter: AtomicU16::new(0),
-            reassemblers: Arc::new(Mutex::new(HashMap::new())),
-            pending_acks: Arc::new(Mutex::new(HashMap::new())),
+            reassembly: Arc::new(Mutex::new(Reassembly::default())),
+            l2cap_decoder: std::sync::Mutex::new(FrameDecoder::new()),
+            pending_acks: Arc::new(std::sync::Mutex::new(HashMap::new())),
             encryption,
+            throughput: std::sync::Mutex::new(ThroughputMeter::new()),
         })
     }
 
-    /// Get the next message ID
-    fn next_message_id(&self) -> u16 {
-        self.message_id_counter.fetch_add(1, Ordering::SeqCst)
-    }
-
     /// Called by platform when BLE data is received
     ///
-    /// This method handles chunked data reassembly and queues
-    /// complete messages for the recv() method.
+    /// This method handles chunk reassembly and frame demultiplexing, and
+    /// queues complete messages for the recv() method.
     /// When a complete message is received, an ACK is sent back.
     ///
     /// # Arguments
     /// * `data` - Raw bytes received from BLE (a single chunk)
     pub async fn on_data_received(&self, data: &[u8]) {
-        let mut reassemblers = self.reassemblers.lock().await;
-        if let Some(result) = process_chunk(data, &mut reassemblers, self.encryption.as_ref()) {
-            if let Some(msg) = result.message {
-                // Send ACK for complete message
-                if let Err(e) = self.sender.send_ack(&self.device_id, result.message_id) {
-                    warn!(
-                        message_id = result.message_id,
-                        error = %e,
-                        "Failed to send ACK"
-                    );
-                } else {
-                    debug!(
-                        message_id = result.message_id,
-                        "ACK sent for received message"
-                    );
-                }
-
-                let mut queue = self.recv_queue.lock().await;
-                queue.push_back(msg);
-                self.recv_notify.notify_one();
-            }
+        let mut reassembly = self.reassembly.lock().await;
+        if let Some(msg) = process_chunk(&self.link, data, &mut reassembly, self.encryption.as_ref()) {
+            let mut queue = self.recv_queue.lock().await;
+            queue.push_back(msg);
+            self.recv_notify.notify_one();
         }
     }
 
@@ -312,58 +837,25 @@ impl BleTransport {
             let data = data.to_vec();
             let recv_queue = self.recv_queue.clone();
             let recv_notify = self.recv_notify.clone();
-            let reassemblers = self.reassemblers.clone();
-            let sender = self.sender.clone();
-            let device_id = self.device_id.clone();
+            let reassembly = self.reassembly.clone();
+            let link = self.link.clone();
             let encryption = self.encryption.clone();
 
             handle.spawn(async move {
-                let mut reassemblers = reassemblers.lock().await;
-                if let Some(result) = process_chunk(&data, &mut reassemblers, encryption.as_ref()) {
-                    if let Some(msg) = result.message {
-                        // Send ACK for complete message
-                        if let Err(e) = sender.send_ack(&device_id, result.message_id) {
-                            warn!(
-                                message_id = result.message_id,
-                                error = %e,
-                                "Failed to send ACK"
-                            );
-                        } else {
-                            debug!(
-                                message_id = result.message_id,
-                                "ACK sent for received message"
-                            );
-                        }
-
-                        let mut queue = recv_queue.lock().await;
-                        queue.push_back(msg);
-                        recv_notify.notify_one();
-                    }
+                let mut reassembly = reassembly.lock().await;
+                if let Some(msg) = process_chunk(&link, &data, &mut reassembly, encryption.as_ref()) {
+                    let mut queue = recv_queue.lock().await;
+                    queue.push_back(msg);
+                    recv_notify.notify_one();
                 }
             });
         } else {
             // No runtime available, use blocking lock
-            let mut reassemblers = self.reassemblers.blocking_lock();
-            if let Some(result) = process_chunk(data, &mut reassemblers, self.encryption.as_ref()) {
-                if let Some(msg) = result.message {
-                    // Send ACK for complete message
-                    if let Err(e) = self.sender.send_ack(&self.device_id, result.message_id) {
-                        warn!(
-                            message_id = result.message_id,
-                            error = %e,
-                            "Failed to send ACK (sync)"
-                        );
-                    } else {
-                        debug!(
-                            message_id = result.message_id,
-                            "ACK sent for received message (sync)"
-                        );
-                    }
-
-                    let mut queue = self.recv_queue.blocking_lock();
-                    queue.push_back(msg);
-                    self.recv_notify.notify_one();
-                }
+            let mut reassembly = self.reassembly.blocking_lock();
+            if let Some(msg) = process_chunk(&self.link, data, &mut reassembly, self.encryption.as_ref()) {
+                let mut queue = self.recv_queue.blocking_lock();
+                queue.push_back(msg);
+                self.recv_notify.notify_one();
             }
         }
     }
@@ -372,10 +864,139 @@ impl BleTransport {
     pub fn on_connection_state_changed(&self, connected: bool) {
         self.connected.store(connected, Ordering::SeqCst);
         if !connected {
+            self.on_l2cap_state_changed(false);
             self.recv_notify.notify_waiters();
         }
     }
 
+    /// Ask the platform to open an L2CAP channel to the peer
+    ///
+    /// Call this on the central after connecting. Fails if the peer does not
+    /// publish an L2CAP PSM or the platform lacks L2CAP support; the
+    /// transport then keeps using GATT.
+    pub fn open_l2cap(&self) -> Result<(), TransportError> {
+        self.link
+            .sender
+            .open_l2cap(&self.link.device_id)
+            .map_err(|e| TransportError::Ble(format!("L2CAP unavailable: {}", e)))
+    }
+
+    /// Whether frames are currently sent over L2CAP
+    pub fn is_l2cap_open(&self) -> bool {
+        self.link.l2cap.load(Ordering::SeqCst)
+    }
+
+    /// Switch to the v2 chunk header if the peer supports it
+    ///
+    /// Call this on the central after connecting. Peers that do not publish
+    /// a chunk version keep receiving v1 chunks.
+    pub fn negotiate_chunk_version(&self) -> ChunkVersion {
+        if self.link.sender.peer_chunk_version(&self.link.device_id) == ChunkVersion::V2 {
+            self.link.upgrade_chunk_version();
+        }
+        self.chunk_version()
+    }
+
+    /// Chunk header version used for sending
+    pub fn chunk_version(&self) -> ChunkVersion {
+        self.link.chunk_version()
+    }
+
+    /// Switch to multiplexed framing if the peer supports it
+    ///
+    /// Call this on the central after connecting. Mux frames travel in v2
+    /// chunks, so this also switches the chunk header. Peers that do not
+    /// publish a framing characteristic keep receiving whole messages.
+    pub fn negotiate_framing(&self) -> Framing {
+        if self.link.sender.peer_supports_mux(&self.link.device_id) {
+            self.link.upgrade_framing();
+        }
+        self.framing()
+    }
+
+    /// Message framing used for sending
+    pub fn framing(&self) -> Framing {
+        self.link.framing()
+    }
+
+    /// Current link parameters of the connection
+    ///
+    /// Falls back to defaults around the MTU if the platform does not
+    /// report connection interval, PHY and data length.
+    pub fn link_parameters(&self) -> LinkParameters {
+        self.link.link_parameters()
+    }
+
+    /// Effective throughput in bytes per second
+    ///
+    /// A moving average over acknowledged messages, from queueing to ACK.
+    /// `None` until the first message is acknowledged.
+    pub fn effective_throughput(&self) -> Option<u32> {
+        self.throughput.lock().unwrap().bytes_per_second()
+    }
+
+    /// Called by platform when the L2CAP channel to the peer opens or closes
+    ///
+    /// Opening switches new frames to the channel; closing falls back to
+    /// GATT and discards any partially received frame.
+    pub fn on_l2cap_state_changed(&self, open: bool) {
+        let was_open = self.link.l2cap.swap(open, Ordering::SeqCst);
+        if was_open == open {
+            return;
+        }
+        debug!(device_id = %self.link.device_id, open, "BLE L2CAP channel state changed");
+        if open {
+            if let Err(e) = self.link.pump() {
+                warn!(error = %e, "Failed to send BLE frames over L2CAP");
+            }
+        } else {
+            *self.l2cap_decoder.lock().unwrap() = FrameDecoder::new();
+        }
+    }
+
+    /// Called by platform when bytes arrive on the L2CAP channel
+    ///
+    /// The bytes are a segment of the frame stream; complete frames are
+    /// processed like reassembled GATT frames.
+    ///
+    /// # Arguments
+    /// * `data` - Raw bytes received from the L2CAP channel
+    pub async fn on_l2cap_data_received(&self, data: &[u8]) {
+        let frames = {
+            let mut decoder = self.l2cap_decoder.lock().unwrap();
+            decoder.read_buf().extend_from_slice(data);
+            let mut frames = Vec::new();
+            loop {
+                let decoded = decoder.peek_header().and_then(|header| match header {
+                    Some(header) => {
+                        self.link.mux.lock().unwrap().config().check_header(&header)?;
+                        decoder.decode()
+                    }
+                    None => Ok(None),
+                });
+                match decoded {
+                    Ok(Some(frame)) => frames.push(frame),
+                    Ok(None) => break,
+                    Err(e) => {
+                        // The stream cannot be resynchronized; drop what is buffered
+                        warn!(error = %e, "Invalid frame on L2CAP channel");
+                        *decoder = FrameDecoder::new();
+                        break;
+                    }
+                }
+            }
+            frames
+        };
+
+        for frame in frames {
+            if let Some(msg) = process_frame(&self.link, frame, self.encryption.as_ref()) {
+                let mut queue = self.recv_queue.lock().await;
+                queue.push_back(msg);
+                self.recv_notify.notify_one();
+            }
+        }
+    }
+
     /// Called by platform when an ACK is received for a sent message
     ///
     /// This method signals the waiting sender that their message was received.
@@ -383,7 +1004,12 @@ impl BleTransport {
     /// # Arguments
     /// * `message_id` - The message ID that was acknowledged
     pub async fn on_ack_received(&self, message_id: u16) {
-        let mut pending = self.pending_acks.lock().await;
+        self.on_ack_received_sync(message_id);
+    }
+
+    /// Called by platform when an ACK is received (sync version)
+    pub fn on_ack_received_sync(&self, message_id: u16) {
+        let mut pending = self.pending_acks.lock().unwrap();
         if let Some(sender) = pending.remove(&message_id) {
             let _ = sender.send(());
             debug!(message_id, "ACK received and waiter notified");
@@ -392,128 +1018,153 @@ impl BleTransport {
         }
     }
 
-    /// Called by platform when an ACK is received (sync version)
-    pub fn on_ack_received_sync(&self, message_id: u16) {
-        if let Ok(handle) = tokio::runtime::Handle::try_current() {
-            let pending_acks = self.pending_acks.clone();
-            handle.spawn(async move {
-                let mut pending = pending_acks.lock().await;
-                if let Some(sender) = pending.remove(&message_id) {
-                    let _ = sender.send(());
-                    debug!(message_id, "ACK received and waiter notified (sync)");
+    /// Called by platform with the raw value of a DATA_ACK notification
+    ///
+    /// A 2-byte value is a message ACK (see [`on_ack_received`](Self::on_ack_received));
+    /// anything longer is a chunk report that drives selective retransmission.
+    ///
+    /// # Arguments
+    /// * `data` - Raw bytes received on DATA_ACK
+    pub async fn on_ack_data_received(&self, data: &[u8]) {
+        self.on_ack_data_received_sync(data);
+    }
+
+    /// Called by platform with the raw value of a DATA_ACK notification (sync version)
+    pub fn on_ack_data_received_sync(&self, data: &[u8]) {
+        if data.len() < CHUNK_REPORT_HEADER_SIZE {
+            match <[u8; 2]>::try_from(data) {
+                Ok(bytes) => self.on_ack_received_sync(u16::from_le_bytes(bytes)),
+                Err(_) => warn!(data_len = data.len(), "Received ACK with invalid length"),
+            }
+            return;
+        }
+
+        match ChunkReport::decode(data) {
+            Ok(report) => {
+                if let Err(e) = self.link.on_report(&report) {
+                    warn!(error = %e, "Failed to send BLE chunks after report");
                 }
-            });
-        } else {
-            let mut pending = self.pending_acks.blocking_lock();
-            if let Some(sender) = pending.remove(&message_id) {
-                let _ = sender.send(());
-                debug!(message_id, "ACK received and waiter notified (blocking)");
             }
+            Err(e) => warn!(error = %e, "Invalid chunk report"),
         }
     }
 }
 
 #[async_trait]
 impl Transport for BleTransport {
-    #[instrument(skip(self, msg), fields(device_id = %self.device_id, msg_type = ?msg.msg_type))]
+    #[instrument(skip(self, msg), fields(device_id = %self.link.device_id, msg_type = ?msg.msg_type))]
     async fn send(&self, msg: &Message) -> Result<(), TransportError> {
+        let device_id = &self.link.device_id;
         if !self.connected.load(Ordering::SeqCst) {
             return Err(TransportError::ConnectionClosed);
         }
 
-        if !self.sender.is_ble_connected(&self.device_id) {
+        if !self.link.sender.is_ble_connected(device_id) {
             self.connected.store(false, Ordering::SeqCst);
             return Err(TransportError::ConnectionClosed);
         }
 
         // Subscribe to ACK notifications before sending
-        if let Err(e) = self.sender.subscribe_ack(&self.device_id) {
+        if let Err(e) = self.link.sender.subscribe_ack(device_id) {
             warn!(error = %e, "Failed to subscribe to ACK notifications");
             // Continue anyway - ACK might still work if already subscribed
         }
 
         // Serialize message
-        let data = msg.serialize()
+        let mut data = msg.serialize()
             .map_err(|e| TransportError::Serialization(e.to_string()))?;
 
-        // Encrypt data if encryption is enabled
-        let data = if let Some(ref cipher) = self.encryption {
-            debug!(device_id = %self.device_id, "Encrypting message before chunking");
-            cipher.encrypt(&data)
-                .map_err(|e| TransportError::Other(format!("Encryption failed: {}", e)))?
-        } else {
-            data
-        };
-
-        // Get MTU and chunk data
-        let mtu = self.sender.get_mtu(&self.device_id);
-        let mtu = if mtu == 0 { DEFAULT_BLE_MTU } else { mtu };
-        let message_id = self.next_message_id();
-
-        let chunks = Chunker::chunk(&data, message_id, mtu)
-            .map_err(|e| TransportError::Ble(e.to_string()))?;
-
-        debug!(
-            device_id = %self.device_id,
-            message_id,
-            chunks = chunks.len(),
-            data_len = data.len(),
-            "Sending BLE message"
-        );
-
-        // Create ACK waiter before sending
-        let (ack_tx, ack_rx) = oneshot::channel();
-        {
-            let mut pending = self.pending_acks.lock().await;
-            pending.insert(message_id, ack_tx);
+        // Encrypt data in place if encryption is enabled
+        if let Some(ref cipher) = self.encryption {
+            debug!(device_id = %device_id, "Encrypting message before framing");
+            cipher.encrypt_in_place(&mut data)
+                .map_err(|e| TransportError::Other(format!("Encryption failed: {}", e)))?;
         }
+        let data_len = data.len();
 
-        // Send each chunk
-        for (i, chunk) in chunks.iter().enumerate() {
-            if let Err(e) = self.sender.send_ble_data(&self.device_id, chunk) {
-                self.connected.store(false, Ordering::SeqCst);
-                // Clean up pending ACK waiter
-                let mut pending = self.pending_acks.lock().await;
-                pending.remove(&message_id);
-                return Err(TransportError::SendFailed(format!("BLE send failed at chunk {}: {}", i, e)));
+        // Queue the message and create its ACK waiter before any frame goes out
+        let (ack_tx, ack_rx) = oneshot::channel();
+        let framing = self.link.framing();
+        debug!(device_id = %device_id, data_len, ?framing, "Sending BLE message");
+        let (message_id, sent) = match framing {
+            Framing::Mux => {
+                let message_id = {
+                    let mut mux = self.link.mux.lock().unwrap();
+                    let message_id = ack_id(mux.queue(data, is_control(msg)));
+                    self.pending_acks.lock().unwrap().insert(message_id, ack_tx);
+                    message_id
+                };
+                (message_id, self.link.pump())
             }
+            Framing::LengthPrefixed => {
+                let message_id = self.link.message_id_counter.fetch_add(1, Ordering::SeqCst) as u16;
+                self.pending_acks.lock().unwrap().insert(message_id, ack_tx);
+                (message_id, self.link.send_whole(message_id, &data))
+            }
+        };
+
+        if let Err(e) = sent {
+            self.connected.store(false, Ordering::SeqCst);
+            // Clean up pending ACK waiter
+            self.pending_acks.lock().unwrap().remove(&message_id);
+            return Err(e);
         }
 
         debug!(
-            device_id = %self.device_id,
+            device_id = %device_id,
             message_id,
-            "BLE chunks sent, waiting for ACK"
+            "BLE frames sent, waiting for ACK"
         );
 
-        // Wait for ACK with timeout
+        // Wait for ACK, running the retransmission timer meanwhile. The timeout
+        // counts from the last chunk the peer acknowledged, so a long transfer
+        // on a lossy link is not cut off while it is still making progress.
         let ack_timeout = Duration::from_millis(DEFAULT_ACK_TIMEOUT_MS);
-        match tokio::time::timeout(ack_timeout, ack_rx).await {
-            Ok(Ok(())) => {
+        let started = Instant::now();
+        let mut ack_rx = ack_rx;
+        let mut timer = tokio::time::interval(Duration::from_millis(RETRANSMIT_INTERVAL_MS));
+        timer.tick().await;
+        let ack = loop {
+            tokio::select! {
+                result = &mut ack_rx => break Some(result),
+                _ = timer.tick() => {
+                    if let Err(e) = self.link.tick() {
+                        warn!(device_id = %device_id, error = %e, "BLE retransmission failed");
+                    }
+                    let last_progress = started.max(*self.link.last_progress.lock().unwrap());
+                    if last_progress.elapsed() >= ack_timeout {
+                        break None;
+                    }
+                }
+            }
+        };
+        match ack {
+            Some(Ok(())) => {
+                let mut throughput = self.throughput.lock().unwrap();
+                throughput.record(data_len, started.elapsed());
                 debug!(
-                    device_id = %self.device_id,
+                    device_id = %device_id,
                     message_id,
+                    bytes_per_second = throughput.bytes_per_second(),
                     "BLE message acknowledged"
                 );
                 Ok(())
             }
-            Ok(Err(_)) => {
+            Some(Err(_)) => {
                 // Channel was closed (sender dropped)
                 warn!(
-                    device_id = %self.device_id,
+                    device_id = %device_id,
                     message_id,
                     "ACK channel closed unexpectedly"
                 );
                 // Consider it a success since data was sent
                 Ok(())
             }
-            Err(_) => {
+            None => {
                 // Timeout - clean up and warn
-                {
-                    let mut pending = self.pending_acks.lock().await;
-                    pending.remove(&message_id);
-                }
+                self.pending_acks.lock().unwrap().remove(&message_id);
                 warn!(
-                    device_id = %self.device_id,
+                    device_id = %device_id,
                     message_id,
                     timeout_ms = DEFAULT_ACK_TIMEOUT_MS,
                     "ACK timeout - message may not have been received"
@@ -526,7 +1177,7 @@ impl Transport for BleTransport {
         }
     }
 
-    #[instrument(skip(self), fields(device_id = %self.device_id))]
+    #[instrument(skip(self), fields(device_id = %self.link.device_id))]
     async fn recv(&self) -> Result<Message, TransportError> {
         loop {
             // Check connection state
@@ -538,7 +1189,7 @@ impl Transport for BleTransport {
             {
                 let mut queue = self.recv_queue.lock().await;
                 if let Some(msg) = queue.pop_front() {
-                    debug!(device_id = %self.device_id, "BLE message received");
+                    debug!(device_id = %self.link.device_id, "BLE message received");
                     return Ok(msg);
                 }
             }
@@ -549,7 +1200,7 @@ impl Transport for BleTransport {
     }
 
     fn is_connected(&self) -> bool {
-        self.connected.load(Ordering::SeqCst) && self.sender.is_ble_connected(&self.device_id)
+        self.connected.load(Ordering::SeqCst) && self.link.sender.is_ble_connected(&self.link.device_id)
     }
 
     fn channel(&self) -> Channel {
@@ -557,13 +1208,13 @@ impl Transport for BleTransport {
     }
 
     fn peer_device_id(&self) -> &str {
-        &self.device_id
+        &self.link.device_id
     }
 
     async fn close(&self) -> Result<(), TransportError> {
         self.connected.store(false, Ordering::SeqCst);
         self.recv_notify.notify_waiters();
-        debug!(device_id = %self.device_id, "BLE transport closed");
+        debug!(device_id = %self.link.device_id, "BLE transport closed");
         Ok(())
     }
 }
@@ -574,6 +1225,13 @@ pub struct MockBleSender {
     connected: AtomicBool,
     mtu: usize,
     sent_data: std::sync::Mutex<Vec<Vec<u8>>>,
+    sent_reports: std::sync::Mutex<Vec<Vec<u8>>>,
+    l2cap: AtomicBool,
+    sent_l2cap: std::sync::Mutex<Vec<Vec<u8>>>,
+    link: std::sync::Mutex<Option<LinkParameters>>,
+    chunk_version: std::sync::Mutex<ChunkVersion>,
+    mux_framing: AtomicBool,
+    sent_acks: std::sync::Mutex<Vec<u16>>,
 }
 
 #[cfg(test)]
@@ -583,6 +1241,13 @@ impl MockBleSender {
             connected: AtomicBool::new(true),
             mtu: DEFAULT_BLE_MTU,
             sent_data: std::sync::Mutex::new(Vec::new()),
+            sent_reports: std::sync::Mutex::new(Vec::new()),
+            l2cap: AtomicBool::new(false),
+            sent_l2cap: std::sync::Mutex::new(Vec::new()),
+            link: std::sync::Mutex::new(None),
+            chunk_version: std::sync::Mutex::new(ChunkVersion::V1),
+            mux_framing: AtomicBool::new(false),
+            sent_acks: std::sync::Mutex::new(Vec::new()),
         }
     }
 
@@ -592,6 +1257,13 @@ impl MockBleSender {
             connected: AtomicBool::new(true),
             mtu,
             sent_data: std::sync::Mutex::new(Vec::new()),
+            sent_reports: std::sync::Mutex::new(Vec::new()),
+            l2cap: AtomicBool::new(false),
+            sent_l2cap: std::sync::Mutex::new(Vec::new()),
+            link: std::sync::Mutex::new(None),
+            chunk_version: std::sync::Mutex::new(ChunkVersion::V1),
+            mux_framing: AtomicBool::new(false),
+            sent_acks: std::sync::Mutex::new(Vec::new()),
         }
     }
 
@@ -602,6 +1274,43 @@ impl MockBleSender {
     pub fn get_sent_data(&self) -> Vec<Vec<u8>> {
         self.sent_data.lock().unwrap().clone()
     }
+
+    pub fn take_sent_data(&self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut *self.sent_data.lock().unwrap())
+    }
+
+    pub fn take_sent_reports(&self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut *self.sent_reports.lock().unwrap())
+    }
+
+    /// Accept (or start rejecting) L2CAP opens and writes
+    pub fn set_l2cap(&self, supported: bool) {
+        self.l2cap.store(supported, Ordering::SeqCst);
+    }
+
+    pub fn take_sent_l2cap(&self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut *self.sent_l2cap.lock().unwrap())
+    }
+
+    /// Report negotiated link parameters instead of the bare MTU
+    pub fn set_link_parameters(&self, link: LinkParameters) {
+        *self.link.lock().unwrap() = Some(link);
+    }
+
+    /// Publish a chunk version characteristic for the peer
+    pub fn set_peer_chunk_version(&self, version: ChunkVersion) {
+        *self.chunk_version.lock().unwrap() = version;
+    }
+
+    /// Publish a framing characteristic (and v2 chunks) for the peer
+    pub fn set_peer_mux(&self) {
+        self.set_peer_chunk_version(ChunkVersion::V2);
+        self.mux_framing.store(true, Ordering::SeqCst);
+    }
+
+    pub fn take_sent_acks(&self) -> Vec<u16> {
+        std::mem::take(&mut *self.sent_acks.lock().unwrap())
+    }
 }
 
 #[cfg(test)]
@@ -621,6 +1330,43 @@ impl BleSender for MockBleSender {
     fn get_mtu(&self, _device_id: &str) -> usize {
         self.mtu
     }
+
+    fn send_ack(&self, _device_id: &str, message_id: u16) -> Result<(), String> {
+        self.sent_acks.lock().unwrap().push(message_id);
+        Ok(())
+    }
+
+    fn send_chunk_report(&self, _device_id: &str, report: &[u8]) -> Result<(), String> {
+        self.sent_reports.lock().unwrap().push(report.to_vec());
+        Ok(())
+    }
+
+    fn open_l2cap(&self, _device_id: &str) -> Result<(), String> {
+        if !self.l2cap.load(Ordering::SeqCst) {
+            return Err("No PSM".to_string());
+        }
+        Ok(())
+    }
+
+    fn send_l2cap_data(&self, _device_id: &str, data: &[u8]) -> Result<(), String> {
+        if !self.l2cap.load(Ordering::SeqCst) {
+            return Err("Channel closed".to_string());
+        }
+        self.sent_l2cap.lock().unwrap().push(data.to_vec());
+        Ok(())
+    }
+
+    fn link_parameters(&self, _device_id: &str) -> Option<LinkParameters> {
+        *self.link.lock().unwrap()
+    }
+
+    fn peer_chunk_version(&self, _device_id: &str) -> ChunkVersion {
+        *self.chunk_version.lock().unwrap()
+    }
+
+    fn peer_supports_mux(&self, _device_id: &str) -> bool {
+        self.mux_framing.load(Ordering::SeqCst)
+    }
 }
 
 #[cfg(test)]
@@ -693,18 +1439,29 @@ mod tests {
         assert!(!transport.is_connected());
     }
 
+    /// Chunks for a message sent as a single control frame
+    fn control_chunks(msg: &Message, stream_id: u32, ble_message_id: u32) -> Vec<Vec<u8>> {
+        let frame = MuxFrame::control(stream_id, msg.serialize().unwrap());
+        Chunker::chunk_versioned(&frame.encode(), ble_message_id, DEFAULT_BLE_MTU, ChunkVersion::V2, ChunkFlags::MUX)
+            .unwrap()
+    }
+
+    /// A transport to a peer that supports multiplexed framing
+    fn mux_transport(device_id: &str, sender: &Arc<MockBleSender>) -> Arc<BleTransport> {
+        sender.set_peer_mux();
+        let transport = Arc::new(BleTransport::new(device_id.to_string(), sender.clone(), None).unwrap());
+        assert_eq!(transport.negotiate_framing(), Framing::Mux);
+        transport
+    }
+
     #[tokio::test]
     async fn test_ble_transport_recv_with_injected_data() {
         let sender = Arc::new(MockBleSender::new());
         let transport = Arc::new(BleTransport::new("device_1".to_string(), sender, None).unwrap());
 
-        // Create a message and chunk it
-        let msg = create_test_message("hello from BLE");
-        let serialized = bincode::serialize(&msg).unwrap();
-        let chunks = Chunker::chunk(&serialized, 1, DEFAULT_BLE_MTU).unwrap();
-
         // Inject chunks as if received from BLE
-        for chunk in chunks {
+        let msg = create_test_message("hello from BLE");
+        for chunk in control_chunks(&msg, 1, 1) {
             transport.on_data_received(&chunk).await;
         }
 
@@ -722,17 +1479,8 @@ mod tests {
         let msg1 = create_test_message("message 1");
         let msg2 = create_test_message("message 2");
 
-        let data1 = bincode::serialize(&msg1).unwrap();
-        let data2 = bincode::serialize(&msg2).unwrap();
-
-        let chunks1 = Chunker::chunk(&data1, 1, DEFAULT_BLE_MTU).unwrap();
-        let chunks2 = Chunker::chunk(&data2, 2, DEFAULT_BLE_MTU).unwrap();
-
         // Inject all chunks
-        for chunk in chunks1 {
-            transport.on_data_received(&chunk).await;
-        }
-        for chunk in chunks2 {
+        for chunk in control_chunks(&msg1, 1, 1).into_iter().chain(control_chunks(&msg2, 2, 2)) {
             transport.on_data_received(&chunk).await;
         }
 
@@ -743,4 +1491,305 @@ mod tests {
         assert_eq!(received1.payload, msg1.payload);
         assert_eq!(received2.payload, msg2.payload);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ble_lost_chunk_is_retransmitted_selectively() {
+        let sender_a = Arc::new(MockBleSender::new());
+        let sender_b = Arc::new(MockBleSender::new());
+        let a = mux_transport("device_b", &sender_a);
+        let b = Arc::new(BleTransport::new("device_a".to_string(), sender_b.clone(), None).unwrap());
+
+        let msg = create_test_message(&"y".repeat(100));
+        let a_send = a.clone();
+        let send_msg = msg.clone();
+        let send_task = tokio::spawn(async move { a_send.send(&send_msg).await });
+        tokio::task::yield_now().await;
+
+        let first = sender_a.take_sent_data();
+        assert!(first.len() > 4);
+        for (i, chunk) in first.iter().enumerate() {
+            if i != 2 {
+                b.on_data_received(chunk).await;
+            }
+        }
+
+        // B reports the gap and A resends only the missing chunk, ahead of
+        // the chunks the report let into the window
+        for report in sender_b.take_sent_reports() {
+            a.on_ack_data_received(&report).await;
+        }
+        let mut resent = sender_a.take_sent_data();
+        assert_eq!(resent[0], first[2]);
+        assert!(resent[1..].iter().all(|chunk| !first.contains(chunk)));
+
+        // Keep exchanging chunks and reports until A's window empties
+        while !resent.is_empty() {
+            for chunk in &resent {
+                b.on_data_received(chunk).await;
+            }
+            for report in sender_b.take_sent_reports() {
+                a.on_ack_data_received(&report).await;
+            }
+            resent = sender_a.take_sent_data();
+        }
+        let received = b.recv().await.unwrap();
+        assert_eq!(received.payload, msg.payload);
+        assert!(a.link.window.lock().unwrap().is_idle());
+        assert!(send_task.await.unwrap().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ble_control_message_not_blocked_by_bulk_transfer() {
+        let sender_a = Arc::new(MockBleSender::new());
+        let sender_b = Arc::new(MockBleSender::new());
+        let a = mux_transport("device_b", &sender_a);
+        let b = Arc::new(BleTransport::new("device_a".to_string(), sender_b.clone(), None).unwrap());
+
+        // A large clip only gets one window ahead of the receiver
+        let bulk = create_test_message(&"x".repeat(200 * 1024));
+        let a_bulk = a.clone();
+        let bulk_msg = bulk.clone();
+        let bulk_task = tokio::spawn(async move { a_bulk.send(&bulk_msg).await });
+        tokio::task::yield_now().await;
+
+        // A heartbeat sent now goes out immediately instead of waiting for the clip
+        let a_ctrl = a.clone();
+        let ctrl_task = tokio::spawn(async move {
+            a_ctrl.send(&Message::heartbeat("device_a".to_string())).await
+        });
+        tokio::task::yield_now().await;
+
+        let first_batch = sender_a.take_sent_data();
+        for chunk in &first_batch {
+            b.on_data_received(chunk).await;
+        }
+        let first = b.recv().await.unwrap();
+        assert_eq!(first.msg_type, nearclip_sync::MessageType::Heartbeat);
+
+        // Relay chunk reports and window updates back and forth until the clip arrives
+        let mut received = None;
+        for _ in 0..1000 {
+            for report in sender_b.take_sent_reports() {
+                a.on_ack_data_received(&report).await;
+            }
+            for report in sender_a.take_sent_reports() {
+                b.on_ack_data_received(&report).await;
+            }
+            for chunk in sender_b.take_sent_data() {
+                a.on_data_received(&chunk).await;
+            }
+            for chunk in sender_a.take_sent_data() {
+                b.on_data_received(&chunk).await;
+            }
+            if let Ok(Ok(msg)) = tokio::time::timeout(Duration::from_millis(1), b.recv()).await {
+                received = Some(msg);
+                break;
+            }
+        }
+        assert_eq!(received.expect("clip should arrive").payload, bulk.payload);
+
+        assert!(bulk_task.await.unwrap().is_ok());
+        assert!(ctrl_task.await.unwrap().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ble_frames_use_l2cap_when_open() {
+        let sender_a = Arc::new(MockBleSender::new());
+        let sender_b = Arc::new(MockBleSender::new());
+        sender_a.set_l2cap(true);
+        let a = mux_transport("device_b", &sender_a);
+        let b = Arc::new(BleTransport::new("device_a".to_string(), sender_b.clone(), None).unwrap());
+
+        a.open_l2cap().unwrap();
+        assert!(!a.is_l2cap_open());
+        a.on_l2cap_state_changed(true);
+        b.on_l2cap_state_changed(true);
+
+        let msg = create_test_message(&"z".repeat(10 * 1024));
+        let a_send = a.clone();
+        let send_msg = msg.clone();
+        let send_task = tokio::spawn(async move { a_send.send(&send_msg).await });
+        tokio::task::yield_now().await;
+
+        // Whole frames, nothing chunked over GATT
+        assert!(sender_a.take_sent_data().is_empty());
+        let stream: Vec<u8> = sender_a.take_sent_l2cap().concat();
+        assert!(stream.len() > msg.payload.len());
+
+        // The receiver decodes frames across arbitrary segment boundaries
+        for segment in stream.chunks(100) {
+            b.on_l2cap_data_received(segment).await;
+        }
+        assert_eq!(b.recv().await.unwrap().payload, msg.payload);
+        assert!(send_task.await.unwrap().is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ble_l2cap_failure_falls_back_to_gatt() {
+        let sender = Arc::new(MockBleSender::new());
+        let transport = mux_transport("device_1", &sender);
+
+        // The peer publishes no PSM
+        assert!(transport.open_l2cap().is_err());
+
+        // A channel that fails on write is abandoned and the frame goes over GATT
+        transport.on_l2cap_state_changed(true);
+        let msg = create_test_message("hello");
+        let t = transport.clone();
+        let send_msg = msg.clone();
+        let send_task = tokio::spawn(async move { t.send(&send_msg).await });
+        tokio::task::yield_now().await;
+
+        assert!(!transport.is_l2cap_open());
+        assert!(sender.take_sent_l2cap().is_empty());
+        assert!(!sender.take_sent_data().is_empty());
+        send_task.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ble_chunks_sized_from_link_parameters() {
+        let sender = Arc::new(MockBleSender::with_mtu(247));
+        let transport = Arc::new(BleTransport::new("device_1".to_string(), sender.clone(), None).unwrap());
+        let msg = create_test_message(&"x".repeat(2000));
+
+        // Without DLE a 247-byte MTU is trimmed to whole 27-byte packets
+        sender.set_link_parameters(LinkParameters::new().with_mtu(247));
+        let t = transport.clone();
+        let m = msg.clone();
+        tokio::spawn(async move { t.send(&m).await });
+        tokio::task::yield_now().await;
+        let longest = sender.take_sent_data().iter().map(Vec::len).max().unwrap();
+        assert_eq!(longest, 239 - ATT_HEADER_SIZE);
+
+        sender.set_link_parameters(
+            LinkParameters::new().with_mtu(247).with_data_length(nearclip_ble::MAX_DATA_LENGTH),
+        );
+        assert_eq!(transport.link_parameters().chunk_mtu(), 247);
+        let t = transport.clone();
+        tokio::spawn(async move { t.send(&msg).await });
+        tokio::task::yield_now().await;
+        let longest = sender.take_sent_data().iter().map(Vec::len).max().unwrap();
+        assert_eq!(longest, 247 - ATT_HEADER_SIZE);
+        assert_eq!(transport.effective_throughput(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ble_chunk_v2_negotiated_and_corrupt_chunk_retransmitted() {
+        let sender_a = Arc::new(MockBleSender::new());
+        let sender_b = Arc::new(MockBleSender::new());
+        sender_a.set_peer_chunk_version(ChunkVersion::V2);
+        let a = Arc::new(BleTransport::new("device_b".to_string(), sender_a.clone(), None).unwrap());
+        let b = Arc::new(BleTransport::new("device_a".to_string(), sender_b.clone(), None).unwrap());
+
+        assert_eq!(b.negotiate_chunk_version(), ChunkVersion::V1);
+        assert_eq!(a.negotiate_chunk_version(), ChunkVersion::V2);
+        sender_a.set_peer_mux();
+        assert_eq!(a.negotiate_framing(), Framing::Mux);
+
+        // Message IDs no longer wrap at 16 bits
+        a.link.message_id_counter.store(u16::MAX as u32 + 1, Ordering::SeqCst);
+        let msg = create_test_message(&"v".repeat(100));
+        let a_send = a.clone();
+        let send_msg = msg.clone();
+        let send_task = tokio::spawn(async move { a_send.send(&send_msg).await });
+        tokio::task::yield_now().await;
+
+        let first = sender_a.take_sent_data();
+        let header = ChunkHeader::from_bytes(&first[0]).unwrap();
+        assert_eq!(header.version, ChunkVersion::V2);
+        assert_eq!(header.message_id, u16::MAX as u32 + 1);
+
+        // A bit flip in chunk 2 fails its CRC and is reported missing
+        for (i, chunk) in first.iter().enumerate() {
+            let mut chunk = chunk.clone();
+            if i == 2 {
+                *chunk.last_mut().unwrap() ^= 0x01;
+            }
+            b.on_data_received(&chunk).await;
+        }
+        assert_eq!(b.chunk_version(), ChunkVersion::V2);
+        assert_eq!(b.framing(), Framing::Mux);
+        let reports = sender_b.take_sent_reports();
+        assert!(reports
+            .iter()
+            .all(|r| ChunkReport::decode(r).unwrap().version == ChunkVersion::V2));
+        for report in reports {
+            a.on_ack_data_received(&report).await;
+        }
+        // The damaged chunk is resent first, then the window moves on
+        let mut next = sender_a.take_sent_data();
+        assert_eq!(next[0], first[2]);
+        while !next.is_empty() {
+            for chunk in &next {
+                b.on_data_received(chunk).await;
+            }
+            for report in sender_b.take_sent_reports() {
+                a.on_ack_data_received(&report).await;
+            }
+            next = sender_a.take_sent_data();
+        }
+
+        assert_eq!(b.recv().await.unwrap().payload, msg.payload);
+        assert!(a.link.window.lock().unwrap().is_idle());
+        send_task.abort();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ble_old_peer_gets_whole_messages_in_v1_chunks() {
+        let sender = Arc::new(MockBleSender::new());
+        let transport = Arc::new(BleTransport::new("device_1".to_string(), sender.clone(), None).unwrap());
+        assert_eq!(transport.negotiate_chunk_version(), ChunkVersion::V1);
+        assert_eq!(transport.negotiate_framing(), Framing::LengthPrefixed);
+
+        // The whole message is chunked under a message ID that wraps at 16 bits
+        transport.link.message_id_counter.store(u16::MAX as u32 + 5, Ordering::SeqCst);
+        let msg = create_test_message("to old peer");
+        let t = transport.clone();
+        let send_msg = msg.clone();
+        let send_task = tokio::spawn(async move { t.send(&send_msg).await });
+        tokio::task::yield_now().await;
+
+        let chunks = sender.take_sent_data();
+        let header = ChunkHeader::from_bytes(&chunks[0]).unwrap();
+        assert_eq!(header.version, ChunkVersion::V1);
+        assert_eq!(header.message_id, 4);
+        let mut reassembler = Reassembler::new(4, header.total_chunks, DEFAULT_REASSEMBLE_TIMEOUT);
+        for chunk in &chunks {
+            let header = ChunkHeader::from_bytes(chunk).unwrap();
+            reassembler.add_chunk(header, chunk[header.header_size()..].to_vec()).unwrap();
+        }
+        assert_eq!(Message::deserialize(&reassembler.assemble().unwrap()).unwrap().payload, msg.payload);
+
+        // The peer ACKs the chunk message ID
+        transport.on_ack_data_received(&4u16.to_le_bytes()).await;
+        assert!(send_task.await.unwrap().is_ok());
+
+        // Whole messages from the peer are accepted and ACKed without chunk reports
+        let msg = create_test_message("from old peer");
+        for chunk in Chunker::chunk(&msg.serialize().unwrap(), 0x0102, DEFAULT_BLE_MTU).unwrap() {
+            transport.on_data_received(&chunk).await;
+        }
+        assert_eq!(transport.recv().await.unwrap().payload, msg.payload);
+        assert_eq!(sender.take_sent_acks(), vec![0x0102]);
+        assert!(sender.take_sent_reports().is_empty());
+        assert_eq!(transport.chunk_version(), ChunkVersion::V1);
+        assert_eq!(transport.framing(), Framing::LengthPrefixed);
+    }
+
+    #[tokio::test]
+    async fn test_ble_mux_chunk_switches_framing() {
+        let sender = Arc::new(MockBleSender::new());
+        let transport = Arc::new(BleTransport::new("device_1".to_string(), sender.clone(), None).unwrap());
+        assert_eq!(transport.framing(), Framing::LengthPrefixed);
+
+        let msg = create_test_message("from new peer");
+        for chunk in control_chunks(&msg, 7, 1) {
+            transport.on_data_received(&chunk).await;
+        }
+        assert_eq!(transport.recv().await.unwrap().payload, msg.payload);
+        assert_eq!(sender.take_sent_acks(), vec![7]);
+        assert_eq!(transport.framing(), Framing::Mux);
+        assert_eq!(transport.chunk_version(), ChunkVersion::V2);
+    }
+
 }
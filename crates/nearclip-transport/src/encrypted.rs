@@ -97,29 +97,32 @@ impl EncryptedTransport {
 
     /// Encrypt a message
     ///
-    /// Serializes the message and encrypts the bytes.
+    /// Serializes the message and encrypts the bytes in place.
     #[instrument(skip(self, msg), fields(msg_type = ?msg.msg_type, device_id = %msg.device_id))]
     fn encrypt_message(&self, msg: &Message) -> Result<Vec<u8>, TransportError> {
         // Serialize the message
-        let serialized = msg.serialize()
+        let mut data = msg.serialize()
             .map_err(|e| TransportError::Serialization(e.to_string()))?;
 
-        // Encrypt the serialized bytes
-        self.cipher.encrypt(&serialized)
-            .map_err(|e| TransportError::Other(format!("Encryption failed: {}", e)))
+        // Encrypt the serialized bytes without a second buffer
+        self.cipher.encrypt_in_place(&mut data)
+            .map_err(|e| TransportError::Other(format!("Encryption failed: {}", e)))?;
+        Ok(data)
     }
 
     /// Decrypt a message
     ///
-    /// Decrypts the bytes and deserializes the message.
+    /// Decrypts the bytes in place and deserializes the message straight
+    /// from the plaintext, so no decrypted copy is allocated. `data` holds
+    /// garbage afterwards.
     #[instrument(skip(self, data), fields(data_len = data.len()))]
-    fn decrypt_message(&self, data: &[u8]) -> Result<Message, TransportError> {
+    fn decrypt_message(&self, data: &mut [u8]) -> Result<Message, TransportError> {
         // Decrypt the bytes
-        let decrypted = self.cipher.decrypt(data)
+        let decrypted = self.cipher.decrypt_slice_in_place(data)
             .map_err(|e| TransportError::Other(format!("Decryption failed: {}", e)))?;
 
         // Deserialize the message
-        Message::deserialize(&decrypted)
+        Message::deserialize(decrypted)
             .map_err(|e| TransportError::Deserialization(e.to_string()))
     }
 }
@@ -159,10 +162,10 @@ impl Transport for EncryptedTransport {
         debug!("Receiving encrypted message");
 
         // Receive the wrapper message
-        let wrapper_msg = self.inner.recv().await?;
+        let mut wrapper_msg = self.inner.recv().await?;
 
-        // Extract and decrypt the payload
-        let decrypted = self.decrypt_message(&wrapper_msg.payload)?;
+        // Decrypt the payload where it lies
+        let decrypted = self.decrypt_message(&mut wrapper_msg.payload)?;
 
         debug!(
             "Decrypted message: type={:?}, device={}",
@@ -191,6 +194,10 @@ impl Transport for EncryptedTransport {
     async fn close(&self) -> Result<(), TransportError> {
         self.inner.close().await
     }
+
+    fn mark_authenticated(&self) {
+        self.inner.mark_authenticated()
+    }
 }
 
 /// Convert cipher errors to transport errors
@@ -272,10 +279,10 @@ mod tests {
         let original = Message::clipboard_sync(b"test content", "device-1".to_string());
 
         // Encrypt
-        let encrypted_data = encrypted.encrypt_message(&original).unwrap();
+        let mut encrypted_data = encrypted.encrypt_message(&original).unwrap();
 
         // Decrypt
-        let decrypted = encrypted.decrypt_message(&encrypted_data).unwrap();
+        let decrypted = encrypted.decrypt_message(&mut encrypted_data).unwrap();
 
         assert_eq!(decrypted.msg_type, original.msg_type);
         assert_eq!(decrypted.payload, original.payload);
@@ -58,6 +58,10 @@ pub enum TransportError {
     #[error("invalid state: {0}")]
     InvalidState(String),
 
+    /// Peer violated the framing protocol (bad frame, flow control)
+    #[error("protocol error: {0}")]
+    Protocol(String),
+
     /// Other error
     #[error("{0}")]
     Other(String),
@@ -106,6 +110,7 @@ impl Clone for TransportError {
             TransportError::Network(s) => TransportError::Network(s.clone()),
             TransportError::Ble(s) => TransportError::Ble(s.clone()),
             TransportError::InvalidState(s) => TransportError::InvalidState(s.clone()),
+            TransportError::Protocol(s) => TransportError::Protocol(s.clone()),
             TransportError::Other(s) => TransportError::Other(s.clone()),
         }
     }
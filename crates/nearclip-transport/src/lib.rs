@@ -1,7 +1,7 @@
 //! Unified Transport Layer for NearClip
 //!
 //! This crate provides a unified abstraction over different transport mechanisms
-//! (WiFi/TCP and BLE), allowing upper layers to send messages without caring
+//! (WiFi/TCP, QUIC, BLE and relay), allowing upper layers to send messages without caring
 //! about the underlying transport.
 //!
 //! # Architecture
@@ -43,7 +43,10 @@
 mod encrypted;
 mod error;
 mod traits;
+mod mux;
 mod wifi;
+mod quic;
+mod relay;
 mod ble;
 mod mock;
 mod manager;
@@ -51,10 +54,16 @@ mod manager;
 pub use encrypted::EncryptedTransport;
 pub use error::TransportError;
 pub use traits::{Transport, TransportConnector, TransportListener, TransportCallback};
+pub use mux::{
+    FrameDecoder, FrameHeader, FrameType, Framing, Multiplexer, MuxConfig, MuxFrame, MuxMessage,
+    DEFAULT_MAX_FRAME_PAYLOAD, DEFAULT_READ_BUFFER_SIZE, DEFAULT_STREAM_WINDOW, MUX_ALPN, MUX_HEADER_SIZE,
+};
 pub use wifi::{WifiTransport, WifiTransportConnector, WifiTransportListener};
-pub use ble::{BleTransport, BleSender};
+pub use quic::{QuicTransport, QuicTransportConnector, QuicTransportListener, QUIC_BULK_THRESHOLD};
+pub use relay::{RelayTransport, RelayTransportConnector, RelayTransportListener, PeerSecretLookup};
+pub use ble::{BleTransport, BleSender, BleHardwareSender};
 pub use mock::{MockTransport, MockConfig, create_mock_pair};
-pub use manager::{TransportManager, TransportManagerConfig};
+pub use manager::{TransportManager, TransportManagerConfig, DEFAULT_MULTIPATH_PAYLOAD_LIMIT};
 
 // Re-export Channel from nearclip-sync for convenience
-pub use nearclip_sync::Channel;
+pub use nearclip_sync::{Channel, ChannelPreference};
@@ -1,14 +1,22 @@
 //! Transport manager - manages connections and channel selection
 
-use nearclip_sync::{Channel, ChannelInfo, ChannelSelector, ChannelStatus, Message, PriorityChannelSelector};
+use nearclip_sync::{
+    Channel, ChannelInfo, ChannelMonitor, ChannelPreference, ChannelSelector, ChannelStatus,
+    Message, PriorityChannelSelector,
+};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 
 use crate::error::TransportError;
 use crate::traits::{Transport, TransportCallback, TransportConnector, TransportListener};
 
+/// Default maximum payload size (bytes) sent on all channels at once
+/// for devices with [`ChannelPreference::Hybrid`]
+pub const DEFAULT_MULTIPATH_PAYLOAD_LIMIT: usize = 4 * 1024;
+
 /// Transport manager configuration
 #[derive(Debug, Clone)]
 pub struct TransportManagerConfig {
@@ -16,6 +24,9 @@ pub struct TransportManagerConfig {
     pub auto_select_channel: bool,
     /// Whether to attempt failover on send failure
     pub failover_on_error: bool,
+    /// Largest payload that is raced over every channel for hybrid devices.
+    /// Larger messages use the single best channel.
+    pub multipath_payload_limit: usize,
 }
 
 impl Default for TransportManagerConfig {
@@ -23,6 +34,7 @@ impl Default for TransportManagerConfig {
         Self {
             auto_select_channel: true,
             failover_on_error: true,
+            multipath_payload_limit: DEFAULT_MULTIPATH_PAYLOAD_LIMIT,
         }
     }
 }
@@ -39,6 +51,9 @@ pub struct TransportManager {
     /// Channel selector for choosing the best transport
     channel_selector: Box<dyn ChannelSelector>,
 
+    /// Per-device channel preferences (override the global selector)
+    device_preferences: RwLock<HashMap<String, ChannelPreference>>,
+
     /// Transport connectors (for outbound connections)
     connectors: RwLock<Vec<Arc<dyn TransportConnector>>>,
 
@@ -48,6 +63,9 @@ pub struct TransportManager {
     /// Callback for transport events
     callback: Option<Arc<dyn TransportCallback>>,
 
+    /// Receives send results and throughput samples for quality-aware selection
+    channel_monitor: Option<Arc<ChannelMonitor>>,
+
     /// Configuration
     config: TransportManagerConfig,
 }
@@ -63,9 +81,11 @@ impl TransportManager {
         Self {
             connections: RwLock::new(HashMap::new()),
             channel_selector: selector,
+            device_preferences: RwLock::new(HashMap::new()),
             connectors: RwLock::new(Vec::new()),
             listeners: RwLock::new(Vec::new()),
             callback: None,
+            channel_monitor: None,
             config: TransportManagerConfig::default(),
         }
     }
@@ -75,9 +95,11 @@ impl TransportManager {
         Self {
             connections: RwLock::new(HashMap::new()),
             channel_selector: Box::new(PriorityChannelSelector),
+            device_preferences: RwLock::new(HashMap::new()),
             connectors: RwLock::new(Vec::new()),
             listeners: RwLock::new(Vec::new()),
             callback: None,
+            channel_monitor: None,
             config,
         }
     }
@@ -87,6 +109,21 @@ impl TransportManager {
         self.callback = Some(callback);
     }
 
+    /// Set the monitor that records per-channel send metrics
+    ///
+    /// Every send attempt reports its outcome, and successful sends their
+    /// throughput, both per device and to the monitor's aggregate metrics,
+    /// so a quality-aware selector sharing the same monitor sees live data
+    /// for each device it selects for.
+    pub fn set_channel_monitor(&mut self, monitor: Arc<ChannelMonitor>) {
+        self.channel_monitor = Some(monitor);
+    }
+
+    /// Get the channel metrics monitor, if one is set
+    pub fn channel_monitor(&self) -> Option<Arc<ChannelMonitor>> {
+        self.channel_monitor.clone()
+    }
+
     /// Add a transport connector
     pub async fn add_connector(&self, connector: Arc<dyn TransportConnector>) {
         self.connectors.write().await.push(connector);
@@ -158,10 +195,34 @@ impl TransportManager {
         }
     }
 
+    /// Set the channel preference for a device
+    ///
+    /// `ChannelPreference::Auto` removes any override so the global
+    /// channel selector is used again.
+    pub async fn set_device_preference(&self, device_id: &str, preference: ChannelPreference) {
+        let mut preferences = self.device_preferences.write().await;
+        if preference == ChannelPreference::Auto {
+            preferences.remove(device_id);
+        } else {
+            preferences.insert(device_id.to_string(), preference);
+        }
+        debug!("Channel preference for device {} set to {}", device_id, preference);
+    }
+
+    /// Get the channel preference for a device
+    pub async fn device_preference(&self, device_id: &str) -> ChannelPreference {
+        self.device_preferences.read().await
+            .get(device_id)
+            .copied()
+            .unwrap_or_default()
+    }
+
     /// Get the best transport for a device
     ///
-    /// Uses the channel selector to choose the best available transport.
+    /// Uses the device's channel preference if one is set, otherwise the
+    /// global channel selector, to choose the best available transport.
     pub async fn get_best_transport(&self, device_id: &str) -> Result<Arc<dyn Transport>, TransportError> {
+        let preference = self.device_preference(device_id).await;
         let connections = self.connections.read().await;
         let transports = connections.get(device_id)
             .ok_or_else(|| TransportError::NotConnected(device_id.to_string()))?;
@@ -183,7 +244,10 @@ impl TransportManager {
             .collect();
 
         // Select best channel
-        let best_channel = self.channel_selector.select(&channel_infos)
+        let best_channel = match preference {
+            ChannelPreference::Auto => self.channel_selector.select_for_device(device_id, &channel_infos),
+            preference => preference.select(&channel_infos),
+        }
             .ok_or_else(|| TransportError::NoAvailableChannel(device_id.to_string()))?;
 
         // Find the transport for that channel
@@ -202,18 +266,33 @@ impl TransportManager {
     }
 
     /// Send a message to a device (auto-selects best channel)
+    ///
+    /// For devices with [`ChannelPreference::Hybrid`], payloads up to
+    /// `multipath_payload_limit` are sent on every connected channel at
+    /// once; the receiver drops the duplicates by message ID.
     pub async fn send_to_device(&self, device_id: &str, msg: &Message) -> Result<(), TransportError> {
+        let preference = self.device_preference(device_id).await;
+        if preference.is_multipath() && msg.payload.len() <= self.config.multipath_payload_limit {
+            let transports: Vec<_> = self.get_transports(device_id).await
+                .into_iter()
+                .filter(|t| t.is_connected())
+                .collect();
+            if transports.len() > 1 {
+                return Self::send_multipath(device_id, transports, msg, self.channel_monitor.clone()).await;
+            }
+        }
+
         let transport = self.get_best_transport(device_id).await?;
-        let result = transport.send(msg).await;
+        let result = self.send_recorded(device_id, &transport, msg).await;
 
         // Handle failover if enabled
         if result.is_err() && self.config.failover_on_error {
-            // Try other channels
+            // Try other channels allowed by the device preference
             let transports = self.get_transports(device_id).await;
             for t in transports {
-                if t.channel() != transport.channel() && t.is_connected() {
+                if t.channel() != transport.channel() && t.is_connected() && preference.allows(t.channel()) {
                     debug!("Attempting failover to {} for device {}", t.channel(), device_id);
-                    if let Ok(()) = t.send(msg).await {
+                    if let Ok(()) = self.send_recorded(device_id, &t, msg).await {
                         return Ok(());
                     }
                 }
@@ -223,6 +302,89 @@ impl TransportManager {
         result
     }
 
+    /// Send on a single transport, reporting the outcome to the channel monitor
+    async fn send_recorded(
+        &self,
+        device_id: &str,
+        transport: &Arc<dyn Transport>,
+        msg: &Message,
+    ) -> Result<(), TransportError> {
+        let started = Instant::now();
+        let result = transport.send(msg).await;
+        Self::record_send(self.channel_monitor.as_deref(), device_id, transport.channel(), msg, started, &result);
+        result
+    }
+
+    /// Report a send outcome (and throughput on success) to the monitor
+    fn record_send(
+        monitor: Option<&ChannelMonitor>,
+        device_id: &str,
+        channel: Channel,
+        msg: &Message,
+        started: Instant,
+        result: &Result<(), TransportError>,
+    ) {
+        if let Some(monitor) = monitor {
+            monitor.record_send_result(channel, result.is_ok());
+            monitor.record_device_send_result(device_id, channel, result.is_ok());
+            if result.is_ok() {
+                let elapsed = started.elapsed();
+                monitor.record_throughput(channel, msg.payload.len(), elapsed);
+                monitor.record_device_throughput(device_id, channel, msg.payload.len(), elapsed);
+            }
+        }
+    }
+
+    /// Send the same message on several transports concurrently
+    ///
+    /// Succeeds as soon as one channel delivers; fails only if all fail.
+    async fn send_multipath(
+        device_id: &str,
+        transports: Vec<Arc<dyn Transport>>,
+        msg: &Message,
+        monitor: Option<Arc<ChannelMonitor>>,
+    ) -> Result<(), TransportError> {
+        debug!(
+            "Sending message {} to device {} on {} channels",
+            msg.message_id, device_id, transports.len()
+        );
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for transport in transports {
+            let msg = msg.clone();
+            let monitor = monitor.clone();
+            let device_id = device_id.to_string();
+            tasks.spawn(async move {
+                let channel = transport.channel();
+                let started = Instant::now();
+                let result = transport.send(&msg).await;
+                Self::record_send(monitor.as_deref(), &device_id, channel, &msg, started, &result);
+                (channel, result)
+            });
+        }
+
+        let mut last_error = None;
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((channel, Ok(()))) => {
+                    debug!("Multipath send to device {} delivered via {}", device_id, channel);
+                    // Let the slower channels finish in the background
+                    tasks.detach_all();
+                    return Ok(());
+                }
+                Ok((channel, Err(e))) => {
+                    debug!("Multipath send to device {} failed via {}: {}", device_id, channel, e);
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    last_error = Some(TransportError::Other(format!("Send task failed: {}", e)));
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| TransportError::NoAvailableChannel(device_id.to_string())))
+    }
+
     /// Broadcast a message to all connected devices
     ///
     /// Returns a list of (device_id, result) pairs.
@@ -483,4 +645,240 @@ mod tests {
 
         assert_eq!(manager.connected_count().await, 0);
     }
+
+    fn add_wifi_and_ble(device_id: &str) -> (Arc<MockTransport>, Arc<MockTransport>) {
+        let wifi = Arc::new(MockTransport::new(
+            device_id,
+            MockConfig::new().with_channel(Channel::Wifi)
+        ));
+        let ble = Arc::new(MockTransport::new(
+            device_id,
+            MockConfig::new().with_channel(Channel::Ble)
+        ));
+        (wifi, ble)
+    }
+
+    #[tokio::test]
+    async fn test_device_preference_ble_only() {
+        let manager = TransportManager::new();
+        let (wifi, ble) = add_wifi_and_ble("device_1");
+        manager.add_transport("device_1", wifi.clone()).await;
+        manager.add_transport("device_1", ble.clone()).await;
+
+        manager.set_device_preference("device_1", ChannelPreference::BleOnly).await;
+        assert_eq!(manager.device_preference("device_1").await, ChannelPreference::BleOnly);
+
+        let best = manager.get_best_transport("device_1").await.unwrap();
+        assert_eq!(best.channel(), Channel::Ble);
+
+        manager.send_to_device("device_1", &create_test_message("hi")).await.unwrap();
+        assert_eq!(ble.get_sent_messages().await.len(), 1);
+        assert!(wifi.get_sent_messages().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_device_preference_auto_resets() {
+        let manager = TransportManager::new();
+        manager.set_device_preference("device_1", ChannelPreference::WifiOnly).await;
+        manager.set_device_preference("device_1", ChannelPreference::Auto).await;
+        assert_eq!(manager.device_preference("device_1").await, ChannelPreference::Auto);
+    }
+
+    #[tokio::test]
+    async fn test_wifi_only_does_not_fail_over_to_ble() {
+        let manager = TransportManager::new();
+        let wifi = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new()
+                .with_channel(Channel::Wifi)
+                .with_error(TransportError::SendFailed("down".to_string()))
+        ));
+        let ble = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new().with_channel(Channel::Ble)
+        ));
+        manager.add_transport("device_1", wifi).await;
+        manager.add_transport("device_1", ble.clone()).await;
+        manager.set_device_preference("device_1", ChannelPreference::WifiOnly).await;
+
+        let result = manager.send_to_device("device_1", &create_test_message("hi")).await;
+        assert!(result.is_err());
+        assert!(ble.get_sent_messages().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_sends_small_payload_on_all_channels() {
+        let manager = TransportManager::new();
+        let (wifi, ble) = add_wifi_and_ble("device_1");
+        manager.add_transport("device_1", wifi.clone()).await;
+        manager.add_transport("device_1", ble.clone()).await;
+        manager.set_device_preference("device_1", ChannelPreference::Hybrid).await;
+
+        let msg = create_test_message("small");
+        manager.send_to_device("device_1", &msg).await.unwrap();
+
+        // The slower channel finishes in the background
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let wifi_sent = wifi.get_sent_messages().await;
+        let ble_sent = ble.get_sent_messages().await;
+        assert_eq!(wifi_sent.len(), 1);
+        assert_eq!(ble_sent.len(), 1);
+        assert_eq!(wifi_sent[0].message_id, ble_sent[0].message_id);
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_large_payload_uses_single_channel() {
+        let manager = TransportManager::with_config(TransportManagerConfig {
+            multipath_payload_limit: 4,
+            ..Default::default()
+        });
+        let (wifi, ble) = add_wifi_and_ble("device_1");
+        manager.add_transport("device_1", wifi.clone()).await;
+        manager.add_transport("device_1", ble.clone()).await;
+        manager.set_device_preference("device_1", ChannelPreference::Hybrid).await;
+
+        manager.send_to_device("device_1", &create_test_message("too large")).await.unwrap();
+
+        assert_eq!(wifi.get_sent_messages().await.len(), 1);
+        assert!(ble.get_sent_messages().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_succeeds_if_one_channel_fails() {
+        let manager = TransportManager::new();
+        let wifi = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new()
+                .with_channel(Channel::Wifi)
+                .with_error(TransportError::SendFailed("flaky".to_string()))
+        ));
+        let ble = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new().with_channel(Channel::Ble)
+        ));
+        manager.add_transport("device_1", wifi).await;
+        manager.add_transport("device_1", ble.clone()).await;
+        manager.set_device_preference("device_1", ChannelPreference::Hybrid).await;
+
+        manager.send_to_device("device_1", &create_test_message("hi")).await.unwrap();
+        assert_eq!(ble.get_sent_messages().await.len(), 1);
+    }
+
+    struct NoopStatusCallback;
+
+    impl nearclip_sync::ChannelStatusCallback for NoopStatusCallback {
+        fn on_status_changed(&self, _: Channel, _: ChannelStatus, _: ChannelStatus) {}
+        fn on_all_channels_unavailable(&self) {}
+    }
+
+    fn create_monitor() -> Arc<ChannelMonitor> {
+        Arc::new(ChannelMonitor::new(
+            nearclip_sync::ChannelMonitorConfig::new(),
+            Arc::new(NoopStatusCallback),
+        ).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_send_records_channel_metrics() {
+        let monitor = create_monitor();
+        let mut manager = TransportManager::new();
+        manager.set_channel_monitor(monitor.clone());
+        let wifi = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new()
+                .with_channel(Channel::Wifi)
+                .with_error(TransportError::SendFailed("down".to_string()))
+        ));
+        let ble = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new().with_channel(Channel::Ble).with_latency(std::time::Duration::from_millis(5))
+        ));
+        manager.add_transport("device_1", wifi).await;
+        manager.add_transport("device_1", ble).await;
+
+        // WiFi fails, failover delivers via BLE
+        manager.send_to_device("device_1", &create_test_message("hi")).await.unwrap();
+
+        let wifi_metrics = monitor.metrics(Channel::Wifi);
+        assert_eq!(wifi_metrics.recent_failures, 1);
+        assert_eq!(wifi_metrics.consecutive_failures, 1);
+        let ble_metrics = monitor.metrics(Channel::Ble);
+        assert_eq!(ble_metrics.recent_sends, 1);
+        assert_eq!(ble_metrics.recent_failures, 0);
+        assert!(ble_metrics.throughput_bps.is_some());
+        assert_eq!(monitor.device_metrics("device_1", Channel::Wifi).recent_failures, 1);
+        assert!(!monitor.device_metrics("device_2", Channel::Wifi).has_samples());
+    }
+
+    #[tokio::test]
+    async fn test_quality_selector_avoids_failing_channel() {
+        let monitor = create_monitor();
+        let selector = Arc::new(nearclip_sync::QualityChannelSelector::new(
+            nearclip_sync::QualitySelectorConfig::new().with_min_dwell(std::time::Duration::ZERO),
+            monitor.clone(),
+        ).unwrap());
+        let mut manager = TransportManager::with_selector(Box::new(selector.clone()));
+        manager.set_channel_monitor(monitor);
+        let wifi = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new()
+                .with_channel(Channel::Wifi)
+                .with_error(TransportError::SendFailed("down".to_string()))
+        ));
+        let ble = Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new().with_channel(Channel::Ble)
+        ));
+        manager.add_transport("device_1", wifi).await;
+        manager.add_transport("device_1", ble).await;
+
+        assert_eq!(manager.get_best_transport("device_1").await.unwrap().channel(), Channel::Wifi);
+        for _ in 0..5 {
+            manager.send_to_device("device_1", &create_test_message("hi")).await.unwrap();
+        }
+
+        assert_eq!(manager.get_best_transport("device_1").await.unwrap().channel(), Channel::Ble);
+        let decision = selector.recent_decisions().pop().unwrap();
+        assert_eq!(decision.device_id.as_deref(), Some("device_1"));
+        assert_eq!(decision.previous, Some(Channel::Wifi));
+        assert_eq!(decision.selected, Some(Channel::Ble));
+    }
+
+    #[tokio::test]
+    async fn test_quality_selector_is_per_device() {
+        let monitor = create_monitor();
+        let selector = Arc::new(nearclip_sync::QualityChannelSelector::new(
+            nearclip_sync::QualitySelectorConfig::new().with_min_dwell(std::time::Duration::ZERO),
+            monitor.clone(),
+        ).unwrap());
+        let mut manager = TransportManager::with_selector(Box::new(selector.clone()));
+        manager.set_channel_monitor(monitor);
+        manager.add_transport("device_1", Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new()
+                .with_channel(Channel::Wifi)
+                .with_error(TransportError::SendFailed("down".to_string()))
+        ))).await;
+        manager.add_transport("device_1", Arc::new(MockTransport::new(
+            "device_1",
+            MockConfig::new().with_channel(Channel::Ble)
+        ))).await;
+        manager.add_transport("device_2", Arc::new(MockTransport::new(
+            "device_2",
+            MockConfig::new().with_channel(Channel::Wifi)
+        ))).await;
+        manager.add_transport("device_2", Arc::new(MockTransport::new(
+            "device_2",
+            MockConfig::new().with_channel(Channel::Ble)
+        ))).await;
+
+        for _ in 0..5 {
+            manager.send_to_device("device_1", &create_test_message("hi")).await.unwrap();
+        }
+
+        // device_1's failing WiFi must not move device_2 off WiFi
+        assert_eq!(manager.get_best_transport("device_1").await.unwrap().channel(), Channel::Ble);
+        assert_eq!(manager.get_best_transport("device_2").await.unwrap().channel(), Channel::Wifi);
+        assert_eq!(selector.current_channel_for("device_2"), Some(Channel::Wifi));
+    }
 }
@@ -0,0 +1,841 @@
+//! Multiplexed framing
+//!
+//! Lets several messages share one connection so a large clipboard transfer
+//! no longer stalls heartbeats and acks queued behind it. Every outgoing
+//! message gets its own stream; large messages are split into `Data` frames
+//! and interleaved round-robin, small control messages travel in a single
+//! `Control` frame that always jumps the queue.
+//!
+//! # Frame layout
+//!
+//! ```text
+//! ┌──────┬───────┬───────────┬──────────┬─────────────┐
+//! │ type │ flags │ stream_id │  length  │   payload   │
+//! │  1B  │  1B   │  4B (BE)  │  4B (BE) │ length bytes│
+//! └──────┴───────┴───────────┴──────────┴─────────────┘
+//! ```
+//!
+//! - `Data` (0x00): part of a stream; `FIN` flag marks the last frame
+//! - `Control` (0x01): a complete message in one frame, sent before any data
+//! - `WindowUpdate` (0x02): 4-byte BE credit for one of the receiver's streams
+//!
+//! # Flow control
+//!
+//! Each stream starts with `stream_window` bytes of credit. The sender stops
+//! a stream when its credit runs out; the receiver hands credit back with a
+//! `WindowUpdate` once half the window has arrived. This bounds how much bulk
+//! data can sit in front of a control frame in socket or BLE queues.
+//!
+//! [`Multiplexer`] is a pure state machine; `WifiTransport` and `BleTransport`
+//! move its frames over their links.
+//!
+//! # Negotiation
+//!
+//! Peers from before multiplexing send each message whole, behind a length
+//! prefix (WiFi) or chunked on its own (BLE). Framing is therefore
+//! negotiated per connection and [`Framing::LengthPrefixed`] is kept for
+//! peers that do not advertise mux support: over TLS the client and server
+//! offer [`MUX_ALPN`], over BLE the peripheral publishes a framing
+//! characteristic and mux frames are flagged in their chunk headers.
+//!
+//! # Buffers
+//!
+//! Payloads are [`Bytes`]: outgoing frames are slices of the queued message
+//! and [`FrameDecoder`] hands out incoming payloads as views into its read
+//! buffer. The only copy on the receive path joins the frames of a
+//! multi-frame message into one exactly sized buffer.
+
+use bytes::{Buf, Bytes, BytesMut};
+use nearclip_sync::{Message, MessageType};
+use std::collections::{HashMap, VecDeque};
+
+use crate::error::TransportError;
+
+/// Frame header size in bytes
+pub const MUX_HEADER_SIZE: usize = 10;
+
+/// Default maximum payload per frame (16 KB)
+pub const DEFAULT_MAX_FRAME_PAYLOAD: usize = 16 * 1024;
+
+/// Default per-stream flow control window (256 KB)
+pub const DEFAULT_STREAM_WINDOW: u32 = 256 * 1024;
+
+/// Default maximum reassembled message size (16 MB)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+/// Default number of inbound streams that may be open at once
+pub const DEFAULT_MAX_INBOUND_STREAMS: usize = 16;
+
+/// Default capacity of a [`FrameDecoder`] read buffer (64 KB)
+pub const DEFAULT_READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// TLS ALPN protocol offered by peers that speak multiplexed framing
+pub const MUX_ALPN: &[u8] = b"nearclip-mux/1";
+
+/// Last frame of a stream
+const FLAG_FIN: u8 = 0x01;
+
+/// Message framing used on a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// Each message is sent whole: behind a 4-byte big-endian length prefix
+    /// on a byte stream, or chunked on its own over BLE GATT
+    LengthPrefixed,
+    /// Multiplexed frames with per-stream flow control
+    Mux,
+}
+
+impl Framing {
+    /// Framing implied by the ALPN protocol a TLS handshake settled on
+    ///
+    /// Peers that offer no ALPN, or none in common, use length prefixes.
+    pub fn from_alpn(protocol: Option<&[u8]>) -> Self {
+        if protocol == Some(MUX_ALPN) {
+            Framing::Mux
+        } else {
+            Framing::LengthPrefixed
+        }
+    }
+}
+
+/// Frame type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum FrameType {
+    /// Stream data
+    Data = 0x00,
+    /// Complete control message
+    Control = 0x01,
+    /// Flow control credit
+    WindowUpdate = 0x02,
+}
+
+impl TryFrom<u8> for FrameType {
+    type Error = TransportError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0x00 => Ok(FrameType::Data),
+            0x01 => Ok(FrameType::Control),
+            0x02 => Ok(FrameType::WindowUpdate),
+            other => Err(TransportError::Protocol(format!("Unknown frame type: 0x{:02x}", other))),
+        }
+    }
+}
+
+/// Whether a message is sent with control priority (everything but clipboard content)
+pub(crate) fn is_control(msg: &Message) -> bool {
+    msg.msg_type != MessageType::ClipboardSync
+}
+
+/// Decoded frame header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameHeader {
+    pub frame_type: FrameType,
+    pub fin: bool,
+    pub stream_id: u32,
+    pub length: u32,
+}
+
+impl FrameHeader {
+    /// Parse a header
+    pub fn parse(bytes: &[u8; MUX_HEADER_SIZE]) -> Result<Self, TransportError> {
+        Ok(Self {
+            frame_type: FrameType::try_from(bytes[0])?,
+            fin: bytes[1] & FLAG_FIN != 0,
+            stream_id: u32::from_be_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]),
+            length: u32::from_be_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]),
+        })
+    }
+}
+
+/// A multiplexed frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MuxFrame {
+    pub frame_type: FrameType,
+    pub fin: bool,
+    pub stream_id: u32,
+    pub payload: Bytes,
+}
+
+impl MuxFrame {
+    /// Create a data frame
+    pub fn data(stream_id: u32, payload: impl Into<Bytes>, fin: bool) -> Self {
+        Self { frame_type: FrameType::Data, fin, stream_id, payload: payload.into() }
+    }
+
+    /// Create a control frame
+    pub fn control(stream_id: u32, payload: impl Into<Bytes>) -> Self {
+        Self { frame_type: FrameType::Control, fin: true, stream_id, payload: payload.into() }
+    }
+
+    /// Create a window update frame
+    pub fn window_update(stream_id: u32, increment: u32) -> Self {
+        Self {
+            frame_type: FrameType::WindowUpdate,
+            fin: false,
+            stream_id,
+            payload: Bytes::copy_from_slice(&increment.to_be_bytes()),
+        }
+    }
+
+    /// Whether this frame completes a message
+    pub fn completes_message(&self) -> bool {
+        self.frame_type != FrameType::WindowUpdate && self.fin
+    }
+
+    /// Encode header and payload
+    pub fn encode(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(MUX_HEADER_SIZE + self.payload.len());
+        buf.push(self.frame_type as u8);
+        buf.push(if self.fin { FLAG_FIN } else { 0 });
+        buf.extend_from_slice(&self.stream_id.to_be_bytes());
+        buf.extend_from_slice(&(self.payload.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&self.payload);
+        buf
+    }
+
+    /// Decode exactly one frame
+    pub fn decode(bytes: &[u8]) -> Result<Self, TransportError> {
+        let header: &[u8; MUX_HEADER_SIZE] = bytes
+            .get(..MUX_HEADER_SIZE)
+            .and_then(|h| h.try_into().ok())
+            .ok_or_else(|| TransportError::Protocol(format!("Frame too short: {} bytes", bytes.len())))?;
+        let header = FrameHeader::parse(header)?;
+        let payload = &bytes[MUX_HEADER_SIZE..];
+        if payload.len() != header.length as usize {
+            return Err(TransportError::Protocol(format!(
+                "Frame length mismatch: header says {}, got {}",
+                header.length,
+                payload.len()
+            )));
+        }
+        Ok(Self {
+            frame_type: header.frame_type,
+            fin: header.fin,
+            stream_id: header.stream_id,
+            payload: Bytes::copy_from_slice(payload),
+        })
+    }
+}
+
+/// Incremental frame decoder over a reusable read buffer
+///
+/// Bytes read from the connection are appended to one `BytesMut` per
+/// connection and each payload is split off as a `Bytes` view into it, so
+/// frames are not copied. The allocation is reused once those payloads have
+/// been dropped.
+///
+/// Callers should validate [`peek_header`](Self::peek_header) before reading
+/// more data: [`decode`](Self::decode) reserves room for the whole frame.
+///
+/// # Example
+///
+/// ```
+/// use nearclip_transport::{FrameDecoder, MuxFrame};
+///
+/// let wire = MuxFrame::control(1, b"ping".to_vec()).encode();
+/// let mut decoder = FrameDecoder::new();
+///
+/// decoder.read_buf().extend_from_slice(&wire[..4]);
+/// assert!(decoder.decode().unwrap().is_none());
+///
+/// decoder.read_buf().extend_from_slice(&wire[4..]);
+/// assert_eq!(decoder.decode().unwrap().unwrap().payload, &b"ping"[..]);
+/// ```
+#[derive(Debug)]
+pub struct FrameDecoder {
+    buf: BytesMut,
+    capacity: usize,
+}
+
+impl Default for FrameDecoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameDecoder {
+    /// Create a decoder with the default buffer capacity
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_READ_BUFFER_SIZE)
+    }
+
+    /// Create a decoder that reads in chunks of up to `capacity` bytes
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { buf: BytesMut::with_capacity(capacity), capacity }
+    }
+
+    /// Buffer to append received bytes to
+    ///
+    /// Guarantees spare capacity for at least a quarter of a read chunk.
+    pub fn read_buf(&mut self) -> &mut BytesMut {
+        if self.buf.capacity() - self.buf.len() < self.capacity.div_ceil(4) {
+            self.buf.reserve(self.capacity);
+        }
+        &mut self.buf
+    }
+
+    /// Number of bytes buffered but not yet decoded
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Header of the next frame, once it has been fully received
+    pub fn peek_header(&self) -> Result<Option<FrameHeader>, TransportError> {
+        match self.buf.get(..MUX_HEADER_SIZE) {
+            Some(header) => FrameHeader::parse(header.try_into().expect("header size")).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Take the next frame, or `None` if it has not been fully received
+    pub fn decode(&mut self) -> Result<Option<MuxFrame>, TransportError> {
+        let Some(header) = self.peek_header()? else {
+            return Ok(None);
+        };
+        let frame_len = MUX_HEADER_SIZE + header.length as usize;
+        if self.buf.len() < frame_len {
+            self.buf.reserve(frame_len - self.buf.len());
+            return Ok(None);
+        }
+
+        self.buf.advance(MUX_HEADER_SIZE);
+        Ok(Some(MuxFrame {
+            frame_type: header.frame_type,
+            fin: header.fin,
+            stream_id: header.stream_id,
+            payload: self.buf.split_to(header.length as usize).freeze(),
+        }))
+    }
+}
+
+/// Multiplexer configuration
+#[derive(Debug, Clone)]
+pub struct MuxConfig {
+    /// Maximum payload per frame
+    pub max_frame_payload: usize,
+    /// Initial flow control credit per stream
+    pub stream_window: u32,
+    /// Maximum reassembled message size
+    pub max_message_size: usize,
+    /// Maximum inbound streams in progress at once
+    pub max_inbound_streams: usize,
+}
+
+impl Default for MuxConfig {
+    fn default() -> Self {
+        Self {
+            max_frame_payload: DEFAULT_MAX_FRAME_PAYLOAD,
+            stream_window: DEFAULT_STREAM_WINDOW,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            max_inbound_streams: DEFAULT_MAX_INBOUND_STREAMS,
+        }
+    }
+}
+
+impl MuxConfig {
+    /// Create the default configuration (tuned for TCP)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Configuration tuned for BLE: small frames and a small window keep a
+    /// control message at most a few frames behind bulk data
+    pub fn ble() -> Self {
+        Self {
+            max_frame_payload: 4 * 1024,
+            stream_window: 32 * 1024,
+            ..Self::default()
+        }
+    }
+
+    /// Set the maximum frame payload
+    pub fn with_max_frame_payload(mut self, size: usize) -> Self {
+        self.max_frame_payload = size;
+        self
+    }
+
+    /// Set the per-stream window
+    pub fn with_stream_window(mut self, window: u32) -> Self {
+        self.stream_window = window;
+        self
+    }
+
+    /// Set the maximum reassembled message size
+    pub fn with_max_message_size(mut self, size: usize) -> Self {
+        self.max_message_size = size;
+        self
+    }
+
+    /// Set the maximum number of concurrent inbound streams
+    pub fn with_max_inbound_streams(mut self, max: usize) -> Self {
+        self.max_inbound_streams = max;
+        self
+    }
+
+    /// Check an incoming header before its payload is read or allocated
+    pub fn check_header(&self, header: &FrameHeader) -> Result<(), TransportError> {
+        let max = match header.frame_type {
+            FrameType::WindowUpdate => 4,
+            FrameType::Data | FrameType::Control => self.max_frame_payload,
+        };
+        if header.length as usize > max {
+            return Err(TransportError::Protocol(format!(
+                "{:?} frame too large: {} bytes (max {})",
+                header.frame_type, header.length, max
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// An outgoing stream
+struct OutStream {
+    id: u32,
+    data: Bytes,
+    offset: usize,
+    window: u32,
+}
+
+/// An incoming stream being reassembled
+struct InStream {
+    /// Payloads received so far, still views into the decoder's buffers
+    chunks: Vec<Bytes>,
+    len: usize,
+    /// Credit the sender still has
+    window: u32,
+    /// Bytes received since the last window update
+    unacknowledged: u32,
+}
+
+/// A message completed by [`Multiplexer::receive`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MuxMessage {
+    /// Sender's stream ID
+    pub stream_id: u32,
+    /// Message bytes
+    pub data: Bytes,
+}
+
+/// Multiplexing state machine for one connection
+///
+/// # Example
+///
+/// ```
+/// use nearclip_transport::{Multiplexer, MuxConfig};
+///
+/// let mut a = Multiplexer::new(MuxConfig::new());
+/// let mut b = Multiplexer::new(MuxConfig::new());
+///
+/// a.queue(vec![0u8; 100_000], false);   // bulk transfer
+/// a.queue(b"heartbeat".to_vec(), true); // jumps ahead of the remaining bulk frames
+///
+/// let first = a.next_frame().unwrap();
+/// let msg = b.receive(first).unwrap().unwrap();
+/// assert_eq!(msg.data, &b"heartbeat"[..]);
+/// ```
+pub struct Multiplexer {
+    config: MuxConfig,
+    next_stream_id: u32,
+    /// Control messages and window updates, sent before any data
+    urgent: VecDeque<MuxFrame>,
+    /// Outgoing streams, served round-robin
+    outgoing: VecDeque<OutStream>,
+    incoming: HashMap<u32, InStream>,
+}
+
+impl Multiplexer {
+    /// Create a multiplexer
+    pub fn new(config: MuxConfig) -> Self {
+        Self {
+            config,
+            next_stream_id: 1,
+            urgent: VecDeque::new(),
+            outgoing: VecDeque::new(),
+            incoming: HashMap::new(),
+        }
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &MuxConfig {
+        &self.config
+    }
+
+    /// Change the maximum accepted message size (e.g. after authentication)
+    pub fn set_max_message_size(&mut self, size: usize) {
+        self.config.max_message_size = size;
+    }
+
+    /// Queue a message and return its stream ID
+    ///
+    /// A `control` message that fits in one frame is sent as a `Control`
+    /// frame ahead of all data; anything else becomes a data stream.
+    pub fn queue(&mut self, data: impl Into<Bytes>, control: bool) -> u32 {
+        let data = data.into();
+        let id = self.next_stream_id;
+        self.next_stream_id = self.next_stream_id.checked_add(1).unwrap_or(1);
+
+        if control && data.len() <= self.config.max_frame_payload {
+            self.urgent.push_back(MuxFrame::control(id, data));
+        } else {
+            self.outgoing.push_back(OutStream { id, data, offset: 0, window: self.config.stream_window });
+        }
+        id
+    }
+
+    /// Next frame to put on the wire, or `None` if nothing is sendable
+    ///
+    /// Streams whose window is exhausted are skipped until a window update
+    /// arrives.
+    pub fn next_frame(&mut self) -> Option<MuxFrame> {
+        self.next_frame_skipping(|_| false)
+    }
+
+    /// Like [`next_frame`](Self::next_frame), but skips data streams for
+    /// which `busy` returns true
+    ///
+    /// Lets a link that may deliver frames out of order keep at most one
+    /// frame of each stream in flight. Control frames are never skipped.
+    pub fn next_frame_skipping(&mut self, busy: impl Fn(u32) -> bool) -> Option<MuxFrame> {
+        if let Some(frame) = self.urgent.pop_front() {
+            return Some(frame);
+        }
+
+        for _ in 0..self.outgoing.len() {
+            let mut stream = self.outgoing.pop_front()?;
+            let remaining = stream.data.len() - stream.offset;
+            if (remaining > 0 && stream.window == 0) || busy(stream.id) {
+                self.outgoing.push_back(stream);
+                continue;
+            }
+
+            let len = remaining.min(self.config.max_frame_payload).min(stream.window as usize);
+            let payload = stream.data.slice(stream.offset..stream.offset + len);
+            stream.offset += len;
+            stream.window -= len as u32;
+            let fin = stream.offset == stream.data.len();
+            let frame = MuxFrame::data(stream.id, payload, fin);
+            if !fin {
+                self.outgoing.push_back(stream);
+            }
+            return Some(frame);
+        }
+        None
+    }
+
+    /// Whether frames are waiting (possibly blocked on flow control)
+    pub fn has_pending(&self) -> bool {
+        !self.urgent.is_empty() || !self.outgoing.is_empty()
+    }
+
+    /// Whether a window update is waiting to be sent
+    pub fn has_urgent(&self) -> bool {
+        !self.urgent.is_empty()
+    }
+
+    /// Process a frame from the peer
+    ///
+    /// Returns the message it completes, if any. Window updates for the peer
+    /// are queued and come out of [`next_frame`](Self::next_frame).
+    pub fn receive(&mut self, frame: MuxFrame) -> Result<Option<MuxMessage>, TransportError> {
+        match frame.frame_type {
+            FrameType::Control => {
+                self.check_size(frame.payload.len())?;
+                Ok(Some(MuxMessage { stream_id: frame.stream_id, data: frame.payload }))
+            }
+            FrameType::WindowUpdate => {
+                let increment: [u8; 4] = frame.payload.as_ref().try_into()
+                    .map_err(|_| TransportError::Protocol("Malformed window update".to_string()))?;
+                let increment = u32::from_be_bytes(increment);
+                if let Some(stream) = self.outgoing.iter_mut().find(|s| s.id == frame.stream_id) {
+                    stream.window = stream.window.saturating_add(increment);
+                }
+                Ok(None)
+            }
+            FrameType::Data => self.receive_data(frame),
+        }
+    }
+
+    fn receive_data(&mut self, frame: MuxFrame) -> Result<Option<MuxMessage>, TransportError> {
+        if !self.incoming.contains_key(&frame.stream_id) {
+            if self.incoming.len() >= self.config.max_inbound_streams {
+                return Err(TransportError::Protocol(format!(
+                    "Too many concurrent streams (max {})",
+                    self.config.max_inbound_streams
+                )));
+            }
+            self.incoming.insert(frame.stream_id, InStream {
+                chunks: Vec::new(),
+                len: 0,
+                window: self.config.stream_window,
+                unacknowledged: 0,
+            });
+        }
+
+        let max_message_size = self.config.max_message_size;
+        let half_window = self.config.stream_window / 2;
+        let stream = self.incoming.get_mut(&frame.stream_id).expect("inserted above");
+        let len = frame.payload.len() as u32;
+        if len > stream.window {
+            return Err(TransportError::Protocol(format!(
+                "Stream {} exceeded its flow control window",
+                frame.stream_id
+            )));
+        }
+        if stream.len + frame.payload.len() > max_message_size {
+            return Err(TransportError::Protocol(format!(
+                "Message too large: more than {} bytes",
+                max_message_size
+            )));
+        }
+        stream.window -= len;
+        stream.unacknowledged += len;
+
+        if frame.fin {
+            let stream = self.incoming.remove(&frame.stream_id).expect("present");
+            // A single-frame message needs no reassembly copy; otherwise
+            // the message is copied once into an exactly sized buffer
+            let data = if stream.chunks.is_empty() {
+                frame.payload
+            } else {
+                let mut buf = BytesMut::with_capacity(stream.len + frame.payload.len());
+                for chunk in stream.chunks.iter().chain(std::iter::once(&frame.payload)) {
+                    buf.extend_from_slice(chunk);
+                }
+                buf.freeze()
+            };
+            return Ok(Some(MuxMessage { stream_id: frame.stream_id, data }));
+        }
+        stream.len += frame.payload.len();
+        stream.chunks.push(frame.payload);
+
+        if stream.unacknowledged >= half_window.max(1) {
+            let increment = std::mem::take(&mut stream.unacknowledged);
+            stream.window += increment;
+            self.urgent.push_back(MuxFrame::window_update(frame.stream_id, increment));
+        }
+        Ok(None)
+    }
+
+    fn check_size(&self, len: usize) -> Result<(), TransportError> {
+        if len > self.config.max_message_size {
+            return Err(TransportError::Protocol(format!(
+                "Message too large: {} bytes (max {})",
+                len, self.config.max_message_size
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for Multiplexer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Multiplexer")
+            .field("urgent", &self.urgent.len())
+            .field("outgoing", &self.outgoing.len())
+            .field("incoming", &self.incoming.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Move every sendable frame from `a` to `b`, returning completed messages
+    fn pump(a: &mut Multiplexer, b: &mut Multiplexer) -> Vec<Vec<u8>> {
+        let mut done = Vec::new();
+        while let Some(frame) = a.next_frame() {
+            let frame = MuxFrame::decode(&frame.encode()).unwrap();
+            if let Some(msg) = b.receive(frame).unwrap() {
+                done.push(msg.data.to_vec());
+            }
+        }
+        done
+    }
+
+    #[test]
+    fn test_framing_from_alpn() {
+        assert_eq!(Framing::from_alpn(Some(MUX_ALPN)), Framing::Mux);
+        assert_eq!(Framing::from_alpn(Some(b"nearclip/1")), Framing::LengthPrefixed);
+        assert_eq!(Framing::from_alpn(None), Framing::LengthPrefixed);
+    }
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let frame = MuxFrame::data(7, vec![1, 2, 3], true);
+        let encoded = frame.encode();
+        assert_eq!(encoded.len(), MUX_HEADER_SIZE + 3);
+        assert_eq!(MuxFrame::decode(&encoded).unwrap(), frame);
+
+        let update = MuxFrame::window_update(9, 1024);
+        assert_eq!(MuxFrame::decode(&update.encode()).unwrap(), update);
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_frames() {
+        assert!(MuxFrame::decode(&[0u8; 4]).is_err());
+
+        let mut bad_type = MuxFrame::control(1, vec![]).encode();
+        bad_type[0] = 0x7f;
+        assert!(matches!(MuxFrame::decode(&bad_type), Err(TransportError::Protocol(_))));
+
+        let mut truncated = MuxFrame::data(1, vec![0u8; 8], true).encode();
+        truncated.pop();
+        assert!(MuxFrame::decode(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_decoder_splits_stream_into_frames() {
+        let frames = [
+            MuxFrame::data(1, vec![1u8; 3000], false),
+            MuxFrame::window_update(2, 512),
+            MuxFrame::data(1, vec![2u8; 10], true),
+        ];
+        let wire: Vec<u8> = frames.iter().flat_map(|f| f.encode()).collect();
+
+        // Feed the stream in awkward pieces
+        let mut decoder = FrameDecoder::with_capacity(64);
+        let mut decoded = Vec::new();
+        for piece in wire.chunks(7) {
+            decoder.read_buf().extend_from_slice(piece);
+            while let Some(frame) = decoder.decode().unwrap() {
+                decoded.push(frame);
+            }
+        }
+        assert_eq!(decoded, frames);
+        assert_eq!(decoder.buffered(), 0);
+    }
+
+    #[test]
+    fn test_decoder_rejects_bad_header() {
+        let mut decoder = FrameDecoder::new();
+        decoder.read_buf().extend_from_slice(&[0x7f; MUX_HEADER_SIZE]);
+        assert!(matches!(decoder.peek_header(), Err(TransportError::Protocol(_))));
+        assert!(decoder.decode().is_err());
+    }
+
+    #[test]
+    fn test_single_frame_message_shares_payload() {
+        let mut decoder = FrameDecoder::new();
+        decoder.read_buf().extend_from_slice(&MuxFrame::data(1, vec![9u8; 100], true).encode());
+        let frame = decoder.decode().unwrap().unwrap();
+        let payload_ptr = frame.payload.as_ptr();
+
+        let mut mux = Multiplexer::new(MuxConfig::new());
+        let msg = mux.receive(frame).unwrap().unwrap();
+        assert_eq!(msg.data.as_ptr(), payload_ptr);
+    }
+
+    #[test]
+    fn test_check_header_limits_frame_size() {
+        let config = MuxConfig::new().with_max_frame_payload(100);
+        let header = |frame_type, length| FrameHeader { frame_type, fin: true, stream_id: 1, length };
+        assert!(config.check_header(&header(FrameType::Data, 100)).is_ok());
+        assert!(config.check_header(&header(FrameType::Control, 101)).is_err());
+        assert!(config.check_header(&header(FrameType::WindowUpdate, 5)).is_err());
+    }
+
+    #[test]
+    fn test_control_jumps_ahead_of_bulk() {
+        let config = MuxConfig::new().with_max_frame_payload(1000);
+        let mut a = Multiplexer::new(config.clone());
+        let mut b = Multiplexer::new(config);
+
+        a.queue(vec![1u8; 5000], false);
+        // First bulk frame already on the wire
+        let frame = a.next_frame().unwrap();
+        assert_eq!(frame.frame_type, FrameType::Data);
+        assert!(b.receive(frame).unwrap().is_none());
+
+        a.queue(b"ack".to_vec(), true);
+        let frame = a.next_frame().unwrap();
+        assert_eq!(frame.frame_type, FrameType::Control);
+        assert_eq!(b.receive(frame).unwrap().unwrap().data, &b"ack"[..]);
+
+        assert_eq!(pump(&mut a, &mut b), vec![vec![1u8; 5000]]);
+    }
+
+    #[test]
+    fn test_large_control_message_becomes_stream() {
+        let mut mux = Multiplexer::new(MuxConfig::new().with_max_frame_payload(10));
+        mux.queue(vec![0u8; 11], true);
+        assert_eq!(mux.next_frame().unwrap().frame_type, FrameType::Data);
+    }
+
+    #[test]
+    fn test_streams_interleave_round_robin() {
+        let mut mux = Multiplexer::new(MuxConfig::new().with_max_frame_payload(10));
+        let first = mux.queue(vec![1u8; 30], false);
+        let second = mux.queue(vec![2u8; 30], false);
+
+        let ids: Vec<u32> = std::iter::from_fn(|| mux.next_frame()).map(|f| f.stream_id).collect();
+        assert_eq!(ids, vec![first, second, first, second, first, second]);
+    }
+
+    #[test]
+    fn test_flow_control_blocks_until_window_update() {
+        let config = MuxConfig::new().with_max_frame_payload(100).with_stream_window(200);
+        let mut a = Multiplexer::new(config.clone());
+        let mut b = Multiplexer::new(config);
+        a.queue(vec![5u8; 1000], false);
+
+        // Only one window's worth goes out
+        let mut sent = 0;
+        while let Some(frame) = a.next_frame() {
+            sent += frame.payload.len();
+            assert!(b.receive(frame).unwrap().is_none());
+        }
+        assert_eq!(sent, 200);
+        assert!(a.has_pending());
+
+        // The receiver returns credit, the sender continues
+        let received = loop {
+            while let Some(update) = b.next_frame() {
+                assert_eq!(update.frame_type, FrameType::WindowUpdate);
+                a.receive(update).unwrap();
+            }
+            let done = pump(&mut a, &mut b);
+            if !done.is_empty() {
+                break done;
+            }
+        };
+        assert_eq!(received, vec![vec![5u8; 1000]]);
+        assert!(!a.has_pending());
+    }
+
+    #[test]
+    fn test_window_violation_rejected() {
+        let config = MuxConfig::new().with_stream_window(10);
+        let mut b = Multiplexer::new(config);
+        let result = b.receive(MuxFrame::data(1, vec![0u8; 11], false));
+        assert!(matches!(result, Err(TransportError::Protocol(_))));
+    }
+
+    #[test]
+    fn test_message_size_limit() {
+        let mut b = Multiplexer::new(MuxConfig::new().with_max_message_size(100));
+        assert!(b.receive(MuxFrame::control(1, vec![0u8; 101])).is_err());
+
+        let mut b = Multiplexer::new(MuxConfig::new().with_max_message_size(100));
+        assert!(b.receive(MuxFrame::data(1, vec![0u8; 60], false)).unwrap().is_none());
+        assert!(b.receive(MuxFrame::data(1, vec![0u8; 60], true)).is_err());
+    }
+
+    #[test]
+    fn test_inbound_stream_limit() {
+        let mut b = Multiplexer::new(MuxConfig::new().with_max_inbound_streams(2));
+        b.receive(MuxFrame::data(1, vec![0], false)).unwrap();
+        b.receive(MuxFrame::data(2, vec![0], false)).unwrap();
+        assert!(b.receive(MuxFrame::data(3, vec![0], false)).is_err());
+    }
+
+    #[test]
+    fn test_empty_message() {
+        let mut a = Multiplexer::new(MuxConfig::new());
+        let mut b = Multiplexer::new(MuxConfig::new());
+        a.queue(Vec::new(), false);
+        assert_eq!(pump(&mut a, &mut b), vec![Vec::<u8>::new()]);
+    }
+}
@@ -0,0 +1,545 @@
+//! QUIC transport implementation
+//!
+//! Uses the same pinned certificates as the WiFi (TCP/TLS) transport. Each
+//! connection carries:
+//!
+//! - one bidirectional **control stream**, opened by the dialer, carrying
+//!   length-prefixed messages in order (heartbeats, pairing, acks, ...)
+//! - one unidirectional **bulk stream** per large message, so clipboard
+//!   payloads never hold up control traffic
+//!
+//! Bulk messages on separate streams may overtake each other and control
+//! messages; receivers must not rely on ordering between them.
+//!
+//! Reconnecting through the same [`QuicTransportConnector`] uses 0-RTT:
+//! messages are sent before the handshake completes. Until the server
+//! confirms the 0-RTT data, every message goes on the control stream and is
+//! kept; if the server rejects 0-RTT they are replayed on a fresh control
+//! stream, so callers never see the rejection.
+
+use async_trait::async_trait;
+use nearclip_net::quic::{QuicClient, QuicConnection, QuicServer};
+use nearclip_net::tcp::{ConnectionGuard, ConnectionLimits, ConnectionPermit, TokenBucket};
+use nearclip_sync::{Channel, Message, MessageType};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, Mutex};
+use tracing::{debug, instrument, warn};
+
+use crate::error::TransportError;
+use crate::traits::{Transport, TransportConnector, TransportListener};
+
+/// Maximum message size (16 MB)
+const MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
+
+/// Serialized messages at or above this size are sent on their own stream
+pub const QUIC_BULK_THRESHOLD: usize = 16 * 1024;
+
+/// First byte written on the control stream so the acceptor sees it
+const CONTROL_STREAM_HEADER: u8 = 0x01;
+
+/// Time the acceptor waits for the control stream when no limits apply
+const DEFAULT_CONTROL_STREAM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Received messages buffered before readers apply backpressure
+const INCOMING_QUEUE_SIZE: usize = 64;
+
+/// State shared between the transport and its reader tasks
+struct Shared {
+    device_id: String,
+    conn: quinn::Connection,
+    permit: Option<Arc<ConnectionPermit>>,
+    connected: AtomicBool,
+    authenticated: AtomicBool,
+    max_unauthenticated_message_size: u32,
+    rate_limiter: Option<std::sync::Mutex<TokenBucket>>,
+    incoming: mpsc::Sender<Result<Message, TransportError>>,
+}
+
+impl Shared {
+    /// Drop the connection and ban the peer's IP (inbound connections only)
+    fn reject_peer(&self, reason: &str) -> TransportError {
+        warn!("Rejecting peer {} ({}): {}", self.device_id, self.conn.remote_address(), reason);
+        if let Some(permit) = &self.permit {
+            permit.ban();
+        }
+        self.connected.store(false, Ordering::SeqCst);
+        self.conn.close(0u32.into(), b"rejected");
+        TransportError::ReceiveFailed(reason.to_string())
+    }
+
+    /// Read one length-prefixed message; `Ok(None)` when the stream ended cleanly
+    async fn read_frame(&self, stream: &mut quinn::RecvStream) -> Result<Option<Message>, FrameError> {
+        let mut len_buf = [0u8; 4];
+        match stream.read_exact(&mut len_buf).await {
+            Ok(()) => {}
+            Err(quinn::ReadExactError::FinishedEarly(0)) => return Ok(None),
+            Err(e) => return Err(FrameError::from(e)),
+        }
+
+        if let Some(limiter) = &self.rate_limiter {
+            if !limiter.lock().unwrap().try_acquire() {
+                return Err(FrameError::Transport(self.reject_peer("Message rate limit exceeded")));
+            }
+        }
+
+        let msg_len = u32::from_be_bytes(len_buf);
+        if !self.authenticated.load(Ordering::SeqCst) && msg_len > self.max_unauthenticated_message_size {
+            return Err(FrameError::Transport(self.reject_peer(&format!(
+                "Message too large before authentication: {} bytes (max {})",
+                msg_len, self.max_unauthenticated_message_size
+            ))));
+        }
+        if msg_len > MAX_MESSAGE_SIZE {
+            warn!("Message too large: {} bytes", msg_len);
+            return Err(FrameError::Transport(TransportError::ReceiveFailed(format!(
+                "Message too large: {} bytes (max {})",
+                msg_len, MAX_MESSAGE_SIZE
+            ))));
+        }
+
+        let mut data = vec![0u8; msg_len as usize];
+        stream.read_exact(&mut data).await.map_err(FrameError::from)?;
+
+        let msg = Message::deserialize(&data)
+            .map_err(|e| FrameError::Transport(TransportError::Deserialization(e.to_string())))?;
+        debug!("Received message ({} bytes) from {}", data.len(), self.device_id);
+        Ok(Some(msg))
+    }
+}
+
+/// Why reading a frame stopped
+enum FrameError {
+    /// The stream was opened in rejected 0-RTT data; it will be replaced
+    ZeroRttRejected,
+    /// The connection is gone
+    Closed,
+    /// The frame itself was invalid
+    Transport(TransportError),
+}
+
+impl From<quinn::ReadExactError> for FrameError {
+    fn from(e: quinn::ReadExactError) -> Self {
+        match e {
+            quinn::ReadExactError::ReadError(quinn::ReadError::ZeroRttRejected) => Self::ZeroRttRejected,
+            quinn::ReadExactError::ReadError(quinn::ReadError::ConnectionLost(_)) => Self::Closed,
+            e => Self::Transport(TransportError::ReceiveFailed(e.to_string())),
+        }
+    }
+}
+
+/// Read messages from the control stream until it ends
+async fn run_control_reader(shared: Arc<Shared>, mut stream: quinn::RecvStream) {
+    loop {
+        match shared.read_frame(&mut stream).await {
+            Ok(Some(msg)) => {
+                if shared.incoming.send(Ok(msg)).await.is_err() {
+                    return;
+                }
+            }
+            Ok(None) | Err(FrameError::Closed) => {
+                shared.connected.store(false, Ordering::SeqCst);
+                return;
+            }
+            Err(FrameError::ZeroRttRejected) => return,
+            Err(FrameError::Transport(e)) => {
+                shared.connected.store(false, Ordering::SeqCst);
+                let _ = shared.incoming.send(Err(e)).await;
+                return;
+            }
+        }
+    }
+}
+
+/// Accept bulk streams, each carrying a single message
+async fn run_bulk_acceptor(shared: Arc<Shared>) {
+    while let Ok(mut stream) = shared.conn.accept_uni().await {
+        let shared = shared.clone();
+        tokio::spawn(async move {
+            let result = match shared.read_frame(&mut stream).await {
+                Ok(Some(msg)) => Ok(msg),
+                Ok(None) | Err(FrameError::Closed) | Err(FrameError::ZeroRttRejected) => return,
+                Err(FrameError::Transport(e)) => Err(e),
+            };
+            let _ = shared.incoming.send(result).await;
+        });
+    }
+    shared.connected.store(false, Ordering::SeqCst);
+}
+
+/// Sending half of the control stream
+struct ControlStream {
+    send: quinn::SendStream,
+    /// Frames sent while 0-RTT is unconfirmed (None once confirmed)
+    early: Option<Vec<Vec<u8>>>,
+}
+
+/// QUIC transport
+///
+/// Wraps a QUIC connection and implements the Transport trait. Inbound
+/// connections carry the server's `ConnectionLimits` and are limited the
+/// same way as [`WifiTransport`](crate::WifiTransport).
+pub struct QuicTransport {
+    shared: Arc<Shared>,
+    control: Arc<Mutex<ControlStream>>,
+    incoming: Mutex<mpsc::Receiver<Result<Message, TransportError>>>,
+}
+
+impl QuicTransport {
+    /// Open the control stream on an outbound connection
+    ///
+    /// # Arguments
+    /// * `device_id` - The peer device ID
+    /// * `connection` - The QUIC connection to wrap
+    pub async fn connect(device_id: String, mut connection: QuicConnection) -> Result<Self, TransportError> {
+        let (mut send, recv) = connection
+            .connection()
+            .open_bi()
+            .await
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+        let zero_rtt = connection.take_zero_rtt_accepted();
+        let early = zero_rtt.as_ref().map(|_| Vec::new());
+        match send.write_all(&[CONTROL_STREAM_HEADER]).await {
+            Ok(()) | Err(quinn::WriteError::ZeroRttRejected) => {}
+            Err(e) => return Err(TransportError::ConnectionFailed(e.to_string())),
+        }
+
+        let transport = Self::start(device_id, &connection, ControlStream { send, early }, recv);
+        if let Some(zero_rtt) = zero_rtt {
+            tokio::spawn(confirm_zero_rtt(transport.shared.clone(), transport.control.clone(), zero_rtt));
+        }
+        Ok(transport)
+    }
+
+    /// Wait for the dialer's control stream on an inbound connection
+    ///
+    /// Gives up after the connection's handshake timeout.
+    pub async fn accept(device_id: String, connection: QuicConnection) -> Result<Self, TransportError> {
+        let timeout = connection
+            .permit()
+            .map(|p| p.limits().handshake_timeout)
+            .unwrap_or(DEFAULT_CONTROL_STREAM_TIMEOUT);
+
+        let accepted = tokio::time::timeout(timeout, async {
+            let (send, mut recv) = connection.connection().accept_bi().await.map_err(|e| e.to_string())?;
+            let mut header = [0u8; 1];
+            recv.read_exact(&mut header).await.map_err(|e| e.to_string())?;
+            if header[0] != CONTROL_STREAM_HEADER {
+                return Err(format!("Unexpected stream header: {:#04x}", header[0]));
+            }
+            Ok((send, recv))
+        })
+        .await;
+
+        let (send, recv) = match accepted {
+            Ok(Ok(streams)) => streams,
+            Ok(Err(e)) => {
+                connection.close("no control stream");
+                return Err(TransportError::ConnectionFailed(e));
+            }
+            Err(_) => {
+                connection.close("no control stream");
+                return Err(TransportError::ConnectionFailed(format!(
+                    "No control stream from {} within {:?}",
+                    connection.peer_addr(),
+                    timeout
+                )));
+            }
+        };
+
+        Ok(Self::start(device_id, &connection, ControlStream { send, early: None }, recv))
+    }
+
+    fn start(
+        device_id: String,
+        connection: &QuicConnection,
+        control: ControlStream,
+        control_recv: quinn::RecvStream,
+    ) -> Self {
+        let limits: Option<ConnectionLimits> = connection.permit().map(|p| p.limits().clone());
+        let (tx, rx) = mpsc::channel(INCOMING_QUEUE_SIZE);
+        let shared = Arc::new(Shared {
+            device_id,
+            conn: connection.connection().clone(),
+            permit: connection.shared_permit(),
+            connected: AtomicBool::new(true),
+            authenticated: AtomicBool::new(limits.is_none()),
+            max_unauthenticated_message_size: limits
+                .as_ref()
+                .map(|l| l.max_unauthenticated_message_size)
+                .unwrap_or(MAX_MESSAGE_SIZE),
+            rate_limiter: limits.map(|l| std::sync::Mutex::new(l.rate_limiter())),
+            incoming: tx,
+        });
+
+        tokio::spawn(run_control_reader(shared.clone(), control_recv));
+        tokio::spawn(run_bulk_acceptor(shared.clone()));
+
+        Self {
+            shared,
+            control: Arc::new(Mutex::new(control)),
+            incoming: Mutex::new(rx),
+        }
+    }
+
+    /// Whether the peer has completed the pairing handshake
+    ///
+    /// Always true for outbound connections.
+    pub fn is_authenticated(&self) -> bool {
+        self.shared.authenticated.load(Ordering::SeqCst)
+    }
+
+    /// Whether the connection still has unconfirmed 0-RTT data
+    pub async fn is_zero_rtt_pending(&self) -> bool {
+        self.control.lock().await.early.is_some()
+    }
+
+    /// Remote address of the connection
+    pub fn peer_addr(&self) -> std::net::SocketAddr {
+        self.shared.conn.remote_address()
+    }
+
+    /// Send one frame on a fresh unidirectional stream
+    async fn send_bulk(&self, frame: &[u8]) -> Result<(), TransportError> {
+        let result = async {
+            let mut stream = self.shared.conn.open_uni().await.map_err(|e| e.to_string())?;
+            stream.write_all(frame).await.map_err(|e| e.to_string())?;
+            stream.finish().map_err(|e| e.to_string())
+        }
+        .await;
+        result.map_err(|e| {
+            self.shared.connected.store(false, Ordering::SeqCst);
+            TransportError::SendFailed(e)
+        })
+    }
+}
+
+/// Wait for the server's 0-RTT verdict and replay early frames if rejected
+async fn confirm_zero_rtt(shared: Arc<Shared>, control: Arc<Mutex<ControlStream>>, zero_rtt: quinn::ZeroRttAccepted) {
+    let accepted = zero_rtt.await;
+    let mut control = control.lock().await;
+    let frames = control.early.take().unwrap_or_default();
+    if accepted {
+        debug!("0-RTT data accepted by {}", shared.device_id);
+        return;
+    }
+
+    warn!("0-RTT data rejected by {}, replaying {} messages", shared.device_id, frames.len());
+    let replay = async {
+        let (mut send, recv) = shared.conn.open_bi().await.map_err(|e| e.to_string())?;
+        send.write_all(&[CONTROL_STREAM_HEADER]).await.map_err(|e| e.to_string())?;
+        for frame in &frames {
+            send.write_all(frame).await.map_err(|e| e.to_string())?;
+        }
+        Ok::<_, String>((send, recv))
+    };
+    match replay.await {
+        Ok((send, recv)) => {
+            control.send = send;
+            tokio::spawn(run_control_reader(shared, recv));
+        }
+        Err(e) => {
+            warn!("Failed to replay 0-RTT data to {}: {}", shared.device_id, e);
+            shared.connected.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Whether a message goes on its own stream
+fn is_bulk(msg: &Message, len: usize) -> bool {
+    msg.msg_type == MessageType::ClipboardSync || len >= QUIC_BULK_THRESHOLD
+}
+
+#[async_trait]
+impl Transport for QuicTransport {
+    #[instrument(skip(self, msg), fields(device_id = %self.shared.device_id, msg_type = ?msg.msg_type))]
+    async fn send(&self, msg: &Message) -> Result<(), TransportError> {
+        if !self.is_connected() {
+            return Err(TransportError::ConnectionClosed);
+        }
+
+        let data = msg.serialize()
+            .map_err(|e| TransportError::Serialization(e.to_string()))?;
+        let mut frame = Vec::with_capacity(4 + data.len());
+        frame.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&data);
+
+        let mut control = self.control.lock().await;
+        if control.early.is_none() && is_bulk(msg, data.len()) {
+            drop(control);
+            self.send_bulk(&frame).await?;
+        } else {
+            if let Some(early) = control.early.as_mut() {
+                early.push(frame.clone());
+            }
+            match control.send.write_all(&frame).await {
+                // Kept in `early`, replayed once the rejection is seen
+                Ok(()) | Err(quinn::WriteError::ZeroRttRejected) => {}
+                Err(e) => {
+                    self.shared.connected.store(false, Ordering::SeqCst);
+                    return Err(TransportError::SendFailed(e.to_string()));
+                }
+            }
+        }
+
+        debug!("Sent message ({} bytes) to {}", data.len(), self.shared.device_id);
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(device_id = %self.shared.device_id))]
+    async fn recv(&self) -> Result<Message, TransportError> {
+        let mut incoming = self.incoming.lock().await;
+        tokio::select! {
+            biased;
+            Some(result) = incoming.recv() => result,
+            _ = self.shared.conn.closed() => {
+                self.shared.connected.store(false, Ordering::SeqCst);
+                Err(TransportError::ConnectionClosed)
+            }
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        self.shared.connected.load(Ordering::SeqCst) && self.shared.conn.close_reason().is_none()
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Quic
+    }
+
+    fn peer_device_id(&self) -> &str {
+        &self.shared.device_id
+    }
+
+    async fn close(&self) -> Result<(), TransportError> {
+        self.shared.connected.store(false, Ordering::SeqCst);
+        self.shared.conn.close(0u32.into(), b"closed");
+        debug!("QUIC transport closed for device {}", self.shared.device_id);
+        Ok(())
+    }
+
+    fn mark_authenticated(&self) {
+        self.shared.authenticated.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for QuicTransport {
+    fn drop(&mut self) {
+        self.shared.conn.close(0u32.into(), b"dropped");
+    }
+}
+
+/// QUIC transport connector
+///
+/// Creates outbound QUIC connections. Keep one connector per process so
+/// reconnections can resume with 0-RTT.
+pub struct QuicTransportConnector {
+    client: QuicClient,
+}
+
+impl QuicTransportConnector {
+    /// Create a new QUIC transport connector
+    ///
+    /// Must be called from within a tokio runtime.
+    pub fn new(tls_config: Arc<rustls::ClientConfig>) -> Result<Self, TransportError> {
+        let client = QuicClient::new(tls_config)
+            .map_err(|e| TransportError::Other(e.to_string()))?;
+        Ok(Self { client })
+    }
+
+    /// Create from an existing QUIC client
+    pub fn from_client(client: QuicClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl TransportConnector for QuicTransportConnector {
+    async fn connect(
+        &self,
+        device_id: &str,
+        address: &str,
+    ) -> Result<Arc<dyn Transport>, TransportError> {
+        // Parse address (expected format: "host:port")
+        let addr: std::net::SocketAddr = address.parse()
+            .map_err(|e| TransportError::ConnectionFailed(format!("Invalid address: {}", e)))?;
+
+        let connection = self.client.connect(addr, "nearclip.local").await
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+
+        let transport = QuicTransport::connect(device_id.to_string(), connection).await?;
+        Ok(Arc::new(transport))
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Quic
+    }
+}
+
+/// QUIC transport listener
+///
+/// Accepts inbound QUIC connections from remote devices.
+pub struct QuicTransportListener {
+    server: Arc<QuicServer>,
+    local_address: String,
+}
+
+impl QuicTransportListener {
+    /// Create a new QUIC transport listener
+    pub fn new(server: QuicServer) -> Self {
+        let local_address = server.local_addr()
+            .map(|addr| addr.to_string())
+            .unwrap_or_else(|_| "unknown".to_string());
+        Self {
+            server: Arc::new(server),
+            local_address,
+        }
+    }
+
+    /// Get the UDP port this listener is bound to
+    pub fn port(&self) -> u16 {
+        self.server.local_addr()
+            .map(|addr| addr.port())
+            .unwrap_or(0)
+    }
+
+    /// Get the connection guard (per-IP limits and bans)
+    pub fn guard(&self) -> &ConnectionGuard {
+        self.server.guard()
+    }
+}
+
+#[async_trait]
+impl TransportListener for QuicTransportListener {
+    async fn accept(&self) -> Result<Arc<dyn Transport>, TransportError> {
+        let connection = self.server.accept().await
+            .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
+
+        // Device ID is learned from the pairing request; use the peer address until then
+        let peer_addr = connection.peer_addr().to_string();
+        let transport = QuicTransport::accept(peer_addr, connection).await?;
+        Ok(Arc::new(transport))
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Quic
+    }
+
+    fn local_address(&self) -> String {
+        self.local_address.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bulk_classification() {
+        let device = "device".to_string();
+        assert!(is_bulk(&Message::clipboard_sync(b"hi", device.clone()), 10));
+        assert!(!is_bulk(&Message::heartbeat(device.clone()), 10));
+        assert!(is_bulk(&Message::heartbeat(device), QUIC_BULK_THRESHOLD));
+    }
+}
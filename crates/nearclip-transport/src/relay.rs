@@ -0,0 +1,206 @@
+//! Relay transport implementation
+//!
+//! Carries messages through a self-hosted `nearclip-relay` server when two
+//! devices share neither a LAN nor BLE range. All peers share the device's
+//! single [`RelayClient`] connection; each transport is one peer's inbox.
+//!
+//! The connector and listener only hand out transports wrapped in
+//! [`EncryptedTransport`], keyed by the pairing secret, so the relay only
+//! ever sees ciphertext.
+
+use async_trait::async_trait;
+use nearclip_relay::{RelayClient, RelayInbox};
+use nearclip_sync::{Channel, Message};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Mutex, Notify};
+use tracing::{debug, instrument, warn};
+
+use crate::encrypted::EncryptedTransport;
+use crate::error::TransportError;
+use crate::traits::{Transport, TransportConnector, TransportListener};
+
+/// Looks up the pairing shared secret for a device (`None` if not paired)
+pub type PeerSecretLookup = Arc<dyn Fn(&str) -> Option<Vec<u8>> + Send + Sync>;
+
+/// Relay transport for one peer
+///
+/// Messages are serialized and forwarded as opaque payloads. Use it through
+/// [`RelayTransportConnector`] / [`RelayTransportListener`], which add
+/// end-to-end encryption.
+pub struct RelayTransport {
+    device_id: String,
+    client: Arc<RelayClient>,
+    inbox: Mutex<RelayInbox>,
+    connected: AtomicBool,
+    /// Wakes a pending `recv` when the transport is closed
+    closed: Notify,
+}
+
+impl RelayTransport {
+    /// Create a relay transport for a peer
+    ///
+    /// # Arguments
+    /// * `device_id` - The peer device ID
+    /// * `client` - The shared relay connection
+    /// * `inbox` - The peer's inbox from `RelayClient::open` or `RelayClient::accept`
+    pub fn new(device_id: String, client: Arc<RelayClient>, inbox: RelayInbox) -> Self {
+        Self {
+            device_id,
+            client,
+            inbox: Mutex::new(inbox),
+            connected: AtomicBool::new(true),
+            closed: Notify::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for RelayTransport {
+    #[instrument(skip(self, msg), fields(device_id = %self.device_id))]
+    async fn send(&self, msg: &Message) -> Result<(), TransportError> {
+        if !self.is_connected() {
+            return Err(TransportError::ConnectionClosed);
+        }
+
+        let data = msg.serialize()
+            .map_err(|e| TransportError::Serialization(e.to_string()))?;
+        self.client.send(&self.device_id, data).await
+            .map_err(|e| TransportError::SendFailed(e.to_string()))?;
+
+        debug!("Relayed message to {}", self.device_id);
+        Ok(())
+    }
+
+    #[instrument(skip(self), fields(device_id = %self.device_id))]
+    async fn recv(&self) -> Result<Message, TransportError> {
+        let closed = self.closed.notified();
+        tokio::pin!(closed);
+        closed.as_mut().enable();
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err(TransportError::ConnectionClosed);
+        }
+
+        let mut inbox = self.inbox.lock().await;
+        let data = tokio::select! {
+            data = inbox.recv() => data.ok_or_else(|| {
+                self.connected.store(false, Ordering::SeqCst);
+                TransportError::ConnectionClosed
+            })?,
+            _ = closed => return Err(TransportError::ConnectionClosed),
+        };
+        Message::deserialize(&data)
+            .map_err(|e| TransportError::Deserialization(e.to_string()))
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+            && self.client.is_connected()
+            && self.client.is_peer_online(&self.device_id)
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Relay
+    }
+
+    fn peer_device_id(&self) -> &str {
+        &self.device_id
+    }
+
+    async fn close(&self) -> Result<(), TransportError> {
+        // The relay connection is shared with other peers and stays open
+        self.connected.store(false, Ordering::SeqCst);
+        self.closed.notify_waiters();
+        debug!("Relay transport closed for device {}", self.device_id);
+        Ok(())
+    }
+}
+
+/// Wrap a relay transport in end-to-end encryption
+fn encrypted(
+    device_id: &str,
+    client: &Arc<RelayClient>,
+    inbox: RelayInbox,
+    secrets: &PeerSecretLookup,
+) -> Result<Arc<dyn Transport>, TransportError> {
+    let secret = secrets(device_id)
+        .ok_or_else(|| TransportError::ConnectionFailed(format!("Device {} is not paired", device_id)))?;
+    let inner = Arc::new(RelayTransport::new(device_id.to_string(), client.clone(), inbox));
+    Ok(Arc::new(EncryptedTransport::new(inner, &secret)?))
+}
+
+/// Relay transport connector
+///
+/// Opens end-to-end encrypted transports to paired devices that are online
+/// at the relay. The address passed to `connect` is ignored.
+pub struct RelayTransportConnector {
+    client: Arc<RelayClient>,
+    secrets: PeerSecretLookup,
+}
+
+impl RelayTransportConnector {
+    /// Create a new relay transport connector
+    pub fn new(client: Arc<RelayClient>, secrets: PeerSecretLookup) -> Self {
+        Self { client, secrets }
+    }
+}
+
+#[async_trait]
+impl TransportConnector for RelayTransportConnector {
+    async fn connect(
+        &self,
+        device_id: &str,
+        _address: &str,
+    ) -> Result<Arc<dyn Transport>, TransportError> {
+        if !self.client.is_peer_online(device_id) {
+            return Err(TransportError::ConnectionFailed(format!(
+                "Device {} is not reachable through relay {}",
+                device_id,
+                self.client.relay_addr()
+            )));
+        }
+        encrypted(device_id, &self.client, self.client.open(device_id), &self.secrets)
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Relay
+    }
+}
+
+/// Relay transport listener
+///
+/// Yields an end-to-end encrypted transport when a paired device sends its
+/// first message through the relay. Payloads from unpaired devices are dropped.
+pub struct RelayTransportListener {
+    client: Arc<RelayClient>,
+    secrets: PeerSecretLookup,
+}
+
+impl RelayTransportListener {
+    /// Create a new relay transport listener
+    pub fn new(client: Arc<RelayClient>, secrets: PeerSecretLookup) -> Self {
+        Self { client, secrets }
+    }
+}
+
+#[async_trait]
+impl TransportListener for RelayTransportListener {
+    async fn accept(&self) -> Result<Arc<dyn Transport>, TransportError> {
+        loop {
+            let (device_id, inbox) = self.client.accept().await
+                .ok_or(TransportError::ConnectionClosed)?;
+            match encrypted(&device_id, &self.client, inbox, &self.secrets) {
+                Ok(transport) => return Ok(transport),
+                Err(e) => warn!("Ignoring relay peer {}: {}", device_id, e),
+            }
+        }
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Relay
+    }
+
+    fn local_address(&self) -> String {
+        format!("relay://{}", self.client.relay_addr())
+    }
+}
@@ -44,6 +44,13 @@ pub trait Transport: Send + Sync {
 
     /// Close the transport connection
     async fn close(&self) -> Result<(), TransportError>;
+
+    /// Mark the peer as authenticated
+    ///
+    /// Called once the peer has completed the pairing handshake. Transports
+    /// that apply stricter limits to unauthenticated peers (such as a smaller
+    /// maximum message size) lift them here. The default does nothing.
+    fn mark_authenticated(&self) {}
 }
 
 /// Transport connector - used to establish outbound connections
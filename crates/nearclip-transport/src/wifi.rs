@@ -1,27 +1,239 @@
 //! WiFi transport implementation using TCP/TLS
 
 use async_trait::async_trait;
-use nearclip_net::tcp::{TcpReadHalf, TcpWriteHalf, TcpConnection, TcpServer, TcpClient, TcpClientConfig};
+use bytes::Buf;
+use nearclip_net::tcp::{
+    ConnectionGuard, ConnectionLimits, TcpClient, TcpClientConfig, TcpConnection, TcpReadHalf, TcpServer, TcpWriteHalf,
+    TokenBucket,
+};
 use nearclip_sync::{Channel, Message};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{mpsc, oneshot, Mutex, Notify};
+use tokio::task::JoinHandle;
 use tracing::{debug, warn, instrument};
 
 use crate::error::TransportError;
+use crate::mux::{is_control, FrameDecoder, FrameHeader, Framing, MuxConfig, Multiplexer, MUX_ALPN};
 use crate::traits::{Transport, TransportConnector, TransportListener};
 
 /// Maximum message size (16 MB)
 const MAX_MESSAGE_SIZE: u32 = 16 * 1024 * 1024;
 
+/// Length prefix of a message without mux framing
+const LENGTH_PREFIX_SIZE: usize = 4;
+
+/// Received messages buffered before the reader applies backpressure
+const INCOMING_QUEUE_SIZE: usize = 64;
+
+/// Multiplexer plus senders waiting for their stream to be fully written
+struct MuxState {
+    mux: Multiplexer,
+    completions: HashMap<u32, oneshot::Sender<Result<(), TransportError>>>,
+}
+
+/// State shared between the transport and its reader and writer tasks
+struct Shared {
+    device_id: String,
+    framing: Framing,
+    state: std::sync::Mutex<MuxState>,
+    /// Wakes the writer task when frames or window credit become available
+    wake: Notify,
+    connected: AtomicBool,
+    authenticated: AtomicBool,
+    max_unauthenticated_message_size: u32,
+    rate_limiter: Option<std::sync::Mutex<TokenBucket>>,
+}
+
+impl Shared {
+    fn is_authenticated(&self) -> bool {
+        self.authenticated.load(Ordering::SeqCst)
+    }
+
+    /// Drop the connection and ban the peer's IP (inbound connections only)
+    fn reject_peer(&self, reader: &TcpReadHalf, reason: &str) -> TransportError {
+        warn!("Rejecting peer {} ({}): {}", self.device_id, reader.peer_addr(), reason);
+        if let Some(permit) = reader.permit() {
+            permit.ban();
+        }
+        self.connected.store(false, Ordering::SeqCst);
+        TransportError::ReceiveFailed(reason.to_string())
+    }
+
+    /// Fail the connection on a framing violation (banning unauthenticated peers)
+    fn protocol_violation(&self, reader: &TcpReadHalf, error: TransportError) -> TransportError {
+        if !self.is_authenticated() {
+            return self.reject_peer(reader, &error.to_string());
+        }
+        warn!("Framing error from {}: {}", self.device_id, error);
+        self.connected.store(false, Ordering::SeqCst);
+        error
+    }
+
+    /// Read whatever the connection has into the decoder's buffer
+    async fn fill(&self, reader: &mut TcpReadHalf, decoder: &mut FrameDecoder) -> Result<(), TransportError> {
+        let n = reader.read_buf(decoder.read_buf()).await
+            .map_err(|e| {
+                self.connected.store(false, Ordering::SeqCst);
+                TransportError::ReceiveFailed(e.to_string())
+            })?;
+        if n == 0 {
+            self.connected.store(false, Ordering::SeqCst);
+            return Err(TransportError::ConnectionClosed);
+        }
+        Ok(())
+    }
+
+    /// Validate a frame header before its payload is buffered
+    fn check_header(&self, reader: &TcpReadHalf, header: &FrameHeader) -> Result<(), TransportError> {
+        self.state.lock().unwrap().mux.config().check_header(header)
+            .map_err(|e| self.protocol_violation(reader, e))?;
+
+        if !self.is_authenticated() && header.length > self.max_unauthenticated_message_size {
+            return Err(self.reject_peer(
+                reader,
+                &format!(
+                    "Message too large before authentication: {} bytes (max {})",
+                    header.length, self.max_unauthenticated_message_size
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Read one length-prefixed message from a peer without mux framing
+    ///
+    /// The bytes are buffered in the decoder's read buffer; the length is
+    /// checked against the limits before the body is read.
+    async fn read_length_prefixed(
+        &self,
+        reader: &mut TcpReadHalf,
+        decoder: &mut FrameDecoder,
+    ) -> Result<Message, TransportError> {
+        while decoder.buffered() < LENGTH_PREFIX_SIZE {
+            self.fill(reader, decoder).await?;
+        }
+
+        if let Some(limiter) = &self.rate_limiter {
+            if !limiter.lock().unwrap().try_acquire() {
+                return Err(self.reject_peer(reader, "Message rate limit exceeded"));
+            }
+        }
+
+        let buf = decoder.read_buf();
+        let msg_len = u32::from_be_bytes([buf[0], buf[1], buf[2], buf[3]]);
+        if !self.is_authenticated() && msg_len > self.max_unauthenticated_message_size {
+            return Err(self.reject_peer(
+                reader,
+                &format!(
+                    "Message too large before authentication: {} bytes (max {})",
+                    msg_len, self.max_unauthenticated_message_size
+                ),
+            ));
+        }
+        if msg_len > MAX_MESSAGE_SIZE {
+            warn!("Message too large: {} bytes", msg_len);
+            self.connected.store(false, Ordering::SeqCst);
+            return Err(TransportError::ReceiveFailed(format!(
+                "Message too large: {} bytes (max {})",
+                msg_len, MAX_MESSAGE_SIZE
+            )));
+        }
+
+        while decoder.buffered() < LENGTH_PREFIX_SIZE + msg_len as usize {
+            self.fill(reader, decoder).await?;
+        }
+        let buf = decoder.read_buf();
+        buf.advance(LENGTH_PREFIX_SIZE);
+        let data = buf.split_to(msg_len as usize);
+
+        let msg = Message::deserialize(&data)
+            .map_err(|e| TransportError::Deserialization(e.to_string()))?;
+
+        debug!("Received message ({} bytes) from {}", data.len(), self.device_id);
+        Ok(msg)
+    }
+
+    /// Read frames until one completes a message
+    ///
+    /// Window updates are applied as they arrive and credit owed to the
+    /// peer is handed to the writer task.
+    async fn read_message(&self, reader: &mut TcpReadHalf, decoder: &mut FrameDecoder) -> Result<Message, TransportError> {
+        let data = loop {
+            let header = decoder.peek_header()
+                .map_err(|e| self.protocol_violation(reader, e))?;
+            let frame = match header {
+                Some(header) => {
+                    self.check_header(reader, &header)?;
+                    decoder.decode().map_err(|e| self.protocol_violation(reader, e))?
+                }
+                None => None,
+            };
+            let Some(frame) = frame else {
+                self.fill(reader, decoder).await?;
+                continue;
+            };
+
+            let (completed, wake_writer) = {
+                let mut state = self.state.lock().unwrap();
+                let completed = state.mux.receive(frame);
+                (completed, state.mux.has_pending())
+            };
+            if wake_writer {
+                self.wake.notify_one();
+            }
+            if let Some(msg) = completed.map_err(|e| self.protocol_violation(reader, e))? {
+                break msg.data;
+            }
+        };
+
+        if let Some(limiter) = &self.rate_limiter {
+            if !limiter.lock().unwrap().try_acquire() {
+                return Err(self.reject_peer(reader, "Message rate limit exceeded"));
+            }
+        }
+
+        // Deserialize
+        let msg = Message::deserialize(&data)
+            .map_err(|e| TransportError::Deserialization(e.to_string()))?;
+
+        debug!("Received message ({} bytes) from {}", data.len(), self.device_id);
+        Ok(msg)
+    }
+}
+
 /// WiFi transport using TCP/TLS
 ///
 /// Wraps a TLS-encrypted TCP connection and implements the Transport trait.
+/// Messages are carried in multiplexed frames (see [`crate::mux`]): a writer
+/// task sends frames round-robin, so heartbeats and acks keep flowing while a
+/// large clipboard transfer is in progress, and a reader task applies the
+/// peer's window updates even when nobody is calling `recv`.
+///
+/// The reader decodes frames out of one reusable [`FrameDecoder`] buffer, so
+/// memory grows with the bytes actually received rather than with a length
+/// announced by the peer.
+///
+/// Framing follows the ALPN protocol negotiated in the TLS handshake: if
+/// either side did not offer [`MUX_ALPN`], messages are sent whole behind a
+/// 4-byte big-endian length prefix, as peers without multiplexing expect.
+///
+/// Connections accepted by a `TcpServer` carry its `ConnectionLimits`: until
+/// `mark_authenticated` is called, messages larger than
+/// `max_unauthenticated_message_size` are rejected before any buffer is
+/// allocated, and every message is subject to a per-connection token bucket.
+/// Peers that break either limit are banned for `ban_duration`. Outbound
+/// connections are not limited.
+///
+/// Must be created from within a tokio runtime.
 pub struct WifiTransport {
-    device_id: String,
+    shared: Arc<Shared>,
     writer: Arc<Mutex<TcpWriteHalf>>,
     reader: Arc<Mutex<TcpReadHalf>>,
-    connected: AtomicBool,
+    incoming: Mutex<mpsc::Receiver<Result<Message, TransportError>>>,
+    writer_task: JoinHandle<()>,
+    reader_task: JoinHandle<()>,
 }
 
 impl WifiTransport {
@@ -32,133 +244,207 @@ impl WifiTransport {
     /// * `connection` - The TCP connection to wrap
     pub fn new(device_id: String, connection: TcpConnection) -> Self {
         let (reader, writer) = connection.into_split();
-        Self {
-            device_id,
-            writer: Arc::new(Mutex::new(writer)),
-            reader: Arc::new(Mutex::new(reader)),
-            connected: AtomicBool::new(true),
-        }
+        Self::from_split(device_id, reader, writer)
     }
 
     /// Create from pre-split connection halves
+    ///
+    /// The framing is taken from the reader's negotiated ALPN protocol.
     pub fn from_split(device_id: String, reader: TcpReadHalf, writer: TcpWriteHalf) -> Self {
-        Self {
+        let framing = Framing::from_alpn(reader.alpn_protocol());
+        let limits: Option<ConnectionLimits> = reader.permit().map(|p| p.limits().clone());
+        let max_unauthenticated_message_size = limits
+            .as_ref()
+            .map(|l| l.max_unauthenticated_message_size)
+            .unwrap_or(MAX_MESSAGE_SIZE);
+
+        let mux = Multiplexer::new(
+            MuxConfig::new().with_max_message_size(max_unauthenticated_message_size.min(MAX_MESSAGE_SIZE) as usize),
+        );
+        debug!("WiFi connection to {} uses {:?} framing", device_id, framing);
+        let shared = Arc::new(Shared {
             device_id,
-            writer: Arc::new(Mutex::new(writer)),
-            reader: Arc::new(Mutex::new(reader)),
+            framing,
+            state: std::sync::Mutex::new(MuxState { mux, completions: HashMap::new() }),
+            wake: Notify::new(),
             connected: AtomicBool::new(true),
+            authenticated: AtomicBool::new(limits.is_none()),
+            max_unauthenticated_message_size,
+            rate_limiter: limits.map(|l| std::sync::Mutex::new(l.rate_limiter())),
+        });
+
+        let writer = Arc::new(Mutex::new(writer));
+        let reader = Arc::new(Mutex::new(reader));
+        let (tx, rx) = mpsc::channel(INCOMING_QUEUE_SIZE);
+        let writer_task = tokio::spawn(run_writer(shared.clone(), writer.clone()));
+        let reader_task = tokio::spawn(run_reader(shared.clone(), reader.clone(), tx));
+
+        Self {
+            shared,
+            writer,
+            reader,
+            incoming: Mutex::new(rx),
+            writer_task,
+            reader_task,
         }
     }
 
+    /// Whether the peer has completed the pairing handshake
+    ///
+    /// Always true for outbound connections.
+    pub fn is_authenticated(&self) -> bool {
+        self.shared.is_authenticated()
+    }
+
+    /// Message framing negotiated for this connection
+    pub fn framing(&self) -> Framing {
+        self.shared.framing
+    }
+
     /// Get the writer half (for external use if needed)
+    ///
+    /// Bytes written directly bypass the framing and will corrupt the stream.
     pub fn writer(&self) -> Arc<Mutex<TcpWriteHalf>> {
         self.writer.clone()
     }
 
     /// Get the reader half (for external use if needed)
+    ///
+    /// The reader task holds this lock while the connection is open.
     pub fn reader(&self) -> Arc<Mutex<TcpReadHalf>> {
         self.reader.clone()
     }
 }
 
+impl Drop for WifiTransport {
+    fn drop(&mut self) {
+        self.writer_task.abort();
+        self.reader_task.abort();
+    }
+}
+
+/// Write frames as they become sendable until the connection fails
+async fn run_writer(shared: Arc<Shared>, writer: Arc<Mutex<TcpWriteHalf>>) {
+    loop {
+        let frame = shared.state.lock().unwrap().mux.next_frame();
+        let Some(frame) = frame else {
+            shared.wake.notified().await;
+            continue;
+        };
+
+        let result = {
+            let mut writer = writer.lock().await;
+            match writer.write_all(&frame.encode()).await {
+                Ok(()) => writer.flush().await,
+                Err(e) => Err(e),
+            }
+        };
+
+        let mut state = shared.state.lock().unwrap();
+        match result {
+            Ok(()) => {
+                if frame.completes_message() {
+                    if let Some(done) = state.completions.remove(&frame.stream_id) {
+                        let _ = done.send(Ok(()));
+                    }
+                }
+            }
+            Err(e) => {
+                debug!("WiFi writer for {} stopped: {}", shared.device_id, e);
+                shared.connected.store(false, Ordering::SeqCst);
+                for (_, done) in state.completions.drain() {
+                    let _ = done.send(Err(TransportError::SendFailed(e.to_string())));
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Read messages into the incoming queue until the connection fails
+async fn run_reader(
+    shared: Arc<Shared>,
+    reader: Arc<Mutex<TcpReadHalf>>,
+    incoming: mpsc::Sender<Result<Message, TransportError>>,
+) {
+    let mut reader = reader.lock().await;
+    let mut decoder = FrameDecoder::new();
+    loop {
+        let result = match shared.framing {
+            Framing::Mux => shared.read_message(&mut reader, &mut decoder).await,
+            Framing::LengthPrefixed => shared.read_length_prefixed(&mut reader, &mut decoder).await,
+        };
+        match result {
+            // A bad message body does not desynchronize the framing
+            Err(TransportError::Deserialization(e)) => {
+                let _ = incoming.send(Err(TransportError::Deserialization(e))).await;
+            }
+            Err(e) => {
+                shared.connected.store(false, Ordering::SeqCst);
+                let _ = incoming.send(Err(e)).await;
+                return;
+            }
+            Ok(msg) => {
+                if incoming.send(Ok(msg)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl Transport for WifiTransport {
-    #[instrument(skip(self, msg), fields(device_id = %self.device_id, msg_type = ?msg.msg_type))]
+    #[instrument(skip(self, msg), fields(device_id = %self.shared.device_id, msg_type = ?msg.msg_type))]
     async fn send(&self, msg: &Message) -> Result<(), TransportError> {
-        if !self.connected.load(Ordering::SeqCst) {
+        if !self.shared.connected.load(Ordering::SeqCst) {
             return Err(TransportError::ConnectionClosed);
         }
 
         let data = msg.serialize()
             .map_err(|e| TransportError::Serialization(e.to_string()))?;
+        let len = data.len();
+
+        if self.shared.framing == Framing::LengthPrefixed {
+            let mut writer = self.writer.lock().await;
+            let result = async {
+                writer.write_all(&(len as u32).to_be_bytes()).await?;
+                writer.write_all(&data).await?;
+                writer.flush().await
+            }
+            .await;
+            if let Err(e) = result {
+                self.shared.connected.store(false, Ordering::SeqCst);
+                return Err(TransportError::SendFailed(e.to_string()));
+            }
+            debug!("Sent message ({} bytes) to {}", len, self.shared.device_id);
+            return Ok(());
+        }
 
-        let mut writer = self.writer.lock().await;
-
-        // Write length prefix (4 bytes, big-endian)
-        let len_bytes = (data.len() as u32).to_be_bytes();
-        writer.write_all(&len_bytes).await
-            .map_err(|e| {
-                self.connected.store(false, Ordering::SeqCst);
-                TransportError::SendFailed(e.to_string())
-            })?;
-
-        // Write message data
-        writer.write_all(&data).await
-            .map_err(|e| {
-                self.connected.store(false, Ordering::SeqCst);
-                TransportError::SendFailed(e.to_string())
-            })?;
-
-        // Flush
-        writer.flush().await
-            .map_err(|e| {
-                self.connected.store(false, Ordering::SeqCst);
-                TransportError::SendFailed(e.to_string())
-            })?;
+        let (done_tx, done_rx) = oneshot::channel();
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            let stream_id = state.mux.queue(data, is_control(msg));
+            state.completions.insert(stream_id, done_tx);
+        }
+        self.shared.wake.notify_one();
 
-        debug!("Sent message ({} bytes) to {}", data.len(), self.device_id);
+        done_rx.await.unwrap_or(Err(TransportError::ConnectionClosed))?;
+        debug!("Sent message ({} bytes) to {}", len, self.shared.device_id);
         Ok(())
     }
 
-    #[instrument(skip(self), fields(device_id = %self.device_id))]
+    #[instrument(skip(self), fields(device_id = %self.shared.device_id))]
     async fn recv(&self) -> Result<Message, TransportError> {
-        if !self.connected.load(Ordering::SeqCst) {
-            return Err(TransportError::ConnectionClosed);
+        let mut incoming = self.incoming.lock().await;
+        match incoming.recv().await {
+            Some(result) => result,
+            None => Err(TransportError::ConnectionClosed),
         }
-
-        let mut reader = self.reader.lock().await;
-
-        // Read length prefix (4 bytes)
-        let mut len_buf = [0u8; 4];
-        let mut total_read = 0;
-        while total_read < 4 {
-            let n = reader.read(&mut len_buf[total_read..]).await
-                .map_err(|e| {
-                    self.connected.store(false, Ordering::SeqCst);
-                    TransportError::ReceiveFailed(e.to_string())
-                })?;
-            if n == 0 {
-                self.connected.store(false, Ordering::SeqCst);
-                return Err(TransportError::ConnectionClosed);
-            }
-            total_read += n;
-        }
-
-        let msg_len = u32::from_be_bytes(len_buf);
-        if msg_len > MAX_MESSAGE_SIZE {
-            warn!("Message too large: {} bytes", msg_len);
-            return Err(TransportError::ReceiveFailed(format!(
-                "Message too large: {} bytes (max {})",
-                msg_len, MAX_MESSAGE_SIZE
-            )));
-        }
-
-        // Read message data
-        let mut data = vec![0u8; msg_len as usize];
-        let mut total_read = 0;
-        while total_read < msg_len as usize {
-            let n = reader.read(&mut data[total_read..]).await
-                .map_err(|e| {
-                    self.connected.store(false, Ordering::SeqCst);
-                    TransportError::ReceiveFailed(e.to_string())
-                })?;
-            if n == 0 {
-                self.connected.store(false, Ordering::SeqCst);
-                return Err(TransportError::ConnectionClosed);
-            }
-            total_read += n;
-        }
-
-        // Deserialize
-        let msg = Message::deserialize(&data)
-            .map_err(|e| TransportError::Deserialization(e.to_string()))?;
-
-        debug!("Received message ({} bytes) from {}", data.len(), self.device_id);
-        Ok(msg)
     }
 
     fn is_connected(&self) -> bool {
-        self.connected.load(Ordering::SeqCst)
+        self.shared.connected.load(Ordering::SeqCst)
     }
 
     fn channel(&self) -> Channel {
@@ -166,14 +452,21 @@ impl Transport for WifiTransport {
     }
 
     fn peer_device_id(&self) -> &str {
-        &self.device_id
+        &self.shared.device_id
     }
 
     async fn close(&self) -> Result<(), TransportError> {
-        self.connected.store(false, Ordering::SeqCst);
-        debug!("WiFi transport closed for device {}", self.device_id);
+        self.shared.connected.store(false, Ordering::SeqCst);
+        self.writer_task.abort();
+        self.reader_task.abort();
+        debug!("WiFi transport closed for device {}", self.shared.device_id);
         Ok(())
     }
+
+    fn mark_authenticated(&self) {
+        self.shared.authenticated.store(true, Ordering::SeqCst);
+        self.shared.state.lock().unwrap().mux.set_max_message_size(MAX_MESSAGE_SIZE as usize);
+    }
 }
 
 /// WiFi transport connector
@@ -201,7 +494,7 @@ impl TransportConnector for WifiTransportConnector {
         let addr: std::net::SocketAddr = address.parse()
             .map_err(|e| TransportError::ConnectionFailed(format!("Invalid address: {}", e)))?;
 
-        let config = TcpClientConfig::new(addr);
+        let config = TcpClientConfig::new(addr).with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
         let connection = TcpClient::connect(config, self.tls_config.clone(), "nearclip.local").await
             .map_err(|e| TransportError::ConnectionFailed(e.to_string()))?;
 
@@ -224,6 +517,9 @@ pub struct WifiTransportListener {
 
 impl WifiTransportListener {
     /// Create a new WiFi transport listener
+    ///
+    /// Bind the server with [`MUX_ALPN`] among its ALPN protocols to accept
+    /// multiplexed framing; otherwise every connection is length-prefixed.
     pub fn new(server: TcpServer) -> Self {
         let local_address = server.local_addr()
             .map(|addr| addr.to_string())
@@ -240,6 +536,11 @@ impl WifiTransportListener {
             .map(|addr| addr.port())
             .unwrap_or(0)
     }
+
+    /// Get the connection guard (per-IP limits and bans)
+    pub fn guard(&self) -> &ConnectionGuard {
+        self.server.guard()
+    }
 }
 
 #[async_trait]
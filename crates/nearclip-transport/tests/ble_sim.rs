@@ -0,0 +1,366 @@
+//! BLE transport over the simulated air
+//!
+//! These tests run two encrypted `BleTransport`s over `SimAir` and verify:
+//! - Clipboard sync in both directions, chunked to the negotiated MTU
+//! - ACKs travel back over DATA_ACK
+//! - Lost chunks are retransmitted selectively, even on a lossy link
+//! - L2CAP is used when both sides support it, GATT otherwise
+//! - Chunks follow the negotiated data length; throughput is measured
+//! - The v2 chunk header is used when the peer supports it, v1 otherwise
+//! - Multiplexed framing is negotiated; older peers get whole messages
+//! - A dropped link surfaces as a send error
+
+use nearclip_ble::{
+    BleHardware, BleHardwareEvent, BlePhy, ChunkVersion, ConnectionPriority, SimAir, SimAirConfig, SimBleHardware,
+    SimDeviceConfig, DATA_ACK_CHARACTERISTIC_UUID, DATA_TRANSFER_CHARACTERISTIC_UUID, MAX_DATA_LENGTH,
+};
+use nearclip_crypto::EcdhKeyPair;
+use nearclip_sync::Message;
+use nearclip_transport::{BleHardwareSender, BleTransport, Framing, Transport, TransportError};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Route hardware events to a transport the way the platform bridge does
+///
+/// Returns the number of GATT data chunks received so far.
+fn route(
+    transport: Arc<BleTransport>,
+    mut events: mpsc::UnboundedReceiver<BleHardwareEvent>,
+) -> Arc<AtomicUsize> {
+    let data_uuid = DATA_TRANSFER_CHARACTERISTIC_UUID.to_string();
+    let ack_uuid = DATA_ACK_CHARACTERISTIC_UUID.to_string();
+    let gatt_chunks = Arc::new(AtomicUsize::new(0));
+    let counter = gatt_chunks.clone();
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            match event {
+                BleHardwareEvent::DataReceived { char_uuid, data, .. } if char_uuid == data_uuid => {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    transport.on_data_received(&data).await;
+                }
+                BleHardwareEvent::DataReceived { char_uuid, data, .. } if char_uuid == ack_uuid => {
+                    transport.on_ack_data_received(&data).await;
+                }
+                BleHardwareEvent::L2capOpened { .. } => transport.on_l2cap_state_changed(true),
+                BleHardwareEvent::L2capClosed { .. } => transport.on_l2cap_state_changed(false),
+                BleHardwareEvent::L2capDataReceived { data, .. } => {
+                    transport.on_l2cap_data_received(&data).await;
+                }
+                BleHardwareEvent::Disconnected { .. } => transport.on_connection_state_changed(false),
+                _ => {}
+            }
+        }
+    });
+    gatt_chunks
+}
+
+struct Pair {
+    air: Arc<SimAir>,
+    phone_address: String,
+    laptop_address: String,
+    phone: Arc<BleTransport>,
+    laptop: Arc<BleTransport>,
+    phone_hw: Arc<SimBleHardware>,
+    /// GATT data chunks received by the laptop
+    laptop_gatt_chunks: Arc<AtomicUsize>,
+}
+
+/// Connect a phone (central) to a laptop (peripheral) and build both transports
+async fn connected_pair(config: SimAirConfig) -> Pair {
+    connected_pair_with(config, SimDeviceConfig::new("laptop".into(), "laptop-hash".into())).await
+}
+
+async fn connected_pair_with(config: SimAirConfig, laptop_config: SimDeviceConfig) -> Pair {
+    let air = SimAir::new(config);
+    let (phone_hw, mut phone_events) =
+        air.add_device(SimDeviceConfig::new("phone".into(), "phone-hash".into()));
+    let (laptop_hw, mut laptop_events) = air.add_device(laptop_config);
+    let phone_address = phone_hw.address().to_string();
+    let laptop_address = laptop_hw.address().to_string();
+
+    laptop_hw.start_advertising(&[]);
+    phone_hw.connect(&laptop_address);
+    assert!(matches!(phone_events.recv().await, Some(BleHardwareEvent::Connected { .. })));
+    assert!(matches!(laptop_events.recv().await, Some(BleHardwareEvent::CentralConnected { .. })));
+    phone_hw
+        .subscribe_characteristic(&laptop_address, &DATA_TRANSFER_CHARACTERISTIC_UUID.to_string())
+        .unwrap();
+
+    let keypair_a = EcdhKeyPair::generate();
+    let keypair_b = EcdhKeyPair::generate();
+    let secret = keypair_a.compute_shared_secret(&keypair_b.public_key_bytes()).unwrap();
+
+    let phone_hw: Arc<SimBleHardware> = Arc::new(phone_hw);
+    let laptop_hw: Arc<SimBleHardware> = Arc::new(laptop_hw);
+    let phone = Arc::new(
+        BleTransport::new(laptop_address.clone(), Arc::new(BleHardwareSender::new(phone_hw.clone())), Some(&secret))
+            .unwrap(),
+    );
+    let laptop = Arc::new(
+        BleTransport::new(phone_address.clone(), Arc::new(BleHardwareSender::new(laptop_hw)), Some(&secret))
+            .unwrap(),
+    );
+    // The central reads the peer's capabilities, like the platform bridge does
+    phone.negotiate_framing();
+    route(phone.clone(), phone_events);
+    let laptop_gatt_chunks = route(laptop.clone(), laptop_events);
+
+    Pair {
+        air,
+        phone_address,
+        laptop_address,
+        phone,
+        laptop,
+        phone_hw,
+        laptop_gatt_chunks,
+    }
+}
+
+async fn recv(transport: &BleTransport) -> Message {
+    tokio::time::timeout(Duration::from_secs(30), transport.recv())
+        .await
+        .expect("message should arrive")
+        .unwrap()
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_encrypted_clipboard_sync_both_directions() {
+    let pair = connected_pair(SimAirConfig::new()).await;
+
+    // Several MTU-sized chunks per message
+    let content = "clipboard ".repeat(300);
+    let started = tokio::time::Instant::now();
+    pair.phone
+        .send(&Message::clipboard_sync(content.as_bytes(), "phone".into()))
+        .await
+        .unwrap();
+    // Returned on ACK, well before the 5 s ACK timeout
+    assert!(started.elapsed() < Duration::from_secs(1));
+
+    let received = recv(&pair.laptop).await;
+    assert_eq!(received.payload, content.as_bytes());
+    assert_eq!(received.device_id, "phone");
+
+    pair.laptop
+        .send(&Message::clipboard_sync(b"reply", "laptop".into()))
+        .await
+        .unwrap();
+    let reply = recv(&pair.phone).await;
+    assert_eq!(reply.payload, b"reply");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_lost_chunk_is_retransmitted() {
+    let pair = connected_pair(SimAirConfig::new()).await;
+
+    pair.air.drop_next(1);
+    let content = "x".repeat(1000);
+    let started = tokio::time::Instant::now();
+    pair.phone
+        .send(&Message::clipboard_sync(content.as_bytes(), "phone".into()))
+        .await
+        .unwrap();
+    // Recovered from the receiver's report, not the retransmission timer
+    assert!(started.elapsed() < Duration::from_millis(500));
+    assert_eq!(recv(&pair.laptop).await.payload, content.as_bytes());
+
+    pair.phone
+        .send(&Message::clipboard_sync(b"next", "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, b"next");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_sync_survives_seeded_packet_loss() {
+    let pair = connected_pair(SimAirConfig::new().with_loss_rate(0.2).with_seed(3)).await;
+
+    // Reports and ACKs are lost too; every clip still arrives intact
+    let clips: Vec<String> = (0..5).map(|i| format!("clip {} ", i).repeat(200)).collect();
+    for clip in &clips {
+        pair.phone
+            .send(&Message::clipboard_sync(clip.as_bytes(), "phone".into()))
+            .await
+            .unwrap();
+    }
+    for clip in &clips {
+        assert_eq!(recv(&pair.laptop).await.payload, clip.as_bytes());
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_dropped_link_fails_send() {
+    let pair = connected_pair(SimAirConfig::new()).await;
+    assert!(pair.air.inject_disconnect(&pair.phone_address, &pair.laptop_address, "supervision timeout"));
+    let result = pair
+        .phone
+        .send(&Message::clipboard_sync(b"lost", "phone".into()))
+        .await;
+    assert!(matches!(result, Err(TransportError::ConnectionClosed)));
+}
+
+/// Open L2CAP from the phone and wait until both ends use it
+async fn open_l2cap(pair: &Pair) {
+    pair.phone.open_l2cap().unwrap();
+    tokio::time::timeout(Duration::from_secs(1), async {
+        while !(pair.phone.is_l2cap_open() && pair.laptop.is_l2cap_open()) {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+    })
+    .await
+    .expect("L2CAP channel should open");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_bulk_data_uses_l2cap_when_both_sides_support_it() {
+    let pair = connected_pair_with(
+        SimAirConfig::new(),
+        SimDeviceConfig::new("laptop".into(), "laptop-hash".into()).with_l2cap_psm(0x0081),
+    )
+    .await;
+    open_l2cap(&pair).await;
+
+    let content = "clipboard ".repeat(10_000);
+    pair.phone
+        .send(&Message::clipboard_sync(content.as_bytes(), "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, content.as_bytes());
+    assert_eq!(pair.laptop_gatt_chunks.load(Ordering::SeqCst), 0);
+
+    pair.laptop
+        .send(&Message::clipboard_sync(b"reply", "laptop".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.phone).await.payload, b"reply");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_falls_back_to_gatt_without_l2cap() {
+    let pair = connected_pair(SimAirConfig::new()).await;
+
+    // The laptop publishes no PSM
+    assert!(pair.phone.open_l2cap().is_err());
+    pair.phone
+        .send(&Message::clipboard_sync(b"over gatt", "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, b"over gatt");
+    assert!(pair.laptop_gatt_chunks.load(Ordering::SeqCst) > 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_closed_l2cap_channel_falls_back_to_gatt() {
+    let pair = connected_pair_with(
+        SimAirConfig::new(),
+        SimDeviceConfig::new("laptop".into(), "laptop-hash".into()).with_l2cap_psm(0x0081),
+    )
+    .await;
+    open_l2cap(&pair).await;
+
+    assert!(pair.air.inject_l2cap_close(&pair.phone_address, &pair.laptop_address, "peer closed"));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!pair.phone.is_l2cap_open());
+
+    pair.phone
+        .send(&Message::clipboard_sync(b"after close", "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, b"after close");
+    assert!(pair.laptop_gatt_chunks.load(Ordering::SeqCst) > 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_multi_frame_clip_survives_packet_loss() {
+    let pair = connected_pair(SimAirConfig::new().with_loss_rate(0.1).with_seed(13)).await;
+
+    // Several multiplexed frames per clip; a lost chunk must not reorder them
+    let clip = (0..20_000u32).map(|i| (i % 251) as u8).collect::<Vec<u8>>();
+    pair.phone
+        .send(&Message::clipboard_sync(&clip, "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, clip);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_link_tuning_sizes_chunks_and_reports_throughput() {
+    let laptop_config = SimDeviceConfig::new("laptop".into(), "laptop-hash".into()).with_mtu(100);
+    let pair = connected_pair_with(SimAirConfig::new(), laptop_config).await;
+    let content = "y".repeat(4000);
+    assert_eq!(pair.phone.effective_throughput(), None);
+
+    // 100-byte MTU without DLE: chunks are trimmed to three 27-byte packets
+    pair.phone
+        .send(&Message::clipboard_sync(content.as_bytes(), "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, content.as_bytes());
+    let untuned_chunks = pair.laptop_gatt_chunks.swap(0, Ordering::SeqCst);
+    assert!(pair.phone.effective_throughput().unwrap() > 0);
+
+    let laptop = pair.laptop_address.as_str();
+    pair.phone_hw.request_connection_priority(laptop, ConnectionPriority::High).unwrap();
+    pair.phone_hw.request_phy(laptop, BlePhy::Le2M).unwrap();
+    pair.phone_hw.request_data_length(laptop, MAX_DATA_LENGTH).unwrap();
+    let link = pair.phone.link_parameters();
+    assert_eq!((link.phy, link.data_length), (BlePhy::Le2M, MAX_DATA_LENGTH));
+    assert_eq!(link.chunk_mtu(), 100);
+
+    // With DLE every chunk uses the full MTU
+    pair.phone
+        .send(&Message::clipboard_sync(content.as_bytes(), "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, content.as_bytes());
+    assert!(pair.laptop_gatt_chunks.load(Ordering::SeqCst) < untuned_chunks);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_chunk_v2_used_when_peer_supports_it() {
+    let pair = connected_pair(SimAirConfig::new().with_loss_rate(0.1).with_seed(7)).await;
+    assert_eq!(pair.phone.negotiate_chunk_version(), ChunkVersion::V2);
+    assert_eq!(pair.laptop.chunk_version(), ChunkVersion::V1);
+
+    let content = "v2 clip ".repeat(500);
+    pair.phone
+        .send(&Message::clipboard_sync(content.as_bytes(), "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, content.as_bytes());
+
+    // The peripheral learns from the chunks it received and answers in v2
+    assert_eq!(pair.laptop.chunk_version(), ChunkVersion::V2);
+    pair.laptop
+        .send(&Message::clipboard_sync(b"reply", "laptop".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.phone).await.payload, b"reply");
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_old_peer_keeps_chunk_v1() {
+    let laptop_config = SimDeviceConfig::new("laptop".into(), "laptop-hash".into())
+        .with_chunk_version(ChunkVersion::V1)
+        .with_mux_framing(false);
+    let pair = connected_pair_with(SimAirConfig::new(), laptop_config).await;
+    assert_eq!(pair.phone.framing(), Framing::LengthPrefixed);
+    assert_eq!(pair.phone.negotiate_chunk_version(), ChunkVersion::V1);
+
+    let content = "v1 clip ".repeat(100);
+    pair.phone
+        .send(&Message::clipboard_sync(content.as_bytes(), "phone".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.laptop).await.payload, content.as_bytes());
+    assert_eq!(pair.laptop.chunk_version(), ChunkVersion::V1);
+    assert_eq!(pair.laptop.framing(), Framing::LengthPrefixed);
+
+    pair.laptop
+        .send(&Message::clipboard_sync(b"reply", "laptop".into()))
+        .await
+        .unwrap();
+    assert_eq!(recv(&pair.phone).await.payload, b"reply");
+}
@@ -42,7 +42,7 @@ pub struct MockBleTransport {
     /// MTU for chunking
     mtu: usize,
     /// Reassemblers for incoming chunks (message_id -> reassembler)
-    pub reassemblers: Arc<Mutex<HashMap<u16, Reassembler>>>,
+    pub reassemblers: Arc<Mutex<HashMap<u32, Reassembler>>>,
     /// Next message ID
     next_message_id: Arc<AtomicU16>,
 }
@@ -147,7 +147,7 @@ impl MockBleTransport {
     pub async fn process_chunk(
         &self,
         chunk: &[u8],
-        reassemblers: &mut HashMap<u16, Reassembler>,
+        reassemblers: &mut HashMap<u32, Reassembler>,
     ) -> Result<(), TransportError> {
         if chunk.len() < CHUNK_HEADER_SIZE {
             return Err(TransportError::Other(format!(
@@ -0,0 +1,119 @@
+//! Relay transport integration tests
+//!
+//! Two paired devices reach each other through a local relay server. The
+//! transports are end-to-end encrypted and rank below every local channel
+//! in `TransportManager`.
+
+use nearclip_crypto::{EcdhKeyPair, TlsCertificate, TlsClientConfig, TlsServerConfig};
+use nearclip_relay::{RelayClient, RelayServer, RelayServerConfig, RELAY_SERVER_NAME};
+use nearclip_sync::{Channel, Message};
+use nearclip_transport::{
+    MockConfig, MockTransport, PeerSecretLookup, RelayTransportConnector, RelayTransportListener,
+    TransportConnector, TransportListener, TransportManager,
+};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SECRET: [u8; 32] = [42u8; 32];
+
+/// Start a relay and connect two mutually paired devices to it
+async fn setup() -> (Arc<RelayClient>, Arc<RelayClient>, tokio::task::JoinHandle<()>) {
+    let cert = TlsCertificate::generate(&[RELAY_SERVER_NAME.to_string()]).unwrap();
+    let config = RelayServerConfig::new().with_bind_addr("127.0.0.1:0".parse().unwrap());
+    let server = RelayServer::bind(config, TlsServerConfig::new(&cert).unwrap().config())
+        .await
+        .unwrap();
+    let addr = server.local_addr().unwrap();
+    let relay_task = tokio::spawn(async move { server.run().await });
+
+    let tls = TlsClientConfig::new(cert.cert_der()).unwrap().config();
+    let (key_a, key_b) = (EcdhKeyPair::generate(), EcdhKeyPair::generate());
+    let a = Arc::new(RelayClient::connect(addr, tls.clone(), "device-a", &key_a).await.unwrap());
+    let b = Arc::new(RelayClient::connect(addr, tls, "device-b", &key_b).await.unwrap());
+    a.allow_peers(["device-b".to_string()]).await.unwrap();
+    b.allow_peers(["device-a".to_string()]).await.unwrap();
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !a.is_peer_online("device-b") || !b.is_peer_online("device-a") {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("peers should see each other");
+
+    (a, b, relay_task)
+}
+
+fn paired_with(peer: &'static str) -> PeerSecretLookup {
+    Arc::new(move |device_id: &str| (device_id == peer).then(|| SECRET.to_vec()))
+}
+
+#[tokio::test]
+async fn test_relay_transport_roundtrip() {
+    let (a, b, relay_task) = setup().await;
+    let connector = RelayTransportConnector::new(a, paired_with("device-b"));
+    let listener = RelayTransportListener::new(b, paired_with("device-a"));
+
+    let client = connector.connect("device-b", "").await.unwrap();
+    assert_eq!(client.channel(), Channel::Relay);
+    assert!(client.is_connected());
+
+    let msg = Message::clipboard_sync(b"across networks", "device-a".to_string());
+    client.send(&msg).await.unwrap();
+
+    let server = tokio::time::timeout(Duration::from_secs(5), listener.accept()).await.unwrap().unwrap();
+    assert_eq!(server.peer_device_id(), "device-a");
+    let received = tokio::time::timeout(Duration::from_secs(5), server.recv()).await.unwrap().unwrap();
+    assert_eq!(received.payload, msg.payload);
+
+    server.send(&Message::ack_for(msg.message_id, "device-b".to_string())).await.unwrap();
+    let ack = tokio::time::timeout(Duration::from_secs(5), client.recv()).await.unwrap().unwrap();
+    assert_eq!(ack.acked_message_id(), Some(msg.message_id));
+
+    relay_task.abort();
+}
+
+#[tokio::test]
+async fn test_relay_connector_requires_pairing_secret() {
+    let (a, _b, relay_task) = setup().await;
+    let connector = RelayTransportConnector::new(a, Arc::new(|_: &str| None));
+
+    assert!(connector.connect("device-b", "").await.is_err());
+    assert!(connector.connect("device-c", "").await.is_err());
+
+    relay_task.abort();
+}
+
+#[tokio::test]
+async fn test_manager_prefers_local_channels_over_relay() {
+    let (a, _b, relay_task) = setup().await;
+    let connector = RelayTransportConnector::new(a, paired_with("device-b"));
+    let manager = TransportManager::new();
+
+    manager.add_transport("device-b", connector.connect("device-b", "").await.unwrap()).await;
+    assert_eq!(manager.get_best_transport("device-b").await.unwrap().channel(), Channel::Relay);
+
+    let ble = Arc::new(MockTransport::new("device-b", MockConfig::new().with_channel(Channel::Ble)));
+    manager.add_transport("device-b", ble).await;
+    assert_eq!(manager.get_best_transport("device-b").await.unwrap().channel(), Channel::Ble);
+
+    relay_task.abort();
+}
+
+#[tokio::test]
+async fn test_relay_close_wakes_pending_recv() {
+    let (a, _b, relay_task) = setup().await;
+    let connector = RelayTransportConnector::new(a, paired_with("device-b"));
+    let transport = connector.connect("device-b", "").await.unwrap();
+
+    let receiver = transport.clone();
+    let pending = tokio::spawn(async move { receiver.recv().await });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    tokio::time::timeout(Duration::from_secs(5), transport.close()).await.unwrap().unwrap();
+    let result = tokio::time::timeout(Duration::from_secs(5), pending).await.unwrap().unwrap();
+    assert!(result.is_err());
+    assert!(!transport.is_connected());
+
+    relay_task.abort();
+}
@@ -0,0 +1,106 @@
+//! WiFi listener limit integration tests
+//!
+//! A local client connects to a `WifiTransportListener` over TLS and
+//! breaks the pre-authentication message size limit or the message rate
+//! limit. The server side must reject the message and ban the client IP,
+//! with multiplexed framing and with length-prefixed framing alike.
+
+use nearclip_crypto::{TlsCertificate, TlsClientConfig, TlsServerConfig};
+use nearclip_net::{ConnectionLimits, TcpServer, TcpServerConfig};
+use nearclip_sync::{Message, MessageType};
+use nearclip_transport::{
+    Transport, TransportConnector, TransportListener, WifiTransportConnector, WifiTransportListener, MUX_ALPN,
+};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+
+const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+async fn connect_pair(
+    limits: ConnectionLimits,
+) -> (WifiTransportListener, Arc<dyn Transport>, Arc<dyn Transport>) {
+    connect_pair_with(limits, vec![MUX_ALPN.to_vec()]).await
+}
+
+/// Connect to a listener offering the given ALPN protocols
+async fn connect_pair_with(
+    limits: ConnectionLimits,
+    alpn_protocols: Vec<Vec<u8>>,
+) -> (WifiTransportListener, Arc<dyn Transport>, Arc<dyn Transport>) {
+    let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+    let server_tls = TlsServerConfig::new(&cert).unwrap().config();
+    let client_tls = TlsClientConfig::new(cert.cert_der()).unwrap().config();
+
+    let config = TcpServerConfig::new()
+        .with_bind_addr(LOCALHOST)
+        .with_limits(limits)
+        .with_alpn_protocols(alpn_protocols);
+    let listener = WifiTransportListener::new(TcpServer::bind(config, server_tls).await.unwrap());
+    let address = format!("127.0.0.1:{}", listener.port());
+
+    let connector = WifiTransportConnector::new(client_tls);
+    let (client, server) = tokio::join!(connector.connect("server", &address), listener.accept());
+    (listener, client.unwrap(), server.unwrap())
+}
+
+fn message(size: usize) -> Message {
+    Message::new(MessageType::Heartbeat, vec![0u8; size], "client".to_string())
+}
+
+#[tokio::test]
+async fn test_oversized_message_before_authentication_bans_peer() {
+    let limits = ConnectionLimits::new().with_max_unauthenticated_message_size(1024);
+    let (listener, client, server) = connect_pair(limits).await;
+
+    client.send(&message(16)).await.unwrap();
+    assert!(server.recv().await.is_ok());
+
+    client.send(&message(4096)).await.unwrap();
+    assert!(server.recv().await.is_err());
+    assert!(!server.is_connected());
+    assert!(listener.guard().is_banned(LOCALHOST));
+}
+
+#[tokio::test]
+async fn test_large_message_allowed_after_authentication() {
+    let limits = ConnectionLimits::new().with_max_unauthenticated_message_size(1024);
+    let (listener, client, server) = connect_pair(limits).await;
+
+    server.mark_authenticated();
+    client.send(&message(64 * 1024)).await.unwrap();
+    assert_eq!(server.recv().await.unwrap().payload.len(), 64 * 1024);
+    assert!(!listener.guard().is_banned(LOCALHOST));
+
+    // Outbound connections are never limited
+    server.send(&message(64 * 1024)).await.unwrap();
+    assert!(client.recv().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_message_flood_bans_peer() {
+    let limits = ConnectionLimits::new().with_message_rate(1, 3);
+    let (listener, client, server) = connect_pair(limits).await;
+
+    for _ in 0..5 {
+        client.send(&message(16)).await.unwrap();
+    }
+    for _ in 0..3 {
+        assert!(server.recv().await.is_ok());
+    }
+    assert!(server.recv().await.is_err());
+    assert!(listener.guard().is_banned(LOCALHOST));
+}
+
+#[tokio::test]
+async fn test_limits_apply_to_length_prefixed_framing() {
+    let limits = ConnectionLimits::new().with_max_unauthenticated_message_size(1024);
+    let (listener, client, server) = connect_pair_with(limits, Vec::new()).await;
+
+    client.send(&message(16)).await.unwrap();
+    assert!(server.recv().await.is_ok());
+
+    client.send(&message(4096)).await.unwrap();
+    assert!(server.recv().await.is_err());
+    assert!(!server.is_connected());
+    assert!(listener.guard().is_banned(LOCALHOST));
+}
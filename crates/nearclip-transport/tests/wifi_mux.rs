@@ -0,0 +1,184 @@
+//! WiFi multiplexing integration tests
+//!
+//! A heartbeat sent while a large clip is in flight over the same TLS
+//! connection must overtake the clip instead of waiting behind it.
+//!
+//! Peers that do not offer the mux ALPN protocol keep exchanging
+//! length-prefixed messages with both the new listener and the new connector.
+
+use nearclip_crypto::{TlsCertificate, TlsClientConfig, TlsServerConfig};
+use nearclip_net::{TcpClient, TcpClientConfig, TcpServer, TcpServerConfig};
+use nearclip_sync::{Message, MessageType};
+use nearclip_transport::{
+    Framing, Transport, TransportConnector, TransportListener, WifiTransport, WifiTransportConnector,
+    WifiTransportListener, MUX_ALPN,
+};
+use std::net::{IpAddr, Ipv4Addr};
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn connect_pair() -> (Arc<dyn Transport>, Arc<dyn Transport>) {
+    let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+    let server_tls = TlsServerConfig::new(&cert).unwrap().config();
+    let client_tls = TlsClientConfig::new(cert.cert_der()).unwrap().config();
+
+    let config = TcpServerConfig::new()
+        .with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        .with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
+    let listener = WifiTransportListener::new(TcpServer::bind(config, server_tls).await.unwrap());
+    let address = format!("127.0.0.1:{}", listener.port());
+
+    let connector = WifiTransportConnector::new(client_tls);
+    let (client, server) = tokio::join!(connector.connect("server", &address), listener.accept());
+    let server = server.unwrap();
+    server.mark_authenticated();
+    (client.unwrap(), server)
+}
+
+async fn recv(transport: &Arc<dyn Transport>) -> Message {
+    tokio::time::timeout(Duration::from_secs(10), transport.recv())
+        .await
+        .expect("message should arrive")
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_heartbeat_overtakes_large_clip() {
+    let (client, server) = connect_pair().await;
+
+    let clip = Message::clipboard_sync(&vec![7u8; 4 * 1024 * 1024], "client".to_string());
+    let sender = client.clone();
+    let clip_task = tokio::spawn(async move { sender.send(&clip).await });
+    tokio::task::yield_now().await;
+
+    client.send(&Message::heartbeat("client".to_string())).await.unwrap();
+
+    // The clip is stuck behind flow control until the server reads, so the
+    // heartbeat must come out first
+    assert_eq!(recv(&server).await.msg_type, MessageType::Heartbeat);
+    let received = recv(&server).await;
+    assert_eq!(received.msg_type, MessageType::ClipboardSync);
+    assert_eq!(received.payload.len(), 4 * 1024 * 1024);
+    assert!(clip_task.await.unwrap().is_ok());
+}
+
+#[tokio::test]
+async fn test_messages_flow_both_ways_during_transfer() {
+    let (client, server) = connect_pair().await;
+
+    let clip = Message::clipboard_sync(&vec![1u8; 1024 * 1024], "client".to_string());
+    let sender = client.clone();
+    let clip_task = tokio::spawn(async move { sender.send(&clip).await });
+
+    // The server answers heartbeats over the same connection mid-transfer
+    server.send(&Message::heartbeat("server".to_string())).await.unwrap();
+    assert_eq!(recv(&client).await.msg_type, MessageType::Heartbeat);
+
+    assert_eq!(recv(&server).await.payload.len(), 1024 * 1024);
+    assert!(clip_task.await.unwrap().is_ok());
+}
+
+/// Length-prefix a message the way peers without multiplexing frame it
+fn length_prefixed(msg: &Message) -> Vec<u8> {
+    let data = msg.serialize().unwrap();
+    let mut framed = (data.len() as u32).to_be_bytes().to_vec();
+    framed.extend_from_slice(&data);
+    framed
+}
+
+#[tokio::test]
+async fn test_client_without_mux_alpn_uses_length_prefix() {
+    let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+    let server_tls = TlsServerConfig::new(&cert).unwrap().config();
+    let client_tls = TlsClientConfig::new(cert.cert_der()).unwrap().config();
+
+    let config = TcpServerConfig::new()
+        .with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        .with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
+    let server = TcpServer::bind(config, server_tls).await.unwrap();
+    let addr = server.local_addr().unwrap();
+
+    // An old client offers no ALPN and writes a length-prefixed message
+    let (client, accepted) = tokio::join!(
+        TcpClient::connect(TcpClientConfig::new(addr), client_tls, "nearclip.local"),
+        server.accept()
+    );
+    let mut client = client.unwrap();
+    let server = WifiTransport::new("client".to_string(), accepted.unwrap());
+    assert_eq!(server.framing(), Framing::LengthPrefixed);
+
+    client
+        .write_all(&length_prefixed(&Message::heartbeat("client".to_string())))
+        .await
+        .unwrap();
+    client.flush().await.unwrap();
+    let received = tokio::time::timeout(Duration::from_secs(10), server.recv()).await.unwrap().unwrap();
+    assert_eq!(received.msg_type, MessageType::Heartbeat);
+
+    let reply = Message::clipboard_sync(b"reply", "server".to_string());
+    server.send(&reply).await.unwrap();
+    let expected = length_prefixed(&reply);
+    let mut buf = vec![0u8; expected.len()];
+    let mut read = 0;
+    while read < buf.len() {
+        read += client.read(&mut buf[read..]).await.unwrap();
+    }
+    assert_eq!(buf, expected);
+}
+
+#[tokio::test]
+async fn test_connector_falls_back_to_length_prefix_for_old_server() {
+    let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+    let server_tls = TlsServerConfig::new(&cert).unwrap().config();
+    let client_tls = TlsClientConfig::new(cert.cert_der()).unwrap().config();
+
+    // An old server offers no ALPN
+    let config = TcpServerConfig::new().with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST));
+    let server = TcpServer::bind(config, server_tls).await.unwrap();
+    let address = server.local_addr().unwrap().to_string();
+
+    let connector = WifiTransportConnector::new(client_tls);
+    let (client, accepted) = tokio::join!(connector.connect("server", &address), server.accept());
+    let client = client.unwrap();
+    let mut server = accepted.unwrap();
+
+    let msg = Message::clipboard_sync(b"to old server", "client".to_string());
+    client.send(&msg).await.unwrap();
+    let expected = length_prefixed(&msg);
+    let mut buf = vec![0u8; expected.len()];
+    let mut read = 0;
+    while read < buf.len() {
+        read += server.read(&mut buf[read..]).await.unwrap();
+    }
+    assert_eq!(buf, expected);
+
+    server
+        .write_all(&length_prefixed(&Message::heartbeat("server".to_string())))
+        .await
+        .unwrap();
+    server.flush().await.unwrap();
+    assert_eq!(recv(&client).await.msg_type, MessageType::Heartbeat);
+}
+
+#[tokio::test]
+async fn test_mux_negotiated_between_new_peers() {
+    let cert = TlsCertificate::generate(&["nearclip.local".to_string()]).unwrap();
+    let server_tls = TlsServerConfig::new(&cert).unwrap().config();
+    let client_tls = TlsClientConfig::new(cert.cert_der()).unwrap().config();
+
+    let config = TcpServerConfig::new()
+        .with_bind_addr(IpAddr::V4(Ipv4Addr::LOCALHOST))
+        .with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
+    let server = TcpServer::bind(config, server_tls).await.unwrap();
+    let addr = server.local_addr().unwrap();
+
+    let client_config = TcpClientConfig::new(addr).with_alpn_protocols(vec![MUX_ALPN.to_vec()]);
+    let (client, accepted) = tokio::join!(
+        TcpClient::connect(client_config, client_tls, "nearclip.local"),
+        server.accept()
+    );
+    let client = WifiTransport::new("server".to_string(), client.unwrap());
+    let server = WifiTransport::new("client".to_string(), accepted.unwrap());
+    assert_eq!(client.framing(), Framing::Mux);
+    assert_eq!(server.framing(), Framing::Mux);
+}